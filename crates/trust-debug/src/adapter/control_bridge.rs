@@ -141,6 +141,16 @@ fn spawn_command_drain(rx: std::sync::mpsc::Receiver<ResourceCommand>) -> thread
                         "bytecode reload unavailable in debug control",
                     ))));
                 }
+                ResourceCommand::OnlineChange { respond_to, .. } => {
+                    let _ = respond_to.send(Err(RuntimeError::ControlError(SmolStr::new(
+                        "online change unavailable in debug control",
+                    ))));
+                }
+                ResourceCommand::CallMethod { respond_to, .. } => {
+                    let _ = respond_to.send(Err(RuntimeError::ControlError(SmolStr::new(
+                        "method calls unavailable in debug control",
+                    ))));
+                }
                 ResourceCommand::MeshSnapshot { respond_to, .. } => {
                     let _ = respond_to.send(IndexMap::<SmolStr, Value>::new());
                 }
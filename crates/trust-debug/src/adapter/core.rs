@@ -30,7 +30,7 @@ use crate::runtime::DebugRuntime;
 use super::io::io_state_from_snapshot;
 use super::protocol_io::{read_message, write_message_locked, write_protocol_log};
 use super::remote::RemoteStop;
-use super::stop::StopCoordinator;
+use super::stop::{stop_description, StopCoordinator};
 use super::util::env_flag;
 use super::{CoordinateConverter, DebugAdapter, DispatchOutcome, LaunchState, StopGate};
 
@@ -400,7 +400,11 @@ impl DebugAdapter {
             "terminate" => self.handle_terminate(request),
             "setBreakpoints" => self.handle_set_breakpoints(request),
             "setExceptionBreakpoints" => self.handle_set_exception_breakpoints(request),
+            "exceptionInfo" => self.handle_exception_info(request),
             "breakpointLocations" => self.handle_breakpoint_locations(request),
+            "dataBreakpointInfo" => self.handle_data_breakpoint_info(request),
+            "setDataBreakpoints" => self.handle_set_data_breakpoints(request),
+            "setFunctionBreakpoints" => self.handle_set_function_breakpoints(request),
             "stIoState" => self.handle_io_state(request),
             "stIoWrite" => self.handle_io_write(request),
             "stVarState" => self.handle_var_state(request),
@@ -417,6 +421,8 @@ impl DebugAdapter {
             "next" => self.handle_next(request),
             "stepIn" => self.handle_step_in(request),
             "stepOut" => self.handle_step_out(request),
+            "stepBack" => self.handle_step_back(request),
+            "reverseContinue" => self.handle_reverse_continue(request),
             "evaluate" => self.handle_evaluate(request),
             _ => DispatchOutcome {
                 responses: vec![self.error_response(&request, "unsupported command")],
@@ -622,18 +628,28 @@ impl DebugAdapter {
     pub(super) fn remote_stop_events(&self, stop: RemoteStop) -> Vec<Value> {
         let thread_id = stop.thread_id.or(Some(1));
         let output = self.debug_output_message(format!(
-            "[trust-debug] stopped: reason={} thread_id={}",
+            "[trust-debug] stopped: reason={} thread_id={}{}",
             stop.reason,
             thread_id
                 .map(|id| id.to_string())
-                .unwrap_or_else(|| "<none>".to_string())
+                .unwrap_or_else(|| "<none>".to_string()),
+            stop.thread_name
+                .as_deref()
+                .map(|name| format!(" task={name}"))
+                .unwrap_or_default()
         ));
+        let description = stop
+            .thread_name
+            .as_deref()
+            .map(|name| stop_description(&stop.reason, name));
         let stopped = self.event(
             "stopped",
             Some(StoppedEventBody {
                 reason: stop.reason,
                 thread_id,
-                all_threads_stopped: Some(true),
+                all_threads_stopped: Some(stop.all_threads_stopped),
+                description,
+                text: None,
             }),
         );
         vec![output, stopped]
@@ -673,7 +689,7 @@ impl DebugAdapter {
         args
     }
 
-    fn to_client_breakpoints(
+    pub(super) fn to_client_breakpoints(
         &self,
         mut response: SetBreakpointsResponseBody,
     ) -> SetBreakpointsResponseBody {
@@ -1,18 +1,29 @@
 //! Breakpoint-related requests and location queries.
 //! - handle_set_breakpoints: configure source breakpoints
-//! - handle_set_exception_breakpoints: ignore exception breakpoints
+//! - handle_set_function_breakpoints: configure POU/method entry breakpoints
+//! - handle_set_exception_breakpoints: select fault categories that pause
+//! - handle_exception_info: describe the fault behind an exception stop
 //! - handle_breakpoint_locations: enumerate valid locations
+//! - handle_data_breakpoint_info: resolve watchable variables
+//! - handle_set_data_breakpoints: configure data breakpoints
 
 use serde_json::Value;
 
-use trust_runtime::debug::location_to_line_col;
+use trust_runtime::debug::{location_to_line_col, FaultCategory};
+use trust_runtime::io::{IoAddress, IoBinding};
+use trust_runtime::memory::VariableStorage;
+use trust_runtime::value::Value as RuntimeValue;
 
 use crate::protocol::{
-    BreakpointLocation, BreakpointLocationsArguments, BreakpointLocationsResponseBody, Request,
-    SetBreakpointsArguments,
+    Breakpoint, BreakpointLocation, BreakpointLocationsArguments, BreakpointLocationsResponseBody,
+    DataBreakpointAccessType, DataBreakpointInfoArguments, DataBreakpointInfoResponseBody,
+    ExceptionInfoArguments, ExceptionInfoResponseBody, Request, SetBreakpointsArguments,
+    SetBreakpointsResponseBody, SetDataBreakpointsArguments, SetDataBreakpointsResponseBody,
+    SetExceptionBreakpointsArguments, SetFunctionBreakpointsArguments,
 };
 
-use super::super::{DebugAdapter, DispatchOutcome};
+use super::super::io::format_io_address;
+use super::super::{DebugAdapter, DispatchOutcome, PausedStateView, VariableHandle};
 
 impl DebugAdapter {
     pub(in crate::adapter) fn handle_set_breakpoints(
@@ -64,17 +75,234 @@ impl DebugAdapter {
         }
     }
 
+    pub(in crate::adapter) fn handle_set_function_breakpoints(
+        &mut self,
+        request: Request<Value>,
+    ) -> DispatchOutcome {
+        let Some(args) = request.arguments.clone().and_then(|value| {
+            serde_json::from_value::<SetFunctionBreakpointsArguments>(value).ok()
+        }) else {
+            return DispatchOutcome {
+                responses: vec![
+                    self.error_response(&request, "invalid setFunctionBreakpoints args")
+                ],
+                ..DispatchOutcome::default()
+            };
+        };
+
+        let body = if self.remote_session.is_some() {
+            SetBreakpointsResponseBody {
+                breakpoints: args
+                    .breakpoints
+                    .iter()
+                    .map(|_| {
+                        Breakpoint::unlocated(
+                            false,
+                            Some(
+                                "function breakpoints are not supported in attach mode".to_string(),
+                            ),
+                        )
+                    })
+                    .collect(),
+            }
+        } else {
+            let response = self.session.set_function_breakpoints(&args);
+            self.to_client_breakpoints(response)
+        };
+        DispatchOutcome {
+            responses: vec![self.ok_response(&request, Some(body))],
+            ..DispatchOutcome::default()
+        }
+    }
+
     pub(in crate::adapter) fn handle_set_exception_breakpoints(
         &mut self,
         request: Request<Value>,
     ) -> DispatchOutcome {
-        let events =
-            vec![self.debug_output_message("[trust-debug] setExceptionBreakpoints ignored")];
+        let Some(args) = request.arguments.clone().and_then(|value| {
+            serde_json::from_value::<SetExceptionBreakpointsArguments>(value).ok()
+        }) else {
+            return DispatchOutcome {
+                responses: vec![
+                    self.error_response(&request, "invalid setExceptionBreakpoints args")
+                ],
+                ..DispatchOutcome::default()
+            };
+        };
+
+        if self.remote_session.is_some() {
+            let events = if args.filters.is_empty() {
+                Vec::new()
+            } else {
+                vec![self.debug_output_message(
+                    "[trust-debug] exception breakpoints are not supported in attach mode",
+                )]
+            };
+            return DispatchOutcome {
+                responses: vec![self.ok_response::<Value>(&request, None)],
+                events,
+                ..DispatchOutcome::default()
+            };
+        }
+
+        let mut filters = Vec::new();
+        let mut events = Vec::new();
+        for id in &args.filters {
+            match FaultCategory::from_id(id) {
+                Some(category) => filters.push(category),
+                None => events.push(self.debug_output_message(format!(
+                    "[trust-debug] unknown exception filter '{id}'"
+                ))),
+            }
+        }
+        self.session.debug_control().set_exception_filters(filters);
         DispatchOutcome {
             responses: vec![self.ok_response::<Value>(&request, None)],
             events,
-            should_exit: false,
-            stop_gate: None,
+            ..DispatchOutcome::default()
+        }
+    }
+
+    pub(in crate::adapter) fn handle_exception_info(
+        &mut self,
+        request: Request<Value>,
+    ) -> DispatchOutcome {
+        let Some(_args) = request
+            .arguments
+            .clone()
+            .and_then(|value| serde_json::from_value::<ExceptionInfoArguments>(value).ok())
+        else {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, "invalid exceptionInfo args")],
+                ..DispatchOutcome::default()
+            };
+        };
+
+        let fault = self
+            .remote_session
+            .is_none()
+            .then(|| self.session.debug_control().last_fault())
+            .flatten();
+        let Some(fault) = fault else {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, "no exception at this stop")],
+                ..DispatchOutcome::default()
+            };
+        };
+        let body = ExceptionInfoResponseBody {
+            exception_id: fault.category.id().to_string(),
+            description: Some(fault.message.to_string()),
+            break_mode: "userUnhandled".to_string(),
+        };
+        DispatchOutcome {
+            responses: vec![self.ok_response(&request, Some(body))],
+            ..DispatchOutcome::default()
+        }
+    }
+
+    pub(in crate::adapter) fn handle_data_breakpoint_info(
+        &mut self,
+        request: Request<Value>,
+    ) -> DispatchOutcome {
+        let Some(args) = request
+            .arguments
+            .clone()
+            .and_then(|value| serde_json::from_value::<DataBreakpointInfoArguments>(value).ok())
+        else {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, "invalid dataBreakpointInfo args")],
+                ..DispatchOutcome::default()
+            };
+        };
+
+        let name = args.name.trim();
+        let data_id = if self.remote_session.is_some() {
+            None
+        } else {
+            let handle = args
+                .variables_reference
+                .filter(|reference| *reference != 0)
+                .and_then(|reference| self.variable_handles.get(&reference).cloned());
+            let bindings = self.session.metadata().io_bindings();
+            let view =
+                PausedStateView::new(self.session.debug_control(), self.session.runtime_handle());
+            match handle {
+                Some(
+                    VariableHandle::IoInputs | VariableHandle::IoOutputs | VariableHandle::IoMemory,
+                ) => io_data_id(bindings, name),
+                Some(handle) => view
+                    .with_storage(|storage| data_id_for_handle(storage, &handle, name))
+                    .flatten(),
+                None if name.starts_with('%') => io_data_id(bindings, name),
+                None => view
+                    .with_storage(|storage| data_id_for_path(storage, name))
+                    .flatten()
+                    .or_else(|| io_data_id(bindings, name)),
+            }
+        };
+
+        let body = match data_id {
+            Some(data_id) => DataBreakpointInfoResponseBody {
+                data_id: Some(data_id),
+                description: name.to_string(),
+                access_types: Some(vec![
+                    DataBreakpointAccessType::Read,
+                    DataBreakpointAccessType::Write,
+                    DataBreakpointAccessType::ReadWrite,
+                ]),
+                can_persist: Some(false),
+            },
+            None => DataBreakpointInfoResponseBody {
+                data_id: None,
+                description: if self.remote_session.is_some() {
+                    "data breakpoints are not supported in attach mode".to_string()
+                } else {
+                    format!("'{name}' cannot be watched (only globals, FB members and I/O)")
+                },
+                access_types: None,
+                can_persist: None,
+            },
+        };
+        DispatchOutcome {
+            responses: vec![self.ok_response(&request, Some(body))],
+            ..DispatchOutcome::default()
+        }
+    }
+
+    pub(in crate::adapter) fn handle_set_data_breakpoints(
+        &mut self,
+        request: Request<Value>,
+    ) -> DispatchOutcome {
+        let Some(args) = request
+            .arguments
+            .clone()
+            .and_then(|value| serde_json::from_value::<SetDataBreakpointsArguments>(value).ok())
+        else {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, "invalid setDataBreakpoints args")],
+                ..DispatchOutcome::default()
+            };
+        };
+
+        let body = if self.remote_session.is_some() {
+            SetDataBreakpointsResponseBody {
+                breakpoints: args
+                    .breakpoints
+                    .iter()
+                    .map(|_| {
+                        Breakpoint::unlocated(
+                            false,
+                            Some("data breakpoints are not supported in attach mode".to_string()),
+                        )
+                    })
+                    .collect(),
+            }
+        } else {
+            self.session.set_data_breakpoints(&args)
+        };
+        DispatchOutcome {
+            responses: vec![self.ok_response(&request, Some(body))],
+            ..DispatchOutcome::default()
         }
     }
 
@@ -265,3 +493,58 @@ impl DebugAdapter {
         }
     }
 }
+
+fn data_id_for_handle(
+    storage: &VariableStorage,
+    handle: &VariableHandle,
+    name: &str,
+) -> Option<String> {
+    let instance_id = match handle {
+        VariableHandle::Globals | VariableHandle::Retain => {
+            return storage.get_global(name).map(|_| format!("global:{name}"));
+        }
+        VariableHandle::Instance(id) => *id,
+        VariableHandle::Locals(frame_id) => storage
+            .frames()
+            .iter()
+            .find(|frame| frame.id == *frame_id)
+            .filter(|frame| !frame.variables.contains_key(name))
+            .and_then(|frame| frame.instance_id)?,
+        _ => return None,
+    };
+    storage
+        .get_instance_var(instance_id, name)
+        .map(|_| format!("instance:{}:{name}", instance_id.0))
+}
+
+/// Resolve `Global` or `Global.member.member` through nested FB instances.
+fn data_id_for_path(storage: &VariableStorage, path: &str) -> Option<String> {
+    let mut segments = path.split('.').map(str::trim).collect::<Vec<_>>();
+    let field = segments.pop()?;
+    let Some((root, members)) = segments.split_first() else {
+        return storage.get_global(field).map(|_| format!("global:{field}"));
+    };
+    let RuntimeValue::Instance(mut instance_id) = storage.get_global(root)? else {
+        return None;
+    };
+    for member in members {
+        let RuntimeValue::Instance(next) = storage.get_instance_var(instance_id, member)? else {
+            return None;
+        };
+        instance_id = *next;
+    }
+    storage
+        .get_instance_var(instance_id, field)
+        .map(|_| format!("instance:{}:{field}", instance_id.0))
+}
+
+fn io_data_id(bindings: &[IoBinding], name: &str) -> Option<String> {
+    let parsed = IoAddress::parse(name).ok();
+    bindings
+        .iter()
+        .find(|binding| {
+            parsed.as_ref() == Some(&binding.address)
+                || binding.display_name.as_deref() == Some(name)
+        })
+        .map(|binding| format!("io:{}", format_io_address(&binding.address)))
+}
@@ -8,16 +8,17 @@ use std::time::Instant;
 use serde_json::Value;
 
 use trust_runtime::control::ControlEndpoint;
+use trust_runtime::debug::FaultCategory;
 
 use crate::protocol::{
-    AttachArguments, Capabilities, InitializeArguments, InitializeResponseBody, LaunchArguments,
-    Request,
+    AttachArguments, Capabilities, ExceptionBreakpointsFilter, InitializeArguments,
+    InitializeResponseBody, LaunchArguments, Request,
 };
 
 use super::super::control_bridge::{default_control_endpoint, DebugControlServer};
 use super::super::launch::{
-    launch_control_auth_token, launch_control_endpoint, launch_program_path, launch_stop_on_entry,
-    source_options_from_launch,
+    launch_control_auth_token, launch_control_endpoint, launch_history_cycles, launch_program_path,
+    launch_stop_on_entry, source_options_from_launch,
 };
 use super::super::remote::attach_from_args;
 use super::super::util::is_configuration_request;
@@ -51,12 +52,21 @@ impl DebugAdapter {
             supports_hit_conditional_breakpoints: Some(true),
             supports_log_points: Some(true),
             supports_breakpoint_locations_request: Some(true),
-            supports_function_breakpoints: Some(false),
+            supports_function_breakpoints: Some(true),
+            supports_data_breakpoints: Some(true),
             supports_evaluate_for_hovers: Some(true),
             supports_set_variable: Some(true),
             supports_set_expression: Some(true),
             supports_pause_request: Some(true),
             supports_terminate_request: Some(true),
+            supports_step_back: Some(true),
+            exception_breakpoint_filters: Some(
+                FaultCategory::ALL
+                    .into_iter()
+                    .map(exception_filter)
+                    .collect(),
+            ),
+            supports_exception_info_request: Some(true),
         };
 
         let response = self.ok_response(&request, Some(InitializeResponseBody { capabilities }));
@@ -128,13 +138,16 @@ impl DebugAdapter {
         }
         let program = launch_program_path(&args);
         let stop_on_entry = launch_stop_on_entry(&args);
+        let history_cycles = launch_history_cycles(&args);
         let source_update = source_options_from_launch(&args);
         self.session.update_source_options(source_update);
+        self.session.debug_control().enable_history(history_cycles);
         let mut events = Vec::new();
         events.push(self.debug_output_message(format!(
-            "[trust-debug] launch: program={} stopOnEntry={} configurationDone={}",
+            "[trust-debug] launch: program={} stopOnEntry={} historyCycles={} configurationDone={}",
             program.as_deref().unwrap_or("<none>"),
             stop_on_entry,
+            history_cycles,
             self.launch_state.is_configured()
         )));
 
@@ -353,11 +366,26 @@ impl DebugAdapter {
         let mut events = Vec::new();
         self.launch_state.set_configured();
         match attach_from_args(&args) {
-            Ok((remote_session, state)) => {
+            Ok((mut remote_session, state)) => {
+                let non_stop = args
+                    .additional
+                    .get("nonStop")
+                    .and_then(|value| value.as_bool())
+                    .unwrap_or(false);
+                let non_stop_error = non_stop
+                    .then(|| remote_session.set_non_stop(true).err())
+                    .flatten();
                 self.remote_session = Some(remote_session);
                 self.start_remote_polling();
                 events
                     .push(self.debug_output_message("[trust-debug] attach: connected".to_string()));
+                if let Some(err) = non_stop_error {
+                    events.push(self.debug_output_message(format!(
+                        "[trust-debug] attach: nonStop unavailable ({err})"
+                    )));
+                } else if non_stop {
+                    events.push(self.debug_output_message("[trust-debug] attach: nonStop enabled"));
+                }
                 if let Some(state) = state {
                     if state.paused {
                         if let Some(stop) = state.last_stop {
@@ -389,3 +417,25 @@ fn format_control_endpoint(endpoint: &ControlEndpoint) -> String {
         ControlEndpoint::Unix(path) => format!("unix://{}", path.display()),
     }
 }
+
+fn exception_filter(category: FaultCategory) -> ExceptionBreakpointsFilter {
+    let (label, description) = match category {
+        FaultCategory::Arithmetic => (
+            "Arithmetic faults",
+            "Division or modulo by zero and arithmetic overflow",
+        ),
+        FaultCategory::Bounds => ("Array bounds", "Array index outside the declared range"),
+        FaultCategory::NullReference => ("Null reference", "Dereference of a NULL reference"),
+        FaultCategory::Watchdog => (
+            "Watchdog timeout",
+            "Cycle runs longer than the configured watchdog timeout",
+        ),
+        FaultCategory::Assertion => ("Assertions", "Failed ASSERT_* call"),
+    };
+    ExceptionBreakpointsFilter {
+        filter: category.id().to_string(),
+        label: label.to_string(),
+        description: Some(description.to_string()),
+        default: Some(false),
+    }
+}
@@ -24,7 +24,11 @@ impl DebugAdapter {
             .clone()
             .and_then(|value| serde_json::from_value::<DisconnectArguments>(value).ok());
 
-        if self.remote_session.is_some() {
+        if let Some(remote) = self.remote_session.as_mut() {
+            // Leave the runtime in all-stop mode for the next session.
+            if remote.non_stop() {
+                let _ = remote.set_non_stop(false);
+            }
             self.stop_remote_polling();
             self.remote_session = None;
             if let Ok(mut guard) = self.remote_breakpoints.lock() {
@@ -62,7 +66,11 @@ impl DebugAdapter {
             .clone()
             .and_then(|value| serde_json::from_value::<TerminateArguments>(value).ok());
 
-        if self.remote_session.is_some() {
+        if let Some(remote) = self.remote_session.as_mut() {
+            // Leave the runtime in all-stop mode for the next session.
+            if remote.non_stop() {
+                let _ = remote.set_non_stop(false);
+            }
             self.stop_remote_polling();
             self.remote_session = None;
             if let Ok(mut guard) = self.remote_breakpoints.lock() {
@@ -1,6 +1,6 @@
 //! Request handlers grouped by DAP area.
 //! - initialize: initialize/launch/configuration timing
-//! - breakpoints: breakpoint CRUD + location resolution
+//! - breakpoints: breakpoint CRUD (source + data) + location resolution
 //! - lifecycle: disconnect/terminate/reload
 //! - threads: thread list
 //! - stack_trace: stackTrace request
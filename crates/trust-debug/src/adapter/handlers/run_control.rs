@@ -2,6 +2,7 @@
 //! - handle_continue: resume execution
 //! - handle_pause: request pause
 //! - handle_next/step_in/step_out: stepping commands
+//! - handle_step_back/reverse_continue: cycle history navigation
 
 use serde_json::Value;
 use std::sync::atomic::Ordering;
@@ -10,7 +11,8 @@ use trust_runtime::debug::DebugMode;
 
 use crate::protocol::{
     ContinueArguments, ContinueResponseBody, NextArguments, PauseArguments, Request,
-    StepInArguments, StepOutArguments,
+    ReverseContinueArguments, StepBackArguments, StepInArguments, StepOutArguments,
+    StoppedEventBody,
 };
 
 use super::super::{DebugAdapter, DispatchOutcome};
@@ -20,7 +22,7 @@ impl DebugAdapter {
         &mut self,
         request: Request<Value>,
     ) -> DispatchOutcome {
-        let Some(_args) = request
+        let Some(args) = request
             .arguments
             .clone()
             .and_then(|value| serde_json::from_value::<ContinueArguments>(value).ok())
@@ -33,17 +35,20 @@ impl DebugAdapter {
 
         if let Some(remote) = self.remote_session.as_mut() {
             self.pause_expected.store(false, Ordering::SeqCst);
-            if let Err(err) = remote.resume() {
+            // Non-stop sessions resume the selected task only.
+            let thread_id = remote.non_stop().then_some(args.thread_id);
+            if let Err(err) = remote.resume(thread_id) {
                 return DispatchOutcome {
                     responses: vec![self.error_response(&request, &err.to_string())],
                     ..DispatchOutcome::default()
                 };
             }
+            let all_threads_continued = !remote.non_stop();
             return DispatchOutcome {
                 responses: vec![self.ok_response(
                     &request,
                     Some(ContinueResponseBody {
-                        all_threads_continued: Some(true),
+                        all_threads_continued: Some(all_threads_continued),
                     }),
                 )],
                 stop_gate: Some(self.stop_gate.enter()),
@@ -67,7 +72,7 @@ impl DebugAdapter {
     }
 
     pub(in crate::adapter) fn handle_pause(&mut self, request: Request<Value>) -> DispatchOutcome {
-        let Some(_args) = request
+        let Some(args) = request
             .arguments
             .clone()
             .and_then(|value| serde_json::from_value::<PauseArguments>(value).ok())
@@ -80,7 +85,9 @@ impl DebugAdapter {
 
         if let Some(remote) = self.remote_session.as_mut() {
             self.pause_expected.store(true, Ordering::SeqCst);
-            if let Err(err) = remote.pause() {
+            // Non-stop sessions pause the selected task only.
+            let thread_id = remote.non_stop().then_some(args.thread_id);
+            if let Err(err) = remote.pause(thread_id) {
                 return DispatchOutcome {
                     responses: vec![self.error_response(&request, &err.to_string())],
                     ..DispatchOutcome::default()
@@ -129,7 +136,7 @@ impl DebugAdapter {
         };
 
         if let Some(remote) = self.remote_session.as_mut() {
-            if let Err(err) = remote.step_over() {
+            if let Err(err) = remote.step_over(_args.thread_id) {
                 return DispatchOutcome {
                     responses: vec![self.error_response(&request, &err.to_string())],
                     ..DispatchOutcome::default()
@@ -142,6 +149,10 @@ impl DebugAdapter {
             };
         }
 
+        if self.session.debug_control().history_cursor().is_some() {
+            return self.history_forward(&request, _args.thread_id);
+        }
+
         self.session
             .debug_control()
             .step_over_thread(_args.thread_id);
@@ -169,7 +180,7 @@ impl DebugAdapter {
         };
 
         if let Some(remote) = self.remote_session.as_mut() {
-            if let Err(err) = remote.step_in() {
+            if let Err(err) = remote.step_in(_args.thread_id) {
                 return DispatchOutcome {
                     responses: vec![self.error_response(&request, &err.to_string())],
                     ..DispatchOutcome::default()
@@ -182,6 +193,10 @@ impl DebugAdapter {
             };
         }
 
+        if self.session.debug_control().history_cursor().is_some() {
+            return self.history_forward(&request, _args.thread_id);
+        }
+
         self.session.debug_control().step_thread(_args.thread_id);
 
         DispatchOutcome {
@@ -207,7 +222,7 @@ impl DebugAdapter {
         };
 
         if let Some(remote) = self.remote_session.as_mut() {
-            if let Err(err) = remote.step_out() {
+            if let Err(err) = remote.step_out(_args.thread_id) {
                 return DispatchOutcome {
                     responses: vec![self.error_response(&request, &err.to_string())],
                     ..DispatchOutcome::default()
@@ -230,4 +245,113 @@ impl DebugAdapter {
             ..DispatchOutcome::default()
         }
     }
+
+    pub(in crate::adapter) fn handle_step_back(
+        &mut self,
+        request: Request<Value>,
+    ) -> DispatchOutcome {
+        let Some(args) = request
+            .arguments
+            .clone()
+            .and_then(|value| serde_json::from_value::<StepBackArguments>(value).ok())
+        else {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, "invalid stepBack args")],
+                ..DispatchOutcome::default()
+            };
+        };
+        if let Some(message) = self.history_unavailable() {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, message)],
+                ..DispatchOutcome::default()
+            };
+        }
+        match self.session.debug_control().step_back() {
+            Some(cycle) => self.history_stopped(&request, args.thread_id, Some(cycle)),
+            None => DispatchOutcome {
+                responses: vec![self.error_response(&request, "no earlier cycle recorded")],
+                ..DispatchOutcome::default()
+            },
+        }
+    }
+
+    pub(in crate::adapter) fn handle_reverse_continue(
+        &mut self,
+        request: Request<Value>,
+    ) -> DispatchOutcome {
+        let Some(args) = request
+            .arguments
+            .clone()
+            .and_then(|value| serde_json::from_value::<ReverseContinueArguments>(value).ok())
+        else {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, "invalid reverseContinue args")],
+                ..DispatchOutcome::default()
+            };
+        };
+        if let Some(message) = self.history_unavailable() {
+            return DispatchOutcome {
+                responses: vec![self.error_response(&request, message)],
+                ..DispatchOutcome::default()
+            };
+        }
+        match self.session.debug_control().reverse_continue() {
+            Some(cycle) => self.history_stopped(&request, args.thread_id, Some(cycle)),
+            None => DispatchOutcome {
+                responses: vec![self.error_response(&request, "no earlier cycle recorded")],
+                ..DispatchOutcome::default()
+            },
+        }
+    }
+
+    fn history_unavailable(&self) -> Option<&'static str> {
+        if self.remote_session.is_some() {
+            return Some("reverse debugging is not supported when attached");
+        }
+        let control = self.session.debug_control();
+        if !control.history_enabled() {
+            return Some(
+                "cycle history is disabled (set historyCycles in the launch configuration)",
+            );
+        }
+        if !matches!(control.mode(), DebugMode::Paused) {
+            return Some("reverse debugging requires a paused runtime");
+        }
+        None
+    }
+
+    fn history_forward(&mut self, request: &Request<Value>, thread_id: u32) -> DispatchOutcome {
+        let cycle = self.session.debug_control().step_forward_history();
+        self.history_stopped(request, thread_id, cycle)
+    }
+
+    /// Report a history move as a step stop; `None` means the live state is shown again.
+    fn history_stopped(
+        &self,
+        request: &Request<Value>,
+        thread_id: u32,
+        cycle: Option<u64>,
+    ) -> DispatchOutcome {
+        let message = match cycle {
+            Some(cycle) => format!("[trust-debug] history: showing end of cycle {cycle}"),
+            None => "[trust-debug] history: showing live state".to_string(),
+        };
+        DispatchOutcome {
+            responses: vec![self.ok_response::<Value>(request, None)],
+            events: vec![
+                self.debug_output_message(message),
+                self.event(
+                    "stopped",
+                    Some(StoppedEventBody {
+                        reason: "step".to_string(),
+                        thread_id: Some(thread_id),
+                        all_threads_stopped: Some(true),
+                        description: None,
+                        text: None,
+                    }),
+                ),
+            ],
+            ..DispatchOutcome::default()
+        }
+    }
 }
@@ -24,8 +24,9 @@ impl DebugAdapter {
         };
 
         if self.remote_session.is_none() {
-            if let Some(current_thread) = self.session.debug_control().current_thread() {
-                if current_thread != args.thread_id {
+            // Only the task that reported the stop has a captured call stack.
+            if let Some(stopped_thread) = self.session.debug_control().stopped_thread() {
+                if stopped_thread != args.thread_id {
                     let body = StackTraceResponseBody {
                         stack_frames: Vec::new(),
                         total_frames: Some(0),
@@ -39,7 +40,7 @@ impl DebugAdapter {
         }
 
         if let Some(remote) = self.remote_session.as_mut() {
-            let mut stack_frames = remote.stack_trace().unwrap_or_default();
+            let mut stack_frames = remote.stack_trace(args.thread_id).unwrap_or_default();
             for frame in &mut stack_frames {
                 frame.line = self.to_client_line(frame.line);
                 frame.column = self.to_client_column(frame.column);
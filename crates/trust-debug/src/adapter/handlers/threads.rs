@@ -1,5 +1,5 @@
 //! Thread requests.
-//! - handle_threads: enumerate runtime tasks
+//! - handle_threads: enumerate runtime tasks (one thread per task)
 
 use serde_json::Value;
 
@@ -12,13 +12,18 @@ impl DebugAdapter {
         &mut self,
         request: Request<Value>,
     ) -> DispatchOutcome {
-        if self.remote_session.is_some() {
-            let body = ThreadsResponseBody {
-                threads: vec![Thread {
-                    id: 1,
-                    name: "MainTask".to_string(),
-                }],
-            };
+        if let Some(remote) = self.remote_session.as_mut() {
+            let threads = remote
+                .threads()
+                .ok()
+                .filter(|threads| !threads.is_empty())
+                .unwrap_or_else(|| {
+                    vec![Thread {
+                        id: 1,
+                        name: "MainTask".to_string(),
+                    }]
+                });
+            let body = ThreadsResponseBody { threads };
             return DispatchOutcome {
                 responses: vec![self.ok_response(&request, Some(body))],
                 ..DispatchOutcome::default()
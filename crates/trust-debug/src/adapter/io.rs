@@ -423,7 +423,7 @@ pub(super) fn io_state_from_snapshot(snapshot: IoSnapshot) -> IoStateEventBody {
     }
 }
 
-fn format_io_address(address: &IoAddress) -> String {
+pub(super) fn format_io_address(address: &IoAddress) -> String {
     let area = match address.area {
         IoArea::Input => "I",
         IoArea::Output => "Q",
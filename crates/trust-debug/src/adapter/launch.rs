@@ -1,6 +1,7 @@
 //! Launch argument helpers.
 //! - launch_program_path: extract program path
 //! - launch_stop_on_entry: stop-on-entry flag
+//! - launch_history_cycles: reverse-debugging history depth
 //! - source_options_from_launch: derive source filtering options
 
 use serde_json::Value;
@@ -22,6 +23,17 @@ pub(super) fn launch_stop_on_entry(args: &LaunchArguments) -> bool {
         .unwrap_or(false)
 }
 
+/// Cycles recorded for `stepBack`/`reverseContinue`.
+///
+/// Recording diffs the variable storage after every scan, so it is off
+/// unless the launch configuration sets `historyCycles`.
+pub(super) fn launch_history_cycles(args: &LaunchArguments) -> usize {
+    args.additional
+        .get("historyCycles")
+        .and_then(|value| value.as_u64())
+        .map_or(0, |value| usize::try_from(value).unwrap_or(usize::MAX))
+}
+
 pub(super) fn source_options_from_launch(args: &LaunchArguments) -> SourceOptionsUpdate {
     SourceOptionsUpdate {
         root: launch_runtime_root(args),
@@ -10,7 +10,8 @@ use serde_json::{json, Value};
 
 use crate::protocol::{
     AttachArguments, Breakpoint, BreakpointLocation, BreakpointLocationsResponseBody,
-    EvaluateResponseBody, IoStateEntry, IoStateEventBody, Scope, Source, StackFrame, Variable,
+    EvaluateResponseBody, IoStateEntry, IoStateEventBody, Scope, Source, StackFrame, Thread,
+    Variable,
 };
 
 type RemoteResult<T> = std::result::Result<T, String>;
@@ -51,6 +52,8 @@ struct ControlResponse {
 pub struct RemoteStop {
     pub reason: String,
     pub thread_id: Option<u32>,
+    pub thread_name: Option<String>,
+    pub all_threads_stopped: bool,
     pub file_id: Option<u32>,
     pub breakpoint_generation: Option<u64>,
 }
@@ -66,6 +69,7 @@ pub struct RemoteSession {
     endpoint: RemoteEndpoint,
     token: Option<String>,
     client: ControlClient,
+    non_stop: bool,
 }
 
 impl RemoteSession {
@@ -75,6 +79,7 @@ impl RemoteSession {
             endpoint,
             token,
             client,
+            non_stop: false,
         })
     }
 
@@ -96,6 +101,35 @@ impl RemoteSession {
         Ok(RemoteDebugState { paused, last_stop })
     }
 
+    pub fn threads(&mut self) -> RemoteResult<Vec<Thread>> {
+        let payload = self.request("debug.threads", None)?;
+        let threads = payload
+            .get("threads")
+            .and_then(|value| value.as_array())
+            .map(|threads| {
+                threads
+                    .iter()
+                    .filter_map(|thread| {
+                        let id = thread.get("id")?.as_u64()? as u32;
+                        let name = thread.get("name")?.as_str()?.to_string();
+                        Some(Thread { id, name })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(threads)
+    }
+
+    pub fn set_non_stop(&mut self, enabled: bool) -> RemoteResult<()> {
+        let _ = self.request("debug.non_stop", Some(json!({ "enabled": enabled })))?;
+        self.non_stop = enabled;
+        Ok(())
+    }
+
+    pub fn non_stop(&self) -> bool {
+        self.non_stop
+    }
+
     pub fn debug_stops(&mut self) -> RemoteResult<Vec<RemoteStop>> {
         let payload = self.request("debug.stops", None)?;
         let stops = payload
@@ -203,8 +237,8 @@ impl RemoteSession {
         Ok(BreakpointLocationsResponseBody { breakpoints })
     }
 
-    pub fn stack_trace(&mut self) -> RemoteResult<Vec<StackFrame>> {
-        let payload = self.request("debug.stack", None)?;
+    pub fn stack_trace(&mut self, thread_id: u32) -> RemoteResult<Vec<StackFrame>> {
+        let payload = self.request("debug.stack", Some(json!({ "thread_id": thread_id })))?;
         let frames = payload
             .get("stack_frames")
             .cloned()
@@ -245,28 +279,28 @@ impl RemoteSession {
         serde_json::from_value::<EvaluateResponseBody>(payload).map_err(|err| err.to_string())
     }
 
-    pub fn pause(&mut self) -> RemoteResult<()> {
-        let _ = self.request("pause", None)?;
+    pub fn pause(&mut self, thread_id: Option<u32>) -> RemoteResult<()> {
+        let _ = self.request("pause", Some(json!({ "thread_id": thread_id })))?;
         Ok(())
     }
 
-    pub fn resume(&mut self) -> RemoteResult<()> {
-        let _ = self.request("resume", None)?;
+    pub fn resume(&mut self, thread_id: Option<u32>) -> RemoteResult<()> {
+        let _ = self.request("resume", Some(json!({ "thread_id": thread_id })))?;
         Ok(())
     }
 
-    pub fn step_in(&mut self) -> RemoteResult<()> {
-        let _ = self.request("step_in", None)?;
+    pub fn step_in(&mut self, thread_id: u32) -> RemoteResult<()> {
+        let _ = self.request("step_in", Some(json!({ "thread_id": thread_id })))?;
         Ok(())
     }
 
-    pub fn step_over(&mut self) -> RemoteResult<()> {
-        let _ = self.request("step_over", None)?;
+    pub fn step_over(&mut self, thread_id: u32) -> RemoteResult<()> {
+        let _ = self.request("step_over", Some(json!({ "thread_id": thread_id })))?;
         Ok(())
     }
 
-    pub fn step_out(&mut self) -> RemoteResult<()> {
-        let _ = self.request("step_out", None)?;
+    pub fn step_out(&mut self, thread_id: u32) -> RemoteResult<()> {
+        let _ = self.request("step_out", Some(json!({ "thread_id": thread_id })))?;
         Ok(())
     }
 
@@ -464,6 +498,14 @@ fn parse_stop(value: &Value) -> Option<RemoteStop> {
         .get("thread_id")
         .and_then(|value| value.as_u64())
         .map(|value| value as u32);
+    let thread_name = value
+        .get("thread_name")
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+    let all_threads_stopped = value
+        .get("all_threads_stopped")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true);
     let file_id = value
         .get("file_id")
         .and_then(|value| value.as_u64())
@@ -474,6 +516,8 @@ fn parse_stop(value: &Value) -> Option<RemoteStop> {
     Some(RemoteStop {
         reason,
         thread_id,
+        thread_name,
+        all_threads_stopped,
         file_id,
         breakpoint_generation,
     })
@@ -70,7 +70,11 @@ impl StopCoordinator {
                     return false;
                 }
             }
-            DebugStopReason::Breakpoint | DebugStopReason::Step => {
+            DebugStopReason::Breakpoint
+            | DebugStopReason::DataBreakpoint
+            | DebugStopReason::FunctionBreakpoint
+            | DebugStopReason::Exception
+            | DebugStopReason::Step => {
                 self.pause_expected.store(false, Ordering::SeqCst);
             }
         }
@@ -111,18 +115,35 @@ impl StopCoordinator {
     fn emit_stop(&self, stop: DebugStop) -> bool {
         let reason = match stop.reason {
             DebugStopReason::Breakpoint => "breakpoint",
+            DebugStopReason::DataBreakpoint => "data breakpoint",
+            DebugStopReason::FunctionBreakpoint => "function breakpoint",
             DebugStopReason::Step => "step",
             DebugStopReason::Pause => "pause",
             DebugStopReason::Entry => "entry",
+            DebugStopReason::Exception => "exception",
+        };
+        let text = match stop.reason {
+            DebugStopReason::Exception => self
+                .stop_control
+                .last_fault()
+                .map(|fault| fault.message.to_string()),
+            _ => None,
         };
         let thread_id = stop.thread_id.or(Some(1));
+        let task = stop
+            .thread_id
+            .and_then(|id| self.stop_control.thread_name(id))
+            .map(|name| name.to_string());
         let output_body = OutputEventBody {
             output: format!(
-                "[trust-debug] stopped: reason={} thread_id={}\n",
+                "[trust-debug] stopped: reason={} thread_id={}{}\n",
                 reason,
                 thread_id
                     .map(|id| id.to_string())
-                    .unwrap_or_else(|| "<none>".to_string())
+                    .unwrap_or_else(|| "<none>".to_string()),
+                task.as_deref()
+                    .map(|name| format!(" task={name}"))
+                    .unwrap_or_default()
             ),
             category: Some("console".to_string()),
             source: None,
@@ -135,11 +156,13 @@ impl StopCoordinator {
             event: "output".to_string(),
             body: Some(output_body),
         };
-        let all_threads_stopped = self.stop_control.target_thread().is_none();
+        let all_threads_stopped = !self.stop_control.non_stop();
         let body = StoppedEventBody {
             reason: reason.to_string(),
             thread_id,
             all_threads_stopped: Some(all_threads_stopped),
+            description: task.as_deref().map(|name| stop_description(reason, name)),
+            text,
         };
         let event = Event {
             seq: self.seq.fetch_add(1, Ordering::Relaxed),
@@ -194,9 +217,12 @@ impl StopCoordinator {
     fn trace_stop(&self, action: &str, stop: &DebugStop, detail: Option<String>) {
         let reason = match stop.reason {
             DebugStopReason::Breakpoint => "breakpoint",
+            DebugStopReason::DataBreakpoint => "data breakpoint",
+            DebugStopReason::FunctionBreakpoint => "function breakpoint",
             DebugStopReason::Step => "step",
             DebugStopReason::Pause => "pause",
             DebugStopReason::Entry => "entry",
+            DebugStopReason::Exception => "exception",
         };
         let location = stop
             .location
@@ -216,6 +242,11 @@ impl StopCoordinator {
     }
 }
 
+/// Stop description shown by the client, naming the task that stopped.
+pub(super) fn stop_description(reason: &str, task: &str) -> String {
+    format!("Paused on {reason} in task {task}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
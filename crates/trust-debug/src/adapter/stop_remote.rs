@@ -12,6 +12,7 @@ use crate::protocol::{Event, MessageType, OutputEventBody, StoppedEventBody};
 
 use super::protocol_io::write_protocol_log;
 use super::remote::{RemoteEndpoint, RemoteSession, RemoteStop};
+use super::stop::stop_description;
 use super::StopGate;
 
 const POLL_INTERVAL: Duration = Duration::from_millis(50);
@@ -71,12 +72,10 @@ fn should_emit_stop(
     breakpoints: &Arc<Mutex<HashMap<u32, u64>>>,
 ) -> bool {
     match stop.reason.as_str() {
-        "pause" | "entry" => {
-            if !pause_expected.swap(false, Ordering::SeqCst) {
-                return false;
-            }
+        "pause" | "entry" if !pause_expected.swap(false, Ordering::SeqCst) => {
+            return false;
         }
-        "breakpoint" | "step" => {
+        "breakpoint" | "data breakpoint" | "function breakpoint" | "step" => {
             pause_expected.store(false, Ordering::SeqCst);
         }
         _ => {}
@@ -102,11 +101,15 @@ fn emit_stop_event(
     let thread_id = stop.thread_id.or(Some(1));
     let output_body = OutputEventBody {
         output: format!(
-            "[trust-debug] stopped: reason={} thread_id={}\n",
+            "[trust-debug] stopped: reason={} thread_id={}{}\n",
             stop.reason,
             thread_id
                 .map(|id| id.to_string())
-                .unwrap_or_else(|| "<none>".to_string())
+                .unwrap_or_else(|| "<none>".to_string()),
+            stop.thread_name
+                .as_deref()
+                .map(|name| format!(" task={name}"))
+                .unwrap_or_default()
         ),
         category: Some("console".to_string()),
         source: None,
@@ -122,7 +125,12 @@ fn emit_stop_event(
     let stopped_body = StoppedEventBody {
         reason: stop.reason.clone(),
         thread_id,
-        all_threads_stopped: Some(true),
+        all_threads_stopped: Some(stop.all_threads_stopped),
+        description: stop
+            .thread_name
+            .as_deref()
+            .map(|name| stop_description(&stop.reason, name)),
+        text: None,
     };
     let stopped_event = Event {
         seq: seq.fetch_add(1, Ordering::Relaxed),
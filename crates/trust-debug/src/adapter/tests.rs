@@ -6,12 +6,16 @@ use super::protocol_io::{read_message, write_message};
 use super::*;
 use crate::protocol::{
     BreakpointLocationsArguments, BreakpointLocationsResponseBody, ContinueArguments,
-    EvaluateArguments, EvaluateResponseBody, Event, InitializeArguments, InitializeResponseBody,
-    IoStateEventBody, IoWriteArguments, MessageType, NextArguments, PauseArguments, Request,
-    Response, ScopesArguments, ScopesResponseBody, SetBreakpointsArguments,
-    SetBreakpointsResponseBody, SetExpressionArguments, SetExpressionResponseBody, Source,
-    SourceBreakpoint, StackTraceArguments, StackTraceResponseBody, StepInArguments,
-    StepOutArguments, ThreadsResponseBody, VariablesArguments, VariablesResponseBody,
+    DataBreakpoint, DataBreakpointInfoArguments, DataBreakpointInfoResponseBody, EvaluateArguments,
+    EvaluateResponseBody, Event, ExceptionInfoArguments, ExceptionInfoResponseBody,
+    FunctionBreakpoint, InitializeArguments, InitializeResponseBody, IoStateEventBody,
+    IoWriteArguments, MessageType, NextArguments, PauseArguments, Request, Response,
+    ReverseContinueArguments, ScopesArguments, ScopesResponseBody, SetBreakpointsArguments,
+    SetBreakpointsResponseBody, SetDataBreakpointsArguments, SetDataBreakpointsResponseBody,
+    SetExceptionBreakpointsArguments, SetExpressionArguments, SetExpressionResponseBody,
+    SetFunctionBreakpointsArguments, Source, SourceBreakpoint, StackTraceArguments,
+    StackTraceResponseBody, StepBackArguments, StepInArguments, StepOutArguments,
+    ThreadsResponseBody, VariablesArguments, VariablesResponseBody,
 };
 use crate::DebugSession;
 use indexmap::IndexMap;
@@ -19,7 +23,9 @@ use smol_str::SmolStr;
 use std::collections::BTreeMap;
 use std::io::BufReader;
 use trust_hir::{Type, TypeId};
-use trust_runtime::debug::{DebugControl, DebugHook, DebugStopReason, SourceLocation};
+use trust_runtime::debug::{
+    DebugControl, DebugHook, DebugStopReason, FaultCategory, SourceLocation,
+};
 use trust_runtime::harness::TestHarness;
 use trust_runtime::io::IoAddress;
 use trust_runtime::task::{ProgramDef, TaskConfig};
@@ -842,6 +848,499 @@ END_PROGRAM
     );
 }
 
+#[test]
+fn dap_stack_trace_is_scoped_to_stopped_task() {
+    let source = r#"
+CONFIGURATION Conf
+VAR_GLOBAL
+trigger1 : BOOL := FALSE;
+trigger2 : BOOL := FALSE;
+fast_count : DINT := 0;
+slow_count : DINT := 0;
+END_VAR
+TASK Fast (SINGLE := trigger1, PRIORITY := 1);
+TASK Slow (SINGLE := trigger2, PRIORITY := 2);
+PROGRAM P1 WITH Fast : FastProg;
+PROGRAM P2 WITH Slow : SlowProg;
+END_CONFIGURATION
+
+PROGRAM FastProg
+fast_count := fast_count + 1;
+END_PROGRAM
+
+PROGRAM SlowProg
+slow_count := slow_count + 1;
+END_PROGRAM
+"#;
+
+    let harness = TestHarness::from_source(source).unwrap();
+    let mut session = DebugSession::new(harness.into_runtime());
+    session.register_source("main.st", 0, source);
+    let mut adapter = DebugAdapter::new(session);
+
+    let line = source
+        .lines()
+        .position(|line| line.contains("slow_count := slow_count + 1;"))
+        .unwrap() as u32
+        + 1;
+    let args = SetBreakpointsArguments {
+        source: Source {
+            name: Some("main".into()),
+            path: Some("main.st".into()),
+            source_reference: None,
+        },
+        breakpoints: Some(vec![SourceBreakpoint {
+            line,
+            column: None,
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+        }]),
+        lines: None,
+        source_modified: None,
+    };
+    adapter.dispatch_request(Request {
+        seq: 1,
+        message_type: MessageType::Request,
+        command: "setBreakpoints".to_string(),
+        arguments: Some(serde_json::to_value(args).unwrap()),
+    });
+
+    let control = adapter.session().debug_control();
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+    control.set_stop_sender(stop_tx);
+    let slow_id = adapter
+        .session()
+        .metadata()
+        .task_thread_id(&SmolStr::new("Slow"))
+        .unwrap();
+    let fast_id = adapter
+        .session()
+        .metadata()
+        .task_thread_id(&SmolStr::new("Fast"))
+        .unwrap();
+
+    let runtime = adapter.session().runtime_handle();
+    let handle = std::thread::spawn(move || {
+        let mut guard = runtime.lock().unwrap();
+        for trigger in ["trigger1", "trigger2"] {
+            guard
+                .storage_mut()
+                .set_global(trigger, RuntimeValue::Bool(true));
+        }
+        guard.execute_cycle().unwrap();
+    });
+
+    let stop = stop_rx
+        .recv_timeout(std::time::Duration::from_secs(2))
+        .unwrap();
+    assert_eq!(stop.reason, DebugStopReason::Breakpoint);
+    assert_eq!(stop.thread_id, Some(slow_id));
+    assert_eq!(control.thread_name(slow_id).as_deref(), Some("Slow"));
+
+    let mut stack_frames = |seq: u32, thread_id: u32| {
+        let outcome = adapter.dispatch_request(Request {
+            seq,
+            message_type: MessageType::Request,
+            command: "stackTrace".to_string(),
+            arguments: Some(
+                serde_json::to_value(StackTraceArguments {
+                    thread_id,
+                    start_frame: None,
+                    levels: None,
+                })
+                .unwrap(),
+            ),
+        });
+        let response: Response<StackTraceResponseBody> =
+            serde_json::from_value(outcome.responses[0].clone()).unwrap();
+        response.body.unwrap().stack_frames
+    };
+    assert!(stack_frames(2, fast_id).is_empty());
+    let slow_frames = stack_frames(3, slow_id);
+    assert_eq!(slow_frames.len(), 1);
+    assert_eq!(slow_frames[0].line, line);
+
+    control.continue_run();
+    handle.join().unwrap();
+}
+
+#[test]
+fn dap_data_breakpoints_resolve_members_and_io() {
+    let source = r#"
+CONFIGURATION Conf
+VAR_GLOBAL
+out AT %QD0 : DINT;
+END_VAR
+PROGRAM P1 : Prog;
+END_CONFIGURATION
+
+FUNCTION_BLOCK Accum
+VAR_INPUT
+delta : DINT;
+END_VAR
+VAR_OUTPUT
+count : DINT := 0;
+END_VAR
+count := count + delta;
+END_FUNCTION_BLOCK
+
+PROGRAM Prog
+VAR
+c : Accum;
+END_VAR
+c(delta := 2);
+out := c.count;
+END_PROGRAM
+"#;
+
+    let harness = TestHarness::from_source(source).unwrap();
+    let mut session = DebugSession::new(harness.into_runtime());
+    session.register_source("main.st", 0, source);
+    let mut adapter = DebugAdapter::new(session);
+
+    let mut data_id = |seq: u32, name: &str| {
+        let args = DataBreakpointInfoArguments {
+            variables_reference: None,
+            name: name.to_string(),
+            frame_id: None,
+        };
+        let outcome = adapter.dispatch_request(Request {
+            seq,
+            message_type: MessageType::Request,
+            command: "dataBreakpointInfo".to_string(),
+            arguments: Some(serde_json::to_value(args).unwrap()),
+        });
+        let response: Response<DataBreakpointInfoResponseBody> =
+            serde_json::from_value(outcome.responses[0].clone()).unwrap();
+        response.body.unwrap().data_id
+    };
+    let member = data_id(1, "P1.c.count").expect("member data id");
+    let io = data_id(2, "%QD0").expect("io data id");
+    assert_eq!(io, "io:%QD0");
+    assert_eq!(data_id(3, "P1.missing"), None);
+
+    let args = SetDataBreakpointsArguments {
+        breakpoints: vec![
+            DataBreakpoint {
+                data_id: member,
+                access_type: None,
+                condition: Some("count = 4".into()),
+                hit_condition: None,
+            },
+            DataBreakpoint {
+                data_id: "global:unknown".into(),
+                access_type: None,
+                condition: None,
+                hit_condition: None,
+            },
+        ],
+    };
+    let outcome = adapter.dispatch_request(Request {
+        seq: 4,
+        message_type: MessageType::Request,
+        command: "setDataBreakpoints".to_string(),
+        arguments: Some(serde_json::to_value(args).unwrap()),
+    });
+    let response: Response<SetDataBreakpointsResponseBody> =
+        serde_json::from_value(outcome.responses[0].clone()).unwrap();
+    let verified = response
+        .body
+        .unwrap()
+        .breakpoints
+        .iter()
+        .map(|bp| bp.verified)
+        .collect::<Vec<_>>();
+    assert_eq!(verified, vec![true, false]);
+
+    let control = adapter.session().debug_control();
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+    control.set_stop_sender(stop_tx);
+    let runtime = adapter.into_session().runtime_handle();
+    let runtime_thread = Arc::clone(&runtime);
+    let handle = std::thread::spawn(move || {
+        let mut guard = runtime_thread.lock().unwrap();
+        guard.execute_cycle().unwrap();
+        guard.execute_cycle().unwrap();
+    });
+
+    let stop = stop_rx
+        .recv_timeout(std::time::Duration::from_secs(2))
+        .unwrap();
+    assert_eq!(stop.reason, DebugStopReason::DataBreakpoint);
+    let snapshot = control.snapshot().expect("paused snapshot");
+    assert_eq!(
+        snapshot.storage.get_global("out"),
+        Some(&RuntimeValue::DInt(2))
+    );
+    control.continue_run();
+    handle.join().unwrap();
+}
+
+#[test]
+fn dap_function_breakpoints_resolve_methods() {
+    let source = r#"
+FUNCTION_BLOCK Motor
+VAR_INPUT
+enable : BOOL;
+END_VAR
+VAR
+speed : DINT;
+END_VAR
+speed := speed + 1;
+METHOD PUBLIC Start : BOOL
+VAR_INPUT
+target : DINT;
+END_VAR
+speed := target;
+Start := TRUE;
+END_METHOD
+END_FUNCTION_BLOCK
+
+PROGRAM Main
+VAR
+motor1 : Motor;
+END_VAR
+motor1(enable := TRUE);
+motor1.Start(target := 5);
+END_PROGRAM
+"#;
+
+    let harness = TestHarness::from_source(source).unwrap();
+    let mut session = DebugSession::new(harness.into_runtime());
+    session.register_source("main.st", 0, source);
+    let mut adapter = DebugAdapter::new(session);
+
+    let function_breakpoint = |name: &str, condition: Option<&str>| FunctionBreakpoint {
+        name: name.to_string(),
+        condition: condition.map(str::to_string),
+        hit_condition: None,
+    };
+    let args = SetFunctionBreakpointsArguments {
+        breakpoints: vec![
+            function_breakpoint("Main.motor1.Start", Some("target > 3")),
+            function_breakpoint("Motor", None),
+            function_breakpoint("Motor.Stop", None),
+        ],
+    };
+    let outcome = adapter.dispatch_request(Request {
+        seq: 1,
+        message_type: MessageType::Request,
+        command: "setFunctionBreakpoints".to_string(),
+        arguments: Some(serde_json::to_value(args).unwrap()),
+    });
+    let response: Response<SetBreakpointsResponseBody> =
+        serde_json::from_value(outcome.responses[0].clone()).unwrap();
+    let breakpoints = response.body.unwrap().breakpoints;
+    let line_of =
+        |text: &str| source.lines().position(|line| line.contains(text)).unwrap() as u32 + 1;
+    assert!(breakpoints[0].verified);
+    assert_eq!(breakpoints[0].line, Some(line_of("speed := target;")));
+    assert_eq!(breakpoints[1].line, Some(line_of("speed := speed + 1;")));
+    assert!(!breakpoints[2].verified);
+
+    let control = adapter.session().debug_control();
+    assert_eq!(control.function_breakpoints().len(), 2);
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+    control.set_stop_sender(stop_tx);
+    let runtime = adapter.into_session().runtime_handle();
+    let runtime_thread = Arc::clone(&runtime);
+    let handle = std::thread::spawn(move || {
+        let mut guard = runtime_thread.lock().unwrap();
+        guard.execute_cycle().unwrap();
+    });
+
+    for _ in 0..2 {
+        let stop = stop_rx
+            .recv_timeout(std::time::Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(stop.reason, DebugStopReason::FunctionBreakpoint);
+        control.continue_run();
+    }
+    handle.join().unwrap();
+}
+
+#[test]
+fn dap_step_back_replays_recorded_cycles() {
+    let source = r#"
+CONFIGURATION Conf
+VAR_GLOBAL
+    total : DINT := 0;
+END_VAR
+PROGRAM P1 : Main;
+END_CONFIGURATION
+
+PROGRAM Main
+total := total + 1;
+END_PROGRAM
+"#;
+
+    let harness = TestHarness::from_source(source).unwrap();
+    let session = DebugSession::new(harness.into_runtime());
+    let mut adapter = DebugAdapter::new(session);
+    let control = adapter.session().debug_control();
+    control.enable_history(4);
+    let runtime = adapter.session().runtime_handle();
+    for _ in 0..3 {
+        runtime.lock().unwrap().execute_cycle().unwrap();
+    }
+
+    fn dispatch(adapter: &mut DebugAdapter, command: &str, arguments: Value) -> DispatchOutcome {
+        adapter.dispatch_request(Request {
+            seq: 1,
+            message_type: MessageType::Request,
+            command: command.to_string(),
+            arguments: Some(arguments),
+        })
+    }
+    fn stopped(outcome: &DispatchOutcome) -> bool {
+        let response: Response<Value> =
+            serde_json::from_value(outcome.responses[0].clone()).unwrap();
+        response.success
+            && outcome
+                .events
+                .iter()
+                .any(|event| event.get("event").and_then(Value::as_str) == Some("stopped"))
+    }
+    fn total(adapter: &mut DebugAdapter) -> String {
+        let arguments = serde_json::to_value(EvaluateArguments {
+            expression: "total".to_string(),
+            frame_id: None,
+            context: Some("watch".to_string()),
+        })
+        .unwrap();
+        let outcome = dispatch(adapter, "evaluate", arguments);
+        let response: Response<EvaluateResponseBody> =
+            serde_json::from_value(outcome.responses[0].clone()).unwrap();
+        response.body.unwrap().result
+    }
+
+    let step_back = serde_json::to_value(StepBackArguments { thread_id: 1 }).unwrap();
+    let reverse = serde_json::to_value(ReverseContinueArguments { thread_id: 1 }).unwrap();
+    let next = serde_json::to_value(NextArguments { thread_id: 1 }).unwrap();
+    assert!(!stopped(&dispatch(
+        &mut adapter,
+        "stepBack",
+        step_back.clone()
+    )));
+
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+    control.set_stop_sender(stop_tx);
+    control.pause();
+    let runtime_thread = Arc::clone(&runtime);
+    let handle = std::thread::spawn(move || {
+        runtime_thread.lock().unwrap().execute_cycle().unwrap();
+    });
+    stop_rx
+        .recv_timeout(std::time::Duration::from_secs(2))
+        .unwrap();
+
+    assert!(stopped(&dispatch(&mut adapter, "stepBack", step_back)));
+    assert_eq!(control.history_cursor(), Some(2));
+    assert_eq!(total(&mut adapter), "DInt(3)");
+    assert!(stopped(&dispatch(&mut adapter, "reverseContinue", reverse)));
+    assert_eq!(control.history_cursor(), Some(0));
+    assert_eq!(total(&mut adapter), "DInt(1)");
+    for cursor in [Some(1), Some(2), None] {
+        assert!(stopped(&dispatch(&mut adapter, "next", next.clone())));
+        assert_eq!(control.history_cursor(), cursor);
+    }
+    assert_eq!(total(&mut adapter), "DInt(3)");
+
+    control.continue_run();
+    handle.join().unwrap();
+}
+
+#[test]
+fn dap_exception_filters_pause_on_faults() {
+    let source = r#"
+CONFIGURATION Conf
+PROGRAM P1 : Main;
+END_CONFIGURATION
+
+PROGRAM Main
+VAR
+    values : ARRAY[1..3] OF DINT;
+    index : DINT := 4;
+END_VAR
+values[index] := 1;
+END_PROGRAM
+"#;
+
+    let harness = TestHarness::from_source(source).unwrap();
+    let mut adapter = DebugAdapter::new(DebugSession::new(harness.into_runtime()));
+    let outcome = adapter.dispatch_request(Request {
+        seq: 1,
+        message_type: MessageType::Request,
+        command: "initialize".to_string(),
+        arguments: Some(serde_json::to_value(InitializeArguments::default()).unwrap()),
+    });
+    let response: Response<InitializeResponseBody> =
+        serde_json::from_value(outcome.responses[0].clone()).unwrap();
+    let capabilities = response.body.unwrap().capabilities;
+    let filters = capabilities
+        .exception_breakpoint_filters
+        .unwrap()
+        .into_iter()
+        .map(|filter| filter.filter)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        filters,
+        [
+            "arithmetic",
+            "bounds",
+            "nullReference",
+            "watchdog",
+            "assertion"
+        ]
+    );
+
+    let outcome = adapter.dispatch_request(Request {
+        seq: 2,
+        message_type: MessageType::Request,
+        command: "setExceptionBreakpoints".to_string(),
+        arguments: Some(
+            serde_json::to_value(SetExceptionBreakpointsArguments {
+                filters: vec!["bounds".to_string(), "overflow".to_string()],
+            })
+            .unwrap(),
+        ),
+    });
+    let response: Response<Value> = serde_json::from_value(outcome.responses[0].clone()).unwrap();
+    assert!(response.success);
+    assert_eq!(outcome.events.len(), 1);
+    let control = adapter.session().debug_control();
+    assert_eq!(control.exception_filters(), vec![FaultCategory::Bounds]);
+
+    let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+    control.set_stop_sender(stop_tx);
+    let runtime = adapter.session().runtime_handle();
+    let runtime_thread = Arc::clone(&runtime);
+    let handle = std::thread::spawn(move || runtime_thread.lock().unwrap().execute_cycle());
+    let stop = stop_rx
+        .recv_timeout(std::time::Duration::from_secs(2))
+        .unwrap();
+    assert_eq!(stop.reason, DebugStopReason::Exception);
+
+    let outcome = adapter.dispatch_request(Request {
+        seq: 3,
+        message_type: MessageType::Request,
+        command: "exceptionInfo".to_string(),
+        arguments: Some(serde_json::to_value(ExceptionInfoArguments { thread_id: 1 }).unwrap()),
+    });
+    let response: Response<ExceptionInfoResponseBody> =
+        serde_json::from_value(outcome.responses[0].clone()).unwrap();
+    let body = response.body.unwrap();
+    assert_eq!(body.exception_id, "bounds");
+    assert_eq!(
+        body.description.as_deref(),
+        Some("array index 4 out of bounds [1..3]")
+    );
+
+    control.continue_run();
+    assert!(handle.join().unwrap().is_err());
+}
+
 #[test]
 fn dispatch_threads_stack_scopes_variables() {
     let mut runtime = Runtime::new();
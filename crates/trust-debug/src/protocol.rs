@@ -61,6 +61,10 @@ pub struct StoppedEventBody {
     pub thread_id: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub all_threads_stopped: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
 }
 
 /// Terminated event body.
@@ -224,6 +228,8 @@ pub struct Capabilities {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub supports_function_breakpoints: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_data_breakpoints: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub supports_evaluate_for_hovers: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub supports_set_variable: Option<bool>,
@@ -233,6 +239,24 @@ pub struct Capabilities {
     pub supports_pause_request: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub supports_terminate_request: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_step_back: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exception_breakpoint_filters: Option<Vec<ExceptionBreakpointsFilter>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supports_exception_info_request: Option<bool>,
+}
+
+/// Exception filter offered in the client's breakpoint UI.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionBreakpointsFilter {
+    pub filter: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<bool>,
 }
 
 #[cfg(test)]
@@ -362,6 +386,20 @@ pub struct StepOutArguments {
     pub thread_id: u32,
 }
 
+/// Arguments for `stepBack`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StepBackArguments {
+    pub thread_id: u32,
+}
+
+/// Arguments for `reverseContinue`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReverseContinueArguments {
+    pub thread_id: u32,
+}
+
 /// Arguments for `evaluate`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -638,6 +676,21 @@ impl Breakpoint {
             end_column: None,
         }
     }
+
+    /// Breakpoint without a source position (data breakpoints).
+    #[must_use]
+    pub fn unlocated(verified: bool, message: Option<String>) -> Self {
+        Self {
+            id: None,
+            verified,
+            message,
+            source: None,
+            line: None,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
 }
 
 /// Arguments for `setBreakpoints`.
@@ -693,3 +746,104 @@ pub struct BreakpointLocationsResponseBody {
 pub struct SetBreakpointsResponseBody {
     pub breakpoints: Vec<Breakpoint>,
 }
+
+/// Access type of a data breakpoint.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DataBreakpointAccessType {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// Arguments for `dataBreakpointInfo`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpointInfoArguments {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variables_reference: Option<u32>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_id: Option<u32>,
+}
+
+/// Response body for `dataBreakpointInfo`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpointInfoResponseBody {
+    pub data_id: Option<String>,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_types: Option<Vec<DataBreakpointAccessType>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_persist: Option<bool>,
+}
+
+/// DAP data breakpoint requested by the client.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DataBreakpoint {
+    pub data_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_type: Option<DataBreakpointAccessType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
+/// Arguments for `setDataBreakpoints`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDataBreakpointsArguments {
+    pub breakpoints: Vec<DataBreakpoint>,
+}
+
+/// Response body for `setDataBreakpoints`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDataBreakpointsResponseBody {
+    pub breakpoints: Vec<Breakpoint>,
+}
+
+/// DAP function breakpoint requested by the client.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionBreakpoint {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hit_condition: Option<String>,
+}
+
+/// Arguments for `setExceptionBreakpoints`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetExceptionBreakpointsArguments {
+    pub filters: Vec<String>,
+}
+
+/// Arguments for `exceptionInfo`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionInfoArguments {
+    pub thread_id: u32,
+}
+
+/// Response body for `exceptionInfo`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionInfoResponseBody {
+    pub exception_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub break_mode: String,
+}
+
+/// Arguments for `setFunctionBreakpoints`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetFunctionBreakpointsArguments {
+    pub breakpoints: Vec<FunctionBreakpoint>,
+}
@@ -7,7 +7,10 @@ use trust_runtime::debug::DebugControl;
 use trust_runtime::harness::CompileError;
 use trust_runtime::{Runtime, RuntimeMetadata};
 
-use crate::protocol::{Breakpoint, SetBreakpointsArguments, SetBreakpointsResponseBody, Source};
+use crate::protocol::{
+    Breakpoint, SetBreakpointsArguments, SetBreakpointsResponseBody, SetDataBreakpointsArguments,
+    SetDataBreakpointsResponseBody, SetFunctionBreakpointsArguments, Source,
+};
 use crate::session::{SourceFile, SourceOptionsUpdate};
 
 /// Narrow interface for debug adapters.
@@ -16,6 +19,14 @@ pub trait DebugRuntime: Send {
     fn set_program_path(&mut self, path: String);
     fn reload_program(&mut self, path: Option<&str>) -> Result<Vec<Breakpoint>, CompileError>;
     fn set_breakpoints(&mut self, args: &SetBreakpointsArguments) -> SetBreakpointsResponseBody;
+    fn set_data_breakpoints(
+        &mut self,
+        args: &SetDataBreakpointsArguments,
+    ) -> SetDataBreakpointsResponseBody;
+    fn set_function_breakpoints(
+        &mut self,
+        args: &SetFunctionBreakpointsArguments,
+    ) -> SetBreakpointsResponseBody;
     fn take_breakpoint_report(&mut self) -> Option<String>;
     fn debug_control(&self) -> DebugControl;
     fn runtime_handle(&self) -> Arc<Mutex<Runtime>>;
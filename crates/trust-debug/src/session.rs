@@ -5,17 +5,26 @@ use std::sync::{Arc, Mutex};
 use glob::glob;
 
 use trust_hir::{db::FileId, SourceKey, SourceRegistry};
+use trust_runtime::bundle_builder::project_compile_session;
 use trust_runtime::control::SourceFile as ControlSourceFile;
-use trust_runtime::debug::{DebugBreakpoint, DebugControl, HitCondition, LogFragment};
+use trust_runtime::debug::{
+    location_to_line_col, DataAccess, DebugBreakpoint, DebugControl, DebugDataBreakpoint,
+    DebugFunctionBreakpoint, HitCondition, LogFragment,
+};
 #[cfg(test)]
 use trust_runtime::harness::TestHarness;
 use trust_runtime::harness::{
-    parse_debug_expression, CompileError, CompileSession, SourceFile as HarnessSourceFile,
+    parse_debug_expression, CompileError, SourceFile as HarnessSourceFile,
 };
+use trust_runtime::io::{IoAddress, IoBinding, IoTarget};
+use trust_runtime::memory::{InstanceId, VariableStorage};
+use trust_runtime::value::ValueRef;
 use trust_runtime::{Runtime, RuntimeMetadata};
 
 use crate::protocol::{
-    Breakpoint, SetBreakpointsArguments, SetBreakpointsResponseBody, Source, SourceBreakpoint,
+    Breakpoint, DataBreakpointAccessType, FunctionBreakpoint, SetBreakpointsArguments,
+    SetBreakpointsResponseBody, SetDataBreakpointsArguments, SetDataBreakpointsResponseBody,
+    SetFunctionBreakpointsArguments, Source, SourceBreakpoint,
 };
 use crate::runtime::DebugRuntime;
 
@@ -27,6 +36,8 @@ const MSG_INVALID_LOG_MESSAGE: &str = "invalid log message";
 const MSG_INVALID_CONDITION: &str = "invalid breakpoint condition";
 const MSG_INVALID_HIT_CONDITION: &str = "invalid hit condition";
 const MSG_NO_STATEMENT: &str = "no statement at or after requested location";
+const MSG_UNKNOWN_DATA_ID: &str = "data breakpoint target not found";
+const MSG_UNKNOWN_FUNCTION: &str = "no POU, method or instance with this name";
 const DEFAULT_IGNORE_PRAGMAS: &[&str] = &["@trustlsp:runtime-ignore"];
 const PRAGMA_SCAN_LINES: usize = 20;
 
@@ -80,6 +91,7 @@ pub struct DebugSession {
     sources: HashMap<SourceKey, SourceFile>,
     source_registry: SourceRegistry,
     breakpoints: BreakpointManager,
+    function_breakpoints: Vec<FunctionBreakpoint>,
     program_path: Option<String>,
     source_options: SourceOptions,
 }
@@ -98,6 +110,7 @@ impl DebugSession {
             sources: HashMap::new(),
             source_registry: SourceRegistry::new(),
             breakpoints: BreakpointManager::new(),
+            function_breakpoints: Vec::new(),
             program_path: None,
             source_options: SourceOptions::default(),
         }
@@ -115,6 +128,7 @@ impl DebugSession {
             sources: HashMap::new(),
             source_registry: SourceRegistry::new(),
             breakpoints: BreakpointManager::new(),
+            function_breakpoints: Vec::new(),
             program_path: None,
             source_options: SourceOptions::default(),
         }
@@ -192,7 +206,9 @@ impl DebugSession {
             .iter()
             .map(|(path, text)| HarnessSourceFile::with_path(path.clone(), text.clone()))
             .collect::<Vec<_>>();
-        let compile = CompileSession::from_sources(source_files);
+        let root = resolve_root(&self.source_options, &canonicalize_lossy(Path::new(&path)))?;
+        let compile = project_compile_session(&root, source_files)
+            .map_err(|err| CompileError::new(err.to_string()))?;
         let mut runtime = compile.build_runtime()?;
         runtime.set_debug_control(self.control.clone());
         runtime.apply_retain_snapshot(&retained);
@@ -211,6 +227,7 @@ impl DebugSession {
 
         // Ensure no stale breakpoints linger across reloads.
         self.control.clear_breakpoints();
+        let _ = self.install_function_breakpoints();
 
         Ok(self.revalidate_breakpoints())
     }
@@ -264,6 +281,141 @@ impl DebugSession {
         self.breakpoints.set_breakpoints(context, args)
     }
 
+    /// Replace all data breakpoints (globals, FB instance members and I/O bindings).
+    #[must_use]
+    pub fn set_data_breakpoints(
+        &mut self,
+        args: &SetDataBreakpointsArguments,
+    ) -> SetDataBreakpointsResponseBody {
+        let profile = self.metadata.profile();
+        let mut registry = self.metadata.registry().clone();
+        let snapshot = self.control.snapshot();
+        let mut installed = Vec::new();
+        let mut breakpoints = Vec::with_capacity(args.breakpoints.len());
+        for requested in &args.breakpoints {
+            let bindings = self.metadata.io_bindings();
+            let target = match snapshot.as_ref() {
+                Some(snapshot) => resolve_data_id(&snapshot.storage, bindings, &requested.data_id),
+                None => self.runtime.lock().ok().and_then(|runtime| {
+                    resolve_data_id(runtime.storage(), bindings, &requested.data_id)
+                }),
+            };
+            let Some(target) = target else {
+                breakpoints.push(Breakpoint::unlocated(
+                    false,
+                    Some(MSG_UNKNOWN_DATA_ID.into()),
+                ));
+                continue;
+            };
+            let access = match requested.access_type {
+                Some(DataBreakpointAccessType::Read) => DataAccess::Read,
+                Some(DataBreakpointAccessType::ReadWrite) => DataAccess::ReadWrite,
+                Some(DataBreakpointAccessType::Write) | None => DataAccess::Write,
+            };
+            let mut breakpoint = DebugDataBreakpoint::new(target, access);
+            if let Some(condition) = requested.condition.as_deref() {
+                match parse_debug_expression(condition, &mut registry, profile, &[]) {
+                    Ok(expr) => breakpoint.condition = Some(expr),
+                    Err(err) => {
+                        breakpoints.push(Breakpoint::unlocated(
+                            false,
+                            Some(format!("{MSG_INVALID_CONDITION}: {err}")),
+                        ));
+                        continue;
+                    }
+                }
+            }
+            if let Some(hit_condition) = requested.hit_condition.as_deref() {
+                let Some(parsed) = parse_hit_condition(hit_condition) else {
+                    breakpoints.push(Breakpoint::unlocated(
+                        false,
+                        Some(MSG_INVALID_HIT_CONDITION.into()),
+                    ));
+                    continue;
+                };
+                breakpoint.hit_condition = Some(parsed);
+            }
+            installed.push(breakpoint);
+            breakpoints.push(Breakpoint::unlocated(true, None));
+        }
+        self.control.set_data_breakpoints(installed);
+        SetDataBreakpointsResponseBody { breakpoints }
+    }
+
+    /// Replace all function breakpoints (POU, `FB.Method` or instance path names).
+    #[must_use]
+    pub fn set_function_breakpoints(
+        &mut self,
+        args: &SetFunctionBreakpointsArguments,
+    ) -> SetBreakpointsResponseBody {
+        self.function_breakpoints = args.breakpoints.clone();
+        self.install_function_breakpoints()
+    }
+
+    fn install_function_breakpoints(&self) -> SetBreakpointsResponseBody {
+        let profile = self.metadata.profile();
+        let mut registry = self.metadata.registry().clone();
+        let snapshot = self.control.snapshot();
+        let mut installed = Vec::new();
+        let mut breakpoints = Vec::with_capacity(self.function_breakpoints.len());
+        for requested in &self.function_breakpoints {
+            let target = match snapshot.as_ref() {
+                Some(snapshot) => self
+                    .metadata
+                    .resolve_function_breakpoint(&snapshot.storage, &requested.name),
+                None => self.runtime.lock().ok().and_then(|runtime| {
+                    self.metadata
+                        .resolve_function_breakpoint(runtime.storage(), &requested.name)
+                }),
+            };
+            let Some(target) = target else {
+                breakpoints.push(Breakpoint::unlocated(
+                    false,
+                    Some(MSG_UNKNOWN_FUNCTION.into()),
+                ));
+                continue;
+            };
+            let mut breakpoint = DebugBreakpoint::new(target.location);
+            if let Some(condition) = requested.condition.as_deref() {
+                match parse_debug_expression(condition, &mut registry, profile, &target.using) {
+                    Ok(expr) => breakpoint.condition = Some(expr),
+                    Err(err) => {
+                        breakpoints.push(Breakpoint::unlocated(
+                            false,
+                            Some(format!("{MSG_INVALID_CONDITION}: {err}")),
+                        ));
+                        continue;
+                    }
+                }
+            }
+            if let Some(hit_condition) = requested.hit_condition.as_deref() {
+                let Some(parsed) = parse_hit_condition(hit_condition) else {
+                    breakpoints.push(Breakpoint::unlocated(
+                        false,
+                        Some(MSG_INVALID_HIT_CONDITION.into()),
+                    ));
+                    continue;
+                };
+                breakpoint.hit_condition = Some(parsed);
+            }
+            let file_id = target.location.file_id;
+            breakpoints.push(match self.source_text_for_file_id(file_id) {
+                Some(text) => {
+                    let (line, column) = location_to_line_col(text, &target.location);
+                    Breakpoint::verified(line + 1, column + 1, self.source_for_file_id(file_id))
+                }
+                None => Breakpoint::unlocated(true, None),
+            });
+            installed.push(DebugFunctionBreakpoint {
+                name: requested.name.as_str().into(),
+                breakpoint,
+                instance: target.instance,
+            });
+        }
+        self.control.set_function_breakpoints(installed);
+        SetBreakpointsResponseBody { breakpoints }
+    }
+
     /// Revalidate previously requested breakpoints after reload.
     pub fn revalidate_breakpoints(&mut self) -> Vec<Breakpoint> {
         let context = BreakpointContext::new(&self.sources, &self.metadata, &self.control);
@@ -293,6 +445,20 @@ impl DebugRuntime for DebugSession {
         DebugSession::set_breakpoints(self, args)
     }
 
+    fn set_data_breakpoints(
+        &mut self,
+        args: &SetDataBreakpointsArguments,
+    ) -> SetDataBreakpointsResponseBody {
+        DebugSession::set_data_breakpoints(self, args)
+    }
+
+    fn set_function_breakpoints(
+        &mut self,
+        args: &SetFunctionBreakpointsArguments,
+    ) -> SetBreakpointsResponseBody {
+        DebugSession::set_function_breakpoints(self, args)
+    }
+
     fn take_breakpoint_report(&mut self) -> Option<String> {
         DebugSession::take_breakpoint_report(self)
     }
@@ -980,6 +1146,27 @@ fn requested_breakpoints(args: &SetBreakpointsArguments) -> Vec<SourceBreakpoint
         .collect()
 }
 
+/// Resolve a data breakpoint id (`global:<name>`, `instance:<id>:<name>`, `io:<address>`).
+fn resolve_data_id(
+    storage: &VariableStorage,
+    bindings: &[IoBinding],
+    data_id: &str,
+) -> Option<ValueRef> {
+    if let Some(name) = data_id.strip_prefix("global:") {
+        return storage.ref_for_global(name);
+    }
+    if let Some(rest) = data_id.strip_prefix("instance:") {
+        let (id, name) = rest.split_once(':')?;
+        return storage.ref_for_instance(InstanceId(id.parse().ok()?), name);
+    }
+    let address = IoAddress::parse(data_id.strip_prefix("io:")?).ok()?;
+    let binding = bindings.iter().find(|binding| binding.address == address)?;
+    match &binding.target {
+        IoTarget::Name(name) => storage.ref_for_global(name),
+        IoTarget::Reference(reference) => Some(reference.clone()),
+    }
+}
+
 fn parse_hit_condition(raw: &str) -> Option<HitCondition> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
@@ -52,6 +52,7 @@ struct GlobalCandidate {
     origin: SymbolOrigin,
     range: TextRange,
     name: SmolStr,
+    is_instance: bool,
 }
 
 pub(in crate::db) fn check_shared_global_task_hazards(
@@ -78,7 +79,12 @@ pub(in crate::db) fn check_shared_global_task_hazards(
     let usage_by_global = collect_global_usage(&program_tasks, &program_accesses);
 
     for (global_id, usage) in usage_by_global {
-        if usage.writes.is_empty() {
+        let Some(global) = globals_by_id.get(&global_id) else {
+            continue;
+        };
+        // Task threads merge instance variables at task boundaries, so two
+        // tasks calling the same instance interleave its internal state.
+        if usage.writes.is_empty() && !global.is_instance {
             continue;
         }
 
@@ -87,32 +93,35 @@ pub(in crate::db) fn check_shared_global_task_hazards(
         if access_tasks.len() <= 1 {
             continue;
         }
-
-        let Some(global) = globals_by_id.get(&global_id) else {
-            continue;
-        };
         if global.origin.file_id != file_id {
             continue;
         }
 
         let access_list = format_task_list(&access_tasks, &task_info);
-        let write_list = format_task_list(&usage.writes, &task_info);
-        let message = format!(
-            "shared global '{}' accessed by multiple tasks ({access_list}) with writes in ({write_list})",
-            global.name
-        );
+        let (message, related_tasks, related_note) = if global.is_instance {
+            let message = format!(
+                "global instance '{}' used by multiple tasks ({access_list}); task threads merge its state at task boundaries",
+                global.name
+            );
+            (message, &access_tasks, "uses the instance")
+        } else {
+            let write_list = format_task_list(&usage.writes, &task_info);
+            let message = format!(
+                "shared global '{}' accessed by multiple tasks ({access_list}) with writes in ({write_list})",
+                global.name
+            );
+            (message, &usage.writes, "participates in shared writes")
+        };
         let mut diagnostic = Diagnostic::warning(
             DiagnosticCode::SharedGlobalTaskHazard,
             global.range,
             message,
         );
 
-        for task_id in usage.writes.iter().take(MAX_RELATED_TASKS) {
+        for task_id in related_tasks.iter().take(MAX_RELATED_TASKS) {
             if let Some(info) = task_info.get(task_id) {
-                diagnostic = diagnostic.with_related(
-                    info.range,
-                    format!("TASK '{}' participates in shared writes", info.label),
-                );
+                diagnostic = diagnostic
+                    .with_related(info.range, format!("TASK '{}' {related_note}", info.label));
             }
         }
 
@@ -148,11 +157,16 @@ fn collect_global_candidates(
             namespace: namespace_path_for_symbol(symbols, symbol.id),
             name: normalized_name(symbol.name.as_str()),
         };
+        let is_instance = matches!(
+            symbols.type_by_id(symbols.resolve_alias_type(symbol.type_id)),
+            Some(Type::FunctionBlock { .. } | Type::Class { .. })
+        );
         let candidate = GlobalCandidate {
             symbol_id: symbol.id,
             origin,
             range: symbol.range,
             name: symbol.name.clone(),
+            is_instance,
         };
         by_key.insert(key, candidate.clone());
         by_id.insert(symbol.id, candidate);
@@ -483,7 +497,7 @@ fn name_from_name_ref(node: &SyntaxNode) -> Option<SmolStr> {
     node.descendants_with_tokens()
         .filter_map(|e| e.into_token())
         .find(|t| t.kind() == SyntaxKind::Ident)
-        .map(|t| SmolStr::new(t.text()))
+        .map(|t| SmolStr::new(identifier_text(t.text())))
 }
 
 fn is_nested_field_expr(node: &SyntaxNode) -> bool {
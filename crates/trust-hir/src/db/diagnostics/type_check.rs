@@ -64,6 +64,9 @@ pub(in crate::db) fn type_check_pou(
         }
         checker.stmt().check_statement_list_with_labels(&stmt_list);
     }
+    if matches!(node.kind(), SyntaxKind::Program | SyntaxKind::FunctionBlock) {
+        checker.stmt().check_sfc_chart(node);
+    }
 
     checker.finish_return_checks(node);
 }
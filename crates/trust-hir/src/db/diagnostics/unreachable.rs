@@ -55,14 +55,10 @@ fn check_constant_if_branches(root: &SyntaxNode, diagnostics: &mut DiagnosticBui
                     Some(false) => {
                         mark_unreachable_statements(&branch.statements, diagnostics);
                     }
-                    Some(true) => {
-                        if previous_all_false {
-                            branch_always_taken = true;
-                        } else {
-                            previous_all_false = false;
-                        }
+                    Some(true) if previous_all_false => {
+                        branch_always_taken = true;
                     }
-                    None => {
+                    Some(true) | None => {
                         previous_all_false = false;
                     }
                 }
@@ -122,6 +118,7 @@ fn is_statement_kind(kind: SyntaxKind) -> bool {
             | SyntaxKind::ContinueStmt
             | SyntaxKind::JmpStmt
             | SyntaxKind::LabelStmt
+            | SyntaxKind::RegionStmt
             | SyntaxKind::EmptyStmt
     )
 }
@@ -17,8 +17,8 @@ use crate::symbols::{
 };
 use crate::type_check::{string_literal_info, TypeChecker};
 use crate::types::{Type, TypeId};
-use trust_syntax::parser::parse;
-use trust_syntax::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use trust_syntax::parser::{parse_with_dialect, Dialect};
+use trust_syntax::syntax::{identifier_text, SyntaxKind, SyntaxNode, SyntaxToken};
 
 mod diagnostics;
 mod queries;
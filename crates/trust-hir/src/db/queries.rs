@@ -27,6 +27,9 @@ pub trait SourceDatabase {
 
     /// Set the source text for a file.
     fn set_source_text(&mut self, file_id: FileId, text: String);
+
+    /// Vendor dialect used to parse every source.
+    fn dialect(&self) -> Dialect;
 }
 
 /// Derived queries for semantic analysis.
@@ -55,6 +58,7 @@ pub struct Database {
     sources: FxHashMap<FileId, Arc<String>>,
     salsa_state: Mutex<salsa_backend::SalsaState>,
     source_revision: AtomicU64,
+    dialect: Dialect,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -69,6 +73,7 @@ impl Default for Database {
             sources: FxHashMap::default(),
             salsa_state: Mutex::new(salsa_backend::SalsaState::default()),
             source_revision: AtomicU64::new(1),
+            dialect: Dialect::default(),
         }
     }
 }
@@ -82,6 +87,7 @@ impl Database {
                 true, false,
             )),
             source_revision: AtomicU64::new(1),
+            dialect: Dialect::default(),
         }
     }
 }
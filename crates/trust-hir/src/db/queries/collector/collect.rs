@@ -8,6 +8,7 @@ impl SymbolCollector {
             SyntaxKind::Resource => self.collect_resource(node),
             SyntaxKind::TaskConfig => self.collect_task_config(node),
             SyntaxKind::ProgramConfig => self.collect_program_config(node),
+            SyntaxKind::DataBlock => self.collect_data_block(node),
             SyntaxKind::Function => {
                 let return_type = self.return_type_from_node(node).unwrap_or(TypeId::UNKNOWN);
                 self.collect_pou(
@@ -70,6 +71,9 @@ impl SymbolCollector {
             SyntaxKind::UsingDirective => {
                 self.collect_using_directive(node);
             }
+            SyntaxKind::Step => {
+                self.collect_sfc_step(node);
+            }
             _ => {
                 for child in node.children() {
                     self.visit_node(&child);
@@ -225,6 +229,45 @@ impl SymbolCollector {
         self.table.pop_scope();
     }
 
+    /// Siemens DATA_BLOCK: a global variable whose type is either the declared
+    /// instance/UDT type or an anonymous struct built from the block fields.
+    fn collect_data_block(&mut self, node: &SyntaxNode) {
+        let Some((name, range)) = name_from_node(node) else {
+            return;
+        };
+
+        let type_id = if let Some(type_ref) = node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::TypeRef)
+        {
+            self.resolve_type_from_ref(&type_ref)
+        } else if let Some(struct_def) = node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::StructDef)
+        {
+            self.collect_struct_type(&struct_def, self.qualify_current_name(&name))
+        } else {
+            let var_decls = node
+                .children()
+                .filter(|child| child.kind() == SyntaxKind::VarBlock)
+                .flat_map(|block| block.children())
+                .filter(|child| child.kind() == SyntaxKind::VarDecl);
+            self.register_struct_from_var_decls(self.qualify_current_name(&name), var_decls)
+        };
+
+        let mut symbol = Symbol::new(
+            SymbolId::UNKNOWN,
+            name,
+            SymbolKind::Variable {
+                qualifier: VarQualifier::Global,
+            },
+            type_id,
+            range,
+        );
+        symbol.parent = self.current_parent();
+        self.declare_symbol(symbol);
+    }
+
     fn collect_resource(&mut self, node: &SyntaxNode) {
         let Some((name, range)) = name_from_node(node) else {
             for child in node.children() {
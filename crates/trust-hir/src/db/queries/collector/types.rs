@@ -92,9 +92,19 @@ impl SymbolCollector {
     }
 
     pub(super) fn collect_struct_type(&mut self, node: &SyntaxNode, name: SmolStr) -> TypeId {
+        let var_decls = node.children().filter(|n| n.kind() == SyntaxKind::VarDecl);
+        self.register_struct_from_var_decls(name, var_decls)
+    }
+
+    /// Registers a struct type whose fields are the given VAR declarations.
+    pub(super) fn register_struct_from_var_decls(
+        &mut self,
+        name: SmolStr,
+        var_decls: impl Iterator<Item = SyntaxNode>,
+    ) -> TypeId {
         let mut fields = Vec::new();
 
-        for var_decl in node.children().filter(|n| n.kind() == SyntaxKind::VarDecl) {
+        for var_decl in var_decls {
             let (field_names, field_type, direct_address) = self.extract_var_decl_info(&var_decl);
             for (field_name, range) in field_names {
                 self.validate_identifier(&field_name, range, false);
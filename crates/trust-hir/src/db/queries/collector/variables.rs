@@ -1,5 +1,6 @@
 use super::*;
 use crate::db::diagnostics::is_expression_kind;
+use crate::types::{sfc_step_fields, SFC_STEP_TYPE_NAME};
 
 impl SymbolCollector {
     pub(super) fn extract_var_decl_info(
@@ -194,6 +195,29 @@ impl SymbolCollector {
         }
     }
 
+    /// Declares an SFC step as a read-only variable exposing `X` and `T`.
+    pub(super) fn collect_sfc_step(&mut self, node: &SyntaxNode) {
+        let Some((name, range)) = name_from_node(node) else {
+            return;
+        };
+        let type_id = self
+            .table
+            .lookup_type(SFC_STEP_TYPE_NAME)
+            .unwrap_or_else(|| {
+                self.table
+                    .register_struct_type(SFC_STEP_TYPE_NAME, sfc_step_fields())
+            });
+        let mut symbol = Symbol::new(
+            SymbolId::UNKNOWN,
+            name,
+            SymbolKind::Constant,
+            type_id,
+            range,
+        );
+        symbol.parent = self.current_parent();
+        self.declare_symbol(symbol);
+    }
+
     pub(super) fn collect_var_config_block(&mut self, _node: &SyntaxNode) {}
 
     fn in_configuration_scope(&self) -> bool {
@@ -55,7 +55,8 @@ impl Database {
         }
 
         let text = self.sources.get(&file_id)?;
-        let source = salsa_backend::SourceInput::new(&state.db, text.as_ref().clone());
+        let source =
+            salsa_backend::SourceInput::new(&state.db, text.as_ref().clone(), self.dialect);
         state.sources.insert(file_id, source);
         salsa_backend::sync_project_inputs(state);
         Some(source)
@@ -89,6 +90,20 @@ impl Database {
         tables
     }
 
+    /// Sets the vendor dialect used to parse every source.
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        if self.dialect == dialect {
+            return;
+        }
+        self.dialect = dialect;
+        self.with_salsa_state(|state| {
+            let sources = state.sources.values().copied().collect::<Vec<_>>();
+            for source in sources {
+                source.set_dialect(&mut state.db).to(dialect);
+            }
+        });
+    }
+
     /// Returns all known file IDs.
     pub fn file_ids(&self) -> Vec<FileId> {
         self.sources.keys().copied().collect()
@@ -217,7 +232,8 @@ impl Database {
                 }
                 continue;
             }
-            let source = salsa_backend::SourceInput::new(&state.db, text.as_ref().clone());
+            let source =
+                salsa_backend::SourceInput::new(&state.db, text.as_ref().clone(), self.dialect);
             state.sources.insert(known_file_id, source);
             project_changed = true;
         }
@@ -271,7 +287,8 @@ impl SourceDatabase for Database {
             if let Some(source) = state.sources.get(&file_id).copied() {
                 source.set_text(&mut state.db).to(text.as_ref().clone());
             } else {
-                let source = salsa_backend::SourceInput::new(&state.db, text.as_ref().clone());
+                let source =
+                    salsa_backend::SourceInput::new(&state.db, text.as_ref().clone(), self.dialect);
                 state.sources.insert(file_id, source);
                 file_set_changed = true;
             }
@@ -281,6 +298,10 @@ impl SourceDatabase for Database {
             state.synced_revision = new_revision;
         });
     }
+
+    fn dialect(&self) -> Dialect {
+        self.dialect
+    }
 }
 
 impl SemanticDatabase for Database {
@@ -6,7 +6,10 @@ pub(in crate::db) fn name_from_node(node: &SyntaxNode) -> Option<(SmolStr, TextR
         .find(|n| n.kind() == SyntaxKind::Name)
         .and_then(|name_node| first_ident_token(&name_node))
         .or_else(|| first_ident_token(node))?;
-    Some((SmolStr::new(token.text()), token.text_range()))
+    Some((
+        SmolStr::new(identifier_text(token.text())),
+        token.text_range(),
+    ))
 }
 
 pub(in crate::db) fn qualified_name_parts(
@@ -222,7 +225,7 @@ pub(in crate::db) fn type_path_from_type_ref(
         .filter_map(|e| e.into_token())
     {
         if token.kind() == SyntaxKind::Ident {
-            let part = SmolStr::new(token.text());
+            let part = SmolStr::new(identifier_text(token.text()));
             return Some((vec![(part, token.text_range())], token.text_range()));
         }
         if let Some(name) = builtin_type_name_from_syntax(token.kind()) {
@@ -451,10 +454,8 @@ pub(in crate::db) fn parse_access_path(node: &SyntaxNode) -> Option<ParsedAccess
                 SyntaxKind::DirectAddress => {
                     return None;
                 }
-                SyntaxKind::IntLiteral => {
-                    if expect_member {
-                        return None;
-                    }
+                SyntaxKind::IntLiteral if expect_member => {
+                    return None;
                 }
                 SyntaxKind::Dot => {
                     expect_member = true;
@@ -463,16 +464,12 @@ pub(in crate::db) fn parse_access_path(node: &SyntaxNode) -> Option<ParsedAccess
                     in_index = true;
                     index_count = 1;
                 }
-                SyntaxKind::Comma => {
-                    if in_index {
-                        index_count += 1;
-                    }
+                SyntaxKind::Comma if in_index => {
+                    index_count += 1;
                 }
-                SyntaxKind::RBracket => {
-                    if in_index {
-                        segments.push(AccessPathSegment::Index(index_count));
-                        in_index = false;
-                    }
+                SyntaxKind::RBracket if in_index => {
+                    segments.push(AccessPathSegment::Index(index_count));
+                    in_index = false;
                 }
                 _ => {}
             }
@@ -544,12 +541,12 @@ pub(in crate::db) fn program_config_instance_and_type(
                     }
                 }
             }
-            SyntaxKind::QualifiedName | SyntaxKind::TypeRef => {
-                if expect_type && type_parts.is_none() {
-                    if let Some((parts, _)) = qualified_name_parts(child) {
-                        type_parts = Some(parts.into_iter().map(|(name, _)| name).collect());
-                        expect_type = false;
-                    }
+            SyntaxKind::QualifiedName | SyntaxKind::TypeRef
+                if expect_type && type_parts.is_none() =>
+            {
+                if let Some((parts, _)) = qualified_name_parts(child) {
+                    type_parts = Some(parts.into_iter().map(|(name, _)| name).collect());
+                    expect_type = false;
                 }
             }
             _ => {}
@@ -202,6 +202,7 @@ pub(super) fn project_inputs(state: &SalsaState) -> ProjectInputs {
 pub(super) struct SourceInput {
     #[returns(ref)]
     pub(super) text: String,
+    pub(super) dialect: Dialect,
 }
 
 #[salsa::input]
@@ -212,7 +213,7 @@ pub(super) struct ProjectInputs {
 
 #[salsa::tracked(returns(ref))]
 pub(super) fn parse_green(db: &dyn salsa::Database, input: SourceInput) -> GreenNode {
-    let parsed = parse(input.text(db));
+    let parsed = parse_with_dialect(input.text(db), input.dialect(db));
     parsed.syntax().green().into_owned()
 }
 
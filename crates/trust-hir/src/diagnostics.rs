@@ -74,6 +74,8 @@ pub enum DiagnosticCode {
     InvalidTaskConfig,
     /// Unknown task reference in program configuration.
     UnknownTask,
+    /// Invalid Sequential Function Chart element.
+    InvalidSfc,
 
     // Warnings (W001-W099)
     /// Unused variable.
@@ -140,6 +142,7 @@ impl DiagnosticCode {
             Self::CyclicDependency => "E305",
             Self::InvalidTaskConfig => "E306",
             Self::UnknownTask => "E307",
+            Self::InvalidSfc => "E308",
             // Warnings
             Self::UnusedVariable => "W001",
             Self::UnusedParameter => "W002",
@@ -186,7 +189,8 @@ impl DiagnosticCode {
             | Self::OutOfRange
             | Self::CyclicDependency
             | Self::InvalidTaskConfig
-            | Self::UnknownTask => DiagnosticSeverity::Error,
+            | Self::UnknownTask
+            | Self::InvalidSfc => DiagnosticSeverity::Error,
 
             // Warnings
             Self::UnusedVariable
@@ -71,6 +71,8 @@ impl SymbolTable {
         self.register_timer_function_block("TP_LTIME", TypeId::LTIME);
         self.register_timer_function_block("TON_LTIME", TypeId::LTIME);
         self.register_timer_function_block("TOF_LTIME", TypeId::LTIME);
+
+        self.register_plant_function_blocks();
     }
 
     fn register_bistable_function_blocks(&mut self) {
@@ -197,6 +199,62 @@ impl SymbolTable {
         );
     }
 
+    fn register_plant_function_blocks(&mut self) {
+        self.register_simple_function_block(
+            "PLANT_LAG",
+            &[
+                ("IN", TypeId::REAL, ParamDirection::In),
+                ("T", TypeId::TIME, ParamDirection::In),
+                ("OUT", TypeId::REAL, ParamDirection::Out),
+            ],
+        );
+        self.register_simple_function_block(
+            "PLANT_DEADTIME",
+            &[
+                ("IN", TypeId::REAL, ParamDirection::In),
+                ("DELAY", TypeId::TIME, ParamDirection::In),
+                ("OUT", TypeId::REAL, ParamDirection::Out),
+            ],
+        );
+        self.register_simple_function_block(
+            "PLANT_INTEGRATOR",
+            &[
+                ("IN", TypeId::REAL, ParamDirection::In),
+                ("RESET", TypeId::BOOL, ParamDirection::In),
+                ("Y0", TypeId::REAL, ParamDirection::In),
+                ("OUT", TypeId::REAL, ParamDirection::Out),
+            ],
+        );
+        self.register_simple_function_block(
+            "PLANT_VALVE",
+            &[
+                ("CMD", TypeId::REAL, ParamDirection::In),
+                ("TRAVEL", TypeId::TIME, ParamDirection::In),
+                ("KV", TypeId::REAL, ParamDirection::In),
+                ("POS", TypeId::REAL, ParamDirection::Out),
+                ("FLOW", TypeId::REAL, ParamDirection::Out),
+                ("OPENED", TypeId::BOOL, ParamDirection::Out),
+                ("CLOSED", TypeId::BOOL, ParamDirection::Out),
+            ],
+        );
+        self.register_simple_function_block(
+            "PLANT_TANK",
+            &[
+                ("QIN", TypeId::REAL, ParamDirection::In),
+                ("QOUT", TypeId::REAL, ParamDirection::In),
+                ("AREA", TypeId::REAL, ParamDirection::In),
+                ("HEIGHT", TypeId::REAL, ParamDirection::In),
+                ("RESET", TypeId::BOOL, ParamDirection::In),
+                ("L0", TypeId::REAL, ParamDirection::In),
+                ("LEVEL", TypeId::REAL, ParamDirection::Out),
+                ("VOLUME", TypeId::REAL, ParamDirection::Out),
+                ("OUTFLOW", TypeId::REAL, ParamDirection::Out),
+                ("OVERFLOW", TypeId::BOOL, ParamDirection::Out),
+                ("EMPTY", TypeId::BOOL, ParamDirection::Out),
+            ],
+        );
+    }
+
     fn register_simple_function_block(
         &mut self,
         name: &str,
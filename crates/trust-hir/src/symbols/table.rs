@@ -31,6 +31,8 @@ pub struct SymbolTable {
     next_id: u32,
     /// Next type ID to assign.
     next_type_id: u32,
+    /// First symbol ID after the built-in function blocks and their parameters.
+    first_user_id: u32,
 }
 
 impl Default for SymbolTable {
@@ -55,6 +57,7 @@ impl SymbolTable {
             const_values: FxHashMap::default(),
             next_id: 0,
             next_type_id: TypeId::USER_TYPES_START,
+            first_user_id: 0,
         };
         // Create global scope
         table
@@ -62,6 +65,7 @@ impl SymbolTable {
             .push(Scope::new(ScopeId::GLOBAL, ScopeKind::Global, None, None));
         table.register_builtin_types();
         table.register_builtin_function_blocks();
+        table.first_user_id = table.next_id;
         table
     }
 
@@ -295,9 +299,11 @@ impl SymbolTable {
         if let Some(id) = self.global_names.get(&normalized) {
             return Some(*id);
         }
+        // Prefer declared symbols over built-in FB parameters (`IN`, `Q`, `OUT`).
         self.symbols
             .values()
-            .find(|sym| sym.name.as_str().eq_ignore_ascii_case(name))
+            .filter(|sym| sym.name.as_str().eq_ignore_ascii_case(name))
+            .min_by_key(|sym| sym.id.0 < self.first_user_id)
             .map(|sym| sym.id)
     }
 
@@ -12,7 +12,7 @@ use crate::symbols::{
     Visibility,
 };
 use crate::types::{Type, TypeId};
-use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
+use trust_syntax::syntax::{identifier_text, SyntaxKind, SyntaxNode};
 
 mod calls;
 mod compatibility;
@@ -21,6 +21,7 @@ mod expr;
 mod helpers;
 mod literals;
 mod ops;
+mod sfc;
 mod standard;
 mod stmt;
 mod symbol_resolve;
@@ -171,6 +172,7 @@ fn is_statement_kind(kind: SyntaxKind) -> bool {
             | SyntaxKind::ContinueStmt
             | SyntaxKind::JmpStmt
             | SyntaxKind::LabelStmt
+            | SyntaxKind::RegionStmt
             | SyntaxKind::EmptyStmt
     )
 }
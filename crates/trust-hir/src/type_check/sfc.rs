@@ -0,0 +1,258 @@
+use super::*;
+use crate::types::SFC_STEP_TYPE_NAME;
+
+/// Action qualifiers executed by the runtime.
+const SUPPORTED_QUALIFIERS: &[&str] = &["N", "R", "S", "P", "L", "D"];
+/// Qualifiers defined by IEC 61131-3 that are not executed yet.
+const UNSUPPORTED_QUALIFIERS: &[&str] = &["P0", "P1", "SD", "DS", "SL"];
+/// Qualifiers that require a duration operand.
+const TIMED_QUALIFIERS: &[&str] = &["L", "D", "SD", "DS", "SL"];
+
+impl<'a, 'b> StmtChecker<'a, 'b> {
+    // ========== Sequential Function Chart ==========
+
+    /// Checks the SFC network declared in a PROGRAM or FUNCTION_BLOCK body.
+    pub(crate) fn check_sfc_chart(&mut self, pou: &SyntaxNode) {
+        let elements = sfc_elements(pou);
+        let steps: Vec<_> = elements
+            .iter()
+            .filter(|n| n.kind() == SyntaxKind::Step)
+            .cloned()
+            .collect();
+        for element in &elements {
+            match element.kind() {
+                SyntaxKind::Step => self.check_sfc_step(element),
+                SyntaxKind::Transition => self.check_sfc_transition(element),
+                _ => {}
+            }
+        }
+
+        if steps.is_empty() {
+            if let Some(transition) = elements.first() {
+                self.checker.diagnostics.error(
+                    DiagnosticCode::InvalidSfc,
+                    transition.text_range(),
+                    "transition declared without any SFC steps",
+                );
+            }
+            return;
+        }
+
+        let initial: Vec<_> = steps.iter().filter(|step| is_initial_step(step)).collect();
+        match initial.as_slice() {
+            [_] => {}
+            [] => {
+                self.checker.diagnostics.error(
+                    DiagnosticCode::InvalidSfc,
+                    steps[0].text_range(),
+                    "SFC network requires exactly one INITIAL_STEP",
+                );
+            }
+            [_, rest @ ..] => {
+                for step in rest {
+                    self.checker.diagnostics.error(
+                        DiagnosticCode::InvalidSfc,
+                        step.text_range(),
+                        "SFC network requires exactly one INITIAL_STEP",
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_sfc_step(&mut self, node: &SyntaxNode) {
+        for association in node
+            .children()
+            .filter(|n| n.kind() == SyntaxKind::ActionAssociation)
+        {
+            self.check_sfc_action_association(node, &association);
+        }
+    }
+
+    fn check_sfc_action_association(&mut self, step: &SyntaxNode, node: &SyntaxNode) {
+        let mut refs = node.children().filter(|n| n.kind() == SyntaxKind::NameRef);
+        if let Some(action_ref) = refs.next() {
+            self.check_sfc_action_target(step, &action_ref);
+        }
+        // Remaining name references are indicator variables.
+        for indicator in refs {
+            let ty = self.checker.expr().check_expression(&indicator);
+            self.checker
+                .expr()
+                .check_boolean(ty, indicator.text_range());
+        }
+
+        let Some(qualifier) = node
+            .children()
+            .find(|n| n.kind() == SyntaxKind::ActionQualifier)
+        else {
+            return;
+        };
+        let Some(token) = qualifier
+            .children_with_tokens()
+            .filter_map(|e| e.into_token())
+            .find(|t| t.kind() == SyntaxKind::Ident)
+        else {
+            return;
+        };
+        let text = token.text().to_ascii_uppercase();
+        if UNSUPPORTED_QUALIFIERS.contains(&text.as_str()) {
+            self.checker.diagnostics.error(
+                DiagnosticCode::InvalidSfc,
+                token.text_range(),
+                format!("action qualifier '{}' is not supported", token.text()),
+            );
+        } else if !SUPPORTED_QUALIFIERS.contains(&text.as_str()) {
+            self.checker.diagnostics.error(
+                DiagnosticCode::InvalidSfc,
+                token.text_range(),
+                format!("unknown action qualifier '{}'", token.text()),
+            );
+        }
+
+        if TIMED_QUALIFIERS.contains(&text.as_str()) {
+            if let Some(duration) = first_expression_child(&qualifier) {
+                let ty = self.checker.expr().check_expression(&duration);
+                let resolved = self.checker.resolve_alias_type(ty);
+                if resolved != TypeId::UNKNOWN
+                    && resolved != TypeId::TIME
+                    && resolved != TypeId::LTIME
+                {
+                    self.checker.diagnostics.error(
+                        DiagnosticCode::TypeMismatch,
+                        duration.text_range(),
+                        "expected TIME duration for timed action qualifier",
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_sfc_action_target(&mut self, step: &SyntaxNode, action_ref: &SyntaxNode) {
+        let Some(name) = self.checker.resolve_ref().get_name_from_ref(action_ref) else {
+            return;
+        };
+        let declared_action = step.parent().is_some_and(|body| {
+            body.children()
+                .filter(|n| n.kind() == SyntaxKind::Action)
+                .filter_map(|action| action.children().find(|n| n.kind() == SyntaxKind::Name))
+                .filter_map(|name| self.checker.resolve_ref().get_name_from_ref(&name))
+                .any(|action| action.eq_ignore_ascii_case(name.as_str()))
+        });
+        if declared_action {
+            return;
+        }
+
+        let symbol_type = self
+            .checker
+            .symbols
+            .resolve(name.as_str(), self.checker.current_scope)
+            .and_then(|id| self.checker.symbols.get(id))
+            .filter(|symbol| {
+                matches!(
+                    symbol.kind,
+                    SymbolKind::Variable { .. } | SymbolKind::Parameter { .. }
+                )
+            })
+            .map(|symbol| symbol.type_id);
+        match symbol_type {
+            Some(type_id) if self.checker.resolve_alias_type(type_id) == TypeId::BOOL => {}
+            Some(_) => {
+                self.checker.diagnostics.error(
+                    DiagnosticCode::InvalidSfc,
+                    action_ref.text_range(),
+                    format!("action variable '{}' must be of type BOOL", name),
+                );
+            }
+            None => {
+                self.checker.diagnostics.error(
+                    DiagnosticCode::InvalidSfc,
+                    action_ref.text_range(),
+                    format!("unknown action '{}'", name),
+                );
+            }
+        }
+    }
+
+    fn check_sfc_transition(&mut self, node: &SyntaxNode) {
+        if let Some(priority) = first_expression_child(node) {
+            let ty = self.checker.expr().check_expression(&priority);
+            let resolved = self.checker.resolve_alias_type(ty);
+            let is_integer = self
+                .checker
+                .symbols
+                .type_by_id(resolved)
+                .is_some_and(|ty| ty.is_integer());
+            if resolved != TypeId::UNKNOWN && !is_integer {
+                self.checker.diagnostics.error(
+                    DiagnosticCode::TypeMismatch,
+                    priority.text_range(),
+                    "expected integer transition priority",
+                );
+            }
+        }
+
+        for step_list in node.children().filter(|n| n.kind() == SyntaxKind::StepList) {
+            for step_ref in step_list
+                .children()
+                .filter(|n| n.kind() == SyntaxKind::NameRef)
+            {
+                self.check_sfc_step_ref(&step_ref);
+            }
+        }
+
+        if let Some(condition) = node
+            .children()
+            .find(|n| n.kind() == SyntaxKind::Condition)
+            .and_then(|n| first_expression_child(&n))
+        {
+            let ty = self.checker.expr().check_expression(&condition);
+            self.checker
+                .expr()
+                .check_boolean(ty, condition.text_range());
+        }
+    }
+
+    fn check_sfc_step_ref(&mut self, step_ref: &SyntaxNode) {
+        let Some(name) = self.checker.resolve_ref().get_name_from_ref(step_ref) else {
+            return;
+        };
+        let step_type = self.checker.symbols.lookup_type(SFC_STEP_TYPE_NAME);
+        let is_step = self
+            .checker
+            .symbols
+            .resolve(name.as_str(), self.checker.current_scope)
+            .and_then(|id| self.checker.symbols.get(id))
+            .is_some_and(|symbol| step_type.is_some_and(|ty| symbol.type_id == ty));
+        if !is_step {
+            self.checker.diagnostics.error(
+                DiagnosticCode::InvalidSfc,
+                step_ref.text_range(),
+                format!("unknown SFC step '{}'", name),
+            );
+        }
+    }
+}
+
+/// Returns the steps and transitions of a POU body, in declaration order.
+///
+/// PROGRAM bodies keep SFC elements inside the statement list, while
+/// FUNCTION_BLOCK bodies declare them next to methods and actions.
+fn sfc_elements(pou: &SyntaxNode) -> Vec<SyntaxNode> {
+    pou.children()
+        .flat_map(|child| {
+            if child.kind() == SyntaxKind::StmtList {
+                child.children().collect::<Vec<_>>()
+            } else {
+                vec![child]
+            }
+        })
+        .filter(|n| matches!(n.kind(), SyntaxKind::Step | SyntaxKind::Transition))
+        .collect()
+}
+
+fn is_initial_step(step: &SyntaxNode) -> bool {
+    step.children_with_tokens()
+        .filter_map(|e| e.into_token())
+        .any(|token| token.kind() == SyntaxKind::KwInitialStep)
+}
@@ -18,6 +18,7 @@ impl<'a, 'b> StmtChecker<'a, 'b> {
             SyntaxKind::ContinueStmt => self.check_continue_stmt(node),
             SyntaxKind::JmpStmt => self.check_jmp_stmt(node),
             SyntaxKind::LabelStmt => self.check_label_stmt(node),
+            SyntaxKind::RegionStmt => self.check_region_stmt(node),
             SyntaxKind::StmtList => {
                 for child in node.children() {
                     self.check_statement(&child);
@@ -659,6 +660,12 @@ impl<'a, 'b> StmtChecker<'a, 'b> {
         }
     }
 
+    fn check_region_stmt(&mut self, node: &SyntaxNode) {
+        if let Some(body) = node.children().find(|n| n.kind() == SyntaxKind::StmtList) {
+            self.check_statement(&body);
+        }
+    }
+
     fn check_statement_children(&mut self, node: &SyntaxNode) {
         for child in node.children() {
             if is_statement_kind(child.kind()) {
@@ -25,7 +25,7 @@ impl<'a, 'b> ResolveCheckerRef<'a, 'b> {
                     | SyntaxKind::KwNewDunder
                     | SyntaxKind::KwDeleteDunder
             ) {
-                return Some(SmolStr::new(token.text()));
+                return Some(SmolStr::new(identifier_text(token.text())));
             }
         }
         None
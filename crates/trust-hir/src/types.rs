@@ -7,6 +7,8 @@ mod builtins;
 mod compat;
 mod defs;
 mod registry;
+mod sfc;
 
 pub use defs::{StructField, Type, TypeId, UnionVariant};
 pub use registry::TypeRegistry;
+pub use sfc::{sfc_step_fields, SFC_STEP_TYPE_NAME};
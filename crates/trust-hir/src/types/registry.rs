@@ -34,6 +34,12 @@ impl TypeRegistry {
         self.names.insert(SmolStr::new(name), id);
     }
 
+    /// Returns the ID the next registered type receives.
+    #[must_use]
+    pub fn next_type_id(&self) -> TypeId {
+        TypeId(self.next_id)
+    }
+
     /// Registers a new type and returns its ID.
     pub fn register(&mut self, name: impl Into<SmolStr>, ty: Type) -> TypeId {
         let id = TypeId(self.next_id);
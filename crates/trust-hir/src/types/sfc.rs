@@ -0,0 +1,30 @@
+//! Implicit types backing Sequential Function Chart (SFC) steps.
+//!
+//! Every SFC step exposes a read-only `X` (active) flag and `T` (elapsed
+//! time since activation), which are modelled as a hidden struct type.
+
+use smol_str::SmolStr;
+
+use super::defs::{StructField, TypeId};
+
+/// Name of the implicit struct type used for SFC step variables.
+///
+/// The `__` prefix is not a valid ST identifier, so user types cannot collide.
+pub const SFC_STEP_TYPE_NAME: &str = "__SFC_STEP";
+
+/// Returns the fields of the implicit SFC step struct (`X: BOOL`, `T: TIME`).
+#[must_use]
+pub fn sfc_step_fields() -> Vec<StructField> {
+    vec![
+        StructField {
+            name: SmolStr::new("X"),
+            type_id: TypeId::BOOL,
+            address: None,
+        },
+        StructField {
+            name: SmolStr::new("T"),
+            type_id: TypeId::TIME,
+            address: None,
+        },
+    ]
+}
@@ -5,10 +5,17 @@ pub use trust_hir::db::{Database, FileId, SemanticDatabase, SourceDatabase};
 pub use trust_hir::diagnostics::{DiagnosticCode, DiagnosticSeverity};
 pub use trust_hir::symbols::{ParamDirection, SymbolKind, Visibility};
 pub use trust_hir::Type;
+pub use trust_syntax::parser::Dialect;
 
 /// Helper to check diagnostics for a source file.
 pub fn check_errors(source: &str) -> Vec<DiagnosticCode> {
+    check_errors_with_dialect(source, Dialect::Iec)
+}
+
+/// Helper to check diagnostics for a source file parsed with a vendor dialect.
+pub fn check_errors_with_dialect(source: &str, dialect: Dialect) -> Vec<DiagnosticCode> {
     let mut db = Database::new();
+    db.set_dialect(dialect);
     let file = FileId(0);
     db.set_source_text(file, source.to_string());
     db.diagnostics(file)
@@ -24,9 +31,20 @@ pub fn check_no_errors(source: &str) {
     assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
 }
 
+/// Helper to assert no errors in source parsed with a vendor dialect.
+pub fn check_no_errors_with_dialect(source: &str, dialect: Dialect) {
+    let errors = check_errors_with_dialect(source, dialect);
+    assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+}
+
 /// Helper to assert a specific error is present.
 pub fn check_has_error(source: &str, expected: DiagnosticCode) {
-    let errors = check_errors(source);
+    check_has_error_with_dialect(source, expected, Dialect::Iec);
+}
+
+/// Helper to assert a specific error is present with a vendor dialect.
+pub fn check_has_error_with_dialect(source: &str, expected: DiagnosticCode, dialect: Dialect) {
+    let errors = check_errors_with_dialect(source, dialect);
     assert!(
         errors.contains(&expected),
         "Expected {:?} in {:?}",
@@ -135,3 +135,127 @@ END_PROGRAM
 "#,
     );
 }
+
+#[test]
+fn test_siemens_data_blocks_and_quoted_names() {
+    check_no_errors_with_dialect(
+        r#"
+FUNCTION_BLOCK "FB_Motor"
+VERSION : 0.1
+    VAR_INPUT
+        enable : BOOL;
+    END_VAR
+    VAR
+        #running : BOOL;
+    END_VAR
+BEGIN
+    REGION Control
+        #running := #enable;
+    END_REGION
+END_FUNCTION_BLOCK
+
+DATA_BLOCK "Line_DB"
+{ S7_Optimized_Access := 'TRUE' }
+VERSION : 0.1
+NON_RETAIN
+    VAR
+        speed : INT := 10;
+        active : BOOL;
+    END_VAR
+BEGIN
+    speed := 20;
+END_DATA_BLOCK
+
+DATA_BLOCK "Motor_Inst"
+"FB_Motor"
+BEGIN
+END_DATA_BLOCK
+
+ORGANIZATION_BLOCK "Main"
+TITLE = Main cycle
+VERSION : 0.1
+    VAR_TEMP
+        tmp : INT;
+    END_VAR
+BEGIN
+    #tmp := "Line_DB".speed;
+    "Line_DB".active := #tmp > 5;
+    "Motor_Inst"(enable := "Line_DB".active);
+END_ORGANIZATION_BLOCK
+"#,
+        Dialect::Siemens,
+    );
+}
+
+#[test]
+fn test_siemens_data_block_unknown_field() {
+    check_has_error_with_dialect(
+        r#"
+DATA_BLOCK "Line_DB"
+    VAR
+        speed : INT;
+    END_VAR
+BEGIN
+END_DATA_BLOCK
+
+ORGANIZATION_BLOCK "Main"
+BEGIN
+    "Line_DB".missing := 1;
+END_ORGANIZATION_BLOCK
+"#,
+        DiagnosticCode::CannotResolve,
+        Dialect::Siemens,
+    );
+}
+
+#[test]
+fn test_siemens_block_words_are_identifiers_in_iec() {
+    check_no_errors(
+        r#"
+PROGRAM Main
+    VAR data_block : INT; END_VAR
+    data_block := data_block + 1;
+END_PROGRAM
+"#,
+    );
+}
+
+#[test]
+fn test_siemens_dialect_reads_quoted_operands_as_names() {
+    let source = r#"
+DATA_BLOCK "Line_DB"
+    VAR
+        speed : INT;
+    END_VAR
+BEGIN
+END_DATA_BLOCK
+
+ORGANIZATION_BLOCK "Main"
+    VAR_TEMP
+        speed : INT;
+    END_VAR
+BEGIN
+    #speed := "Line_DB".speed + 1;
+    #speed := "speed" * 2;
+END_ORGANIZATION_BLOCK
+"#;
+    check_no_errors_with_dialect(source, Dialect::Siemens);
+
+    let source = r#"
+PROGRAM Main
+    VAR speed : INT; END_VAR
+    #speed := "speed" * 2;
+END_PROGRAM
+"#;
+    let mut db = Database::new();
+    let file = FileId(0);
+    db.set_source_text(file, source.to_string());
+    let has_errors = |db: &Database| db.diagnostics(file).iter().any(|d| d.is_error());
+    assert!(has_errors(&db), "\"speed\" is a WSTRING literal in IEC");
+
+    db.set_dialect(Dialect::Siemens);
+    assert!(!has_errors(&db), "{:?}", db.diagnostics(file));
+
+    db.set_dialect(Dialect::Iec);
+    assert!(has_errors(&db));
+}
@@ -0,0 +1,125 @@
+mod common;
+use common::*;
+
+// Sequential Function Chart Tests
+#[test]
+fn test_sfc_program_valid() {
+    check_no_errors(
+        r#"
+PROGRAM Main
+VAR
+    start : BOOL;
+    done : BOOL;
+    lamp : BOOL;
+    count : INT;
+END_VAR
+    INITIAL_STEP Idle:
+    END_STEP
+    STEP Fill:
+        Count(N);
+        lamp(D, T#2s);
+    END_STEP
+    TRANSITION FROM Idle TO Fill := start;
+    END_TRANSITION
+    TRANSITION (PRIORITY := 1) FROM Fill TO Idle := done AND Fill.T > T#5s;
+    END_TRANSITION
+    ACTION Count:
+        count := count + 1;
+        IF Fill.X THEN
+            lamp := TRUE;
+        END_IF;
+    END_ACTION
+END_PROGRAM
+"#,
+    );
+}
+
+#[test]
+fn test_sfc_transition_condition_must_be_bool() {
+    check_has_error(
+        r#"
+PROGRAM Main
+VAR
+    count : INT;
+END_VAR
+    INITIAL_STEP Idle:
+    END_STEP
+    STEP Run:
+    END_STEP
+    TRANSITION FROM Idle TO Run := count;
+    END_TRANSITION
+END_PROGRAM
+"#,
+        DiagnosticCode::TypeMismatch,
+    );
+}
+
+#[test]
+fn test_sfc_unknown_step_in_transition() {
+    check_has_error(
+        r#"
+PROGRAM Main
+    INITIAL_STEP Idle:
+    END_STEP
+    TRANSITION FROM Idle TO Missing := TRUE;
+    END_TRANSITION
+END_PROGRAM
+"#,
+        DiagnosticCode::InvalidSfc,
+    );
+}
+
+#[test]
+fn test_sfc_requires_single_initial_step() {
+    check_has_error(
+        r#"
+FUNCTION_BLOCK Seq
+    STEP A:
+    END_STEP
+    STEP B:
+    END_STEP
+    TRANSITION FROM A TO B := TRUE;
+    END_TRANSITION
+END_FUNCTION_BLOCK
+"#,
+        DiagnosticCode::InvalidSfc,
+    );
+}
+
+#[test]
+fn test_sfc_unknown_action_and_qualifier() {
+    let errors = check_errors(
+        r#"
+PROGRAM Main
+    INITIAL_STEP Idle:
+        Missing(N);
+        Idle_Action(X);
+    END_STEP
+    ACTION Idle_Action:
+    END_ACTION
+END_PROGRAM
+"#,
+    );
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|code| **code == DiagnosticCode::InvalidSfc)
+            .count(),
+        2,
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn test_sfc_step_flags_are_read_only() {
+    check_has_error(
+        r#"
+PROGRAM Main
+    INITIAL_STEP Idle:
+    END_STEP
+    Idle.X := TRUE;
+END_PROGRAM
+"#,
+        DiagnosticCode::ConstantModification,
+    );
+}
@@ -453,6 +453,35 @@ END_PROGRAM
     assert!(!warnings.contains(&DiagnosticCode::SharedGlobalTaskHazard));
 }
 
+#[test]
+fn test_shared_global_instance_task_hazard_warning() {
+    let warnings = check_warnings(
+        r#"
+CONFIGURATION Conf
+VAR_GLOBAL
+    Delay : TON;
+END_VAR
+RESOURCE R ON CPU
+    TASK Fast (INTERVAL := T#10ms, PRIORITY := 1);
+    TASK Slow (INTERVAL := T#20ms, PRIORITY := 2);
+    PROGRAM P1 WITH Fast : Starter;
+    PROGRAM P2 WITH Slow : Watcher;
+END_RESOURCE
+END_CONFIGURATION
+
+PROGRAM Starter
+    Delay(IN := TRUE, PT := T#1s);
+END_PROGRAM
+
+PROGRAM Watcher
+    VAR done : BOOL; END_VAR
+    done := Delay.Q;
+END_PROGRAM
+"#,
+    );
+    assert!(warnings.contains(&DiagnosticCode::SharedGlobalTaskHazard));
+}
+
 #[test]
 fn test_used_function_no_unused_pou_warning() {
     let warnings = check_warnings(
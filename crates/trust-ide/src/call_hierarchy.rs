@@ -9,7 +9,7 @@ use rustc_hash::FxHashSet;
 use trust_hir::db::{FileId, SemanticDatabase, SourceDatabase};
 use trust_hir::symbols::{SymbolId, SymbolKind, SymbolTable};
 use trust_hir::Database;
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
 
 use crate::util::{
@@ -82,7 +82,7 @@ pub fn prepare_call_hierarchy_in_files(
     allowed_files: Option<&FxHashSet<FileId>>,
 ) -> Option<CallHierarchyItem> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = symbols_for_call_hierarchy(db, file_id, allowed_files);
 
@@ -259,7 +259,7 @@ fn collect_call_edges_in_files(
 
     for file_id in file_ids {
         let source = db.source_text(file_id);
-        let parsed = parse(&source);
+        let parsed = parse_with_dialect(&source, db.dialect());
         let root = parsed.syntax();
         let symbols = symbols_for_call_hierarchy(db, file_id, allowed_files);
 
@@ -157,16 +157,12 @@ fn detect_context(root: &SyntaxNode, position: TextSize) -> CompletionContext {
             // Colon triggers type annotation completion
             SyntaxKind::Colon => return CompletionContext::TypeAnnotation,
             // Comma in argument list
-            SyntaxKind::Comma => {
-                if is_in_argument_list(&prev) {
-                    return CompletionContext::Argument;
-                }
+            SyntaxKind::Comma if is_in_argument_list(&prev) => {
+                return CompletionContext::Argument;
             }
             // Opening paren might be function call
-            SyntaxKind::LParen => {
-                if is_in_call_expr(&prev) {
-                    return CompletionContext::Argument;
-                }
+            SyntaxKind::LParen if is_in_call_expr(&prev) => {
+                return CompletionContext::Argument;
             }
             _ => {}
         }
@@ -202,22 +198,19 @@ fn detect_context(root: &SyntaxNode, position: TextSize) -> CompletionContext {
             SyntaxKind::StmtList => return CompletionContext::Statement,
 
             // Inside a POU - we're in statement context
+            // Only if we're past the VAR blocks
             SyntaxKind::Program
             | SyntaxKind::Function
             | SyntaxKind::FunctionBlock
-            | SyntaxKind::Method => {
-                // Only if we're past the VAR blocks
-                if is_past_var_blocks(&ancestor, position) {
-                    return CompletionContext::Statement;
-                }
+            | SyntaxKind::Method
+                if is_past_var_blocks(&ancestor, position) =>
+            {
+                return CompletionContext::Statement;
             }
 
-            // At the source file level
-            SyntaxKind::SourceFile => {
-                // Check if we're inside a POU or at top level
-                if !is_inside_pou(&ancestor, position) {
-                    return CompletionContext::TopLevel;
-                }
+            // At the source file level, outside any POU
+            SyntaxKind::SourceFile if !is_inside_pou(&ancestor, position) => {
+                return CompletionContext::TopLevel;
             }
 
             _ => {}
@@ -6,7 +6,7 @@ use text_size::{TextRange, TextSize};
 
 use trust_hir::db::SemanticDatabase;
 use trust_hir::{Database, SourceDatabase, SymbolId};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 
 use crate::util::{
     field_declaration_ranges, field_type, resolve_target_at_position, ResolvedTarget,
@@ -32,7 +32,7 @@ pub fn goto_definition(
         ResolvedTarget::Symbol(symbol_id) => definition_of_symbol(db, file_id, symbol_id),
         ResolvedTarget::Field(field) => {
             let source = db.source_text(file_id);
-            let parsed = parse(&source);
+            let parsed = parse_with_dialect(&source, db.dialect());
             let root = parsed.syntax();
             let symbols = db.file_symbols_with_project(file_id);
             let range = field_declaration_ranges(&root, &symbols, &field)
@@ -10,7 +10,7 @@ use trust_hir::db::SemanticDatabase;
 use trust_hir::diagnostics::DiagnosticCode;
 use trust_hir::symbols::{ScopeId, SymbolModifiers, SymbolTable, VarQualifier, Visibility};
 use trust_hir::{Database, SourceDatabase, Symbol, SymbolKind, Type, TypeId};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
 use trust_syntax::{lex, TokenKind};
 
@@ -90,7 +90,7 @@ pub fn hover_with_filter(
             let symbol = symbols.get(symbol_id)?;
             let (symbol_source, symbol_root, symbol_range) = if let Some(origin) = symbol.origin {
                 let origin_source = db.source_text(origin.file_id);
-                let origin_parsed = parse(&origin_source);
+                let origin_parsed = parse_with_dialect(&origin_source, db.dialect());
                 let origin_symbols = db.file_symbols(origin.file_id);
                 let origin_range = origin_symbols
                     .get(origin.symbol_id)
@@ -7,7 +7,7 @@ use text_size::{TextRange, TextSize};
 
 use trust_hir::db::FileId;
 use trust_hir::{Database, SourceDatabase, Symbol, SymbolKind, Type};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
 
 use crate::util::{
@@ -55,7 +55,7 @@ fn find_interface_implementations(
 
     for candidate_file in db.file_ids() {
         let source = db.source_text(candidate_file);
-        let parsed = parse(&source);
+        let parsed = parse_with_dialect(&source, db.dialect());
         let root = parsed.syntax();
         let symbols = db.file_symbols_with_project(candidate_file);
 
@@ -8,7 +8,7 @@ use text_size::{TextRange, TextSize};
 use trust_hir::db::{FileId, SourceDatabase};
 use trust_hir::symbols::ParamDirection;
 use trust_hir::Database;
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
 
 use crate::signature_help::signature_for_call_expr;
@@ -35,7 +35,7 @@ pub struct InlayHint {
 /// Computes inlay hints within a source range.
 pub fn inlay_hints(db: &Database, file_id: FileId, range: TextRange) -> Vec<InlayHint> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
 
     let mut hints = Vec::new();
@@ -4,7 +4,7 @@ use text_size::TextRange;
 use trust_hir::db::{FileId, SemanticDatabase};
 use trust_hir::symbols::{Symbol, VarQualifier};
 use trust_hir::{Database, SourceDatabase, SymbolKind};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::SyntaxKind;
 
 use crate::util::{resolve_target_at_position_with_context, ResolvedTarget};
@@ -57,7 +57,7 @@ pub struct InlineValueData {
 /// Computes inline value hints and runtime targets within the given range.
 pub fn inline_value_data(db: &Database, file_id: FileId, range: TextRange) -> InlineValueData {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols_with_project(file_id);
 
@@ -145,7 +145,7 @@ fn inline_text_for_constant(db: &Database, file_id: FileId, symbol: &Symbol) ->
     };
 
     let decl_source = db.source_text(decl_file_id);
-    let decl_root = parse(&decl_source).syntax();
+    let decl_root = parse_with_dialect(&decl_source, db.dialect()).syntax();
     let var_decl = find_var_decl_for_range(&decl_root, decl_range)?;
     let initializer = initializer_from_var_decl(&decl_source, &var_decl)?;
     Some(format!(" = {initializer}"))
@@ -64,7 +64,9 @@ pub use refactor::{
 pub use references::{find_references, FindReferencesOptions, Reference};
 pub use rename::rename;
 pub use selection_range::{selection_ranges, SelectionRange};
-pub use semantic_tokens::{semantic_tokens, SemanticToken, SemanticTokenType};
+pub use semantic_tokens::{
+    inactive_region_tokens, semantic_tokens, SemanticToken, SemanticTokenType,
+};
 pub use signature_help::{
     call_signature_info, signature_help, CallSignatureInfo, CallSignatureParam, Signature,
     SignatureHelpResult, SignatureParameter,
@@ -9,7 +9,7 @@ use text_size::{TextRange, TextSize};
 use trust_hir::db::{FileId, SemanticDatabase};
 use trust_hir::symbols::{SymbolKind, SymbolTable};
 use trust_hir::{is_reserved_keyword, is_valid_identifier, Database, SourceDatabase, SymbolId};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
 
 use super::utilities;
@@ -143,7 +143,7 @@ pub fn generate_interface_stubs(
     position: TextSize,
 ) -> Option<RenameResult> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols_with_project(file_id);
 
@@ -232,7 +232,7 @@ pub fn inline_symbol(db: &Database, file_id: FileId, position: TextSize) -> Opti
         (file_id, symbol.range)
     };
     let decl_source = db.source_text(decl_file_id);
-    let decl_root = parse(&decl_source).syntax();
+    let decl_root = parse_with_dialect(&decl_source, db.dialect()).syntax();
     let var_decl = find_var_decl_for_range(&decl_root, decl_range)?;
     let expr = initializer_expr_in_var_decl(&var_decl)?;
     let expr_info = inline_expr_info(db, decl_file_id, &decl_source, &decl_root, &expr)?;
@@ -308,7 +308,7 @@ pub fn inline_symbol(db: &Database, file_id: FileId, position: TextSize) -> Opti
 /// Extracts selected statements into a new METHOD on the enclosing CLASS/FUNCTION_BLOCK.
 pub fn extract_method(db: &Database, file_id: FileId, range: TextRange) -> Option<ExtractResult> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let selection = trim_range_to_non_whitespace(&source, range)?;
 
@@ -379,7 +379,7 @@ pub fn extract_method(db: &Database, file_id: FileId, range: TextRange) -> Optio
 /// Extracts a selected expression into a GET-only PROPERTY on the enclosing CLASS.
 pub fn extract_property(db: &Database, file_id: FileId, range: TextRange) -> Option<ExtractResult> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let selection = trim_range_to_non_whitespace(&source, range)?;
     let expr_node = expression_node_for_selection(&root, selection)?;
@@ -454,7 +454,7 @@ pub fn extract_property(db: &Database, file_id: FileId, range: TextRange) -> Opt
 /// Extracts selected statements into a new FUNCTION POU.
 pub fn extract_pou(db: &Database, file_id: FileId, range: TextRange) -> Option<ExtractResult> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let selection = trim_range_to_non_whitespace(&source, range)?;
 
@@ -582,7 +582,7 @@ pub fn convert_function_to_function_block(
     position: TextSize,
 ) -> Option<RenameResult> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let node = find_enclosing_owner_node(&root, position, &[SyntaxKind::Function])?;
 
@@ -691,7 +691,7 @@ pub fn convert_function_block_to_function(
     position: TextSize,
 ) -> Option<RenameResult> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let node = find_enclosing_owner_node(&root, position, &[SyntaxKind::FunctionBlock])?;
     let symbols = db.file_symbols_with_project(file_id);
@@ -768,7 +768,7 @@ fn apply_move_in_file(
     result: &mut RenameResult,
 ) {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols_with_project(file_id);
 
@@ -974,7 +974,7 @@ fn collect_interface_stubs(
             .map(|origin| origin.file_id)
             .unwrap_or(fallback_file_id);
         let interface_source = db.source_text(interface_file_id);
-        let interface_root = parse(&interface_source).syntax();
+        let interface_root = parse_with_dialect(&interface_source, db.dialect()).syntax();
         if let Some(interface_node) =
             find_interface_node_for_symbol(&interface_root, interface_symbol.range)
         {
@@ -1989,7 +1989,7 @@ fn update_function_call_sites(
 
     for ref_file_id in db.file_ids() {
         let source = db.source_text(ref_file_id);
-        let root = parse(&source).syntax();
+        let root = parse_with_dialect(&source, db.dialect()).syntax();
         let symbols = db.file_symbols_with_project(ref_file_id);
 
         for call_expr in root
@@ -2113,7 +2113,7 @@ fn update_function_call_sites(
 fn function_block_has_type_references(db: &Database, owner_name: &str) -> bool {
     for file_id in db.file_ids() {
         let source = db.source_text(file_id);
-        let parsed = parse(&source);
+        let parsed = parse_with_dialect(&source, db.dialect());
         let root = parsed.syntax();
         let symbols = db.file_symbols_with_project(file_id);
         for name_node in root
@@ -2196,6 +2196,7 @@ fn is_statement_kind(kind: SyntaxKind) -> bool {
             | SyntaxKind::LabelStmt
             | SyntaxKind::ExprStmt
             | SyntaxKind::EmptyStmt
+            | SyntaxKind::RegionStmt
     )
 }
 
@@ -2395,7 +2396,7 @@ fn reference_has_disallowed_context(
     is_path_like: bool,
 ) -> bool {
     let source = db.source_text(file_id);
-    let root = parse(&source).syntax();
+    let root = parse_with_dialect(&source, db.dialect()).syntax();
     let Some(token) = root.token_at_offset(range.start()).right_biased() else {
         return false;
     };
@@ -2699,7 +2700,7 @@ END_PROGRAM
         let decl_file_id = origin.file_id;
         assert_eq!(decl_file_id, const_id);
         let decl_source = db.source_text(decl_file_id);
-        let decl_root = parse(&decl_source).syntax();
+        let decl_root = parse_with_dialect(&decl_source, db.dialect()).syntax();
         let decl_range = db
             .file_symbols(origin.file_id)
             .get(origin.symbol_id)
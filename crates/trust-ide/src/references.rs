@@ -7,7 +7,7 @@ use text_size::{TextRange, TextSize};
 use smol_str::SmolStr;
 use trust_hir::db::{FileId, SemanticDatabase};
 use trust_hir::{Database, SourceDatabase, SymbolId, TypeId};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
 
 use crate::util::{
@@ -124,7 +124,7 @@ fn find_references_to_symbol_in_file_by_identity(
     target_name: &SmolStr,
 ) -> Vec<Reference> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols_with_project(file_id);
 
@@ -212,7 +212,7 @@ fn find_type_references_in_file_by_identity(
     identity: SymbolIdentity,
 ) -> Vec<Reference> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols_with_project(file_id);
 
@@ -264,7 +264,7 @@ pub fn find_references_to_symbol(
     options: FindReferencesOptions,
 ) -> Vec<Reference> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols(file_id);
 
@@ -381,7 +381,7 @@ pub(crate) fn find_references_to_field(
     options: FindReferencesOptions,
 ) -> Vec<Reference> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols(file_id);
 
@@ -6,7 +6,7 @@ use text_size::{TextRange, TextSize};
 
 use trust_hir::db::{FileId, SourceDatabase};
 use trust_hir::Database;
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxNode, SyntaxToken};
 
 /// A selection range with an optional parent.
@@ -25,7 +25,7 @@ pub fn selection_ranges(
     positions: &[TextSize],
 ) -> Vec<SelectionRange> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
 
     positions
@@ -7,9 +7,9 @@ use text_size::{TextRange, TextSize};
 use trust_hir::db::{FileId, SemanticDatabase};
 use trust_hir::symbols::{SymbolKind, SymbolTable};
 use trust_hir::{Database, SourceDatabase};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
-use trust_syntax::{lex, TokenKind};
+use trust_syntax::{lex_with_dialect, TokenKind};
 
 use crate::util::{
     resolve_target_at_position_with_context, scope_at_position, ResolvedTarget, SymbolFilter,
@@ -266,8 +266,8 @@ fn is_type_position(root: &SyntaxNode, offset: TextSize) -> bool {
 /// Computes semantic tokens for a file.
 pub fn semantic_tokens(db: &Database, file_id: FileId) -> Vec<SemanticToken> {
     let source = db.source_text(file_id);
-    let tokens = lex(&source);
-    let parsed = parse(&source);
+    let tokens = lex_with_dialect(&source, db.dialect());
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols(file_id);
 
@@ -356,6 +356,11 @@ pub fn semantic_tokens(db: &Database, file_id: FileId) -> Vec<SemanticToken> {
             | TokenKind::KwNamespace
             | TokenKind::KwEndNamespace
             | TokenKind::KwUsing
+            | TokenKind::KwOrganizationBlock
+            | TokenKind::KwEndOrganizationBlock
+            | TokenKind::KwDataBlock
+            | TokenKind::KwEndDataBlock
+            | TokenKind::KwEndRegion
             | TokenKind::KwAction
             | TokenKind::KwEndAction
             | TokenKind::KwGet
@@ -472,10 +477,45 @@ pub fn semantic_tokens(db: &Database, file_id: FileId) -> Vec<SemanticToken> {
     result
 }
 
+/// Marks code excluded by conditional compilation as comments, one token per line.
+pub fn inactive_region_tokens(source: &str, inactive: &[TextRange]) -> Vec<SemanticToken> {
+    let mut result = Vec::new();
+    for range in inactive {
+        let mut line_start = usize::from(range.start());
+        for line in source[*range].split_inclusive('\n') {
+            let text = line.trim_end_matches(['\r', '\n']);
+            let indent = text.len() - text.trim_start().len();
+            let content = text.trim();
+            if !content.is_empty() {
+                let start = TextSize::from((line_start + indent) as u32);
+                result.push(SemanticToken::new(
+                    TextRange::at(start, TextSize::of(content)),
+                    SemanticTokenType::Comment,
+                ));
+            }
+            line_start += line.len();
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_inactive_region_tokens_split_lines() {
+        let source = "x := 1;\n  y := 2;\n\n  z := 3;\n";
+        let range = TextRange::new(8.into(), TextSize::of(source));
+        let tokens = inactive_region_tokens(source, &[range]);
+
+        let texts: Vec<_> = tokens.iter().map(|token| &source[token.range]).collect();
+        assert_eq!(texts, vec!["y := 2;", "z := 3;"]);
+        assert!(tokens
+            .iter()
+            .all(|token| token.token_type == SemanticTokenType::Comment));
+    }
+
     #[test]
     fn test_semantic_token_creation() {
         let range = TextRange::new(0.into(), 5.into());
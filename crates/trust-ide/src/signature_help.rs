@@ -9,7 +9,7 @@ use text_size::{TextRange, TextSize};
 use trust_hir::db::{FileId, SemanticDatabase};
 use trust_hir::symbols::{ParamDirection, Symbol, SymbolKind, SymbolTable};
 use trust_hir::{Database, SourceDatabase, TypeId};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
 
 use crate::util::{
@@ -143,7 +143,7 @@ pub(crate) fn call_signature_context(
     position: TextSize,
 ) -> Option<CallSignatureContext> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let token = find_token_at_position(&root, position)?;
     let call_expr = token
@@ -205,7 +205,7 @@ pub fn signature_help(
     position: TextSize,
 ) -> Option<SignatureHelpResult> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let token = find_token_at_position(&root, position)?;
     let call_expr = token
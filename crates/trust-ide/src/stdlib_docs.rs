@@ -83,6 +83,8 @@ const DOC_FB_BISTABLE: &str = "Standard bistable function block (IEC 61131-3 Ed.
 const DOC_FB_EDGE: &str = "Standard edge detection function block (IEC 61131-3 Ed.3, Table 44).";
 const DOC_FB_COUNTER: &str = "Standard counter function block (IEC 61131-3 Ed.3, Table 45).";
 const DOC_FB_TIMER: &str = "Standard timer function block (IEC 61131-3 Ed.3, Table 46).";
+const DOC_FB_PLANT: &str =
+    "Plant model block for process simulation (truST extension, not part of IEC 61131-3).";
 
 const NUMERIC_SINGLE: &[&str] = &[
     "ABS", "SQRT", "LN", "LOG", "EXP", "SIN", "COS", "TAN", "ASIN", "ACOS", "ATAN", "ATAN2",
@@ -331,6 +333,11 @@ const STANDARD_FB_NAMES: &[(&str, &str)] = &[
     ("TP_LTIME", DOC_FB_TIMER),
     ("TON_LTIME", DOC_FB_TIMER),
     ("TOF_LTIME", DOC_FB_TIMER),
+    ("PLANT_LAG", DOC_FB_PLANT),
+    ("PLANT_DEADTIME", DOC_FB_PLANT),
+    ("PLANT_INTEGRATOR", DOC_FB_PLANT),
+    ("PLANT_VALVE", DOC_FB_PLANT),
+    ("PLANT_TANK", DOC_FB_PLANT),
 ];
 
 static STANDARD_FB_SET: Lazy<FxHashSet<SmolStr>> = Lazy::new(|| {
@@ -417,6 +424,11 @@ pub fn standard_fb_names() -> &'static [&'static str] {
         "TP_LTIME",
         "TON_LTIME",
         "TOF_LTIME",
+        "PLANT_LAG",
+        "PLANT_DEADTIME",
+        "PLANT_INTEGRATOR",
+        "PLANT_VALVE",
+        "PLANT_TANK",
     ];
     NAMES
 }
@@ -7,7 +7,7 @@ use text_size::{TextRange, TextSize};
 use trust_hir::db::{FileId, SemanticDatabase, SourceDatabase};
 use trust_hir::symbols::{ScopeId, Symbol, SymbolId, SymbolKind, SymbolTable};
 use trust_hir::{Database, Type};
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
 
 use crate::util::{
@@ -44,7 +44,7 @@ pub fn prepare_type_hierarchy(
     position: TextSize,
 ) -> Option<TypeHierarchyItem> {
     let source = db.source_text(file_id);
-    let parsed = parse(&source);
+    let parsed = parse_with_dialect(&source, db.dialect());
     let root = parsed.syntax();
     let symbols = db.file_symbols_with_project(file_id);
 
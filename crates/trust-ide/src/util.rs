@@ -10,8 +10,8 @@ use rustc_hash::FxHashSet;
 use trust_hir::db::{FileId, SemanticDatabase};
 use trust_hir::symbols::{ScopeId, Symbol, SymbolKind, SymbolTable};
 use trust_hir::{Database, SourceDatabase, SymbolId, Type, TypeId};
-use trust_syntax::parser::parse;
-use trust_syntax::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use trust_syntax::parser::parse_with_dialect;
+use trust_syntax::syntax::{identifier_text, SyntaxKind, SyntaxNode, SyntaxToken};
 use trust_syntax::{lex, TokenKind};
 
 /// Finds the enclosing POU (Program Organization Unit) node for a given position.
@@ -173,7 +173,7 @@ pub(crate) struct IdeContext<'a> {
 impl<'a> IdeContext<'a> {
     pub(crate) fn new(db: &'a Database, file_id: FileId) -> Self {
         let source = db.source_text(file_id);
-        let parsed = parse(&source);
+        let parsed = parse_with_dialect(&source, db.dialect());
         let root = parsed.syntax();
         let symbols = db.file_symbols_with_project(file_id);
         Self {
@@ -548,14 +548,14 @@ pub(crate) fn name_from_name_ref(node: &SyntaxNode) -> Option<SmolStr> {
     node.descendants_with_tokens()
         .filter_map(|e| e.into_token())
         .find(|t| t.kind() == SyntaxKind::Ident)
-        .map(|t| SmolStr::new(t.text()))
+        .map(|t| SmolStr::new(identifier_text(t.text())))
 }
 
 pub(crate) fn name_from_name_node(node: &SyntaxNode) -> Option<SmolStr> {
     node.descendants_with_tokens()
         .filter_map(|e| e.into_token())
         .find(|t| t.kind() == SyntaxKind::Ident)
-        .map(|t| SmolStr::new(t.text()))
+        .map(|t| SmolStr::new(identifier_text(t.text())))
 }
 
 pub(crate) fn namespace_path_for_symbol(symbols: &SymbolTable, symbol: &Symbol) -> Vec<SmolStr> {
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 use tower_lsp::lsp_types::DiagnosticSeverity;
 use tracing::warn;
+use trust_syntax::preprocess::Defines;
 
 pub(crate) const CONFIG_FILES: &[&str] = &["trust-lsp.toml", ".trust-lsp.toml", "trustlsp.toml"];
 
@@ -175,6 +176,21 @@ impl ProjectConfig {
             .unwrap_or_else(|| PathBuf::from(".trust-lsp/index-cache"));
         Some(resolve_path(&self.root, dir.to_string_lossy().as_ref()))
     }
+
+    /// Returns the defines for conditional compilation (`[build]` plus the selected target).
+    pub fn active_defines(&self) -> Defines {
+        let target = self.build.target.as_deref().and_then(|name| {
+            self.targets
+                .iter()
+                .find(|target| target.name.eq_ignore_ascii_case(name))
+        });
+        Defines::new(
+            self.build
+                .defines
+                .iter()
+                .chain(target.into_iter().flat_map(|target| target.defines.iter())),
+        )
+    }
 }
 
 impl ProjectConfig {
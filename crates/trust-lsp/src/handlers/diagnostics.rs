@@ -18,7 +18,8 @@ use tower_lsp::Client;
 use trust_hir::db::FileId;
 use trust_hir::symbols::SymbolKind;
 use trust_hir::DiagnosticSeverity as HirSeverity;
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
+use trust_syntax::preprocess::mask_inactive;
 
 use crate::config::{DiagnosticSettings, ProjectConfig, CONFIG_FILES};
 use crate::external_diagnostics::collect_external_diagnostics;
@@ -221,11 +222,18 @@ pub(crate) fn collect_diagnostics_with_ticket(
         attach_explainers(state, uri, content, None, &mut diagnostics);
         return diagnostics;
     }
-    let parsed = parse(content);
+    let regions = state.conditional_regions(uri, content);
+    let dialect = state.dialect();
+    let parsed = if regions.inactive.is_empty() {
+        parse_with_dialect(content, dialect)
+    } else {
+        parse_with_dialect(&mask_inactive(content, &regions.inactive), dialect)
+    };
 
     let mut diagnostics: Vec<Diagnostic> = parsed
         .errors()
         .iter()
+        .chain(regions.errors.iter())
         .map(|err| {
             let range = Range {
                 start: offset_to_position(content, err.range.start().into()),
@@ -888,6 +896,10 @@ fn diagnostic_explainer(code: &str, message: &str) -> Option<DiagnosticExplainer
             iec_ref: "IEC 61131-3 Ed.3 §6.2; §6.8.2; Table 62",
             spec_path: "docs/specs/09-semantic-rules.md",
         }),
+        "E308" => Some(DiagnosticExplainer {
+            iec_ref: "IEC 61131-3 Ed.3 §6.7; Tables 54-60",
+            spec_path: "docs/specs/04-pou-declarations.md",
+        }),
         "E303" | "E304" => Some(DiagnosticExplainer {
             iec_ref: "IEC 61131-3 Ed.3 §6.2.6",
             spec_path: "docs/specs/02-data-types.md",
@@ -15,7 +15,7 @@ use text_size::{TextRange, TextSize};
 use trust_hir::db::{SemanticDatabase, SourceDatabase};
 use trust_hir::symbols::{ParamDirection, ScopeId, SymbolKind as HirSymbolKind, SymbolTable};
 use trust_hir::TypeId;
-use trust_syntax::parser::parse;
+use trust_syntax::parser::parse_with_dialect;
 use trust_syntax::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
 
 use crate::config::{find_config_file, WorkspaceVisibility, CONFIG_FILES};
@@ -814,7 +814,7 @@ fn code_action_with_ticket(
     if state.semantic_request_cancelled(request_ticket) {
         return None;
     }
-    let parsed = parse(&doc.content);
+    let parsed = parse_with_dialect(&doc.content, state.dialect());
     let root = parsed.syntax();
     let mut actions = Vec::new();
     let target_range = params.range;
@@ -1247,6 +1247,23 @@ pub fn prepare_rename(
     }))
 }
 
+/// Semantic tokens for a document, with conditionally excluded code shown as comments.
+fn document_semantic_tokens(
+    state: &ServerState,
+    doc: &crate::state::Document,
+) -> Vec<trust_ide::SemanticToken> {
+    let mut tokens = state.with_database(|db| trust_ide::semantic_tokens(db, doc.file_id));
+    let regions = state.conditional_regions(&doc.uri, &doc.content);
+    if !regions.inactive.is_empty() {
+        tokens.extend(trust_ide::inactive_region_tokens(
+            &doc.content,
+            &regions.inactive,
+        ));
+        tokens.sort_by_key(|token| token.range.start());
+    }
+    tokens
+}
+
 pub fn semantic_tokens_full(
     state: &ServerState,
     params: SemanticTokensParams,
@@ -1254,8 +1271,7 @@ pub fn semantic_tokens_full(
     let uri = &params.text_document.uri;
     let doc = state.get_document(uri)?;
 
-    // Get semantic tokens from trust_ide
-    let tokens = state.with_database(|db| trust_ide::semantic_tokens(db, doc.file_id));
+    let tokens = document_semantic_tokens(state, &doc);
 
     let data = semantic_tokens_to_lsp(&doc.content, tokens, 0, 0);
     let result_id = state.store_semantic_tokens(uri.clone(), data.clone());
@@ -1273,7 +1289,7 @@ pub fn semantic_tokens_full_delta(
     let uri = &params.text_document.uri;
     let doc = state.get_document(uri)?;
 
-    let tokens = state.with_database(|db| trust_ide::semantic_tokens(db, doc.file_id));
+    let tokens = document_semantic_tokens(state, &doc);
     let data = semantic_tokens_to_lsp(&doc.content, tokens, 0, 0);
 
     let previous = state.semantic_tokens_cache(uri);
@@ -1313,7 +1329,7 @@ pub fn semantic_tokens_range(
         }));
     }
 
-    let tokens = state.with_database(|db| trust_ide::semantic_tokens(db, doc.file_id));
+    let tokens = document_semantic_tokens(state, &doc);
     let filtered = tokens
         .into_iter()
         .filter(|token| {
@@ -1371,7 +1387,7 @@ fn semantic_tokens_delta_edits(
 pub fn folding_range(state: &ServerState, params: FoldingRangeParams) -> Option<Vec<FoldingRange>> {
     let uri = &params.text_document.uri;
     let doc = state.get_document(uri)?;
-    let parsed = parse(&doc.content);
+    let parsed = parse_with_dialect(&doc.content, state.dialect());
     let root = parsed.syntax();
 
     let mut ranges = Vec::new();
@@ -2859,6 +2875,9 @@ fn node_kind_for_end_keyword(keyword: &str) -> Option<SyntaxKind> {
         "END_ACTION" => Some(SyntaxKind::Action),
         "END_CONFIGURATION" => Some(SyntaxKind::Configuration),
         "END_RESOURCE" => Some(SyntaxKind::Resource),
+        "END_ORGANIZATION_BLOCK" => Some(SyntaxKind::Program),
+        "END_DATA_BLOCK" => Some(SyntaxKind::DataBlock),
+        "END_REGION" => Some(SyntaxKind::RegionStmt),
         _ => None,
     }
 }
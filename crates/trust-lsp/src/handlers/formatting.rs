@@ -6,7 +6,8 @@ use tower_lsp::lsp_types::{
 };
 
 use serde_json::Value;
-use trust_syntax::{lex, Token, TokenKind};
+use trust_syntax::parser::Dialect;
+use trust_syntax::{lex_with_dialect, Token, TokenKind};
 
 use crate::state::ServerState;
 
@@ -42,6 +43,7 @@ struct FormatConfig {
     max_line_length: Option<usize>,
     spacing_style: SpacingStyle,
     end_keyword_style: EndKeywordStyle,
+    dialect: Dialect,
 }
 
 fn format_config(
@@ -58,6 +60,7 @@ fn format_config(
         max_line_length: None,
         spacing_style: SpacingStyle::Spaced,
         end_keyword_style: EndKeywordStyle::Aligned,
+        dialect: state.dialect(),
     };
 
     if let Some(workspace_config) = state.workspace_config_for_uri(uri) {
@@ -228,7 +231,8 @@ pub fn range_formatting(
         return Some(Vec::new());
     }
 
-    let (start_line, end_line) = expand_range_to_block(&doc.content, start_line, end_line);
+    let (start_line, end_line) =
+        expand_range_to_block(&doc.content, config.dialect, start_line, end_line);
     let edit = format_lines_edit(&doc.content, &formatted, start_line, end_line)?;
     Some(vec![edit])
 }
@@ -257,7 +261,7 @@ pub fn on_type_formatting(
 }
 
 fn format_document(source: &str, config: &FormatConfig) -> String {
-    let tokens = lex(source);
+    let tokens = lex_with_dialect(source, config.dialect);
     let line_starts = line_starts(source);
     let line_count = line_starts.len();
     let mut line_tokens: Vec<Vec<Token>> = vec![Vec::new(); line_count];
@@ -526,6 +530,8 @@ enum BlockKind {
     Transition,
     Configuration,
     Resource,
+    OrganizationBlock,
+    DataBlock,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -535,8 +541,13 @@ struct BlockSpan {
     kind: BlockKind,
 }
 
-fn expand_range_to_block(source: &str, start_line: usize, end_line: usize) -> (usize, usize) {
-    let spans = block_spans(source);
+fn expand_range_to_block(
+    source: &str,
+    dialect: Dialect,
+    start_line: usize,
+    end_line: usize,
+) -> (usize, usize) {
+    let spans = block_spans(source, dialect);
     let mut best: Option<BlockSpan> = None;
     for span in spans {
         if span.start_line <= start_line && span.end_line >= end_line {
@@ -554,8 +565,8 @@ fn expand_range_to_block(source: &str, start_line: usize, end_line: usize) -> (u
     }
 }
 
-fn block_spans(source: &str) -> Vec<BlockSpan> {
-    let tokens = lex(source);
+fn block_spans(source: &str, dialect: Dialect) -> Vec<BlockSpan> {
+    let tokens = lex_with_dialect(source, dialect);
     let line_starts = line_starts(source);
     let mut spans = Vec::new();
     let mut stack: Vec<(BlockKind, usize)> = Vec::new();
@@ -620,6 +631,8 @@ fn block_start_kind(kind: TokenKind) -> Option<BlockKind> {
         TokenKind::KwTransition => Some(BlockKind::Transition),
         TokenKind::KwConfiguration => Some(BlockKind::Configuration),
         TokenKind::KwResource => Some(BlockKind::Resource),
+        TokenKind::KwOrganizationBlock => Some(BlockKind::OrganizationBlock),
+        TokenKind::KwDataBlock => Some(BlockKind::DataBlock),
         _ => None,
     }
 }
@@ -650,6 +663,8 @@ fn block_end_kind(kind: TokenKind) -> Option<BlockKind> {
         TokenKind::KwEndTransition => Some(BlockKind::Transition),
         TokenKind::KwEndConfiguration => Some(BlockKind::Configuration),
         TokenKind::KwEndResource => Some(BlockKind::Resource),
+        TokenKind::KwEndOrganizationBlock => Some(BlockKind::OrganizationBlock),
+        TokenKind::KwEndDataBlock => Some(BlockKind::DataBlock),
         _ => None,
     }
 }
@@ -664,6 +679,8 @@ fn is_dedent_token(kind: TokenKind) -> bool {
             | TokenKind::KwEndProperty
             | TokenKind::KwEndInterface
             | TokenKind::KwEndNamespace
+            | TokenKind::KwEndOrganizationBlock
+            | TokenKind::KwEndDataBlock
             | TokenKind::KwEndAction
             | TokenKind::KwEndVar
             | TokenKind::KwEndType
@@ -692,6 +709,8 @@ fn is_end_keyword(kind: TokenKind) -> bool {
             | TokenKind::KwEndProperty
             | TokenKind::KwEndInterface
             | TokenKind::KwEndNamespace
+            | TokenKind::KwEndOrganizationBlock
+            | TokenKind::KwEndDataBlock
             | TokenKind::KwEndAction
             | TokenKind::KwEndVar
             | TokenKind::KwEndType
@@ -719,6 +738,8 @@ fn line_has_indent_start(tokens: &[Token]) -> bool {
                 | TokenKind::KwProperty
                 | TokenKind::KwInterface
                 | TokenKind::KwNamespace
+                | TokenKind::KwOrganizationBlock
+                | TokenKind::KwDataBlock
                 | TokenKind::KwAction
                 | TokenKind::KwVar
                 | TokenKind::KwVarInput
@@ -1048,7 +1069,9 @@ fn wrap_long_lines(
 
 #[cfg(test)]
 mod tests {
-    use super::{format_document, EndKeywordStyle, FormatConfig, KeywordCase, SpacingStyle};
+    use super::{
+        format_document, Dialect, EndKeywordStyle, FormatConfig, KeywordCase, SpacingStyle,
+    };
 
     #[test]
     fn format_document_normalizes_spacing() {
@@ -1062,6 +1085,7 @@ mod tests {
             max_line_length: None,
             spacing_style: SpacingStyle::Spaced,
             end_keyword_style: EndKeywordStyle::Aligned,
+            dialect: Dialect::Iec,
         };
         let formatted = format_document(source, &config);
         assert!(formatted.contains("x := 1 + 2;"));
@@ -1082,6 +1106,7 @@ mod tests {
             max_line_length: None,
             spacing_style: SpacingStyle::Spaced,
             end_keyword_style: EndKeywordStyle::Aligned,
+            dialect: Dialect::Iec,
         };
         let formatted = format_document(source, &config);
         let mut lines = formatted.lines();
@@ -1108,6 +1133,7 @@ mod tests {
             max_line_length: None,
             spacing_style: SpacingStyle::Spaced,
             end_keyword_style: EndKeywordStyle::Aligned,
+            dialect: Dialect::Iec,
         };
         let formatted = format_document(source, &config);
         println!("{formatted}");
@@ -1134,6 +1160,7 @@ mod tests {
             max_line_length: None,
             spacing_style: SpacingStyle::Compact,
             end_keyword_style: EndKeywordStyle::Aligned,
+            dialect: Dialect::Iec,
         };
         let formatted = format_document(source, &config);
         assert!(formatted.contains("x:INT;"));
@@ -1152,6 +1179,7 @@ mod tests {
             max_line_length: None,
             spacing_style: SpacingStyle::Spaced,
             end_keyword_style: EndKeywordStyle::Indented,
+            dialect: Dialect::Iec,
         };
         let formatted = format_document(source, &config);
         let lines: Vec<&str> = formatted.lines().collect();
@@ -1177,6 +1205,7 @@ mod tests {
             max_line_length: None,
             spacing_style: SpacingStyle::Spaced,
             end_keyword_style: EndKeywordStyle::Aligned,
+            dialect: Dialect::Iec,
         };
         let formatted = format_document(source, &config);
         assert!(formatted.contains("    x:=1  {PRAGMA}  y:=2;"));
@@ -1194,6 +1223,7 @@ mod tests {
             max_line_length: Some(20),
             spacing_style: SpacingStyle::Spaced,
             end_keyword_style: EndKeywordStyle::Aligned,
+            dialect: Dialect::Iec,
         };
         let formatted = format_document(source, &config);
         assert!(formatted.contains("msg := 'a,b,c,d,e,f';"));
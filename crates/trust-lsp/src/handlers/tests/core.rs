@@ -2273,6 +2273,96 @@ fn lsp_mitsubishi_gxworks3_example_has_no_unexpected_diagnostics() {
     );
 }
 
+#[test]
+fn lsp_conditional_compilation_follows_selected_target() {
+    let source = r#"
+PROGRAM Main
+VAR x : INT; END_VAR
+{IF defined(SIM)}
+x := sim_only;
+{ELSE}
+x := 1;
+{END_IF}
+END_PROGRAM
+"#;
+    let config = |target: Option<&str>| ProjectConfig {
+        root: PathBuf::from("/workspace"),
+        config_path: None,
+        include_paths: Vec::new(),
+        vendor_profile: None,
+        stdlib: StdlibSettings::default(),
+        libraries: Vec::new(),
+        dependencies: Vec::new(),
+        dependency_resolution_issues: Vec::new(),
+        diagnostic_external_paths: Vec::new(),
+        build: BuildConfig {
+            target: target.map(str::to_string),
+            ..BuildConfig::default()
+        },
+        targets: vec![TargetProfile {
+            name: "sim".to_string(),
+            profile: None,
+            flags: Vec::new(),
+            defines: vec!["SIM=1".to_string()],
+        }],
+        indexing: IndexingConfig::default(),
+        diagnostics: DiagnosticSettings::default(),
+        runtime: RuntimeConfig::default(),
+        workspace: WorkspaceSettings::default(),
+        telemetry: TelemetryConfig::default(),
+    };
+
+    let state = ServerState::new();
+    let root_uri = tower_lsp::lsp_types::Url::parse("file:///workspace/").expect("workspace uri");
+    state.set_workspace_folders(vec![root_uri.clone()]);
+    state.set_workspace_config(root_uri.clone(), config(None));
+    let uri = tower_lsp::lsp_types::Url::parse("file:///workspace/main.st").expect("main uri");
+    state.open_document(uri.clone(), 1, source.to_string());
+
+    let errors = |state: &ServerState| {
+        let file_id = state.get_document(&uri).expect("document").file_id;
+        let ticket = state.begin_semantic_request();
+        super::diagnostics::collect_diagnostics_with_ticket_for_tests(
+            state, &uri, source, file_id, ticket,
+        )
+        .into_iter()
+        .filter(|diag| diag.severity == Some(tower_lsp::lsp_types::DiagnosticSeverity::ERROR))
+        .map(|diag| diag.message)
+        .collect::<Vec<_>>()
+    };
+    assert!(errors(&state).is_empty(), "{:?}", errors(&state));
+
+    let params = tower_lsp::lsp_types::SemanticTokensParams {
+        text_document: tower_lsp::lsp_types::TextDocumentIdentifier { uri: uri.clone() },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let Some(tower_lsp::lsp_types::SemanticTokensResult::Tokens(tokens)) =
+        semantic_tokens_full(&state, params)
+    else {
+        panic!("expected semantic tokens");
+    };
+    let mut line = 0;
+    let comment_lines: Vec<u32> = tokens
+        .data
+        .iter()
+        .filter_map(|token| {
+            line += token.delta_line;
+            (token.token_type == 9).then_some(line)
+        })
+        .collect();
+    assert_eq!(comment_lines, vec![4]);
+
+    state.set_workspace_config(root_uri, config(Some("sim")));
+    assert!(
+        errors(&state)
+            .iter()
+            .any(|message| message.contains("sim_only")),
+        "{:?}",
+        errors(&state)
+    );
+}
+
 fn spawn_control_stub() -> (String, thread::JoinHandle<()>) {
     let listener = TcpListener::bind("127.0.0.1:0").expect("bind control stub");
     let addr = listener.local_addr().expect("control stub addr");
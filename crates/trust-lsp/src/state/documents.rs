@@ -3,6 +3,7 @@ use tower_lsp::lsp_types::Url;
 
 use crate::config::ProjectConfig;
 use trust_hir::{db::FileId, SourceKey};
+use trust_syntax::preprocess::{conditional_regions, mask_inactive, ConditionalRegions};
 
 use super::path::{canonicalize_path, path_to_uri, source_key_for_uri, uri_to_path};
 use super::{Document, ServerState};
@@ -14,9 +15,10 @@ pub(super) fn open_document(
     content: String,
 ) -> FileId {
     let key = source_key_for_uri(&uri);
+    let text = analysis_text(state, &uri, &content);
     let file_id = {
         let mut project = state.project.write();
-        project.set_source_text(key, content.clone())
+        project.set_source_text(key, text)
     };
 
     let access = next_document_access(state);
@@ -69,9 +71,10 @@ fn index_document_impl(
     }
 
     let key = source_key_for_uri(&uri);
+    let text = analysis_text(state, &uri, &content);
     let file_id = {
         let mut project = state.project.write();
-        project.set_source_text(key.clone(), content.clone())
+        project.set_source_text(key.clone(), text)
     };
 
     let access = next_document_access(state);
@@ -100,9 +103,10 @@ fn index_document_impl(
 
 pub(super) fn update_document(state: &ServerState, uri: &Url, version: i32, content: String) {
     let key = source_key_for_uri(uri);
+    let text = analysis_text(state, uri, &content);
     let file_id = {
         let mut project = state.project.write();
-        project.set_source_text(key, content.clone())
+        project.set_source_text(key, text)
     };
 
     let access = next_document_access(state);
@@ -141,10 +145,11 @@ pub(super) fn rename_document(state: &ServerState, old_uri: &Url, new_uri: &Url)
 
     let old_key = source_key_for_uri(old_uri);
     let new_key = source_key_for_uri(new_uri);
+    let text = analysis_text(state, new_uri, &doc.content);
     let mut project = state.project.write();
     project.remove_source(&old_key);
     project.remove_source(&new_key);
-    let file_id = project.set_source_text(new_key, doc.content.clone());
+    let file_id = project.set_source_text(new_key, text);
 
     doc.uri = new_uri.clone();
     doc.file_id = file_id;
@@ -152,6 +157,54 @@ pub(super) fn rename_document(state: &ServerState, old_uri: &Url, new_uri: &Url)
     Some(file_id)
 }
 
+/// Evaluates conditional compilation pragmas with the defines of the owning workspace.
+pub(super) fn document_conditional_regions(
+    state: &ServerState,
+    uri: &Url,
+    content: &str,
+) -> ConditionalRegions {
+    if !content.contains('{') {
+        return ConditionalRegions::default();
+    }
+    let defines = state
+        .workspace_config_for_uri(uri)
+        .map(|config| config.active_defines())
+        .unwrap_or_default();
+    conditional_regions(content, &defines)
+}
+
+/// Returns the text handed to analysis, with inactive conditional code blanked out.
+fn analysis_text(state: &ServerState, uri: &Url, content: &str) -> String {
+    let regions = document_conditional_regions(state, uri, content);
+    if regions.inactive.is_empty() {
+        content.to_string()
+    } else {
+        mask_inactive(content, &regions.inactive)
+    }
+}
+
+/// Re-evaluates conditional compilation for documents owned by `root`.
+pub(super) fn refresh_conditional_sources(state: &ServerState, root: &Url) {
+    let docs: Vec<(Url, String)> = state
+        .documents
+        .read()
+        .values()
+        .map(|doc| (doc.uri.clone(), doc.content.clone()))
+        .collect();
+    for (uri, content) in docs {
+        let owned = super::path::workspace_config_match_for_uri(state, &uri)
+            .is_some_and(|(owner, _)| &owner == root);
+        if !owned {
+            continue;
+        }
+        let text = analysis_text(state, &uri, &content);
+        state
+            .project
+            .write()
+            .set_source_text(source_key_for_uri(&uri), text);
+    }
+}
+
 pub(super) fn get_document(state: &ServerState, uri: &Url) -> Option<Document> {
     state.documents.read().get(uri).cloned()
 }
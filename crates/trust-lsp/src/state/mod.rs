@@ -17,7 +17,10 @@ use tower_lsp::lsp_types::{SemanticToken, Url};
 use crate::config::ProjectConfig;
 use crate::library_docs::library_doc_map;
 use crate::telemetry::{TelemetryCollector, TelemetryEvent};
-use trust_hir::{db::FileId, Database, Project};
+use trust_hir::db::{FileId, SourceDatabase};
+use trust_hir::{Database, Project};
+use trust_syntax::parser::Dialect;
+use trust_syntax::preprocess::ConditionalRegions;
 
 const BACKGROUND_REQUEST_LIMIT: usize = 1;
 
@@ -233,7 +236,14 @@ impl ServerState {
     /// Stores configuration for a workspace root.
     pub fn set_workspace_config(&self, root: Url, config: ProjectConfig) {
         self.workspace_configs.write().insert(root.clone(), config);
+        let dialect = Dialect::from_vendor_profile(
+            self.primary_workspace_config()
+                .and_then(|config| config.vendor_profile)
+                .as_deref(),
+        );
+        self.project.write().database_mut().set_dialect(dialect);
         self.library_docs.write().remove(&root);
+        documents::refresh_conditional_sources(self, &root);
     }
 
     /// Returns all workspace configurations with their roots.
@@ -357,6 +367,11 @@ impl ServerState {
         documents::uri_for_file_id(self, file_id)
     }
 
+    /// Evaluates conditional compilation pragmas for a document's content.
+    pub fn conditional_regions(&self, uri: &Url, content: &str) -> ConditionalRegions {
+        documents::document_conditional_regions(self, uri, content)
+    }
+
     /// Finds a document by file ID.
     pub fn document_for_file_id(&self, file_id: FileId) -> Option<Document> {
         documents::document_for_file_id(self, file_id)
@@ -403,6 +418,12 @@ impl ServerState {
         documents::apply_memory_budget(self);
     }
 
+    /// Vendor dialect used to parse documents (from the primary workspace's
+    /// `vendor_profile`).
+    pub fn dialect(&self) -> Dialect {
+        self.with_database(|db| db.dialect())
+    }
+
     /// Executes a function with a read lock on the database.
     pub fn with_database<F, R>(&self, f: F) -> R
     where
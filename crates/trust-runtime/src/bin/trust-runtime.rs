@@ -200,11 +200,20 @@ fn run() -> anyhow::Result<()> {
             message,
             dry_run,
         }) => commit::run_commit(project, message, dry_run),
+        Some(Command::Deploy {
+            project,
+            restart,
+            target: Some(target),
+            sign_key,
+            token,
+            ..
+        }) => deploy::run_remote_deploy(project, target, sign_key, token, restart),
         Some(Command::Deploy {
             project,
             root,
             label,
             restart,
+            ..
         }) => {
             let result = deploy::run_deploy(project, root, label)?;
             if let Some(mode) = restart {
@@ -217,6 +226,7 @@ fn run() -> anyhow::Result<()> {
             }
             Ok(())
         }
+        Some(Command::DeployKeygen { out, id, force }) => deploy::run_deploy_keygen(out, id, force),
         Some(Command::Rollback { root }) => deploy::run_rollback(root),
         Some(Command::Completions { shell }) => completions::run_completions(shell),
         Some(Command::Conformance {
@@ -247,6 +257,7 @@ fn suggest_subcommand(input: &str) -> Option<&'static str> {
         "plcopen",
         "registry",
         "deploy",
+        "deploy-keygen",
         "rollback",
         "commit",
         "completions",
@@ -245,6 +245,27 @@ pub enum Command {
         /// Restart mode after deployment (optional).
         #[arg(long)]
         restart: Option<String>,
+        /// Push to a running runtime web endpoint (e.g. http://plc:8080) instead of a local root.
+        #[arg(long, conflicts_with_all = ["root", "label"])]
+        target: Option<String>,
+        /// Sign the pushed payload as `KEY_ID=PRIVATE_KEY_PATH` (repeat for multi-signer policies).
+        #[arg(long = "sign-key", value_name = "KEY_ID=PATH", action = ArgAction::Append, requires = "target")]
+        sign_key: Vec<String>,
+        /// Web access token for the target runtime (X-Trust-Token).
+        #[arg(long, requires = "target")]
+        token: Option<String>,
+    },
+    /// Generate an Ed25519 deploy signing key and print its keyring entry.
+    DeployKeygen {
+        /// Private key output path (PKCS#8 PEM).
+        #[arg(long)]
+        out: PathBuf,
+        /// Key id to record in the runtime keyring.
+        #[arg(long)]
+        id: String,
+        /// Replace an existing private key at `--out`.
+        #[arg(long)]
+        force: bool,
     },
     /// Roll back to the previous project version in a deployment root.
     Rollback {
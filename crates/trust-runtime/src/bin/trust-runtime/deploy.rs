@@ -2,13 +2,22 @@
 
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use indicatif::{ProgressBar, ProgressStyle};
+use trust_runtime::bundle_builder::{collect_source_paths, project_compile_session};
 use trust_runtime::config::{IoConfig, RuntimeBundle, RuntimeConfig};
+use trust_runtime::harness::SourceFile;
 use trust_runtime::io::{IoAddress, IoDriverRegistry};
-use trust_runtime::watchdog::WatchdogPolicy;
+use trust_runtime::retain::{FileRetainStore, RetainStore};
+use trust_runtime::watchdog::{RetainMode, WatchdogPolicy};
+use trust_runtime::web::deploy::{
+    generate_deploy_key, sign_deploy_request, DeployRequest, DeploySource,
+};
 
 use crate::style;
 
@@ -81,6 +90,123 @@ pub fn run_deploy(
     })
 }
 
+/// Push a project to a running runtime over `/api/deploy`, signed with each
+/// `KEY_ID=PATH` Ed25519 private key.
+pub fn run_remote_deploy(
+    bundle: PathBuf,
+    target: String,
+    sign_keys: Vec<String>,
+    token: Option<String>,
+    restart: Option<String>,
+) -> anyhow::Result<()> {
+    let source_bundle = RuntimeBundle::load(&bundle)?;
+    validate_bundle(&source_bundle)?;
+    let root = &source_bundle.root;
+    let io_path = root.join("io.toml");
+    let sources = collect_sources(root)?
+        .into_iter()
+        .map(|(path, bytes)| DeploySource {
+            path: path.replace('\\', "/"),
+            content: String::from_utf8_lossy(&bytes).into_owned(),
+        })
+        .collect();
+    let mut request = DeployRequest {
+        runtime_toml: Some(fs::read_to_string(root.join("runtime.toml"))?),
+        io_toml: if io_path.is_file() {
+            Some(fs::read_to_string(&io_path)?)
+        } else {
+            None
+        },
+        program_stbc_b64: Some(STANDARD.encode(fs::read(root.join("program.stbc"))?)),
+        sources: Some(sources),
+        signature: None,
+        signatures: None,
+        restart,
+    };
+    let mut signatures = Vec::new();
+    for entry in &sign_keys {
+        let (key_id, path) = entry
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("--sign-key expects KEY_ID=PATH, got '{entry}'"))?;
+        let pem = fs::read_to_string(path)
+            .map_err(|err| anyhow::anyhow!("read signing key {path}: {err}"))?;
+        signatures.push(sign_deploy_request(&request, key_id.trim(), &pem)?);
+    }
+    let mut signatures = signatures.into_iter();
+    request.signature = signatures.next();
+    let rest = signatures.collect::<Vec<_>>();
+    if !rest.is_empty() {
+        request.signatures = Some(rest);
+    }
+
+    let url = format!("{}/api/deploy", target.trim_end_matches('/'));
+    let mut call = ureq::post(&url).set("Content-Type", "application/json");
+    if let Some(token) = token.as_deref() {
+        call = call.set("X-Trust-Token", token);
+    }
+    let body = match call.send_string(&serde_json::to_string(&request)?) {
+        Ok(response) => response.into_string()?,
+        Err(ureq::Error::Status(code, response)) => {
+            let text = response.into_string().unwrap_or_default();
+            anyhow::bail!("deploy to {target} failed ({code}): {text}");
+        }
+        Err(err) => anyhow::bail!("deploy to {target} failed: {err}"),
+    };
+    let response: serde_json::Value = serde_json::from_str(&body)?;
+    if response.get("ok").and_then(serde_json::Value::as_bool) != Some(true) {
+        let error = response
+            .get("error")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("unknown error");
+        anyhow::bail!("deploy to {target} rejected: {error}");
+    }
+    println!(
+        "{}",
+        style::success(format!(
+            "Deployed project {} -> {target} ({} signature(s))",
+            bundle.display(),
+            sign_keys.len()
+        ))
+    );
+    Ok(())
+}
+
+pub fn run_deploy_keygen(out: PathBuf, id: String, force: bool) -> anyhow::Result<()> {
+    if out.exists() {
+        if !force {
+            anyhow::bail!(
+                "refusing to overwrite {} (pass --force to replace it)",
+                out.display()
+            );
+        }
+        fs::remove_file(&out)?;
+    }
+    let (private_pem, public_hex) = generate_deploy_key()?;
+    if let Some(parent) = out.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    // Create the file owner-only so the key is never readable by others, and
+    // fail instead of following a file that appeared in the meantime.
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&out)
+        .map_err(|err| anyhow::anyhow!("create {}: {err}", out.display()))?;
+    file.write_all(private_pem.as_bytes())?;
+    println!(
+        "{}",
+        style::success(format!("Wrote private key {}", out.display()))
+    );
+    println!("Add to the runtime keyring (runtime.deploy.keyring_path):\n");
+    println!("[[keys]]\nid = \"{id}\"\npublic_key = \"{public_hex}\"");
+    Ok(())
+}
+
 pub fn run_rollback(root: Option<PathBuf>) -> anyhow::Result<()> {
     let root = root.unwrap_or(std::env::current_dir()?);
     let current_link = root.join("current");
@@ -115,8 +241,17 @@ fn validate_bundle(bundle: &RuntimeBundle) -> anyhow::Result<()> {
             .validate(driver.name.as_str(), &driver.params)
             .map_err(anyhow::Error::from)?;
     }
-    let mut runtime = trust_runtime::Runtime::new();
-    runtime.apply_bytecode_bytes(&bundle.bytecode, Some(&bundle.runtime.resource_name))?;
+    // Without sources the target runs from program.stbc alone, so the module
+    // must carry everything needed to build the runtime.
+    if bundle.root.join("sources").is_dir() {
+        let mut runtime = trust_runtime::Runtime::new();
+        runtime.apply_bytecode_bytes(&bundle.bytecode, Some(&bundle.runtime.resource_name))?;
+    } else {
+        trust_runtime::Runtime::from_bytecode_bytes(
+            &bundle.bytecode,
+            Some(&bundle.runtime.resource_name),
+        )?;
+    }
     Ok(())
 }
 
@@ -131,9 +266,11 @@ fn copy_bundle(source: &Path, dest: &Path) -> anyhow::Result<()> {
     }
     copy_file(source.join("program.stbc"), dest.join("program.stbc"))?;
 
-    let sources = source.join("sources");
-    if sources.is_dir() {
-        copy_dir(&sources, &dest.join("sources"))?;
+    for dir in ["sources", "plant"] {
+        let path = source.join(dir);
+        if path.is_dir() {
+            copy_dir(&path, &dest.join(dir))?;
+        }
     }
     Ok(())
 }
@@ -238,6 +375,7 @@ struct BundleChangeSummary {
     io_changes: Vec<String>,
     bytecode_changed: bool,
     source_diff: SourceDiff,
+    retain_changes: Vec<String>,
 }
 
 impl BundleChangeSummary {
@@ -248,12 +386,14 @@ impl BundleChangeSummary {
             .map(|b| b.bytecode != next.bytecode)
             .unwrap_or(true);
         let source_diff = diff_sources(previous.map(|b| b.root.as_path()), next.root.as_path());
+        let retain_changes = diff_retained_values(previous, next);
         Self {
             previous_path: previous.map(|b| b.root.clone()),
             runtime_changes,
             io_changes,
             bytecode_changed,
             source_diff,
+            retain_changes,
         }
     }
 
@@ -298,6 +438,12 @@ impl BundleChangeSummary {
                 println!("sources modified: {}", self.source_diff.modified.join(", "));
             }
         }
+        if !self.retain_changes.is_empty() {
+            println!("retain migration:");
+            for change in &self.retain_changes {
+                println!("  - {change}");
+            }
+        }
     }
 
     fn render(&self) -> String {
@@ -354,6 +500,12 @@ impl BundleChangeSummary {
                 ));
             }
         }
+        if !self.retain_changes.is_empty() {
+            lines.push("retain migration:".to_string());
+            for change in &self.retain_changes {
+                lines.push(format!("  - {change}"));
+            }
+        }
         lines.join("\n")
     }
 }
@@ -474,6 +626,73 @@ fn diff_io(previous: Option<&IoConfig>, next: &IoConfig) -> Vec<String> {
     changes
 }
 
+/// Describe how the retained values of the running version map onto `next`.
+fn diff_retained_values(previous: Option<&RuntimeBundle>, next: &RuntimeBundle) -> Vec<String> {
+    let Some(path) = previous.and_then(retain_file) else {
+        return Vec::new();
+    };
+    if !path.is_file() {
+        return Vec::new();
+    }
+    let loaded = match FileRetainStore::new(&path).recover() {
+        Ok(loaded) => loaded,
+        Err(err) => return vec![format!("cannot read {}: {err}", path.display())],
+    };
+    let mut changes = Vec::new();
+    if let Some(fault) = loaded.fault {
+        changes.push(format!("warning: {}", fault.reason));
+    }
+    let runtime = match build_source_runtime(next) {
+        Ok(Some(runtime)) => runtime,
+        Ok(None) => {
+            changes.push("not checked (project has no sources)".to_string());
+            return changes;
+        }
+        Err(err) => {
+            changes.push(format!("not checked: {err}"));
+            return changes;
+        }
+    };
+    let (_, report) = runtime.plan_retain_migration(&loaded.snapshot);
+    changes.push(format!("restored unchanged: {}", report.restored.len()));
+    for entry in &report.converted {
+        changes.push(format!(
+            "converted {} -> {} ({})",
+            entry.source, entry.target, entry.detail
+        ));
+    }
+    for entry in &report.dropped {
+        changes.push(format!("dropped {} ({})", entry.source, entry.reason));
+    }
+    changes
+}
+
+fn retain_file(bundle: &RuntimeBundle) -> Option<PathBuf> {
+    if bundle.runtime.retain_mode != RetainMode::File {
+        return None;
+    }
+    let path = bundle.runtime.retain_path.as_ref()?;
+    Some(if path.is_relative() {
+        bundle.root.join(path)
+    } else {
+        path.clone()
+    })
+}
+
+fn build_source_runtime(bundle: &RuntimeBundle) -> anyhow::Result<Option<trust_runtime::Runtime>> {
+    let sources = collect_sources(&bundle.root)?;
+    if sources.is_empty() {
+        return Ok(None);
+    }
+    let files = sources
+        .iter()
+        .map(|(path, bytes)| SourceFile::with_path(path, String::from_utf8_lossy(bytes)))
+        .collect();
+    Ok(Some(
+        project_compile_session(&bundle.root, files)?.build_runtime()?,
+    ))
+}
+
 fn diff_sources(previous_root: Option<&Path>, next_root: &Path) -> SourceDiff {
     let prev = previous_root.and_then(|root| collect_sources(root).ok());
     let next = collect_sources(next_root).unwrap_or_default();
@@ -489,10 +708,8 @@ fn diff_sources(previous_root: Option<&Path>, next_root: &Path) -> SourceDiff {
         match (prev.get(&key), next.get(&key)) {
             (None, Some(_)) => added.push(key),
             (Some(_), None) => removed.push(key),
-            (Some(prev_bytes), Some(next_bytes)) => {
-                if prev_bytes != next_bytes {
-                    modified.push(key);
-                }
+            (Some(prev_bytes), Some(next_bytes)) if prev_bytes != next_bytes => {
+                modified.push(key);
             }
             _ => {}
         }
@@ -548,28 +765,18 @@ fn format_address(address: &IoAddress) -> String {
 
 fn collect_sources(root: &Path) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
     let sources_root = root.join("sources");
-    if !sources_root.is_dir() {
-        return Ok(BTreeMap::new());
-    }
     let mut map = BTreeMap::new();
-    let patterns = ["**/*.st", "**/*.ST", "**/*.pou", "**/*.POU"];
-    for pattern in patterns {
-        for entry in glob::glob(&format!("{}/{}", sources_root.display(), pattern))? {
-            let path = entry?;
-            if !path.is_file() {
-                continue;
-            }
-            let relative = path
-                .strip_prefix(&sources_root)
-                .unwrap_or(&path)
-                .to_string_lossy()
-                .to_string();
-            if map.contains_key(&relative) {
-                continue;
-            }
-            let bytes = fs::read(&path)?;
-            map.insert(relative, bytes);
+    for path in collect_source_paths(&sources_root)? {
+        let relative = path
+            .strip_prefix(&sources_root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string();
+        if map.contains_key(&relative) {
+            continue;
         }
+        let bytes = fs::read(&path)?;
+        map.insert(relative, bytes);
     }
     Ok(map)
 }
@@ -8,18 +8,20 @@ use std::sync::{Arc, Mutex};
 use serde_json::json;
 use smol_str::SmolStr;
 use trust_runtime::bundle::detect_bundle_path;
+use trust_runtime::bundle_builder::project_compile_session;
 use trust_runtime::bytecode::BytecodeModule;
 use trust_runtime::config::RuntimeBundle;
 use trust_runtime::control::{
     ControlEndpoint, ControlServer, ControlState, SourceFile, SourceRegistry,
 };
 use trust_runtime::discovery::{start_discovery, DiscoveryState};
-use trust_runtime::harness::CompileSession;
 use trust_runtime::historian::HistorianService;
-use trust_runtime::io::IoDriverRegistry;
+use trust_runtime::io::{start_sparkplug, IoDriverRegistry};
 use trust_runtime::mesh::start_mesh;
 use trust_runtime::metrics::RuntimeMetrics;
-use trust_runtime::opcua::{start_wire_server, OpcUaWireServer};
+use trust_runtime::opcua::{
+    start_wire_server, OpcUaAlarmSource, OpcUaMethodCaller, OpcUaWireServer,
+};
 use trust_runtime::retain::FileRetainStore;
 use trust_runtime::scheduler::{ResourceCommand, ResourceRunner, StartGate, StdClock};
 use trust_runtime::security::load_tls_materials;
@@ -206,22 +208,17 @@ pub fn run_runtime(
         let sources_path = bundle.root.join("sources");
         if sources_path.is_dir() {
             let sources = load_sources(&sources_path)?;
-            let session = CompileSession::from_sources(
-                sources
-                    .files()
-                    .iter()
-                    .map(|file| {
-                        trust_runtime::harness::SourceFile::with_path(
-                            file.path.to_string_lossy().as_ref(),
-                            file.text.clone(),
-                        )
-                    })
-                    .collect(),
-            );
+            let session = project_compile_session(&bundle.root, compile_sources(&sources))?;
             let runtime = session.build_runtime()?;
             (Some(bundle), runtime, sources)
         } else {
-            let runtime = Runtime::new();
+            let runtime =
+                Runtime::from_bytecode_bytes(&bundle.bytecode, Some(&bundle.runtime.resource_name))
+                    .map_err(|err| {
+                        anyhow::anyhow!(
+                            "failed to load program.stbc: {err} (rebuild with trust-runtime build)"
+                        )
+                    })?;
             let sources = SourceRegistry::default();
             (Some(bundle), runtime, sources)
         }
@@ -234,18 +231,7 @@ pub fn run_runtime(
                 .unwrap_or_else(|| PathBuf::from("."))
         });
         let sources = load_sources(&runtime_root)?;
-        let session = CompileSession::from_sources(
-            sources
-                .files()
-                .iter()
-                .map(|file| {
-                    trust_runtime::harness::SourceFile::with_path(
-                        file.path.to_string_lossy().as_ref(),
-                        file.text.clone(),
-                    )
-                })
-                .collect(),
-        );
+        let session = project_compile_session(&runtime_root, compile_sources(&sources))?;
         let runtime = session.build_runtime()?;
         (None, runtime, sources)
     };
@@ -271,7 +257,8 @@ pub fn run_runtime(
     let simulation_warning =
         simulation_warning_message(simulation_enabled, simulation_time_scale).unwrap_or_default();
     let simulation_controller = simulation_enabled
-        .then(|| trust_runtime::simulation::SimulationController::new(simulation_config));
+        .then(|| trust_runtime::simulation::SimulationController::from_config(simulation_config))
+        .transpose()?;
 
     let debug = runtime.enable_debug();
     let metrics = Arc::new(Mutex::new(RuntimeMetrics::new()));
@@ -326,13 +313,18 @@ pub fn run_runtime(
                 runtime.set_retain_store(None, None);
             }
         }
-        if let Err(err) =
-            runtime.apply_bytecode_bytes(&bundle.bytecode, Some(&bundle.runtime.resource_name))
-        {
-            anyhow::bail!(
-                "failed to apply bytecode metadata: {err} (project folder may require sources)"
-            );
+        // A bundle without sources was already built from program.stbc.
+        if !sources.files().is_empty() {
+            if let Err(err) =
+                runtime.apply_bytecode_bytes(&bundle.bytecode, Some(&bundle.runtime.resource_name))
+            {
+                anyhow::bail!("failed to apply bytecode metadata: {err}");
+            }
+            let module = BytecodeModule::decode(&bundle.bytecode)?;
+            load_program_bodies(&mut runtime, Some(&module), &sources)?;
         }
+    } else {
+        load_program_bodies(&mut runtime, None, &sources)?;
     }
 
     runtime.restart(restart_mode)?;
@@ -342,6 +334,39 @@ pub fn run_runtime(
         Some(bundle) => LogLevel::parse(bundle.runtime.log_level.as_str()),
         None => LogLevel::Info,
     });
+    if let Some(fault) = runtime.retain_fault() {
+        logger.log(
+            LogLevel::Warn,
+            "retain_recovery",
+            json!({
+                "event_id": "TRUST-RT-RETAIN-001",
+                "reason": fault.reason.as_str(),
+            }),
+        );
+    }
+    if let Some(report) = runtime
+        .retain_migration()
+        .filter(|report| !report.is_clean())
+    {
+        logger.log(
+            LogLevel::Warn,
+            "retain_migration",
+            json!({
+                "event_id": "TRUST-RT-RETAIN-002",
+                "restored": report.restored.len(),
+                "converted": report
+                    .converted
+                    .iter()
+                    .map(|entry| format!("{} -> {} ({})", entry.source, entry.target, entry.detail))
+                    .collect::<Vec<_>>(),
+                "dropped": report
+                    .dropped
+                    .iter()
+                    .map(|entry| format!("{} ({})", entry.source, entry.reason))
+                    .collect::<Vec<_>>(),
+            }),
+        );
+    }
 
     let metadata = Arc::new(Mutex::new(runtime.metadata_snapshot()));
     let events = Arc::new(Mutex::new(VecDeque::new()));
@@ -390,10 +415,14 @@ pub fn run_runtime(
         .as_ref()
         .map(|bundle| bundle.runtime.cycle_interval)
         .unwrap_or_else(|| Duration::from_millis(10));
+    let task_threads = bundle
+        .as_ref()
+        .is_some_and(|bundle| bundle.runtime.task_threads);
     let mut runner = ResourceRunner::new(runtime, StdClock::new(), cycle_interval)
         .with_restart_signal(pending_restart.clone())
         .with_start_gate(start_gate.clone())
-        .with_time_scale(simulation_time_scale);
+        .with_time_scale(simulation_time_scale)
+        .with_task_threads(task_threads);
     if let Some(simulation) = simulation_controller {
         runner = runner.with_simulation(simulation);
     }
@@ -586,11 +615,29 @@ pub fn run_runtime(
             }
             snapshot_debug.snapshot()
         });
+        let method_control = control.clone();
+        let method_caller: OpcUaMethodCaller = Arc::new(move |instance, method, inputs| {
+            let (tx, rx) = std::sync::mpsc::channel();
+            method_control.send_command(ResourceCommand::CallMethod {
+                instance: instance.into(),
+                method: method.into(),
+                inputs,
+                respond_to: tx,
+            })?;
+            rx.recv_timeout(std::time::Duration::from_secs(2))
+                .map_err(|_| {
+                    trust_runtime::error::RuntimeError::ControlError("method call timed out".into())
+                })?
+        });
+        let opcua_metadata = metadata.lock().ok().map(|guard| guard.clone());
         opcua_server = start_wire_server(
             bundle.runtime.resource_name.as_str(),
             &bundle.runtime.opcua,
             snapshot_provider,
             Some(bundle.root.as_path()),
+            opcua_metadata.as_ref(),
+            Some(method_caller),
+            Some(OpcUaAlarmSource::from_control_state(state.clone())),
         )?;
     }
 
@@ -644,6 +691,13 @@ pub fn run_runtime(
     } else {
         None
     };
+    let _sparkplug = match bundle
+        .as_ref()
+        .and_then(|bundle| bundle.runtime.sparkplug.clone())
+    {
+        Some(config) => Some(start_sparkplug(config, state.clone())?),
+        None => None,
+    };
     start_gate.open();
 
     if show_banner {
@@ -705,6 +759,7 @@ pub fn run_runtime(
                 "resource": bundle.runtime.resource_name.to_string(),
                 "restart": format!("{restart_mode:?}"),
                 "cycle_interval_ms": bundle.runtime.cycle_interval.as_millis(),
+                "task_threads": bundle.runtime.task_threads,
                 "io_driver": bundle
                     .io
                     .drivers
@@ -815,6 +870,66 @@ fn format_endpoint(endpoint: &ControlEndpoint) -> String {
     }
 }
 
+/// Switch program execution to the bytecode VM.
+///
+/// Bundles execute the bodies stored in `program.stbc`; a config-only run
+/// encodes the compiled sources first. Source paths are canonicalized so
+/// debug entries written by the bundle builder map back to the registry.
+fn load_program_bodies(
+    runtime: &mut Runtime,
+    module: Option<&BytecodeModule>,
+    sources: &SourceRegistry,
+) -> anyhow::Result<()> {
+    if sources.files().is_empty() {
+        return Ok(());
+    }
+    let texts = sources
+        .files()
+        .iter()
+        .map(|file| file.text.as_str())
+        .collect::<Vec<_>>();
+    let paths = sources
+        .files()
+        .iter()
+        .map(|file| {
+            file.path
+                .canonicalize()
+                .unwrap_or_else(|_| file.path.clone())
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    let paths = paths.iter().map(String::as_str).collect::<Vec<_>>();
+    let encoded;
+    let module = match module {
+        Some(module) => module,
+        None => {
+            encoded = BytecodeModule::from_runtime_with_sources_and_paths(runtime, &texts, &paths)?;
+            &encoded
+        }
+    };
+    runtime
+        .load_bytecode_program(module, &texts, &paths)
+        .map_err(|err| {
+            anyhow::anyhow!(
+                "failed to load program bodies from bytecode: {err} (rebuild with trust-runtime build)"
+            )
+        })
+}
+
+fn compile_sources(sources: &SourceRegistry) -> Vec<trust_runtime::harness::SourceFile> {
+    sources
+        .files()
+        .iter()
+        .map(|file| {
+            trust_runtime::harness::SourceFile::with_path(
+                file.path.to_string_lossy().as_ref(),
+                file.text.clone(),
+            )
+        })
+        .collect()
+}
+
 fn load_sources(root: &Path) -> anyhow::Result<SourceRegistry> {
     let mut files = Vec::new();
     let patterns = ["**/*.st", "**/*.ST", "**/*.pou", "**/*.POU"];
@@ -9,7 +9,7 @@ use anyhow::Context;
 use serde_json::json;
 use smol_str::SmolStr;
 use trust_runtime::bundle::detect_bundle_path;
-use trust_runtime::bundle_builder::resolve_sources_root;
+use trust_runtime::bundle_builder::{project_compile_session, resolve_sources_root};
 use trust_runtime::error::RuntimeError;
 use trust_runtime::eval::call_function_block;
 use trust_runtime::harness::{CompileSession, SourceFile as HarnessSourceFile};
@@ -154,7 +154,7 @@ pub fn run_test(
             )
         })
         .collect::<Vec<_>>();
-    let session = CompileSession::from_sources(compile_sources);
+    let session = project_compile_session(&project_root, compile_sources)?;
     let _ = session.build_runtime()?;
 
     let test_timeout = if timeout == 0 {
@@ -5,10 +5,11 @@ use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 
 use smol_str::SmolStr;
+use trust_runtime::bundle_builder::{collect_source_paths, project_compile_session};
 use trust_runtime::bundle_template::{
     build_io_config_auto, render_io_toml, render_runtime_toml, IoConfigTemplate, IoDriverTemplate,
 };
-use trust_runtime::harness::{CompileSession, SourceFile};
+use trust_runtime::harness::SourceFile;
 
 use crate::git::git_init;
 use crate::prompt::{prompt_choice, prompt_path, prompt_string, prompt_u64, prompt_yes_no};
@@ -109,10 +110,13 @@ pub fn create_bundle(path: Option<PathBuf>) -> anyhow::Result<PathBuf> {
     fs::write(sources_dir.join("main.st"), &main_text)?;
     fs::write(sources_dir.join("config.st"), &config_text)?;
 
-    let session = CompileSession::from_sources(vec![
-        SourceFile::with_path("config.st", config_text),
-        SourceFile::with_path("main.st", main_text),
-    ]);
+    let session = project_compile_session(
+        &root,
+        vec![
+            SourceFile::with_path("config.st", config_text),
+            SourceFile::with_path("main.st", main_text),
+        ],
+    )?;
     let bytecode = session.build_bytecode_bytes()?;
     fs::write(&program_path, bytecode)?;
 
@@ -172,7 +176,7 @@ pub fn create_bundle_auto(path: Option<PathBuf>) -> anyhow::Result<PathBuf> {
         } else {
             sources
         };
-        let session = CompileSession::from_sources(sources);
+        let session = project_compile_session(&root, sources)?;
         let bytecode = session.build_bytecode_bytes()?;
         fs::write(&program_path, bytecode)?;
     }
@@ -348,25 +352,14 @@ fn upgrade_legacy_sources(
 
 fn collect_sources(root: &Path) -> anyhow::Result<Vec<SourceFile>> {
     let mut files = Vec::new();
-    let patterns = ["**/*.st", "**/*.ST", "**/*.pou", "**/*.POU"];
-    for pattern in patterns {
-        for entry in glob::glob(&format!("{}/{}", root.display(), pattern))? {
-            let path = entry?;
-            let path_string = path.display().to_string();
-            if files
-                .iter()
-                .any(|file: &SourceFile| file.path.as_deref() == Some(path_string.as_str()))
-            {
-                continue;
-            }
-            let text = std::fs::read_to_string(&path)?;
-            if is_legacy_global_io(&path_string, &text) {
-                continue;
-            }
-            files.push(SourceFile::with_path(path_string, text));
+    for path in collect_source_paths(root)? {
+        let path_string = path.display().to_string();
+        let text = std::fs::read_to_string(&path)?;
+        if is_legacy_global_io(&path_string, &text) {
+            continue;
         }
+        files.push(SourceFile::with_path(path_string, text));
     }
-    files.sort_by(|a, b| a.path.cmp(&b.path));
     Ok(files)
 }
 
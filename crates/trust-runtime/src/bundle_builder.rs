@@ -7,6 +7,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::harness::{CompileSession, SourceFile};
+use trust_syntax::parser::Dialect;
 
 const DEPENDENCY_MANIFEST_FILES: &[&str] = &["trust-lsp.toml", ".trust-lsp.toml", "trustlsp.toml"];
 
@@ -44,7 +45,7 @@ pub fn build_program_stbc(
         );
     }
 
-    let session = CompileSession::from_sources(sources);
+    let session = project_compile_session(bundle_root, sources)?;
     let bytes = session.build_bytecode_bytes()?;
     fs::create_dir_all(bundle_root)?;
     let program_path = bundle_root.join("program.stbc");
@@ -64,6 +65,24 @@ pub fn build_program_stbc(
     })
 }
 
+/// Compile session for sources of the project at `project_root`.
+///
+/// Applies the defines selected by the project's `trust-lsp.toml`
+/// (`[build].defines` plus the active `[[targets]]` entry) and the parse
+/// dialect of its `[project].vendor_profile`. Every compile of project sources
+/// goes through here so `{IF defined(...)}` pragmas and vendor syntax resolve
+/// the same way in builds, runs, tests, deploys and online changes.
+pub fn project_compile_session(
+    project_root: &Path,
+    sources: Vec<SourceFile>,
+) -> anyhow::Result<CompileSession> {
+    let manifest = load_dependency_manifest(project_root)?;
+    let dialect = Dialect::from_vendor_profile(manifest.project.vendor_profile.as_deref());
+    Ok(CompileSession::from_sources(sources)
+        .defines(manifest.active_defines())
+        .dialect(dialect))
+}
+
 /// Resolve the effective project source root for bundle operations.
 ///
 /// Behavior:
@@ -234,29 +253,36 @@ fn preferred_dependency_sources_root(path: &Path) -> PathBuf {
     }
 }
 
+/// Structured Text sources (`.st`/`.pou`) below `sources_root`, sorted.
+///
+/// A missing directory yields no sources.
+pub fn collect_source_paths(sources_root: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = BTreeSet::new();
+    if !sources_root.is_dir() {
+        return Ok(Vec::new());
+    }
+    for pattern in ["**/*.st", "**/*.ST", "**/*.pou", "**/*.POU"] {
+        for entry in glob::glob(&format!("{}/{}", sources_root.display(), pattern))? {
+            let path = entry?;
+            if path.is_file() {
+                paths.insert(path);
+            }
+        }
+    }
+    Ok(paths.into_iter().collect())
+}
+
 fn collect_sources(source_roots: &[PathBuf]) -> anyhow::Result<(Vec<SourceFile>, Vec<PathBuf>)> {
-    let patterns = ["**/*.st", "**/*.ST", "**/*.pou", "**/*.POU"];
-    let mut seen = BTreeSet::new();
     let mut source_map = BTreeMap::new();
-
     for root in source_roots {
-        if !root.is_dir() {
-            continue;
-        }
-        for pattern in patterns {
-            for entry in glob::glob(&format!("{}/{}", root.display(), pattern))? {
-                let path = entry?;
-                if !path.is_file() {
-                    continue;
-                }
-                let resolved = canonicalize_or_self(&path);
-                let path_text = resolved.to_string_lossy().to_string();
-                if !seen.insert(path_text.clone()) {
-                    continue;
-                }
-                let text = fs::read_to_string(&resolved)?;
-                source_map.insert(path_text, text);
+        for path in collect_source_paths(root)? {
+            let resolved = canonicalize_or_self(&path);
+            let path_text = resolved.to_string_lossy().to_string();
+            if source_map.contains_key(&path_text) {
+                continue;
             }
+            let text = fs::read_to_string(&resolved)?;
+            source_map.insert(path_text, text);
         }
     }
 
@@ -325,7 +351,42 @@ struct DependencyManifestFile {
     #[serde(default)]
     package: PackageSection,
     #[serde(default)]
+    project: ProjectSection,
+    #[serde(default)]
     dependencies: BTreeMap<String, ManifestDependencyEntry>,
+    #[serde(default)]
+    build: BuildSection,
+    #[serde(default)]
+    targets: Vec<TargetSection>,
+}
+
+impl DependencyManifestFile {
+    /// `[build].defines` plus the defines of the selected `[[targets]]` entry.
+    fn active_defines(&self) -> Vec<String> {
+        let mut defines = self.build.defines.clone();
+        if let Some(target) = self.build.target.as_deref().and_then(|name| {
+            self.targets
+                .iter()
+                .find(|target| target.name.eq_ignore_ascii_case(name))
+        }) {
+            defines.extend(target.defines.iter().cloned());
+        }
+        defines
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BuildSection {
+    target: Option<String>,
+    #[serde(default)]
+    defines: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TargetSection {
+    name: String,
+    #[serde(default)]
+    defines: Vec<String>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -333,6 +394,11 @@ struct PackageSection {
     version: Option<String>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct ProjectSection {
+    vendor_profile: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum ManifestDependencyEntry {
@@ -459,6 +525,73 @@ version = "2.0.0"
         fs::remove_dir_all(root).ok();
     }
 
+    #[test]
+    fn build_applies_selected_target_defines() {
+        let root = temp_dir("trust-runtime-build-defines");
+        write_file(
+            &root.join("sources/main.st"),
+            r#"
+PROGRAM Main
+VAR
+    y : INT;
+END_VAR
+{IF defined(SIM)}
+y := 1;
+{ELSE}
+y := missing_io;
+{END_IF}
+END_PROGRAM
+"#,
+        );
+        write_file(
+            &root.join("trust-lsp.toml"),
+            r#"
+[build]
+target = "sim"
+
+[[targets]]
+name = "sim"
+defines = ["SIM=1"]
+"#,
+        );
+
+        build_program_stbc(&root, None).expect("sim target should build");
+
+        write_file(&root.join("trust-lsp.toml"), "[build]\n");
+        let err = build_program_stbc(&root, None).expect_err("else branch should compile");
+        assert!(err.to_string().contains("missing_io"), "{err}");
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn build_parses_with_the_project_vendor_dialect() {
+        let root = temp_dir("trust-runtime-build-dialect");
+        write_file(
+            &root.join("sources/main.st"),
+            r#"
+ORGANIZATION_BLOCK "Main"
+    VAR
+        Start : BOOL;
+        running : BOOL;
+    END_VAR
+BEGIN
+    #running := "Start";
+END_ORGANIZATION_BLOCK
+"#,
+        );
+        write_file(
+            &root.join("trust-lsp.toml"),
+            "[project]\nvendor_profile = \"siemens\"\n",
+        );
+        build_program_stbc(&root, None).expect("siemens profile should build");
+
+        write_file(&root.join("trust-lsp.toml"), "[project]\n");
+        build_program_stbc(&root, None).expect_err("\"Start\" is a WSTRING literal in IEC");
+
+        fs::remove_dir_all(root).ok();
+    }
+
     #[test]
     fn build_fails_for_missing_dependency_path() {
         let root = temp_dir("trust-runtime-build-missing");
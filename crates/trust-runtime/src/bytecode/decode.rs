@@ -8,11 +8,12 @@ use super::reader::BytecodeReader;
 use super::util::align4;
 use super::{
     BytecodeError, BytecodeModule, BytecodeVersion, ConstEntry, ConstPool, DebugEntry, DebugMap,
-    EnumVariant, Field, InterfaceImpl, InterfaceMethod, IoBinding, IoMap, MethodEntry,
-    PouClassMeta, PouEntry, PouIndex, PouKind, RefEntry, RefLocation, RefSegment, RefTable,
-    ResourceEntry, ResourceMeta, RetainInit, RetainInitEntry, Section, SectionData, SectionEntry,
-    SectionId, StringTable, TypeData, TypeEntry, TypeKind, TypeTable, VarMeta, VarMetaEntry,
-    HEADER_FLAG_CRC32, HEADER_SIZE, MAGIC, SECTION_ENTRY_SIZE, SUPPORTED_MAJOR_VERSION,
+    EnumVariant, Field, InitImage, InitInstance, InitSlot, InitValue, InterfaceImpl,
+    InterfaceMethod, IoBinding, IoMap, MethodEntry, PouClassMeta, PouDecl, PouDecls, PouEntry,
+    PouIndex, PouKind, RefEntry, RefLocation, RefSegment, RefTable, ResourceEntry, ResourceMeta,
+    RetainInit, RetainInitEntry, Section, SectionData, SectionEntry, SectionId, StringTable,
+    TypeData, TypeEntry, TypeKind, TypeTable, VarDecl, VarMeta, VarMetaEntry, HEADER_FLAG_CRC32,
+    HEADER_SIZE, MAGIC, SECTION_ENTRY_SIZE, SUPPORTED_MAJOR_VERSION,
 };
 
 impl BytecodeModule {
@@ -185,7 +186,7 @@ fn decode_section_data(
                 let id = reader.read_u32()?;
                 let name_idx = reader.read_u32()?;
                 let kind = reader.read_u8()?;
-                let _flags = reader.read_u8()?;
+                let flags = reader.read_u8()?;
                 let _reserved = reader.read_u16()?;
                 let code_offset = reader.read_u32()?;
                 let code_length = reader.read_u32()?;
@@ -280,6 +281,7 @@ fn decode_section_data(
                     id,
                     name_idx,
                     kind,
+                    flags,
                     code_offset,
                     code_length,
                     local_ref_start,
@@ -420,10 +422,143 @@ fn decode_section_data(
             }
             SectionData::RetainInit(RetainInit { entries })
         }
+        SectionId::PouDecls => {
+            let entry_count = reader.read_u32()? as usize;
+            let mut entries = Vec::with_capacity(entry_count);
+            for _ in 0..entry_count {
+                let pou_id = reader.read_u32()?;
+                let using_count = reader.read_u32()? as usize;
+                let mut using = Vec::with_capacity(using_count);
+                for _ in 0..using_count {
+                    using.push(reader.read_u32()?);
+                }
+                let vars = decode_var_decls(&mut reader)?;
+                let temps = decode_var_decls(&mut reader)?;
+                entries.push(PouDecl {
+                    pou_id,
+                    using,
+                    vars,
+                    temps,
+                });
+            }
+            SectionData::PouDecls(PouDecls { entries })
+        }
+        SectionId::InitImage => {
+            let next_instance_id = reader.read_u32()?;
+            let globals = decode_init_slots(&mut reader)?;
+            let instance_count = reader.read_u32()? as usize;
+            let mut instances = Vec::with_capacity(instance_count);
+            for _ in 0..instance_count {
+                let id = reader.read_u32()?;
+                let type_name_idx = reader.read_u32()?;
+                let parent = reader.read_u32()?;
+                let parent = if parent == u32::MAX {
+                    None
+                } else {
+                    Some(parent)
+                };
+                let vars = decode_init_slots(&mut reader)?;
+                instances.push(InitInstance {
+                    id,
+                    type_name_idx,
+                    parent,
+                    vars,
+                });
+            }
+            let name_count = reader.read_u32()? as usize;
+            let mut io_names = Vec::with_capacity(name_count);
+            for _ in 0..name_count {
+                let name_idx = reader.read_u32()?;
+                io_names.push((name_idx != u32::MAX).then_some(name_idx));
+            }
+            SectionData::InitImage(InitImage {
+                globals,
+                instances,
+                next_instance_id,
+                io_names,
+            })
+        }
     };
     Ok(data)
 }
 
+fn decode_var_decls(reader: &mut BytecodeReader<'_>) -> Result<Vec<VarDecl>, BytecodeError> {
+    let count = reader.read_u32()? as usize;
+    let mut vars = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name_idx = reader.read_u32()?;
+        let type_id = reader.read_u32()?;
+        let flags = reader.read_u8()?;
+        let has_init = reader.read_u8()?;
+        let _reserved = reader.read_u16()?;
+        let init = if has_init != 0 {
+            Some(decode_init_value(reader)?)
+        } else {
+            None
+        };
+        vars.push(VarDecl {
+            name_idx,
+            type_id,
+            flags,
+            init,
+        });
+    }
+    Ok(vars)
+}
+
+fn decode_init_slots(reader: &mut BytecodeReader<'_>) -> Result<Vec<InitSlot>, BytecodeError> {
+    let count = reader.read_u32()? as usize;
+    let mut slots = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name_idx = reader.read_u32()?;
+        let value = decode_init_value(reader)?;
+        slots.push(InitSlot { name_idx, value });
+    }
+    Ok(slots)
+}
+
+fn decode_init_value(reader: &mut BytecodeReader<'_>) -> Result<InitValue, BytecodeError> {
+    let tag = reader.read_u8()?;
+    reader.read_bytes(3)?;
+    let value = match tag {
+        0 => InitValue::Const(reader.read_u32()?),
+        1 => {
+            let dim_count = reader.read_u32()? as usize;
+            let mut dims = Vec::with_capacity(dim_count);
+            for _ in 0..dim_count {
+                let lower = reader.read_i64()?;
+                let upper = reader.read_i64()?;
+                dims.push((lower, upper));
+            }
+            let element_count = reader.read_u32()? as usize;
+            let mut elements = Vec::with_capacity(element_count.min(reader.remaining()));
+            for _ in 0..element_count {
+                elements.push(decode_init_value(reader)?);
+            }
+            InitValue::Array { dims, elements }
+        }
+        2 => {
+            let type_name_idx = reader.read_u32()?;
+            let fields = decode_init_slots(reader)?;
+            InitValue::Struct {
+                type_name_idx,
+                fields,
+            }
+        }
+        3 => {
+            let ref_idx = reader.read_u32()?;
+            InitValue::Reference((ref_idx != u32::MAX).then_some(ref_idx))
+        }
+        4 => InitValue::Instance(reader.read_u32()?),
+        _ => {
+            return Err(BytecodeError::InvalidSection(
+                format!("invalid init value tag {tag}").into(),
+            ))
+        }
+    };
+    Ok(value)
+}
+
 fn decode_string_table(
     version: BytecodeVersion,
     reader: &mut BytecodeReader<'_>,
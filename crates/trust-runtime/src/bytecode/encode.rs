@@ -4,8 +4,8 @@
 
 use super::util::{align4, pad_to};
 use super::{
-    BytecodeError, BytecodeModule, BytecodeVersion, SectionData, SectionEntry, TypeData, TypeEntry,
-    TypeTable, HEADER_FLAG_CRC32, HEADER_SIZE, MAGIC, SECTION_ENTRY_SIZE,
+    BytecodeError, BytecodeModule, BytecodeVersion, InitSlot, InitValue, SectionData, SectionEntry,
+    TypeData, TypeEntry, TypeTable, HEADER_FLAG_CRC32, HEADER_SIZE, MAGIC, SECTION_ENTRY_SIZE,
 };
 
 impl BytecodeModule {
@@ -132,7 +132,7 @@ fn encode_section_data(
                 out.extend_from_slice(&entry.id.to_le_bytes());
                 out.extend_from_slice(&entry.name_idx.to_le_bytes());
                 out.push(entry.kind as u8);
-                out.push(0);
+                out.push(entry.flags);
                 out.extend_from_slice(&0u16.to_le_bytes());
                 out.extend_from_slice(&entry.code_offset.to_le_bytes());
                 out.extend_from_slice(&entry.code_length.to_le_bytes());
@@ -243,11 +243,95 @@ fn encode_section_data(
                 out.extend_from_slice(&entry.const_idx.to_le_bytes());
             }
         }
+        SectionData::PouDecls(decls) => {
+            out.extend_from_slice(&(decls.entries.len() as u32).to_le_bytes());
+            for entry in &decls.entries {
+                out.extend_from_slice(&entry.pou_id.to_le_bytes());
+                out.extend_from_slice(&(entry.using.len() as u32).to_le_bytes());
+                for idx in &entry.using {
+                    out.extend_from_slice(&idx.to_le_bytes());
+                }
+                for vars in [&entry.vars, &entry.temps] {
+                    out.extend_from_slice(&(vars.len() as u32).to_le_bytes());
+                    for var in vars {
+                        out.extend_from_slice(&var.name_idx.to_le_bytes());
+                        out.extend_from_slice(&var.type_id.to_le_bytes());
+                        out.push(var.flags);
+                        out.push(u8::from(var.init.is_some()));
+                        out.extend_from_slice(&0u16.to_le_bytes());
+                        if let Some(init) = &var.init {
+                            encode_init_value(init, &mut out);
+                        }
+                    }
+                }
+            }
+        }
+        SectionData::InitImage(image) => {
+            out.extend_from_slice(&image.next_instance_id.to_le_bytes());
+            encode_init_slots(&image.globals, &mut out);
+            out.extend_from_slice(&(image.instances.len() as u32).to_le_bytes());
+            for instance in &image.instances {
+                out.extend_from_slice(&instance.id.to_le_bytes());
+                out.extend_from_slice(&instance.type_name_idx.to_le_bytes());
+                out.extend_from_slice(&instance.parent.unwrap_or(u32::MAX).to_le_bytes());
+                encode_init_slots(&instance.vars, &mut out);
+            }
+            out.extend_from_slice(&(image.io_names.len() as u32).to_le_bytes());
+            for name_idx in &image.io_names {
+                out.extend_from_slice(&name_idx.unwrap_or(u32::MAX).to_le_bytes());
+            }
+        }
         SectionData::Raw(raw) => out.extend_from_slice(raw),
     }
     Ok(out)
 }
 
+fn encode_init_slots(slots: &[InitSlot], out: &mut Vec<u8>) {
+    out.extend_from_slice(&(slots.len() as u32).to_le_bytes());
+    for slot in slots {
+        out.extend_from_slice(&slot.name_idx.to_le_bytes());
+        encode_init_value(&slot.value, out);
+    }
+}
+
+fn encode_init_value(value: &InitValue, out: &mut Vec<u8>) {
+    let tag = match value {
+        InitValue::Const(_) => 0,
+        InitValue::Array { .. } => 1,
+        InitValue::Struct { .. } => 2,
+        InitValue::Reference(_) => 3,
+        InitValue::Instance(_) => 4,
+    };
+    out.push(tag);
+    out.extend_from_slice(&[0u8; 3]);
+    match value {
+        InitValue::Const(idx) | InitValue::Instance(idx) => {
+            out.extend_from_slice(&idx.to_le_bytes());
+        }
+        InitValue::Array { dims, elements } => {
+            out.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for (lower, upper) in dims {
+                out.extend_from_slice(&lower.to_le_bytes());
+                out.extend_from_slice(&upper.to_le_bytes());
+            }
+            out.extend_from_slice(&(elements.len() as u32).to_le_bytes());
+            for element in elements {
+                encode_init_value(element, out);
+            }
+        }
+        InitValue::Struct {
+            type_name_idx,
+            fields,
+        } => {
+            out.extend_from_slice(&type_name_idx.to_le_bytes());
+            encode_init_slots(fields, out);
+        }
+        InitValue::Reference(ref_idx) => {
+            out.extend_from_slice(&ref_idx.unwrap_or(u32::MAX).to_le_bytes());
+        }
+    }
+}
+
 fn encode_type_table(
     version: BytecodeVersion,
     table: &TypeTable,
@@ -0,0 +1,365 @@
+use smol_str::SmolStr;
+use trust_hir::symbols::ParamDirection;
+
+use crate::bytecode::{CALL_ARGS_NARROW_PARAMS, CALL_ARGS_POSITIONAL, CALL_ARGS_WIDE};
+use crate::eval::expr::Expr;
+use crate::eval::{ArgValue, CallArg, Param};
+use crate::stdlib::StdParams;
+use crate::value::Value;
+
+use super::typing::{lvalue_expr, CallTarget};
+use super::util::normalize_name;
+use super::{BytecodeEncoder, BytecodeError, CodegenContext};
+
+impl<'a> BytecodeEncoder<'a> {
+    /// Emit a call; every call leaves exactly one value on the stack
+    /// (NULL for function blocks and methods without a return type).
+    pub(super) fn emit_call(
+        &mut self,
+        ctx: &CodegenContext,
+        target: &Expr,
+        args: &[CallArg],
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        let Some(callee) = self.call_target(ctx, target) else {
+            return Ok(false);
+        };
+        match callee {
+            CallTarget::Ref => match args {
+                [CallArg {
+                    value: ArgValue::Target(target),
+                    ..
+                }] => self.emit_place(ctx, &lvalue_expr(target), code),
+                _ => Ok(false),
+            },
+            CallTarget::Split(name) => self.emit_split_call(ctx, &name, args, code),
+            CallTarget::Std(name) => self.emit_std_call(ctx, &name, args, code),
+            CallTarget::Function(key) => {
+                let Some(func) = self.runtime.functions().get(&key) else {
+                    return Ok(false);
+                };
+                let Some(pou_id) = self.pou_ids.function_id(&key) else {
+                    return Ok(false);
+                };
+                if !self.emit_call_args(ctx, &func.params, args, true, code)? {
+                    return Ok(false);
+                }
+                code.push(0x05);
+                code.extend_from_slice(&pou_id.to_le_bytes());
+                Ok(true)
+            }
+            CallTarget::Method { owner, name } => {
+                let Some(method) = self.find_method(&owner, &name) else {
+                    return Ok(false);
+                };
+                match target {
+                    Expr::Field { target: base, .. } => {
+                        if !self.emit_expr(ctx, base, code)? {
+                            return Ok(false);
+                        }
+                    }
+                    _ => code.push(0x23),
+                }
+                if !self.emit_call_args(ctx, &method.params, args, true, code)? {
+                    return Ok(false);
+                }
+                let key = normalize_name(&owner);
+                if self.runtime.interfaces().contains_key(&key) {
+                    let Some(type_id) = self.runtime.registry().lookup(&owner) else {
+                        return Ok(false);
+                    };
+                    let type_idx = self.type_index(type_id)?;
+                    let methods = self.interface_methods_for(&owner)?;
+                    let Some(slot) = methods.iter().find_map(|entry| {
+                        self.strings
+                            .entries
+                            .get(entry.name_idx as usize)
+                            .filter(|method_name| method_name.eq_ignore_ascii_case(&name))
+                            .map(|_| entry.slot)
+                    }) else {
+                        return Ok(false);
+                    };
+                    code.push(0x08);
+                    code.extend_from_slice(&type_idx.to_le_bytes());
+                    code.extend_from_slice(&slot.to_le_bytes());
+                } else {
+                    let table = self.method_table_for(&owner)?;
+                    let Some(slot) = table.iter().find_map(|entry| {
+                        self.strings
+                            .entries
+                            .get(entry.name_idx as usize)
+                            .filter(|method_name| method_name.eq_ignore_ascii_case(&name))
+                            .map(|_| entry.vtable_slot)
+                    }) else {
+                        return Ok(false);
+                    };
+                    code.push(0x07);
+                    code.extend_from_slice(&slot.to_le_bytes());
+                }
+                Ok(true)
+            }
+            CallTarget::FunctionBlock(name) => {
+                let key = normalize_name(&name);
+                let Some(fb) = self.runtime.function_blocks().get(&key) else {
+                    return Ok(false);
+                };
+                let Some(pou_id) = self.pou_ids.function_block_id(&key) else {
+                    return Ok(false);
+                };
+                if !self.emit_expr(ctx, target, code)? {
+                    return Ok(false);
+                }
+                if !self.emit_call_args(ctx, &fb.params, args, false, code)? {
+                    return Ok(false);
+                }
+                code.push(0x05);
+                code.extend_from_slice(&pou_id.to_le_bytes());
+                Ok(true)
+            }
+        }
+    }
+
+    /// Push supplied arguments in parameter order followed by the LWORD
+    /// argument mask, binding them the way `prepare_bindings` does.
+    ///
+    /// IN arguments are pushed as values, OUT and IN_OUT arguments as
+    /// references to their targets. Callees with more parameters than one
+    /// mask can mark get the wide mask layout of [`CALL_ARGS_WIDE`].
+    fn emit_call_args(
+        &mut self,
+        ctx: &CodegenContext,
+        params: &[Param],
+        args: &[CallArg],
+        frame_params: bool,
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        let positional = args.iter().all(|arg| arg.name.is_none());
+        let mut positional_args = args.iter();
+        if positional {
+            let expected = params.iter().filter(|param| !is_en_eno(param)).count();
+            if args.is_empty() && expected > 0 {
+                // Let the runtime binding report the argument count mismatch.
+                return self.emit_const_value(&Value::LWord(CALL_ARGS_POSITIONAL), code);
+            }
+            if args.len() != expected {
+                return Ok(false);
+            }
+        }
+        let mut words = vec![0u64; params.len().div_ceil(64).max(1)];
+        for (idx, param) in params.iter().enumerate() {
+            let arg = if positional {
+                if is_en_eno(param) {
+                    continue;
+                }
+                positional_args.next()
+            } else {
+                args.iter()
+                    .find(|arg| arg.name.as_ref() == Some(&param.name))
+            };
+            let Some(arg) = arg else {
+                // Omitted IN_OUT parameters would shift the callee frame layout.
+                if frame_params && matches!(param.direction, ParamDirection::InOut) {
+                    return Ok(false);
+                }
+                continue;
+            };
+            let emitted = match (param.direction, &arg.value) {
+                (ParamDirection::In, ArgValue::Expr(expr)) => self.emit_expr(ctx, expr, code)?,
+                (ParamDirection::In, ArgValue::Target(target)) => {
+                    self.emit_expr(ctx, &lvalue_expr(target), code)?
+                }
+                (ParamDirection::Out | ParamDirection::InOut, ArgValue::Target(target)) => {
+                    self.emit_place(ctx, &lvalue_expr(target), code)?
+                }
+                (ParamDirection::Out | ParamDirection::InOut, ArgValue::Expr(_)) => false,
+            };
+            if !emitted {
+                return Ok(false);
+            }
+            words[idx / 64] |= 1 << (idx % 64);
+        }
+        let mut mask = if positional { CALL_ARGS_POSITIONAL } else { 0 };
+        if params.len() <= CALL_ARGS_NARROW_PARAMS {
+            mask |= words[0];
+        } else {
+            for word in &words {
+                if !self.emit_const_value(&Value::LWord(*word), code)? {
+                    return Ok(false);
+                }
+            }
+            mask |= CALL_ARGS_WIDE | words.len() as u64;
+        }
+        self.emit_const_value(&Value::LWord(mask), code)
+    }
+
+    fn emit_std_call(
+        &mut self,
+        ctx: &CodegenContext,
+        name: &SmolStr,
+        args: &[CallArg],
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        let ordered = if args.iter().any(|arg| arg.name.is_some()) {
+            let params = match self.runtime.stdlib().get(name) {
+                Some(entry) => entry.params.clone(),
+                None => StdParams::Fixed(vec![SmolStr::new("IN")]),
+            };
+            match bind_std_named_args(&params, args) {
+                Some(ordered) => ordered,
+                None => return Ok(false),
+            }
+        } else {
+            args.iter().collect()
+        };
+        for arg in &ordered {
+            if !self.emit_arg_value(ctx, arg, code)? {
+                return Ok(false);
+            }
+        }
+        self.emit_std_op(name, ordered.len(), code)
+    }
+
+    fn emit_split_call(
+        &mut self,
+        ctx: &CodegenContext,
+        name: &SmolStr,
+        args: &[CallArg],
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        let params: &[&str] = match name.as_str() {
+            "SPLIT_DATE" => &["IN", "YEAR", "MONTH", "DAY"],
+            "SPLIT_TOD" | "SPLIT_LTOD" => &["IN", "HOUR", "MINUTE", "SECOND", "MILLISECOND"],
+            _ => &[
+                "IN",
+                "YEAR",
+                "MONTH",
+                "DAY",
+                "HOUR",
+                "MINUTE",
+                "SECOND",
+                "MILLISECOND",
+            ],
+        };
+        if args.len() != params.len() {
+            return Ok(false);
+        }
+        let ordered: Vec<&CallArg> = if args.iter().all(|arg| arg.name.is_none()) {
+            args.iter().collect()
+        } else {
+            let mut ordered = Vec::with_capacity(params.len());
+            for param in params {
+                let mut matching = args.iter().filter(|arg| {
+                    arg.name
+                        .as_ref()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(param))
+                });
+                match (matching.next(), matching.next()) {
+                    (Some(arg), None) => ordered.push(arg),
+                    _ => return Ok(false),
+                }
+            }
+            ordered
+        };
+        for (idx, arg) in ordered.iter().enumerate() {
+            let emitted = match (&arg.value, idx) {
+                (_, 0) => self.emit_arg_value(ctx, arg, code)?,
+                (ArgValue::Target(target), _) => {
+                    self.emit_place(ctx, &lvalue_expr(target), code)?
+                }
+                (ArgValue::Expr(_), _) => false,
+            };
+            if !emitted {
+                return Ok(false);
+            }
+        }
+        self.emit_std_op(name, ordered.len(), code)
+    }
+
+    /// `CALL_STD name_idx` with the argument count pushed as a UDINT.
+    pub(super) fn emit_std_op(
+        &mut self,
+        name: &str,
+        count: usize,
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        let Ok(count) = u32::try_from(count) else {
+            return Ok(false);
+        };
+        if !self.emit_const_value(&Value::UDInt(count), code)? {
+            return Ok(false);
+        }
+        let name_idx = self.strings.intern(name);
+        code.push(0x70);
+        code.extend_from_slice(&name_idx.to_le_bytes());
+        Ok(true)
+    }
+
+    fn emit_arg_value(
+        &mut self,
+        ctx: &CodegenContext,
+        arg: &CallArg,
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        match &arg.value {
+            ArgValue::Expr(expr) => self.emit_expr(ctx, expr, code),
+            ArgValue::Target(target) => self.emit_expr(ctx, &lvalue_expr(target), code),
+        }
+    }
+}
+
+/// Order named standard-function arguments like `bind_stdlib_named_args`;
+/// `None` when the evaluator would reject the binding.
+fn bind_std_named_args<'c>(params: &StdParams, args: &'c [CallArg]) -> Option<Vec<&'c CallArg>> {
+    if args.iter().any(|arg| arg.name.is_none()) {
+        return None;
+    }
+    let (fixed, variadic) = match params {
+        StdParams::Fixed(params) => {
+            if args.len() != params.len() {
+                return None;
+            }
+            (params.as_slice(), None)
+        }
+        StdParams::Variadic {
+            fixed,
+            prefix,
+            start,
+            min,
+        } => (fixed.as_slice(), Some((prefix, *start, *min))),
+    };
+    let mut fixed_args: Vec<Option<&CallArg>> = vec![None; fixed.len()];
+    let mut variadic_args: Vec<Option<&CallArg>> = Vec::new();
+    for arg in args {
+        let key = arg.name.as_ref()?.to_ascii_uppercase();
+        if let Some(position) = fixed.iter().position(|param| param.as_str() == key) {
+            if fixed_args[position].replace(arg).is_some() {
+                return None;
+            }
+            continue;
+        }
+        let (prefix, start, _) = variadic?;
+        let index = key
+            .strip_prefix(prefix.as_str())
+            .filter(|suffix| !suffix.is_empty())?
+            .parse::<usize>()
+            .ok()?;
+        let offset = index.checked_sub(start)?;
+        if variadic_args.len() <= offset {
+            variadic_args.resize(offset + 1, None);
+        }
+        if variadic_args[offset].replace(arg).is_some() {
+            return None;
+        }
+    }
+    if let Some((_, _, min)) = variadic {
+        if variadic_args.len() < min {
+            return None;
+        }
+    }
+    fixed_args.into_iter().chain(variadic_args).collect()
+}
+
+fn is_en_eno(param: &Param) -> bool {
+    matches!(param.direction, ParamDirection::In | ParamDirection::Out)
+        && (param.name.eq_ignore_ascii_case("EN") || param.name.eq_ignore_ascii_case("ENO"))
+}
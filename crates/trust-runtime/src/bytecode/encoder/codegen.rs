@@ -1,11 +1,12 @@
 use smol_str::SmolStr;
+use trust_hir::{Type, TypeId};
 
 use crate::bytecode::DebugEntry;
 use crate::value::{Value, ValueRef};
 
-use super::consts::type_id_for_value;
-use super::util::to_u32;
-use super::{AccessKind, BytecodeEncoder, BytecodeError, CodegenContext};
+use super::typing::lvalue_expr;
+use super::util::normalize_name;
+use super::{AccessKind, BytecodeEncoder, BytecodeError, CodegenContext, LabelScope, LoopJumps};
 
 impl<'a> BytecodeEncoder<'a> {
     fn emit_assign(
@@ -23,16 +24,18 @@ impl<'a> BytecodeEncoder<'a> {
             code.truncate(start_len);
             return Ok(false);
         }
-        let reference = match self.resolve_lvalue_ref(ctx, target)? {
-            Some(reference) => reference,
-            None => {
-                code.truncate(start_len);
-                return Ok(false);
-            }
-        };
-        let ref_idx = self.ref_index_for(&reference)?;
-        code.push(0x21);
-        code.extend_from_slice(&ref_idx.to_le_bytes());
+        if let Some(reference) = self.resolve_lvalue_ref(ctx, target)? {
+            let ref_idx = self.ref_index_for(&reference)?;
+            code.push(0x21);
+            code.extend_from_slice(&ref_idx.to_le_bytes());
+            return Ok(true);
+        }
+        if !self.emit_place(ctx, &lvalue_expr(target), code)? {
+            code.truncate(start_len);
+            return Ok(false);
+        }
+        code.push(0x13); // SWAP
+        code.push(0x33); // STORE
         Ok(true)
     }
 
@@ -60,7 +63,7 @@ impl<'a> BytecodeEncoder<'a> {
         Ok(Some(true))
     }
 
-    fn emit_dynamic_ref_for_lvalue(
+    pub(super) fn emit_dynamic_ref_for_lvalue(
         &mut self,
         ctx: &CodegenContext,
         target: &crate::eval::expr::LValue,
@@ -94,7 +97,7 @@ impl<'a> BytecodeEncoder<'a> {
         }
     }
 
-    fn lvalue_is_self_field(
+    pub(super) fn lvalue_is_self_field(
         &self,
         ctx: &CodegenContext,
         target: &crate::eval::expr::LValue,
@@ -212,7 +215,111 @@ impl<'a> BytecodeEncoder<'a> {
         Ok(true)
     }
 
-    fn emit_expr(
+    /// Push a reference to the storage designated by `expr`.
+    pub(super) fn emit_place(
+        &mut self,
+        ctx: &CodegenContext,
+        expr: &crate::eval::expr::Expr,
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        use crate::eval::expr::Expr;
+        let start_len = code.len();
+        let emitted = match expr {
+            Expr::Name(name) => {
+                if let Some(reference) = ctx.local_ref(name).cloned() {
+                    self.emit_push_ref(&reference, code)?;
+                    true
+                } else if self.emit_self_field_ref(ctx, name, code)? {
+                    true
+                } else if let Some(reference) = self.resolve_name_ref(ctx, name)? {
+                    self.emit_push_ref(&reference, code)?;
+                    true
+                } else {
+                    false
+                }
+            }
+            Expr::Field { target, field } => {
+                let base = if self.emit_place(ctx, target, code)? {
+                    true
+                } else {
+                    // Instances returned by expressions (e.g. `THIS`) are
+                    // addressed through the instance itself.
+                    self.expr_is_instance(ctx, target) && self.emit_expr(ctx, target, code)?
+                };
+                if base {
+                    let field_idx = self.strings.intern(field.clone());
+                    code.push(0x30);
+                    code.extend_from_slice(&field_idx.to_le_bytes());
+                }
+                base
+            }
+            Expr::Index { target, indices } => {
+                let mut emitted = self.emit_place(ctx, target, code)?;
+                for index in indices {
+                    emitted = emitted && self.emit_expr(ctx, index, code)?;
+                    code.push(0x31);
+                }
+                emitted
+            }
+            Expr::Deref(target) => self.emit_expr(ctx, target, code)?,
+            Expr::This => {
+                code.push(0x23);
+                true
+            }
+            Expr::Super => {
+                code.push(0x24);
+                true
+            }
+            _ => false,
+        };
+        if !emitted {
+            code.truncate(start_len);
+        }
+        Ok(emitted)
+    }
+
+    fn emit_place_load(
+        &mut self,
+        ctx: &CodegenContext,
+        expr: &crate::eval::expr::Expr,
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        if !self.emit_place(ctx, expr, code)? {
+            return Ok(false);
+        }
+        code.push(0x32);
+        Ok(true)
+    }
+
+    fn expr_is_instance(&self, ctx: &CodegenContext, expr: &crate::eval::expr::Expr) -> bool {
+        matches!(
+            expr,
+            crate::eval::expr::Expr::This | crate::eval::expr::Expr::Super
+        ) || self
+            .expr_type(ctx, expr)
+            .and_then(|type_id| self.class_like_name(type_id))
+            .is_some()
+    }
+
+    fn expr_is_type(
+        &self,
+        ctx: &CodegenContext,
+        expr: &crate::eval::expr::Expr,
+        expected: TypeId,
+    ) -> bool {
+        self.expr_type(ctx, expr).is_some_and(|type_id| {
+            type_id == expected || self.resolved_type(type_id) == self.resolved_type(expected)
+        })
+    }
+
+    fn expr_is_reference(&self, ctx: &CodegenContext, expr: &crate::eval::expr::Expr) -> bool {
+        matches!(expr, crate::eval::expr::Expr::Ref(_))
+            || self.expr_type(ctx, expr).is_some_and(|type_id| {
+                matches!(self.resolved_type(type_id), Some(Type::Reference { .. }))
+            })
+    }
+
+    pub(super) fn emit_expr(
         &mut self,
         ctx: &CodegenContext,
         expr: &crate::eval::expr::Expr,
@@ -260,26 +367,20 @@ impl<'a> BytecodeEncoder<'a> {
                         return Ok(true);
                     }
                     code.truncate(start_len);
-                    let reference = match self.resolve_lvalue_ref(
+                    if let Some(reference) = self.resolve_lvalue_ref(
                         ctx,
                         &crate::eval::expr::LValue::Field {
                             name: base.clone(),
                             field: field.clone(),
                         },
                     )? {
-                        Some(reference) => reference,
-                        None => {
-                            code.truncate(start_len);
-                            return Ok(false);
-                        }
-                    };
-                    let ref_idx = self.ref_index_for(&reference)?;
-                    code.push(0x20);
-                    code.extend_from_slice(&ref_idx.to_le_bytes());
-                    Ok(true)
-                } else {
-                    Ok(false)
+                        let ref_idx = self.ref_index_for(&reference)?;
+                        code.push(0x20);
+                        code.extend_from_slice(&ref_idx.to_le_bytes());
+                        return Ok(true);
+                    }
                 }
+                self.emit_place_load(ctx, expr, code)
             }
             crate::eval::expr::Expr::Index { target, indices } => {
                 if let crate::eval::expr::Expr::Name(base) = target.as_ref() {
@@ -287,26 +388,58 @@ impl<'a> BytecodeEncoder<'a> {
                         return Ok(true);
                     }
                     code.truncate(start_len);
-                    let reference = match self.resolve_lvalue_ref(
+                    if let Some(reference) = self.resolve_lvalue_ref(
                         ctx,
                         &crate::eval::expr::LValue::Index {
                             name: base.clone(),
                             indices: indices.clone(),
                         },
                     )? {
-                        Some(reference) => reference,
-                        None => {
-                            code.truncate(start_len);
-                            return Ok(false);
-                        }
-                    };
-                    let ref_idx = self.ref_index_for(&reference)?;
-                    code.push(0x20);
-                    code.extend_from_slice(&ref_idx.to_le_bytes());
-                    Ok(true)
-                } else {
-                    Ok(false)
+                        let ref_idx = self.ref_index_for(&reference)?;
+                        code.push(0x20);
+                        code.extend_from_slice(&ref_idx.to_le_bytes());
+                        return Ok(true);
+                    }
                 }
+                self.emit_place_load(ctx, expr, code)
+            }
+            crate::eval::expr::Expr::Deref(target) => {
+                if !self.emit_expr(ctx, target, code)? {
+                    code.truncate(start_len);
+                    return Ok(false);
+                }
+                code.push(0x32);
+                Ok(true)
+            }
+            crate::eval::expr::Expr::Ref(target) => {
+                self.emit_place(ctx, &lvalue_expr(target), code)
+            }
+            crate::eval::expr::Expr::This => {
+                code.push(0x23);
+                Ok(true)
+            }
+            crate::eval::expr::Expr::Super => {
+                code.push(0x24);
+                Ok(true)
+            }
+            crate::eval::expr::Expr::SizeOf(crate::eval::expr::SizeOfTarget::Type(type_id)) => {
+                let size = crate::value::size_of_type(*type_id, self.runtime.registry())
+                    .ok()
+                    .and_then(|size| i32::try_from(size).ok());
+                match size {
+                    Some(size) => self.emit_const_value(&Value::DInt(size), code),
+                    None => Ok(false),
+                }
+            }
+            crate::eval::expr::Expr::SizeOf(crate::eval::expr::SizeOfTarget::Expr(target)) => {
+                if !self.emit_expr(ctx, target, code)? {
+                    code.truncate(start_len);
+                    return Ok(false);
+                }
+                self.emit_std_op("SIZEOF", 1, code)
+            }
+            crate::eval::expr::Expr::Call { target, args } => {
+                self.emit_call(ctx, target, args, code)
             }
             crate::eval::expr::Expr::Unary { op, expr } => {
                 use crate::eval::ops::UnaryOp;
@@ -344,14 +477,26 @@ impl<'a> BytecodeEncoder<'a> {
                     code.truncate(start_len);
                     return Ok(false);
                 }
+                // BOOL AND/OR skip the right operand like the evaluator does.
+                let short_circuit = match op {
+                    BinaryOp::And | BinaryOp::Or if self.expr_is_type(ctx, left, TypeId::BOOL) => {
+                        code.push(0x11);
+                        let jump = if *op == BinaryOp::And { 0x04 } else { 0x03 };
+                        Some(self.emit_jump_placeholder(code, jump))
+                    }
+                    _ => None,
+                };
                 if !self.emit_expr(ctx, right, code)? {
                     code.truncate(start_len);
                     return Ok(false);
                 }
                 code.push(opcode);
+                if let Some(jump) = short_circuit {
+                    let end = code.len();
+                    self.patch_jump(code, jump, end)?;
+                }
                 Ok(true)
             }
-            _ => Ok(false),
         };
         match result {
             Ok(true) => Ok(true),
@@ -370,13 +515,13 @@ impl<'a> BytecodeEncoder<'a> {
         &mut self,
         ctx: &mut CodegenContext,
         pou_id: u32,
+        name: &SmolStr,
         body: &[crate::eval::stmt::Stmt],
     ) -> Result<(Vec<u8>, Vec<DebugEntry>), BytecodeError> {
+        ctx.pou_name = name.clone();
         let mut code = Vec::new();
         let mut debug_entries = Vec::new();
-        for stmt in body {
-            self.emit_stmt(ctx, pou_id, stmt, &mut code, &mut debug_entries)?;
-        }
+        self.emit_block(ctx, pou_id, body, &mut code, &mut debug_entries)?;
         Ok((code, debug_entries))
     }
 
@@ -388,28 +533,34 @@ impl<'a> BytecodeEncoder<'a> {
         code: &mut Vec<u8>,
         debug_entries: &mut Vec<DebugEntry>,
     ) -> Result<(), BytecodeError> {
-        let offset = to_u32(code.len(), "debug code offset")?;
-        if let (Some(location), Some(sources)) = (stmt.location(), self.sources) {
-            let source = sources
-                .get(location.file_id as usize)
-                .ok_or_else(|| BytecodeError::InvalidSection("debug source missing".into()))?;
-            let (line, column) = crate::debug::location_to_line_col(source, location);
-            let line = line.saturating_add(1);
-            let column = column.saturating_add(1);
-            let file_idx = self.file_path_index(location.file_id)?;
-            debug_entries.push(DebugEntry {
-                pou_id,
-                code_offset: offset,
-                file_idx,
-                line,
-                column,
-                kind: 0,
-            });
+        let code_start = code.len();
+        if let Some(location) = stmt.location() {
+            self.push_debug_entry(pou_id, code_start, location, debug_entries)?;
         }
+        let debug_start = debug_entries.len();
+        let loop_jumps = ctx
+            .loops
+            .last()
+            .map(|jumps| (jumps.exits.len(), jumps.continues.len()));
+        let label_jumps = ctx.labels.last().map(|scope| scope.pending.len());
         let emitted = match stmt {
             crate::eval::stmt::Stmt::Assign { target, value, .. } => {
                 self.emit_assign(ctx, target, value, code)?
             }
+            crate::eval::stmt::Stmt::AssignAttempt { target, value, .. } => {
+                // Only reference-to-reference attempts are statically known to
+                // keep the value unchanged.
+                self.expr_is_reference(ctx, &lvalue_expr(target))
+                    && self.expr_is_reference(ctx, value)
+                    && self.emit_assign(ctx, target, value, code)?
+            }
+            crate::eval::stmt::Stmt::Expr { expr, .. } => {
+                let emitted = self.emit_expr(ctx, expr, code)?;
+                if emitted {
+                    code.push(0x12);
+                }
+                emitted
+            }
             crate::eval::stmt::Stmt::If {
                 condition,
                 then_block,
@@ -467,21 +618,60 @@ impl<'a> BytecodeEncoder<'a> {
             crate::eval::stmt::Stmt::Label { stmt, .. } => {
                 if let Some(stmt) = stmt.as_deref() {
                     self.emit_stmt(ctx, pou_id, stmt, code, debug_entries)?;
+                }
+                true
+            }
+            crate::eval::stmt::Stmt::Jmp { target, .. } => {
+                // Labels are only visible within the statement list declaring them.
+                let key = normalize_name(target);
+                match ctx.labels.last_mut() {
+                    Some(scope) if scope.positions.contains_key(&key) => {
+                        let jump = self.emit_jump_placeholder(code, 0x02);
+                        scope.pending.push((jump, key));
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            crate::eval::stmt::Stmt::Return { expr, .. } => self.emit_return(ctx, expr, code)?,
+            crate::eval::stmt::Stmt::Exit { .. } => match ctx.loops.last_mut() {
+                Some(jumps) => {
+                    jumps.exits.push(self.emit_jump_placeholder(code, 0x02));
+                    true
+                }
+                None => false,
+            },
+            crate::eval::stmt::Stmt::Continue { .. } => match ctx.loops.last_mut() {
+                Some(jumps) => {
+                    jumps.continues.push(self.emit_jump_placeholder(code, 0x02));
                     true
-                } else {
-                    false
                 }
+                None => false,
+            },
+            crate::eval::stmt::Stmt::Sfc { chart, .. } => {
+                self.emit_sfc_chart(ctx, pou_id, chart, code, debug_entries)?
             }
-            _ => false,
         };
 
         if !emitted {
-            code.push(0x00);
+            code.truncate(code_start);
+            debug_entries.truncate(debug_start);
+            if let (Some(jumps), Some((exits, continues))) = (ctx.loops.last_mut(), loop_jumps) {
+                jumps.exits.truncate(exits);
+                jumps.continues.truncate(continues);
+            }
+            if let (Some(scope), Some(pending)) = (ctx.labels.last_mut(), label_jumps) {
+                scope.pending.truncate(pending);
+            }
+            return Err(BytecodeError::UnsupportedStatement {
+                pou: ctx.pou_name.clone(),
+                offset: stmt.location().map_or(0, |location| location.start),
+            });
         }
         Ok(())
     }
 
-    fn emit_block(
+    pub(super) fn emit_block(
         &mut self,
         ctx: &mut CodegenContext,
         pou_id: u32,
@@ -489,12 +679,101 @@ impl<'a> BytecodeEncoder<'a> {
         code: &mut Vec<u8>,
         debug_entries: &mut Vec<DebugEntry>,
     ) -> Result<(), BytecodeError> {
+        let mut scope = LabelScope::default();
         for stmt in block {
-            self.emit_stmt(ctx, pou_id, stmt, code, debug_entries)?;
+            if let crate::eval::stmt::Stmt::Label { name, .. } = stmt {
+                scope.positions.insert(normalize_name(name), None);
+            }
+        }
+        ctx.labels.push(scope);
+        let mut result = Ok(());
+        for stmt in block {
+            if let crate::eval::stmt::Stmt::Label { name, .. } = stmt {
+                let start = code.len();
+                if let Some(scope) = ctx.labels.last_mut() {
+                    // The first declaration of a label wins, as in the evaluator.
+                    scope
+                        .positions
+                        .entry(normalize_name(name))
+                        .and_modify(|position| {
+                            position.get_or_insert(start);
+                        });
+                }
+            }
+            result = self.emit_stmt(ctx, pou_id, stmt, code, debug_entries);
+            if result.is_err() {
+                break;
+            }
+        }
+        let scope = ctx.labels.pop().unwrap_or_default();
+        result?;
+        for (jump, label) in scope.pending {
+            let target = scope
+                .positions
+                .get(&label)
+                .copied()
+                .flatten()
+                .ok_or_else(|| BytecodeError::InvalidSection("label position missing".into()))?;
+            self.patch_jump(code, jump, target)?;
         }
         Ok(())
     }
 
+    /// Emit a loop body with its own EXIT/CONTINUE jump list.
+    fn emit_loop_body(
+        &mut self,
+        ctx: &mut CodegenContext,
+        pou_id: u32,
+        body: &[crate::eval::stmt::Stmt],
+        code: &mut Vec<u8>,
+        debug_entries: &mut Vec<DebugEntry>,
+    ) -> Result<LoopJumps, BytecodeError> {
+        ctx.loops.push(LoopJumps::default());
+        let result = self.emit_block(ctx, pou_id, body, code, debug_entries);
+        let jumps = ctx.loops.pop().unwrap_or_default();
+        result.map(|()| jumps)
+    }
+
+    fn patch_loop_jumps(
+        &self,
+        code: &mut [u8],
+        jumps: LoopJumps,
+        continue_target: usize,
+        exit_target: usize,
+    ) -> Result<(), BytecodeError> {
+        for jump in jumps.continues {
+            self.patch_jump(code, jump, continue_target)?;
+        }
+        for jump in jumps.exits {
+            self.patch_jump(code, jump, exit_target)?;
+        }
+        Ok(())
+    }
+
+    fn emit_return(
+        &mut self,
+        ctx: &CodegenContext,
+        expr: &Option<crate::eval::expr::Expr>,
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        if let Some(expr) = expr {
+            if !self.emit_expr(ctx, expr, code)? {
+                return Ok(false);
+            }
+            let return_ref = ctx
+                .return_name
+                .as_ref()
+                .and_then(|name| ctx.local_ref(name))
+                .cloned();
+            match return_ref {
+                Some(reference) => self.emit_store_ref(&reference, code)?,
+                None => code.push(0x12),
+            }
+        }
+        code.push(0x06);
+        Ok(true)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn emit_if_stmt(
         &mut self,
@@ -509,18 +788,6 @@ impl<'a> BytecodeEncoder<'a> {
     ) -> Result<bool, BytecodeError> {
         let code_start = code.len();
         let debug_start = debug_entries.len();
-        if !expr_supported(condition) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Ok(false);
-        }
-        for (cond, _) in else_if {
-            if !expr_supported(cond) {
-                code.truncate(code_start);
-                debug_entries.truncate(debug_start);
-                return Ok(false);
-            }
-        }
         if !self.emit_expr(ctx, condition, code)? {
             code.truncate(code_start);
             debug_entries.truncate(debug_start);
@@ -582,11 +849,6 @@ impl<'a> BytecodeEncoder<'a> {
     ) -> Result<bool, BytecodeError> {
         let code_start = code.len();
         let debug_start = debug_entries.len();
-        if !expr_supported(selector) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Ok(false);
-        }
         if !self.emit_expr(ctx, selector, code)? {
             code.truncate(code_start);
             debug_entries.truncate(debug_start);
@@ -668,11 +930,6 @@ impl<'a> BytecodeEncoder<'a> {
     ) -> Result<bool, BytecodeError> {
         let code_start = code.len();
         let debug_start = debug_entries.len();
-        if !expr_supported(condition) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Ok(false);
-        }
         let loop_start = code.len();
         if !self.emit_expr(ctx, condition, code)? {
             code.truncate(code_start);
@@ -680,15 +937,19 @@ impl<'a> BytecodeEncoder<'a> {
             return Ok(false);
         }
         let jump_false = self.emit_jump_placeholder(code, 0x04);
-        if let Err(err) = self.emit_block(ctx, pou_id, body, code, debug_entries) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Err(err);
-        }
+        let jumps = match self.emit_loop_body(ctx, pou_id, body, code, debug_entries) {
+            Ok(jumps) => jumps,
+            Err(err) => {
+                code.truncate(code_start);
+                debug_entries.truncate(debug_start);
+                return Err(err);
+            }
+        };
         let jump_back = self.emit_jump_placeholder(code, 0x02);
         self.patch_jump(code, jump_back, loop_start)?;
         let loop_end = code.len();
         self.patch_jump(code, jump_false, loop_end)?;
+        self.patch_loop_jumps(code, jumps, loop_start, loop_end)?;
         Ok(true)
     }
 
@@ -703,17 +964,16 @@ impl<'a> BytecodeEncoder<'a> {
     ) -> Result<bool, BytecodeError> {
         let code_start = code.len();
         let debug_start = debug_entries.len();
-        if !expr_supported(until) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Ok(false);
-        }
         let loop_start = code.len();
-        if let Err(err) = self.emit_block(ctx, pou_id, body, code, debug_entries) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Err(err);
-        }
+        let jumps = match self.emit_loop_body(ctx, pou_id, body, code, debug_entries) {
+            Ok(jumps) => jumps,
+            Err(err) => {
+                code.truncate(code_start);
+                debug_entries.truncate(debug_start);
+                return Err(err);
+            }
+        };
+        let until_start = code.len();
         if !self.emit_expr(ctx, until, code)? {
             code.truncate(code_start);
             debug_entries.truncate(debug_start);
@@ -721,6 +981,8 @@ impl<'a> BytecodeEncoder<'a> {
         }
         let jump_false = self.emit_jump_placeholder(code, 0x04);
         self.patch_jump(code, jump_false, loop_start)?;
+        let loop_end = code.len();
+        self.patch_loop_jumps(code, jumps, until_start, loop_end)?;
         Ok(true)
     }
 
@@ -739,16 +1001,7 @@ impl<'a> BytecodeEncoder<'a> {
     ) -> Result<bool, BytecodeError> {
         let code_start = code.len();
         let debug_start = debug_entries.len();
-        if !expr_supported(start) || !expr_supported(end) || !expr_supported(step) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Ok(false);
-        }
-        let Some((end_temp, step_temp)) = ctx.next_for_temp_pair() else {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Ok(false);
-        };
+        let (end_temp, step_temp) = self.next_for_temp_pair(ctx)?;
         let control_access = match self
             .resolve_lvalue_ref(ctx, &crate::eval::expr::LValue::Name(control.clone()))?
         {
@@ -823,11 +1076,15 @@ impl<'a> BytecodeEncoder<'a> {
 
         let body_start = code.len();
         self.patch_jump(code, jump_to_body, body_start)?;
-        if let Err(err) = self.emit_block(ctx, pou_id, body, code, debug_entries) {
-            code.truncate(code_start);
-            debug_entries.truncate(debug_start);
-            return Err(err);
-        }
+        let jumps = match self.emit_loop_body(ctx, pou_id, body, code, debug_entries) {
+            Ok(jumps) => jumps,
+            Err(err) => {
+                code.truncate(code_start);
+                debug_entries.truncate(debug_start);
+                return Err(err);
+            }
+        };
+        let increment = code.len();
         self.emit_load_access(&control_access, code)?;
         self.emit_load_ref(&step_ref, code)?;
         code.push(0x40);
@@ -838,17 +1095,18 @@ impl<'a> BytecodeEncoder<'a> {
         let loop_end = code.len();
         self.patch_jump(code, jump_false_end_neg, loop_end)?;
         self.patch_jump(code, jump_false_end_pos, loop_end)?;
+        self.patch_loop_jumps(code, jumps, increment, loop_end)?;
         Ok(true)
     }
 
-    fn emit_jump_placeholder(&self, code: &mut Vec<u8>, opcode: u8) -> usize {
+    pub(super) fn emit_jump_placeholder(&self, code: &mut Vec<u8>, opcode: u8) -> usize {
         let pos = code.len();
         code.push(opcode);
         code.extend_from_slice(&0i32.to_le_bytes());
         pos
     }
 
-    fn patch_jump(
+    pub(super) fn patch_jump(
         &self,
         code: &mut [u8],
         jump_pos: usize,
@@ -890,7 +1148,18 @@ impl<'a> BytecodeEncoder<'a> {
         Ok(())
     }
 
-    fn emit_const_value(
+    fn emit_push_ref(
+        &mut self,
+        reference: &ValueRef,
+        code: &mut Vec<u8>,
+    ) -> Result<(), BytecodeError> {
+        let ref_idx = self.ref_index_for(reference)?;
+        code.push(0x22);
+        code.extend_from_slice(&ref_idx.to_le_bytes());
+        Ok(())
+    }
+
+    pub(super) fn emit_const_value(
         &mut self,
         value: &Value,
         code: &mut Vec<u8>,
@@ -904,46 +1173,3 @@ impl<'a> BytecodeEncoder<'a> {
         Ok(true)
     }
 }
-
-fn expr_supported(expr: &crate::eval::expr::Expr) -> bool {
-    use crate::eval::expr::Expr;
-    use crate::eval::ops::{BinaryOp, UnaryOp};
-    match expr {
-        Expr::Literal(value) => {
-            if matches!(value, Value::String(_) | Value::WString(_)) {
-                return false;
-            }
-            type_id_for_value(value).is_some()
-        }
-        Expr::Name(_) => true,
-        Expr::Field { target, field: _ } => matches!(target.as_ref(), Expr::Name(_)),
-        Expr::Index { target, indices } => {
-            matches!(target.as_ref(), Expr::Name(_)) && indices.iter().all(expr_supported)
-        }
-        Expr::Unary { op, expr } => {
-            matches!(op, UnaryOp::Neg | UnaryOp::Not | UnaryOp::Pos) && expr_supported(expr)
-        }
-        Expr::Binary { op, left, right } => {
-            matches!(
-                op,
-                BinaryOp::Add
-                    | BinaryOp::Sub
-                    | BinaryOp::Mul
-                    | BinaryOp::Div
-                    | BinaryOp::Mod
-                    | BinaryOp::Pow
-                    | BinaryOp::And
-                    | BinaryOp::Or
-                    | BinaryOp::Xor
-                    | BinaryOp::Eq
-                    | BinaryOp::Ne
-                    | BinaryOp::Lt
-                    | BinaryOp::Le
-                    | BinaryOp::Gt
-                    | BinaryOp::Ge
-            ) && expr_supported(left)
-                && expr_supported(right)
-        }
-        _ => false,
-    }
-}
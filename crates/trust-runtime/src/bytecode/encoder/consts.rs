@@ -19,12 +19,21 @@ impl<'a> BytecodeEncoder<'a> {
                 .ok_or_else(|| BytecodeError::InvalidSection("unsupported const value".into()))?,
         };
         let type_idx = self.type_index(type_id)?;
-        let payload = encode_const_payload(value)?;
-        let idx = self.const_pool.len() as u32;
-        self.const_pool.push(ConstEntry {
+        let payload = match value {
+            Value::String(text) => self.strings.intern(text.clone()).to_le_bytes().to_vec(),
+            Value::WString(text) => self.strings.intern(text.as_str()).to_le_bytes().to_vec(),
+            _ => encode_const_payload(value)?,
+        };
+        let entry = ConstEntry {
             type_id: type_idx,
             payload,
-        });
+        };
+        if let Some(idx) = self.const_map.get(&entry) {
+            return Ok(*idx);
+        }
+        let idx = self.const_pool.len() as u32;
+        self.const_pool.push(entry.clone());
+        self.const_map.insert(entry, idx);
         Ok(idx)
     }
 
@@ -66,12 +75,7 @@ fn const_expr_supported(expr: &crate::eval::expr::Expr) -> bool {
     use crate::eval::expr::Expr;
     use crate::eval::ops::{BinaryOp, UnaryOp};
     match expr {
-        Expr::Literal(value) => {
-            if matches!(value, Value::String(_) | Value::WString(_)) {
-                return false;
-            }
-            type_id_for_value(value).is_some()
-        }
+        Expr::Literal(value) => type_id_for_value(value).is_some(),
         Expr::Unary { op, expr } => {
             matches!(op, UnaryOp::Neg | UnaryOp::Not | UnaryOp::Pos) && const_expr_supported(expr)
         }
@@ -130,6 +134,7 @@ pub(super) fn type_id_for_value(value: &Value) -> Option<TypeId> {
         Value::Dt(_) => Some(TypeId::DT),
         Value::Ldt(_) => Some(TypeId::LDT),
         Value::Enum(_) => Some(TypeId::INT),
+        Value::Null => Some(TypeId::NULL),
         _ => None,
     }
 }
@@ -154,16 +159,6 @@ fn encode_const_payload(value: &Value) -> Result<Vec<u8>, BytecodeError> {
         Value::LWord(v) => payload.extend_from_slice(&v.to_le_bytes()),
         Value::Char(v) => payload.extend_from_slice(&v.to_le_bytes()),
         Value::WChar(v) => payload.extend_from_slice(&v.to_le_bytes()),
-        Value::String(value) => {
-            return Err(BytecodeError::InvalidSection(
-                format!("string const not supported yet: {value}").into(),
-            ));
-        }
-        Value::WString(_) => {
-            return Err(BytecodeError::InvalidSection(
-                "wstring const not supported yet".into(),
-            ));
-        }
         Value::Time(value) | Value::LTime(value) => {
             payload.extend_from_slice(&value.as_nanos().to_le_bytes());
         }
@@ -188,6 +183,7 @@ fn encode_const_payload(value: &Value) -> Result<Vec<u8>, BytecodeError> {
         Value::Enum(value) => {
             payload.extend_from_slice(&value.numeric_value.to_le_bytes());
         }
+        Value::Null => payload.extend_from_slice(&u32::MAX.to_le_bytes()),
         _ => {
             return Err(BytecodeError::InvalidSection(
                 "unsupported const payload".into(),
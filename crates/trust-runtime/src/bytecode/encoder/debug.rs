@@ -1,8 +1,39 @@
 use smol_str::SmolStr;
 
+use crate::bytecode::DebugEntry;
+use crate::debug::SourceLocation;
+
+use super::util::to_u32;
 use super::{BytecodeEncoder, BytecodeError};
 
 impl<'a> BytecodeEncoder<'a> {
+    /// Record a statement boundary at `code_offset` when sources are known.
+    pub(super) fn push_debug_entry(
+        &mut self,
+        pou_id: u32,
+        code_offset: usize,
+        location: &SourceLocation,
+        debug_entries: &mut Vec<DebugEntry>,
+    ) -> Result<(), BytecodeError> {
+        let Some(sources) = self.sources else {
+            return Ok(());
+        };
+        let source = sources
+            .get(location.file_id as usize)
+            .ok_or_else(|| BytecodeError::InvalidSection("debug source missing".into()))?;
+        let (line, column) = crate::debug::location_to_line_col(source, location);
+        let file_idx = self.file_path_index(location.file_id)?;
+        debug_entries.push(DebugEntry {
+            pou_id,
+            code_offset: to_u32(code_offset, "debug code offset")?,
+            file_idx,
+            line: line.saturating_add(1),
+            column: column.saturating_add(1),
+            kind: 0,
+        });
+        Ok(())
+    }
+
     pub(super) fn file_path_index(&mut self, file_id: u32) -> Result<u32, BytecodeError> {
         if let Some(idx) = self.file_path_indices.get(&file_id) {
             return Ok(*idx);
@@ -0,0 +1,341 @@
+use smol_str::SmolStr;
+
+use crate::eval::{EvalContext, VarDef};
+use crate::memory::{InstanceId, MemoryLocation, VariableStorage};
+use crate::value::{Duration, Value};
+
+use crate::bytecode::{
+    InitImage, InitInstance, InitSlot, InitValue, PouDecl, PouDecls, VarDecl, VAR_FLAG_CONSTANT,
+    VAR_FLAG_EXTERNAL, VAR_FLAG_RUNTIME_INIT,
+};
+
+use super::{BytecodeEncoder, BytecodeError};
+
+impl<'a> BytecodeEncoder<'a> {
+    pub(super) fn build_pou_decls(&mut self) -> Result<PouDecls, BytecodeError> {
+        let runtime = self.runtime;
+        let image = runtime.initial_storage().unwrap_or(runtime.storage());
+        let instance_limit = image.next_instance_id();
+        // Instance variable initializers are evaluated once, on a throwaway
+        // instance created next to the initial image.
+        let mut scratch = image.image();
+        let mut entries = Vec::new();
+
+        for program in runtime.programs().values() {
+            let pou_id = self.decl_pou_id(self.pou_ids.program_id(&program.name))?;
+            let instance = crate::instance::create_program_instance(
+                &mut scratch,
+                runtime.registry(),
+                &runtime.profile(),
+                runtime.classes(),
+                runtime.function_blocks(),
+                runtime.functions(),
+                runtime.stdlib(),
+                program,
+            )
+            .ok();
+            let vars =
+                self.instance_var_decls(&scratch, instance, instance_limit, &program.vars)?;
+            let temps = self.local_var_decls(instance_limit, &program.temps)?;
+            let using = self.intern_names(&program.using);
+            entries.push(PouDecl {
+                pou_id,
+                using,
+                vars,
+                temps,
+            });
+        }
+        for fb in runtime.function_blocks().values() {
+            if self.is_stdlib_fb(&fb.name) {
+                continue;
+            }
+            let pou_id = self.decl_pou_id(self.pou_ids.function_block_id(&fb.name))?;
+            let instance = crate::instance::create_fb_instance(
+                &mut scratch,
+                runtime.registry(),
+                &runtime.profile(),
+                runtime.classes(),
+                runtime.function_blocks(),
+                runtime.functions(),
+                runtime.stdlib(),
+                fb,
+            )
+            .ok();
+            let vars = self.instance_var_decls(&scratch, instance, instance_limit, &fb.vars)?;
+            let temps = self.local_var_decls(instance_limit, &fb.temps)?;
+            let using = self.intern_names(&fb.using);
+            entries.push(PouDecl {
+                pou_id,
+                using,
+                vars,
+                temps,
+            });
+            for method in &fb.methods {
+                let pou_id = self.decl_pou_id(self.pou_ids.method_id(&fb.name, &method.name))?;
+                let vars = self.local_var_decls(instance_limit, &method.locals)?;
+                let using = self.intern_names(&method.using);
+                entries.push(PouDecl {
+                    pou_id,
+                    using,
+                    vars,
+                    temps: Vec::new(),
+                });
+            }
+        }
+        for func in runtime.functions().values() {
+            let pou_id = self.decl_pou_id(self.pou_ids.function_id(&func.name))?;
+            let vars = self.local_var_decls(instance_limit, &func.locals)?;
+            let using = self.intern_names(&func.using);
+            entries.push(PouDecl {
+                pou_id,
+                using,
+                vars,
+                temps: Vec::new(),
+            });
+        }
+        for class in runtime.classes().values() {
+            let pou_id = self.decl_pou_id(self.pou_ids.class_id(&class.name))?;
+            let instance = crate::instance::create_class_instance(
+                &mut scratch,
+                runtime.registry(),
+                &runtime.profile(),
+                runtime.classes(),
+                runtime.function_blocks(),
+                runtime.functions(),
+                runtime.stdlib(),
+                class,
+            )
+            .ok();
+            let vars = self.instance_var_decls(&scratch, instance, instance_limit, &class.vars)?;
+            let using = self.intern_names(&class.using);
+            entries.push(PouDecl {
+                pou_id,
+                using,
+                vars,
+                temps: Vec::new(),
+            });
+            for method in &class.methods {
+                let pou_id = self.decl_pou_id(self.pou_ids.method_id(&class.name, &method.name))?;
+                let vars = self.local_var_decls(instance_limit, &method.locals)?;
+                let using = self.intern_names(&method.using);
+                entries.push(PouDecl {
+                    pou_id,
+                    using,
+                    vars,
+                    temps: Vec::new(),
+                });
+            }
+        }
+        Ok(PouDecls { entries })
+    }
+
+    pub(super) fn build_init_image(&mut self) -> Result<InitImage, BytecodeError> {
+        let runtime = self.runtime;
+        let storage = runtime.initial_storage().unwrap_or(runtime.storage());
+        let mut globals = Vec::with_capacity(storage.globals().len());
+        for (name, value) in storage.globals() {
+            globals.push(self.init_slot(name, value)?);
+        }
+        let mut ids = storage.instances().keys().copied().collect::<Vec<_>>();
+        ids.sort_by_key(|id| id.0);
+        let mut instances = Vec::with_capacity(ids.len());
+        for id in ids {
+            let instance = &storage.instances()[&id];
+            let mut vars = Vec::with_capacity(instance.variables.len());
+            for (name, value) in &instance.variables {
+                vars.push(self.init_slot(name, value)?);
+            }
+            instances.push(InitInstance {
+                id: id.0,
+                type_name_idx: self.strings.intern(instance.type_name.clone()),
+                parent: instance.parent.map(|parent| parent.0),
+                vars,
+            });
+        }
+        let io_names = runtime
+            .io()
+            .bindings()
+            .iter()
+            .map(|binding| {
+                binding
+                    .display_name
+                    .as_ref()
+                    .map(|name| self.strings.intern(name.clone()))
+            })
+            .collect();
+        Ok(InitImage {
+            globals,
+            instances,
+            next_instance_id: storage.next_instance_id(),
+            io_names,
+        })
+    }
+
+    fn decl_pou_id(&self, id: Option<u32>) -> Result<u32, BytecodeError> {
+        id.ok_or_else(|| BytecodeError::InvalidSection("declaration for unknown POU".into()))
+    }
+
+    fn intern_names(&mut self, names: &[SmolStr]) -> Vec<u32> {
+        names
+            .iter()
+            .map(|name| self.strings.intern(name.clone()))
+            .collect()
+    }
+
+    /// Declarations of instance variables, with initializer values read back
+    /// from `instance`.
+    fn instance_var_decls(
+        &mut self,
+        scratch: &VariableStorage,
+        instance: Option<InstanceId>,
+        instance_limit: u32,
+        vars: &[VarDef],
+    ) -> Result<Vec<VarDecl>, BytecodeError> {
+        let mut decls = Vec::with_capacity(vars.len());
+        for var in vars {
+            let value = match (&var.initializer, instance) {
+                (Some(_), Some(id)) if !var.external => scratch.get_instance_var(id, &var.name),
+                _ => None,
+            };
+            decls.push(self.var_decl(var, value, instance_limit)?);
+        }
+        Ok(decls)
+    }
+
+    /// Declarations of locals and temporaries, whose initializers run on
+    /// every call; only initializers that evaluate without any program state
+    /// are stored.
+    fn local_var_decls(
+        &mut self,
+        instance_limit: u32,
+        vars: &[VarDef],
+    ) -> Result<Vec<VarDecl>, BytecodeError> {
+        let runtime = self.runtime;
+        let mut storage = VariableStorage::default();
+        storage.push_frame("INIT");
+        let mut decls = Vec::with_capacity(vars.len());
+        for var in vars {
+            let value = match &var.initializer {
+                Some(expr) if !var.external => {
+                    let mut ctx = EvalContext {
+                        storage: &mut storage,
+                        registry: runtime.registry(),
+                        profile: runtime.profile(),
+                        now: Duration::ZERO,
+                        debug: None,
+                        call_depth: 0,
+                        functions: None,
+                        stdlib: Some(runtime.stdlib()),
+                        function_blocks: None,
+                        classes: None,
+                        using: None,
+                        access: None,
+                        current_instance: None,
+                        return_name: None,
+                        loop_depth: 0,
+                        pause_requested: false,
+                        execution_deadline: None,
+                    };
+                    crate::eval::expr::eval_expr(&mut ctx, expr).ok()
+                }
+                _ => None,
+            };
+            if let Some(value) = &value {
+                storage.set_local(var.name.clone(), value.clone());
+            }
+            decls.push(self.var_decl(var, value.as_ref(), instance_limit)?);
+        }
+        Ok(decls)
+    }
+
+    fn var_decl(
+        &mut self,
+        var: &VarDef,
+        value: Option<&Value>,
+        instance_limit: u32,
+    ) -> Result<VarDecl, BytecodeError> {
+        let mut flags = match var.retain {
+            crate::RetainPolicy::Unspecified => 0,
+            crate::RetainPolicy::Retain => 1,
+            crate::RetainPolicy::NonRetain => 2,
+            crate::RetainPolicy::Persistent => 3,
+        };
+        if var.external {
+            flags |= VAR_FLAG_EXTERNAL;
+        }
+        if var.constant {
+            flags |= VAR_FLAG_CONSTANT;
+        }
+        let init = match value {
+            Some(value) if is_static_value(value, instance_limit) => Some(self.init_value(value)?),
+            _ => None,
+        };
+        if var.initializer.is_some() && !var.external && init.is_none() {
+            flags |= VAR_FLAG_RUNTIME_INIT;
+        }
+        Ok(VarDecl {
+            name_idx: self.strings.intern(var.name.clone()),
+            type_id: self.type_index(var.type_id)?,
+            flags,
+            init,
+        })
+    }
+
+    fn init_slot(&mut self, name: &SmolStr, value: &Value) -> Result<InitSlot, BytecodeError> {
+        Ok(InitSlot {
+            name_idx: self.strings.intern(name.clone()),
+            value: self.init_value(value)?,
+        })
+    }
+
+    fn init_value(&mut self, value: &Value) -> Result<InitValue, BytecodeError> {
+        Ok(match value {
+            Value::Array(array) => InitValue::Array {
+                dims: array.dimensions.clone(),
+                elements: array
+                    .elements
+                    .iter()
+                    .map(|element| self.init_value(element))
+                    .collect::<Result<_, _>>()?,
+            },
+            Value::Struct(value) => InitValue::Struct {
+                type_name_idx: self.strings.intern(value.type_name.clone()),
+                fields: value
+                    .fields
+                    .iter()
+                    .map(|(name, field)| self.init_slot(name, field))
+                    .collect::<Result<_, _>>()?,
+            },
+            Value::Reference(reference) => InitValue::Reference(
+                reference
+                    .as_ref()
+                    .map(|reference| self.ref_index_for(reference))
+                    .transpose()?,
+            ),
+            Value::Instance(id) => InitValue::Instance(id.0),
+            _ => InitValue::Const(self.const_index_for(value)?),
+        })
+    }
+}
+
+/// Whether `value` is the same for every instance: it holds no instances and
+/// no references into frames or into instances created after the image.
+fn is_static_value(value: &Value, instance_limit: u32) -> bool {
+    match value {
+        Value::Array(array) => array
+            .elements
+            .iter()
+            .all(|element| is_static_value(element, instance_limit)),
+        Value::Struct(value) => value
+            .fields
+            .values()
+            .all(|field| is_static_value(field, instance_limit)),
+        Value::Reference(Some(reference)) => match reference.location {
+            MemoryLocation::Local(_) => false,
+            MemoryLocation::Instance(id) => id.0 < instance_limit,
+            _ => true,
+        },
+        Value::Instance(_) => false,
+        _ => true,
+    }
+}
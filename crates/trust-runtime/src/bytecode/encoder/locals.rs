@@ -5,8 +5,8 @@ use smol_str::SmolStr;
 use crate::memory::{FrameId, MemoryLocation};
 use crate::value::ValueRef;
 
-use super::util::{count_for_loops, normalize_name};
-use super::{BytecodeEncoder, BytecodeError, LocalScope};
+use super::util::{count_for_loops, normalize_name, to_u32};
+use super::{BytecodeEncoder, BytecodeError, CodegenContext, LocalScope};
 use crate::eval::Param;
 use crate::eval::VarDef;
 
@@ -61,6 +61,45 @@ impl<'a> BytecodeEncoder<'a> {
         pairs
     }
 
+    /// Next FOR end/step temporaries of the body, extending the local frame
+    /// once the pairs reserved up front are used up.
+    pub(super) fn next_for_temp_pair(
+        &mut self,
+        ctx: &mut CodegenContext,
+    ) -> Result<(SmolStr, SmolStr), BytecodeError> {
+        if let Some(pair) = ctx.for_temp_pairs.get(ctx.next_for_temp).cloned() {
+            ctx.next_for_temp += 1;
+            return Ok(pair);
+        }
+        let mut used: HashSet<SmolStr> = ctx.locals.keys().map(normalize_name).collect();
+        let idx = ctx.for_temp_pairs.len();
+        let end_name = self.unique_temp_name("__st_rt_for_end", idx, &mut used);
+        let step_name = self.unique_temp_name("__st_rt_for_step", idx, &mut used);
+        let frame_id = match ctx.frame {
+            Some(frame_id) => frame_id,
+            None => {
+                let frame_id = self.alloc_local_frame_id();
+                ctx.frame = Some(frame_id);
+                ctx.local_ref_start = to_u32(self.ref_entries.len(), "local ref start")?;
+                frame_id
+            }
+        };
+        for name in [&end_name, &step_name] {
+            let value_ref = ValueRef {
+                location: MemoryLocation::Local(frame_id),
+                offset: ctx.local_ref_count as usize,
+                path: Vec::new(),
+            };
+            self.ref_index_for(&value_ref)?;
+            ctx.locals.insert(name.clone(), value_ref);
+            ctx.local_ref_count = ctx.local_ref_count.saturating_add(1);
+        }
+        let pair = (end_name, step_name);
+        ctx.for_temp_pairs.push(pair.clone());
+        ctx.next_for_temp += 1;
+        Ok(pair)
+    }
+
     fn unique_temp_name(&self, prefix: &str, idx: usize, used: &mut HashSet<SmolStr>) -> SmolStr {
         let mut attempt = 0usize;
         loop {
@@ -87,6 +126,7 @@ impl<'a> BytecodeEncoder<'a> {
         if names.is_empty() {
             return Ok(LocalScope {
                 locals: HashMap::new(),
+                frame: None,
                 local_ref_start,
                 local_ref_count: 0,
                 for_temp_pairs,
@@ -117,6 +157,7 @@ impl<'a> BytecodeEncoder<'a> {
             .saturating_sub(local_ref_start as usize) as u32;
         Ok(LocalScope {
             locals,
+            frame: Some(frame_id),
             local_ref_start,
             local_ref_count,
             for_temp_pairs,
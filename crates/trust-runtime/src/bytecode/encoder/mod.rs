@@ -2,21 +2,25 @@
 
 #![allow(missing_docs)]
 
+mod calls;
 mod codegen;
 mod consts;
 mod debug;
+mod decls;
 mod io;
 mod locals;
 mod pou;
 mod refs;
+mod sfc;
 mod types;
+mod typing;
 mod util;
 
 use std::collections::{HashMap, HashSet};
 
 use smol_str::SmolStr;
 
-use crate::memory::InstanceId;
+use crate::memory::{FrameId, InstanceId};
 use crate::value::ValueRef;
 use trust_hir::TypeId;
 
@@ -193,6 +197,7 @@ struct BytecodeEncoder<'a> {
     types: Vec<TypeEntry>,
     type_map: HashMap<TypeId, u32>,
     const_pool: Vec<ConstEntry>,
+    const_map: HashMap<ConstEntry, u32>,
     ref_entries: Vec<RefEntry>,
     ref_map: HashMap<ValueRef, u32>,
     next_local_frame_id: u32,
@@ -202,33 +207,90 @@ struct BytecodeEncoder<'a> {
     method_stack: Vec<SmolStr>,
     interface_tables: HashMap<SmolStr, Vec<InterfaceMethod>>,
     interface_stack: Vec<SmolStr>,
+    global_types: HashMap<SmolStr, TypeId>,
 }
 
 #[derive(Clone, Default)]
 struct CodegenContext {
     instance_id: Option<InstanceId>,
     locals: HashMap<SmolStr, ValueRef>,
+    /// Local frame of the body; created on demand for extra FOR temporaries.
+    frame: Option<FrameId>,
+    local_ref_start: u32,
+    /// Slots in the local frame.
+    local_ref_count: u32,
     self_fields: HashMap<SmolStr, SmolStr>,
     for_temp_pairs: Vec<(SmolStr, SmolStr)>,
     next_for_temp: usize,
+    /// POU being emitted, named in lowering errors.
+    pou_name: SmolStr,
+    /// FUNCTION_BLOCK or CLASS whose instance is `THIS` in the body.
+    owner: Option<SmolStr>,
+    using: Vec<SmolStr>,
+    /// Declared types of locals, parameters and program variables.
+    var_types: HashMap<SmolStr, TypeId>,
+    /// Return variable of a FUNCTION or METHOD body.
+    return_name: Option<SmolStr>,
+    loops: Vec<LoopJumps>,
+    labels: Vec<LabelScope>,
+}
+
+/// Pending EXIT/CONTINUE jumps of a loop being emitted.
+#[derive(Clone, Default)]
+struct LoopJumps {
+    exits: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+/// Labels declared in a statement list and the JMPs waiting for them.
+#[derive(Clone, Default)]
+struct LabelScope {
+    positions: HashMap<SmolStr, Option<usize>>,
+    pending: Vec<(usize, SmolStr)>,
 }
 
 impl CodegenContext {
     fn new(
         instance_id: Option<InstanceId>,
-        locals: HashMap<SmolStr, ValueRef>,
+        scope: LocalScope,
         self_fields: HashMap<SmolStr, SmolStr>,
-        for_temp_pairs: Vec<(SmolStr, SmolStr)>,
     ) -> Self {
         Self {
             instance_id,
-            locals,
+            locals: scope.locals,
+            frame: scope.frame,
+            local_ref_start: scope.local_ref_start,
+            local_ref_count: scope.local_ref_count,
             self_fields,
-            for_temp_pairs,
+            for_temp_pairs: scope.for_temp_pairs,
             next_for_temp: 0,
+            pou_name: SmolStr::default(),
+            owner: None,
+            using: Vec::new(),
+            var_types: HashMap::new(),
+            return_name: None,
+            loops: Vec::new(),
+            labels: Vec::new(),
         }
     }
 
+    fn with_types(
+        mut self,
+        owner: Option<SmolStr>,
+        using: Vec<SmolStr>,
+        var_types: HashMap<SmolStr, TypeId>,
+    ) -> Self {
+        self.owner = owner;
+        self.using = using;
+        self.var_types = var_types;
+        self
+    }
+
+    fn with_return_name(mut self, return_name: Option<SmolStr>) -> Self {
+        self.return_name = return_name;
+        self
+    }
+
     fn local_ref(&self, name: &SmolStr) -> Option<&ValueRef> {
         self.locals.get(name)
     }
@@ -237,18 +299,11 @@ impl CodegenContext {
         let key = normalize_name(name);
         self.self_fields.get(&key)
     }
-
-    fn next_for_temp_pair(&mut self) -> Option<(SmolStr, SmolStr)> {
-        let pair = self.for_temp_pairs.get(self.next_for_temp).cloned();
-        if pair.is_some() {
-            self.next_for_temp += 1;
-        }
-        pair
-    }
 }
 
 struct LocalScope {
     locals: HashMap<SmolStr, ValueRef>,
+    frame: Option<FrameId>,
     local_ref_start: u32,
     local_ref_count: u32,
     for_temp_pairs: Vec<(SmolStr, SmolStr)>,
@@ -266,6 +321,11 @@ impl<'a> BytecodeEncoder<'a> {
             .into_iter()
             .map(|fb| normalize_name(&fb.name))
             .collect();
+        let global_types = runtime
+            .globals()
+            .iter()
+            .map(|(name, meta)| (normalize_name(name), meta.type_id))
+            .collect();
         Self {
             runtime,
             sources: None,
@@ -276,6 +336,7 @@ impl<'a> BytecodeEncoder<'a> {
             types: Vec::new(),
             type_map: HashMap::new(),
             const_pool: Vec::new(),
+            const_map: HashMap::new(),
             ref_entries: Vec::new(),
             ref_map: HashMap::new(),
             next_local_frame_id: 0,
@@ -285,6 +346,7 @@ impl<'a> BytecodeEncoder<'a> {
             method_stack: Vec::new(),
             interface_tables: HashMap::new(),
             interface_stack: Vec::new(),
+            global_types,
         }
     }
 
@@ -311,6 +373,12 @@ impl<'a> BytecodeEncoder<'a> {
         let io_map = self.build_io_map()?;
         let var_meta = self.build_var_meta()?;
         let retain_init = self.build_retain_init(&var_meta)?;
+        // Loading without sources needs both sections; a program whose initial
+        // state the module cannot express still encodes, just without them.
+        let decls = match (self.build_pou_decls(), self.build_init_image()) {
+            (Ok(pou_decls), Ok(init_image)) => Some((pou_decls, init_image)),
+            _ => None,
+        };
         let type_offsets = compute_type_offsets_for_entries(&self.types);
         let type_table = TypeTable {
             offsets: type_offsets,
@@ -381,6 +449,18 @@ impl<'a> BytecodeEncoder<'a> {
                 data: SectionData::RetainInit(retain_init),
             });
         }
+        if let Some((pou_decls, init_image)) = decls {
+            sections.push(Section {
+                id: SectionId::PouDecls.as_raw(),
+                flags: 0,
+                data: SectionData::PouDecls(pou_decls),
+            });
+            sections.push(Section {
+                id: SectionId::InitImage.as_raw(),
+                flags: 0,
+                data: SectionData::InitImage(init_image),
+            });
+        }
         if !debug_entries.is_empty() {
             sections.push(Section {
                 id: SectionId::DebugStringTable.as_raw(),
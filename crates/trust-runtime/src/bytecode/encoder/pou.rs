@@ -2,7 +2,9 @@ use std::collections::{HashMap, HashSet};
 
 use smol_str::SmolStr;
 
-use crate::eval::{ClassDef, FunctionBlockDef, FunctionDef, MethodDef, Param};
+use crate::eval::{
+    function_return_name, ClassDef, FunctionBlockDef, FunctionDef, MethodDef, Param,
+};
 use crate::value::Value;
 use trust_hir::symbols::ParamDirection;
 
@@ -11,7 +13,7 @@ use crate::bytecode::{
 };
 
 use super::util::{normalize_name, to_u32};
-use super::{BytecodeEncoder, BytecodeError, CodegenContext, LocalScope};
+use super::{BytecodeEncoder, BytecodeError, CodegenContext};
 
 impl<'a> BytecodeEncoder<'a> {
     pub(super) fn build_pou_index_and_bodies(
@@ -38,14 +40,15 @@ impl<'a> BytecodeEncoder<'a> {
                 Some(Value::Instance(id)) => Some(*id),
                 _ => None,
             };
-            let LocalScope {
-                locals,
-                local_ref_start,
-                local_ref_count,
-                for_temp_pairs,
-            } = self.local_scope_for_body(None, &[], &program.temps, &program.body)?;
-            let mut ctx = CodegenContext::new(instance_id, locals, HashMap::new(), for_temp_pairs);
-            let (code, mut local_debug) = self.emit_pou_body(&mut ctx, id, &program.body)?;
+            let scope = self.local_scope_for_body(None, &[], &program.temps, &program.body)?;
+            let var_types = self.var_types_for(None, &[], &[&program.vars, &program.temps]);
+            let mut ctx = CodegenContext::new(instance_id, scope, HashMap::new()).with_types(
+                None,
+                program.using.clone(),
+                var_types,
+            );
+            let (code, mut local_debug) =
+                self.emit_pou_body(&mut ctx, id, &program.name, &program.body)?;
             let code_offset = to_u32(offset, "POU code offset")?;
             let code_length = to_u32(code.len(), "POU code length")?;
             for entry in &mut local_debug {
@@ -57,8 +60,8 @@ impl<'a> BytecodeEncoder<'a> {
             let mut entry = self.pou_entry_program(program, id)?;
             entry.code_offset = code_offset;
             entry.code_length = code_length;
-            entry.local_ref_start = local_ref_start;
-            entry.local_ref_count = local_ref_count;
+            entry.local_ref_start = ctx.local_ref_start;
+            entry.local_ref_count = ctx.local_ref_count;
             entries.push(entry);
             debug_entries.extend(local_debug);
             bodies.extend_from_slice(&code);
@@ -71,15 +74,15 @@ impl<'a> BytecodeEncoder<'a> {
                 .pou_ids
                 .function_block_id(&fb.name)
                 .ok_or_else(|| BytecodeError::InvalidSection("function block id missing".into()))?;
-            let LocalScope {
-                locals,
-                local_ref_start,
-                local_ref_count,
-                for_temp_pairs,
-            } = self.local_scope_for_body(None, &[], &fb.temps, &fb.body)?;
+            let scope = self.local_scope_for_body(None, &[], &fb.temps, &fb.body)?;
             let self_fields = self.self_fields_for_owner(&fb.name)?;
-            let mut ctx = CodegenContext::new(None, locals, self_fields, for_temp_pairs);
-            let (code, mut local_debug) = self.emit_pou_body(&mut ctx, id, &fb.body)?;
+            let var_types = self.var_types_for(None, &[], &[&fb.temps]);
+            let mut ctx = CodegenContext::new(None, scope, self_fields).with_types(
+                Some(fb.name.clone()),
+                fb.using.clone(),
+                var_types,
+            );
+            let (code, mut local_debug) = self.emit_pou_body(&mut ctx, id, &fb.name, &fb.body)?;
             let code_offset = to_u32(offset, "POU code offset")?;
             let code_length = to_u32(code.len(), "POU code length")?;
             for entry in &mut local_debug {
@@ -95,8 +98,8 @@ impl<'a> BytecodeEncoder<'a> {
             };
             entry.code_offset = code_offset;
             entry.code_length = code_length;
-            entry.local_ref_start = local_ref_start;
-            entry.local_ref_count = local_ref_count;
+            entry.local_ref_start = ctx.local_ref_start;
+            entry.local_ref_count = ctx.local_ref_count;
             entries.push(entry);
             debug_entries.extend(local_debug);
             bodies.extend_from_slice(&code);
@@ -109,19 +112,23 @@ impl<'a> BytecodeEncoder<'a> {
                 .pou_ids
                 .function_id(&func.name)
                 .ok_or_else(|| BytecodeError::InvalidSection("function id missing".into()))?;
-            let LocalScope {
-                locals,
-                local_ref_start,
-                local_ref_count,
-                for_temp_pairs,
-            } = self.local_scope_for_body(
-                Some(&func.name),
+            let return_name = function_return_name(&func.name);
+            let scope = self.local_scope_for_body(
+                Some(&return_name),
                 &func.params,
                 &func.locals,
                 &func.body,
             )?;
-            let mut ctx = CodegenContext::new(None, locals, HashMap::new(), for_temp_pairs);
-            let (code, mut local_debug) = self.emit_pou_body(&mut ctx, id, &func.body)?;
+            let var_types = self.var_types_for(
+                Some((&return_name, func.return_type)),
+                &func.params,
+                &[&func.locals],
+            );
+            let mut ctx = CodegenContext::new(None, scope, HashMap::new())
+                .with_types(None, func.using.clone(), var_types)
+                .with_return_name(Some(return_name));
+            let (code, mut local_debug) =
+                self.emit_pou_body(&mut ctx, id, &func.name, &func.body)?;
             let code_offset = to_u32(offset, "POU code offset")?;
             let code_length = to_u32(code.len(), "POU code length")?;
             for entry in &mut local_debug {
@@ -133,8 +140,8 @@ impl<'a> BytecodeEncoder<'a> {
             let mut entry = self.pou_entry_function(func, id)?;
             entry.code_offset = code_offset;
             entry.code_length = code_length;
-            entry.local_ref_start = local_ref_start;
-            entry.local_ref_count = local_ref_count;
+            entry.local_ref_start = ctx.local_ref_start;
+            entry.local_ref_count = ctx.local_ref_count;
             entries.push(entry);
             debug_entries.extend(local_debug);
             bodies.extend_from_slice(&code);
@@ -162,20 +169,23 @@ impl<'a> BytecodeEncoder<'a> {
                     .pou_ids
                     .method_id(owner, &method.name)
                     .ok_or_else(|| BytecodeError::InvalidSection("method id missing".into()))?;
-                let LocalScope {
-                    locals,
-                    local_ref_start,
-                    local_ref_count,
-                    for_temp_pairs,
-                } = self.local_scope_for_body(
+                let scope = self.local_scope_for_body(
                     method.return_type.as_ref().map(|_| &method.name),
                     &method.params,
                     &method.locals,
                     &method.body,
                 )?;
                 let self_fields = self.self_fields_for_owner(owner)?;
-                let mut ctx = CodegenContext::new(None, locals, self_fields, for_temp_pairs);
-                let (code, mut local_debug) = self.emit_pou_body(&mut ctx, id, &method.body)?;
+                let var_types = self.var_types_for(
+                    method.return_type.map(|type_id| (&method.name, type_id)),
+                    &method.params,
+                    &[&method.locals],
+                );
+                let mut ctx = CodegenContext::new(None, scope, self_fields)
+                    .with_types(Some(owner.clone()), method.using.clone(), var_types)
+                    .with_return_name(method.return_type.map(|_| method.name.clone()));
+                let (code, mut local_debug) =
+                    self.emit_pou_body(&mut ctx, id, &method.name, &method.body)?;
                 let code_offset = to_u32(offset, "POU code offset")?;
                 let code_length = to_u32(code.len(), "POU code length")?;
                 for entry in &mut local_debug {
@@ -187,8 +197,8 @@ impl<'a> BytecodeEncoder<'a> {
                 let mut entry = self.pou_entry_method(method, owner_id, id)?;
                 entry.code_offset = code_offset;
                 entry.code_length = code_length;
-                entry.local_ref_start = local_ref_start;
-                entry.local_ref_count = local_ref_count;
+                entry.local_ref_start = ctx.local_ref_start;
+                entry.local_ref_count = ctx.local_ref_count;
                 entries.push(entry);
                 debug_entries.extend(local_debug);
                 bodies.extend_from_slice(&code);
@@ -207,20 +217,23 @@ impl<'a> BytecodeEncoder<'a> {
                     .pou_ids
                     .method_id(owner, &method.name)
                     .ok_or_else(|| BytecodeError::InvalidSection("method id missing".into()))?;
-                let LocalScope {
-                    locals,
-                    local_ref_start,
-                    local_ref_count,
-                    for_temp_pairs,
-                } = self.local_scope_for_body(
+                let scope = self.local_scope_for_body(
                     method.return_type.as_ref().map(|_| &method.name),
                     &method.params,
                     &method.locals,
                     &method.body,
                 )?;
                 let self_fields = self.self_fields_for_owner(owner)?;
-                let mut ctx = CodegenContext::new(None, locals, self_fields, for_temp_pairs);
-                let (code, mut local_debug) = self.emit_pou_body(&mut ctx, id, &method.body)?;
+                let var_types = self.var_types_for(
+                    method.return_type.map(|type_id| (&method.name, type_id)),
+                    &method.params,
+                    &[&method.locals],
+                );
+                let mut ctx = CodegenContext::new(None, scope, self_fields)
+                    .with_types(Some(owner.clone()), method.using.clone(), var_types)
+                    .with_return_name(method.return_type.map(|_| method.name.clone()));
+                let (code, mut local_debug) =
+                    self.emit_pou_body(&mut ctx, id, &method.name, &method.body)?;
                 let code_offset = to_u32(offset, "POU code offset")?;
                 let code_length = to_u32(code.len(), "POU code length")?;
                 for entry in &mut local_debug {
@@ -232,8 +245,8 @@ impl<'a> BytecodeEncoder<'a> {
                 let mut entry = self.pou_entry_method(method, owner_id, id)?;
                 entry.code_offset = code_offset;
                 entry.code_length = code_length;
-                entry.local_ref_start = local_ref_start;
-                entry.local_ref_count = local_ref_count;
+                entry.local_ref_start = ctx.local_ref_start;
+                entry.local_ref_count = ctx.local_ref_count;
                 entries.push(entry);
                 debug_entries.extend(local_debug);
                 bodies.extend_from_slice(&code);
@@ -256,6 +269,7 @@ impl<'a> BytecodeEncoder<'a> {
             id,
             name_idx,
             kind: PouKind::Program,
+            flags: 0,
             code_offset: 0,
             code_length: 0,
             local_ref_start: 0,
@@ -279,6 +293,7 @@ impl<'a> BytecodeEncoder<'a> {
             id,
             name_idx,
             kind: PouKind::Function,
+            flags: 0,
             code_offset: 0,
             code_length: 0,
             local_ref_start: 0,
@@ -307,6 +322,7 @@ impl<'a> BytecodeEncoder<'a> {
             id,
             name_idx,
             kind: PouKind::FunctionBlock,
+            flags: 0,
             code_offset: 0,
             code_length: 0,
             local_ref_start: 0,
@@ -325,6 +341,7 @@ impl<'a> BytecodeEncoder<'a> {
             id,
             name_idx,
             kind: PouKind::Class,
+            flags: 0,
             code_offset: 0,
             code_length: 0,
             local_ref_start: 0,
@@ -352,6 +369,7 @@ impl<'a> BytecodeEncoder<'a> {
             id,
             name_idx,
             kind: PouKind::Method,
+            flags: 0,
             code_offset: 0,
             code_length: 0,
             local_ref_start: 0,
@@ -420,7 +438,10 @@ impl<'a> BytecodeEncoder<'a> {
         })
     }
 
-    fn method_table_for(&mut self, owner: &SmolStr) -> Result<Vec<MethodEntry>, BytecodeError> {
+    pub(super) fn method_table_for(
+        &mut self,
+        owner: &SmolStr,
+    ) -> Result<Vec<MethodEntry>, BytecodeError> {
         let key = normalize_name(owner);
         if let Some(existing) = self.method_tables.get(&key) {
             return Ok(existing.clone());
@@ -549,7 +570,7 @@ impl<'a> BytecodeEncoder<'a> {
         Ok(table)
     }
 
-    fn class_like_def(&self, key: &SmolStr) -> Option<ClassLikeDef<'_>> {
+    pub(super) fn class_like_def(&self, key: &SmolStr) -> Option<ClassLikeDef<'_>> {
         if let Some(fb) = self.runtime.function_blocks().get(key) {
             Some(ClassLikeDef::FunctionBlock(fb))
         } else {
@@ -633,20 +654,20 @@ impl ClassLike for ClassDef {
     }
 }
 
-enum ClassLikeDef<'a> {
+pub(super) enum ClassLikeDef<'a> {
     FunctionBlock(&'a FunctionBlockDef),
     Class(&'a ClassDef),
 }
 
 impl<'a> ClassLikeDef<'a> {
-    fn base_name(&self) -> Option<SmolStr> {
+    pub(super) fn base_name(&self) -> Option<SmolStr> {
         match self {
             ClassLikeDef::FunctionBlock(def) => def.base_name(),
             ClassLikeDef::Class(def) => def.base_name(),
         }
     }
 
-    fn methods(&self) -> &[MethodDef] {
+    pub(super) fn methods(&self) -> &[MethodDef] {
         match self {
             ClassLikeDef::FunctionBlock(def) => def.methods(),
             ClassLikeDef::Class(def) => def.methods(),
@@ -0,0 +1,147 @@
+use crate::bytecode::sfc::{encode_chart, CodeSpan, SfcBodySpan, SfcCodeChart, OP_SFC_CHART};
+use crate::bytecode::DebugEntry;
+use crate::eval::expr::{Expr, LValue};
+use crate::eval::sfc::{
+    SfcAction, SfcActionBody, SfcAssociation, SfcChart, SfcStep, SfcTransition,
+};
+
+use super::typing::lvalue_expr;
+use super::util::to_u32;
+use super::{BytecodeEncoder, BytecodeError, CodegenContext};
+
+impl<'a> BytecodeEncoder<'a> {
+    /// Emit an SFC network as spans followed by an `SFC_CHART` instruction.
+    ///
+    /// Layout: `JMP chart; <spans>; chart: SFC_CHART len <descriptor>`. The
+    /// spans only run when the VM evaluates the chart.
+    pub(super) fn emit_sfc_chart(
+        &mut self,
+        ctx: &mut CodegenContext,
+        pou_id: u32,
+        chart: &SfcChart,
+        code: &mut Vec<u8>,
+        debug_entries: &mut Vec<DebugEntry>,
+    ) -> Result<bool, BytecodeError> {
+        let skip = self.emit_jump_placeholder(code, 0x02);
+        let mut lowered = SfcCodeChart::default();
+
+        for step in &chart.steps {
+            let mut associations = Vec::with_capacity(step.associations.len());
+            for association in &step.associations {
+                let duration = match &association.duration {
+                    Some(expr) => match self.emit_sfc_expr(ctx, expr, code)? {
+                        Some(span) => Some(span),
+                        None => return Ok(false),
+                    },
+                    None => None,
+                };
+                associations.push(SfcAssociation {
+                    action: association.action,
+                    qualifier: association.qualifier,
+                    duration,
+                });
+            }
+            lowered.steps.push(SfcStep {
+                name: step.name.clone(),
+                initial: step.initial,
+                associations,
+            });
+        }
+
+        for action in &chart.actions {
+            let start = code.len();
+            let body = match &action.body {
+                SfcActionBody::Statements(body) => {
+                    self.emit_block(ctx, pou_id, body, code, debug_entries)?;
+                    SfcBodySpan::Statements(span(start, code.len())?)
+                }
+                SfcActionBody::Variable(name) => {
+                    if !self.emit_sfc_flag_store(ctx, &LValue::Name(name.clone()), code)? {
+                        return Ok(false);
+                    }
+                    SfcBodySpan::Flag(span(start, code.len())?)
+                }
+            };
+            lowered.actions.push(SfcAction {
+                name: action.name.clone(),
+                body,
+            });
+        }
+
+        for transition in &chart.transitions {
+            let start = code.len();
+            if let Some(location) = &transition.location {
+                self.push_debug_entry(pou_id, start, location, debug_entries)?;
+            }
+            let Some(condition) = self.emit_sfc_expr(ctx, &transition.condition, code)? else {
+                return Ok(false);
+            };
+            let priority = match &transition.priority {
+                Some(expr) => match self.emit_sfc_expr(ctx, expr, code)? {
+                    Some(span) => Some(span),
+                    None => return Ok(false),
+                },
+                None => None,
+            };
+            lowered.transitions.push(SfcTransition {
+                from: transition.from.clone(),
+                to: transition.to.clone(),
+                condition,
+                priority,
+                location: None,
+            });
+        }
+
+        let chart_start = code.len();
+        self.patch_jump(code, skip, chart_start)?;
+        let descriptor = encode_chart(&lowered, |name| self.strings.intern(name.clone()));
+        code.push(OP_SFC_CHART);
+        code.extend_from_slice(&to_u32(descriptor.len(), "SFC chart length")?.to_le_bytes());
+        code.extend_from_slice(&descriptor);
+        Ok(true)
+    }
+
+    fn emit_sfc_expr(
+        &mut self,
+        ctx: &CodegenContext,
+        expr: &Expr,
+        code: &mut Vec<u8>,
+    ) -> Result<Option<CodeSpan>, BytecodeError> {
+        let start = code.len();
+        if !self.emit_expr(ctx, expr, code)? {
+            return Ok(None);
+        }
+        span(start, code.len()).map(Some)
+    }
+
+    /// Store the `Q` flag the VM pushes before running the span.
+    fn emit_sfc_flag_store(
+        &mut self,
+        ctx: &CodegenContext,
+        target: &LValue,
+        code: &mut Vec<u8>,
+    ) -> Result<bool, BytecodeError> {
+        let start = code.len();
+        if self.lvalue_is_self_field(ctx, target) {
+            if !self.emit_dynamic_ref_for_lvalue(ctx, target, code)? {
+                code.truncate(start);
+                return Ok(false);
+            }
+        } else if let Some(reference) = self.resolve_lvalue_ref(ctx, target)? {
+            let ref_idx = self.ref_index_for(&reference)?;
+            code.push(0x21);
+            code.extend_from_slice(&ref_idx.to_le_bytes());
+            return Ok(true);
+        } else if !self.emit_place(ctx, &lvalue_expr(target), code)? {
+            code.truncate(start);
+            return Ok(false);
+        }
+        code.push(0x13); // SWAP
+        code.push(0x33); // STORE
+        Ok(true)
+    }
+}
+
+fn span(start: usize, end: usize) -> Result<CodeSpan, BytecodeError> {
+    Ok(to_u32(start, "SFC span")?..to_u32(end, "SFC span")?)
+}
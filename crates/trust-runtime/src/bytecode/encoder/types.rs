@@ -105,11 +105,19 @@ impl<'a> BytecodeEncoder<'a> {
         type_id: TypeId,
         ty: &Type,
     ) -> Result<TypeEntry, BytecodeError> {
-        let name_idx = self
-            .runtime
-            .registry()
-            .type_name(type_id)
-            .map(|name| self.strings.intern(name));
+        // Named types keep their declared spelling so values decoded from the
+        // module carry the same type names as the runtime's own.
+        let name = match ty {
+            Type::Struct { name, .. }
+            | Type::Union { name, .. }
+            | Type::Enum { name, .. }
+            | Type::Alias { name, .. }
+            | Type::FunctionBlock { name }
+            | Type::Class { name }
+            | Type::Interface { name } => Some(name.clone()),
+            _ => self.runtime.registry().type_name(type_id),
+        };
+        let name_idx = name.map(|name| self.strings.intern(name));
         let (kind, data) = match ty {
             Type::Bool => (
                 TypeKind::Primitive,
@@ -399,6 +407,11 @@ impl<'a> BytecodeEncoder<'a> {
                 let methods = self.interface_methods_for(name)?;
                 (TypeKind::Interface, TypeData::Interface { methods })
             }
+            Type::Null => {
+                // NULL constants are encoded as a reference that targets itself.
+                let target_type_id = self.type_index(type_id)?;
+                (TypeKind::Reference, TypeData::Reference { target_type_id })
+            }
             Type::Pointer { .. } => {
                 return Err(BytecodeError::InvalidSection(
                     "unsupported pointer type".into(),
@@ -406,7 +419,6 @@ impl<'a> BytecodeEncoder<'a> {
             }
             Type::Unknown
             | Type::Void
-            | Type::Null
             | Type::Any
             | Type::AnyDerived
             | Type::AnyElementary
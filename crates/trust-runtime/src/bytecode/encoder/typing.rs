@@ -0,0 +1,297 @@
+use std::collections::{HashMap, HashSet};
+
+use smol_str::SmolStr;
+
+use crate::eval::expr::{Expr, LValue};
+use crate::eval::{Param, VarDef};
+use crate::stdlib::{conversions, time};
+use trust_hir::{Type, TypeId};
+
+use super::consts::type_id_for_value;
+use super::pou::ClassLikeDef;
+use super::util::normalize_name;
+use super::{BytecodeEncoder, CodegenContext};
+
+/// Callee of a call expression, resolved in the evaluator's lookup order.
+pub(super) enum CallTarget {
+    Ref,
+    Split(SmolStr),
+    Function(SmolStr),
+    Std(SmolStr),
+    Method { owner: SmolStr, name: SmolStr },
+    FunctionBlock(SmolStr),
+}
+
+impl<'a> BytecodeEncoder<'a> {
+    pub(super) fn var_types_for(
+        &self,
+        return_var: Option<(&SmolStr, TypeId)>,
+        params: &[Param],
+        vars: &[&[VarDef]],
+    ) -> HashMap<SmolStr, TypeId> {
+        let mut types = HashMap::new();
+        if let Some((name, type_id)) = return_var {
+            types.insert(normalize_name(name), type_id);
+        }
+        for param in params {
+            types.insert(normalize_name(&param.name), param.type_id);
+        }
+        for var in vars.iter().flat_map(|vars| vars.iter()) {
+            types
+                .entry(normalize_name(&var.name))
+                .or_insert(var.type_id);
+        }
+        types
+    }
+
+    /// Static type of an expression, when it can be determined at encode time.
+    pub(super) fn expr_type(&self, ctx: &CodegenContext, expr: &Expr) -> Option<TypeId> {
+        use crate::eval::ops::BinaryOp;
+        match expr {
+            Expr::Literal(crate::value::Value::Enum(value)) => {
+                self.runtime.registry().lookup(&value.type_name)
+            }
+            Expr::Literal(value) => type_id_for_value(value),
+            Expr::Name(name) => self.name_type(ctx, name),
+            Expr::Field { target, field } => {
+                let target_type = self.expr_type(ctx, target)?;
+                self.field_type(target_type, field)
+            }
+            Expr::Index { target, .. } => match self.resolved_type(self.expr_type(ctx, target)?)? {
+                Type::Array { element, .. } => Some(*element),
+                _ => None,
+            },
+            Expr::Deref(target) => match self.resolved_type(self.expr_type(ctx, target)?)? {
+                Type::Reference { target } | Type::Pointer { target } => Some(*target),
+                _ => None,
+            },
+            Expr::This => ctx
+                .owner
+                .as_ref()
+                .and_then(|owner| self.runtime.registry().lookup(owner)),
+            Expr::Super => {
+                let owner = ctx.owner.as_ref()?;
+                let base = self.class_like_def(&normalize_name(owner))?.base_name()?;
+                self.runtime.registry().lookup(&base)
+            }
+            Expr::SizeOf(_) => Some(TypeId::DINT),
+            Expr::Unary { expr, .. } => self.expr_type(ctx, expr),
+            Expr::Binary { op, left, .. } => match op {
+                BinaryOp::Eq
+                | BinaryOp::Ne
+                | BinaryOp::Lt
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Ge => Some(TypeId::BOOL),
+                _ => self.expr_type(ctx, left),
+            },
+            Expr::Call { target, .. } => match self.call_target(ctx, target)? {
+                CallTarget::Function(key) => Some(self.runtime.functions().get(&key)?.return_type),
+                CallTarget::Method { owner, name } => self.find_method(&owner, &name)?.return_type,
+                _ => None,
+            },
+            Expr::Ref(_) => None,
+        }
+    }
+
+    /// Resolve the callee of a call expression from static information.
+    pub(super) fn call_target(&self, ctx: &CodegenContext, target: &Expr) -> Option<CallTarget> {
+        if let Some(name) = call_target_name(target) {
+            let key = normalize_name(&name);
+            if key == "REF" {
+                return Some(CallTarget::Ref);
+            }
+            if time::is_split_name(key.as_str()) {
+                return Some(CallTarget::Split(key));
+            }
+            if self.runtime.functions().contains_key(&key) {
+                return Some(CallTarget::Function(key));
+            }
+            if !name.contains('.') {
+                for namespace in &ctx.using {
+                    let qualified =
+                        SmolStr::new(format!("{namespace}.{name}").to_ascii_uppercase());
+                    if self.runtime.functions().contains_key(&qualified) {
+                        return Some(CallTarget::Function(qualified));
+                    }
+                }
+            }
+            if self.runtime.stdlib().get(&key).is_some()
+                || conversions::is_conversion_name(key.as_str())
+            {
+                return Some(CallTarget::Std(key));
+            }
+        }
+        match target {
+            Expr::Field {
+                target: base,
+                field,
+            } => {
+                let owner = self
+                    .expr_type(ctx, base)
+                    .and_then(|type_id| self.class_like_name(type_id));
+                if let Some(owner) = owner {
+                    if self.find_method(&owner, field).is_some() {
+                        return Some(CallTarget::Method {
+                            owner,
+                            name: field.clone(),
+                        });
+                    }
+                }
+            }
+            Expr::Name(name) => {
+                if let Some(owner) = &ctx.owner {
+                    if self.find_method(owner, name).is_some() {
+                        return Some(CallTarget::Method {
+                            owner: owner.clone(),
+                            name: name.clone(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        match self.resolved_type(self.expr_type(ctx, target)?)? {
+            Type::FunctionBlock { name } => Some(CallTarget::FunctionBlock(name.clone())),
+            _ => None,
+        }
+    }
+
+    pub(super) fn resolved_type(&self, type_id: TypeId) -> Option<&'a Type> {
+        let registry = self.runtime.registry();
+        let mut current = type_id;
+        for _ in 0..32 {
+            match registry.get(current)? {
+                Type::Alias { target, .. } => current = *target,
+                ty => return Some(ty),
+            }
+        }
+        None
+    }
+
+    /// FUNCTION_BLOCK, CLASS or INTERFACE name of a type.
+    pub(super) fn class_like_name(&self, type_id: TypeId) -> Option<SmolStr> {
+        match self.resolved_type(type_id)? {
+            Type::FunctionBlock { name } | Type::Class { name } | Type::Interface { name } => {
+                Some(name.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Find a method on a class-like or interface, walking its base chain.
+    pub(super) fn find_method(
+        &self,
+        owner: &SmolStr,
+        name: &SmolStr,
+    ) -> Option<&'a crate::eval::MethodDef> {
+        let mut seen = HashSet::new();
+        let mut current = Some(normalize_name(owner));
+        while let Some(key) = current {
+            if !seen.insert(key.clone()) {
+                return None;
+            }
+            let (methods, base) = if let Some(fb) = self.runtime.function_blocks().get(&key) {
+                (&fb.methods, ClassLikeDef::FunctionBlock(fb).base_name())
+            } else if let Some(class) = self.runtime.classes().get(&key) {
+                (&class.methods, class.base.clone())
+            } else {
+                let interface = self.runtime.interfaces().get(&key)?;
+                (&interface.methods, interface.base.clone())
+            };
+            if let Some(method) = methods
+                .iter()
+                .find(|method| method.name.eq_ignore_ascii_case(name))
+            {
+                return Some(method);
+            }
+            current = base.as_ref().map(normalize_name);
+        }
+        None
+    }
+
+    fn name_type(&self, ctx: &CodegenContext, name: &SmolStr) -> Option<TypeId> {
+        let key = normalize_name(name);
+        if let Some(type_id) = ctx.var_types.get(&key) {
+            return Some(*type_id);
+        }
+        if let Some(owner) = &ctx.owner {
+            if let Some(type_id) = self.class_like_var_type(owner, name) {
+                return Some(type_id);
+            }
+        }
+        self.global_types.get(&key).copied()
+    }
+
+    fn field_type(&self, target_type: TypeId, field: &SmolStr) -> Option<TypeId> {
+        match self.resolved_type(target_type)? {
+            Type::Struct { fields, .. } => fields
+                .iter()
+                .find(|entry| entry.name.eq_ignore_ascii_case(field))
+                .map(|entry| entry.type_id),
+            Type::FunctionBlock { name } | Type::Class { name } => {
+                self.class_like_var_type(name, field)
+            }
+            _ => None,
+        }
+    }
+
+    fn class_like_var_type(&self, owner: &SmolStr, name: &SmolStr) -> Option<TypeId> {
+        let mut seen = HashSet::new();
+        let mut current = Some(normalize_name(owner));
+        while let Some(key) = current {
+            if !seen.insert(key.clone()) {
+                return None;
+            }
+            let def = self.class_like_def(&key)?;
+            let found = match &def {
+                ClassLikeDef::FunctionBlock(fb) => fb
+                    .params
+                    .iter()
+                    .find(|param| param.name.eq_ignore_ascii_case(name))
+                    .map(|param| param.type_id)
+                    .or_else(|| find_var_type(&fb.vars, name)),
+                ClassLikeDef::Class(class) => find_var_type(&class.vars, name),
+            };
+            if found.is_some() {
+                return found;
+            }
+            current = def.base_name().as_ref().map(normalize_name);
+        }
+        None
+    }
+}
+
+fn find_var_type(vars: &[VarDef], name: &SmolStr) -> Option<TypeId> {
+    vars.iter()
+        .find(|var| var.name.eq_ignore_ascii_case(name))
+        .map(|var| var.type_id)
+}
+
+/// Dotted name of a call target (`F`, `NS.F`, `fb.M`), as the evaluator builds it.
+pub(super) fn call_target_name(expr: &Expr) -> Option<SmolStr> {
+    match expr {
+        Expr::Name(name) => Some(name.clone()),
+        Expr::Field { target, field } => {
+            let prefix = call_target_name(target)?;
+            Some(SmolStr::new(format!("{prefix}.{field}")))
+        }
+        _ => None,
+    }
+}
+
+/// Read expression equivalent to an assignment target.
+pub(super) fn lvalue_expr(target: &LValue) -> Expr {
+    match target {
+        LValue::Name(name) => Expr::Name(name.clone()),
+        LValue::Field { name, field } => Expr::Field {
+            target: Box::new(Expr::Name(name.clone())),
+            field: field.clone(),
+        },
+        LValue::Index { name, indices } => Expr::Index {
+            target: Box::new(Expr::Name(name.clone())),
+            indices: indices.clone(),
+        },
+        LValue::Deref(expr) => Expr::Deref(expr.clone()),
+    }
+}
@@ -48,6 +48,13 @@ pub(super) fn count_for_loops(stmts: &[crate::eval::stmt::Stmt]) -> usize {
                     count = count.saturating_add(count_for_loops(std::slice::from_ref(stmt)));
                 }
             }
+            crate::eval::stmt::Stmt::Sfc { chart, .. } => {
+                for action in &chart.actions {
+                    if let crate::eval::sfc::SfcActionBody::Statements(body) = &action.body {
+                        count = count.saturating_add(count_for_loops(body));
+                    }
+                }
+            }
             _ => {}
         }
     }
@@ -30,6 +30,29 @@ pub(crate) const HEADER_SIZE: u16 = 24;
 pub(crate) const SECTION_ENTRY_SIZE: usize = 12;
 pub(crate) const HEADER_FLAG_CRC32: u32 = 0x0001;
 
+/// POU flag: the body holds NOP placeholders for statements that were not lowered.
+///
+/// The encoder reports such statements as [`BytecodeError::UnsupportedStatement`]
+/// instead of setting this flag; modules carrying it are rejected by validation
+/// with [`BytecodeError::MissingPouBody`].
+pub const POU_FLAG_PARTIAL_BODY: u8 = 0x01;
+
+/// Call argument mask bit: arguments were passed positionally.
+///
+/// The low bits of the mask pushed before `CALL`, `CALL_METHOD` and
+/// `CALL_VIRTUAL` mark which callee parameters were supplied.
+pub const CALL_ARGS_POSITIONAL: u64 = 1 << 63;
+
+/// Call argument mask bit: the supplied parameters are listed in wider mask words.
+///
+/// Calls to POUs with more than [`CALL_ARGS_NARROW_PARAMS`] parameters push
+/// one LWORD per 64 parameters before the mask, and the low 32 bits of the
+/// mask hold the number of those words.
+pub const CALL_ARGS_WIDE: u64 = 1 << 62;
+
+/// Parameters that fit in the low bits of a single call argument mask.
+pub const CALL_ARGS_NARROW_PARAMS: usize = 62;
+
 /// Process image sizing derived from bytecode metadata.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct ProcessImageConfig {
@@ -102,6 +125,10 @@ pub enum BytecodeError {
     InvalidPouId(u32),
     #[error("invalid index {index} for {kind}")]
     InvalidIndex { kind: SmolStr, index: u32 },
+    #[error("missing body for POU '{0}'")]
+    MissingPouBody(SmolStr),
+    #[error("cannot lower statement at offset {offset} in POU '{pou}' to bytecode")]
+    UnsupportedStatement { pou: SmolStr, offset: u32 },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -118,6 +145,8 @@ pub enum SectionId {
     DebugStringTable = 0x000A,
     VarMeta = 0x000B,
     RetainInit = 0x000C,
+    PouDecls = 0x000D,
+    InitImage = 0x000E,
 }
 
 impl SectionId {
@@ -136,6 +165,8 @@ impl SectionId {
             0x000A => Some(Self::DebugStringTable),
             0x000B => Some(Self::VarMeta),
             0x000C => Some(Self::RetainInit),
+            0x000D => Some(Self::PouDecls),
+            0x000E => Some(Self::InitImage),
             _ => None,
         }
     }
@@ -175,6 +206,8 @@ pub enum SectionData {
     DebugMap(DebugMap),
     VarMeta(VarMeta),
     RetainInit(RetainInit),
+    PouDecls(PouDecls),
+    InitImage(InitImage),
     Raw(Vec<u8>),
 }
 
@@ -292,7 +325,7 @@ pub struct ConstPool {
     pub entries: Vec<ConstEntry>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ConstEntry {
     pub type_id: u32,
     pub payload: Vec<u8>,
@@ -349,6 +382,7 @@ pub struct PouEntry {
     pub id: u32,
     pub name_idx: u32,
     pub kind: PouKind,
+    pub flags: u8,
     pub code_offset: u32,
     pub code_length: u32,
     pub local_ref_start: u32,
@@ -418,6 +452,83 @@ pub struct RetainInitEntry {
     pub const_idx: u32,
 }
 
+/// Variable declaration flags: retain policy in the low two bits
+/// (0 unspecified, 1 `RETAIN`, 2 `NON_RETAIN`, 3 `PERSISTENT`).
+pub const VAR_FLAG_RETAIN_MASK: u8 = 0x03;
+/// Variable declaration flag: `VAR_EXTERNAL`.
+pub const VAR_FLAG_EXTERNAL: u8 = 0x04;
+/// Variable declaration flag: `CONSTANT`.
+pub const VAR_FLAG_CONSTANT: u8 = 0x08;
+/// Variable declaration flag: the initializer depends on run-time state and
+/// is only available from the sources.
+pub const VAR_FLAG_RUNTIME_INIT: u8 = 0x10;
+
+/// Variable declarations of each POU, used to lay out instances and frames
+/// without the sources.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PouDecls {
+    pub entries: Vec<PouDecl>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PouDecl {
+    pub pou_id: u32,
+    pub using: Vec<u32>,
+    /// Instance variables (programs, FBs, classes) or locals (functions, methods).
+    pub vars: Vec<VarDecl>,
+    /// `VAR_TEMP` of programs and FBs.
+    pub temps: Vec<VarDecl>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarDecl {
+    pub name_idx: u32,
+    pub type_id: u32,
+    pub flags: u8,
+    pub init: Option<InitValue>,
+}
+
+/// Initial value of a variable in the `INIT_IMAGE` and `POU_DECLS` sections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InitValue {
+    Const(u32),
+    Array {
+        dims: Vec<(i64, i64)>,
+        elements: Vec<InitValue>,
+    },
+    Struct {
+        type_name_idx: u32,
+        fields: Vec<InitSlot>,
+    },
+    Reference(Option<u32>),
+    Instance(u32),
+}
+
+/// Variables of the configuration after initialization: globals (including
+/// program instances) and every FB, class and program instance by id.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct InitImage {
+    pub globals: Vec<InitSlot>,
+    pub instances: Vec<InitInstance>,
+    pub next_instance_id: u32,
+    /// Display name of each `IO_MAP` binding, in binding order.
+    pub io_names: Vec<Option<u32>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitSlot {
+    pub name_idx: u32,
+    pub value: InitValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitInstance {
+    pub id: u32,
+    pub type_name_idx: u32,
+    pub parent: Option<u32>,
+    pub vars: Vec<InitSlot>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PouClassMeta {
     pub parent_pou_id: Option<u32>,
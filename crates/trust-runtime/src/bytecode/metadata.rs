@@ -91,7 +91,7 @@ fn resource_to_metadata(
 }
 
 impl RefEntry {
-    fn to_value_ref(&self, strings: &StringTable) -> Result<ValueRef, BytecodeError> {
+    pub(super) fn to_value_ref(&self, strings: &StringTable) -> Result<ValueRef, BytecodeError> {
         let location = match self.location {
             RefLocation::Global => MemoryLocation::Global,
             RefLocation::Local => MemoryLocation::Local(FrameId(self.owner_id)),
@@ -127,7 +127,7 @@ impl RefEntry {
     }
 }
 
-fn lookup_string(strings: &StringTable, idx: u32) -> Result<SmolStr, BytecodeError> {
+pub(super) fn lookup_string(strings: &StringTable, idx: u32) -> Result<SmolStr, BytecodeError> {
     strings
         .entries
         .get(idx as usize)
@@ -8,7 +8,11 @@ mod encoder;
 mod format;
 mod metadata;
 mod reader;
+mod restore;
+mod sfc;
 mod util;
 mod validate;
+mod vm;
 
 pub use format::*;
+pub use vm::BytecodeVm;
@@ -0,0 +1,622 @@
+//! Rebuild runtime declarations and the initial variable image from a module.
+//!
+//! Used to run a bundle that ships `program.stbc` without its ST sources: the
+//! type table, POU_INDEX, POU_DECLS and INIT_IMAGE sections carry everything
+//! the runtime otherwise gets from the compile pipeline. POU bodies stay empty
+//! and execute on the VM.
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+use trust_hir::symbols::ParamDirection;
+use trust_hir::types::{StructField, TypeRegistry, UnionVariant};
+use trust_hir::{Type, TypeId};
+
+use crate::eval::expr::Expr;
+use crate::eval::{
+    ClassDef, FunctionBlockBase, FunctionBlockDef, FunctionDef, MethodDef, Param, VarDef,
+};
+use crate::io::{IoAddress, IoBinding, IoTarget};
+use crate::memory::{InstanceData, InstanceId, VariableStorage};
+use crate::task::ProgramDef;
+use crate::value::{ArrayValue, StructValue, Value, ValueRef};
+use crate::{GlobalInitValue, RetainPolicy};
+
+use super::metadata::lookup_string;
+use super::{
+    BytecodeError, BytecodeModule, BytecodeVm, InitSlot, InitValue, PouDecl, PouEntry, PouKind,
+    SectionData, SectionId, StringTable, TypeData, TypeEntry, TypeKind, VarDecl, VAR_FLAG_CONSTANT,
+    VAR_FLAG_EXTERNAL, VAR_FLAG_RETAIN_MASK, VAR_FLAG_RUNTIME_INIT,
+};
+
+/// Declarations and initial state restored from a bytecode module.
+pub(crate) struct RestoredProgram {
+    pub(crate) functions: Vec<FunctionDef>,
+    pub(crate) function_blocks: Vec<FunctionBlockDef>,
+    pub(crate) classes: Vec<ClassDef>,
+    pub(crate) programs: Vec<ProgramDef>,
+    pub(crate) globals: Vec<RestoredGlobal>,
+    pub(crate) storage: VariableStorage,
+    pub(crate) io_bindings: Vec<IoBinding>,
+    pub(crate) vm: BytecodeVm,
+}
+
+pub(crate) struct RestoredGlobal {
+    pub(crate) name: SmolStr,
+    pub(crate) type_id: TypeId,
+    pub(crate) retain: RetainPolicy,
+    pub(crate) init: GlobalInitValue,
+}
+
+impl BytecodeModule {
+    /// Rebuild declarations, the initial variable image and IO bindings.
+    ///
+    /// Types from the module are registered in `registry`, which must hold
+    /// only the builtin and standard library types of a fresh runtime.
+    pub(crate) fn restore_program(
+        &self,
+        registry: &mut TypeRegistry,
+    ) -> Result<RestoredProgram, BytecodeError> {
+        let vm = BytecodeVm::new(self)?;
+        let strings = match self.section(SectionId::StringTable) {
+            Some(SectionData::StringTable(table)) => table,
+            _ => return Err(BytecodeError::MissingSection("STRING_TABLE".into())),
+        };
+        let types = match self.section(SectionId::TypeTable) {
+            Some(SectionData::TypeTable(table)) => table,
+            _ => return Err(BytecodeError::MissingSection("TYPE_TABLE".into())),
+        };
+        let pou_index = match self.section(SectionId::PouIndex) {
+            Some(SectionData::PouIndex(index)) => index,
+            _ => return Err(BytecodeError::MissingSection("POU_INDEX".into())),
+        };
+        let decls = match self.section(SectionId::PouDecls) {
+            Some(SectionData::PouDecls(decls)) => decls,
+            _ => return Err(BytecodeError::MissingSection("POU_DECLS".into())),
+        };
+        let image = match self.section(SectionId::InitImage) {
+            Some(SectionData::InitImage(image)) => image,
+            _ => return Err(BytecodeError::MissingSection("INIT_IMAGE".into())),
+        };
+
+        let builtin_types = registry.next_type_id().0;
+        let restore = Restore {
+            strings,
+            consts: vm.consts(),
+            refs: vm.refs(),
+            types: register_types(registry, strings, &types.entries)?,
+        };
+
+        let pous = pou_index
+            .entries
+            .iter()
+            .map(|entry| (entry.id, entry))
+            .collect::<HashMap<_, _>>();
+        let decls = decls
+            .entries
+            .iter()
+            .map(|decl| (decl.pou_id, decl))
+            .collect::<HashMap<_, _>>();
+
+        let mut functions = Vec::new();
+        let mut function_blocks = Vec::new();
+        let mut classes = Vec::new();
+        let mut programs = Vec::new();
+        for entry in &pou_index.entries {
+            let name = restore.string(entry.name_idx)?;
+            let Some(decl) = decls.get(&entry.id).copied() else {
+                // Standard library function blocks are native and carry no
+                // declarations.
+                let builtin = registry
+                    .lookup(&name)
+                    .is_some_and(|id| id.0 < builtin_types);
+                if entry.kind == PouKind::FunctionBlock && builtin {
+                    continue;
+                }
+                if entry.kind == PouKind::Method {
+                    continue;
+                }
+                return Err(BytecodeError::InvalidSection(
+                    format!("missing declarations for POU '{name}'").into(),
+                ));
+            };
+            match entry.kind {
+                PouKind::Program => programs.push(ProgramDef {
+                    name: name.clone(),
+                    vars: restore.var_defs(&name, &decl.vars)?,
+                    temps: restore.var_defs(&name, &decl.temps)?,
+                    using: restore.names(&decl.using)?,
+                    body: Vec::new(),
+                }),
+                PouKind::Function => functions.push(FunctionDef {
+                    name: name.clone(),
+                    return_type: restore.type_id(entry.return_type_id.ok_or_else(|| {
+                        BytecodeError::InvalidSection(
+                            format!("function '{name}' without return type").into(),
+                        )
+                    })?)?,
+                    params: restore.params(entry)?,
+                    locals: restore.var_defs(&name, &decl.vars)?,
+                    using: restore.names(&decl.using)?,
+                    body: Vec::new(),
+                }),
+                PouKind::FunctionBlock => {
+                    let base = match parent(&pous, entry)? {
+                        Some(parent) if parent.kind == PouKind::Class => {
+                            Some(FunctionBlockBase::Class(restore.string(parent.name_idx)?))
+                        }
+                        Some(parent) => Some(FunctionBlockBase::FunctionBlock(
+                            restore.string(parent.name_idx)?,
+                        )),
+                        None => None,
+                    };
+                    function_blocks.push(FunctionBlockDef {
+                        name: name.clone(),
+                        base,
+                        params: restore.params(entry)?,
+                        vars: restore.var_defs(&name, &decl.vars)?,
+                        temps: restore.var_defs(&name, &decl.temps)?,
+                        using: restore.names(&decl.using)?,
+                        methods: restore.methods(&pou_index.entries, &decls, entry)?,
+                        body: Vec::new(),
+                    });
+                }
+                PouKind::Class => classes.push(ClassDef {
+                    name: name.clone(),
+                    base: parent(&pous, entry)?
+                        .map(|parent| restore.string(parent.name_idx))
+                        .transpose()?,
+                    vars: restore.var_defs(&name, &decl.vars)?,
+                    using: restore.names(&decl.using)?,
+                    methods: restore.methods(&pou_index.entries, &decls, entry)?,
+                }),
+                PouKind::Method => {}
+            }
+        }
+
+        let mut image_globals = IndexMap::with_capacity(image.globals.len());
+        for slot in &image.globals {
+            let (name, value) = restore.slot(slot)?;
+            image_globals.insert(name, value);
+        }
+        let mut instances = FxHashMap::default();
+        for instance in &image.instances {
+            let mut variables = IndexMap::with_capacity(instance.vars.len());
+            for slot in &instance.vars {
+                let (name, value) = restore.slot(slot)?;
+                variables.insert(name, value);
+            }
+            instances.insert(
+                InstanceId(instance.id),
+                InstanceData {
+                    type_name: restore.string(instance.type_name_idx)?,
+                    variables,
+                    parent: instance.parent.map(InstanceId),
+                },
+            );
+        }
+
+        let mut globals = Vec::new();
+        if let Some(SectionData::VarMeta(meta)) = self.section(SectionId::VarMeta) {
+            for entry in &meta.entries {
+                let name = restore.string(entry.name_idx)?;
+                let type_id = restore.type_id(entry.type_id)?;
+                let init = match registry.get(type_id) {
+                    Some(Type::FunctionBlock { name }) => GlobalInitValue::FunctionBlock {
+                        type_name: name.clone(),
+                    },
+                    Some(Type::Class { name }) => GlobalInitValue::Class {
+                        type_name: name.clone(),
+                    },
+                    _ => GlobalInitValue::Value(
+                        image_globals.get(&name).cloned().unwrap_or(Value::Null),
+                    ),
+                };
+                globals.push(RestoredGlobal {
+                    name,
+                    type_id,
+                    retain: retain_policy(entry.retain),
+                    init,
+                });
+            }
+        }
+
+        let mut io_bindings = Vec::new();
+        if let Some(SectionData::IoMap(map)) = self.section(SectionId::IoMap) {
+            for (binding, name) in map.bindings.iter().zip(&image.io_names) {
+                let address = restore.string(binding.address_str_idx)?;
+                let address = IoAddress::parse(&address)
+                    .map_err(|err| BytecodeError::InvalidSection(err.to_string().into()))?;
+                io_bindings.push(IoBinding {
+                    target: IoTarget::Reference(restore.reference(binding.ref_idx)?),
+                    address,
+                    value_type: binding
+                        .type_id
+                        .map(|type_id| restore.type_id(type_id))
+                        .transpose()?,
+                    display_name: name.map(|idx| restore.string(idx)).transpose()?,
+                });
+            }
+        }
+
+        let storage = VariableStorage::from_image(image_globals, instances, image.next_instance_id);
+        Ok(RestoredProgram {
+            functions,
+            function_blocks,
+            classes,
+            programs,
+            globals,
+            storage,
+            io_bindings,
+            vm,
+        })
+    }
+}
+
+struct Restore<'a> {
+    strings: &'a StringTable,
+    consts: &'a [Value],
+    refs: &'a [ValueRef],
+    /// Runtime type id of each type table entry.
+    types: Vec<TypeId>,
+}
+
+impl Restore<'_> {
+    fn string(&self, idx: u32) -> Result<SmolStr, BytecodeError> {
+        lookup_string(self.strings, idx)
+    }
+
+    fn names(&self, indices: &[u32]) -> Result<Vec<SmolStr>, BytecodeError> {
+        indices.iter().map(|idx| self.string(*idx)).collect()
+    }
+
+    fn type_id(&self, idx: u32) -> Result<TypeId, BytecodeError> {
+        self.types
+            .get(idx as usize)
+            .copied()
+            .ok_or_else(|| invalid_index("type", idx))
+    }
+
+    fn constant(&self, idx: u32) -> Result<Value, BytecodeError> {
+        self.consts
+            .get(idx as usize)
+            .cloned()
+            .ok_or_else(|| invalid_index("const", idx))
+    }
+
+    fn reference(&self, idx: u32) -> Result<ValueRef, BytecodeError> {
+        self.refs
+            .get(idx as usize)
+            .cloned()
+            .ok_or_else(|| invalid_index("ref", idx))
+    }
+
+    fn value(&self, value: &InitValue) -> Result<Value, BytecodeError> {
+        Ok(match value {
+            InitValue::Const(idx) => self.constant(*idx)?,
+            InitValue::Array { dims, elements } => Value::Array(ArrayValue {
+                elements: elements
+                    .iter()
+                    .map(|element| self.value(element))
+                    .collect::<Result<_, _>>()?,
+                dimensions: dims.clone(),
+            }),
+            InitValue::Struct {
+                type_name_idx,
+                fields,
+            } => {
+                let mut values = IndexMap::with_capacity(fields.len());
+                for field in fields {
+                    let (name, value) = self.slot(field)?;
+                    values.insert(name, value);
+                }
+                Value::Struct(StructValue {
+                    type_name: self.string(*type_name_idx)?,
+                    fields: values,
+                })
+            }
+            InitValue::Reference(idx) => {
+                Value::Reference(idx.map(|idx| self.reference(idx)).transpose()?)
+            }
+            InitValue::Instance(id) => Value::Instance(InstanceId(*id)),
+        })
+    }
+
+    fn slot(&self, slot: &InitSlot) -> Result<(SmolStr, Value), BytecodeError> {
+        Ok((self.string(slot.name_idx)?, self.value(&slot.value)?))
+    }
+
+    fn var_defs(&self, pou: &SmolStr, decls: &[VarDecl]) -> Result<Vec<VarDef>, BytecodeError> {
+        decls
+            .iter()
+            .map(|decl| {
+                let name = self.string(decl.name_idx)?;
+                if decl.flags & VAR_FLAG_RUNTIME_INIT != 0 {
+                    return Err(BytecodeError::InvalidSection(
+                        format!("initializer of '{pou}.{name}' needs the ST sources").into(),
+                    ));
+                }
+                Ok(VarDef {
+                    name,
+                    type_id: self.type_id(decl.type_id)?,
+                    initializer: decl
+                        .init
+                        .as_ref()
+                        .map(|init| self.value(init).map(Expr::Literal))
+                        .transpose()?,
+                    retain: retain_policy(decl.flags & VAR_FLAG_RETAIN_MASK),
+                    external: decl.flags & VAR_FLAG_EXTERNAL != 0,
+                    constant: decl.flags & VAR_FLAG_CONSTANT != 0,
+                    address: None,
+                })
+            })
+            .collect()
+    }
+
+    fn params(&self, entry: &PouEntry) -> Result<Vec<Param>, BytecodeError> {
+        entry
+            .params
+            .iter()
+            .map(|param| {
+                let direction = match param.direction {
+                    0 => ParamDirection::In,
+                    1 => ParamDirection::Out,
+                    2 => ParamDirection::InOut,
+                    other => {
+                        return Err(BytecodeError::InvalidSection(
+                            format!("invalid parameter direction {other}").into(),
+                        ))
+                    }
+                };
+                Ok(Param {
+                    name: self.string(param.name_idx)?,
+                    type_id: self.type_id(param.type_id)?,
+                    direction,
+                    address: None,
+                    default: param
+                        .default_const_idx
+                        .map(|idx| self.constant(idx).map(Expr::Literal))
+                        .transpose()?,
+                })
+            })
+            .collect()
+    }
+
+    fn methods(
+        &self,
+        entries: &[PouEntry],
+        decls: &HashMap<u32, &PouDecl>,
+        owner: &PouEntry,
+    ) -> Result<Vec<MethodDef>, BytecodeError> {
+        let mut methods = Vec::new();
+        for entry in entries
+            .iter()
+            .filter(|entry| entry.kind == PouKind::Method && entry.owner_pou_id == Some(owner.id))
+        {
+            let name = self.string(entry.name_idx)?;
+            let decl = decls.get(&entry.id).ok_or_else(|| {
+                BytecodeError::InvalidSection(
+                    format!("missing declarations for method '{name}'").into(),
+                )
+            })?;
+            methods.push(MethodDef {
+                name: name.clone(),
+                return_type: entry
+                    .return_type_id
+                    .map(|idx| self.type_id(idx))
+                    .transpose()?,
+                params: self.params(entry)?,
+                locals: self.var_defs(&name, &decl.vars)?,
+                using: self.names(&decl.using)?,
+                body: Vec::new(),
+            });
+        }
+        Ok(methods)
+    }
+}
+
+/// Map every type table entry to a runtime type id, registering the types a
+/// fresh registry does not know yet.
+fn register_types(
+    registry: &mut TypeRegistry,
+    strings: &StringTable,
+    entries: &[TypeEntry],
+) -> Result<Vec<TypeId>, BytecodeError> {
+    let first = registry.next_type_id().0;
+    let mut ids = Vec::with_capacity(entries.len());
+    let mut pending = Vec::new();
+    for (idx, entry) in entries.iter().enumerate() {
+        let name = entry
+            .name_idx
+            .map(|idx| lookup_string(strings, idx))
+            .transpose()?;
+        let id = match &entry.data {
+            TypeData::Primitive {
+                prim_id,
+                max_length: 0,
+            } => primitive_type_id(*prim_id)?,
+            // NULL constants are encoded as a reference that targets itself.
+            TypeData::Reference { target_type_id } if *target_type_id as usize == idx => {
+                TypeId::NULL
+            }
+            _ => match name.as_deref().and_then(|name| registry.lookup(name)) {
+                Some(id) if entry.kind == TypeKind::FunctionBlock => id,
+                _ => {
+                    pending.push((idx, name));
+                    TypeId(first + pending.len() as u32 - 1)
+                }
+            },
+        };
+        ids.push(id);
+    }
+    for (idx, name) in pending {
+        let entry = &entries[idx];
+        let name = name.unwrap_or_else(|| SmolStr::new(format!("__BYTECODE_TYPE_{idx}")));
+        let ty = restore_type(strings, &ids, &name, entry)?;
+        registry.register(name, ty);
+    }
+    Ok(ids)
+}
+
+fn restore_type(
+    strings: &StringTable,
+    ids: &[TypeId],
+    name: &SmolStr,
+    entry: &TypeEntry,
+) -> Result<Type, BytecodeError> {
+    let type_id = |idx: u32| {
+        ids.get(idx as usize)
+            .copied()
+            .ok_or_else(|| invalid_index("type", idx))
+    };
+    Ok(match &entry.data {
+        TypeData::Primitive {
+            prim_id: 24,
+            max_length,
+        } => Type::String {
+            max_len: Some(u32::from(*max_length)),
+        },
+        TypeData::Primitive {
+            prim_id: 25,
+            max_length,
+        } => Type::WString {
+            max_len: Some(u32::from(*max_length)),
+        },
+        TypeData::Primitive { prim_id, .. } => {
+            return Err(BytecodeError::InvalidSection(
+                format!("length on primitive {prim_id}").into(),
+            ))
+        }
+        TypeData::Array { elem_type_id, dims } => Type::Array {
+            element: type_id(*elem_type_id)?,
+            dimensions: dims.clone(),
+        },
+        TypeData::Struct { fields } => Type::Struct {
+            name: name.clone(),
+            fields: fields
+                .iter()
+                .map(|field| {
+                    Ok(StructField {
+                        name: lookup_string(strings, field.name_idx)?,
+                        type_id: type_id(field.type_id)?,
+                        address: None,
+                    })
+                })
+                .collect::<Result<_, BytecodeError>>()?,
+        },
+        TypeData::Union { fields } => Type::Union {
+            name: name.clone(),
+            variants: fields
+                .iter()
+                .map(|field| {
+                    Ok(UnionVariant {
+                        name: lookup_string(strings, field.name_idx)?,
+                        type_id: type_id(field.type_id)?,
+                        address: None,
+                    })
+                })
+                .collect::<Result<_, BytecodeError>>()?,
+        },
+        TypeData::Enum {
+            base_type_id,
+            variants,
+        } => Type::Enum {
+            name: name.clone(),
+            base: type_id(*base_type_id)?,
+            values: variants
+                .iter()
+                .map(|variant| Ok((lookup_string(strings, variant.name_idx)?, variant.value)))
+                .collect::<Result<_, BytecodeError>>()?,
+        },
+        TypeData::Alias { target_type_id } => Type::Alias {
+            name: name.clone(),
+            target: type_id(*target_type_id)?,
+        },
+        TypeData::Subrange {
+            base_type_id,
+            lower,
+            upper,
+        } => Type::Subrange {
+            base: type_id(*base_type_id)?,
+            lower: *lower,
+            upper: *upper,
+        },
+        TypeData::Reference { target_type_id } => Type::Reference {
+            target: type_id(*target_type_id)?,
+        },
+        TypeData::Pou { .. } if entry.kind == TypeKind::Class => Type::Class { name: name.clone() },
+        TypeData::Pou { .. } => Type::FunctionBlock { name: name.clone() },
+        TypeData::Interface { .. } => Type::Interface { name: name.clone() },
+    })
+}
+
+fn primitive_type_id(prim_id: u16) -> Result<TypeId, BytecodeError> {
+    Ok(match prim_id {
+        1 => TypeId::BOOL,
+        2 => TypeId::BYTE,
+        3 => TypeId::WORD,
+        4 => TypeId::DWORD,
+        5 => TypeId::LWORD,
+        6 => TypeId::SINT,
+        7 => TypeId::INT,
+        8 => TypeId::DINT,
+        9 => TypeId::LINT,
+        10 => TypeId::USINT,
+        11 => TypeId::UINT,
+        12 => TypeId::UDINT,
+        13 => TypeId::ULINT,
+        14 => TypeId::REAL,
+        15 => TypeId::LREAL,
+        16 => TypeId::TIME,
+        17 => TypeId::LTIME,
+        18 => TypeId::DATE,
+        19 => TypeId::LDATE,
+        20 => TypeId::TOD,
+        21 => TypeId::LTOD,
+        22 => TypeId::DT,
+        23 => TypeId::LDT,
+        24 => TypeId::STRING,
+        25 => TypeId::WSTRING,
+        26 => TypeId::CHAR,
+        27 => TypeId::WCHAR,
+        _ => {
+            return Err(BytecodeError::InvalidSection(
+                format!("unknown primitive {prim_id}").into(),
+            ))
+        }
+    })
+}
+
+fn parent<'a>(
+    pous: &HashMap<u32, &'a PouEntry>,
+    entry: &PouEntry,
+) -> Result<Option<&'a PouEntry>, BytecodeError> {
+    entry
+        .class_meta
+        .as_ref()
+        .and_then(|meta| meta.parent_pou_id)
+        .map(|id| {
+            pous.get(&id)
+                .copied()
+                .ok_or(BytecodeError::InvalidPouId(id))
+        })
+        .transpose()
+}
+
+fn retain_policy(raw: u8) -> RetainPolicy {
+    match raw {
+        1 => RetainPolicy::Retain,
+        2 => RetainPolicy::NonRetain,
+        3 => RetainPolicy::Persistent,
+        _ => RetainPolicy::Unspecified,
+    }
+}
+
+fn invalid_index(kind: &str, index: u32) -> BytecodeError {
+    BytecodeError::InvalidIndex {
+        kind: kind.into(),
+        index,
+    }
+}
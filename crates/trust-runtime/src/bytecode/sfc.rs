@@ -0,0 +1,289 @@
+//! SFC chart descriptors embedded in POU bodies.
+//!
+//! An `SFC_CHART` instruction (`0x80 u32 len <descriptor>`) runs one scan of a
+//! chart. The descriptor lists steps, actions and transitions; expressions and
+//! action bodies are ordinary instruction spans emitted ahead of the chart
+//! instruction and referenced by POU-relative offsets.
+
+#![allow(missing_docs)]
+
+use std::ops::Range;
+
+use smol_str::SmolStr;
+
+use crate::eval::sfc::{SfcAction, SfcAssociation, SfcChart, SfcQualifier, SfcStep, SfcTransition};
+
+use super::reader::BytecodeReader;
+use super::BytecodeError;
+
+pub(crate) const OP_SFC_CHART: u8 = 0x80;
+
+/// Instruction span inside a POU body.
+pub(crate) type CodeSpan = Range<u32>;
+
+/// Action body form inside a chart descriptor.
+#[derive(Debug, Clone)]
+pub(crate) enum SfcBodySpan {
+    /// Statement list executed while `Q` is set and once on its falling edge.
+    Statements(CodeSpan),
+    /// Store sequence writing the `Q` flag (pushed by the VM) to a BOOL.
+    Flag(CodeSpan),
+}
+
+/// Chart with expressions and bodies replaced by instruction spans.
+pub(crate) type SfcCodeChart = SfcChart<CodeSpan, SfcBodySpan>;
+
+/// Chart whose step and action names are still string table indices.
+pub(crate) type SfcIndexedChart = (SfcCodeChart, Vec<u32>);
+
+fn qualifier_code(qualifier: SfcQualifier) -> u8 {
+    match qualifier {
+        SfcQualifier::N => 0,
+        SfcQualifier::R => 1,
+        SfcQualifier::S => 2,
+        SfcQualifier::P => 3,
+        SfcQualifier::L => 4,
+        SfcQualifier::D => 5,
+    }
+}
+
+fn qualifier_from_code(code: u8) -> Result<SfcQualifier, BytecodeError> {
+    Ok(match code {
+        0 => SfcQualifier::N,
+        1 => SfcQualifier::R,
+        2 => SfcQualifier::S,
+        3 => SfcQualifier::P,
+        4 => SfcQualifier::L,
+        5 => SfcQualifier::D,
+        _ => {
+            return Err(BytecodeError::InvalidSection(
+                format!("invalid SFC qualifier {code}").into(),
+            ))
+        }
+    })
+}
+
+/// Serialize a chart; `name_idx` interns step and action names.
+pub(crate) fn encode_chart(
+    chart: &SfcCodeChart,
+    mut name_idx: impl FnMut(&SmolStr) -> u32,
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    push_u32(&mut out, chart.steps.len() as u32);
+    for step in &chart.steps {
+        push_u32(&mut out, name_idx(&step.name));
+        out.push(u8::from(step.initial));
+        push_u32(&mut out, step.associations.len() as u32);
+        for association in &step.associations {
+            push_u32(&mut out, association.action as u32);
+            out.push(qualifier_code(association.qualifier));
+            push_optional_span(&mut out, association.duration.as_ref());
+        }
+    }
+    push_u32(&mut out, chart.actions.len() as u32);
+    for action in &chart.actions {
+        push_u32(&mut out, name_idx(&action.name));
+        let (kind, span) = match &action.body {
+            SfcBodySpan::Statements(span) => (0u8, span),
+            SfcBodySpan::Flag(span) => (1u8, span),
+        };
+        out.push(kind);
+        push_span(&mut out, span);
+    }
+    push_u32(&mut out, chart.transitions.len() as u32);
+    for transition in &chart.transitions {
+        push_u32(&mut out, transition.from.len() as u32);
+        for step in &transition.from {
+            push_u32(&mut out, *step as u32);
+        }
+        push_u32(&mut out, transition.to.len() as u32);
+        for step in &transition.to {
+            push_u32(&mut out, *step as u32);
+        }
+        push_span(&mut out, &transition.condition);
+        push_optional_span(&mut out, transition.priority.as_ref());
+    }
+    out
+}
+
+/// Decode a chart descriptor and resolve names through `strings`.
+pub(crate) fn decode_chart(
+    bytes: &[u8],
+    strings: &[SmolStr],
+) -> Result<SfcCodeChart, BytecodeError> {
+    let (mut chart, names) = decode_indexed_chart(bytes)?;
+    let mut names = names.into_iter().map(|idx| {
+        strings
+            .get(idx as usize)
+            .cloned()
+            .ok_or(BytecodeError::InvalidIndex {
+                kind: "string".into(),
+                index: idx,
+            })
+    });
+    for step in &mut chart.steps {
+        step.name = names.next().transpose()?.unwrap_or_default();
+    }
+    for action in &mut chart.actions {
+        action.name = names.next().transpose()?.unwrap_or_default();
+    }
+    Ok(chart)
+}
+
+/// Decode a chart descriptor, returning step then action name indices.
+///
+/// Step, action and transition cross references are range checked.
+pub(crate) fn decode_indexed_chart(bytes: &[u8]) -> Result<SfcIndexedChart, BytecodeError> {
+    let mut reader = BytecodeReader::new(bytes);
+    let mut chart = SfcCodeChart::default();
+    let mut names = Vec::new();
+    let step_count = reader.read_u32()?;
+    for _ in 0..step_count {
+        names.push(reader.read_u32()?);
+        let initial = reader.read_u8()? != 0;
+        let association_count = reader.read_u32()?;
+        let mut associations = Vec::new();
+        for _ in 0..association_count {
+            let action = reader.read_u32()? as usize;
+            let qualifier = qualifier_from_code(reader.read_u8()?)?;
+            let duration = read_optional_span(&mut reader)?;
+            associations.push(SfcAssociation {
+                action,
+                qualifier,
+                duration,
+            });
+        }
+        chart.steps.push(SfcStep {
+            name: SmolStr::default(),
+            initial,
+            associations,
+        });
+    }
+    let action_count = reader.read_u32()?;
+    for _ in 0..action_count {
+        names.push(reader.read_u32()?);
+        let kind = reader.read_u8()?;
+        let span = read_span(&mut reader)?;
+        let body = match kind {
+            0 => SfcBodySpan::Statements(span),
+            1 => SfcBodySpan::Flag(span),
+            _ => {
+                return Err(BytecodeError::InvalidSection(
+                    "invalid SFC action kind".into(),
+                ))
+            }
+        };
+        chart.actions.push(SfcAction {
+            name: SmolStr::default(),
+            body,
+        });
+    }
+    let transition_count = reader.read_u32()?;
+    for _ in 0..transition_count {
+        let from = read_step_list(&mut reader, chart.steps.len())?;
+        let to = read_step_list(&mut reader, chart.steps.len())?;
+        let condition = read_span(&mut reader)?;
+        let priority = read_optional_span(&mut reader)?;
+        chart.transitions.push(SfcTransition {
+            from,
+            to,
+            condition,
+            priority,
+            location: None,
+        });
+    }
+    if reader.remaining() > 0 {
+        return Err(BytecodeError::InvalidSection(
+            "trailing SFC chart data".into(),
+        ));
+    }
+    let action_count = chart.actions.len();
+    if chart
+        .steps
+        .iter()
+        .flat_map(|step| &step.associations)
+        .any(|association| association.action >= action_count)
+    {
+        return Err(BytecodeError::InvalidSection(
+            "SFC association references unknown action".into(),
+        ));
+    }
+    Ok((chart, names))
+}
+
+/// Every instruction span referenced by a chart.
+pub(crate) fn chart_spans(chart: &SfcCodeChart) -> Vec<&CodeSpan> {
+    let mut spans = Vec::new();
+    for step in &chart.steps {
+        spans.extend(
+            step.associations
+                .iter()
+                .filter_map(|association| association.duration.as_ref()),
+        );
+    }
+    for action in &chart.actions {
+        spans.push(match &action.body {
+            SfcBodySpan::Statements(span) | SfcBodySpan::Flag(span) => span,
+        });
+    }
+    for transition in &chart.transitions {
+        spans.push(&transition.condition);
+        spans.extend(transition.priority.as_ref());
+    }
+    spans
+}
+
+fn read_step_list(
+    reader: &mut BytecodeReader<'_>,
+    step_count: usize,
+) -> Result<Vec<usize>, BytecodeError> {
+    let count = reader.read_u32()?;
+    let mut steps = Vec::new();
+    for _ in 0..count {
+        let step = reader.read_u32()? as usize;
+        if step >= step_count {
+            return Err(BytecodeError::InvalidSection(
+                "SFC transition references unknown step".into(),
+            ));
+        }
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+fn read_span(reader: &mut BytecodeReader<'_>) -> Result<CodeSpan, BytecodeError> {
+    let start = reader.read_u32()?;
+    let end = reader.read_u32()?;
+    if end < start {
+        return Err(BytecodeError::InvalidSection(
+            "invalid SFC code span".into(),
+        ));
+    }
+    Ok(start..end)
+}
+
+fn read_optional_span(reader: &mut BytecodeReader<'_>) -> Result<Option<CodeSpan>, BytecodeError> {
+    match reader.read_u8()? {
+        0 => Ok(None),
+        _ => read_span(reader).map(Some),
+    }
+}
+
+fn push_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_span(out: &mut Vec<u8>, span: &CodeSpan) {
+    push_u32(out, span.start);
+    push_u32(out, span.end);
+}
+
+fn push_optional_span(out: &mut Vec<u8>, span: Option<&CodeSpan>) {
+    match span {
+        Some(span) => {
+            out.push(1);
+            push_span(out, span);
+        }
+        None => out.push(0),
+    }
+}
@@ -5,10 +5,12 @@
 use std::collections::HashSet;
 
 use super::reader::BytecodeReader;
+use super::sfc::{chart_spans, decode_indexed_chart, OP_SFC_CHART};
 use super::{
-    BytecodeError, BytecodeModule, ConstEntry, ConstPool, DebugMap, IoMap, PouIndex, PouKind,
-    RefSegment, RefTable, ResourceMeta, RetainInit, SectionData, SectionId, StringTable, TypeData,
-    TypeEntry, TypeKind, TypeTable, VarMeta,
+    BytecodeError, BytecodeModule, ConstEntry, ConstPool, DebugMap, InitImage, InitValue, IoMap,
+    PouDecls, PouIndex, PouKind, RefSegment, RefTable, ResourceMeta, RetainInit, SectionData,
+    SectionId, StringTable, TypeData, TypeEntry, TypeKind, TypeTable, VarMeta,
+    POU_FLAG_PARTIAL_BODY,
 };
 
 impl BytecodeModule {
@@ -66,6 +68,12 @@ impl BytecodeModule {
         if let Some(SectionData::RetainInit(retain)) = self.section(SectionId::RetainInit) {
             validate_retain_init(const_pool, ref_table, retain)?;
         }
+        if let Some(SectionData::PouDecls(decls)) = self.section(SectionId::PouDecls) {
+            validate_pou_decls(strings, types, const_pool, ref_table, pou_index, decls)?;
+        }
+        if let Some(SectionData::InitImage(image)) = self.section(SectionId::InitImage) {
+            validate_init_image(strings, const_pool, ref_table, io_map, image)?;
+        }
         if let Some(SectionData::DebugMap(debug_map)) = self.section(SectionId::DebugMap) {
             if self.version.minor >= 1 && debug_strings.is_none() {
                 return Err(BytecodeError::MissingSection("DEBUG_STRING_TABLE".into()));
@@ -279,6 +287,14 @@ fn validate_pou_index(
 ) -> Result<(), BytecodeError> {
     for entry in &index.entries {
         ensure_string_index(strings, entry.name_idx)?;
+        if entry.flags & POU_FLAG_PARTIAL_BODY != 0 {
+            let name = strings
+                .entries
+                .get(entry.name_idx as usize)
+                .cloned()
+                .unwrap_or_default();
+            return Err(BytecodeError::MissingPouBody(name));
+        }
         if let Some(return_type_id) = entry.return_type_id {
             ensure_type_index(types, return_type_id)?;
         }
@@ -336,12 +352,13 @@ fn validate_pou_index(
                 "POU code out of bounds".into(),
             ));
         }
-        validate_instruction_stream(index, types, start, &bodies[start..end])?;
+        validate_instruction_stream(strings, index, types, start, &bodies[start..end])?;
     }
     Ok(())
 }
 
 fn validate_instruction_stream(
+    strings: &StringTable,
     index: &PouIndex,
     types: &TypeTable,
     _base: usize,
@@ -350,6 +367,7 @@ fn validate_instruction_stream(
     let mut reader = BytecodeReader::new(code);
     let mut starts = Vec::new();
     let mut jumps = Vec::new();
+    let mut spans = Vec::new();
     while reader.remaining() > 0 {
         let pc = reader.pos();
         starts.push(pc as i32);
@@ -403,7 +421,7 @@ fn validate_instruction_stream(
             0x20..=0x22 => {
                 reader.read_u32()?;
             }
-            0x23 => {}
+            0x23 | 0x24 => {}
             0x30 => {
                 reader.read_u32()?;
             }
@@ -414,6 +432,14 @@ fn validate_instruction_stream(
             0x70 => {
                 reader.read_u32()?;
             }
+            OP_SFC_CHART => {
+                let len = reader.read_u32()? as usize;
+                let (chart, names) = decode_indexed_chart(reader.read_bytes(len)?)?;
+                for name_idx in names {
+                    ensure_string_index(strings, name_idx)?;
+                }
+                spans.extend(chart_spans(&chart).into_iter().cloned());
+            }
             _ => return Err(BytecodeError::InvalidOpcode(opcode)),
         }
     }
@@ -428,6 +454,13 @@ fn validate_instruction_stream(
             return Err(BytecodeError::InvalidJumpTarget(target));
         }
     }
+    for span in spans {
+        for target in [span.start as i32, span.end as i32] {
+            if target > code_len || (target != code_len && !start_set.contains(&target)) {
+                return Err(BytecodeError::InvalidJumpTarget(target));
+            }
+        }
+    }
     Ok(())
 }
 
@@ -541,6 +574,131 @@ fn validate_retain_init(
     Ok(())
 }
 
+fn validate_pou_decls(
+    strings: &StringTable,
+    types: &TypeTable,
+    const_pool: &ConstPool,
+    ref_table: &RefTable,
+    pou_index: &PouIndex,
+    decls: &PouDecls,
+) -> Result<(), BytecodeError> {
+    let pou_ids = pou_index
+        .entries
+        .iter()
+        .map(|entry| entry.id)
+        .collect::<HashSet<_>>();
+    for entry in &decls.entries {
+        if !pou_ids.contains(&entry.pou_id) {
+            return Err(BytecodeError::InvalidPouId(entry.pou_id));
+        }
+        for idx in &entry.using {
+            ensure_string_index(strings, *idx)?;
+        }
+        for var in entry.vars.iter().chain(&entry.temps) {
+            ensure_string_index(strings, var.name_idx)?;
+            ensure_type_index(types, var.type_id)?;
+            if let Some(init) = &var.init {
+                validate_init_value(strings, const_pool, ref_table, None, init)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_init_image(
+    strings: &StringTable,
+    const_pool: &ConstPool,
+    ref_table: &RefTable,
+    io_map: &IoMap,
+    image: &InitImage,
+) -> Result<(), BytecodeError> {
+    if image.io_names.len() != io_map.bindings.len() {
+        return Err(BytecodeError::InvalidSection(
+            "init image IO names do not match IO map".into(),
+        ));
+    }
+    for name_idx in image.io_names.iter().flatten() {
+        ensure_string_index(strings, *name_idx)?;
+    }
+    let mut ids = HashSet::new();
+    for instance in &image.instances {
+        if instance.id >= image.next_instance_id || !ids.insert(instance.id) {
+            return Err(BytecodeError::InvalidSection(
+                "invalid init image instance id".into(),
+            ));
+        }
+    }
+    let instances = Some(&ids);
+    for slot in &image.globals {
+        ensure_string_index(strings, slot.name_idx)?;
+        validate_init_value(strings, const_pool, ref_table, instances, &slot.value)?;
+    }
+    for instance in &image.instances {
+        ensure_string_index(strings, instance.type_name_idx)?;
+        if let Some(parent) = instance.parent {
+            ensure_instance_id(instances, parent)?;
+        }
+        for slot in &instance.vars {
+            ensure_string_index(strings, slot.name_idx)?;
+            validate_init_value(strings, const_pool, ref_table, instances, &slot.value)?;
+        }
+    }
+    Ok(())
+}
+
+/// `instances` holds the instance ids of the image; declarations may not
+/// refer to instances.
+fn validate_init_value(
+    strings: &StringTable,
+    const_pool: &ConstPool,
+    ref_table: &RefTable,
+    instances: Option<&HashSet<u32>>,
+    value: &InitValue,
+) -> Result<(), BytecodeError> {
+    match value {
+        InitValue::Const(idx) => ensure_const_index(const_pool, *idx),
+        InitValue::Array { dims, elements } => {
+            let len = dims.iter().try_fold(1usize, |len, (lower, upper)| {
+                let extent = usize::try_from(upper.checked_sub(*lower)?.checked_add(1)?).ok()?;
+                len.checked_mul(extent)
+            });
+            if len != Some(elements.len()) {
+                return Err(BytecodeError::InvalidSection(
+                    "init array length mismatch".into(),
+                ));
+            }
+            elements.iter().try_for_each(|element| {
+                validate_init_value(strings, const_pool, ref_table, instances, element)
+            })
+        }
+        InitValue::Struct {
+            type_name_idx,
+            fields,
+        } => {
+            ensure_string_index(strings, *type_name_idx)?;
+            fields.iter().try_for_each(|field| {
+                ensure_string_index(strings, field.name_idx)?;
+                validate_init_value(strings, const_pool, ref_table, instances, &field.value)
+            })
+        }
+        InitValue::Reference(ref_idx) => match ref_idx {
+            Some(idx) => ensure_ref_index(ref_table, *idx),
+            None => Ok(()),
+        },
+        InitValue::Instance(id) => ensure_instance_id(instances, *id),
+    }
+}
+
+fn ensure_instance_id(instances: Option<&HashSet<u32>>, id: u32) -> Result<(), BytecodeError> {
+    if !instances.is_some_and(|ids| ids.contains(&id)) {
+        return Err(BytecodeError::InvalidIndex {
+            kind: "instance".into(),
+            index: id,
+        });
+    }
+    Ok(())
+}
+
 fn validate_debug_map(
     strings: &StringTable,
     pou_index: &PouIndex,
@@ -0,0 +1,362 @@
+use smol_str::SmolStr;
+use trust_hir::symbols::ParamDirection;
+
+use crate::error::RuntimeError;
+use crate::eval::expr::{eval_expr, Expr, LValue, SizeOfTarget};
+use crate::eval::stmt::StmtResult;
+use crate::eval::{
+    call_function_block_with_body, call_function_with_body, call_method_with_body, ArgValue,
+    CallArg, EvalContext, MethodDef, Param,
+};
+use crate::memory::{InstanceId, VariableStorage};
+use crate::stdlib::time;
+use crate::value::Value;
+
+use super::super::{PouKind, CALL_ARGS_POSITIONAL, CALL_ARGS_WIDE};
+use super::exec::{invalid, resolve_instances};
+use super::{BytecodeVm, VmPou};
+
+impl BytecodeVm {
+    /// `CALL`: invoke a function, or a function block instance by its
+    /// dynamic type.
+    pub(super) fn call_pou<'a>(
+        &self,
+        ctx: &mut EvalContext<'a>,
+        id: u32,
+        stack: &mut Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let pou = self
+            .pou_ids
+            .get(&id)
+            .map(|idx| &self.pous[*idx])
+            .ok_or_else(|| invalid(format!("POU id {id}")))?;
+        let (mask, values) = pop_args(stack)?;
+        let key = upper(&pou.name);
+        match pou.kind {
+            PouKind::Function => {
+                let func = ctx
+                    .functions
+                    .and_then(|functions| functions.get(&key))
+                    .ok_or_else(|| RuntimeError::UndefinedFunction(pou.name.clone()))?;
+                let args = call_args(ctx.storage, &func.params, mask, values)?;
+                call_function_with_body(ctx, func, &args, |ctx| self.run_callee(ctx, pou))
+            }
+            PouKind::FunctionBlock => {
+                let instance_id = pop_instance(stack)?;
+                let function_blocks = ctx.function_blocks.ok_or(RuntimeError::TypeMismatch)?;
+                let declared = function_blocks
+                    .get(&key)
+                    .ok_or_else(|| RuntimeError::UndefinedFunctionBlock(pou.name.clone()))?;
+                let args = call_args(ctx.storage, &declared.params, mask, values)?;
+                let type_name = instance_type_name(ctx.storage, instance_id)?;
+                let fb = function_blocks
+                    .get(&upper(&type_name))
+                    .ok_or_else(|| RuntimeError::UndefinedFunctionBlock(type_name.clone()))?;
+                let callee = self.class_likes.get(&upper(&type_name)).copied();
+                call_function_block_with_body(ctx, fb, instance_id, &args, |ctx| match callee {
+                    Some(idx) => self.run_callee(ctx, &self.pous[idx]),
+                    None => Err(RuntimeError::UndefinedFunctionBlock(type_name.clone())),
+                })?;
+                Ok(Value::Null)
+            }
+            _ => Err(invalid(format!("CALL to non-callable POU '{}'", pou.name))),
+        }
+    }
+
+    /// `CALL_METHOD`: invoke a method by vtable slot of the receiver's type.
+    pub(super) fn call_method<'a>(
+        &self,
+        ctx: &mut EvalContext<'a>,
+        slot: u32,
+        stack: &mut Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let (mask, values) = pop_args(stack)?;
+        let instance_id = pop_instance(stack)?;
+        let class = self.instance_class(ctx.storage, instance_id)?;
+        let method = class
+            .methods
+            .get(slot as usize)
+            .and_then(|idx| self.pous.get(*idx))
+            .ok_or_else(|| invalid(format!("vtable slot {slot}")))?;
+        self.invoke_method(ctx, method, instance_id, mask, values)
+    }
+
+    /// `CALL_VIRTUAL`: invoke an interface method on the receiver's type.
+    pub(super) fn call_virtual<'a>(
+        &self,
+        ctx: &mut EvalContext<'a>,
+        type_idx: u32,
+        slot: u32,
+        stack: &mut Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let name = self
+            .interfaces
+            .get(&type_idx)
+            .and_then(|methods| methods.get(slot as usize))
+            .ok_or_else(|| invalid(format!("interface slot {slot}")))?;
+        let (mask, values) = pop_args(stack)?;
+        let instance_id = pop_instance(stack)?;
+        let class = self.instance_class(ctx.storage, instance_id)?;
+        let method = class
+            .methods
+            .iter()
+            .filter_map(|idx| self.pous.get(*idx))
+            .find(|method| method.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| RuntimeError::UndefinedFunction(name.clone()))?;
+        self.invoke_method(ctx, method, instance_id, mask, values)
+    }
+
+    /// `CALL_STD`: standard functions, conversions, SPLIT_* and SIZEOF.
+    pub(super) fn call_std(
+        &self,
+        ctx: &mut EvalContext<'_>,
+        name: &SmolStr,
+        stack: &mut Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let count = match stack.pop() {
+            Some(Value::UDInt(count)) => count as usize,
+            _ => return Err(invalid("CALL_STD argument count")),
+        };
+        let values = split_values(stack, count)?;
+        if name == "SIZEOF" {
+            let value = values.into_iter().next().unwrap_or(Value::Null);
+            let target = SizeOfTarget::Expr(Box::new(Expr::Literal(value)));
+            return eval_expr(ctx, &Expr::SizeOf(target));
+        }
+        if time::is_split_name(name) {
+            // SPLIT_* write through their output references like any other call.
+            let mut args = Vec::with_capacity(values.len());
+            for (idx, value) in values.into_iter().enumerate() {
+                let value = if idx == 0 {
+                    ArgValue::Expr(Expr::Literal(value))
+                } else {
+                    target_arg(ctx.storage, value)?
+                };
+                args.push(CallArg { name: None, value });
+            }
+            let call = Expr::Call {
+                target: Box::new(Expr::Name(name.clone())),
+                args,
+            };
+            return eval_expr(ctx, &call);
+        }
+        let stdlib = ctx
+            .stdlib
+            .ok_or_else(|| RuntimeError::UndefinedFunction(name.clone()))?;
+        stdlib.call(name, &values)
+    }
+
+    fn invoke_method<'a>(
+        &self,
+        ctx: &mut EvalContext<'a>,
+        pou: &VmPou,
+        instance_id: InstanceId,
+        mask: ArgMask,
+        values: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let owner = pou
+            .owner
+            .and_then(|idx| self.pous.get(idx))
+            .ok_or_else(|| invalid(format!("method '{}' without owner", pou.name)))?;
+        let key = upper(&owner.name);
+        let methods = match ctx.function_blocks.and_then(|fbs| fbs.get(&key)) {
+            Some(fb) => Some(&fb.methods),
+            None => ctx
+                .classes
+                .and_then(|classes| classes.get(&key))
+                .map(|class| &class.methods),
+        };
+        let method = methods
+            .and_then(|methods| {
+                methods
+                    .iter()
+                    .find(|method| method.name.eq_ignore_ascii_case(&pou.name))
+            })
+            .ok_or_else(|| RuntimeError::UndefinedFunction(pou.name.clone()))?;
+        let args = call_args(ctx.storage, &method.params, mask, values)?;
+        call_method_with_body(ctx, method, instance_id, &args, |ctx| {
+            self.run_callee(ctx, pou)
+        })
+    }
+
+    /// Invoke `method` on an instance from outside compiled code (e.g. an
+    /// OPC UA method call). Returns `None` when the instance type has no
+    /// compiled body for the method.
+    pub(crate) fn call_method_by_name<'a>(
+        &self,
+        ctx: &mut EvalContext<'a>,
+        method: &MethodDef,
+        instance_id: InstanceId,
+        args: &[CallArg],
+    ) -> Option<Result<Value, RuntimeError>> {
+        let class = self.instance_class(ctx.storage, instance_id).ok()?;
+        let pou = class
+            .methods
+            .iter()
+            .filter_map(|idx| self.pous.get(*idx))
+            .find(|pou| pou.name.eq_ignore_ascii_case(&method.name))?;
+        Some(call_method_with_body(
+            ctx,
+            method,
+            instance_id,
+            args,
+            |ctx| self.run_callee(ctx, pou),
+        ))
+    }
+
+    fn instance_class(
+        &self,
+        storage: &VariableStorage,
+        instance_id: InstanceId,
+    ) -> Result<&VmPou, RuntimeError> {
+        let type_name = instance_type_name(storage, instance_id)?;
+        self.class_likes
+            .get(&upper(&type_name))
+            .map(|idx| &self.pous[*idx])
+            .ok_or(RuntimeError::TypeMismatch)
+    }
+
+    /// Run a callee body inside the frame set up by the evaluator call path.
+    fn run_callee(
+        &self,
+        ctx: &mut EvalContext<'_>,
+        pou: &VmPou,
+    ) -> Result<StmtResult, RuntimeError> {
+        self.execute_pou(ctx, pou)?;
+        if !pou.has_return {
+            return Ok(StmtResult::Continue);
+        }
+        let value = ctx
+            .storage
+            .current_frame()
+            .and_then(|frame| frame.variables.get_index(0))
+            .map(|(_, value)| value.clone())
+            .unwrap_or(Value::Null);
+        Ok(StmtResult::Return(Some(value)))
+    }
+}
+
+/// Parameters supplied to a call, decoded from its argument mask.
+struct ArgMask {
+    positional: bool,
+    /// Bit `i % 64` of word `i / 64` is set when parameter `i` is supplied.
+    words: Vec<u64>,
+}
+
+impl ArgMask {
+    fn supplied(&self, idx: usize) -> bool {
+        self.words
+            .get(idx / 64)
+            .is_some_and(|word| word & (1 << (idx % 64)) != 0)
+    }
+
+    fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+/// Pop the argument mask and the supplied argument values.
+fn pop_args(stack: &mut Vec<Value>) -> Result<(ArgMask, Vec<Value>), RuntimeError> {
+    let mask = match stack.pop() {
+        Some(Value::LWord(mask)) => mask,
+        _ => return Err(invalid("call argument mask")),
+    };
+    let positional = mask & CALL_ARGS_POSITIONAL != 0;
+    let words = if mask & CALL_ARGS_WIDE != 0 {
+        let count = (mask & u64::from(u32::MAX)) as usize;
+        let start = stack
+            .len()
+            .checked_sub(count)
+            .ok_or_else(|| invalid("stack underflow"))?;
+        stack
+            .split_off(start)
+            .into_iter()
+            .map(|word| match word {
+                Value::LWord(word) => Ok(word),
+                _ => Err(invalid("call argument mask")),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        vec![mask & !CALL_ARGS_POSITIONAL]
+    };
+    let mask = ArgMask { positional, words };
+    let values = split_values(stack, mask.count())?;
+    Ok((mask, values))
+}
+
+fn split_values(stack: &mut Vec<Value>, count: usize) -> Result<Vec<Value>, RuntimeError> {
+    let start = stack
+        .len()
+        .checked_sub(count)
+        .ok_or_else(|| invalid("stack underflow"))?;
+    Ok(stack.split_off(start))
+}
+
+fn pop_instance(stack: &mut Vec<Value>) -> Result<InstanceId, RuntimeError> {
+    match stack.pop() {
+        Some(Value::Instance(id)) => Ok(id),
+        Some(_) => Err(RuntimeError::TypeMismatch),
+        None => Err(invalid("stack underflow")),
+    }
+}
+
+fn instance_type_name(
+    storage: &VariableStorage,
+    instance_id: InstanceId,
+) -> Result<SmolStr, RuntimeError> {
+    storage
+        .get_instance(instance_id)
+        .map(|instance| instance.type_name.clone())
+        .ok_or(RuntimeError::NullReference)
+}
+
+/// Rebuild evaluator call arguments from the values selected by `mask`.
+fn call_args(
+    storage: &VariableStorage,
+    params: &[Param],
+    mask: ArgMask,
+    values: Vec<Value>,
+) -> Result<Vec<CallArg>, RuntimeError> {
+    let positional = mask.positional;
+    let mut values = values.into_iter();
+    let mut args = Vec::new();
+    for (idx, param) in params.iter().enumerate() {
+        if !mask.supplied(idx) {
+            continue;
+        }
+        let value = values
+            .next()
+            .ok_or_else(|| invalid("call argument count"))?;
+        let value = match param.direction {
+            ParamDirection::In => ArgValue::Expr(Expr::Literal(value)),
+            ParamDirection::Out | ParamDirection::InOut => target_arg(storage, value)?,
+        };
+        args.push(CallArg {
+            name: (!positional).then(|| param.name.clone()),
+            value,
+        });
+    }
+    if values.next().is_some() {
+        return Err(invalid("call argument count"));
+    }
+    Ok(args)
+}
+
+fn target_arg(storage: &VariableStorage, value: Value) -> Result<ArgValue, RuntimeError> {
+    match value {
+        Value::Reference(Some(reference)) => {
+            let reference = resolve_instances(storage, reference)?;
+            let target = Expr::Literal(Value::Reference(Some(reference)));
+            Ok(ArgValue::Target(LValue::Deref(Box::new(target))))
+        }
+        Value::Reference(None) => Err(RuntimeError::NullReference),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+fn upper(name: &str) -> SmolStr {
+    SmolStr::new(name.to_ascii_uppercase())
+}
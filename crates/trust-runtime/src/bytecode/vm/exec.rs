@@ -0,0 +1,645 @@
+use smol_str::SmolStr;
+
+use crate::debug::SourceLocation;
+use crate::error::RuntimeError;
+use crate::eval::ops::{apply_binary, apply_unary, BinaryOp, UnaryOp};
+use crate::eval::sfc::{SfcHost, SfcTransition};
+use crate::eval::EvalContext;
+use crate::memory::{FrameId, MemoryLocation, VariableStorage};
+use crate::value::{
+    parse_partial_access, read_partial_access, write_partial_access, PartialAccess,
+    PartialAccessError, RefSegment, Value, ValueRef,
+};
+
+use super::super::sfc::{decode_chart, CodeSpan, SfcBodySpan, OP_SFC_CHART};
+use super::{BytecodeVm, VmPou};
+
+impl BytecodeVm {
+    /// Execute a program body from bytecode.
+    ///
+    /// The caller sets up the program frame and instance exactly as for the
+    /// evaluator; FOR loop temporaries are appended to that frame.
+    pub fn execute_program(
+        &self,
+        ctx: &mut EvalContext<'_>,
+        name: &str,
+    ) -> Result<(), RuntimeError> {
+        let pou = self
+            .program(name)
+            .ok_or_else(|| RuntimeError::UndefinedProgram(name.into()))?;
+        self.execute_pou(ctx, pou)
+    }
+
+    /// Run a POU body in the current frame; callee frames are set up by the
+    /// evaluator call path before this runs.
+    pub(super) fn execute_pou(
+        &self,
+        ctx: &mut EvalContext<'_>,
+        pou: &VmPou,
+    ) -> Result<(), RuntimeError> {
+        let mut pushed = false;
+        let frame = if pou.local_ref_count > 0 {
+            if ctx.storage.current_frame().is_none() {
+                ctx.storage.push_frame(pou.name.clone());
+                pushed = true;
+            }
+            let frame = ctx
+                .storage
+                .current_frame_mut()
+                .ok_or(RuntimeError::InvalidFrame(0))?;
+            while frame.variables.len() < pou.local_ref_count {
+                let slot = frame.variables.len();
+                frame
+                    .variables
+                    .insert(SmolStr::new(format!("__vm_slot_{slot}")), Value::Null);
+            }
+            Some(frame.id)
+        } else {
+            None
+        };
+        let mut machine = Machine {
+            vm: self,
+            pou,
+            frame,
+            stack: Vec::new(),
+            location: None,
+        };
+        let result = machine.run(ctx);
+        #[cfg(feature = "debug")]
+        if let Err(err) = &result {
+            if let Some(hook) = ctx.debug.take() {
+                hook.on_fault(ctx, machine.location, err);
+                ctx.debug = Some(hook);
+            }
+        }
+        if pushed {
+            ctx.storage.pop_frame();
+        }
+        result
+    }
+}
+
+struct Machine<'vm> {
+    vm: &'vm BytecodeVm,
+    pou: &'vm VmPou,
+    frame: Option<FrameId>,
+    stack: Vec<Value>,
+    /// Last statement boundary passed, reported if the POU faults.
+    location: Option<&'vm SourceLocation>,
+}
+
+impl Machine<'_> {
+    fn run(&mut self, ctx: &mut EvalContext<'_>) -> Result<(), RuntimeError> {
+        self.run_span(ctx, 0, self.pou.code_end - self.pou.code_start)
+    }
+
+    /// Execute the instructions in `start..end` (POU-relative offsets).
+    ///
+    /// Jumps may target any offset in the POU; execution stops when `pc`
+    /// reaches `end` or a RET is executed.
+    fn run_span(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        start: usize,
+        end: usize,
+    ) -> Result<(), RuntimeError> {
+        let code = &self.vm.code[self.pou.code_start..self.pou.code_end];
+        let end = end.min(code.len());
+        let mut pc = start;
+        while pc < end {
+            if let Some(locations) = self.pou.statements.get(&pc) {
+                self.location = locations.last().or(self.location);
+                statement(ctx, locations)?;
+            }
+            let opcode = code[pc];
+            pc += 1;
+            match opcode {
+                0x00 => {}
+                0x01 => return Err(RuntimeError::ForStepZero),
+                0x02 => {
+                    let offset = read_i32(code, &mut pc)?;
+                    let target = jump_target(code, pc, offset)?;
+                    if target <= pc {
+                        crate::eval::stmt::check_execution_budget(ctx)?;
+                    }
+                    pc = target;
+                }
+                0x03 | 0x04 => {
+                    let offset = read_i32(code, &mut pc)?;
+                    let target = jump_target(code, pc, offset)?;
+                    let condition = match self.pop()? {
+                        Value::Bool(value) => value,
+                        _ => return Err(RuntimeError::ConditionNotBool),
+                    };
+                    if condition == (opcode == 0x03) {
+                        if target <= pc {
+                            crate::eval::stmt::check_execution_budget(ctx)?;
+                        }
+                        pc = target;
+                    }
+                }
+                0x05 => {
+                    let id = read_u32(code, &mut pc)?;
+                    let value = self.vm.call_pou(ctx, id, &mut self.stack)?;
+                    self.stack.push(value);
+                }
+                0x06 => return Ok(()),
+                0x07 => {
+                    let slot = read_u32(code, &mut pc)?;
+                    let value = self.vm.call_method(ctx, slot, &mut self.stack)?;
+                    self.stack.push(value);
+                }
+                0x08 => {
+                    let type_idx = read_u32(code, &mut pc)?;
+                    let slot = read_u32(code, &mut pc)?;
+                    let value = self.vm.call_virtual(ctx, type_idx, slot, &mut self.stack)?;
+                    self.stack.push(value);
+                }
+                0x10 => {
+                    let idx = read_u32(code, &mut pc)?;
+                    let value = self
+                        .vm
+                        .consts
+                        .get(idx as usize)
+                        .cloned()
+                        .ok_or_else(|| invalid(format!("const index {idx}")))?;
+                    self.stack.push(value);
+                }
+                0x11 => {
+                    let value = self.peek(0)?.clone();
+                    self.stack.push(value);
+                }
+                0x12 => {
+                    self.pop()?;
+                }
+                0x13 => {
+                    let len = self.depth(2)?;
+                    self.stack.swap(len - 1, len - 2);
+                }
+                0x14 => {
+                    let value = self.peek(1)?.clone();
+                    self.stack.push(value);
+                }
+                0x15 => {
+                    let len = self.depth(3)?;
+                    let value = self.stack.remove(len - 3);
+                    self.stack.push(value);
+                }
+                0x16 => {
+                    let depth = *code.get(pc).ok_or_else(|| invalid("truncated operand"))?;
+                    pc += 1;
+                    let value = self.peek(depth as usize)?.clone();
+                    self.stack.push(value);
+                }
+                0x20 => {
+                    let idx = read_u32(code, &mut pc)?;
+                    let reference = self.static_ref(idx)?;
+                    let value = load(ctx.storage, reference)?;
+                    self.stack.push(value);
+                }
+                0x21 => {
+                    let idx = read_u32(code, &mut pc)?;
+                    let reference = self.static_ref(idx)?;
+                    let value = self.pop()?;
+                    store(ctx.storage, reference, value)?;
+                }
+                0x22 => {
+                    let idx = read_u32(code, &mut pc)?;
+                    let reference = self.static_ref(idx)?;
+                    self.stack.push(Value::Reference(Some(reference)));
+                }
+                0x23 => {
+                    let instance = ctx.current_instance.ok_or(RuntimeError::NullReference)?;
+                    self.stack.push(Value::Instance(instance));
+                }
+                0x24 => {
+                    let current = ctx.current_instance.ok_or(RuntimeError::TypeMismatch)?;
+                    let parent = ctx
+                        .storage
+                        .get_instance(current)
+                        .ok_or(RuntimeError::NullReference)?
+                        .parent
+                        .ok_or(RuntimeError::TypeMismatch)?;
+                    self.stack.push(Value::Instance(parent));
+                }
+                0x30 => {
+                    let idx = read_u32(code, &mut pc)?;
+                    let field = self
+                        .vm
+                        .strings
+                        .get(idx as usize)
+                        .cloned()
+                        .ok_or_else(|| invalid(format!("string index {idx}")))?;
+                    let target = self.pop()?;
+                    let reference = ref_field(ctx.storage, target, field)?;
+                    self.stack.push(reference);
+                }
+                0x31 => {
+                    let index = index_to_i64(self.pop()?)?;
+                    let target = self.pop_ref()?;
+                    let reference = ref_index(ctx.storage, target, index)?;
+                    self.stack.push(Value::Reference(Some(reference)));
+                }
+                0x32 => {
+                    let reference = self.pop_ref()?;
+                    let value = load(ctx.storage, reference)?;
+                    self.stack.push(value);
+                }
+                0x33 => {
+                    let value = self.pop()?;
+                    let reference = self.pop_ref()?;
+                    store(ctx.storage, reference, value)?;
+                }
+                0x45 | 0x49 => {
+                    let op = if opcode == 0x45 {
+                        UnaryOp::Neg
+                    } else {
+                        UnaryOp::Not
+                    };
+                    let value = self.pop()?;
+                    self.stack.push(apply_unary(op, value)?);
+                }
+                0x4A | 0x4B | 0x4D | 0x4E => {
+                    let name = match opcode {
+                        0x4A => "SHL",
+                        0x4B => "SHR",
+                        0x4D => "ROL",
+                        _ => "ROR",
+                    };
+                    let right = self.pop()?;
+                    let left = self.pop()?;
+                    let stdlib = ctx
+                        .stdlib
+                        .ok_or_else(|| RuntimeError::UndefinedFunction(name.into()))?;
+                    self.stack.push(stdlib.call(name, &[left, right])?);
+                }
+                0x40..=0x44 | 0x46..=0x48 | 0x4C | 0x50..=0x55 => {
+                    let op = binary_op(opcode);
+                    let right = self.pop()?;
+                    let left = self.pop()?;
+                    self.stack
+                        .push(apply_binary(op, left, right, &ctx.profile)?);
+                }
+                0x60 => {
+                    let idx = read_u32(code, &mut pc)?;
+                    let type_name = self
+                        .vm
+                        .type_names
+                        .get(idx as usize)
+                        .cloned()
+                        .flatten()
+                        .ok_or_else(|| invalid(format!("CAST to unnamed type {idx}")))?;
+                    let value = self.pop()?;
+                    let name = format!("TO_{type_name}");
+                    let stdlib = ctx
+                        .stdlib
+                        .ok_or_else(|| RuntimeError::UndefinedFunction(name.as_str().into()))?;
+                    self.stack.push(stdlib.call(&name, &[value])?);
+                }
+                0x70 => {
+                    let idx = read_u32(code, &mut pc)?;
+                    let name = self
+                        .vm
+                        .strings
+                        .get(idx as usize)
+                        .cloned()
+                        .ok_or_else(|| invalid(format!("string index {idx}")))?;
+                    let value = self.vm.call_std(ctx, &name, &mut self.stack)?;
+                    self.stack.push(value);
+                }
+                OP_SFC_CHART => {
+                    let len = read_u32(code, &mut pc)? as usize;
+                    let descriptor = code
+                        .get(pc..pc + len)
+                        .ok_or_else(|| invalid("truncated SFC chart"))?;
+                    pc += len;
+                    let chart = decode_chart(descriptor, &self.vm.strings)
+                        .map_err(|err| invalid(err.to_string()))?;
+                    crate::eval::sfc::run_chart(ctx, &chart, self)?;
+                }
+                _ => {
+                    return Err(invalid(format!("unsupported opcode 0x{opcode:02X}")));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn static_ref(&self, idx: u32) -> Result<ValueRef, RuntimeError> {
+        let mut reference = self
+            .vm
+            .refs
+            .get(idx as usize)
+            .cloned()
+            .ok_or_else(|| invalid(format!("ref index {idx}")))?;
+        if let MemoryLocation::Local(frame_id) = reference.location {
+            if self.pou.local_frame != Some(frame_id) {
+                return Err(RuntimeError::InvalidFrame(frame_id.0));
+            }
+            let frame = self.frame.ok_or(RuntimeError::InvalidFrame(frame_id.0))?;
+            reference.location = MemoryLocation::Local(frame);
+        }
+        Ok(reference)
+    }
+
+    fn pop(&mut self) -> Result<Value, RuntimeError> {
+        self.stack.pop().ok_or_else(|| invalid("stack underflow"))
+    }
+
+    fn pop_ref(&mut self) -> Result<ValueRef, RuntimeError> {
+        match self.pop()? {
+            Value::Reference(Some(reference)) => Ok(reference),
+            Value::Reference(None) => Err(RuntimeError::NullReference),
+            _ => Err(RuntimeError::TypeMismatch),
+        }
+    }
+
+    fn peek(&self, depth: usize) -> Result<&Value, RuntimeError> {
+        let len = self.depth(depth + 1)?;
+        Ok(&self.stack[len - 1 - depth])
+    }
+
+    fn depth(&self, required: usize) -> Result<usize, RuntimeError> {
+        let len = self.stack.len();
+        if len < required {
+            return Err(invalid("stack underflow"));
+        }
+        Ok(len)
+    }
+}
+
+/// Chart expressions and actions are spans of the POU running the chart.
+impl SfcHost<CodeSpan, SfcBodySpan> for Machine<'_> {
+    fn eval(&mut self, ctx: &mut EvalContext<'_>, expr: &CodeSpan) -> Result<Value, RuntimeError> {
+        self.run_span(ctx, expr.start as usize, expr.end as usize)?;
+        self.pop()
+    }
+
+    fn eval_condition(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        transition: &SfcTransition<CodeSpan>,
+    ) -> Result<Value, RuntimeError> {
+        // The condition span carries the transition's DEBUG_MAP entry.
+        self.eval(ctx, &transition.condition)
+    }
+
+    fn run_action(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        body: &SfcBodySpan,
+        q: bool,
+        final_scan: bool,
+    ) -> Result<(), RuntimeError> {
+        match body {
+            SfcBodySpan::Statements(span) => {
+                if q || final_scan {
+                    self.run_span(ctx, span.start as usize, span.end as usize)?;
+                }
+            }
+            SfcBodySpan::Flag(span) => {
+                self.stack.push(Value::Bool(q));
+                self.run_span(ctx, span.start as usize, span.end as usize)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Statement boundary: enforce the execution budget and notify the debugger.
+fn statement(ctx: &mut EvalContext<'_>, locations: &[SourceLocation]) -> Result<(), RuntimeError> {
+    crate::eval::stmt::check_execution_budget(ctx)?;
+    #[cfg(feature = "debug")]
+    if let Some(hook) = ctx.debug.take() {
+        if locations.is_empty() {
+            hook.on_statement_with_context(ctx, None, ctx.call_depth);
+        }
+        for location in locations {
+            hook.on_statement_with_context(ctx, Some(location), ctx.call_depth);
+        }
+        ctx.debug = Some(hook);
+    }
+    #[cfg(not(feature = "debug"))]
+    let _ = locations;
+    Ok(())
+}
+
+fn binary_op(opcode: u8) -> BinaryOp {
+    match opcode {
+        0x40 => BinaryOp::Add,
+        0x41 => BinaryOp::Sub,
+        0x42 => BinaryOp::Mul,
+        0x43 => BinaryOp::Div,
+        0x44 => BinaryOp::Mod,
+        0x46 => BinaryOp::And,
+        0x47 => BinaryOp::Or,
+        0x48 => BinaryOp::Xor,
+        0x4C => BinaryOp::Pow,
+        0x50 => BinaryOp::Eq,
+        0x51 => BinaryOp::Ne,
+        0x52 => BinaryOp::Lt,
+        0x53 => BinaryOp::Le,
+        0x54 => BinaryOp::Gt,
+        _ => BinaryOp::Ge,
+    }
+}
+
+fn read_u32(code: &[u8], pc: &mut usize) -> Result<u32, RuntimeError> {
+    let bytes = code
+        .get(*pc..*pc + 4)
+        .ok_or_else(|| invalid("truncated operand"))?;
+    *pc += 4;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_i32(code: &[u8], pc: &mut usize) -> Result<i32, RuntimeError> {
+    read_u32(code, pc).map(|value| value as i32)
+}
+
+fn jump_target(code: &[u8], pc: usize, offset: i32) -> Result<usize, RuntimeError> {
+    let target = pc as i64 + i64::from(offset);
+    if target < 0 || target > code.len() as i64 {
+        return Err(invalid(format!("jump target {target}")));
+    }
+    Ok(target as usize)
+}
+
+pub(super) fn invalid(message: impl Into<SmolStr>) -> RuntimeError {
+    RuntimeError::InvalidBytecode(message.into())
+}
+
+fn index_to_i64(value: Value) -> Result<i64, RuntimeError> {
+    match value {
+        Value::SInt(v) => Ok(v as i64),
+        Value::Int(v) => Ok(v as i64),
+        Value::DInt(v) => Ok(v as i64),
+        Value::LInt(v) => Ok(v),
+        Value::USInt(v) => Ok(v as i64),
+        Value::UInt(v) => Ok(v as i64),
+        Value::UDInt(v) => Ok(v as i64),
+        Value::ULInt(v) => Ok(v as i64),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+fn ref_field(
+    storage: &VariableStorage,
+    target: Value,
+    field: SmolStr,
+) -> Result<Value, RuntimeError> {
+    match target {
+        Value::Instance(id) => storage
+            .ref_for_instance_recursive(id, field.as_ref())
+            .map(|reference| Value::Reference(Some(reference)))
+            .ok_or(RuntimeError::UndefinedField(field)),
+        Value::Reference(Some(mut reference)) => {
+            reference.path.push(RefSegment::Field(field));
+            let reference = resolve_instances(storage, reference)?;
+            Ok(Value::Reference(Some(reference)))
+        }
+        Value::Reference(None) => Err(RuntimeError::NullReference),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+/// Apply one array index; consecutive REF_INDEX ops fill a multi-dimensional
+/// index segment before moving on to the element.
+fn ref_index(
+    storage: &VariableStorage,
+    reference: ValueRef,
+    index: i64,
+) -> Result<ValueRef, RuntimeError> {
+    let mut reference = resolve_instances(storage, reference)?;
+    if let Some(RefSegment::Index(indices)) = reference.path.last() {
+        let mut parent = reference.clone();
+        parent.path.pop();
+        if let Some(Value::Array(array)) = storage.read_by_ref(parent) {
+            if let Some(&(lower, upper)) = array.dimensions.get(indices.len()) {
+                check_bounds(index, lower, upper)?;
+                if let Some(RefSegment::Index(indices)) = reference.path.last_mut() {
+                    indices.push(index);
+                }
+                return Ok(reference);
+            }
+        }
+    }
+    match storage.read_by_ref(reference.clone()) {
+        Some(Value::Array(array)) => {
+            let &(lower, upper) = array.dimensions.first().ok_or(RuntimeError::TypeMismatch)?;
+            check_bounds(index, lower, upper)?;
+            reference.path.push(RefSegment::Index(vec![index]));
+            Ok(reference)
+        }
+        Some(_) => Err(RuntimeError::TypeMismatch),
+        None => Err(RuntimeError::NullReference),
+    }
+}
+
+fn check_bounds(index: i64, lower: i64, upper: i64) -> Result<(), RuntimeError> {
+    if index < lower || index > upper {
+        return Err(RuntimeError::IndexOutOfBounds {
+            index,
+            lower,
+            upper,
+        });
+    }
+    Ok(())
+}
+
+fn load(storage: &VariableStorage, reference: ValueRef) -> Result<Value, RuntimeError> {
+    let reference = resolve_instances(storage, reference)?;
+    if let Some((base, access)) = split_partial_access(&reference) {
+        let value = storage
+            .read_by_ref(base)
+            .ok_or(RuntimeError::NullReference)?;
+        return read_partial_access(value, access).map_err(partial_access_error);
+    }
+    storage
+        .read_by_ref(reference)
+        .cloned()
+        .ok_or(RuntimeError::NullReference)
+}
+
+fn store(
+    storage: &mut VariableStorage,
+    reference: ValueRef,
+    value: Value,
+) -> Result<(), RuntimeError> {
+    let reference = resolve_instances(storage, reference)?;
+    let (reference, value) = match split_partial_access(&reference) {
+        Some((base, access)) => {
+            let current = storage
+                .read_by_ref(base.clone())
+                .cloned()
+                .ok_or(RuntimeError::NullReference)?;
+            let updated =
+                write_partial_access(current, access, value).map_err(partial_access_error)?;
+            (base, updated)
+        }
+        None => (reference, value),
+    };
+    if storage.write_by_ref(reference, value) {
+        Ok(())
+    } else {
+        Err(RuntimeError::NullReference)
+    }
+}
+
+/// Rebase field segments that step into FB/class instances onto the
+/// instance storage, e.g. `fb.Q` inside a program instance.
+pub(super) fn resolve_instances(
+    storage: &VariableStorage,
+    mut reference: ValueRef,
+) -> Result<ValueRef, RuntimeError> {
+    'rebase: loop {
+        for (idx, segment) in reference.path.iter().enumerate() {
+            let RefSegment::Field(field) = segment else {
+                continue;
+            };
+            let prefix = ValueRef {
+                location: reference.location,
+                offset: reference.offset,
+                path: reference.path[..idx].to_vec(),
+            };
+            if let Some(Value::Instance(id)) = storage.read_by_ref(prefix) {
+                let base = storage
+                    .ref_for_instance_recursive(*id, field.as_ref())
+                    .ok_or_else(|| RuntimeError::UndefinedField(field.clone()))?;
+                let mut path = base.path;
+                path.extend_from_slice(&reference.path[idx + 1..]);
+                reference = ValueRef {
+                    location: base.location,
+                    offset: base.offset,
+                    path,
+                };
+                continue 'rebase;
+            }
+        }
+        return Ok(reference);
+    }
+}
+
+fn split_partial_access(reference: &ValueRef) -> Option<(ValueRef, PartialAccess)> {
+    let Some(RefSegment::Field(field)) = reference.path.last() else {
+        return None;
+    };
+    let access = parse_partial_access(field.as_str())?;
+    let mut base = reference.clone();
+    base.path.pop();
+    Some((base, access))
+}
+
+fn partial_access_error(err: PartialAccessError) -> RuntimeError {
+    match err {
+        PartialAccessError::IndexOutOfBounds {
+            index,
+            lower,
+            upper,
+        } => RuntimeError::IndexOutOfBounds {
+            index,
+            lower,
+            upper,
+        },
+        PartialAccessError::TypeMismatch => RuntimeError::TypeMismatch,
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::HashMap;
+
+use smol_str::SmolStr;
+
+use crate::debug::SourceLocation;
+use crate::memory::MemoryLocation;
+use crate::value::{
+    DateTimeValue, DateValue, Duration, EnumValue, LDateTimeValue, LDateValue, LTimeOfDayValue,
+    TimeOfDayValue, Value, ValueRef,
+};
+
+use super::super::metadata::lookup_string;
+use super::super::{
+    BytecodeError, BytecodeModule, ConstEntry, DebugEntry, SectionData, SectionId, StringTable,
+    TypeData, TypeEntry, TypeTable,
+};
+use super::{BytecodeVm, PouKind, VmPou};
+
+impl BytecodeVm {
+    pub(super) fn load(
+        module: &BytecodeModule,
+        sources: Option<&[&str]>,
+        paths: Option<&[&str]>,
+    ) -> Result<Self, BytecodeError> {
+        module.validate()?;
+        let strings = match module.section(SectionId::StringTable) {
+            Some(SectionData::StringTable(table)) => table,
+            _ => return Err(BytecodeError::MissingSection("STRING_TABLE".into())),
+        };
+        let types = match module.section(SectionId::TypeTable) {
+            Some(SectionData::TypeTable(table)) => table,
+            _ => return Err(BytecodeError::MissingSection("TYPE_TABLE".into())),
+        };
+        let const_pool = match module.section(SectionId::ConstPool) {
+            Some(SectionData::ConstPool(pool)) => pool,
+            _ => return Err(BytecodeError::MissingSection("CONST_POOL".into())),
+        };
+        let ref_table = match module.section(SectionId::RefTable) {
+            Some(SectionData::RefTable(table)) => table,
+            _ => return Err(BytecodeError::MissingSection("REF_TABLE".into())),
+        };
+        let pou_index = match module.section(SectionId::PouIndex) {
+            Some(SectionData::PouIndex(index)) => index,
+            _ => return Err(BytecodeError::MissingSection("POU_INDEX".into())),
+        };
+        let code = match module.section(SectionId::PouBodies) {
+            Some(SectionData::PouBodies(bodies)) => bodies.clone(),
+            _ => return Err(BytecodeError::MissingSection("POU_BODIES".into())),
+        };
+        let debug_map = match module.section(SectionId::DebugMap) {
+            Some(SectionData::DebugMap(map)) => Some(map),
+            _ => None,
+        };
+        let debug_strings = match module.section(SectionId::DebugStringTable) {
+            Some(SectionData::DebugStringTable(table)) => table,
+            _ => strings,
+        };
+
+        let type_names = types
+            .entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .name_idx
+                    .map(|idx| lookup_string(strings, idx))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let refs = ref_table
+            .entries
+            .iter()
+            .map(|entry| entry.to_value_ref(strings))
+            .collect::<Result<Vec<_>, _>>()?;
+        let consts = const_pool
+            .entries
+            .iter()
+            .map(|entry| decode_const(strings, types, &refs, entry))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let pou_ids: HashMap<u32, usize> = pou_index
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| (entry.id, idx))
+            .collect();
+        let mut pous = Vec::with_capacity(pou_index.entries.len());
+        let mut programs = HashMap::new();
+        let mut class_likes = HashMap::new();
+        for entry in &pou_index.entries {
+            let name = lookup_string(strings, entry.name_idx)?;
+            let local_frame = refs
+                .get(entry.local_ref_start as usize)
+                .filter(|_| entry.local_ref_count > 0)
+                .and_then(|reference| match reference.location {
+                    MemoryLocation::Local(frame_id) => Some(frame_id),
+                    _ => None,
+                });
+            let code_start = entry.code_offset as usize;
+            let mut statements: HashMap<usize, Vec<SourceLocation>> = HashMap::new();
+            if let Some(debug_map) = debug_map {
+                for debug in debug_map
+                    .entries
+                    .iter()
+                    .filter(|debug| debug.pou_id == entry.id)
+                {
+                    let offset = (debug.code_offset as usize).saturating_sub(code_start);
+                    let location = statement_location(debug_strings, sources, paths, debug)?;
+                    statements.entry(offset).or_default().extend(location);
+                }
+            }
+            match entry.kind {
+                PouKind::Program => {
+                    programs.insert(SmolStr::new(name.to_ascii_uppercase()), pous.len());
+                }
+                PouKind::FunctionBlock | PouKind::Class => {
+                    class_likes.insert(SmolStr::new(name.to_ascii_uppercase()), pous.len());
+                }
+                PouKind::Function | PouKind::Method => {}
+            }
+            let owner = entry
+                .owner_pou_id
+                .map(|id| pou_index_of(&pou_ids, id))
+                .transpose()?;
+            let mut methods = Vec::new();
+            if let Some(meta) = &entry.class_meta {
+                for method in &meta.methods {
+                    let slot = method.vtable_slot as usize;
+                    if methods.len() <= slot {
+                        methods.resize(slot + 1, usize::MAX);
+                    }
+                    methods[slot] = pou_index_of(&pou_ids, method.pou_id)?;
+                }
+            }
+            pous.push(VmPou {
+                name,
+                kind: entry.kind,
+                code_start,
+                code_end: code_start + entry.code_length as usize,
+                local_frame,
+                local_ref_count: entry.local_ref_count as usize,
+                owner,
+                has_return: entry.return_type_id.is_some(),
+                methods,
+                statements,
+            });
+        }
+
+        let interfaces = types
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| match &entry.data {
+                TypeData::Interface { methods } => Some((idx as u32, methods)),
+                _ => None,
+            })
+            .map(|(idx, methods)| {
+                let mut names = vec![SmolStr::default(); methods.len()];
+                for method in methods {
+                    let name = lookup_string(strings, method.name_idx)?;
+                    match names.get_mut(method.slot as usize) {
+                        Some(slot) => *slot = name,
+                        None => {
+                            return Err(BytecodeError::InvalidIndex {
+                                kind: "interface slot".into(),
+                                index: method.slot,
+                            })
+                        }
+                    }
+                }
+                Ok((idx, names))
+            })
+            .collect::<Result<HashMap<_, _>, BytecodeError>>()?;
+
+        Ok(Self {
+            code,
+            strings: strings.entries.clone(),
+            type_names,
+            consts,
+            refs,
+            pous,
+            programs,
+            pou_ids,
+            class_likes,
+            interfaces,
+        })
+    }
+}
+
+fn pou_index_of(pou_ids: &HashMap<u32, usize>, id: u32) -> Result<usize, BytecodeError> {
+    pou_ids
+        .get(&id)
+        .copied()
+        .ok_or(BytecodeError::InvalidPouId(id))
+}
+
+fn decode_const(
+    strings: &StringTable,
+    types: &TypeTable,
+    refs: &[ValueRef],
+    entry: &ConstEntry,
+) -> Result<Value, BytecodeError> {
+    let ty = type_entry(types, entry.type_id)?;
+    let payload = entry.payload.as_slice();
+    match &ty.data {
+        TypeData::Primitive { prim_id, .. } => decode_primitive(strings, *prim_id, payload),
+        TypeData::Reference { .. } => match u32::from_le_bytes(fixed(payload)?) {
+            u32::MAX => Ok(Value::Null),
+            idx => refs
+                .get(idx as usize)
+                .cloned()
+                .map(|reference| Value::Reference(Some(reference)))
+                .ok_or_else(|| BytecodeError::InvalidIndex {
+                    kind: "ref".into(),
+                    index: idx,
+                }),
+        },
+        TypeData::Enum { variants, .. } => {
+            let numeric_value = i64::from_le_bytes(fixed(payload)?);
+            let type_name = match ty.name_idx {
+                Some(idx) => lookup_string(strings, idx)?,
+                None => {
+                    return Err(BytecodeError::InvalidSection(
+                        "enum const without name".into(),
+                    ))
+                }
+            };
+            let variant_name = match variants
+                .iter()
+                .find(|variant| variant.value == numeric_value)
+            {
+                Some(variant) => lookup_string(strings, variant.name_idx)?,
+                None => SmolStr::new(numeric_value.to_string()),
+            };
+            Ok(Value::Enum(EnumValue {
+                type_name,
+                variant_name,
+                numeric_value,
+            }))
+        }
+        TypeData::Alias { target_type_id }
+        | TypeData::Subrange {
+            base_type_id: target_type_id,
+            ..
+        } => decode_const(
+            strings,
+            types,
+            refs,
+            &ConstEntry {
+                type_id: *target_type_id,
+                payload: entry.payload.clone(),
+            },
+        ),
+        _ => Err(BytecodeError::InvalidSection(
+            "unsupported const pool type".into(),
+        )),
+    }
+}
+
+fn decode_primitive(
+    strings: &StringTable,
+    prim_id: u16,
+    payload: &[u8],
+) -> Result<Value, BytecodeError> {
+    let value = match prim_id {
+        1 => Value::Bool(u8::from_le_bytes(fixed(payload)?) != 0),
+        2 => Value::Byte(u8::from_le_bytes(fixed(payload)?)),
+        3 => Value::Word(u16::from_le_bytes(fixed(payload)?)),
+        4 => Value::DWord(u32::from_le_bytes(fixed(payload)?)),
+        5 => Value::LWord(u64::from_le_bytes(fixed(payload)?)),
+        6 => Value::SInt(i8::from_le_bytes(fixed(payload)?)),
+        7 => Value::Int(i16::from_le_bytes(fixed(payload)?)),
+        8 => Value::DInt(i32::from_le_bytes(fixed(payload)?)),
+        9 => Value::LInt(i64::from_le_bytes(fixed(payload)?)),
+        10 => Value::USInt(u8::from_le_bytes(fixed(payload)?)),
+        11 => Value::UInt(u16::from_le_bytes(fixed(payload)?)),
+        12 => Value::UDInt(u32::from_le_bytes(fixed(payload)?)),
+        13 => Value::ULInt(u64::from_le_bytes(fixed(payload)?)),
+        14 => Value::Real(f32::from_le_bytes(fixed(payload)?)),
+        15 => Value::LReal(f64::from_le_bytes(fixed(payload)?)),
+        16 => Value::Time(Duration::from_nanos(i64::from_le_bytes(fixed(payload)?))),
+        17 => Value::LTime(Duration::from_nanos(i64::from_le_bytes(fixed(payload)?))),
+        18 => Value::Date(DateValue::new(i64::from_le_bytes(fixed(payload)?))),
+        19 => Value::LDate(LDateValue::new(i64::from_le_bytes(fixed(payload)?))),
+        20 => Value::Tod(TimeOfDayValue::new(i64::from_le_bytes(fixed(payload)?))),
+        21 => Value::LTod(LTimeOfDayValue::new(i64::from_le_bytes(fixed(payload)?))),
+        22 => Value::Dt(DateTimeValue::new(i64::from_le_bytes(fixed(payload)?))),
+        23 => Value::Ldt(LDateTimeValue::new(i64::from_le_bytes(fixed(payload)?))),
+        24 => Value::String(lookup_string(strings, u32::from_le_bytes(fixed(payload)?))?),
+        25 => Value::WString(lookup_string(strings, u32::from_le_bytes(fixed(payload)?))?.into()),
+        26 => Value::Char(u8::from_le_bytes(fixed(payload)?)),
+        27 => Value::WChar(u16::from_le_bytes(fixed(payload)?)),
+        _ => {
+            return Err(BytecodeError::InvalidSection(
+                format!("unsupported const primitive {prim_id}").into(),
+            ))
+        }
+    };
+    Ok(value)
+}
+
+fn type_entry(types: &TypeTable, idx: u32) -> Result<&TypeEntry, BytecodeError> {
+    types
+        .entries
+        .get(idx as usize)
+        .ok_or_else(|| BytecodeError::InvalidIndex {
+            kind: "type".into(),
+            index: idx,
+        })
+}
+
+fn fixed<const N: usize>(payload: &[u8]) -> Result<[u8; N], BytecodeError> {
+    payload
+        .try_into()
+        .map_err(|_| BytecodeError::InvalidSection("const payload size mismatch".into()))
+}
+
+/// Rebuild a statement location from a 1-based line/column debug entry.
+///
+/// The span covers the rest of the source line, which is enough for
+/// breakpoint matching on overlapping ranges.
+fn statement_location(
+    file_strings: &StringTable,
+    sources: Option<&[&str]>,
+    paths: Option<&[&str]>,
+    entry: &DebugEntry,
+) -> Result<Option<SourceLocation>, BytecodeError> {
+    let Some(sources) = sources else {
+        return Ok(None);
+    };
+    let label = lookup_string(file_strings, entry.file_idx)?;
+    let file_id = match paths {
+        Some(paths) => paths.iter().position(|path| *path == label.as_str()),
+        None => label
+            .strip_prefix("file_")
+            .and_then(|id| id.parse::<usize>().ok()),
+    };
+    let Some(file_id) = file_id else {
+        return Ok(None);
+    };
+    let Some(source) = sources.get(file_id) else {
+        return Ok(None);
+    };
+    let mut line_start = 0usize;
+    let mut lines = source.split_inclusive('\n');
+    for _ in 1..entry.line {
+        match lines.next() {
+            Some(line) => line_start += line.len(),
+            None => return Ok(None),
+        }
+    }
+    let Some(line) = lines.next() else {
+        return Ok(None);
+    };
+    let line_end = line_start + line.trim_end_matches(['\r', '\n']).len();
+    let start = (line_start + entry.column.saturating_sub(1) as usize).min(line_end);
+    Ok(Some(SourceLocation {
+        file_id: file_id as u32,
+        start: start as u32,
+        end: line_end.max(start + 1) as u32,
+    }))
+}
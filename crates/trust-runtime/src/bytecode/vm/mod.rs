@@ -0,0 +1,126 @@
+//! Bytecode interpreter for POU bodies.
+//!
+//! The VM executes the instruction streams stored in POU_BODIES against the
+//! same `VariableStorage`, debug hook and standard library as the tree-walking
+//! evaluator. Instance layouts and variable initialization come from the
+//! runtime the module is loaded into, which is either compiled from sources or
+//! restored from the module's POU_DECLS and INIT_IMAGE sections.
+
+#![allow(missing_docs)]
+
+mod call;
+mod exec;
+mod load;
+
+use std::collections::HashMap;
+
+use smol_str::SmolStr;
+
+use crate::debug::SourceLocation;
+use crate::memory::FrameId;
+use crate::value::{Value, ValueRef};
+
+use super::{BytecodeError, BytecodeModule, PouKind};
+
+/// Executes POU bodies from a decoded bytecode module.
+#[derive(Debug, Clone)]
+pub struct BytecodeVm {
+    code: Vec<u8>,
+    strings: Vec<SmolStr>,
+    type_names: Vec<Option<SmolStr>>,
+    consts: Vec<Value>,
+    refs: Vec<ValueRef>,
+    pous: Vec<VmPou>,
+    programs: HashMap<SmolStr, usize>,
+    /// POU id to index into `pous`.
+    pou_ids: HashMap<u32, usize>,
+    /// FUNCTION_BLOCK and CLASS POUs by upper-case name.
+    class_likes: HashMap<SmolStr, usize>,
+    /// Interface type index to method names by slot.
+    interfaces: HashMap<u32, Vec<SmolStr>>,
+}
+
+#[derive(Debug, Clone)]
+struct VmPou {
+    name: SmolStr,
+    kind: PouKind,
+    code_start: usize,
+    code_end: usize,
+    local_frame: Option<FrameId>,
+    local_ref_count: usize,
+    /// Owning FUNCTION_BLOCK/CLASS of a method.
+    owner: Option<usize>,
+    /// Slot 0 of the frame holds the return value.
+    has_return: bool,
+    /// Method POUs by vtable slot (FUNCTION_BLOCK and CLASS only).
+    methods: Vec<usize>,
+    /// Statement boundaries as offsets relative to `code_start`.
+    statements: HashMap<usize, Vec<SourceLocation>>,
+}
+
+impl BytecodeVm {
+    /// Build a VM from a bytecode module without source mapping.
+    pub fn new(module: &BytecodeModule) -> Result<Self, BytecodeError> {
+        Self::load(module, None, None)
+    }
+
+    /// Build a VM and map debug entries back to the given sources.
+    ///
+    /// Sources must be passed in the order used when the module was encoded.
+    pub fn with_sources(module: &BytecodeModule, sources: &[&str]) -> Result<Self, BytecodeError> {
+        Self::load(module, Some(sources), None)
+    }
+
+    /// Build a VM and map debug entries back to sources identified by path.
+    pub fn with_sources_and_paths(
+        module: &BytecodeModule,
+        sources: &[&str],
+        paths: &[&str],
+    ) -> Result<Self, BytecodeError> {
+        Self::load(module, Some(sources), Some(paths))
+    }
+
+    /// Returns true when the module holds a body for the program.
+    #[must_use]
+    pub fn has_program_body(&self, name: &str) -> bool {
+        self.program(name).is_some()
+    }
+
+    /// Names of programs that execute from bytecode.
+    pub fn program_names(&self) -> impl Iterator<Item = &SmolStr> {
+        self.pous
+            .iter()
+            .filter(|pou| pou.kind == PouKind::Program)
+            .map(|pou| &pou.name)
+    }
+
+    /// Returns true when both VMs address the same storage layout.
+    ///
+    /// Compares the resolved reference table and the POU index, which is what
+    /// ties instruction operands to a runtime's instances and frames.
+    #[must_use]
+    pub fn layout_matches(&self, other: &Self) -> bool {
+        self.refs == other.refs
+            && self.pous.len() == other.pous.len()
+            && self
+                .pous
+                .iter()
+                .zip(&other.pous)
+                .all(|(left, right)| left.name == right.name && left.kind == right.kind)
+    }
+
+    /// Decoded CONST_POOL entries, by index.
+    pub(super) fn consts(&self) -> &[Value] {
+        &self.consts
+    }
+
+    /// Resolved REF_TABLE entries, by index.
+    pub(super) fn refs(&self) -> &[ValueRef] {
+        &self.refs
+    }
+
+    fn program(&self, name: &str) -> Option<&VmPou> {
+        let key = SmolStr::new(name.to_ascii_uppercase());
+        self.programs.get(&key).map(|idx| &self.pous[*idx])
+    }
+}
@@ -11,7 +11,7 @@ use smol_str::SmolStr;
 
 use crate::error::RuntimeError;
 use crate::historian::{AlertRule, HistorianConfig, RecordingMode};
-use crate::io::{IoAddress, IoSafeState, IoSize};
+use crate::io::{IoAddress, IoSafeState, IoSize, SparkplugConfig};
 use crate::opcua::{
     OpcUaMessageSecurityMode, OpcUaRuntimeConfig, OpcUaSecurityPolicy, OpcUaSecurityProfile,
 };
@@ -30,6 +30,7 @@ pub struct RuntimeConfig {
     pub bundle_version: u32,
     pub resource_name: SmolStr,
     pub cycle_interval: Duration,
+    pub task_threads: bool,
     pub control_endpoint: SmolStr,
     pub control_auth_token: Option<SmolStr>,
     pub control_debug_enabled: bool,
@@ -47,6 +48,7 @@ pub struct RuntimeConfig {
     pub mesh: MeshConfig,
     pub observability: HistorianConfig,
     pub opcua: OpcUaRuntimeConfig,
+    pub sparkplug: Option<SparkplugConfig>,
     pub tasks: Option<Vec<TaskOverride>>,
 }
 
@@ -114,6 +116,7 @@ pub struct TlsConfig {
 pub struct DeployConfig {
     pub require_signed: bool,
     pub keyring_path: Option<PathBuf>,
+    pub required_signers: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -308,6 +311,7 @@ struct BundleSection {
 struct ResourceSection {
     name: String,
     cycle_interval_ms: u64,
+    task_threads: Option<bool>,
     tasks: Option<Vec<TaskSection>>,
 }
 
@@ -336,6 +340,8 @@ struct RuntimeSection {
     mesh: Option<MeshSection>,
     observability: Option<ObservabilitySection>,
     opcua: Option<OpcUaSection>,
+    /// Parsed by [`SparkplugConfig::from_section`] (shares the mqtt driver keys).
+    sparkplug: Option<toml::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -417,6 +423,7 @@ struct TlsSection {
 struct DeploySection {
     require_signed: Option<bool>,
     keyring_path: Option<String>,
+    required_signers: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -692,7 +699,13 @@ impl RuntimeToml {
         let deploy_section = self.runtime.deploy.unwrap_or(DeploySection {
             require_signed: Some(false),
             keyring_path: None,
+            required_signers: None,
         });
+        if deploy_section.required_signers == Some(0) {
+            return Err(RuntimeError::InvalidConfig(
+                "runtime.deploy.required_signers must be >= 1".into(),
+            ));
+        }
         if deploy_section
             .keyring_path
             .as_deref()
@@ -1046,11 +1059,16 @@ impl RuntimeToml {
             username,
             password,
         };
+        let sparkplug = match self.runtime.sparkplug.as_ref() {
+            Some(section) => SparkplugConfig::from_section(section, &self.resource.name)?,
+            None => None,
+        };
 
         Ok(RuntimeConfig {
             bundle_version: self.bundle.version,
             resource_name: SmolStr::new(self.resource.name),
             cycle_interval: Duration::from_millis(self.resource.cycle_interval_ms as i64),
+            task_threads: self.resource.task_threads.unwrap_or(false),
             control_endpoint: SmolStr::new(self.runtime.control.endpoint),
             control_auth_token,
             control_debug_enabled: debug_enabled,
@@ -1090,6 +1108,7 @@ impl RuntimeToml {
                         Some(PathBuf::from(path))
                     }
                 }),
+                required_signers: deploy_section.required_signers.unwrap_or(1),
             },
             discovery: DiscoveryConfig {
                 enabled: discovery_section.enabled.unwrap_or(true),
@@ -1143,6 +1162,7 @@ impl RuntimeToml {
                 alerts,
             },
             opcua,
+            sparkplug,
             tasks,
         })
     }
@@ -1391,6 +1411,18 @@ params = {}
         ));
     }
 
+    #[test]
+    fn runtime_schema_rejects_zero_required_signers() {
+        let text = format!(
+            "{}\n[runtime.deploy]\nrequire_signed = true\nkeyring_path = \"keys.toml\"\nrequired_signers = 0\n",
+            runtime_toml()
+        );
+        let err = validate_runtime_toml_text(&text).expect_err("zero quorum should fail");
+        assert!(err
+            .to_string()
+            .contains("runtime.deploy.required_signers must be >= 1"));
+    }
+
     #[test]
     fn runtime_schema_requires_tls_credentials_when_tls_enabled() {
         let text = format!(
@@ -1473,6 +1505,18 @@ params = {}
             .contains("runtime.opcua.endpoint_path must start with '/'"));
     }
 
+    #[test]
+    fn runtime_schema_requires_sparkplug_ids_when_enabled() {
+        let text = format!(
+            "{}\n[runtime.sparkplug]\nenabled = true\nbroker = \"127.0.0.1:1883\"\ngroup_id = \"Plant\"\n",
+            runtime_toml()
+        );
+        let err = validate_runtime_toml_text(&text).expect_err("missing edge node id should fail");
+        assert!(err
+            .to_string()
+            .contains("runtime.sparkplug.edge_node_id must be non-empty"));
+    }
+
     #[test]
     fn runtime_schema_requires_opcua_credentials_or_anonymous_when_enabled() {
         let text = format!("{}\n[runtime.opcua]\nenabled = true\n", runtime_toml());
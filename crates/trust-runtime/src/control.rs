@@ -3,6 +3,7 @@
 #![allow(missing_docs)]
 
 mod handlers;
+mod subscriptions;
 mod transport;
 
 use std::collections::VecDeque;
@@ -21,7 +22,7 @@ use crate::error::RuntimeError;
 use crate::io::{IoAddress, IoDriverHealth, IoDriverStatus, IoSnapshot};
 use crate::metrics::RuntimeMetrics;
 use crate::runtime::RuntimeMetadata;
-use crate::scheduler::{ResourceCommand, ResourceControl};
+use crate::scheduler::{OnlineChangeProgram, ResourceCommand, ResourceControl};
 use crate::security::AccessRole;
 use crate::settings::RuntimeSettings;
 use crate::value::Value;
@@ -32,6 +33,7 @@ use base64::Engine;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use smol_str::SmolStr;
+use subscriptions::ControlSession;
 use tracing::debug;
 
 #[derive(Debug, Clone)]
@@ -166,13 +168,16 @@ impl ControlServer {
     }
 }
 
+/// Handles one request line; `session` is set on connections that can carry
+/// subscriptions.
 pub(crate) fn handle_request_line(
     line: &str,
     state: &ControlState,
     client: Option<&str>,
+    session: Option<&ControlSession>,
 ) -> Option<String> {
     let response = match serde_json::from_str::<serde_json::Value>(line) {
-        Ok(value) => handle_request_value(value, state, client),
+        Ok(value) => handle_session_request_value(value, state, client, session),
         Err(err) => ControlResponse::error(0, format!("invalid request: {err}")),
     };
     serde_json::to_string(&response).ok()
@@ -182,6 +187,15 @@ pub(crate) fn handle_request_value(
     value: serde_json::Value,
     state: &ControlState,
     client: Option<&str>,
+) -> ControlResponse {
+    handle_session_request_value(value, state, client, None)
+}
+
+fn handle_session_request_value(
+    value: serde_json::Value,
+    state: &ControlState,
+    client: Option<&str>,
+    session: Option<&ControlSession>,
 ) -> ControlResponse {
     let request: ControlRequest = match serde_json::from_value(value) {
         Ok(req) => req,
@@ -244,7 +258,8 @@ pub(crate) fn handle_request_value(
         );
         return response;
     }
-    let response = handlers::dispatch(&request, state)
+    let response = subscriptions::dispatch(&request, state, session, request_role)
+        .or_else(|| handlers::dispatch(&request, state))
         .unwrap_or_else(|| ControlResponse::error(request.id, "unsupported request".into()));
     record_audit(
         state,
@@ -311,6 +326,8 @@ fn is_debug_request(kind: &str) -> bool {
             | "debug.variables"
             | "debug.evaluate"
             | "debug.breakpoint_locations"
+            | "debug.threads"
+            | "debug.non_stop"
     )
 }
 
@@ -366,8 +383,11 @@ fn required_role_for_control_request(kind: &str, params: Option<&serde_json::Val
         | "debug.scopes"
         | "debug.variables"
         | "debug.breakpoint_locations"
+        | "debug.threads"
         | "breakpoints.list"
-        | "var.forced" => AccessRole::Viewer,
+        | "var.forced"
+        | "subscribe"
+        | "unsubscribe" => AccessRole::Viewer,
         "pause" | "resume" | "restart" | "hmi.alarm.ack" | "pair.claim" => AccessRole::Operator,
         "step_in"
         | "step_over"
@@ -384,11 +404,15 @@ fn required_role_for_control_request(kind: &str, params: Option<&serde_json::Val
         | "io.force"
         | "io.unforce"
         | "debug.evaluate"
+        | "debug.non_stop"
         | "hmi.write" => AccessRole::Engineer,
         "config.set" => required_role_for_config_set(params),
-        "shutdown" | "bytecode.reload" | "pair.start" | "pair.list" | "pair.revoke" => {
-            AccessRole::Admin
-        }
+        "shutdown"
+        | "bytecode.reload"
+        | "program.online_change"
+        | "pair.start"
+        | "pair.list"
+        | "pair.revoke" => AccessRole::Admin,
         _ => AccessRole::Viewer,
     }
 }
@@ -701,10 +725,27 @@ fn handle_hmi_alarms_get(
         },
         None => HmiAlarmsParams::default(),
     };
-    let metadata = match state.metadata.lock() {
-        Ok(guard) => guard,
-        Err(_) => return ControlResponse::error(id, "metadata unavailable".into()),
+    let result = match refresh_hmi_alarms(state, params.limit.unwrap_or(100)) {
+        Ok(result) => result,
+        Err(err) => return ControlResponse::error(id, err),
     };
+    ControlResponse::ok(
+        id,
+        serde_json::to_value(result).expect("serialize hmi.alarms.get"),
+    )
+}
+
+/// Evaluate the HMI alarm state machine against the current values and
+/// return the alarm view. Shared by `hmi.alarms.get` and the OPC UA
+/// Alarms & Conditions feed.
+pub(crate) fn refresh_hmi_alarms(
+    state: &ControlState,
+    history_limit: usize,
+) -> Result<crate::hmi::HmiAlarmResult, String> {
+    let metadata = state
+        .metadata
+        .lock()
+        .map_err(|_| "metadata unavailable".to_string())?;
     let snapshot = load_runtime_snapshot(state);
     let customization = load_hmi_customization(state);
     let schema = crate::hmi::build_schema(
@@ -721,17 +762,12 @@ fn handle_hmi_alarms_get(
         true,
         None,
     );
-    let result = match state.hmi_live.lock() {
-        Ok(mut live) => {
-            crate::hmi::update_live_state(&mut live, &schema, &values);
-            crate::hmi::build_alarm_view(&live, params.limit.unwrap_or(100))
-        }
-        Err(_) => return ControlResponse::error(id, "hmi state unavailable".into()),
-    };
-    ControlResponse::ok(
-        id,
-        serde_json::to_value(result).expect("serialize hmi.alarms.get"),
-    )
+    let mut live = state
+        .hmi_live
+        .lock()
+        .map_err(|_| "hmi state unavailable".to_string())?;
+    crate::hmi::update_live_state(&mut live, &schema, &values);
+    Ok(crate::hmi::build_alarm_view(&live, history_limit))
 }
 
 fn handle_hmi_alarm_ack(
@@ -746,18 +782,9 @@ fn handle_hmi_alarm_ack(
         },
         None => return ControlResponse::error(id, "missing params".into()),
     };
-    let timestamp_ms = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis();
-    let result = match state.hmi_live.lock() {
-        Ok(mut live) => {
-            match crate::hmi::acknowledge_alarm(&mut live, params.id.as_str(), timestamp_ms) {
-                Ok(()) => crate::hmi::build_alarm_view(&live, 100),
-                Err(err) => return ControlResponse::error(id, err),
-            }
-        }
-        Err(_) => return ControlResponse::error(id, "hmi state unavailable".into()),
+    let result = match acknowledge_hmi_alarm(state, params.id.as_str()) {
+        Ok(result) => result,
+        Err(err) => return ControlResponse::error(id, err),
     };
     ControlResponse::ok(
         id,
@@ -765,6 +792,23 @@ fn handle_hmi_alarm_ack(
     )
 }
 
+/// Acknowledge an active HMI alarm and return the updated alarm view.
+pub(crate) fn acknowledge_hmi_alarm(
+    state: &ControlState,
+    alarm_id: &str,
+) -> Result<crate::hmi::HmiAlarmResult, String> {
+    let timestamp_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let mut live = state
+        .hmi_live
+        .lock()
+        .map_err(|_| "hmi state unavailable".to_string())?;
+    crate::hmi::acknowledge_alarm(&mut live, alarm_id, timestamp_ms)?;
+    Ok(crate::hmi::build_alarm_view(&live, 100))
+}
+
 fn handle_hmi_write(
     id: u64,
     params: Option<serde_json::Value>,
@@ -1492,7 +1536,18 @@ fn expect_string_map(
     Ok(output)
 }
 
-fn handle_pause(id: u64, state: &ControlState) -> ControlResponse {
+fn handle_pause(
+    id: u64,
+    params: Option<serde_json::Value>,
+    state: &ControlState,
+) -> ControlResponse {
+    let params: DebugThreadParams = match params {
+        Some(value) => match serde_json::from_value(value) {
+            Ok(parsed) => parsed,
+            Err(err) => return ControlResponse::error(id, format!("invalid params: {err}")),
+        },
+        None => DebugThreadParams::default(),
+    };
     let mode = state
         .control_mode
         .lock()
@@ -1501,14 +1556,25 @@ fn handle_pause(id: u64, state: &ControlState) -> ControlResponse {
     if matches!(mode, ControlMode::Debug) {
         let _ = state
             .debug
-            .apply_action(crate::debug::ControlAction::Pause(None));
+            .apply_action(crate::debug::ControlAction::Pause(params.thread_id));
     } else {
         let _ = state.resource.pause();
     }
     ControlResponse::ok(id, json!({"status": "paused"}))
 }
 
-fn handle_resume(id: u64, state: &ControlState) -> ControlResponse {
+fn handle_resume(
+    id: u64,
+    params: Option<serde_json::Value>,
+    state: &ControlState,
+) -> ControlResponse {
+    let params: DebugThreadParams = match params {
+        Some(value) => match serde_json::from_value(value) {
+            Ok(parsed) => parsed,
+            Err(err) => return ControlResponse::error(id, format!("invalid params: {err}")),
+        },
+        None => DebugThreadParams::default(),
+    };
     let mode = state
         .control_mode
         .lock()
@@ -1517,7 +1583,7 @@ fn handle_resume(id: u64, state: &ControlState) -> ControlResponse {
     if matches!(mode, ControlMode::Debug) {
         let _ = state
             .debug
-            .apply_action(crate::debug::ControlAction::Continue);
+            .apply_action(crate::debug::ControlAction::Continue(params.thread_id));
     } else {
         let _ = state.resource.resume();
     }
@@ -1531,11 +1597,23 @@ enum StepKind {
     Out,
 }
 
-fn handle_step(id: u64, state: &ControlState, kind: StepKind) -> ControlResponse {
+fn handle_step(
+    id: u64,
+    params: Option<serde_json::Value>,
+    state: &ControlState,
+    kind: StepKind,
+) -> ControlResponse {
+    let params: DebugThreadParams = match params {
+        Some(value) => match serde_json::from_value(value) {
+            Ok(parsed) => parsed,
+            Err(err) => return ControlResponse::error(id, format!("invalid params: {err}")),
+        },
+        None => DebugThreadParams::default(),
+    };
     let action = match kind {
-        StepKind::In => crate::debug::ControlAction::StepIn(None),
-        StepKind::Over => crate::debug::ControlAction::StepOver(None),
-        StepKind::Out => crate::debug::ControlAction::StepOut(None),
+        StepKind::In => crate::debug::ControlAction::StepIn(params.thread_id),
+        StepKind::Over => crate::debug::ControlAction::StepOver(params.thread_id),
+        StepKind::Out => crate::debug::ControlAction::StepOut(params.thread_id),
     };
     let _ = state.debug.apply_action(action);
     ControlResponse::ok(id, json!({"status": "stepping"}))
@@ -1566,7 +1644,87 @@ fn handle_debug_stops(id: u64, state: &ControlState) -> ControlResponse {
     ControlResponse::ok(id, json!({ "stops": stops }))
 }
 
-fn handle_debug_stack(id: u64, state: &ControlState) -> ControlResponse {
+fn handle_debug_threads(id: u64, state: &ControlState) -> ControlResponse {
+    let metadata = match state.metadata.lock() {
+        Ok(guard) => guard,
+        Err(_) => return ControlResponse::error(id, "metadata unavailable".into()),
+    };
+    let mut threads = Vec::new();
+    for (idx, task) in metadata.tasks().iter().enumerate() {
+        let thread_id = metadata
+            .task_thread_id(&task.name)
+            .unwrap_or(idx as u32 + 1);
+        threads.push(json!({
+            "id": thread_id,
+            "name": task.name.as_str(),
+            "stopped": state.debug.thread_stopped(thread_id),
+        }));
+    }
+    if metadata.has_background_programs() {
+        if let Some(thread_id) = metadata.background_thread_id() {
+            threads.push(json!({
+                "id": thread_id,
+                "name": "Background",
+                "stopped": state.debug.thread_stopped(thread_id),
+            }));
+        }
+    }
+    if threads.is_empty() {
+        let stopped = state.debug.stopped_thread().is_some();
+        threads.push(json!({ "id": 1, "name": "Main", "stopped": stopped }));
+    }
+    ControlResponse::ok(
+        id,
+        json!({
+            "threads": threads,
+            "non_stop": state.debug.non_stop(),
+        }),
+    )
+}
+
+fn handle_debug_non_stop(
+    id: u64,
+    params: Option<serde_json::Value>,
+    state: &ControlState,
+) -> ControlResponse {
+    let params: DebugNonStopParams = match params {
+        Some(value) => match serde_json::from_value(value) {
+            Ok(parsed) => parsed,
+            Err(err) => return ControlResponse::error(id, format!("invalid params: {err}")),
+        },
+        None => return ControlResponse::error(id, "missing params".into()),
+    };
+    state.debug.set_non_stop(params.enabled);
+    ControlResponse::ok(id, json!({ "non_stop": params.enabled }))
+}
+
+fn handle_debug_stack(
+    id: u64,
+    params: Option<serde_json::Value>,
+    state: &ControlState,
+) -> ControlResponse {
+    let params: DebugThreadParams = match params {
+        Some(value) => match serde_json::from_value(value) {
+            Ok(parsed) => parsed,
+            Err(err) => return ControlResponse::error(id, format!("invalid params: {err}")),
+        },
+        None => DebugThreadParams::default(),
+    };
+    // Only stopped tasks have a captured call stack. In non-stop mode each
+    // stopped task has its own; the scopes and variables that follow use it.
+    if let Some(requested) = params.thread_id {
+        let has_stack = if state.debug.non_stop() {
+            state.debug.focus_thread(requested)
+        } else {
+            state
+                .debug
+                .stopped_thread()
+                .is_none_or(|stopped| stopped == requested)
+        };
+        if !has_stack {
+            return ControlResponse::ok(id, json!({ "stack_frames": [], "total_frames": 0 }));
+        }
+    }
     let snapshot = match state.debug.snapshot() {
         Some(snapshot) => snapshot,
         None => return ControlResponse::error(id, "no snapshot available".into()),
@@ -1964,7 +2122,7 @@ fn handle_debug_evaluate(
         Err(err) => return ControlResponse::error(id, err.to_string()),
     };
     let result = crate::debug::dap::format_value(&value);
-    let type_name = crate::debug::dap::value_type_name(&value);
+    let type_name = crate::value::value_type_name(&value);
     ControlResponse::ok(
         id,
         json!({
@@ -2033,10 +2191,19 @@ fn debug_stop_to_json(
         crate::debug::DebugStopReason::Step => "step",
         crate::debug::DebugStopReason::Pause => "pause",
         crate::debug::DebugStopReason::Entry => "entry",
+        crate::debug::DebugStopReason::DataBreakpoint => "data breakpoint",
+        crate::debug::DebugStopReason::FunctionBreakpoint => "function breakpoint",
+        crate::debug::DebugStopReason::Exception => "exception",
     };
+    let thread_name = stop
+        .thread_id
+        .and_then(|thread_id| state.debug.thread_name(thread_id))
+        .map(|name| name.to_string());
     let mut payload = json!({
         "reason": reason,
         "thread_id": stop.thread_id,
+        "thread_name": thread_name,
+        "all_threads_stopped": !state.debug.non_stop(),
         "breakpoint_generation": stop.breakpoint_generation,
     });
     if let Some(location) = stop.location {
@@ -2585,6 +2752,117 @@ fn handle_bytecode_reload(
     }
 }
 
+fn handle_online_change(
+    id: u64,
+    params: Option<serde_json::Value>,
+    state: &ControlState,
+) -> ControlResponse {
+    let params: OnlineChangeParams = match params {
+        Some(value) => match serde_json::from_value(value) {
+            Ok(parsed) => parsed,
+            Err(err) => return ControlResponse::error(id, format!("invalid params: {err}")),
+        },
+        None => OnlineChangeParams::default(),
+    };
+    let sources = match params.sources {
+        Some(sources) => sources
+            .into_iter()
+            .map(|source| crate::harness::SourceFile::with_path(source.path, source.text))
+            .collect(),
+        None => match state.project_root.as_deref().map(load_project_sources) {
+            Some(Ok(sources)) => sources,
+            Some(Err(err)) => return ControlResponse::error(id, err),
+            None => return ControlResponse::error(id, "project root unavailable".into()),
+        },
+    };
+    if sources.is_empty() {
+        return ControlResponse::error(id, "no sources to compile".into());
+    }
+    let session = match state.project_root.as_deref() {
+        Some(root) => match crate::bundle_builder::project_compile_session(root, sources) {
+            Ok(session) => session,
+            Err(err) => return ControlResponse::error(id, format!("compile failed: {err}")),
+        },
+        None => crate::harness::CompileSession::from_sources(sources),
+    };
+    let mut runtime = match session.build_runtime() {
+        Ok(runtime) => runtime,
+        Err(err) => return ControlResponse::error(id, format!("compile failed: {err}")),
+    };
+    // Used only if the running program executes from bytecode; the source
+    // mapping keeps debugger lines valid after the change.
+    load_source_mapped_vm(&mut runtime, session.sources());
+    let (tx, rx) = std::sync::mpsc::channel();
+    if let Err(err) = state.resource.send_command(ResourceCommand::OnlineChange {
+        program: OnlineChangeProgram::new(runtime),
+        respond_to: tx,
+    }) {
+        return ControlResponse::error(id, err.to_string());
+    }
+    match rx.recv_timeout(std::time::Duration::from_secs(5)) {
+        Ok(Ok((metadata, report))) => {
+            if let Ok(mut guard) = state.metadata.lock() {
+                *guard = metadata;
+            }
+            ControlResponse::ok(
+                id,
+                json!({
+                    "status": "applied",
+                    "preserved": report.preserved.len(),
+                    "converted": report
+                        .converted
+                        .iter()
+                        .map(|entry| json!({ "path": entry.path.as_str(), "detail": entry.detail.as_str() }))
+                        .collect::<Vec<_>>(),
+                    "added": report.added.iter().map(SmolStr::as_str).collect::<Vec<_>>(),
+                    "removed": report.removed.iter().map(SmolStr::as_str).collect::<Vec<_>>(),
+                    "reinitialized": report
+                        .reinitialized
+                        .iter()
+                        .map(|entry| json!({ "path": entry.path.as_str(), "reason": entry.reason.as_str() }))
+                        .collect::<Vec<_>>(),
+                }),
+            )
+        }
+        Ok(Err(err)) => ControlResponse::error(id, err.to_string()),
+        Err(_) => ControlResponse::error(id, "online change timeout".into()),
+    }
+}
+
+fn load_project_sources(root: &Path) -> Result<Vec<crate::harness::SourceFile>, String> {
+    crate::bundle_builder::collect_source_paths(&root.join("sources"))
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .map(|path| {
+            std::fs::read_to_string(&path)
+                .map(|text| {
+                    crate::harness::SourceFile::with_path(path.to_string_lossy().as_ref(), text)
+                })
+                .map_err(|err| format!("failed to read {}: {err}", path.display()))
+        })
+        .collect()
+}
+
+fn load_source_mapped_vm(runtime: &mut crate::Runtime, sources: &[crate::harness::SourceFile]) {
+    let texts = sources
+        .iter()
+        .map(|source| source.text.as_str())
+        .collect::<Vec<_>>();
+    let paths = sources
+        .iter()
+        .map(|source| source.path.as_deref().unwrap_or_default())
+        .collect::<Vec<_>>();
+    let vm = crate::bytecode::BytecodeModule::from_runtime_with_sources_and_paths(
+        runtime, &texts, &paths,
+    )
+    .and_then(|module| {
+        crate::bytecode::BytecodeVm::with_sources_and_paths(&module, &texts, &paths)
+    });
+    if let Ok(vm) = vm {
+        runtime.load_bytecode_vm(vm);
+    }
+}
+
 fn handle_pair_start(id: u64, state: &ControlState) -> ControlResponse {
     let Some(store) = state.pairing.as_ref() else {
         return ControlResponse::error(id, "pairing unavailable".into());
@@ -2843,6 +3121,16 @@ struct BreakpointsClearIdParams {
     file_id: u32,
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct DebugThreadParams {
+    thread_id: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DebugNonStopParams {
+    enabled: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct DebugScopesParams {
     frame_id: u32,
@@ -2930,6 +3218,18 @@ struct BytecodeReloadParams {
     bytes: String,
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct OnlineChangeParams {
+    #[serde(default)]
+    sources: Option<Vec<OnlineChangeSource>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OnlineChangeSource {
+    path: String,
+    text: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct EvalParams {
     expr: String,
@@ -3194,6 +3494,14 @@ mod tests {
                         let _ = respond_to
                             .send(Err(RuntimeError::ControlError(SmolStr::new("unsupported"))));
                     }
+                    ResourceCommand::OnlineChange { respond_to, .. } => {
+                        let _ = respond_to
+                            .send(Err(RuntimeError::ControlError(SmolStr::new("unsupported"))));
+                    }
+                    ResourceCommand::CallMethod { respond_to, .. } => {
+                        let _ = respond_to
+                            .send(Err(RuntimeError::ControlError(SmolStr::new("unsupported"))));
+                    }
                     ResourceCommand::MeshSnapshot { respond_to, .. } => {
                         let _ = respond_to.send(IndexMap::new());
                     }
@@ -3736,6 +4044,36 @@ END_PROGRAM
         );
     }
 
+    #[test]
+    fn opcua_alarm_source_shares_hmi_alarm_state() {
+        let source = r#"
+PROGRAM Main
+VAR
+    // @hmi(min=0, max=100)
+    speed : REAL := 120.0;
+END_VAR
+END_PROGRAM
+"#;
+        let state = hmi_test_state(source);
+        let alarms = crate::opcua::OpcUaAlarmSource::from_control_state(Arc::new(state.clone()));
+
+        let view = (alarms.view)().expect("alarm view");
+        assert_eq!(view.active.len(), 1, "expected one raised alarm");
+        let alarm_id = view.active[0].id.clone();
+        assert!(!view.active[0].acknowledged);
+        (alarms.acknowledge)(alarm_id.as_str()).expect("acknowledge over OPC UA");
+        assert!((alarms.acknowledge)("missing").is_err());
+
+        let hmi = handle_request_value(json!({ "id": 1, "type": "hmi.alarms.get" }), &state, None);
+        assert!(hmi.ok, "hmi.alarms.get failed: {:?}", hmi.error);
+        let state_name = hmi
+            .result
+            .as_ref()
+            .and_then(|value| value.pointer("/active/0/state"))
+            .and_then(serde_json::Value::as_str);
+        assert_eq!(state_name, Some("acknowledged"));
+    }
+
     #[test]
     fn request_routing_contract_dispatches_core_handler_modules() {
         let source = r#"
@@ -3817,6 +4155,57 @@ END_PROGRAM
         );
     }
 
+    #[test]
+    fn debug_threads_list_tasks_and_toggle_non_stop() {
+        let source = r#"
+CONFIGURATION Conf
+TASK Fast (INTERVAL := T#10ms, PRIORITY := 0);
+TASK Slow (INTERVAL := T#50ms, PRIORITY := 5);
+PROGRAM P1 WITH Fast : FastProg;
+PROGRAM P2 WITH Slow : SlowProg;
+END_CONFIGURATION
+
+PROGRAM FastProg
+VAR
+    count : DINT;
+END_VAR
+count := count + 1;
+END_PROGRAM
+
+PROGRAM SlowProg
+VAR
+    count : DINT;
+END_VAR
+count := count + 1;
+END_PROGRAM
+"#;
+        let state = hmi_test_state(source);
+
+        let threads = handle_request_value(json!({"id": 1, "type": "debug.threads"}), &state, None);
+        assert!(threads.ok, "debug.threads failed: {:?}", threads.error);
+        let result = threads.result.expect("threads result");
+        let names = result["threads"]
+            .as_array()
+            .expect("threads array")
+            .iter()
+            .filter_map(|thread| thread["name"].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Fast", "Slow"]);
+        assert_eq!(result["non_stop"], json!(false));
+
+        let non_stop = handle_request_value(
+            json!({"id": 2, "type": "debug.non_stop", "params": { "enabled": true }}),
+            &state,
+            None,
+        );
+        assert!(non_stop.ok, "debug.non_stop failed: {:?}", non_stop.error);
+        assert!(state.debug.non_stop());
+        assert_eq!(
+            required_role_for_control_request("debug.non_stop", None),
+            AccessRole::Engineer
+        );
+    }
+
     #[test]
     fn config_set_reports_field_level_diagnostics_for_unknown_and_type_errors() {
         let source = r#"
@@ -3885,6 +4274,139 @@ END_PROGRAM
         ));
     }
 
+    #[test]
+    fn subscribe_streams_changes_until_unsubscribe() {
+        let source = r#"
+PROGRAM Main
+VAR
+    speed : REAL := 42.5;
+END_VAR
+END_PROGRAM
+"#;
+        let state = hmi_test_state(source);
+        let standalone = handle_request_value(
+            json!({ "id": 1, "type": "subscribe", "params": { "paths": ["Main.speed"] } }),
+            &state,
+            None,
+        );
+        assert!(!standalone.ok);
+
+        let (tx, rx) = std::sync::mpsc::channel::<String>();
+        let tx = Mutex::new(tx);
+        let session = ControlSession::new(Arc::new(move |line: &str| {
+            tx.lock()
+                .map(|tx| tx.send(line.to_string()).is_ok())
+                .unwrap_or(false)
+        }));
+        let subscribe = handle_session_request_value(
+            json!({
+                "id": 2,
+                "type": "subscribe",
+                "params": {
+                    "paths": ["Main.speed", "%QX0.0", "Main..bad"],
+                    "interval_ms": 10,
+                    "deadband": 0.5
+                }
+            }),
+            &state,
+            None,
+            Some(&session),
+        );
+        assert!(subscribe.ok, "subscribe failed: {:?}", subscribe.error);
+        let result = subscribe.result.expect("subscribe result");
+        let subscription = result["subscription"].as_u64().expect("subscription id");
+        assert_eq!(result["paths"], json!(["Main.speed", "%QX0.0"]));
+        assert_eq!(result["rejected"][0]["path"], json!("Main..bad"));
+        assert_eq!(result["interval_ms"], json!(100));
+        assert_eq!(
+            result["values"]["Main.speed"],
+            json!({ "v": 42.5, "q": "good" })
+        );
+        assert_eq!(result["values"]["%QX0.0"]["q"], json!("stale"));
+
+        let address = IoAddress::parse("%QX0.0").expect("address");
+        *state.io_snapshot.lock().expect("io snapshot") = Some(IoSnapshot {
+            outputs: vec![crate::io::IoSnapshotEntry {
+                name: None,
+                address,
+                value: crate::io::IoSnapshotValue::Value(Value::Bool(true)),
+            }],
+            ..IoSnapshot::default()
+        });
+        let update = rx
+            .recv_timeout(std::time::Duration::from_secs(2))
+            .expect("subscription update");
+        let update: serde_json::Value = serde_json::from_str(&update).expect("update json");
+        assert_eq!(update["type"], json!("subscription.update"));
+        assert_eq!(update["subscription"], json!(subscription));
+        assert_eq!(
+            update["values"],
+            json!({ "%QX0.0": { "v": true, "q": "good" } })
+        );
+
+        let unsubscribe = handle_session_request_value(
+            json!({ "id": 3, "type": "unsubscribe", "params": { "subscription": subscription } }),
+            &state,
+            None,
+            Some(&session),
+        );
+        assert!(
+            unsubscribe.ok,
+            "unsubscribe failed: {:?}",
+            unsubscribe.error
+        );
+        let unknown = handle_session_request_value(
+            json!({ "id": 4, "type": "unsubscribe", "params": { "subscription": subscription } }),
+            &state,
+            None,
+            Some(&session),
+        );
+        assert!(!unknown.ok);
+    }
+
+    #[test]
+    fn subscribe_rejects_subscriptions_beyond_session_cap() {
+        let source = r#"
+PROGRAM Main
+VAR
+    speed : REAL := 42.5;
+END_VAR
+END_PROGRAM
+"#;
+        let state = hmi_test_state(source);
+        let session = ControlSession::new(Arc::new(|_: &str| true));
+        let subscribe = |id: u64| {
+            handle_session_request_value(
+                json!({ "id": id, "type": "subscribe", "params": { "paths": ["Main.speed"] } }),
+                &state,
+                None,
+                Some(&session),
+            )
+        };
+        let mut first = None;
+        for id in 0..16 {
+            let response = subscribe(id);
+            assert!(response.ok, "subscribe {id} failed: {:?}", response.error);
+            first.get_or_insert(response.result.expect("result")["subscription"].clone());
+        }
+        let rejected = subscribe(16);
+        assert!(!rejected.ok);
+        assert!(rejected
+            .error
+            .as_deref()
+            .unwrap_or_default()
+            .contains("too many subscriptions"));
+
+        let unsubscribe = handle_session_request_value(
+            json!({ "id": 17, "type": "unsubscribe", "params": { "subscription": first } }),
+            &state,
+            None,
+            Some(&session),
+        );
+        assert!(unsubscribe.ok);
+        assert!(subscribe(18).ok);
+    }
+
     #[test]
     fn invalid_and_malformed_requests_return_negative_responses() {
         let source = r#"
@@ -3896,7 +4418,7 @@ END_PROGRAM
 "#;
         let state = hmi_test_state(source);
 
-        let invalid_line = handle_request_line("{invalid-json", &state, None)
+        let invalid_line = handle_request_line("{invalid-json", &state, None, None)
             .expect("invalid request should still return response line");
         let invalid_json: serde_json::Value =
             serde_json::from_str(&invalid_line).expect("parse invalid response");
@@ -2,14 +2,35 @@ use super::{ControlRequest, ControlResponse, ControlState};
 
 pub(super) fn dispatch(request: &ControlRequest, state: &ControlState) -> Option<ControlResponse> {
     let response = match request.r#type.as_str() {
-        "pause" => super::super::handle_pause(request.id, state),
-        "resume" => super::super::handle_resume(request.id, state),
-        "step_in" => super::super::handle_step(request.id, state, super::super::StepKind::In),
-        "step_over" => super::super::handle_step(request.id, state, super::super::StepKind::Over),
-        "step_out" => super::super::handle_step(request.id, state, super::super::StepKind::Out),
+        "pause" => super::super::handle_pause(request.id, request.params.clone(), state),
+        "resume" => super::super::handle_resume(request.id, request.params.clone(), state),
+        "step_in" => super::super::handle_step(
+            request.id,
+            request.params.clone(),
+            state,
+            super::super::StepKind::In,
+        ),
+        "step_over" => super::super::handle_step(
+            request.id,
+            request.params.clone(),
+            state,
+            super::super::StepKind::Over,
+        ),
+        "step_out" => super::super::handle_step(
+            request.id,
+            request.params.clone(),
+            state,
+            super::super::StepKind::Out,
+        ),
         "debug.state" => super::super::handle_debug_state(request.id, state),
         "debug.stops" => super::super::handle_debug_stops(request.id, state),
-        "debug.stack" => super::super::handle_debug_stack(request.id, state),
+        "debug.stack" => {
+            super::super::handle_debug_stack(request.id, request.params.clone(), state)
+        }
+        "debug.threads" => super::super::handle_debug_threads(request.id, state),
+        "debug.non_stop" => {
+            super::super::handle_debug_non_stop(request.id, request.params.clone(), state)
+        }
         "debug.scopes" => {
             super::super::handle_debug_scopes(request.id, request.params.clone(), state)
         }
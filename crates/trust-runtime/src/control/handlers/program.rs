@@ -7,6 +7,9 @@ pub(super) fn dispatch(request: &ControlRequest, state: &ControlState) -> Option
         "bytecode.reload" => {
             super::super::handle_bytecode_reload(request.id, request.params.clone(), state)
         }
+        "program.online_change" => {
+            super::super::handle_online_change(request.id, request.params.clone(), state)
+        }
         "pair.start" => super::super::handle_pair_start(request.id, state),
         "pair.claim" => super::super::handle_pair_claim(request.id, request.params.clone(), state),
         "pair.list" => super::super::handle_pair_list(request.id, state),
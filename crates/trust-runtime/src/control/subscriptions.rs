@@ -0,0 +1,553 @@
+//! Push-based variable subscriptions on persistent control connections.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration as StdDuration, Instant};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::debug::DebugSnapshot;
+use crate::io::{IoAddress, IoSnapshot, IoSnapshotValue};
+use crate::security::AccessRole;
+use crate::value::Value;
+
+use super::{
+    format_address, load_runtime_snapshot, required_role_for_control_request, ControlRequest,
+    ControlResponse, ControlState,
+};
+
+const DEFAULT_INTERVAL_MS: u64 = 250;
+const MIN_INTERVAL_MS: u64 = 100;
+const MAX_PATHS: usize = 1024;
+/// Subscriptions one control connection may hold at a time.
+const MAX_SUBSCRIPTIONS: usize = 16;
+/// Longest the sampler sleeps before re-checking its stop flag.
+const STOP_POLL: StdDuration = StdDuration::from_millis(20);
+
+/// Writes one notification line to the client; returns `false` once the
+/// connection is gone.
+pub(crate) type NotificationSink = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Subscriptions owned by one control connection. Dropping the session
+/// stops them.
+///
+/// A single sampler thread per session serves every subscription: each tick
+/// it takes one runtime snapshot for all subscriptions that are due.
+pub(crate) struct ControlSession {
+    shared: Arc<SessionShared>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+struct SessionShared {
+    sink: NotificationSink,
+    next_id: AtomicU64,
+    subscriptions: Mutex<HashMap<u64, Subscription>>,
+    stop: AtomicBool,
+}
+
+struct Subscription {
+    paths: Vec<SubscribedPath>,
+    last: Vec<Option<Sample>>,
+    deadband: Option<f64>,
+    interval: StdDuration,
+    next_due: Instant,
+}
+
+impl Subscription {
+    /// Reads every path and returns those that changed since the last call.
+    fn sample(
+        &mut self,
+        snapshot: Option<&DebugSnapshot>,
+        io: Option<&IoSnapshot>,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        let mut values = serde_json::Map::new();
+        for (path, previous) in self.paths.iter().zip(self.last.iter_mut()) {
+            let sample = sample_path(path, snapshot, io);
+            if !sample_changed(previous.as_ref(), &sample, self.deadband) {
+                continue;
+            }
+            values.insert(
+                path.text.clone(),
+                json!({ "v": sample.value, "q": sample.quality }),
+            );
+            *previous = Some(sample);
+        }
+        values
+    }
+
+    fn needs_variables(&self) -> bool {
+        self.paths
+            .iter()
+            .any(|path| matches!(path.target, PathTarget::Variable(_)))
+    }
+
+    fn needs_io(&self) -> bool {
+        self.paths
+            .iter()
+            .any(|path| matches!(path.target, PathTarget::Io(_)))
+    }
+}
+
+impl ControlSession {
+    pub(crate) fn new(sink: NotificationSink) -> Self {
+        Self {
+            shared: Arc::new(SessionShared {
+                sink,
+                next_id: AtomicU64::new(1),
+                subscriptions: Mutex::new(HashMap::new()),
+                stop: AtomicBool::new(false),
+            }),
+            worker: Mutex::new(None),
+        }
+    }
+
+    fn subscribe(
+        &self,
+        id: u64,
+        params: Option<serde_json::Value>,
+        state: &ControlState,
+        role: AccessRole,
+    ) -> ControlResponse {
+        let params = match params.map(serde_json::from_value::<SubscribeParams>) {
+            Some(Ok(parsed)) => parsed,
+            Some(Err(err)) => return ControlResponse::error(id, format!("invalid params: {err}")),
+            None => return ControlResponse::error(id, "missing params".into()),
+        };
+        if params.paths.len() > MAX_PATHS {
+            return ControlResponse::error(id, format!("too many paths (max {MAX_PATHS})"));
+        }
+        if params
+            .deadband
+            .is_some_and(|value| !value.is_finite() || value < 0.0)
+        {
+            return ControlResponse::error(id, "deadband must be a finite value >= 0".into());
+        }
+        if self.subscription_count() >= MAX_SUBSCRIPTIONS {
+            return ControlResponse::error(
+                id,
+                format!("too many subscriptions on this connection (max {MAX_SUBSCRIPTIONS})"),
+            );
+        }
+        let interval_ms = params
+            .interval_ms
+            .unwrap_or(DEFAULT_INTERVAL_MS)
+            .max(MIN_INTERVAL_MS);
+
+        let mut paths = Vec::new();
+        let mut rejected = Vec::new();
+        for text in params.paths {
+            match SubscribedPath::parse(&text) {
+                Ok(path) => {
+                    let required = required_role_for_control_request(path.read_request(), None);
+                    if role.allows(required) {
+                        paths.push(path);
+                    } else {
+                        rejected.push(json!({
+                            "path": text,
+                            "error": format!("forbidden: requires role {}", required.as_str()),
+                        }));
+                    }
+                }
+                Err(error) => rejected.push(json!({ "path": text, "error": error })),
+            }
+        }
+        if paths.is_empty() {
+            return ControlResponse::error(id, "no subscribable paths".into());
+        }
+        if let Err(err) = self.ensure_worker(state) {
+            return ControlResponse::error(id, format!("subscribe failed: {err}"));
+        }
+
+        let interval = StdDuration::from_millis(interval_ms);
+        let mut subscription = Subscription {
+            last: vec![None; paths.len()],
+            paths,
+            deadband: params.deadband,
+            interval,
+            next_due: Instant::now() + interval,
+        };
+        // The initial values go out with the response; the sampler only
+        // reports changes, so notifications never precede the response.
+        let snapshot = subscription
+            .needs_variables()
+            .then(|| load_runtime_snapshot(state))
+            .flatten();
+        let io = subscription
+            .needs_io()
+            .then(|| read_io_snapshot(state))
+            .flatten();
+        let values = subscription.sample(snapshot.as_ref(), io.as_ref());
+        let accepted = subscription
+            .paths
+            .iter()
+            .map(|path| path.text.clone())
+            .collect::<Vec<_>>();
+        let subscription_id = self.shared.next_id.fetch_add(1, Ordering::Relaxed);
+        {
+            let Ok(mut subscriptions) = self.shared.subscriptions.lock() else {
+                return ControlResponse::error(id, "subscribe failed: session poisoned".into());
+            };
+            // Checked again under the lock so concurrent requests cannot
+            // exceed the cap.
+            if subscriptions.len() >= MAX_SUBSCRIPTIONS {
+                return ControlResponse::error(
+                    id,
+                    format!("too many subscriptions on this connection (max {MAX_SUBSCRIPTIONS})"),
+                );
+            }
+            subscriptions.insert(subscription_id, subscription);
+        }
+        ControlResponse::ok(
+            id,
+            json!({
+                "subscription": subscription_id,
+                "paths": accepted,
+                "rejected": rejected,
+                "interval_ms": interval_ms,
+                "deadband": params.deadband,
+                "values": values,
+            }),
+        )
+    }
+
+    fn unsubscribe(&self, id: u64, params: Option<serde_json::Value>) -> ControlResponse {
+        let params = match params.map(serde_json::from_value::<UnsubscribeParams>) {
+            Some(Ok(parsed)) => parsed,
+            Some(Err(err)) => return ControlResponse::error(id, format!("invalid params: {err}")),
+            None => return ControlResponse::error(id, "missing params".into()),
+        };
+        let removed = self
+            .shared
+            .subscriptions
+            .lock()
+            .ok()
+            .and_then(|mut subscriptions| subscriptions.remove(&params.subscription));
+        match removed {
+            Some(_) => ControlResponse::ok(id, json!({ "subscription": params.subscription })),
+            None => {
+                ControlResponse::error(id, format!("unknown subscription {}", params.subscription))
+            }
+        }
+    }
+
+    fn subscription_count(&self) -> usize {
+        self.shared
+            .subscriptions
+            .lock()
+            .map(|subscriptions| subscriptions.len())
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Starts the session sampler on the first subscription.
+    fn ensure_worker(&self, state: &ControlState) -> std::io::Result<()> {
+        let mut worker = self
+            .worker
+            .lock()
+            .map_err(|_| std::io::Error::other("session poisoned"))?;
+        if worker.is_none() {
+            let shared = self.shared.clone();
+            let state = state.clone();
+            *worker = Some(
+                std::thread::Builder::new()
+                    .name("control-subscriptions".into())
+                    .spawn(move || run_sampler(&shared, &state))?,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ControlSession {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(worker) = self.worker.get_mut().ok().and_then(Option::take) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Samples due subscriptions until the session stops or the client is gone.
+fn run_sampler(shared: &SessionShared, state: &ControlState) {
+    while !shared.stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        let (next_due, needs_variables, needs_io) = {
+            let Ok(subscriptions) = shared.subscriptions.lock() else {
+                return;
+            };
+            let due = subscriptions
+                .values()
+                .filter(|subscription| subscription.next_due <= now);
+            let mut needs_variables = false;
+            let mut needs_io = false;
+            for subscription in due {
+                needs_variables |= subscription.needs_variables();
+                needs_io |= subscription.needs_io();
+            }
+            let next_due = subscriptions
+                .values()
+                .map(|subscription| subscription.next_due)
+                .min();
+            (next_due, needs_variables, needs_io)
+        };
+        if next_due.is_none_or(|next_due| next_due > now) {
+            let wait = next_due.map_or(STOP_POLL, |next_due| next_due - now);
+            std::thread::sleep(wait.min(STOP_POLL));
+            continue;
+        }
+
+        // One snapshot serves every subscription that is due this tick.
+        let snapshot = needs_variables
+            .then(|| load_runtime_snapshot(state))
+            .flatten();
+        let io = needs_io.then(|| read_io_snapshot(state)).flatten();
+        let mut notifications = Vec::new();
+        {
+            let Ok(mut subscriptions) = shared.subscriptions.lock() else {
+                return;
+            };
+            let now = Instant::now();
+            for (subscription_id, subscription) in subscriptions.iter_mut() {
+                if subscription.next_due > now {
+                    continue;
+                }
+                subscription.next_due = now + subscription.interval;
+                let values = subscription.sample(snapshot.as_ref(), io.as_ref());
+                if values.is_empty() {
+                    continue;
+                }
+                notifications.push(json!({
+                    "type": "subscription.update",
+                    "subscription": subscription_id,
+                    "timestamp_ms": now_ms(),
+                    "values": values,
+                }));
+            }
+        }
+        for notification in notifications {
+            if shared.stop.load(Ordering::Relaxed) || !(shared.sink)(&notification.to_string()) {
+                return;
+            }
+        }
+    }
+}
+
+fn read_io_snapshot(state: &ControlState) -> Option<IoSnapshot> {
+    state
+        .io_snapshot
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+}
+
+/// Handles `subscribe`/`unsubscribe`; other request types return `None`.
+pub(super) fn dispatch(
+    request: &ControlRequest,
+    state: &ControlState,
+    session: Option<&ControlSession>,
+    role: AccessRole,
+) -> Option<ControlResponse> {
+    let kind = request.r#type.as_str();
+    if !matches!(kind, "subscribe" | "unsubscribe") {
+        return None;
+    }
+    let Some(session) = session else {
+        return Some(ControlResponse::error(
+            request.id,
+            format!("{kind} requires a persistent control connection"),
+        ));
+    };
+    Some(match kind {
+        "subscribe" => session.subscribe(request.id, request.params.clone(), state, role),
+        _ => session.unsubscribe(request.id, request.params.clone()),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscribeParams {
+    paths: Vec<String>,
+    interval_ms: Option<u64>,
+    deadband: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnsubscribeParams {
+    subscription: u64,
+}
+
+#[derive(Debug, Clone)]
+struct SubscribedPath {
+    text: String,
+    target: PathTarget,
+}
+
+#[derive(Debug, Clone)]
+enum PathTarget {
+    /// Direct address read from the I/O snapshot (`%IX0.1`).
+    Io(String),
+    /// Dotted variable path (`Main.speed`, `Main.recipe.temp`).
+    Variable(Vec<String>),
+}
+
+impl SubscribedPath {
+    fn parse(text: &str) -> Result<Self, String> {
+        let trimmed = text.trim();
+        if trimmed.starts_with('%') {
+            let address =
+                IoAddress::parse(trimmed).map_err(|err| format!("invalid address: {err}"))?;
+            return Ok(Self {
+                text: text.to_string(),
+                target: PathTarget::Io(format_address(&address)),
+            });
+        }
+        let segments = trimmed
+            .split('.')
+            .map(|segment| segment.trim().to_string())
+            .collect::<Vec<_>>();
+        if segments.iter().any(String::is_empty) {
+            return Err("invalid variable path".into());
+        }
+        Ok(Self {
+            text: text.to_string(),
+            target: PathTarget::Variable(segments),
+        })
+    }
+
+    /// Polling request whose role requirement applies to this path.
+    fn read_request(&self) -> &'static str {
+        match self.target {
+            PathTarget::Io(_) => "io.read",
+            PathTarget::Variable(_) => "hmi.values.get",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Sample {
+    value: serde_json::Value,
+    quality: &'static str,
+}
+
+fn sample_path(
+    path: &SubscribedPath,
+    snapshot: Option<&DebugSnapshot>,
+    io: Option<&IoSnapshot>,
+) -> Sample {
+    let (connected, value) = match &path.target {
+        PathTarget::Io(address) => (io.is_some(), io.and_then(|io| read_io(io, address))),
+        PathTarget::Variable(segments) => (
+            snapshot.is_some(),
+            snapshot.and_then(|snapshot| resolve_variable(snapshot, segments)),
+        ),
+    };
+    match value {
+        Some(value) => Sample {
+            value: crate::hmi::value_to_json(&value),
+            quality: "good",
+        },
+        None => Sample {
+            value: serde_json::Value::Null,
+            quality: if connected { "bad" } else { "stale" },
+        },
+    }
+}
+
+fn read_io(io: &IoSnapshot, address: &str) -> Option<Value> {
+    io.inputs
+        .iter()
+        .chain(io.outputs.iter())
+        .chain(io.memory.iter())
+        .find(|entry| format_address(&entry.address) == address)
+        .and_then(|entry| match &entry.value {
+            IoSnapshotValue::Value(value) => Some(value.clone()),
+            _ => None,
+        })
+}
+
+fn resolve_variable(snapshot: &DebugSnapshot, segments: &[String]) -> Option<Value> {
+    let (root, rest) = segments.split_first()?;
+    let mut value = snapshot.storage.get_global(root.as_str())?.clone();
+    for segment in rest {
+        let fields = match &value {
+            Value::Instance(id) => &snapshot.storage.get_instance(*id)?.variables,
+            Value::Struct(value) => &value.fields,
+            _ => return None,
+        };
+        let next = fields
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(segment))
+            .map(|(_, field)| field.clone())?;
+        value = next;
+    }
+    Some(value)
+}
+
+/// Numeric values report changes larger than the deadband; anything else
+/// reports every change in value or quality.
+fn sample_changed(previous: Option<&Sample>, next: &Sample, deadband: Option<f64>) -> bool {
+    let Some(previous) = previous else {
+        return true;
+    };
+    if previous.quality != next.quality {
+        return true;
+    }
+    if let (Some(deadband), Some(old), Some(new)) =
+        (deadband, previous.value.as_f64(), next.value.as_f64())
+    {
+        return (new - old).abs() > deadband;
+    }
+    previous.value != next.value
+}
+
+fn now_ms() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn good(value: serde_json::Value) -> Sample {
+        Sample {
+            value,
+            quality: "good",
+        }
+    }
+
+    #[test]
+    fn deadband_suppresses_small_numeric_changes() {
+        let base = good(json!(10.0));
+        assert!(sample_changed(None, &base, Some(0.5)));
+        assert!(!sample_changed(Some(&base), &good(json!(10.4)), Some(0.5)));
+        assert!(sample_changed(Some(&base), &good(json!(10.6)), Some(0.5)));
+        assert!(sample_changed(Some(&base), &good(json!(10.1)), None));
+        assert!(!sample_changed(Some(&base), &good(json!(10.0)), None));
+        assert!(sample_changed(
+            Some(&good(json!(true))),
+            &good(json!(false)),
+            Some(5.0)
+        ));
+        let bad = Sample {
+            value: serde_json::Value::Null,
+            quality: "bad",
+        };
+        assert!(sample_changed(Some(&base), &bad, Some(100.0)));
+    }
+
+    #[test]
+    fn paths_parse_addresses_and_variables() {
+        let io = SubscribedPath::parse("%IX0.1").expect("io path");
+        assert!(matches!(io.target, PathTarget::Io(ref address) if address == "%IX0.1"));
+        assert_eq!(io.read_request(), "io.read");
+        let var = SubscribedPath::parse("Main.recipe.temp").expect("variable path");
+        assert!(matches!(var.target, PathTarget::Variable(ref segments) if segments.len() == 3));
+        assert_eq!(var.read_request(), "hmi.values.get");
+        assert!(SubscribedPath::parse("Main..x").is_err());
+        assert!(SubscribedPath::parse("%ZZ9").is_err());
+    }
+}
@@ -2,11 +2,12 @@
 
 use std::io::{BufRead, BufReader, Write};
 use std::net::TcpListener;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use crate::error::RuntimeError;
 
+use super::subscriptions::{ControlSession, NotificationSink};
 use super::{handle_request_line, ControlEndpoint, ControlState};
 
 pub(crate) fn spawn_control_server(
@@ -52,12 +53,7 @@ fn handle_client(stream: std::net::TcpStream, state: Arc<ControlState>, client:
         Ok(clone) => BufReader::new(clone),
         Err(_) => return,
     };
-    let mut writer = stream;
-    for line in reader.lines().map_while(Result::ok) {
-        if let Some(response) = handle_request_line(&line, &state, client.as_deref()) {
-            let _ = writeln!(writer, "{response}");
-        }
-    }
+    serve_lines(reader, stream, &state, client.as_deref());
 }
 
 #[cfg(unix)]
@@ -66,10 +62,30 @@ fn handle_unix_client(stream: std::os::unix::net::UnixStream, state: Arc<Control
         Ok(clone) => BufReader::new(clone),
         Err(_) => return,
     };
-    let mut writer = stream;
+    serve_lines(reader, stream, &state, Some("unix"));
+}
+
+/// Answers one request per line. Subscription notifications share the
+/// writer, so each line is written under its lock.
+fn serve_lines<R, W>(reader: BufReader<R>, writer: W, state: &ControlState, client: Option<&str>)
+where
+    R: std::io::Read,
+    W: Write + Send + 'static,
+{
+    let writer = Arc::new(Mutex::new(writer));
+    let sink_writer = writer.clone();
+    let sink: NotificationSink = Arc::new(move |line: &str| {
+        sink_writer
+            .lock()
+            .map(|mut writer| writeln!(writer, "{line}").is_ok())
+            .unwrap_or(false)
+    });
+    let session = ControlSession::new(sink);
     for line in reader.lines().map_while(Result::ok) {
-        if let Some(response) = handle_request_line(&line, &state, Some("unix")) {
-            let _ = writeln!(writer, "{response}");
+        if let Some(response) = handle_request_line(&line, state, client, Some(&session)) {
+            if let Ok(mut writer) = writer.lock() {
+                let _ = writeln!(writer, "{response}");
+            }
         }
     }
 }
@@ -5,9 +5,13 @@
 use std::sync::mpsc::Sender;
 
 use crate::eval::{eval_expr, EvalContext};
+use crate::memory::{MemoryLocation, WatchAccess};
 use crate::value::Value;
 
-use super::{DebugBreakpoint, DebugLog, LogFragment, SourceLocation};
+use super::{
+    DataAccess, DebugBreakpoint, DebugDataBreakpoint, DebugFunctionBreakpoint, DebugLog,
+    LogFragment, SourceLocation,
+};
 
 pub(crate) fn matches_breakpoint(
     breakpoints: &mut [DebugBreakpoint],
@@ -61,6 +65,78 @@ pub(crate) fn matches_breakpoint(
     None
 }
 
+/// Check function breakpoints at a POU entry statement, honouring instance filters.
+pub(crate) fn matches_function_breakpoint(
+    breakpoints: &mut [DebugFunctionBreakpoint],
+    location: &SourceLocation,
+    ctx: &mut Option<&mut EvalContext<'_>>,
+) -> bool {
+    let mut logs = Vec::new();
+    breakpoints.iter_mut().any(|entry| {
+        if let Some(instance) = entry.instance {
+            let current = ctx
+                .as_deref()
+                .and_then(|eval_ctx| eval_ctx.current_instance);
+            if current != Some(instance) {
+                return false;
+            }
+        }
+        matches_breakpoint(
+            std::slice::from_mut(&mut entry.breakpoint),
+            &mut logs,
+            None,
+            location,
+            ctx,
+        )
+        .is_some()
+    })
+}
+
+/// Check recorded accesses against data breakpoints; returns true when one triggers.
+pub(crate) fn matches_data_breakpoint(
+    breakpoints: &mut [DebugDataBreakpoint],
+    accesses: &[WatchAccess],
+    ctx: &mut EvalContext<'_>,
+) -> bool {
+    let mut matched = false;
+    for breakpoint in breakpoints.iter_mut() {
+        let Some(access) = accesses
+            .iter()
+            .find(|access| access.target == breakpoint.target)
+        else {
+            continue;
+        };
+        let triggered = match breakpoint.access {
+            DataAccess::Read => access.read,
+            DataAccess::Write => access.written,
+            DataAccess::ReadWrite => access.read || access.written,
+        };
+        if !triggered || matched {
+            continue;
+        }
+        breakpoint.hits = breakpoint.hits.saturating_add(1);
+        if let Some(hit_condition) = breakpoint.hit_condition {
+            if !hit_condition.is_met(breakpoint.hits) {
+                continue;
+            }
+        }
+        if let Some(condition) = &breakpoint.condition {
+            // Evaluate member conditions in the scope of the owning instance.
+            let previous = ctx.current_instance;
+            if let MemoryLocation::Instance(instance_id) = breakpoint.target.location {
+                ctx.current_instance = Some(instance_id);
+            }
+            let met = condition_matches(ctx, condition);
+            ctx.current_instance = previous;
+            if !met {
+                continue;
+            }
+        }
+        matched = true;
+    }
+    matched
+}
+
 fn condition_matches(ctx: &mut EvalContext<'_>, condition: &crate::eval::expr::Expr) -> bool {
     match eval_expr(ctx, condition) {
         Ok(Value::Bool(true)) => true,
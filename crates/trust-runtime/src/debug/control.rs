@@ -4,22 +4,26 @@
 
 use std::collections::HashMap;
 use std::sync::mpsc::Sender;
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
 
 use smol_str::SmolStr;
 
+use crate::error::RuntimeError;
 use crate::eval::expr::{Expr, LValue};
 use crate::eval::{eval_expr, EvalContext};
-use crate::io::{IoAddress, IoSnapshot};
-use crate::memory::{FrameId, InstanceId};
-use crate::value::Value;
+use crate::io::{IoAddress, IoSnapshot, IoSnapshotEntry};
+use crate::memory::{FrameId, InstanceId, VariableStorage};
+use crate::value::{Duration, Value, ValueRef};
 
-use super::breakpoints::matches_breakpoint;
+use super::breakpoints::{
+    matches_breakpoint, matches_data_breakpoint, matches_function_breakpoint,
+};
+use super::history::CycleHistory;
 use super::hook::DebugHook;
 use super::trace::trace_debug;
 use super::{
-    DebugBreakpoint, DebugLog, DebugSnapshot, DebugStop, DebugStopReason, RuntimeEvent,
-    SourceLocation,
+    DebugBreakpoint, DebugDataBreakpoint, DebugFault, DebugFunctionBreakpoint, DebugLog,
+    DebugSnapshot, DebugStop, DebugStopReason, FaultCategory, RuntimeEvent, SourceLocation,
 };
 
 /// Debugger execution mode.
@@ -36,8 +40,8 @@ pub enum DebugMode {
 pub enum ControlAction {
     /// Pause execution at the next statement boundary.
     Pause(Option<u32>),
-    /// Continue running.
-    Continue,
+    /// Continue running; in non-stop mode a thread id resumes only that task.
+    Continue(Option<u32>),
     /// Execute a single statement, then pause.
     StepIn(Option<u32>),
     /// Step over the current statement.
@@ -72,6 +76,16 @@ struct StepState {
     started: bool,
 }
 
+/// Stop state of one task in non-stop mode.
+#[derive(Debug)]
+struct ThreadStop {
+    mode: DebugMode,
+    pending_stop: Option<DebugStopReason>,
+    snapshot: Option<DebugSnapshot>,
+    last_location: Option<SourceLocation>,
+    frame_locations: HashMap<FrameId, SourceLocation>,
+}
+
 #[derive(Debug)]
 struct DebugState {
     mode: DebugMode,
@@ -80,8 +94,25 @@ struct DebugState {
     last_call_depths: HashMap<u32, u32>,
     current_thread: Option<u32>,
     target_thread: Option<u32>,
+    thread_bindings: HashMap<std::thread::ThreadId, Option<u32>>,
+    thread_names: HashMap<u32, SmolStr>,
+    stop_owner: Option<u32>,
+    non_stop: bool,
+    /// Stop state of each task that has run in non-stop mode.
+    thread_stops: HashMap<u32, ThreadStop>,
     breakpoints: Vec<DebugBreakpoint>,
     breakpoint_generation: HashMap<u32, u64>,
+    data_breakpoints: Vec<DebugDataBreakpoint>,
+    data_targets: Vec<ValueRef>,
+    function_breakpoints: Vec<DebugFunctionBreakpoint>,
+    exception_filters: Vec<FaultCategory>,
+    last_fault: Option<DebugFault>,
+    fault_reported: bool,
+    /// Watchdog exception deadline of each thread running a cycle or task.
+    watchdog_deadlines: HashMap<std::thread::ThreadId, std::time::Instant>,
+    history: Option<CycleHistory>,
+    history_cursor: Option<u64>,
+    live_snapshot: Option<DebugSnapshot>,
     frame_locations: HashMap<FrameId, SourceLocation>,
     logs: Vec<DebugLog>,
     snapshot: Option<DebugSnapshot>,
@@ -94,6 +125,7 @@ struct DebugState {
     runtime_events: Vec<RuntimeEvent>,
     pending_stop: Option<DebugStopReason>,
     stops: Vec<DebugStop>,
+    pause_count: u64,
     last_stop: Option<DebugStop>,
     steps: HashMap<u32, StepState>,
     io_writes: Vec<(IoAddress, Value)>,
@@ -103,6 +135,119 @@ struct DebugState {
     forced_io: Vec<(IoAddress, Value)>,
 }
 
+impl DebugState {
+    /// Task whose own stop state the hook uses, in non-stop mode.
+    fn own_stop_thread(&self) -> Option<u32> {
+        if self.non_stop {
+            self.current_thread
+        } else {
+            None
+        }
+    }
+
+    /// Stop state of a task; a task seen for the first time starts from the
+    /// state requested for all tasks.
+    fn thread_stop(&mut self, thread_id: u32) -> &mut ThreadStop {
+        let (mode, pending_stop) = (self.mode, self.pending_stop);
+        self.thread_stops
+            .entry(thread_id)
+            .or_insert_with(|| ThreadStop {
+                mode,
+                pending_stop,
+                snapshot: None,
+                last_location: None,
+                frame_locations: HashMap::new(),
+            })
+    }
+
+    fn thread_paused(&self, thread_id: u32) -> bool {
+        self.thread_stops
+            .get(&thread_id)
+            .is_some_and(|stop| matches!(stop.mode, DebugMode::Paused))
+    }
+
+    /// Stopped task whose snapshot is reported, in non-stop mode.
+    fn focused_stop(&self) -> Option<&ThreadStop> {
+        self.stop_owner
+            .filter(|_| self.non_stop)
+            .and_then(|thread_id| self.thread_stops.get(&thread_id))
+    }
+
+    fn stop_frame_locations(&self) -> &HashMap<FrameId, SourceLocation> {
+        self.focused_stop()
+            .map_or(&self.frame_locations, |stop| &stop.frame_locations)
+    }
+
+    /// Whether any task is paused, or a pause for all tasks is still pending.
+    fn any_paused(&self) -> bool {
+        if self.non_stop && !self.thread_stops.is_empty() {
+            self.thread_stops
+                .values()
+                .any(|stop| matches!(stop.mode, DebugMode::Paused))
+        } else {
+            matches!(self.mode, DebugMode::Paused)
+        }
+    }
+
+    fn is_target_thread(&self) -> bool {
+        self.non_stop || self.target_thread.is_none() || self.target_thread == self.current_thread
+    }
+
+    /// Execution mode of the thread running the hook.
+    fn hook_mode(&self) -> DebugMode {
+        match self.own_stop_thread() {
+            Some(thread_id) => self
+                .thread_stops
+                .get(&thread_id)
+                .map_or(self.mode, |stop| stop.mode),
+            None => self.mode,
+        }
+    }
+
+    fn hook_pending_stop(&self) -> Option<DebugStopReason> {
+        match self.own_stop_thread() {
+            Some(thread_id) => self
+                .thread_stops
+                .get(&thread_id)
+                .map_or(self.pending_stop, |stop| stop.pending_stop),
+            None => self.pending_stop,
+        }
+    }
+
+    fn take_pending_stop(&mut self) -> Option<DebugStopReason> {
+        match self.own_stop_thread() {
+            Some(thread_id) => self.thread_stop(thread_id).pending_stop.take(),
+            None => self.pending_stop.take(),
+        }
+    }
+
+    /// Pause the thread running the hook for a stop it reports itself.
+    fn enter_pause(&mut self) {
+        match self.own_stop_thread() {
+            Some(thread_id) => {
+                let stop = self.thread_stop(thread_id);
+                stop.mode = DebugMode::Paused;
+                stop.pending_stop = None;
+            }
+            None => {
+                self.mode = DebugMode::Paused;
+                self.pending_stop = None;
+            }
+        }
+    }
+
+    /// Location of the previous statement of the thread running the hook.
+    fn previous_location(&self) -> Option<SourceLocation> {
+        match self.own_stop_thread() {
+            Some(thread_id) => self
+                .thread_stops
+                .get(&thread_id)
+                .and_then(|stop| stop.last_location),
+            None => self.last_location,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct WatchEntry {
     expr: Expr,
@@ -169,8 +314,23 @@ impl DebugControl {
                     last_call_depths: HashMap::new(),
                     current_thread: Some(1),
                     target_thread: None,
+                    thread_bindings: HashMap::new(),
+                    thread_names: HashMap::new(),
+                    stop_owner: None,
+                    non_stop: false,
+                    thread_stops: HashMap::new(),
                     breakpoints: Vec::new(),
                     breakpoint_generation: HashMap::new(),
+                    data_breakpoints: Vec::new(),
+                    data_targets: Vec::new(),
+                    function_breakpoints: Vec::new(),
+                    exception_filters: Vec::new(),
+                    last_fault: None,
+                    fault_reported: false,
+                    watchdog_deadlines: HashMap::new(),
+                    history: None,
+                    history_cursor: None,
+                    live_snapshot: None,
                     frame_locations: HashMap::new(),
                     logs: Vec::new(),
                     snapshot: None,
@@ -183,6 +343,7 @@ impl DebugControl {
                     runtime_events: Vec::new(),
                     pending_stop: None,
                     stops: Vec::new(),
+                    pause_count: 0,
                     last_stop: None,
                     steps: HashMap::new(),
                     io_writes: Vec::new(),
@@ -203,87 +364,52 @@ impl DebugControl {
         let mut notify = false;
         let mut outcome = ControlOutcome::Applied;
         let previous_mode = state.mode;
-        let step_started = matches!(previous_mode, DebugMode::Paused);
+        if !matches!(action, ControlAction::Pause(_)) {
+            leave_history(&mut state);
+            state.last_fault = None;
+        }
 
         match action {
+            ControlAction::Pause(thread_id) if state.non_stop => {
+                if !pause_threads(&mut state, thread_id, DebugStopReason::Pause) {
+                    outcome = ControlOutcome::Ignored;
+                }
+            }
             ControlAction::Pause(thread_id) => {
                 if matches!(state.mode, DebugMode::Paused) {
                     outcome = ControlOutcome::Ignored;
                 } else {
                     state.mode = DebugMode::Paused;
-                    state.steps.clear();
+                    clear_steps(&mut state, thread_id);
                     state.pending_stop = Some(DebugStopReason::Pause);
                     state.snapshot = None;
                     state.target_thread = thread_id;
+                    state.stop_owner = None;
                 }
             }
-            ControlAction::Continue => {
+            ControlAction::Continue(thread_id) if state.non_stop => {
+                continue_threads(&mut state, thread_id);
+                notify = true;
+            }
+            ControlAction::Continue(_) => {
                 state.mode = DebugMode::Running;
-                state.steps.clear();
+                let owner = state.stop_owner.take();
+                clear_steps(&mut state, owner);
                 state.pending_stop = None;
                 state.snapshot = None;
                 state.target_thread = None;
                 notify = true;
             }
             ControlAction::StepIn(thread_id) => {
-                state.steps.clear();
-                let target_thread = thread_id.or(state.current_thread);
-                let step_key = target_thread.unwrap_or(0);
-                let target_depth = state.last_call_depth;
-                state.steps.insert(
-                    step_key,
-                    StepState {
-                        kind: StepKind::Into,
-                        target_depth,
-                        started: step_started,
-                    },
-                );
-                state.mode = DebugMode::Running;
-                state.pending_stop = None;
-                state.snapshot = None;
-                state.target_thread = target_thread;
+                start_step(&mut state, thread_id, StepKind::Into);
                 notify = true;
             }
             ControlAction::StepOver(thread_id) => {
-                state.steps.clear();
-                let target_thread = thread_id.or(state.current_thread);
-                let target_depth = target_thread
-                    .and_then(|id| state.last_call_depths.get(&id).copied())
-                    .unwrap_or(state.last_call_depth);
-                let step_key = target_thread.unwrap_or(0);
-                state.steps.insert(
-                    step_key,
-                    StepState {
-                        kind: StepKind::Over,
-                        target_depth,
-                        started: step_started,
-                    },
-                );
-                state.mode = DebugMode::Running;
-                state.pending_stop = None;
-                state.snapshot = None;
-                state.target_thread = target_thread;
+                start_step(&mut state, thread_id, StepKind::Over);
                 notify = true;
             }
             ControlAction::StepOut(thread_id) => {
-                state.steps.clear();
-                let target_thread = thread_id.or(state.current_thread);
-                let target_depth = target_thread
-                    .and_then(|id| state.last_call_depths.get(&id).copied())
-                    .unwrap_or(state.last_call_depth);
-                let step_key = target_thread.unwrap_or(0);
-                state.steps.insert(
-                    step_key,
-                    StepState {
-                        kind: StepKind::Out,
-                        target_depth: target_depth.saturating_sub(1),
-                        started: step_started,
-                    },
-                );
-                state.mode = DebugMode::Running;
-                state.pending_stop = None;
-                state.snapshot = None;
-                state.target_thread = target_thread;
+                start_step(&mut state, thread_id, StepKind::Out);
                 notify = true;
             }
         }
@@ -335,9 +461,100 @@ impl DebugControl {
         let prev_total = state.breakpoints.len();
         state.breakpoints.clear();
         state.breakpoint_generation.clear();
+        state.data_breakpoints.clear();
+        state.data_targets.clear();
+        state.function_breakpoints.clear();
         trace_debug(&format!("breakpoints.clear prev_total={prev_total}"));
     }
 
+    /// Replace all data breakpoints.
+    pub fn set_data_breakpoints(&self, breakpoints: Vec<DebugDataBreakpoint>) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        state.data_targets = breakpoints
+            .iter()
+            .map(|breakpoint| breakpoint.target.clone())
+            .collect();
+        state.data_breakpoints = breakpoints;
+        trace_debug(&format!(
+            "data_breakpoints.set total={}",
+            state.data_breakpoints.len()
+        ));
+    }
+
+    /// Snapshot current data breakpoints.
+    #[must_use]
+    pub fn data_breakpoints(&self) -> Vec<DebugDataBreakpoint> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.data_breakpoints.clone()
+    }
+
+    /// Replace all function breakpoints.
+    pub fn set_function_breakpoints(&self, breakpoints: Vec<DebugFunctionBreakpoint>) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        state.function_breakpoints = breakpoints;
+        trace_debug(&format!(
+            "function_breakpoints.set total={}",
+            state.function_breakpoints.len()
+        ));
+    }
+
+    /// Snapshot current function breakpoints.
+    #[must_use]
+    pub fn function_breakpoints(&self) -> Vec<DebugFunctionBreakpoint> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.function_breakpoints.clone()
+    }
+
+    /// Replace the fault categories that pause execution.
+    pub fn set_exception_filters(&self, filters: Vec<FaultCategory>) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        state.exception_filters = filters;
+        if !state.exception_filters.contains(&FaultCategory::Watchdog) {
+            state.watchdog_deadlines.clear();
+        }
+        trace_debug(&format!(
+            "exception_filters.set filters={:?}",
+            state.exception_filters
+        ));
+    }
+
+    /// Snapshot the active exception filters.
+    #[must_use]
+    pub fn exception_filters(&self) -> Vec<FaultCategory> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.exception_filters.clone()
+    }
+
+    /// Fault reported by the current exception stop, if any.
+    #[must_use]
+    pub fn last_fault(&self) -> Option<DebugFault> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.last_fault.clone()
+    }
+
+    /// Arm the watchdog exception filter for the cycle or task the calling
+    /// thread is about to run.
+    ///
+    /// `deadline` is the runtime watchdog deadline of that run; the first
+    /// statement the thread reaches past it pauses with an exception stop.
+    pub fn start_watchdog_window(&self, deadline: std::time::Instant) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        let thread = std::thread::current().id();
+        if state.exception_filters.contains(&FaultCategory::Watchdog) {
+            state.watchdog_deadlines.insert(thread, deadline);
+        } else {
+            state.watchdog_deadlines.remove(&thread);
+        }
+    }
+
     /// Returns the number of active breakpoints (primarily for tests).
     #[doc(hidden)]
     pub fn breakpoint_count(&self) -> usize {
@@ -368,6 +585,10 @@ impl DebugControl {
     pub fn pause_entry(&self) {
         let (lock, _) = &*self.state;
         let mut state = lock.lock().expect("debug state poisoned");
+        if state.non_stop {
+            pause_threads(&mut state, None, DebugStopReason::Entry);
+            return;
+        }
         if matches!(state.mode, DebugMode::Paused) {
             return;
         }
@@ -376,11 +597,17 @@ impl DebugControl {
         state.pending_stop = Some(DebugStopReason::Entry);
         state.snapshot = None;
         state.target_thread = None;
+        state.stop_owner = None;
     }
 
     /// Continue running until the next pause request.
     pub fn continue_run(&self) {
-        let _ = self.apply_action(ControlAction::Continue);
+        let _ = self.apply_action(ControlAction::Continue(None));
+    }
+
+    /// Resume a single stopped task in non-stop mode (all tasks otherwise).
+    pub fn continue_thread(&self, thread_id: u32) {
+        let _ = self.apply_action(ControlAction::Continue(Some(thread_id)));
     }
 
     /// Execute a single statement and pause again.
@@ -418,7 +645,11 @@ impl DebugControl {
     pub fn mode(&self) -> DebugMode {
         let (lock, _) = &*self.state;
         let state = lock.lock().expect("debug state poisoned");
-        state.mode
+        if state.any_paused() {
+            DebugMode::Paused
+        } else {
+            DebugMode::Running
+        }
     }
 
     /// Get the last observed statement location.
@@ -426,7 +657,9 @@ impl DebugControl {
     pub fn last_location(&self) -> Option<SourceLocation> {
         let (lock, _) = &*self.state;
         let state = lock.lock().expect("debug state poisoned");
-        state.last_location
+        state
+            .focused_stop()
+            .map_or(state.last_location, |stop| stop.last_location)
     }
 
     /// Get the current breakpoint generation for a file id.
@@ -442,7 +675,7 @@ impl DebugControl {
     pub fn frame_location(&self, frame_id: FrameId) -> Option<SourceLocation> {
         let (lock, _) = &*self.state;
         let state = lock.lock().expect("debug state poisoned");
-        state.frame_locations.get(&frame_id).copied()
+        state.stop_frame_locations().get(&frame_id).copied()
     }
 
     /// Snapshot all recorded frame locations.
@@ -450,7 +683,7 @@ impl DebugControl {
     pub fn frame_locations(&self) -> HashMap<FrameId, SourceLocation> {
         let (lock, _) = &*self.state;
         let state = lock.lock().expect("debug state poisoned");
-        state.frame_locations.clone()
+        state.stop_frame_locations().clone()
     }
 
     /// Get the last observed call depth.
@@ -462,10 +695,104 @@ impl DebugControl {
     }
 
     /// Set the current thread id for the active statement.
+    ///
+    /// The id is bound to the calling OS thread, so hooks of task forks that
+    /// run on their own threads report the task they belong to.
     pub fn set_current_thread(&self, thread_id: Option<u32>) {
         let (lock, _) = &*self.state;
         let mut state = lock.lock().expect("debug state poisoned");
         state.current_thread = thread_id;
+        state
+            .thread_bindings
+            .insert(std::thread::current().id(), thread_id);
+    }
+
+    /// Set the current thread id and remember the task name it belongs to.
+    pub fn set_current_task(&self, thread_id: Option<u32>, name: &str) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        state.current_thread = thread_id;
+        state
+            .thread_bindings
+            .insert(std::thread::current().id(), thread_id);
+        if let Some(thread_id) = thread_id {
+            state
+                .thread_names
+                .entry(thread_id)
+                .or_insert_with(|| SmolStr::new(name));
+        }
+    }
+
+    /// Name of the task a thread id belongs to, if it has run.
+    #[must_use]
+    pub fn thread_name(&self, thread_id: u32) -> Option<SmolStr> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.thread_names.get(&thread_id).cloned()
+    }
+
+    /// Thread that reported the current stop, while paused.
+    ///
+    /// In non-stop mode this is the stopped task that snapshots and frame
+    /// locations are reported for; see [`Self::focus_thread`].
+    #[must_use]
+    pub fn stopped_thread(&self) -> Option<u32> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        if state.non_stop {
+            state.stop_owner.filter(|id| state.thread_paused(*id))
+        } else if matches!(state.mode, DebugMode::Paused) {
+            state.stop_owner
+        } else {
+            None
+        }
+    }
+
+    /// Whether a thread is stopped.
+    #[must_use]
+    pub fn thread_stopped(&self, thread_id: u32) -> bool {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        if state.non_stop {
+            state.thread_paused(thread_id)
+        } else {
+            matches!(state.mode, DebugMode::Paused) && state.stop_owner == Some(thread_id)
+        }
+    }
+
+    /// Report the snapshot and frame locations of a stopped task in non-stop
+    /// mode. Returns false, changing nothing, when the task is not stopped.
+    pub fn focus_thread(&self, thread_id: u32) -> bool {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        if !state.non_stop || !state.thread_paused(thread_id) {
+            return false;
+        }
+        state.stop_owner = Some(thread_id);
+        true
+    }
+
+    /// Stop only the task that hits a stop and let the others keep running.
+    ///
+    /// Each task keeps its own stop state, so several tasks can be stopped
+    /// at once and resumed or stepped one by one. Takes effect only when
+    /// tasks run on their own threads; with a single scan thread a stopped
+    /// task still holds up the tasks after it.
+    pub fn set_non_stop(&self, enabled: bool) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        if state.non_stop != enabled {
+            state.thread_stops.clear();
+        }
+        state.non_stop = enabled;
+    }
+
+    /// Whether non-stop mode is enabled.
+    #[must_use]
+    pub fn non_stop(&self) -> bool {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.non_stop
     }
 
     /// Get the current thread id, if any.
@@ -505,7 +832,10 @@ impl DebugControl {
     pub fn snapshot(&self) -> Option<DebugSnapshot> {
         let (lock, _) = &*self.state;
         let state = lock.lock().expect("debug state poisoned");
-        state.snapshot.clone()
+        match state.focused_stop() {
+            Some(stop) => stop.snapshot.clone(),
+            None => state.snapshot.clone(),
+        }
     }
 
     /// Return whether execution is currently paused.
@@ -513,7 +843,7 @@ impl DebugControl {
     pub fn is_paused(&self) -> bool {
         let (lock, _) = &*self.state;
         let state = lock.lock().expect("debug state poisoned");
-        matches!(state.mode, DebugMode::Paused)
+        state.any_paused()
     }
 
     /// Return the most recent stop, if any.
@@ -532,11 +862,146 @@ impl DebugControl {
         std::mem::take(&mut state.stops)
     }
 
+    /// Number of stops that paused execution, not counting watchdog
+    /// exception stops; the runtime watchdog ignores windows paused in.
+    #[must_use]
+    pub fn pause_count(&self) -> u64 {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.pause_count
+    }
+
     /// Mutate the stored snapshot, if one exists.
     pub fn with_snapshot<T>(&self, f: impl FnOnce(&mut DebugSnapshot) -> T) -> Option<T> {
         let (lock, _) = &*self.state;
         let mut state = lock.lock().expect("debug state poisoned");
-        state.snapshot.as_mut().map(f)
+        let DebugState {
+            non_stop,
+            stop_owner,
+            thread_stops,
+            snapshot,
+            ..
+        } = &mut *state;
+        let focused = stop_owner
+            .filter(|_| *non_stop)
+            .and_then(|id| thread_stops.get_mut(&id));
+        match focused {
+            Some(stop) => stop.snapshot.as_mut().map(f),
+            None => snapshot.as_mut().map(f),
+        }
+    }
+
+    /// Record the last `capacity` cycles for reverse stepping (0 disables).
+    pub fn enable_history(&self, capacity: usize) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        leave_history(&mut state);
+        state.history = (capacity > 0).then(|| CycleHistory::new(capacity));
+    }
+
+    /// Whether cycle history recording is enabled.
+    #[must_use]
+    pub fn history_enabled(&self) -> bool {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.history.is_some()
+    }
+
+    /// Access the recorded cycle history.
+    pub fn with_history<T>(&self, f: impl FnOnce(&CycleHistory) -> T) -> Option<T> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.history.as_ref().map(f)
+    }
+
+    /// Record the start of a cycle (after inputs were latched).
+    pub fn record_cycle_start(
+        &self,
+        cycle: u64,
+        time: Duration,
+        inputs: Vec<IoSnapshotEntry>,
+        storage: &VariableStorage,
+    ) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        if let Some(history) = state.history.as_mut() {
+            history.begin_cycle(cycle, time, inputs, storage);
+        }
+    }
+
+    /// Record the end of a cycle.
+    pub fn record_cycle_end(&self, storage: &VariableStorage) {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        if let Some(history) = state.history.as_mut() {
+            history.end_cycle(storage);
+        }
+    }
+
+    /// Cycle currently shown from history (`None` while showing live state).
+    #[must_use]
+    pub fn history_cursor(&self) -> Option<u64> {
+        let (lock, _) = &*self.state;
+        let state = lock.lock().expect("debug state poisoned");
+        state.history_cursor
+    }
+
+    /// Show the state one recorded cycle earlier while paused.
+    ///
+    /// Returns the cycle now shown, or `None` when no older cycle is recorded.
+    pub fn step_back(&self) -> Option<u64> {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        let history = state.history.as_ref()?;
+        let target = match state.history_cursor {
+            Some(cursor) => cursor.checked_sub(1)?,
+            None => history.newest_cycle()?,
+        };
+        show_history_cycle(&mut state, target)
+    }
+
+    /// Show the state one recorded cycle later; returns to live state after the newest.
+    pub fn step_forward_history(&self) -> Option<u64> {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        let cursor = state.history_cursor?;
+        let newest = state.history.as_ref().and_then(CycleHistory::newest_cycle);
+        if newest.is_none_or(|newest| cursor >= newest) {
+            leave_history(&mut state);
+            return None;
+        }
+        show_history_cycle(&mut state, cursor + 1)
+    }
+
+    /// Walk back through history until a cycle changed a data breakpoint target.
+    ///
+    /// Stops at the oldest recorded cycle when no watched variable changed.
+    /// Line and function breakpoints are not considered: history records
+    /// variable deltas per cycle, not the statements that executed.
+    pub fn reverse_continue(&self) -> Option<u64> {
+        let (lock, _) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        let history = state.history.as_ref()?;
+        let start = match state.history_cursor {
+            Some(cursor) => cursor.checked_sub(1)?,
+            None => history.newest_cycle()?,
+        };
+        let oldest = history.oldest_cycle()?;
+        let target = history
+            .records()
+            .rev()
+            .filter(|record| record.cycle <= start)
+            .find(|record| {
+                state
+                    .data_targets
+                    .iter()
+                    .any(|target| record.touches(target))
+            })
+            .map_or(oldest, |record| record.cycle);
+        if target > start {
+            return None;
+        }
+        show_history_cycle(&mut state, target)
     }
 
     /// Queue an input write to be applied at the next cycle boundary.
@@ -848,7 +1313,61 @@ impl DebugHook for DebugControl {
         location: Option<&SourceLocation>,
         call_depth: u32,
     ) {
-        self.on_statement_inner(location, call_depth, Some(ctx));
+        self.on_statement_inner(location, call_depth, Some(&mut *ctx));
+        // Reads done by the debugger itself (conditions, watches) must not trigger.
+        ctx.storage.clear_watch_accesses();
+    }
+
+    fn on_fault(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        location: Option<&SourceLocation>,
+        error: &RuntimeError,
+    ) {
+        let (lock, cvar) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        bind_thread(&mut state);
+        // Outer statements see the same error while it unwinds; only the innermost stops.
+        if state.fault_reported {
+            return;
+        }
+        let Some(category) = FaultCategory::of(error) else {
+            return;
+        };
+        if !state.exception_filters.contains(&category)
+            || !matches!(state.hook_mode(), DebugMode::Running)
+        {
+            return;
+        }
+        state.fault_reported = true;
+        state.last_fault = Some(DebugFault {
+            category,
+            message: error.to_string().into(),
+        });
+        state.enter_pause();
+        claim_stop(&mut state);
+        update_watch_snapshot(&mut state, ctx);
+        update_snapshot(&mut state, ctx);
+        emit_stop(
+            &mut state,
+            DebugStopReason::Exception,
+            location.copied(),
+            None,
+        );
+        let mut ctx = Some(&mut *ctx);
+        drop(wait_while_paused(cvar, state, location, &mut ctx));
+    }
+
+    fn on_program_end(&mut self, ctx: &mut EvalContext<'_>) {
+        let (lock, cvar) = &*self.state;
+        let mut state = lock.lock().expect("debug state poisoned");
+        bind_thread(&mut state);
+        if check_data_breakpoints(&mut state, ctx) {
+            let location = state.previous_location();
+            let mut ctx = Some(&mut *ctx);
+            drop(wait_while_paused(cvar, state, location.as_ref(), &mut ctx));
+        }
+        ctx.storage.clear_watch_accesses();
     }
 }
 
@@ -861,35 +1380,51 @@ impl DebugControl {
     ) {
         let (lock, cvar) = &*self.state;
         let mut state = lock.lock().expect("debug state poisoned");
+        bind_thread(&mut state);
         trace_debug(&format!(
             "hook.entry location={} depth={} mode={:?} current_thread={:?} target_thread={:?} pending_stop={:?} steps={} breakpoints={}",
             format_location_ref(location),
             call_depth,
-            state.mode,
+            state.hook_mode(),
             state.current_thread,
             state.target_thread,
-            state.pending_stop,
+            state.hook_pending_stop(),
             state.steps.len(),
             state.breakpoints.len()
         ));
-        state.last_location = location.copied();
-        state.last_call_depth = call_depth;
+        // Accesses made by the previous statement stop there, before moving on.
+        if let Some(eval_ctx) = ctx.as_deref_mut() {
+            if check_data_breakpoints(&mut state, eval_ctx) {
+                let previous = state.previous_location();
+                state = wait_while_paused(cvar, state, previous.as_ref(), &mut ctx);
+            }
+        }
+        state.fault_reported = false;
         if let Some(thread_id) = state.current_thread {
             state.last_call_depths.insert(thread_id, call_depth);
         }
-        if let (Some(location), Some(eval_ctx)) = (location, ctx.as_deref()) {
-            let frames = eval_ctx.storage.frames();
-            state
-                .frame_locations
-                .retain(|id, _| frames.iter().any(|frame| frame.id == *id));
-            if let Some(frame) = eval_ctx.storage.current_frame() {
-                state.frame_locations.insert(frame.id, *location);
+        let is_target_thread = state.is_target_thread();
+        // Tasks passing by while another task is stopped must not move its location.
+        let tracks_location = matches!(state.hook_mode(), DebugMode::Running)
+            || (is_target_thread && state.hook_pending_stop().is_some());
+        if tracks_location {
+            state.last_location = location.copied();
+            state.last_call_depth = call_depth;
+            if let Some(thread_id) = state.own_stop_thread() {
+                state.thread_stop(thread_id).last_location = location.copied();
+            }
+            if let (Some(location), Some(eval_ctx)) = (location, ctx.as_deref()) {
+                let frames = eval_ctx.storage.frames();
+                state
+                    .frame_locations
+                    .retain(|id, _| frames.iter().any(|frame| frame.id == *id));
+                if let Some(frame) = eval_ctx.storage.current_frame() {
+                    state.frame_locations.insert(frame.id, *location);
+                }
             }
         }
-        let is_target_thread =
-            state.target_thread.is_none() || state.target_thread == state.current_thread;
-        if matches!(state.mode, DebugMode::Paused) && is_target_thread {
-            if let Some(reason) = state.pending_stop.take() {
+        if matches!(state.hook_mode(), DebugMode::Paused) && is_target_thread {
+            if let Some(reason) = state.take_pending_stop() {
                 trace_debug(&format!(
                     "hook.pending_stop.consume reason={reason:?} location={} thread={:?}",
                     format_location_ref(location),
@@ -903,7 +1438,7 @@ impl DebugControl {
             }
         }
         let effective_mode = if is_target_thread {
-            state.mode
+            state.hook_mode()
         } else {
             DebugMode::Running
         };
@@ -920,7 +1455,7 @@ impl DebugControl {
                 let step_key = state
                     .current_thread
                     .filter(|id| state.steps.contains_key(id))
-                    .or_else(|| state.steps.contains_key(&0).then_some(0));
+                    .or_else(|| (is_target_thread && state.steps.contains_key(&0)).then_some(0));
                 if let Some(step_key) = step_key {
                     if let Some(step) = state.steps.get_mut(&step_key) {
                         if !step.started {
@@ -964,16 +1499,35 @@ impl DebugControl {
                 ));
                 if let Some(generation) = breakpoint_generation {
                     should_pause = true;
-                    state.steps.clear();
                     stop_reason = Some(DebugStopReason::Breakpoint);
                     stop_generation = Some(generation);
-                    state.target_thread = None;
                 }
             }
+            if !should_pause
+                && matches_function_breakpoint(&mut state.function_breakpoints, location, &mut ctx)
+            {
+                should_pause = true;
+                stop_reason = Some(DebugStopReason::FunctionBreakpoint);
+            }
+            if !should_pause
+                && state
+                    .watchdog_deadlines
+                    .get(&std::thread::current().id())
+                    .is_some_and(|deadline| std::time::Instant::now() > *deadline)
+            {
+                should_pause = true;
+                stop_reason = Some(DebugStopReason::Exception);
+                state.last_fault = Some(DebugFault {
+                    category: FaultCategory::Watchdog,
+                    message: RuntimeError::WatchdogTimeout.to_string().into(),
+                });
+            }
             if should_pause {
-                state.mode = DebugMode::Paused;
+                if !matches!(stop_reason, Some(DebugStopReason::Step)) {
+                    claim_stop(&mut state);
+                }
+                state.enter_pause();
                 if let Some(reason) = stop_reason {
-                    state.pending_stop = None;
                     trace_debug(&format!(
                         "hook.pause.enter reason={reason:?} generation={:?} location={} thread={:?}",
                         stop_generation,
@@ -988,62 +1542,197 @@ impl DebugControl {
                 }
             }
         }
-        loop {
-            let is_target_thread =
-                state.target_thread.is_none() || state.target_thread == state.current_thread;
-            if matches!(state.mode, DebugMode::Paused) && is_target_thread {
-                if let Some(reason) = state.pending_stop.take() {
-                    trace_debug(&format!(
-                        "hook.pending_stop.consume reason={reason:?} location={} thread={:?}",
-                        format_location_ref(location),
-                        state.current_thread
-                    ));
-                    if let Some(eval_ctx) = ctx.as_mut() {
-                        update_watch_snapshot(&mut state, eval_ctx);
-                        update_snapshot(&mut state, eval_ctx);
-                    }
-                    emit_stop(&mut state, reason, location.copied(), None);
+        drop(wait_while_paused(cvar, state, location, &mut ctx));
+    }
+}
+
+/// Block while paused for the current thread, consuming pending stops.
+fn wait_while_paused<'a>(
+    cvar: &Condvar,
+    mut state: MutexGuard<'a, DebugState>,
+    location: Option<&SourceLocation>,
+    ctx: &mut Option<&mut EvalContext<'_>>,
+) -> MutexGuard<'a, DebugState> {
+    loop {
+        let is_target_thread = state.is_target_thread();
+        if matches!(state.hook_mode(), DebugMode::Paused) && is_target_thread {
+            if let Some(reason) = state.take_pending_stop() {
+                trace_debug(&format!(
+                    "hook.pending_stop.consume reason={reason:?} location={} thread={:?}",
+                    format_location_ref(location),
+                    state.current_thread
+                ));
+                if let Some(eval_ctx) = ctx.as_mut() {
+                    update_watch_snapshot(&mut state, eval_ctx);
+                    update_snapshot(&mut state, eval_ctx);
                 }
+                emit_stop(&mut state, reason, location.copied(), None);
             }
-            match state.mode {
-                DebugMode::Running => {
-                    trace_debug(&format!(
-                        "hook.exit reason=running location={} thread={:?}",
-                        format_location_ref(location),
-                        state.current_thread
-                    ));
-                    return;
-                }
-                DebugMode::Paused => {
-                    if !is_target_thread {
-                        trace_debug(&format!(
-                            "hook.exit reason=paused_non_target location={} current_thread={:?} target_thread={:?}",
-                            format_location_ref(location),
-                            state.current_thread,
-                            state.target_thread
-                        ));
-                        return;
-                    }
-                    trace_debug(&format!(
-                        "hook.wait location={} current_thread={:?} target_thread={:?}",
-                        format_location_ref(location),
-                        state.current_thread,
-                        state.target_thread
-                    ));
-                    state = cvar.wait(state).expect("debug state poisoned");
+        }
+        match state.hook_mode() {
+            DebugMode::Running => {
+                trace_debug(&format!(
+                    "hook.exit reason=running location={} thread={:?}",
+                    format_location_ref(location),
+                    state.current_thread
+                ));
+                return state;
+            }
+            DebugMode::Paused => {
+                if !is_target_thread {
                     trace_debug(&format!(
-                        "hook.wake mode={:?} location={} current_thread={:?} target_thread={:?}",
-                        state.mode,
+                        "hook.exit reason=paused_non_target location={} current_thread={:?} target_thread={:?}",
                         format_location_ref(location),
                         state.current_thread,
                         state.target_thread
                     ));
+                    return state;
                 }
+                trace_debug(&format!(
+                    "hook.wait location={} current_thread={:?} target_thread={:?}",
+                    format_location_ref(location),
+                    state.current_thread,
+                    state.target_thread
+                ));
+                state = cvar.wait(state).expect("debug state poisoned");
+                bind_thread(&mut state);
+                trace_debug(&format!(
+                    "hook.wake mode={:?} location={} current_thread={:?} target_thread={:?}",
+                    state.hook_mode(),
+                    format_location_ref(location),
+                    state.current_thread,
+                    state.target_thread
+                ));
             }
         }
     }
 }
 
+/// Restore the task id bound to the calling OS thread.
+fn bind_thread(state: &mut DebugState) {
+    if let Some(thread_id) = state.thread_bindings.get(&std::thread::current().id()) {
+        state.current_thread = *thread_id;
+    }
+}
+
+/// Make the current thread the owner of a new stop.
+fn claim_stop(state: &mut DebugState) {
+    let thread_id = state.current_thread;
+    clear_steps(state, thread_id);
+    state.target_thread = None;
+}
+
+/// Pause one task, or every task, in non-stop mode.
+///
+/// Pausing every task also pauses tasks that have not run yet at their first
+/// statement. Returns false when nothing was left to pause.
+fn pause_threads(state: &mut DebugState, thread_id: Option<u32>, reason: DebugStopReason) -> bool {
+    let mut applied = false;
+    let targets: Vec<u32> = match thread_id {
+        Some(thread_id) => vec![thread_id],
+        None => {
+            applied = !matches!(state.mode, DebugMode::Paused);
+            state.mode = DebugMode::Paused;
+            state.pending_stop = Some(reason);
+            state.thread_stops.keys().copied().collect()
+        }
+    };
+    for thread_id in targets {
+        clear_steps(state, Some(thread_id));
+        let stop = state.thread_stop(thread_id);
+        if matches!(stop.mode, DebugMode::Paused) {
+            continue;
+        }
+        stop.mode = DebugMode::Paused;
+        stop.pending_stop = Some(reason);
+        stop.snapshot = None;
+        applied = true;
+    }
+    applied
+}
+
+/// Resume one task, or every task, in non-stop mode.
+fn continue_threads(state: &mut DebugState, thread_id: Option<u32>) {
+    let targets: Vec<u32> = match thread_id {
+        Some(thread_id) => vec![thread_id],
+        None => {
+            state.mode = DebugMode::Running;
+            state.pending_stop = None;
+            state.snapshot = None;
+            state.thread_stops.keys().copied().collect()
+        }
+    };
+    for thread_id in targets {
+        clear_steps(state, Some(thread_id));
+        let stop = state.thread_stop(thread_id);
+        stop.mode = DebugMode::Running;
+        stop.pending_stop = None;
+        stop.snapshot = None;
+    }
+    // Keep reporting a task that is still stopped.
+    if !state
+        .stop_owner
+        .is_some_and(|thread_id| state.thread_paused(thread_id))
+    {
+        state.stop_owner = state
+            .thread_stops
+            .iter()
+            .filter(|(_, stop)| matches!(stop.mode, DebugMode::Paused))
+            .map(|(thread_id, _)| *thread_id)
+            .min();
+    }
+}
+
+/// Arm a step for a thread, or the stopped one, and resume it.
+fn start_step(state: &mut DebugState, thread_id: Option<u32>, kind: StepKind) {
+    let target_thread = thread_id.or(state.stop_owner).or(state.current_thread);
+    let own_stop = target_thread.filter(|_| state.non_stop);
+    let started = match own_stop {
+        Some(thread_id) => state.thread_paused(thread_id),
+        None => matches!(state.mode, DebugMode::Paused),
+    };
+    let call_depth = match kind {
+        StepKind::Into => state.last_call_depth,
+        StepKind::Over | StepKind::Out => target_thread
+            .and_then(|id| state.last_call_depths.get(&id).copied())
+            .unwrap_or(state.last_call_depth),
+    };
+    let target_depth = match kind {
+        StepKind::Out => call_depth.saturating_sub(1),
+        StepKind::Into | StepKind::Over => call_depth,
+    };
+    if let Some(thread_id) = own_stop {
+        continue_threads(state, Some(thread_id));
+    } else {
+        clear_steps(state, target_thread);
+        state.stop_owner = None;
+        state.mode = DebugMode::Running;
+        state.pending_stop = None;
+        state.snapshot = None;
+        state.target_thread = target_thread;
+    }
+    state.steps.insert(
+        target_thread.unwrap_or(0),
+        StepState {
+            kind,
+            target_depth,
+            started,
+        },
+    );
+}
+
+/// Drop pending steps; in non-stop mode only those of `thread_id`.
+fn clear_steps(state: &mut DebugState, thread_id: Option<u32>) {
+    if state.non_stop {
+        if let Some(thread_id) = thread_id {
+            state.steps.remove(&thread_id);
+        }
+        state.steps.remove(&0);
+    } else {
+        state.steps.clear();
+    }
+}
+
 fn format_location_ref(location: Option<&SourceLocation>) -> String {
     location
         .map(|loc| format!("{}:{}..{}", loc.file_id, loc.start, loc.end))
@@ -1060,6 +1749,13 @@ fn emit_stop(
         "stop reason={reason:?} location={:?} thread={:?}",
         location, state.current_thread
     ));
+    // Time spent paused must not count against the watchdog.
+    state.watchdog_deadlines.clear();
+    state.stop_owner = state.current_thread;
+    if let Some(thread_id) = state.own_stop_thread() {
+        let frame_locations = state.frame_locations.clone();
+        state.thread_stop(thread_id).frame_locations = frame_locations;
+    }
     let stop = DebugStop {
         reason,
         location,
@@ -1071,6 +1767,42 @@ fn emit_stop(
     }
     state.last_stop = Some(stop.clone());
     state.stops.push(stop);
+    // A watchdog stop leaves the overrun window alone, so the runtime
+    // watchdog still faults once execution continues.
+    let watchdog_stop = reason == DebugStopReason::Exception
+        && state
+            .last_fault
+            .as_ref()
+            .is_some_and(|fault| fault.category == FaultCategory::Watchdog);
+    if !watchdog_stop {
+        state.pause_count += 1;
+    }
+}
+
+/// Arm storage watches and pause when a data breakpoint triggers.
+fn check_data_breakpoints(state: &mut DebugState, ctx: &mut EvalContext<'_>) -> bool {
+    if !ctx.storage.has_access_watches(&state.data_targets) {
+        ctx.storage.set_access_watches(state.data_targets.clone());
+        return false;
+    }
+    if state.data_breakpoints.is_empty() {
+        return false;
+    }
+    let accesses = ctx.storage.take_watch_accesses();
+    if accesses.is_empty() {
+        return false;
+    }
+    let matched = matches_data_breakpoint(&mut state.data_breakpoints, &accesses, ctx);
+    if !matched || !matches!(state.hook_mode(), DebugMode::Running) {
+        return false;
+    }
+    state.enter_pause();
+    claim_stop(state);
+    update_watch_snapshot(state, ctx);
+    update_snapshot(state, ctx);
+    let location = state.previous_location();
+    emit_stop(state, DebugStopReason::DataBreakpoint, location, None);
+    true
 }
 
 fn update_watch_snapshot(state: &mut DebugState, ctx: &mut EvalContext<'_>) {
@@ -1087,11 +1819,39 @@ fn update_watch_snapshot(state: &mut DebugState, ctx: &mut EvalContext<'_>) {
     }
 }
 
+fn show_history_cycle(state: &mut DebugState, cycle: u64) -> Option<u64> {
+    // History is recorded by the resource cycle; tasks stopped on their own
+    // in non-stop mode have no cycle to show.
+    if state.non_stop || !matches!(state.mode, DebugMode::Paused) {
+        return None;
+    }
+    let history = state.history.as_ref()?;
+    let storage = history.storage_at(cycle)?;
+    let now = history.record(cycle)?.time;
+    if state.history_cursor.is_none() {
+        state.live_snapshot = state.snapshot.take();
+    }
+    state.snapshot = Some(DebugSnapshot { storage, now });
+    state.history_cursor = Some(cycle);
+    trace_debug(&format!("history.show cycle={cycle}"));
+    Some(cycle)
+}
+
+fn leave_history(state: &mut DebugState) {
+    if state.history_cursor.take().is_some() {
+        state.snapshot = state.live_snapshot.take();
+    }
+}
+
 fn update_snapshot(state: &mut DebugState, ctx: &mut EvalContext<'_>) {
-    state.snapshot = Some(DebugSnapshot {
+    let snapshot = Some(DebugSnapshot {
         storage: ctx.storage.clone(),
         now: ctx.now,
     });
+    match state.own_stop_thread() {
+        Some(thread_id) => state.thread_stop(thread_id).snapshot = snapshot,
+        None => state.snapshot = snapshot,
+    }
 }
 
 #[cfg(test)]
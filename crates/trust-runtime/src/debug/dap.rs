@@ -9,7 +9,7 @@ use smol_str::SmolStr;
 
 use crate::io::{IoAddress, IoSize, IoSnapshot, IoSnapshotEntry, IoSnapshotValue};
 use crate::memory::{FrameId, InstanceId, IoArea};
-use crate::value::{ArrayValue, StructValue, Value, ValueRef};
+use crate::value::{value_type_name, ArrayValue, StructValue, Value, ValueRef};
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -94,45 +94,6 @@ impl DebugVariableHandles {
     }
 }
 
-pub fn value_type_name(value: &Value) -> Option<String> {
-    let name = match value {
-        Value::Bool(_) => "BOOL",
-        Value::SInt(_) => "SINT",
-        Value::Int(_) => "INT",
-        Value::DInt(_) => "DINT",
-        Value::LInt(_) => "LINT",
-        Value::USInt(_) => "USINT",
-        Value::UInt(_) => "UINT",
-        Value::UDInt(_) => "UDINT",
-        Value::ULInt(_) => "ULINT",
-        Value::Real(_) => "REAL",
-        Value::LReal(_) => "LREAL",
-        Value::Byte(_) => "BYTE",
-        Value::Word(_) => "WORD",
-        Value::DWord(_) => "DWORD",
-        Value::LWord(_) => "LWORD",
-        Value::Time(_) => "TIME",
-        Value::LTime(_) => "LTIME",
-        Value::Date(_) => "DATE",
-        Value::LDate(_) => "LDATE",
-        Value::Tod(_) => "TOD",
-        Value::LTod(_) => "LTOD",
-        Value::Dt(_) => "DT",
-        Value::Ldt(_) => "LDT",
-        Value::String(_) => "STRING",
-        Value::WString(_) => "WSTRING",
-        Value::Char(_) => "CHAR",
-        Value::WChar(_) => "WCHAR",
-        Value::Array(_) => "ARRAY",
-        Value::Struct(value) => return Some(value.type_name.to_string()),
-        Value::Enum(value) => return Some(value.type_name.to_string()),
-        Value::Reference(_) => "REF",
-        Value::Instance(_) => "INSTANCE",
-        Value::Null => "NULL",
-    };
-    Some(name.to_string())
-}
-
 pub fn format_value(value: &Value) -> String {
     match value {
         Value::Bool(value) => {
@@ -0,0 +1,227 @@
+//! Cycle history recorder for reverse debugging.
+
+#![allow(missing_docs)]
+
+use std::collections::VecDeque;
+
+use smol_str::SmolStr;
+
+use crate::io::IoSnapshotEntry;
+use crate::memory::{MemoryLocation, VariableStorage};
+use crate::value::{Duration, Value, ValueRef};
+
+/// Storage cell written with a new value during a cycle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleChange {
+    /// Storage area (global, retain or instance).
+    pub location: MemoryLocation,
+    /// Slot index within the area.
+    pub offset: usize,
+    /// Variable name.
+    pub name: SmolStr,
+    /// Value at the end of the cycle.
+    pub value: Value,
+}
+
+/// Inputs and storage deltas of one completed cycle.
+#[derive(Debug, Clone)]
+pub struct CycleRecord {
+    /// Cycle counter value when the cycle started.
+    pub cycle: u64,
+    /// Runtime time at cycle start.
+    pub time: Duration,
+    /// Input image latched at cycle start.
+    pub inputs: Vec<IoSnapshotEntry>,
+    /// Variables whose value changed during the cycle.
+    pub changes: Vec<CycleChange>,
+}
+
+impl CycleRecord {
+    /// Whether this cycle changed the storage slot a reference points into.
+    #[must_use]
+    pub fn touches(&self, target: &ValueRef) -> bool {
+        self.changes
+            .iter()
+            .any(|change| change.location == target.location && change.offset == target.offset)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingCycle {
+    cycle: u64,
+    time: Duration,
+    inputs: Vec<IoSnapshotEntry>,
+}
+
+/// Bounded ring buffer of the last N completed cycles.
+///
+/// The oldest state is kept as a full checkpoint; every later state is
+/// rebuilt by replaying recorded deltas on top of it.
+#[derive(Debug, Clone)]
+pub struct CycleHistory {
+    capacity: usize,
+    checkpoint: Option<VariableStorage>,
+    head: Option<VariableStorage>,
+    records: VecDeque<CycleRecord>,
+    pending: Option<PendingCycle>,
+}
+
+impl CycleHistory {
+    /// Create a recorder keeping at most `capacity` cycles.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            checkpoint: None,
+            head: None,
+            records: VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    /// Maximum number of recorded cycles.
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Recorded cycles, oldest first.
+    pub fn records(&self) -> impl DoubleEndedIterator<Item = &CycleRecord> {
+        self.records.iter()
+    }
+
+    /// Return the record for a cycle, if still buffered.
+    #[must_use]
+    pub fn record(&self, cycle: u64) -> Option<&CycleRecord> {
+        self.records.iter().find(|record| record.cycle == cycle)
+    }
+
+    /// Oldest buffered cycle.
+    #[must_use]
+    pub fn oldest_cycle(&self) -> Option<u64> {
+        self.records.front().map(|record| record.cycle)
+    }
+
+    /// Newest buffered cycle.
+    #[must_use]
+    pub fn newest_cycle(&self) -> Option<u64> {
+        self.records.back().map(|record| record.cycle)
+    }
+
+    /// Start recording a cycle after its inputs were latched.
+    pub fn begin_cycle(
+        &mut self,
+        cycle: u64,
+        time: Duration,
+        inputs: Vec<IoSnapshotEntry>,
+        storage: &VariableStorage,
+    ) {
+        if self.head.is_none() {
+            self.checkpoint = Some(storage.clone());
+            self.head = Some(storage.clone());
+        }
+        self.pending = Some(PendingCycle {
+            cycle,
+            time,
+            inputs,
+        });
+    }
+
+    /// Finish the pending cycle and store its deltas.
+    pub fn end_cycle(&mut self, storage: &VariableStorage) {
+        let (Some(pending), Some(head)) = (self.pending.take(), self.head.as_mut()) else {
+            return;
+        };
+        let changes = diff_storage(head, storage);
+        // Only instances created during the cycle need a fresh copy; every
+        // other slot is brought up to date by its delta.
+        if storage
+            .instances()
+            .keys()
+            .all(|id| head.get_instance(*id).is_some())
+        {
+            apply_changes(head, &changes);
+        } else {
+            *head = storage.clone();
+        }
+        self.records.push_back(CycleRecord {
+            cycle: pending.cycle,
+            time: pending.time,
+            inputs: pending.inputs,
+            changes,
+        });
+        while self.records.len() > self.capacity {
+            let Some(evicted) = self.records.pop_front() else {
+                break;
+            };
+            if let Some(checkpoint) = self.checkpoint.as_mut() {
+                apply_changes(checkpoint, &evicted.changes);
+            }
+        }
+    }
+
+    /// Rebuild the storage as it was at the end of `cycle`.
+    #[must_use]
+    pub fn storage_at(&self, cycle: u64) -> Option<VariableStorage> {
+        self.record(cycle)?;
+        let mut storage = self.checkpoint.clone()?;
+        for record in self
+            .records
+            .iter()
+            .take_while(|record| record.cycle <= cycle)
+        {
+            apply_changes(&mut storage, &record.changes);
+        }
+        Some(storage)
+    }
+}
+
+fn diff_storage(before: &VariableStorage, after: &VariableStorage) -> Vec<CycleChange> {
+    let mut changes = Vec::new();
+    let mut diff_area = |location: MemoryLocation,
+                         before: Option<&indexmap::IndexMap<SmolStr, Value>>,
+                         after: &indexmap::IndexMap<SmolStr, Value>| {
+        for (offset, (name, value)) in after.iter().enumerate() {
+            let previous = before.and_then(|area| area.get_index(offset));
+            if previous != Some((name, value)) {
+                changes.push(CycleChange {
+                    location,
+                    offset,
+                    name: name.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+    };
+    diff_area(
+        MemoryLocation::Global,
+        Some(before.globals()),
+        after.globals(),
+    );
+    diff_area(
+        MemoryLocation::Retain,
+        Some(before.retain()),
+        after.retain(),
+    );
+    for (id, instance) in after.instances() {
+        let previous = before.get_instance(*id).map(|data| &data.variables);
+        diff_area(MemoryLocation::Instance(*id), previous, &instance.variables);
+    }
+    changes
+}
+
+fn apply_changes(storage: &mut VariableStorage, changes: &[CycleChange]) {
+    for change in changes {
+        match change.location {
+            MemoryLocation::Global => storage.set_global(change.name.clone(), change.value.clone()),
+            MemoryLocation::Retain => storage.set_retain(change.name.clone(), change.value.clone()),
+            MemoryLocation::Instance(id) => {
+                if storage.get_instance(id).is_none() {
+                    continue;
+                }
+                storage.set_instance_var(id, change.name.clone(), change.value.clone());
+            }
+            MemoryLocation::Local(_) | MemoryLocation::Io(_) => {}
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 #![allow(missing_docs)]
 
+use crate::error::RuntimeError;
 use crate::eval::EvalContext;
 
 use super::SourceLocation;
@@ -20,6 +21,18 @@ pub trait DebugHook {
     ) {
         self.on_statement(location, call_depth);
     }
+
+    /// Called after a program body finishes, before its frame is popped.
+    fn on_program_end(&mut self, _ctx: &mut EvalContext<'_>) {}
+
+    /// Called when a statement fails, before its frames unwind and the fault policy runs.
+    fn on_fault(
+        &mut self,
+        _ctx: &mut EvalContext<'_>,
+        _location: Option<&SourceLocation>,
+        _error: &RuntimeError,
+    ) {
+    }
 }
 
 /// No-op debug hook.
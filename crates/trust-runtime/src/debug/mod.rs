@@ -5,6 +5,7 @@
 mod breakpoints;
 mod control;
 pub mod dap;
+mod history;
 mod hook;
 mod resolve;
 mod trace;
@@ -13,9 +14,14 @@ mod types;
 pub use control::{ControlAction, ControlOutcome, DebugControl, DebugMode, StepKind};
 pub(crate) use control::{ForcedVarTarget, PendingVarTarget};
 pub use dap::{DebugScope, DebugSource, DebugVariable, DebugVariableHandles, VariableHandle};
+pub use history::{CycleChange, CycleHistory, CycleRecord};
 pub use hook::{DebugHook, NoopDebugHook};
-pub use resolve::{location_to_line_col, offset_to_line_col, resolve_breakpoint_location};
+pub use resolve::{
+    location_to_line_col, offset_to_line_col, resolve_breakpoint_location,
+    resolve_function_breakpoint, FunctionBreakpointTarget,
+};
 pub use types::{
-    DebugBreakpoint, DebugLog, DebugSnapshot, DebugStop, DebugStopReason, HitCondition,
-    LogFragment, RuntimeEvent, SourceLocation,
+    DataAccess, DebugBreakpoint, DebugDataBreakpoint, DebugFault, DebugFunctionBreakpoint,
+    DebugLog, DebugSnapshot, DebugStop, DebugStopReason, FaultCategory, HitCondition, LogFragment,
+    RuntimeEvent, SourceLocation,
 };
@@ -2,8 +2,29 @@
 
 #![allow(missing_docs)]
 
+use indexmap::IndexMap;
+use smol_str::SmolStr;
+
+use crate::eval::stmt::Stmt;
+use crate::eval::{ClassDef, FunctionBlockDef, FunctionDef, MethodDef};
+use crate::memory::{InstanceId, VariableStorage};
+use crate::runtime::method_tables;
+use crate::task::ProgramDef;
+use crate::value::Value;
+
 use super::SourceLocation;
 
+/// Function breakpoint resolved to the entry statement of a POU body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionBreakpointTarget {
+    /// First statement of the body.
+    pub location: SourceLocation,
+    /// Instance filter when the name was an instance path.
+    pub instance: Option<InstanceId>,
+    /// USING directives in scope for the body.
+    pub using: Vec<SmolStr>,
+}
+
 /// Resolve a line/column breakpoint to the nearest statement boundary.
 ///
 /// Lines/columns are 0-based and measured in bytes.
@@ -89,6 +110,148 @@ pub fn resolve_breakpoint_location(
     next_stmt
 }
 
+/// Resolve a function breakpoint name to the first statement of a POU body.
+///
+/// Accepts `PROGRAM`/`FUNCTION`/`FUNCTION_BLOCK` names and `FB.Method` (every
+/// instance), instance paths such as `Main.motor1` or `Main.motor1.Start`
+/// (that instance only), and bare method names when they are unique. Programs
+/// declared in a `CONFIGURATION` are registered under their instance name.
+pub fn resolve_function_breakpoint(
+    name: &str,
+    storage: &VariableStorage,
+    functions: &IndexMap<SmolStr, FunctionDef>,
+    function_blocks: &IndexMap<SmolStr, FunctionBlockDef>,
+    classes: &IndexMap<SmolStr, ClassDef>,
+    programs: &IndexMap<SmolStr, ProgramDef>,
+) -> Option<FunctionBreakpointTarget> {
+    let name = name.trim();
+    if let Some(program) = lookup(programs, name) {
+        return entry(&program.body, &program.using, None);
+    }
+    if let Some(function) = lookup(functions, name) {
+        return entry(&function.body, &function.using, None);
+    }
+    if let Some(fb) = lookup(function_blocks, name) {
+        return entry(&fb.body, &fb.using, None);
+    }
+    if let Some((type_name, method_name)) = name.rsplit_once('.') {
+        if let Some(using) = type_using(function_blocks, classes, type_name) {
+            let method = find_method(function_blocks, classes, type_name, method_name)?;
+            return method_entry(method, using, None);
+        }
+    }
+    if let Some(target) = resolve_instance_path(name, storage, function_blocks, classes, programs) {
+        return Some(target);
+    }
+    let mut methods = function_blocks
+        .values()
+        .flat_map(|fb| {
+            fb.methods
+                .iter()
+                .map(|method| (method, fb.using.as_slice()))
+        })
+        .chain(classes.values().flat_map(|class| {
+            class
+                .methods
+                .iter()
+                .map(|method| (method, class.using.as_slice()))
+        }))
+        .filter(|(method, _)| method.name.eq_ignore_ascii_case(name));
+    let (method, using) = methods.next()?;
+    if methods.next().is_some() {
+        return None;
+    }
+    method_entry(method, using, None)
+}
+
+fn resolve_instance_path(
+    path: &str,
+    storage: &VariableStorage,
+    function_blocks: &IndexMap<SmolStr, FunctionBlockDef>,
+    classes: &IndexMap<SmolStr, ClassDef>,
+    programs: &IndexMap<SmolStr, ProgramDef>,
+) -> Option<FunctionBreakpointTarget> {
+    let mut segments = path.split('.').map(str::trim);
+    let Value::Instance(mut instance_id) = storage.get_global(segments.next()?)? else {
+        return None;
+    };
+    let mut method_name = None;
+    for segment in segments {
+        if method_name.is_some() {
+            return None;
+        }
+        match storage.get_instance_var(instance_id, segment) {
+            Some(Value::Instance(next)) => instance_id = *next,
+            _ => method_name = Some(segment),
+        }
+    }
+    let type_name = storage.get_instance(instance_id)?.type_name.clone();
+    if let Some(method_name) = method_name {
+        let using = type_using(function_blocks, classes, &type_name)?;
+        let method = find_method(function_blocks, classes, &type_name, method_name)?;
+        return method_entry(method, using, Some(instance_id));
+    }
+    if let Some(program) = lookup(programs, &type_name) {
+        return entry(&program.body, &program.using, Some(instance_id));
+    }
+    let fb = lookup(function_blocks, &type_name)?;
+    entry(&fb.body, &fb.using, Some(instance_id))
+}
+
+fn lookup<'a, T>(map: &'a IndexMap<SmolStr, T>, name: &str) -> Option<&'a T> {
+    map.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+}
+
+fn type_using<'a>(
+    function_blocks: &'a IndexMap<SmolStr, FunctionBlockDef>,
+    classes: &'a IndexMap<SmolStr, ClassDef>,
+    type_name: &str,
+) -> Option<&'a [SmolStr]> {
+    lookup(function_blocks, type_name)
+        .map(|fb| fb.using.as_slice())
+        .or_else(|| lookup(classes, type_name).map(|class| class.using.as_slice()))
+}
+
+fn find_method<'a>(
+    function_blocks: &'a IndexMap<SmolStr, FunctionBlockDef>,
+    classes: &'a IndexMap<SmolStr, ClassDef>,
+    type_name: &str,
+    method_name: &str,
+) -> Option<&'a MethodDef> {
+    method_tables(function_blocks, classes, type_name)
+        .into_iter()
+        .flatten()
+        .find(|method| method.name.eq_ignore_ascii_case(method_name))
+}
+
+fn method_entry(
+    method: &MethodDef,
+    type_using: &[SmolStr],
+    instance: Option<InstanceId>,
+) -> Option<FunctionBreakpointTarget> {
+    let using = if method.using.is_empty() {
+        type_using
+    } else {
+        method.using.as_slice()
+    };
+    entry(&method.body, using, instance)
+}
+
+fn entry(
+    body: &[Stmt],
+    using: &[SmolStr],
+    instance: Option<InstanceId>,
+) -> Option<FunctionBreakpointTarget> {
+    let location = body.iter().find_map(|stmt| stmt.location().copied())?;
+    Some(FunctionBreakpointTarget {
+        location,
+        instance,
+        using: using.to_vec(),
+    })
+}
+
 /// Convert a byte offset into a 0-based line/column.
 #[must_use]
 pub fn offset_to_line_col(source: &str, offset: u32) -> (u32, u32) {
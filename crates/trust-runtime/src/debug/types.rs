@@ -4,9 +4,10 @@
 
 use smol_str::SmolStr;
 
+use crate::error::RuntimeError;
 use crate::eval::expr::Expr;
-use crate::memory::VariableStorage;
-use crate::value::Duration;
+use crate::memory::{InstanceId, VariableStorage};
+use crate::value::{Duration, ValueRef};
 
 /// Source location for a statement or expression.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -95,6 +96,57 @@ impl DebugBreakpoint {
     }
 }
 
+/// Function breakpoint on the entry statement of a POU body.
+#[derive(Debug, Clone)]
+pub struct DebugFunctionBreakpoint {
+    /// Requested name (e.g. `FB_Motor.Start` or `Main.motor1.Start`).
+    pub name: SmolStr,
+    /// Entry statement with condition and hit count state.
+    pub breakpoint: DebugBreakpoint,
+    /// Only stop while executing on behalf of this instance.
+    pub instance: Option<InstanceId>,
+}
+
+/// Access that triggers a data breakpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataAccess {
+    /// Break when the watched value is read.
+    Read,
+    /// Break when the watched value is written, even with the same value.
+    Write,
+    /// Break on reads and writes.
+    ReadWrite,
+}
+
+/// Data breakpoint on a storage location.
+#[derive(Debug, Clone)]
+pub struct DebugDataBreakpoint {
+    /// Watched storage location.
+    pub target: ValueRef,
+    /// Access kind that triggers the breakpoint.
+    pub access: DataAccess,
+    /// Optional condition evaluated after the access (e.g. `speed = 100`).
+    pub condition: Option<Expr>,
+    /// Optional hit count condition.
+    pub hit_condition: Option<HitCondition>,
+    /// Current hit count for this breakpoint.
+    pub hits: u64,
+}
+
+impl DebugDataBreakpoint {
+    /// Create an unconditional data breakpoint.
+    #[must_use]
+    pub fn new(target: ValueRef, access: DataAccess) -> Self {
+        Self {
+            target,
+            access,
+            condition: None,
+            hit_condition: None,
+            hits: 0,
+        }
+    }
+}
+
 /// Captured log output.
 #[derive(Debug, Clone)]
 pub struct DebugLog {
@@ -177,6 +229,80 @@ pub enum DebugStopReason {
     Pause,
     /// Paused due to stopOnEntry.
     Entry,
+    /// Paused after a watched variable was accessed.
+    DataBreakpoint,
+    /// Paused on entry to a POU with a function breakpoint.
+    FunctionBreakpoint,
+    /// Paused at a statement that raised a runtime fault.
+    Exception,
+}
+
+/// Fault category selectable as an exception breakpoint filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FaultCategory {
+    /// Division/modulo by zero and arithmetic overflow.
+    Arithmetic,
+    /// Array index out of bounds.
+    Bounds,
+    /// Dereference of a NULL reference.
+    NullReference,
+    /// Cycle exceeded the watchdog timeout.
+    Watchdog,
+    /// Failed `ASSERT_*` call.
+    Assertion,
+}
+
+impl FaultCategory {
+    /// All categories, in filter order.
+    pub const ALL: [Self; 5] = [
+        Self::Arithmetic,
+        Self::Bounds,
+        Self::NullReference,
+        Self::Watchdog,
+        Self::Assertion,
+    ];
+
+    /// Stable identifier (used as the DAP exception filter id).
+    #[must_use]
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Arithmetic => "arithmetic",
+            Self::Bounds => "bounds",
+            Self::NullReference => "nullReference",
+            Self::Watchdog => "watchdog",
+            Self::Assertion => "assertion",
+        }
+    }
+
+    /// Parse an identifier produced by [`FaultCategory::id`].
+    #[must_use]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.id() == id)
+    }
+
+    /// Classify a runtime error; `None` for errors outside the filter categories.
+    #[must_use]
+    pub fn of(error: &RuntimeError) -> Option<Self> {
+        match error {
+            RuntimeError::DivisionByZero | RuntimeError::ModuloByZero | RuntimeError::Overflow => {
+                Some(Self::Arithmetic)
+            }
+            RuntimeError::IndexOutOfBounds { .. } => Some(Self::Bounds),
+            RuntimeError::NullReference => Some(Self::NullReference),
+            RuntimeError::WatchdogTimeout => Some(Self::Watchdog),
+            RuntimeError::AssertionFailed(_) => Some(Self::Assertion),
+            _ => None,
+        }
+    }
+}
+
+/// Fault that paused execution through an exception breakpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugFault {
+    /// Matched filter category.
+    pub category: FaultCategory,
+    /// Runtime error message.
+    pub message: SmolStr,
 }
 
 /// Notification emitted when execution stops.
@@ -17,6 +17,7 @@ use crate::value::{default_value_for_type_id, DateTimeProfile, Duration, Value};
 
 pub mod expr;
 pub mod ops;
+pub mod sfc;
 pub mod stmt;
 
 /// Evaluation context shared across expression and statement execution.
@@ -188,6 +189,22 @@ pub fn call_function<'a>(
     func: &'a FunctionDef,
     args: &[CallArg],
 ) -> Result<Value, RuntimeError> {
+    call_function_with_body(ctx, func, args, |ctx| exec_block(ctx, &func.body))
+}
+
+/// Call a function definition, executing its body through `body`.
+///
+/// Argument binding, frame setup and output copy-back match [`call_function`];
+/// the bytecode VM uses this to run callee bodies from POU_BODIES.
+pub(crate) fn call_function_with_body<'a, F>(
+    ctx: &mut EvalContext<'a>,
+    func: &'a FunctionDef,
+    args: &[CallArg],
+    body: F,
+) -> Result<Value, RuntimeError>
+where
+    F: FnOnce(&mut EvalContext<'a>) -> Result<stmt::StmtResult, RuntimeError>,
+{
     let saved_using = ctx.using;
     let saved_return = ctx.return_name.clone();
     let PreparedBindings {
@@ -205,10 +222,11 @@ pub fn call_function<'a>(
 
     ctx.using = Some(&func.using);
     ctx.storage.push_frame(func.name.clone());
-    ctx.return_name = Some(func.name.clone());
+    let return_name = function_return_name(&func.name);
+    ctx.return_name = Some(return_name.clone());
     let return_default = default_value_for_type_id(func.return_type, ctx.registry, &ctx.profile)
         .unwrap_or(Value::Null);
-    ctx.storage.set_local(func.name.clone(), return_default);
+    ctx.storage.set_local(return_name, return_default);
     for (name, value) in param_values {
         ctx.storage.set_local(name, value);
     }
@@ -235,7 +253,7 @@ pub fn call_function<'a>(
         ctx.using = saved_using;
         return Err(err);
     }
-    let result = match exec_block(ctx, &func.body) {
+    let result = match body(ctx) {
         Ok(result) => result,
         Err(err) => {
             ctx.call_depth = saved_call_depth;
@@ -280,6 +298,17 @@ pub fn call_function<'a>(
     Ok(return_value)
 }
 
+/// Name of a function's return variable.
+///
+/// Functions declared in a NAMESPACE are registered by qualified name, but
+/// the body assigns the result through the unqualified name.
+pub(crate) fn function_return_name(name: &SmolStr) -> SmolStr {
+    match name.rsplit_once('.') {
+        Some((_, simple)) => SmolStr::new(simple),
+        None => name.clone(),
+    }
+}
+
 /// Call a method definition on a specific instance.
 pub fn call_method(
     ctx: &mut EvalContext<'_>,
@@ -287,6 +316,22 @@ pub fn call_method(
     instance_id: InstanceId,
     args: &[CallArg],
 ) -> Result<Value, RuntimeError> {
+    call_method_with_body(ctx, method, instance_id, args, |ctx| {
+        exec_block(ctx, &method.body)
+    })
+}
+
+/// Call a method on an instance, executing its body through `body`.
+pub(crate) fn call_method_with_body<'a, F>(
+    ctx: &mut EvalContext<'a>,
+    method: &MethodDef,
+    instance_id: InstanceId,
+    args: &[CallArg],
+    body: F,
+) -> Result<Value, RuntimeError>
+where
+    F: FnOnce(&mut EvalContext<'a>) -> Result<stmt::StmtResult, RuntimeError>,
+{
     let saved_using = ctx.using;
     let saved_instance = ctx.current_instance;
     let saved_return = ctx.return_name.clone();
@@ -340,7 +385,7 @@ pub fn call_method(
         ctx.current_instance = saved_instance;
         return Err(err);
     }
-    let result = match exec_block(ctx, &method.body) {
+    let result = match body(ctx) {
         Ok(result) => result,
         Err(err) => {
             ctx.call_depth = saved_call_depth;
@@ -398,6 +443,22 @@ pub fn call_function_block<'a>(
     instance_id: InstanceId,
     args: &[CallArg],
 ) -> Result<(), RuntimeError> {
+    call_function_block_with_body(ctx, fb, instance_id, args, |ctx| exec_block(ctx, &fb.body))
+}
+
+/// Call a function block instance, executing a user body through `body`.
+///
+/// Standard function blocks still run their built-in implementation.
+pub(crate) fn call_function_block_with_body<'a, F>(
+    ctx: &mut EvalContext<'a>,
+    fb: &'a FunctionBlockDef,
+    instance_id: InstanceId,
+    args: &[CallArg],
+    body: F,
+) -> Result<(), RuntimeError>
+where
+    F: FnOnce(&mut EvalContext<'a>) -> Result<stmt::StmtResult, RuntimeError>,
+{
     let saved_using = ctx.using;
     let saved_instance = ctx.current_instance;
     let PreparedBindings {
@@ -441,7 +502,7 @@ pub fn call_function_block<'a>(
             ctx.using = saved_using;
             return Err(err);
         }
-        exec_block(ctx, &fb.body)
+        body(ctx)
     };
     let result = match result {
         Ok(result) => result,
@@ -0,0 +1,511 @@
+//! Sequential Function Chart (SFC) execution.
+//!
+//! A chart runs once per POU invocation: active steps update their `T`
+//! timers, associated actions execute according to their qualifiers, and
+//! enabled transitions fire to move the active step set. An action whose
+//! activity ends executes one final time with `Q = FALSE`.
+//!
+//! The chart state machine is shared by the evaluator and the bytecode VM;
+//! an [`SfcHost`] supplies the embedded expressions and action bodies.
+
+#![allow(missing_docs)]
+
+use indexmap::IndexMap;
+use smol_str::SmolStr;
+use trust_hir::types::SFC_STEP_TYPE_NAME;
+
+use crate::debug::SourceLocation;
+use crate::error::RuntimeError;
+use crate::eval::expr::{eval_expr, write_lvalue, Expr, LValue};
+use crate::eval::stmt::{exec_block, Stmt, StmtResult};
+use crate::eval::EvalContext;
+use crate::memory::InstanceId;
+use crate::value::{Duration, StructValue, Value};
+
+const STATE_INIT: &str = "__SFC_INIT";
+const STATE_START_PREFIX: &str = "__SFC_START_";
+const STATE_NEW_PREFIX: &str = "__SFC_NEW_";
+const STATE_STORED_PREFIX: &str = "__SFC_STORED_";
+const STATE_ACTIVE_PREFIX: &str = "__SFC_Q_";
+
+/// Lowered SFC network of a PROGRAM or FUNCTION_BLOCK body.
+///
+/// `E` is the form of embedded expressions and `B` the form of action
+/// bodies: eval AST nodes for the evaluator, code spans for the bytecode VM.
+#[derive(Debug, Clone)]
+pub struct SfcChart<E = Expr, B = SfcActionBody> {
+    pub steps: Vec<SfcStep<E>>,
+    pub actions: Vec<SfcAction<B>>,
+    pub transitions: Vec<SfcTransition<E>>,
+}
+
+impl<E, B> Default for SfcChart<E, B> {
+    fn default() -> Self {
+        Self {
+            steps: Vec::new(),
+            actions: Vec::new(),
+            transitions: Vec::new(),
+        }
+    }
+}
+
+/// SFC step with its action associations.
+#[derive(Debug, Clone)]
+pub struct SfcStep<E = Expr> {
+    pub name: SmolStr,
+    pub initial: bool,
+    pub associations: Vec<SfcAssociation<E>>,
+}
+
+/// Association between a step and an action.
+#[derive(Debug, Clone)]
+pub struct SfcAssociation<E = Expr> {
+    pub action: usize,
+    pub qualifier: SfcQualifier,
+    pub duration: Option<E>,
+}
+
+/// Supported action qualifiers (IEC 61131-3 Table 59).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SfcQualifier {
+    /// Non-stored: active while the step is active.
+    N,
+    /// Reset a stored action.
+    R,
+    /// Set (stored) until reset.
+    S,
+    /// Pulse: executes once when the step becomes active.
+    P,
+    /// Time limited: active for the given duration.
+    L,
+    /// Time delayed: active after the given duration.
+    D,
+}
+
+impl SfcQualifier {
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.to_ascii_uppercase().as_str() {
+            "N" => Some(Self::N),
+            "R" => Some(Self::R),
+            "S" => Some(Self::S),
+            "P" => Some(Self::P),
+            "L" => Some(Self::L),
+            "D" => Some(Self::D),
+            _ => None,
+        }
+    }
+}
+
+/// Action executed by SFC steps.
+#[derive(Debug, Clone)]
+pub struct SfcAction<B = SfcActionBody> {
+    pub name: SmolStr,
+    pub body: B,
+}
+
+/// Action implementation: an ACTION body or a BOOL variable driven by the action flag.
+#[derive(Debug, Clone)]
+pub enum SfcActionBody {
+    Statements(Vec<Stmt>),
+    Variable(SmolStr),
+}
+
+/// Transition between step sets.
+#[derive(Debug, Clone)]
+pub struct SfcTransition<E = Expr> {
+    pub from: Vec<usize>,
+    pub to: Vec<usize>,
+    pub condition: E,
+    pub priority: Option<E>,
+    pub location: Option<SourceLocation>,
+}
+
+/// Evaluates the expressions and runs the action bodies of a chart.
+pub(crate) trait SfcHost<E, B> {
+    /// Evaluate a duration or priority expression.
+    fn eval(&mut self, ctx: &mut EvalContext<'_>, expr: &E) -> Result<Value, RuntimeError>;
+
+    /// Evaluate a transition condition; this is a debugger statement boundary.
+    fn eval_condition(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        transition: &SfcTransition<E>,
+    ) -> Result<Value, RuntimeError>;
+
+    /// Run an action with its current `Q` flag.
+    ///
+    /// `final_scan` is set on the falling edge of `Q`, when statement actions
+    /// execute one last time.
+    fn run_action(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        body: &B,
+        q: bool,
+        final_scan: bool,
+    ) -> Result<(), RuntimeError>;
+}
+
+struct EvalHost;
+
+impl SfcHost<Expr, SfcActionBody> for EvalHost {
+    fn eval(&mut self, ctx: &mut EvalContext<'_>, expr: &Expr) -> Result<Value, RuntimeError> {
+        eval_expr(ctx, expr)
+    }
+
+    fn eval_condition(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        transition: &SfcTransition,
+    ) -> Result<Value, RuntimeError> {
+        #[cfg(feature = "debug")]
+        if let Some(hook) = ctx.debug.take() {
+            hook.on_statement_with_context(ctx, transition.location.as_ref(), ctx.call_depth);
+            ctx.debug = Some(hook);
+        }
+        eval_expr(ctx, &transition.condition)
+    }
+
+    fn run_action(
+        &mut self,
+        ctx: &mut EvalContext<'_>,
+        body: &SfcActionBody,
+        q: bool,
+        final_scan: bool,
+    ) -> Result<(), RuntimeError> {
+        match body {
+            SfcActionBody::Statements(body) => {
+                if q || final_scan {
+                    match exec_block(ctx, body)? {
+                        StmtResult::Continue | StmtResult::Return(_) => {}
+                        _ => return Err(RuntimeError::InvalidControlFlow),
+                    }
+                }
+            }
+            SfcActionBody::Variable(name) => {
+                write_lvalue(ctx, &LValue::Name(name.clone()), Value::Bool(q))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Execute one scan of an SFC network.
+pub fn exec_chart(ctx: &mut EvalContext<'_>, chart: &SfcChart) -> Result<StmtResult, RuntimeError> {
+    run_chart(ctx, chart, &mut EvalHost)?;
+    Ok(StmtResult::Continue)
+}
+
+/// Execute one scan of a chart whose expressions and bodies run on `host`.
+pub(crate) fn run_chart<E, B, H: SfcHost<E, B>>(
+    ctx: &mut EvalContext<'_>,
+    chart: &SfcChart<E, B>,
+    host: &mut H,
+) -> Result<(), RuntimeError> {
+    let instance = ctx
+        .current_instance
+        .ok_or_else(|| RuntimeError::UndefinedVariable(STATE_INIT.into()))?;
+    let now = ctx.now;
+
+    if !matches!(
+        ctx.storage.get_instance_var(instance, STATE_INIT),
+        Some(Value::Bool(true))
+    ) {
+        for step in chart.steps.iter().filter(|step| step.initial) {
+            activate_step(ctx, instance, step, now);
+        }
+        ctx.storage
+            .set_instance_var(instance, STATE_INIT, Value::Bool(true));
+    }
+
+    let mut active = Vec::with_capacity(chart.steps.len());
+    for step in &chart.steps {
+        let is_active = step_flag(ctx, instance, step);
+        if is_active {
+            let start = state_duration(ctx, instance, STATE_START_PREFIX, &step.name, now);
+            let elapsed = Duration::from_nanos(now.as_nanos().saturating_sub(start.as_nanos()));
+            write_step(ctx, instance, step, true, elapsed);
+        }
+        active.push(is_active);
+    }
+
+    exec_actions(ctx, instance, chart, &active, host)?;
+
+    for (step, is_active) in chart.steps.iter().zip(&active) {
+        if *is_active {
+            set_state(
+                ctx,
+                instance,
+                STATE_NEW_PREFIX,
+                &step.name,
+                Value::Bool(false),
+            );
+        }
+    }
+
+    fire_transitions(ctx, instance, chart, &active, now, host)
+}
+
+fn exec_actions<E, B, H: SfcHost<E, B>>(
+    ctx: &mut EvalContext<'_>,
+    instance: InstanceId,
+    chart: &SfcChart<E, B>,
+    active: &[bool],
+    host: &mut H,
+) -> Result<(), RuntimeError> {
+    let mut flags = vec![false; chart.actions.len()];
+    let mut pulses = vec![false; chart.actions.len()];
+    for (step, _) in chart
+        .steps
+        .iter()
+        .zip(active)
+        .filter(|(_, active)| **active)
+    {
+        let is_new = matches!(
+            state_value(ctx, instance, STATE_NEW_PREFIX, &step.name),
+            Some(Value::Bool(true))
+        );
+        let elapsed = step_elapsed(ctx, instance, step);
+        for association in &step.associations {
+            let Some(action) = chart.actions.get(association.action) else {
+                continue;
+            };
+            let on = match association.qualifier {
+                SfcQualifier::N => true,
+                SfcQualifier::P => is_new,
+                SfcQualifier::S => {
+                    set_state(
+                        ctx,
+                        instance,
+                        STATE_STORED_PREFIX,
+                        &action.name,
+                        Value::Bool(true),
+                    );
+                    false
+                }
+                SfcQualifier::R => {
+                    set_state(
+                        ctx,
+                        instance,
+                        STATE_STORED_PREFIX,
+                        &action.name,
+                        Value::Bool(false),
+                    );
+                    false
+                }
+                SfcQualifier::L => elapsed < association_duration(ctx, association, host)?,
+                SfcQualifier::D => elapsed >= association_duration(ctx, association, host)?,
+            };
+            if association.qualifier == SfcQualifier::P {
+                pulses[association.action] |= on;
+            } else {
+                flags[association.action] |= on;
+            }
+        }
+    }
+
+    for ((action, flag), pulse) in chart.actions.iter().zip(flags).zip(pulses) {
+        let stored = matches!(
+            state_value(ctx, instance, STATE_STORED_PREFIX, &action.name),
+            Some(Value::Bool(true))
+        );
+        let sustained = flag || stored;
+        let was_active = matches!(
+            state_value(ctx, instance, STATE_ACTIVE_PREFIX, &action.name),
+            Some(Value::Bool(true))
+        );
+        set_state(
+            ctx,
+            instance,
+            STATE_ACTIVE_PREFIX,
+            &action.name,
+            Value::Bool(sustained),
+        );
+        let q = sustained || pulse;
+        // Pulse actions already run exactly once; every other action gets a
+        // final scan on the falling edge of Q.
+        host.run_action(ctx, &action.body, q, was_active && !q)?;
+    }
+    Ok(())
+}
+
+fn fire_transitions<E, B, H: SfcHost<E, B>>(
+    ctx: &mut EvalContext<'_>,
+    instance: InstanceId,
+    chart: &SfcChart<E, B>,
+    active: &[bool],
+    now: Duration,
+    host: &mut H,
+) -> Result<(), RuntimeError> {
+    let mut order = Vec::with_capacity(chart.transitions.len());
+    for (idx, transition) in chart.transitions.iter().enumerate() {
+        let priority = match &transition.priority {
+            Some(expr) => priority_value(host.eval(ctx, expr)?)?,
+            None => i64::MAX,
+        };
+        order.push((priority, idx));
+    }
+    order.sort();
+
+    let mut consumed = vec![false; chart.steps.len()];
+    let mut targets = Vec::new();
+    for (_, idx) in order {
+        let transition = &chart.transitions[idx];
+        let enabled = transition
+            .from
+            .iter()
+            .all(|step| active.get(*step).copied().unwrap_or(false) && !consumed[*step]);
+        if !enabled {
+            continue;
+        }
+        match host.eval_condition(ctx, transition)? {
+            Value::Bool(true) => {}
+            Value::Bool(false) => continue,
+            _ => return Err(RuntimeError::ConditionNotBool),
+        }
+        for step in &transition.from {
+            consumed[*step] = true;
+        }
+        targets.extend(transition.to.iter().copied());
+    }
+
+    for (step, _) in chart
+        .steps
+        .iter()
+        .zip(&consumed)
+        .filter(|(_, consumed)| **consumed)
+    {
+        let elapsed = step_elapsed(ctx, instance, step);
+        write_step(ctx, instance, step, false, elapsed);
+    }
+    for idx in targets {
+        if let Some(step) = chart.steps.get(idx) {
+            activate_step(ctx, instance, step, now);
+        }
+    }
+    Ok(())
+}
+
+fn activate_step<E>(
+    ctx: &mut EvalContext<'_>,
+    instance: InstanceId,
+    step: &SfcStep<E>,
+    now: Duration,
+) {
+    write_step(ctx, instance, step, true, Duration::ZERO);
+    set_state(
+        ctx,
+        instance,
+        STATE_START_PREFIX,
+        &step.name,
+        Value::LTime(now),
+    );
+    set_state(
+        ctx,
+        instance,
+        STATE_NEW_PREFIX,
+        &step.name,
+        Value::Bool(true),
+    );
+}
+
+fn association_duration<E, B, H: SfcHost<E, B>>(
+    ctx: &mut EvalContext<'_>,
+    association: &SfcAssociation<E>,
+    host: &mut H,
+) -> Result<Duration, RuntimeError> {
+    let Some(expr) = &association.duration else {
+        return Ok(Duration::ZERO);
+    };
+    match host.eval(ctx, expr)? {
+        Value::Time(value) | Value::LTime(value) => Ok(value),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+fn priority_value(value: Value) -> Result<i64, RuntimeError> {
+    match value {
+        Value::SInt(v) => Ok(v as i64),
+        Value::Int(v) => Ok(v as i64),
+        Value::DInt(v) => Ok(v as i64),
+        Value::LInt(v) => Ok(v),
+        Value::USInt(v) => Ok(v as i64),
+        Value::UInt(v) => Ok(v as i64),
+        Value::UDInt(v) => Ok(v as i64),
+        Value::ULInt(v) => i64::try_from(v).map_err(|_| RuntimeError::Overflow),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+fn step_flag<E>(ctx: &EvalContext<'_>, instance: InstanceId, step: &SfcStep<E>) -> bool {
+    match ctx.storage.get_instance_var(instance, step.name.as_str()) {
+        Some(Value::Struct(value)) => matches!(value.fields.get("X"), Some(Value::Bool(true))),
+        _ => false,
+    }
+}
+
+fn step_elapsed<E>(ctx: &EvalContext<'_>, instance: InstanceId, step: &SfcStep<E>) -> Duration {
+    match ctx.storage.get_instance_var(instance, step.name.as_str()) {
+        Some(Value::Struct(value)) => match value.fields.get("T") {
+            Some(Value::Time(value)) | Some(Value::LTime(value)) => *value,
+            _ => Duration::ZERO,
+        },
+        _ => Duration::ZERO,
+    }
+}
+
+fn write_step<E>(
+    ctx: &mut EvalContext<'_>,
+    instance: InstanceId,
+    step: &SfcStep<E>,
+    active: bool,
+    elapsed: Duration,
+) {
+    let mut fields = IndexMap::new();
+    fields.insert(SmolStr::new("X"), Value::Bool(active));
+    fields.insert(SmolStr::new("T"), Value::Time(elapsed));
+    let value = Value::Struct(StructValue {
+        type_name: SmolStr::new(SFC_STEP_TYPE_NAME),
+        fields,
+    });
+    ctx.storage
+        .set_instance_var(instance, step.name.clone(), value);
+}
+
+fn state_key(prefix: &str, name: &str) -> SmolStr {
+    SmolStr::new(format!("{prefix}{}", name.to_ascii_uppercase()))
+}
+
+fn state_value<'a>(
+    ctx: &'a EvalContext<'_>,
+    instance: InstanceId,
+    prefix: &str,
+    name: &str,
+) -> Option<&'a Value> {
+    ctx.storage
+        .get_instance_var(instance, state_key(prefix, name).as_str())
+}
+
+fn state_duration(
+    ctx: &EvalContext<'_>,
+    instance: InstanceId,
+    prefix: &str,
+    name: &str,
+    default: Duration,
+) -> Duration {
+    match state_value(ctx, instance, prefix, name) {
+        Some(Value::Time(value)) | Some(Value::LTime(value)) => *value,
+        _ => default,
+    }
+}
+
+fn set_state(
+    ctx: &mut EvalContext<'_>,
+    instance: InstanceId,
+    prefix: &str,
+    name: &str,
+    value: Value,
+) {
+    ctx.storage
+        .set_instance_var(instance, state_key(prefix, name), value);
+}
@@ -7,6 +7,7 @@ use smol_str::SmolStr;
 use crate::debug::SourceLocation;
 use crate::error::RuntimeError;
 use crate::eval::expr::{eval_expr, read_lvalue, write_lvalue, Expr, LValue};
+use crate::eval::sfc::{exec_chart, SfcChart};
 use crate::eval::EvalContext;
 use crate::value::Value;
 
@@ -94,6 +95,10 @@ pub enum Stmt {
     Continue {
         location: Option<SourceLocation>,
     },
+    Sfc {
+        chart: Box<SfcChart>,
+        location: Option<SourceLocation>,
+    },
 }
 
 impl Stmt {
@@ -112,7 +117,8 @@ impl Stmt {
             | Stmt::Jmp { location, .. }
             | Stmt::Return { location, .. }
             | Stmt::Exit { location, .. }
-            | Stmt::Continue { location, .. } => location.as_ref(),
+            | Stmt::Continue { location, .. }
+            | Stmt::Sfc { location, .. } => location.as_ref(),
         }
     }
 }
@@ -125,6 +131,18 @@ pub fn exec_stmt(ctx: &mut EvalContext<'_>, stmt: &Stmt) -> Result<StmtResult, R
         hook.on_statement_with_context(ctx, stmt.location(), ctx.call_depth);
         ctx.debug = Some(hook);
     }
+    let result = exec_stmt_kind(ctx, stmt);
+    #[cfg(feature = "debug")]
+    if let Err(err) = &result {
+        if let Some(hook) = ctx.debug.take() {
+            hook.on_fault(ctx, stmt.location(), err);
+            ctx.debug = Some(hook);
+        }
+    }
+    result
+}
+
+fn exec_stmt_kind(ctx: &mut EvalContext<'_>, stmt: &Stmt) -> Result<StmtResult, RuntimeError> {
     match stmt {
         Stmt::Assign { target, value, .. } => {
             let value = eval_expr(ctx, value)?;
@@ -317,10 +335,11 @@ pub fn exec_stmt(ctx: &mut EvalContext<'_>, stmt: &Stmt) -> Result<StmtResult, R
                 Ok(StmtResult::LoopContinue)
             }
         }
+        Stmt::Sfc { chart, .. } => exec_chart(ctx, chart),
     }
 }
 
-fn check_execution_budget(ctx: &EvalContext<'_>) -> Result<(), RuntimeError> {
+pub(crate) fn check_execution_budget(ctx: &EvalContext<'_>) -> Result<(), RuntimeError> {
     if let Some(deadline) = ctx.execution_deadline {
         if std::time::Instant::now() >= deadline {
             return Err(RuntimeError::ExecutionTimeout);
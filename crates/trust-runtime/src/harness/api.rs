@@ -5,12 +5,16 @@
 use super::build;
 use super::types::{CompileError, SourceFile};
 use crate::Runtime;
+use trust_syntax::parser::Dialect;
+use trust_syntax::preprocess::Defines;
 
 /// Compile helper for runtime + bytecode builds.
 #[derive(Debug, Clone)]
 pub struct CompileSession {
     sources: Vec<SourceFile>,
     label_errors: bool,
+    defines: Defines,
+    dialect: Dialect,
 }
 
 impl CompileSession {
@@ -19,6 +23,8 @@ impl CompileSession {
         Self {
             sources: vec![SourceFile::new(source)],
             label_errors: false,
+            defines: Defines::default(),
+            dialect: Dialect::default(),
         }
     }
 
@@ -28,6 +34,8 @@ impl CompileSession {
         Self {
             sources,
             label_errors,
+            defines: Defines::default(),
+            dialect: Dialect::default(),
         }
     }
 
@@ -37,6 +45,22 @@ impl CompileSession {
         self
     }
 
+    /// Set the defines used to evaluate `{IF defined(...)}` conditional pragmas.
+    pub fn defines<I, S>(mut self, defines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.defines = Defines::new(defines);
+        self
+    }
+
+    /// Set the vendor dialect sources are parsed with.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
     /// Access the registered sources.
     pub fn sources(&self) -> &[SourceFile] {
         &self.sources
@@ -44,12 +68,22 @@ impl CompileSession {
 
     /// Compile sources into a runtime.
     pub fn build_runtime(&self) -> Result<Runtime, CompileError> {
-        build::build_runtime_from_source_files(&self.sources, self.label_errors)
+        build::build_runtime_from_source_files(
+            &self.sources,
+            self.label_errors,
+            &self.defines,
+            self.dialect,
+        )
     }
 
     /// Compile sources into a bytecode module.
     pub fn build_bytecode_module(&self) -> Result<crate::bytecode::BytecodeModule, CompileError> {
-        build::build_bytecode_module_from_source_files(&self.sources, self.label_errors)
+        build::build_bytecode_module_from_source_files(
+            &self.sources,
+            self.label_errors,
+            &self.defines,
+            self.dialect,
+        )
     }
 
     /// Compile sources into bytecode bytes.
@@ -7,7 +7,8 @@ use crate::Runtime;
 use std::path::Path;
 use trust_hir::db::SemanticDatabase;
 use trust_hir::{Project, SourceKey};
-use trust_syntax::parser;
+use trust_syntax::parser::{self, Dialect};
+use trust_syntax::preprocess::{conditional_regions, mask_inactive, Defines};
 
 use super::config::{
     apply_config_inits, apply_globals, apply_program_retain_overrides,
@@ -19,13 +20,22 @@ use super::types::{CompileError, SourceFile};
 pub(super) fn build_runtime_from_source_files(
     sources: &[SourceFile],
     label_errors: bool,
+    defines: &Defines,
+    dialect: Dialect,
 ) -> Result<Runtime, CompileError> {
+    let mut texts = Vec::with_capacity(sources.len());
     let mut parses = Vec::with_capacity(sources.len());
     let mut parse_errors = Vec::new();
     for (idx, source) in sources.iter().enumerate() {
-        let parse = parser::parse(&source.text);
-        if !parse.ok() {
-            for err in parse.errors() {
+        let regions = conditional_regions(&source.text, defines);
+        let text = if regions.inactive.is_empty() {
+            source.text.clone()
+        } else {
+            mask_inactive(&source.text, &regions.inactive)
+        };
+        let parse = parser::parse_with_dialect(&text, dialect);
+        if !parse.ok() || !regions.errors.is_empty() {
+            for err in regions.errors.iter().chain(parse.errors()) {
                 if label_errors {
                     parse_errors.push(format!("{}: {err}", source_label(source, idx)));
                 } else {
@@ -34,19 +44,21 @@ pub(super) fn build_runtime_from_source_files(
             }
         }
         parses.push(parse);
+        texts.push(text);
     }
     if !parse_errors.is_empty() {
         return Err(CompileError::new(parse_errors.join("\n")));
     }
 
     let mut project = Project::new();
+    project.database_mut().set_dialect(dialect);
     let mut file_ids = Vec::with_capacity(sources.len());
-    for (idx, source) in sources.iter().enumerate() {
+    for (idx, (source, text)) in sources.iter().zip(texts).enumerate() {
         let key = match source.path.as_deref() {
             Some(path) => SourceKey::from_path(Path::new(path)),
             None => SourceKey::from_virtual(format!("file_{idx}")),
         };
-        let file_id = project.set_source_text(key, source.text.clone());
+        let file_id = project.set_source_text(key, text);
         file_ids.push(file_id);
     }
 
@@ -175,8 +187,25 @@ pub(super) fn build_runtime_from_source_files(
         }
     }
 
-    let mut program_defs = IndexMap::<SmolStr, ProgramDef>::new();
     let mut globals = Vec::new();
+    let mut data_block_inits = Vec::new();
+    for (idx, parse) in parses.iter().enumerate() {
+        let syntax = parse.syntax();
+        let data_blocks = super::lower_data_blocks(
+            &syntax,
+            runtime.registry_mut(),
+            profile,
+            file_ids[idx].0,
+            &mut statement_locations[idx],
+        )?;
+        for block in data_blocks {
+            globals.push(block.global);
+            data_block_inits.extend(block.inits);
+        }
+    }
+
+    let mut program_defs = IndexMap::<SmolStr, ProgramDef>::new();
+    let mut organization_blocks = IndexMap::<SmolStr, u32>::new();
     for (idx, parse) in parses.iter().enumerate() {
         let syntax = parse.syntax();
         let lowered = super::lower_programs(
@@ -194,6 +223,9 @@ pub(super) fn build_runtime_from_source_files(
                     program.program.name
                 )));
             }
+            if let Some(number) = program.organization_block {
+                organization_blocks.insert(key.as_str().into(), number);
+            }
             program_defs.insert(key.into(), program.program);
             globals.extend(program.globals);
         }
@@ -229,6 +261,7 @@ pub(super) fn build_runtime_from_source_files(
             &config.using,
             &mut wildcards,
         )?;
+        apply_config_inits(&mut runtime, &data_block_inits, &[], &mut wildcards)?;
         apply_config_inits(
             &mut runtime,
             &config.config_inits,
@@ -248,16 +281,22 @@ pub(super) fn build_runtime_from_source_files(
             return Err(CompileError::new("missing PROGRAM declaration"));
         }
         let mut wildcards = apply_globals(&mut runtime, &globals)?;
-        let default_programs = program_defs
-            .values()
-            .map(|program| super::ProgramInstanceConfig {
+        let mut tasks = Vec::new();
+        let mut default_programs = Vec::new();
+        for (key, program) in &program_defs {
+            let task = match organization_blocks.get(key) {
+                Some(&number) => organization_block_task(&program.name, number)?,
+                None => None,
+            };
+            default_programs.push(super::ProgramInstanceConfig {
                 name: program.name.clone(),
                 type_name: program.name.clone(),
-                task: None,
+                task: task.as_ref().map(|task| task.name.clone()),
                 retain: None,
                 fb_tasks: Vec::new(),
-            })
-            .collect::<Vec<_>>();
+            });
+            tasks.extend(task);
+        }
         register_program_instances(
             &mut runtime,
             &program_defs,
@@ -265,10 +304,16 @@ pub(super) fn build_runtime_from_source_files(
             &[],
             &mut wildcards,
         )?;
+        apply_config_inits(&mut runtime, &data_block_inits, &[], &mut wildcards)?;
         ensure_wildcards_resolved(&wildcards)?;
+        attach_programs_to_tasks(&mut tasks, &default_programs)?;
+        for task in tasks {
+            runtime.register_task(task);
+        }
     }
 
     let _ = runtime.ensure_background_thread_id();
+    runtime.capture_initial_state();
 
     for (idx, locations) in statement_locations.into_iter().enumerate() {
         runtime.register_statement_locations(file_ids[idx].0, locations);
@@ -277,11 +322,49 @@ pub(super) fn build_runtime_from_source_files(
     Ok(runtime)
 }
 
+/// Task for a Siemens `OB<n>` that runs without a `CONFIGURATION`.
+///
+/// OB1 runs in the default cycle. The cyclic interrupt OBs 30-38 get an
+/// interval task with the S7 default period; their priority follows the S7
+/// order (OB38 highest). Other OB event classes have no runtime equivalent.
+fn organization_block_task(
+    name: &SmolStr,
+    number: u32,
+) -> Result<Option<crate::task::TaskConfig>, CompileError> {
+    let interval_ms = match number {
+        1 => return Ok(None),
+        30 => 5000,
+        31 => 2000,
+        32 => 1000,
+        33 => 500,
+        34 => 200,
+        35 => 100,
+        36 => 50,
+        37 => 20,
+        38 => 10,
+        _ => {
+            return Err(CompileError::new(format!(
+                "ORGANIZATION_BLOCK '{name}': OB{number} is not a cyclic OB; bind it to a TASK in a CONFIGURATION"
+            )))
+        }
+    };
+    Ok(Some(crate::task::TaskConfig {
+        name: format!("{name}_TASK").into(),
+        interval: crate::value::Duration::from_millis(interval_ms),
+        single: None,
+        priority: 39 - number,
+        programs: Vec::new(),
+        fb_instances: Vec::new(),
+    }))
+}
+
 pub(super) fn build_bytecode_module_from_source_files(
     sources: &[SourceFile],
     label_errors: bool,
+    defines: &Defines,
+    dialect: Dialect,
 ) -> Result<crate::bytecode::BytecodeModule, CompileError> {
-    let runtime = build_runtime_from_source_files(sources, label_errors)?;
+    let runtime = build_runtime_from_source_files(sources, label_errors, defines, dialect)?;
     let source_refs = sources
         .iter()
         .map(|source| source.text.as_str())
@@ -16,7 +16,9 @@ use super::model::{
     AccessDecl, AccessPart, AccessPath, ConfigInit, ConfigModel, FbTaskBinding, GlobalInit,
     LoweringContext, ProgramInstanceConfig,
 };
-use super::vars::{parse_var_decl, var_block_kind, var_block_qualifiers, VarBlockKind};
+use super::vars::{
+    parse_retain_aliases, parse_var_decl, var_block_kind, var_block_qualifiers, VarBlockKind,
+};
 
 pub(crate) fn lower_configuration(
     syntax: &SyntaxNode,
@@ -116,6 +118,7 @@ fn lower_global_var_block(
         .filter(|child| child.kind() == SyntaxKind::VarDecl)
     {
         let (names, type_ref, initializer, address) = parse_var_decl(&var_decl)?;
+        let retain_aliases = parse_retain_aliases(&var_decl)?;
         let type_id = lower_type_ref(&type_ref, ctx)?;
         let init_expr = initializer.map(|expr| lower_expr(&expr, ctx)).transpose()?;
         match kind {
@@ -132,6 +135,7 @@ fn lower_global_var_block(
                         retain: qualifiers.retain,
                         address: address.clone(),
                         using: ctx.using.clone(),
+                        retain_aliases: retain_aliases.clone(),
                     });
                 }
             }
@@ -188,6 +192,7 @@ fn lower_var_access_block(
                     retain: crate::RetainPolicy::Unspecified,
                     address: Some(text.clone()),
                     using: ctx.using.clone(),
+                    retain_aliases: Vec::new(),
                 });
             }
             AccessPath::Parts(_) => {
@@ -0,0 +1,212 @@
+use smol_str::SmolStr;
+use trust_hir::TypeId;
+use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
+
+use crate::value::DateTimeProfile;
+
+use super::super::lower::{const_int_from_node, lower_expr};
+use super::super::types::CompileError;
+use super::super::util::{collect_using_directives, direct_expr_children, node_text};
+use super::lower_type_ref;
+use super::model::{
+    AccessPart, AccessPath, ConfigInit, GlobalInit, LoweredDataBlock, LoweringContext,
+};
+use super::types::lower_struct_def;
+use super::vars::parse_var_decl;
+
+/// Lower Siemens DATA_BLOCK declarations into global variables.
+///
+/// Field initializers and the assignments after `BEGIN` become initial-value
+/// writes applied once the globals exist.
+pub(crate) fn lower_data_blocks(
+    syntax: &SyntaxNode,
+    registry: &mut trust_hir::types::TypeRegistry,
+    profile: DateTimeProfile,
+    file_id: u32,
+    statement_locations: &mut Vec<crate::debug::SourceLocation>,
+) -> Result<Vec<LoweredDataBlock>, CompileError> {
+    let mut blocks = Vec::new();
+    for block_node in syntax
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::DataBlock)
+    {
+        let using = collect_using_directives(&block_node);
+        let mut ctx = LoweringContext {
+            registry,
+            profile,
+            using,
+            file_id,
+            statement_locations,
+        };
+        blocks.push(lower_data_block_node(&block_node, &mut ctx)?);
+    }
+    Ok(blocks)
+}
+
+fn lower_data_block_node(
+    node: &SyntaxNode,
+    ctx: &mut LoweringContext<'_>,
+) -> Result<LoweredDataBlock, CompileError> {
+    let name_node = node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::Name)
+        .ok_or_else(|| CompileError::new("missing DATA_BLOCK name"))?;
+    let name = SmolStr::new(node_text(&name_node));
+
+    let mut inits = Vec::new();
+    let mut fields: Vec<trust_hir::types::StructField> = Vec::new();
+    let type_id = if let Some(type_ref) = node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::TypeRef)
+    {
+        lower_type_ref(&type_ref, ctx)?
+    } else {
+        if let Some(struct_def) = node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::StructDef)
+        {
+            fields = lower_struct_def(&struct_def, ctx)?;
+        }
+        for var_decl in node
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::VarBlock)
+            .flat_map(|block| block.children())
+            .filter(|child| child.kind() == SyntaxKind::VarDecl)
+        {
+            let (names, type_ref, initializer, address) = parse_var_decl(&var_decl)?;
+            let field_type = lower_type_ref(&type_ref, ctx)?;
+            let initializer = initializer.map(|expr| lower_expr(&expr, ctx)).transpose()?;
+            for field in names {
+                if let Some(initializer) = &initializer {
+                    inits.push(ConfigInit {
+                        path: AccessPath::Parts(vec![
+                            AccessPart::Name(name.clone()),
+                            AccessPart::Name(field.clone()),
+                        ]),
+                        address: None,
+                        type_id: field_type,
+                        initializer: Some(initializer.clone()),
+                    });
+                }
+                fields.push(trust_hir::types::StructField {
+                    name: field,
+                    type_id: field_type,
+                    address: address.clone(),
+                });
+            }
+        }
+        if ctx.registry.lookup(name.as_ref()).is_some() {
+            return Err(CompileError::new(format!("duplicate type name '{name}'")));
+        }
+        ctx.registry.register_struct(name.clone(), fields.clone())
+    };
+
+    if let Some(body) = node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::StmtList)
+    {
+        for stmt in body.children() {
+            match stmt.kind() {
+                SyntaxKind::EmptyStmt => {}
+                SyntaxKind::AssignStmt => {
+                    let exprs = direct_expr_children(&stmt);
+                    if exprs.len() != 2 {
+                        return Err(CompileError::new("invalid assignment"));
+                    }
+                    let mut parts = vec![AccessPart::Name(name.clone())];
+                    lower_start_value_target(&exprs[0], &mut parts, ctx)?;
+                    // Fields of instance data blocks are typed by the runtime value.
+                    let field_type = match parts.as_slice() {
+                        [_, AccessPart::Name(field)] => fields
+                            .iter()
+                            .find(|candidate| candidate.name.eq_ignore_ascii_case(field))
+                            .map(|candidate| candidate.type_id)
+                            .unwrap_or(TypeId::UNKNOWN),
+                        _ => TypeId::UNKNOWN,
+                    };
+                    inits.push(ConfigInit {
+                        path: AccessPath::Parts(parts),
+                        address: None,
+                        type_id: field_type,
+                        initializer: Some(lower_expr(&exprs[1], ctx)?),
+                    });
+                }
+                _ => {
+                    return Err(CompileError::new(format!(
+                        "DATA_BLOCK '{name}' only supports assignments after BEGIN"
+                    )))
+                }
+            }
+        }
+    }
+
+    let retain = node
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::BlockProperty)
+        .find_map(|property| {
+            property
+                .children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .find_map(|token| match token.kind() {
+                    SyntaxKind::KwRetain => Some(crate::RetainPolicy::Retain),
+                    SyntaxKind::KwNonRetain => Some(crate::RetainPolicy::NonRetain),
+                    _ => None,
+                })
+        })
+        .unwrap_or_default();
+
+    Ok(LoweredDataBlock {
+        global: GlobalInit {
+            name,
+            type_id,
+            initializer: None,
+            retain,
+            address: None,
+            using: ctx.using.clone(),
+            retain_aliases: Vec::new(),
+        },
+        inits,
+    })
+}
+
+fn lower_start_value_target(
+    node: &SyntaxNode,
+    parts: &mut Vec<AccessPart>,
+    ctx: &mut LoweringContext<'_>,
+) -> Result<(), CompileError> {
+    match node.kind() {
+        SyntaxKind::NameRef => {
+            parts.push(AccessPart::Name(node_text(node).into()));
+            Ok(())
+        }
+        SyntaxKind::FieldExpr => {
+            let target = direct_expr_children(node)
+                .into_iter()
+                .next()
+                .ok_or_else(|| CompileError::new("invalid field expression"))?;
+            lower_start_value_target(&target, parts, ctx)?;
+            let field = node
+                .children()
+                .find(|child| child.kind() == SyntaxKind::Name)
+                .ok_or_else(|| CompileError::new("missing field name"))?;
+            parts.push(AccessPart::Name(node_text(&field).into()));
+            Ok(())
+        }
+        SyntaxKind::IndexExpr => {
+            let exprs = direct_expr_children(node);
+            let (target, indices) = exprs
+                .split_first()
+                .ok_or_else(|| CompileError::new("invalid index expression"))?;
+            lower_start_value_target(target, parts, ctx)?;
+            let indices = indices
+                .iter()
+                .map(|expr| const_int_from_node(expr, ctx))
+                .collect::<Result<Vec<_>, _>>()?;
+            parts.push(AccessPart::Index(indices));
+            Ok(())
+        }
+        _ => Err(CompileError::new(
+            "unsupported DATA_BLOCK start value target",
+        )),
+    }
+}
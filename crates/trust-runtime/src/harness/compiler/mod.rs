@@ -3,12 +3,14 @@
 #![allow(missing_docs)]
 
 mod config;
+mod data_blocks;
 mod model;
 mod pou;
 mod types;
 mod vars;
 
 pub(super) use config::{lower_configuration, resolve_program_type_name};
+pub(super) use data_blocks::lower_data_blocks;
 pub(super) use model::{
     AccessDecl, AccessPart, AccessPath, ConfigInit, GlobalInit, LoweringContext,
     ProgramInstanceConfig, ResolvedAccess, WildcardRequirement,
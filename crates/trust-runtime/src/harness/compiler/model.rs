@@ -12,6 +12,13 @@ use trust_hir::TypeId;
 pub(crate) struct LoweredProgram {
     pub(crate) program: ProgramDef,
     pub(crate) globals: Vec<GlobalInit>,
+    /// `<n>` of an `ORGANIZATION_BLOCK` named `OB<n>`.
+    pub(crate) organization_block: Option<u32>,
+}
+
+pub(crate) struct LoweredDataBlock {
+    pub(crate) global: GlobalInit,
+    pub(crate) inits: Vec<ConfigInit>,
 }
 
 pub(crate) struct ProgramVars {
@@ -87,6 +94,7 @@ pub(crate) struct GlobalInit {
     pub(crate) retain: crate::RetainPolicy,
     pub(crate) address: Option<SmolStr>,
     pub(crate) using: Vec<SmolStr>,
+    pub(crate) retain_aliases: Vec<crate::RetainAlias>,
 }
 
 #[derive(Clone)]
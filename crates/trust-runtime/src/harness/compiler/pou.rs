@@ -10,12 +10,14 @@ use crate::io::IoAddress;
 use crate::task::ProgramDef;
 use crate::value::DateTimeProfile;
 
-use super::super::lower::{lower_expr, lower_stmt_list};
+use super::super::lower::{lower_expr, lower_sfc_chart, lower_stmt_list};
 use super::super::types::CompileError;
 use super::super::util::{collect_using_directives, node_text};
 use super::model::{GlobalInit, LoweredProgram, LoweringContext, ProgramVars};
 use super::types::qualify_with_namespaces;
-use super::vars::{parse_var_decl, var_block_kind, var_block_qualifiers, VarBlockKind};
+use super::vars::{
+    parse_retain_aliases, parse_var_decl, var_block_kind, var_block_qualifiers, VarBlockKind,
+};
 use super::{lower_type_ref, resolve_named_type};
 
 pub(crate) fn lower_programs(
@@ -149,6 +151,7 @@ fn lower_program_node(
     statement_locations: &mut Vec<crate::debug::SourceLocation>,
 ) -> Result<LoweredProgram, CompileError> {
     let name = qualified_pou_name(program_node)?;
+    let organization_block = organization_block_number(program_node, &name);
     let using = collect_using_directives(program_node);
     let mut ctx = LoweringContext {
         registry,
@@ -157,8 +160,12 @@ fn lower_program_node(
         file_id,
         statement_locations,
     };
-    let vars = lower_program_var_blocks(program_node, &mut ctx)?;
-    let body = lower_stmt_list(program_node, &mut ctx)?;
+    let mut vars = lower_program_var_blocks(program_node, &mut ctx)?;
+    let mut body = lower_stmt_list(program_node, &mut ctx)?;
+    if let Some((chart, steps)) = lower_sfc_chart(program_node, &mut ctx)? {
+        vars.vars.extend(steps);
+        body.push(chart);
+    }
     Ok(LoweredProgram {
         program: ProgramDef {
             name,
@@ -168,9 +175,30 @@ fn lower_program_node(
             body,
         },
         globals: vars.globals,
+        organization_block,
     })
 }
 
+/// OB number of an `ORGANIZATION_BLOCK` named `OB<n>` (quoted or not).
+fn organization_block_number(program_node: &SyntaxNode, name: &str) -> Option<u32> {
+    let is_organization_block = program_node
+        .children_with_tokens()
+        .filter_map(|element| element.into_token())
+        .any(|token| token.kind() == SyntaxKind::KwOrganizationBlock);
+    if !is_organization_block {
+        return None;
+    }
+    let name = name.rsplit('.').next()?;
+    let digits = name
+        .get(..2)?
+        .eq_ignore_ascii_case("OB")
+        .then(|| &name[2..])?;
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
 fn lower_function_block_node(
     node: &SyntaxNode,
     ctx: &mut LoweringContext<'_>,
@@ -206,7 +234,7 @@ fn lower_function_block_node(
             });
         }
     }
-    let (params, vars, temps) = lower_function_block_var_blocks(node, ctx)?;
+    let (params, mut vars, temps) = lower_function_block_var_blocks(node, ctx)?;
     let mut methods = Vec::new();
     for method_node in node
         .children()
@@ -214,7 +242,11 @@ fn lower_function_block_node(
     {
         methods.push(lower_method_node(&method_node, ctx)?);
     }
-    let body = lower_stmt_list(node, ctx)?;
+    let mut body = lower_stmt_list(node, ctx)?;
+    if let Some((chart, steps)) = lower_sfc_chart(node, ctx)? {
+        vars.extend(steps);
+        body.push(chart);
+    }
     Ok(FunctionBlockDef {
         name,
         base,
@@ -437,6 +469,7 @@ fn lower_program_var_blocks(
                     continue;
                 }
                 VarBlockKind::Global => {
+                    let retain_aliases = parse_retain_aliases(&var_decl)?;
                     for name in names {
                         globals.push(GlobalInit {
                             name,
@@ -445,6 +478,7 @@ fn lower_program_var_blocks(
                             retain: qualifiers.retain,
                             address: address.clone(),
                             using: ctx.using.clone(),
+                            retain_aliases: retain_aliases.clone(),
                         });
                     }
                 }
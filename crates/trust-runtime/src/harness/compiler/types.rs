@@ -183,7 +183,7 @@ fn lower_type_decl_node(
     Ok(())
 }
 
-fn lower_struct_def(
+pub(super) fn lower_struct_def(
     node: &SyntaxNode,
     ctx: &mut LoweringContext<'_>,
 ) -> Result<Vec<trust_hir::types::StructField>, CompileError> {
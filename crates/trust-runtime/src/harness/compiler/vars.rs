@@ -121,3 +121,80 @@ pub(super) fn parse_var_decl(
 
     Ok((names, type_ref, initializer, address))
 }
+
+/// Collect `{attribute 'retain_alias' := '...'}` pragmas of a declaration.
+///
+/// The value is a comma-separated list of `OldName` (the whole variable) or
+/// `field := OldName` (a struct field) entries; `OldName` may be a dotted path.
+pub(super) fn parse_retain_aliases(
+    var_decl: &SyntaxNode,
+) -> Result<Vec<crate::RetainAlias>, CompileError> {
+    // Pragmas between two declarations end up as trailing trivia of the
+    // previous one.
+    let trailing = var_decl
+        .prev_sibling()
+        .filter(|prev| prev.kind() == SyntaxKind::VarDecl)
+        .into_iter()
+        .flat_map(|prev| {
+            prev.descendants_with_tokens()
+                .filter_map(|element| element.into_token())
+                .skip_while(|token| token.kind() != SyntaxKind::Semicolon)
+                .collect::<Vec<_>>()
+        });
+    let leading = var_decl
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .take_while(|token| token.kind() != SyntaxKind::Colon);
+    let mut aliases = Vec::new();
+    for token in trailing.chain(leading) {
+        if token.kind() != SyntaxKind::Pragma {
+            continue;
+        }
+        let Some(value) = attribute_value(token.text(), "retain_alias") else {
+            continue;
+        };
+        for entry in value.split(',') {
+            let (field, source) = match entry.split_once(":=") {
+                Some((field, source)) => (Some(field.trim()), source.trim()),
+                None => (None, entry.trim()),
+            };
+            if !is_alias_path(source) || field.is_some_and(|field| !is_alias_path(field)) {
+                return Err(CompileError::new(format!(
+                    "invalid retain_alias entry '{}'",
+                    entry.trim()
+                )));
+            }
+            aliases.push(crate::RetainAlias {
+                field: field.map(SmolStr::new),
+                source: SmolStr::new(source),
+            });
+        }
+    }
+    Ok(aliases)
+}
+
+/// Value of `{attribute 'name' := 'value'}` when the pragma names `name`.
+fn attribute_value<'t>(pragma: &'t str, name: &str) -> Option<&'t str> {
+    let body = pragma.strip_prefix('{')?.strip_suffix('}')?.trim();
+    let (keyword, rest) = body.split_once(char::is_whitespace)?;
+    if !keyword.eq_ignore_ascii_case("attribute") {
+        return None;
+    }
+    let rest = rest.trim().strip_prefix('\'')?;
+    let (attribute, rest) = rest.split_once('\'')?;
+    if !attribute.trim().eq_ignore_ascii_case(name) {
+        return None;
+    }
+    let rest = rest.trim().strip_prefix(":=")?.trim();
+    rest.strip_prefix('\'')?.strip_suffix('\'')
+}
+
+fn is_alias_path(path: &str) -> bool {
+    !path.is_empty()
+        && path.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        })
+}
@@ -3,9 +3,11 @@ use smol_str::SmolStr;
 
 use crate::eval::{eval_expr, EvalContext};
 use crate::instance::{create_class_instance, create_fb_instance};
+use crate::stdlib::conversions::value_type_id;
 use crate::task::ProgramDef;
 use crate::value::{default_value_for_type_id, Value};
 use crate::Runtime;
+use trust_hir::TypeId;
 
 use super::io::{
     bind_value_ref_to_address, collect_direct_field_bindings, collect_instance_bindings,
@@ -521,6 +523,7 @@ pub(super) fn apply_globals(
                 init.type_id,
                 init.retain,
                 crate::GlobalInitValue::FunctionBlock { type_name: fb_name },
+                init.retain_aliases.clone(),
             );
             continue;
         }
@@ -532,6 +535,7 @@ pub(super) fn apply_globals(
                 crate::GlobalInitValue::Class {
                     type_name: class_name,
                 },
+                init.retain_aliases.clone(),
             );
             continue;
         }
@@ -545,6 +549,7 @@ pub(super) fn apply_globals(
             init.type_id,
             init.retain,
             crate::GlobalInitValue::Value(value),
+            init.retain_aliases.clone(),
         );
     }
 
@@ -656,6 +661,20 @@ pub(super) fn apply_config_inits(
             continue;
         };
 
+        // Untyped targets (instance data block start values) take the type of
+        // the value they overwrite.
+        let type_id = match &resolved {
+            ResolvedAccess::Variable {
+                reference,
+                partial: None,
+            } if init.type_id == TypeId::UNKNOWN => runtime
+                .storage()
+                .read_by_ref(reference.clone())
+                .and_then(value_type_id)
+                .unwrap_or(TypeId::UNKNOWN),
+            _ => init.type_id,
+        };
+
         let value = {
             let now = runtime.current_time();
             let mut ctx = EvalContext {
@@ -679,7 +698,7 @@ pub(super) fn apply_config_inits(
             };
             let value = eval_expr(&mut ctx, expr)
                 .map_err(|err| CompileError::new(format!("VAR_CONFIG initializer error: {err}")))?;
-            super::coerce_value_to_type(value, init.type_id)?
+            super::coerce_value_to_type(value, type_id)?
         };
 
         match resolved {
@@ -56,6 +56,55 @@ impl TestHarness {
         })
     }
 
+    /// Creates a new test harness from a configured compile session.
+    pub fn from_session(session: &CompileSession) -> Result<Self, CompileError> {
+        let runtime = session.build_runtime()?;
+        Ok(Self {
+            runtime,
+            cycle_count: 0,
+        })
+    }
+
+    /// Creates a harness whose program bodies execute from encoded bytecode.
+    pub fn from_source_with_bytecode_vm(source: &str) -> Result<Self, CompileError> {
+        Self::from_sources_with_bytecode_vm(&[source])
+    }
+
+    /// Creates a bytecode-executing harness from multiple source files.
+    pub fn from_sources_with_bytecode_vm(sources: &[&str]) -> Result<Self, CompileError> {
+        let mut harness = Self::from_sources(sources)?;
+        let module =
+            crate::bytecode::BytecodeModule::from_runtime_with_sources(&harness.runtime, sources)
+                .map_err(|err| CompileError::new(err.to_string()))?;
+        let vm = crate::bytecode::BytecodeVm::with_sources(&module, sources)
+            .map_err(|err| CompileError::new(err.to_string()))?;
+        harness.runtime.load_bytecode_vm(vm);
+        Ok(harness)
+    }
+
+    /// Creates a harness from the encoded bytecode of the sources alone.
+    ///
+    /// The sources are compiled and encoded, then the runtime is rebuilt from
+    /// the decoded module without them, as for a bundle shipped without
+    /// `sources/`.
+    pub fn from_source_bytecode_only(source: &str) -> Result<Self, CompileError> {
+        Self::from_sources_bytecode_only(&[source])
+    }
+
+    /// Creates a sources-less bytecode harness from multiple source files.
+    pub fn from_sources_bytecode_only(sources: &[&str]) -> Result<Self, CompileError> {
+        let compiled = Self::from_sources(sources)?;
+        let bytes = crate::bytecode::BytecodeModule::from_runtime(&compiled.runtime)
+            .and_then(|module| module.encode())
+            .map_err(|err| CompileError::new(err.to_string()))?;
+        let runtime = Runtime::from_bytecode_bytes(&bytes, None)
+            .map_err(|err| CompileError::new(err.to_string()))?;
+        Ok(Self {
+            runtime,
+            cycle_count: 0,
+        })
+    }
+
     /// Sets an input value.
     pub fn set_input(&mut self, name: &str, value: impl Into<Value>) {
         let value = value.into();
@@ -186,6 +235,9 @@ impl TestHarness {
     }
 
     /// Reload a single source file, preserving retained variables when possible.
+    ///
+    /// This is a download with warm restart: non-retained state starts from
+    /// its initial values. Use [`Self::online_change_source`] to keep it.
     pub fn reload_source(&mut self, source: &str) -> Result<(), CompileError> {
         let retained = self.runtime.retain_snapshot();
         let debug = self.runtime.debug_control();
@@ -205,6 +257,9 @@ impl TestHarness {
     }
 
     /// Reload multiple source files, preserving retained variables when possible.
+    ///
+    /// This is a download with warm restart; see [`Self::online_change_sources`]
+    /// for the state-preserving path.
     pub fn reload_sources(&mut self, sources: &[&str]) -> Result<(), CompileError> {
         let retained = self.runtime.retain_snapshot();
         let debug = self.runtime.debug_control();
@@ -223,6 +278,27 @@ impl TestHarness {
         Ok(())
     }
 
+    /// Apply changed source as an online change, keeping the state of every
+    /// variable and FB instance that still exists.
+    pub fn online_change_source(
+        &mut self,
+        source: &str,
+    ) -> Result<crate::OnlineChangeReport, CompileError> {
+        self.online_change_sources(&[source])
+    }
+
+    /// Apply changed sources as an online change, keeping the state of every
+    /// variable and FB instance that still exists.
+    pub fn online_change_sources(
+        &mut self,
+        sources: &[&str],
+    ) -> Result<crate::OnlineChangeReport, CompileError> {
+        let next = TestHarness::from_sources(sources)?.into_runtime();
+        self.runtime
+            .apply_online_change(next)
+            .map_err(|err| CompileError::new(err.to_string()))
+    }
+
     /// Gets the current simulation time.
     #[must_use]
     pub fn current_time(&self) -> Duration {
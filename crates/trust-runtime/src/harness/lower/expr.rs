@@ -13,7 +13,7 @@ use crate::value::{
 };
 use trust_hir::types::TypeRegistry;
 use trust_hir::TypeId;
-use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
+use trust_syntax::syntax::{identifier_text, SyntaxKind, SyntaxNode};
 
 use super::super::util::{direct_expr_children, first_expr_child, is_expression_kind, node_text};
 use super::super::{
@@ -305,7 +305,7 @@ fn lower_literal(node: &SyntaxNode, ctx: &LoweringContext<'_>) -> Result<Expr, C
                 value_literal = Some(parse_dt_literal(token.text(), ctx.profile)?);
             }
             SyntaxKind::Ident => {
-                ident_literal = Some(token.text().to_string());
+                ident_literal = Some(identifier_text(token.text()).to_string());
             }
             _ => {}
         }
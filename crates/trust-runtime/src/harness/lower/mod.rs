@@ -1,7 +1,9 @@
 mod expr;
+mod sfc;
 mod stmt;
 
 pub(super) use expr::{
     const_duration_from_node, const_int_from_node, lower_expr, lower_lvalue, parse_subrange,
 };
+pub(super) use sfc::lower_sfc_chart;
 pub(super) use stmt::lower_stmt_list;
@@ -0,0 +1,236 @@
+use smol_str::SmolStr;
+use trust_hir::types::{sfc_step_fields, SFC_STEP_TYPE_NAME};
+use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
+
+use crate::eval::sfc::{
+    SfcAction, SfcActionBody, SfcAssociation, SfcChart, SfcQualifier, SfcStep, SfcTransition,
+};
+use crate::eval::stmt::Stmt;
+use crate::eval::VarDef;
+
+use super::super::util::{first_expr_child, node_text};
+use super::super::{CompileError, LoweringContext};
+use super::expr::lower_expr;
+use super::stmt::{lower_stmt_list, stmt_location};
+
+/// Lower the SFC network of a PROGRAM or FUNCTION_BLOCK body.
+///
+/// Returns the chart statement to append to the body together with the
+/// step variables (`<step>.X`, `<step>.T`) to declare on the instance.
+pub(in crate::harness) fn lower_sfc_chart(
+    pou: &SyntaxNode,
+    ctx: &mut LoweringContext<'_>,
+) -> Result<Option<(Stmt, Vec<VarDef>)>, CompileError> {
+    let elements = sfc_elements(pou);
+    let step_nodes: Vec<&SyntaxNode> = elements
+        .iter()
+        .filter(|node| node.kind() == SyntaxKind::Step)
+        .collect();
+    if step_nodes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut chart = SfcChart::default();
+    for action in elements
+        .iter()
+        .filter(|node| node.kind() == SyntaxKind::Action)
+    {
+        let name = element_name(action)?;
+        let body = lower_stmt_list(action, ctx)?;
+        chart.actions.push(SfcAction {
+            name,
+            body: SfcActionBody::Statements(body),
+        });
+    }
+
+    for step in &step_nodes {
+        chart.steps.push(SfcStep {
+            name: element_name(step)?,
+            initial: step
+                .children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .any(|token| token.kind() == SyntaxKind::KwInitialStep),
+            associations: Vec::new(),
+        });
+    }
+
+    for (idx, step) in step_nodes.iter().enumerate() {
+        let mut associations = Vec::new();
+        for association in step
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::ActionAssociation)
+        {
+            associations.push(lower_association(&association, &mut chart, ctx)?);
+        }
+        chart.steps[idx].associations = associations;
+    }
+
+    for transition in elements
+        .iter()
+        .filter(|node| node.kind() == SyntaxKind::Transition)
+    {
+        let transition = lower_transition(transition, &chart, ctx)?;
+        chart.transitions.push(transition);
+    }
+
+    let type_id = ctx.registry.lookup(SFC_STEP_TYPE_NAME).unwrap_or_else(|| {
+        ctx.registry
+            .register_struct(SFC_STEP_TYPE_NAME, sfc_step_fields())
+    });
+    let vars = chart
+        .steps
+        .iter()
+        .map(|step| VarDef {
+            name: step.name.clone(),
+            type_id,
+            initializer: None,
+            retain: crate::RetainPolicy::NonRetain,
+            external: false,
+            constant: true,
+            address: None,
+        })
+        .collect();
+
+    Ok(Some((
+        Stmt::Sfc {
+            chart: Box::new(chart),
+            location: None,
+        },
+        vars,
+    )))
+}
+
+fn lower_association(
+    node: &SyntaxNode,
+    chart: &mut SfcChart,
+    ctx: &mut LoweringContext<'_>,
+) -> Result<SfcAssociation, CompileError> {
+    let target = node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::NameRef)
+        .ok_or_else(|| CompileError::new("missing SFC action name"))?;
+    let name = SmolStr::new(node_text(&target));
+    let action = match chart
+        .actions
+        .iter()
+        .position(|action| action.name.eq_ignore_ascii_case(name.as_str()))
+    {
+        Some(idx) => idx,
+        None => {
+            chart.actions.push(SfcAction {
+                name: name.clone(),
+                body: SfcActionBody::Variable(name),
+            });
+            chart.actions.len() - 1
+        }
+    };
+
+    let qualifier_node = node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::ActionQualifier);
+    let (qualifier, duration) = match qualifier_node {
+        Some(qualifier_node) => {
+            let text = qualifier_node
+                .children_with_tokens()
+                .filter_map(|element| element.into_token())
+                .find(|token| token.kind() == SyntaxKind::Ident)
+                .map(|token| token.text().to_string())
+                .unwrap_or_default();
+            let qualifier = SfcQualifier::parse(&text).ok_or_else(|| {
+                CompileError::new(format!("unsupported SFC action qualifier '{text}'"))
+            })?;
+            let duration = first_expr_child(&qualifier_node)
+                .map(|expr| lower_expr(&expr, ctx))
+                .transpose()?;
+            (qualifier, duration)
+        }
+        None => (SfcQualifier::N, None),
+    };
+    if matches!(qualifier, SfcQualifier::L | SfcQualifier::D) && duration.is_none() {
+        return Err(CompileError::new(
+            "timed SFC action qualifier requires a duration",
+        ));
+    }
+
+    Ok(SfcAssociation {
+        action,
+        qualifier,
+        duration,
+    })
+}
+
+fn lower_transition(
+    node: &SyntaxNode,
+    chart: &SfcChart,
+    ctx: &mut LoweringContext<'_>,
+) -> Result<SfcTransition, CompileError> {
+    let mut step_lists = node
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::StepList);
+    let from = lower_step_list(step_lists.next(), chart)?;
+    let to = lower_step_list(step_lists.next(), chart)?;
+    let condition = node
+        .children()
+        .find(|child| child.kind() == SyntaxKind::Condition)
+        .and_then(|condition| first_expr_child(&condition))
+        .ok_or_else(|| CompileError::new("missing SFC transition condition"))?;
+    let condition = lower_expr(&condition, ctx)?;
+    let priority = first_expr_child(node)
+        .map(|expr| lower_expr(&expr, ctx))
+        .transpose()?;
+    Ok(SfcTransition {
+        from,
+        to,
+        condition,
+        priority,
+        location: stmt_location(node, ctx),
+    })
+}
+
+fn lower_step_list(node: Option<SyntaxNode>, chart: &SfcChart) -> Result<Vec<usize>, CompileError> {
+    let node = node.ok_or_else(|| CompileError::new("missing SFC transition step list"))?;
+    let mut steps = Vec::new();
+    for name_ref in node
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::NameRef)
+    {
+        let name = node_text(&name_ref);
+        let idx = chart
+            .steps
+            .iter()
+            .position(|step| step.name.eq_ignore_ascii_case(&name))
+            .ok_or_else(|| CompileError::new(format!("unknown SFC step '{name}'")))?;
+        steps.push(idx);
+    }
+    if steps.is_empty() {
+        return Err(CompileError::new("empty SFC transition step list"));
+    }
+    Ok(steps)
+}
+
+fn element_name(node: &SyntaxNode) -> Result<SmolStr, CompileError> {
+    node.children()
+        .find(|child| child.kind() == SyntaxKind::Name)
+        .map(|name| SmolStr::new(node_text(&name)))
+        .ok_or_else(|| CompileError::new("missing SFC element name"))
+}
+
+/// PROGRAM bodies keep SFC elements inside the statement list, while
+/// FUNCTION_BLOCK bodies declare them next to methods and actions.
+fn sfc_elements(pou: &SyntaxNode) -> Vec<SyntaxNode> {
+    pou.children()
+        .flat_map(|child| {
+            if child.kind() == SyntaxKind::StmtList {
+                child.children().collect::<Vec<_>>()
+            } else {
+                vec![child]
+            }
+        })
+        .filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::Step | SyntaxKind::Transition | SyntaxKind::Action
+            )
+        })
+        .collect()
+}
@@ -26,6 +26,11 @@ pub(in crate::harness) fn lower_stmt_list(
         if !is_statement_kind(stmt_node.kind()) {
             continue;
         }
+        if stmt_node.kind() == SyntaxKind::RegionStmt {
+            // Regions only group code; their statements run in place.
+            stmts.extend(lower_stmt_list(&stmt_node, ctx)?);
+            continue;
+        }
         if let Some(stmt) = lower_stmt(&stmt_node, ctx)? {
             stmts.push(stmt);
         }
@@ -33,7 +38,10 @@ pub(in crate::harness) fn lower_stmt_list(
     Ok(stmts)
 }
 
-fn stmt_location(node: &SyntaxNode, ctx: &mut LoweringContext<'_>) -> Option<SourceLocation> {
+pub(super) fn stmt_location(
+    node: &SyntaxNode,
+    ctx: &mut LoweringContext<'_>,
+) -> Option<SourceLocation> {
     let range = node.text_range();
     let start = node
         .descendants_with_tokens()
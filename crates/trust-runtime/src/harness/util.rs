@@ -1,5 +1,5 @@
 use smol_str::SmolStr;
-use trust_syntax::syntax::{SyntaxKind, SyntaxNode};
+use trust_syntax::syntax::{identifier_text, SyntaxKind, SyntaxNode};
 
 pub(super) fn extract_name_from_expr(node: &SyntaxNode) -> Option<SmolStr> {
     match node.kind() {
@@ -16,7 +16,8 @@ pub(super) fn node_text(node: &SyntaxNode) -> String {
             None => continue,
         };
         match token.kind() {
-            SyntaxKind::Ident | SyntaxKind::Dot | SyntaxKind::KwEn | SyntaxKind::KwEno => {
+            SyntaxKind::Ident => text.push_str(identifier_text(token.text())),
+            SyntaxKind::Dot | SyntaxKind::KwEn | SyntaxKind::KwEno => {
                 text.push_str(token.text());
             }
             _ => {}
@@ -124,6 +125,7 @@ pub(super) fn is_statement_kind(kind: SyntaxKind) -> bool {
             | SyntaxKind::JmpStmt
             | SyntaxKind::LabelStmt
             | SyntaxKind::EmptyStmt
+            | SyntaxKind::RegionStmt
     )
 }
 
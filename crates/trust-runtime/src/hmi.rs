@@ -10,10 +10,9 @@ use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 use trust_hir::types::Type;
 
-use crate::debug::dap::value_type_name;
 use crate::debug::DebugSnapshot;
 use crate::runtime::RuntimeMetadata;
-use crate::value::Value;
+use crate::value::{value_type_name, Value};
 
 const HMI_SCHEMA_VERSION: u32 = 1;
 const DEFAULT_PAGE_ID: &str = "overview";
@@ -1134,13 +1133,7 @@ fn widget_for_type(ty: &Type, writable: bool) -> &'static str {
         | Type::Class { .. }
         | Type::Interface { .. } => "tree",
         ty if ty.is_string() || ty.is_char() => "text",
-        ty if ty.is_numeric() || ty.is_bit_string() || ty.is_time() => {
-            if writable {
-                "slider"
-            } else {
-                "value"
-            }
-        }
+        ty if writable && (ty.is_numeric() || ty.is_bit_string() || ty.is_time()) => "slider",
         _ => "value",
     }
 }
@@ -1196,7 +1189,7 @@ fn widget_for_value(value: &Value, writable: bool) -> &'static str {
     }
 }
 
-fn value_to_json(value: &Value) -> serde_json::Value {
+pub(crate) fn value_to_json(value: &Value) -> serde_json::Value {
     match value {
         Value::Bool(value) => serde_json::Value::Bool(*value),
         Value::SInt(value) => serde_json::json!(*value),
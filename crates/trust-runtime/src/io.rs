@@ -4,10 +4,23 @@
 
 use smol_str::SmolStr;
 
+mod mapping;
 mod modbus;
-pub use modbus::ModbusTcpDriver;
+pub use modbus::{ModbusBlock, ModbusFunction, ModbusOrder, ModbusTcpConfig, ModbusTcpDriver};
+mod modbus_rtu;
+pub use modbus_rtu::{
+    ModbusParity, ModbusRegisterBlock, ModbusRtuConfig, ModbusRtuDriver, ModbusRtuSlave,
+};
+mod modbus_server;
+pub use modbus_server::{
+    ModbusMapEntry, ModbusTable, ModbusTcpServerConfig, ModbusTcpServerDriver,
+};
 mod mqtt;
 pub use mqtt::MqttIoDriver;
+mod opcua_client;
+pub use opcua_client::OpcUaClientDriver;
+mod sparkplug;
+pub use sparkplug::{start_sparkplug, SparkplugConfig, SparkplugService};
 mod ethercat;
 pub use ethercat::EthercatIoDriver;
 mod gpio;
@@ -32,10 +45,37 @@ pub trait IoDriver: Send {
     /// Write the output image to hardware or a simulator.
     fn write_outputs(&mut self, outputs: &[u8]) -> Result<(), RuntimeError>;
 
+    /// Exchange the memory image (`%M`) at cycle start; most drivers ignore it.
+    fn read_memory(&mut self, _memory: &mut [u8]) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
+    /// Publish the memory image (`%M`) at cycle end; most drivers ignore it.
+    fn write_memory(&mut self, _memory: &[u8]) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
     /// Report the current driver health.
     fn health(&self) -> IoDriverHealth {
         IoDriverHealth::Ok
     }
+
+    /// Resolve variable-based mappings when the driver is registered: against
+    /// the program's `AT` bindings, or by name in `storage` for variables
+    /// without one. Most drivers ignore it.
+    fn bind_variables(&mut self, _bindings: &[IoBinding], _storage: &VariableStorage) {}
+
+    /// Store received values of name-mapped variables into the program;
+    /// called after `read_inputs`. Most drivers ignore it.
+    fn read_variables(&mut self, _storage: &mut VariableStorage) -> Result<(), RuntimeError> {
+        Ok(())
+    }
+
+    /// Capture name-mapped program variables to send; called before
+    /// `write_outputs`. Most drivers ignore it.
+    fn write_variables(&mut self, _storage: &VariableStorage) -> Result<(), RuntimeError> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -616,7 +656,7 @@ fn parse_u8(value: &str, full: &str) -> Result<u8, RuntimeError> {
         .map_err(|_| RuntimeError::InvalidIoAddress(full.into()))
 }
 
-fn expected_size_for_type(value_type: TypeId) -> Option<IoSize> {
+pub(crate) fn expected_size_for_type(value_type: TypeId) -> Option<IoSize> {
     match value_type {
         TypeId::BOOL => Some(IoSize::Bit),
         TypeId::SINT | TypeId::USINT | TypeId::BYTE | TypeId::CHAR => Some(IoSize::Byte),
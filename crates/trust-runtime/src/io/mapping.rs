@@ -0,0 +1,426 @@
+//! Single-address process image mappings shared by protocol drivers.
+
+use smol_str::SmolStr;
+use trust_hir::TypeId;
+
+use crate::error::RuntimeError;
+use crate::io::{IoAddress, IoArea, IoBinding, IoSize, IoTarget};
+use crate::memory::VariableStorage;
+use crate::value::{Value, ValueRef};
+
+/// A decoded scalar carried between a protocol payload and the image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Scalar {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Real(f64),
+}
+
+impl Scalar {
+    pub(super) fn as_f64(self) -> f64 {
+        match self {
+            Self::Bool(flag) => f64::from(u8::from(flag)),
+            Self::Int(value) => value as f64,
+            Self::UInt(value) => value as f64,
+            Self::Real(value) => value,
+        }
+    }
+}
+
+/// Parse a `type = "..."` entry into an elementary I/O type.
+pub(super) fn parse_value_type(name: &str, field: &str) -> Result<TypeId, RuntimeError> {
+    TypeId::from_builtin_name(&name.trim().to_ascii_uppercase())
+        .filter(|type_id| crate::io::expected_size_for_type(*type_id).is_some())
+        .ok_or_else(|| {
+            RuntimeError::InvalidConfig(
+                format!("{field} '{name}' is not an elementary I/O type").into(),
+            )
+        })
+}
+
+/// Whether an `AT` binding belongs to the configured variable name
+/// (`Main.speed` or the bare variable name).
+pub(super) fn binding_matches(binding: &IoBinding, name: &str) -> bool {
+    let target = match &binding.target {
+        IoTarget::Name(target) => Some(target.as_str()),
+        IoTarget::Reference(_) => None,
+    };
+    [binding.display_name.as_deref(), target]
+        .into_iter()
+        .flatten()
+        .any(|candidate| candidate.eq_ignore_ascii_case(name))
+}
+
+/// Parse a `direction = "input" | "output"` entry of a variable mapping.
+pub(super) fn parse_direction(text: &str, field: &str) -> Result<IoArea, RuntimeError> {
+    match text.trim().to_ascii_lowercase().as_str() {
+        "input" | "in" => Ok(IoArea::Input),
+        "output" | "out" => Ok(IoArea::Output),
+        other => Err(RuntimeError::InvalidConfig(
+            format!("{field} '{other}' must be input or output").into(),
+        )),
+    }
+}
+
+/// Image location of a variable mapping once bound.
+pub(super) struct BoundVariable {
+    pub(super) address: IoAddress,
+    pub(super) value_type: Option<TypeId>,
+    /// Set when the variable has no `AT` binding and is exchanged by name.
+    pub(super) named: Option<NamedVariable>,
+}
+
+/// Bind a variable mapping to its `AT` binding, or, without one, by name to
+/// the program variable in `direction`.
+pub(super) fn bind_variable(
+    name: &str,
+    bindings: &[IoBinding],
+    storage: &VariableStorage,
+    configured: Option<TypeId>,
+    direction: Option<IoArea>,
+) -> Result<BoundVariable, String> {
+    if let Some(binding) = bindings
+        .iter()
+        .find(|binding| binding_matches(binding, name))
+    {
+        check_mapped_address(&binding.address, configured, &format!("variable '{name}'"))
+            .map_err(|err| err.to_string())?;
+        if direction.is_some_and(|direction| direction != binding.address.area) {
+            return Err(format!(
+                "variable '{name}' is bound AT {:?}; direction does not match",
+                binding.address.area
+            ));
+        }
+        return Ok(BoundVariable {
+            address: binding.address.clone(),
+            value_type: binding.value_type,
+            named: None,
+        });
+    }
+    let Some(direction) = direction else {
+        return Err(format!(
+            "variable '{name}' has no AT binding; set direction = \"input\" or \"output\""
+        ));
+    };
+    let value = variable_ref(storage, name)
+        .and_then(|reference| storage.read_by_ref(reference))
+        .ok_or_else(|| format!("variable '{name}' not found"))?;
+    let actual = value_type_of(value)
+        .ok_or_else(|| format!("variable '{name}' is not an elementary I/O type"))?;
+    if configured.is_some_and(|configured| configured != actual) {
+        return Err(format!(
+            "variable '{name}': type does not match the variable"
+        ));
+    }
+    let value_type = actual;
+    let size = crate::io::expected_size_for_type(value_type)
+        .ok_or_else(|| format!("variable '{name}' is not an elementary I/O type"))?;
+    Ok(BoundVariable {
+        address: IoAddress {
+            area: direction,
+            size,
+            byte: 0,
+            bit: 0,
+            path: Vec::new(),
+            wildcard: false,
+        },
+        value_type: Some(value_type),
+        named: Some(NamedVariable {
+            name: SmolStr::new(name),
+            image: [0; 8],
+        }),
+    })
+}
+
+/// Program variable mapped by name rather than through an `AT` binding.
+///
+/// Its value passes through a private one-value image at the slot address
+/// (byte 0), so drivers encode and decode it like any image slot.
+#[derive(Debug, Clone)]
+pub(super) struct NamedVariable {
+    name: SmolStr,
+    image: [u8; 8],
+}
+
+impl NamedVariable {
+    pub(super) fn image(&self) -> &[u8] {
+        &self.image
+    }
+
+    pub(super) fn image_mut(&mut self) -> &mut [u8] {
+        &mut self.image
+    }
+
+    /// Copy the program value into the private image.
+    pub(super) fn load(
+        &mut self,
+        storage: &VariableStorage,
+        address: &IoAddress,
+        value_type: TypeId,
+    ) -> Result<(), SmolStr> {
+        let value = variable_ref(storage, &self.name)
+            .and_then(|reference| storage.read_by_ref(reference))
+            .ok_or_else(|| SmolStr::new(format!("variable '{}' not found", self.name)))?;
+        let scalar = scalar_of(value).ok_or_else(|| {
+            SmolStr::new(format!(
+                "variable '{}' is not an elementary I/O type",
+                self.name
+            ))
+        })?;
+        write_scalar(&mut self.image, address, value_type, scalar)
+    }
+
+    /// Write the private image value into the program variable.
+    pub(super) fn store(
+        &self,
+        storage: &mut VariableStorage,
+        address: &IoAddress,
+        value_type: TypeId,
+    ) -> Result<(), SmolStr> {
+        let value = value_of(value_type, read_scalar(&self.image, address, value_type));
+        let reference = variable_ref(storage, &self.name)
+            .ok_or_else(|| SmolStr::new(format!("variable '{}' not found", self.name)))?;
+        if storage.write_by_ref(reference, value) {
+            Ok(())
+        } else {
+            Err(SmolStr::new(format!(
+                "variable '{}' cannot be written",
+                self.name
+            )))
+        }
+    }
+}
+
+/// Resolve `speed` (global) or `Main.speed` / `Main.fb.out` (program and FB
+/// instance variables) in program storage, ignoring case.
+fn variable_ref(storage: &VariableStorage, name: &str) -> Option<ValueRef> {
+    let mut segments = name.split('.');
+    let first = segments.next()?;
+    let global = storage
+        .globals()
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case(first))?;
+    let mut reference = storage.ref_for_global(global)?;
+    for segment in segments {
+        let Some(Value::Instance(id)) = storage.read_by_ref(reference) else {
+            return None;
+        };
+        let id = *id;
+        let field = storage
+            .get_instance(id)?
+            .variables
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(segment))?;
+        reference = storage.ref_for_instance(id, field)?;
+    }
+    Some(reference)
+}
+
+fn value_type_of(value: &Value) -> Option<TypeId> {
+    Some(match value {
+        Value::Bool(_) => TypeId::BOOL,
+        Value::SInt(_) => TypeId::SINT,
+        Value::Int(_) => TypeId::INT,
+        Value::DInt(_) => TypeId::DINT,
+        Value::LInt(_) => TypeId::LINT,
+        Value::USInt(_) => TypeId::USINT,
+        Value::UInt(_) => TypeId::UINT,
+        Value::UDInt(_) => TypeId::UDINT,
+        Value::ULInt(_) => TypeId::ULINT,
+        Value::Real(_) => TypeId::REAL,
+        Value::LReal(_) => TypeId::LREAL,
+        Value::Char(_) => TypeId::CHAR,
+        Value::WChar(_) => TypeId::WCHAR,
+        Value::Byte(_) => TypeId::BYTE,
+        Value::Word(_) => TypeId::WORD,
+        Value::DWord(_) => TypeId::DWORD,
+        Value::LWord(_) => TypeId::LWORD,
+        _ => return None,
+    })
+}
+
+fn scalar_of(value: &Value) -> Option<Scalar> {
+    Some(match value {
+        Value::Bool(flag) => Scalar::Bool(*flag),
+        Value::SInt(value) => Scalar::Int(i64::from(*value)),
+        Value::Int(value) => Scalar::Int(i64::from(*value)),
+        Value::DInt(value) => Scalar::Int(i64::from(*value)),
+        Value::LInt(value) => Scalar::Int(*value),
+        Value::USInt(value) | Value::Byte(value) | Value::Char(value) => {
+            Scalar::UInt(u64::from(*value))
+        }
+        Value::UInt(value) | Value::Word(value) | Value::WChar(value) => {
+            Scalar::UInt(u64::from(*value))
+        }
+        Value::UDInt(value) | Value::DWord(value) => Scalar::UInt(u64::from(*value)),
+        Value::ULInt(value) | Value::LWord(value) => Scalar::UInt(*value),
+        Value::Real(value) => Scalar::Real(f64::from(*value)),
+        Value::LReal(value) => Scalar::Real(*value),
+        _ => return None,
+    })
+}
+
+/// Value of `value_type` for a scalar read back from an image, where it was
+/// already range checked.
+fn value_of(value_type: TypeId, scalar: Scalar) -> Value {
+    let (int, uint) = match scalar {
+        Scalar::Bool(flag) => (i64::from(flag), u64::from(flag)),
+        Scalar::Int(value) => (value, value as u64),
+        Scalar::UInt(value) => (value as i64, value),
+        Scalar::Real(value) => (value as i64, value as u64),
+    };
+    match value_type {
+        TypeId::BOOL => Value::Bool(uint != 0),
+        TypeId::SINT => Value::SInt(int as i8),
+        TypeId::INT => Value::Int(int as i16),
+        TypeId::DINT => Value::DInt(int as i32),
+        TypeId::LINT => Value::LInt(int),
+        TypeId::USINT => Value::USInt(uint as u8),
+        TypeId::UINT => Value::UInt(uint as u16),
+        TypeId::UDINT => Value::UDInt(uint as u32),
+        TypeId::ULINT => Value::ULInt(uint),
+        TypeId::REAL => Value::Real(scalar.as_f64() as f32),
+        TypeId::LREAL => Value::LReal(scalar.as_f64()),
+        TypeId::BYTE => Value::Byte(uint as u8),
+        TypeId::WORD => Value::Word(uint as u16),
+        TypeId::DWORD => Value::DWord(uint as u32),
+        TypeId::CHAR => Value::Char(uint as u8),
+        TypeId::WCHAR => Value::WChar(uint as u16),
+        _ => Value::LWord(uint),
+    }
+}
+
+pub(super) fn check_mapped_address(
+    address: &IoAddress,
+    value_type: Option<TypeId>,
+    label: &str,
+) -> Result<(), RuntimeError> {
+    if address.wildcard || address.path.len() > 1 || address.area == IoArea::Memory {
+        return Err(RuntimeError::InvalidConfig(
+            format!("{label} must be a plain %I or %Q address").into(),
+        ));
+    }
+    if let Some(value_type) = value_type {
+        if crate::io::expected_size_for_type(value_type) != Some(address.size) {
+            return Err(RuntimeError::InvalidConfig(
+                format!("{label}: type does not match the address size").into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Effective type of a mapping: configured type, else the bound variable
+/// type, else the bit-string type of the address size.
+pub(super) fn resolve_value_type(
+    address: &IoAddress,
+    configured: Option<TypeId>,
+    bound: Option<TypeId>,
+) -> TypeId {
+    configured
+        .or(bound
+            .filter(|type_id| crate::io::expected_size_for_type(*type_id) == Some(address.size)))
+        .unwrap_or(match address.size {
+            IoSize::Bit => TypeId::BOOL,
+            IoSize::Byte => TypeId::BYTE,
+            IoSize::Word => TypeId::WORD,
+            IoSize::DWord => TypeId::DWORD,
+            IoSize::LWord => TypeId::LWORD,
+        })
+}
+
+pub(super) fn width(address: &IoAddress) -> usize {
+    match address.size {
+        IoSize::Bit | IoSize::Byte => 1,
+        IoSize::Word => 2,
+        IoSize::DWord => 4,
+        IoSize::LWord => 8,
+    }
+}
+
+fn is_signed(value_type: TypeId) -> bool {
+    matches!(
+        value_type,
+        TypeId::SINT | TypeId::INT | TypeId::DINT | TypeId::LINT
+    )
+}
+
+/// Read a scalar of `value_type` at `address` (little-endian image).
+pub(super) fn read_scalar(image: &[u8], address: &IoAddress, value_type: TypeId) -> Scalar {
+    let start = address.byte as usize;
+    if address.size == IoSize::Bit {
+        let byte = image.get(start).copied().unwrap_or(0);
+        return Scalar::Bool((byte >> address.bit) & 1 == 1);
+    }
+    let mut bytes = [0u8; 8];
+    for (idx, byte) in bytes.iter_mut().take(width(address)).enumerate() {
+        *byte = image.get(start + idx).copied().unwrap_or(0);
+    }
+    let bits = u64::from_le_bytes(bytes);
+    match value_type {
+        TypeId::SINT => Scalar::Int(i64::from(bits as u8 as i8)),
+        TypeId::INT => Scalar::Int(i64::from(bits as u16 as i16)),
+        TypeId::DINT => Scalar::Int(i64::from(bits as u32 as i32)),
+        TypeId::LINT => Scalar::Int(bits as i64),
+        TypeId::REAL => Scalar::Real(f64::from(f32::from_bits(bits as u32))),
+        TypeId::LREAL => Scalar::Real(f64::from_bits(bits)),
+        _ => Scalar::UInt(bits),
+    }
+}
+
+/// Write a scalar of `value_type` at `address`, rejecting values that do not
+/// fit. Errors are plain messages for the caller to prefix.
+pub(super) fn write_scalar(
+    image: &mut [u8],
+    address: &IoAddress,
+    value_type: TypeId,
+    value: Scalar,
+) -> Result<(), SmolStr> {
+    let start = address.byte as usize;
+    let width = width(address);
+    if start + width > image.len() {
+        return Err("address is outside the process image".into());
+    }
+    if address.size == IoSize::Bit {
+        let flag = match value {
+            Scalar::Bool(flag) => flag,
+            Scalar::Int(0) | Scalar::UInt(0) => false,
+            Scalar::Int(1) | Scalar::UInt(1) => true,
+            _ => return Err("BOOL payload must be true/false or 0/1".into()),
+        };
+        if flag {
+            image[start] |= 1 << address.bit;
+        } else {
+            image[start] &= !(1 << address.bit);
+        }
+        return Ok(());
+    }
+    let bits = match (value_type, value) {
+        (TypeId::REAL, value) => u64::from((value.as_f64() as f32).to_bits()),
+        (TypeId::LREAL, value) => value.as_f64().to_bits(),
+        (_, Scalar::Real(_)) => return Err("expected an integer payload".into()),
+        (_, Scalar::Bool(flag)) => u64::from(flag),
+        (value_type, Scalar::Int(value)) => {
+            let bits = (width * 8) as u32;
+            let fits = if is_signed(value_type) {
+                bits == 64 || (value >> (bits - 1)) == 0 || (value >> (bits - 1)) == -1
+            } else {
+                value >= 0 && (bits == 64 || (value as u64) >> bits == 0)
+            };
+            if !fits {
+                return Err("value out of range".into());
+            }
+            value as u64
+        }
+        (value_type, Scalar::UInt(value)) => {
+            let bits = (width * 8) as u32 - u32::from(is_signed(value_type));
+            if bits < 64 && value >> bits != 0 {
+                return Err("value out of range".into());
+            }
+            value
+        }
+    };
+    image[start..start + width].copy_from_slice(&bits.to_le_bytes()[..width]);
+    Ok(())
+}
@@ -4,13 +4,14 @@
 
 use std::io::{Read, Write};
 use std::net::{SocketAddr, TcpStream};
-use std::time::Duration as StdDuration;
+use std::time::{Duration as StdDuration, Instant};
 
 use serde::Deserialize;
 use smol_str::SmolStr;
 
 use crate::error::RuntimeError;
-use crate::io::{IoDriver, IoDriverErrorPolicy, IoDriverHealth};
+use crate::io::{IoAddress, IoDriver, IoDriverErrorPolicy, IoDriverHealth, IoSize};
+use crate::memory::IoArea;
 
 #[derive(Debug, Clone)]
 pub struct ModbusTcpConfig {
@@ -20,6 +21,9 @@ pub struct ModbusTcpConfig {
     pub output_start: u16,
     pub timeout: StdDuration,
     pub on_error: IoDriverErrorPolicy,
+    /// Poll blocks; when empty, `input_start`/`output_start` map the whole
+    /// `%I`/`%Q` images onto input and holding registers.
+    pub blocks: Vec<ModbusBlock>,
 }
 
 impl ModbusTcpConfig {
@@ -38,6 +42,18 @@ impl ModbusTcpConfig {
             .map(IoDriverErrorPolicy::parse)
             .transpose()?
             .unwrap_or(IoDriverErrorPolicy::Fault);
+        if !params.blocks.is_empty()
+            && (params.input_start.is_some() || params.output_start.is_some())
+        {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.blocks cannot be combined with input_start/output_start".into(),
+            ));
+        }
+        let blocks = params
+            .blocks
+            .into_iter()
+            .map(ModbusBlock::from_toml)
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Self {
             address,
             unit_id: params.unit_id.unwrap_or(1),
@@ -45,10 +61,277 @@ impl ModbusTcpConfig {
             output_start: params.output_start.unwrap_or(0),
             timeout,
             on_error,
+            blocks,
         })
     }
 }
 
+/// Modbus function used by a poll block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModbusFunction {
+    ReadCoils,
+    ReadDiscreteInputs,
+    ReadHoldingRegisters,
+    ReadInputRegisters,
+    WriteMultipleCoils,
+    WriteMultipleRegisters,
+}
+
+impl ModbusFunction {
+    fn from_code(code: u8) -> Result<Self, RuntimeError> {
+        match code {
+            0x01 => Ok(Self::ReadCoils),
+            0x02 => Ok(Self::ReadDiscreteInputs),
+            0x03 => Ok(Self::ReadHoldingRegisters),
+            0x04 => Ok(Self::ReadInputRegisters),
+            0x0F => Ok(Self::WriteMultipleCoils),
+            0x10 => Ok(Self::WriteMultipleRegisters),
+            _ => Err(RuntimeError::InvalidConfig(
+                format!("io.params.blocks.function {code} (expected 1, 2, 3, 4, 15 or 16)").into(),
+            )),
+        }
+    }
+
+    #[must_use]
+    pub fn code(self) -> u8 {
+        match self {
+            Self::ReadCoils => 0x01,
+            Self::ReadDiscreteInputs => 0x02,
+            Self::ReadHoldingRegisters => 0x03,
+            Self::ReadInputRegisters => 0x04,
+            Self::WriteMultipleCoils => 0x0F,
+            Self::WriteMultipleRegisters => 0x10,
+        }
+    }
+
+    fn is_write(self) -> bool {
+        matches!(
+            self,
+            Self::WriteMultipleCoils | Self::WriteMultipleRegisters
+        )
+    }
+
+    fn is_bit(self) -> bool {
+        matches!(
+            self,
+            Self::ReadCoils | Self::ReadDiscreteInputs | Self::WriteMultipleCoils
+        )
+    }
+
+    fn max_count(self) -> u16 {
+        match self {
+            Self::ReadCoils | Self::ReadDiscreteInputs => 2000,
+            Self::WriteMultipleCoils => 1968,
+            Self::ReadHoldingRegisters | Self::ReadInputRegisters => 125,
+            Self::WriteMultipleRegisters => 123,
+        }
+    }
+}
+
+/// Byte order of a register on the wire, or register order of a 32-bit
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModbusOrder {
+    /// High byte (word) first, the Modbus standard.
+    Big,
+    /// Low byte (word) first.
+    Little,
+}
+
+impl ModbusOrder {
+    fn parse(text: &str, field: &str) -> Result<Self, RuntimeError> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "big" | "be" | "msb" => Ok(Self::Big),
+            "little" | "le" | "lsb" => Ok(Self::Little),
+            _ => Err(RuntimeError::InvalidConfig(
+                format!("io.params.blocks.{field} '{text}' (expected big/little)").into(),
+            )),
+        }
+    }
+}
+
+/// One poll block: `count` coils or registers from `start`, exchanged with
+/// the `%I` (reads) or `%Q` (writes) image at `byte`/`bit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModbusBlock {
+    pub function: ModbusFunction,
+    pub start: u16,
+    pub count: u16,
+    pub byte: usize,
+    pub bit: u8,
+    pub byte_order: ModbusOrder,
+    /// Register order of 32-bit values; `None` maps each register to its own
+    /// word.
+    pub word_order: Option<ModbusOrder>,
+    /// Minimum time between polls; `None` polls every cycle.
+    pub poll: Option<StdDuration>,
+}
+
+impl ModbusBlock {
+    fn from_toml(block: BlockToml) -> Result<Self, RuntimeError> {
+        let function = ModbusFunction::from_code(block.function)?;
+        if !(1..=function.max_count()).contains(&block.count) {
+            return Err(RuntimeError::InvalidConfig(
+                format!(
+                    "io.params.blocks.count {} (expected 1..={} for function {})",
+                    block.count,
+                    function.max_count(),
+                    function.code()
+                )
+                .into(),
+            ));
+        }
+        let target = IoAddress::parse(&block.target)?;
+        let expected = if function.is_write() {
+            IoArea::Output
+        } else {
+            IoArea::Input
+        };
+        if target.area != expected || target.wildcard || target.path.len() > 1 {
+            let area = if function.is_write() { "%Q" } else { "%I" };
+            return Err(RuntimeError::InvalidConfig(
+                format!(
+                    "io.params.blocks.target '{}' must be a flat {area} address for function {}",
+                    block.target,
+                    function.code()
+                )
+                .into(),
+            ));
+        }
+        if !function.is_bit() && target.size == IoSize::Bit {
+            return Err(RuntimeError::InvalidConfig(
+                format!(
+                    "io.params.blocks.target '{}' must be byte aligned for register functions",
+                    block.target
+                )
+                .into(),
+            ));
+        }
+        let byte_order = block
+            .byte_order
+            .as_deref()
+            .map(|text| ModbusOrder::parse(text, "byte_order"))
+            .transpose()?
+            .unwrap_or(ModbusOrder::Big);
+        let word_order = block
+            .word_order
+            .as_deref()
+            .map(|text| ModbusOrder::parse(text, "word_order"))
+            .transpose()?;
+        if function.is_bit() && (block.byte_order.is_some() || block.word_order.is_some()) {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.blocks byte_order/word_order apply to register functions only".into(),
+            ));
+        }
+        if word_order.is_some() && block.count % 2 != 0 {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.blocks.count must be even when word_order is set".into(),
+            ));
+        }
+        Ok(Self {
+            function,
+            start: block.start,
+            count: block.count,
+            byte: target.byte as usize,
+            bit: target.bit,
+            byte_order,
+            word_order,
+            poll: block.poll_ms.map(StdDuration::from_millis),
+        })
+    }
+
+    /// Convert between wire order and the little-endian image, where each
+    /// register is a native `%xW` word and the low word of a 32-bit value
+    /// comes first. Both swaps are their own inverse, so the same function
+    /// serves reads and writes.
+    fn reorder(&self, data: &mut [u8]) {
+        if self.byte_order == ModbusOrder::Big {
+            for register in data.chunks_exact_mut(2) {
+                register.swap(0, 1);
+            }
+        }
+        if self.word_order == Some(ModbusOrder::Big) {
+            for pair in data.chunks_exact_mut(4) {
+                pair.rotate_left(2);
+            }
+        }
+    }
+
+    fn pdu_header(&self) -> Vec<u8> {
+        let mut pdu = vec![self.function.code()];
+        pdu.extend_from_slice(&self.start.to_be_bytes());
+        pdu.extend_from_slice(&self.count.to_be_bytes());
+        pdu
+    }
+
+    fn bit_index(&self, idx: usize) -> (usize, u8) {
+        let index = self.byte * 8 + usize::from(self.bit) + idx;
+        (index / 8, (index % 8) as u8)
+    }
+
+    /// Copy a read response payload into the input image.
+    fn store(&self, data: &[u8], inputs: &mut [u8]) -> Result<(), RuntimeError> {
+        if self.function.is_bit() {
+            if data.len() < usize::from(self.count).div_ceil(8) {
+                return Err(RuntimeError::IoDriver("modbus response truncated".into()));
+            }
+            for idx in 0..usize::from(self.count) {
+                let (byte, bit) = self.bit_index(idx);
+                let Some(slot) = inputs.get_mut(byte) else {
+                    break;
+                };
+                if data[idx / 8] & (1 << (idx % 8)) != 0 {
+                    *slot |= 1 << bit;
+                } else {
+                    *slot &= !(1 << bit);
+                }
+            }
+            return Ok(());
+        }
+        let len = usize::from(self.count) * 2;
+        if data.len() < len {
+            return Err(RuntimeError::IoDriver("modbus response truncated".into()));
+        }
+        let mut registers = data[..len].to_vec();
+        self.reorder(&mut registers);
+        if let Some(target) = inputs.get_mut(self.byte..) {
+            let len = target.len().min(registers.len());
+            target[..len].copy_from_slice(&registers[..len]);
+        }
+        Ok(())
+    }
+
+    /// Build the write request for this block from the output image.
+    fn write_pdu(&self, outputs: &[u8]) -> Vec<u8> {
+        let mut pdu = self.pdu_header();
+        if self.function.is_bit() {
+            let mut packed = vec![0u8; usize::from(self.count).div_ceil(8)];
+            for idx in 0..usize::from(self.count) {
+                let (byte, bit) = self.bit_index(idx);
+                if outputs
+                    .get(byte)
+                    .is_some_and(|value| value & (1 << bit) != 0)
+                {
+                    packed[idx / 8] |= 1 << (idx % 8);
+                }
+            }
+            pdu.push(packed.len() as u8);
+            pdu.extend_from_slice(&packed);
+            return pdu;
+        }
+        let len = usize::from(self.count) * 2;
+        let mut registers = vec![0u8; len];
+        if let Some(source) = outputs.get(self.byte..) {
+            let copy = source.len().min(len);
+            registers[..copy].copy_from_slice(&source[..copy]);
+        }
+        self.reorder(&mut registers);
+        pdu.push(len as u8);
+        pdu.extend_from_slice(&registers);
+        pdu
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ModbusToml {
     address: String,
@@ -57,6 +340,19 @@ struct ModbusToml {
     output_start: Option<u16>,
     timeout_ms: Option<u64>,
     on_error: Option<String>,
+    #[serde(default)]
+    blocks: Vec<BlockToml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockToml {
+    function: u8,
+    start: u16,
+    count: u16,
+    target: String,
+    byte_order: Option<String>,
+    word_order: Option<String>,
+    poll_ms: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -70,6 +366,8 @@ pub struct ModbusTcpDriver {
     transaction_id: u16,
     stream: Option<TcpStream>,
     health: IoDriverHealth,
+    blocks: Vec<ModbusBlock>,
+    last_poll: Vec<Option<Instant>>,
 }
 
 impl ModbusTcpDriver {
@@ -84,6 +382,8 @@ impl ModbusTcpDriver {
             transaction_id: 1,
             stream: None,
             health: IoDriverHealth::Ok,
+            last_poll: vec![None; config.blocks.len()],
+            blocks: config.blocks,
         }
     }
 
@@ -121,21 +421,67 @@ impl ModbusTcpDriver {
             (qty >> 8) as u8,
             qty as u8,
         ];
-        let response = self.send_request(&pdu)?;
-        if response.len() < 2 {
-            return Err(RuntimeError::IoDriver("modbus response too short".into()));
+        self.read_request(&pdu)
+    }
+
+    fn read_request(&mut self, pdu: &[u8]) -> Result<Vec<u8>, RuntimeError> {
+        let response = self.request(pdu, 2)?;
+        let byte_count = response[1] as usize;
+        if response.len() < 2 + byte_count {
+            return Err(RuntimeError::IoDriver("modbus response truncated".into()));
         }
-        if response[0] & 0x80 != 0 {
+        Ok(response[2..2 + byte_count].to_vec())
+    }
+
+    /// Send `pdu` and reject exception responses and responses shorter than
+    /// `min_len`.
+    fn request(&mut self, pdu: &[u8], min_len: usize) -> Result<Vec<u8>, RuntimeError> {
+        let response = self.send_request(pdu)?;
+        if response
+            .first()
+            .is_some_and(|function| function & 0x80 != 0)
+        {
             let code = response.get(1).copied().unwrap_or(0);
             return Err(RuntimeError::IoDriver(
                 format!("modbus exception code {code}").into(),
             ));
         }
-        let byte_count = response[1] as usize;
-        if response.len() < 2 + byte_count {
-            return Err(RuntimeError::IoDriver("modbus response truncated".into()));
+        if response.len() < min_len {
+            return Err(RuntimeError::IoDriver("modbus response too short".into()));
         }
-        Ok(response[2..2 + byte_count].to_vec())
+        Ok(response)
+    }
+
+    /// Run every due block whose direction matches `write`. Stops at the
+    /// first failing block so the error policy sees a single error per cycle.
+    fn poll_blocks(
+        &mut self,
+        write: bool,
+        image: &mut [u8],
+        outputs: &[u8],
+    ) -> Result<(), RuntimeError> {
+        let now = Instant::now();
+        for idx in 0..self.blocks.len() {
+            let block = self.blocks[idx].clone();
+            if block.function.is_write() != write {
+                continue;
+            }
+            let due = match (self.last_poll[idx], block.poll) {
+                (Some(last), Some(period)) => now.duration_since(last) >= period,
+                _ => true,
+            };
+            if !due {
+                continue;
+            }
+            if write {
+                self.request(&block.write_pdu(outputs), 5)?;
+            } else {
+                let data = self.read_request(&block.pdu_header())?;
+                block.store(&data, image)?;
+            }
+            self.last_poll[idx] = Some(now);
+        }
+        Ok(())
     }
 
     fn write_registers(&mut self, start: u16, data: &[u8]) -> Result<(), RuntimeError> {
@@ -150,16 +496,7 @@ impl ModbusTcpDriver {
         payload.push(byte_count as u8);
         payload.extend(std::iter::repeat_n(0u8, byte_count));
         payload[6..6 + data.len()].copy_from_slice(data);
-        let response = self.send_request(&payload)?;
-        if response.len() < 5 {
-            return Err(RuntimeError::IoDriver("modbus response too short".into()));
-        }
-        if response[0] & 0x80 != 0 {
-            let code = response.get(1).copied().unwrap_or(0);
-            return Err(RuntimeError::IoDriver(
-                format!("modbus exception code {code}").into(),
-            ));
-        }
+        self.request(&payload, 5)?;
         Ok(())
     }
 
@@ -231,6 +568,15 @@ impl ModbusTcpDriver {
 
 impl IoDriver for ModbusTcpDriver {
     fn read_inputs(&mut self, inputs: &mut [u8]) -> Result<(), RuntimeError> {
+        if !self.blocks.is_empty() {
+            return match self.poll_blocks(false, inputs, &[]) {
+                Ok(()) => {
+                    self.mark_ok();
+                    Ok(())
+                }
+                Err(err) => self.handle_error(err),
+            };
+        }
         if inputs.is_empty() {
             return Ok(());
         }
@@ -247,6 +593,15 @@ impl IoDriver for ModbusTcpDriver {
     }
 
     fn write_outputs(&mut self, outputs: &[u8]) -> Result<(), RuntimeError> {
+        if !self.blocks.is_empty() {
+            return match self.poll_blocks(true, &mut [], outputs) {
+                Ok(()) => {
+                    self.mark_ok();
+                    Ok(())
+                }
+                Err(err) => self.handle_error(err),
+            };
+        }
         if outputs.is_empty() {
             return Ok(());
         }
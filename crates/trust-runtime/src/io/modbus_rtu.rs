@@ -0,0 +1,499 @@
+//! Modbus RTU (serial line) master I/O driver.
+
+#![allow(missing_docs)]
+
+use std::io::{Read, Write};
+use std::time::{Duration as StdDuration, Instant};
+
+use serde::Deserialize;
+use smol_str::SmolStr;
+
+use crate::error::RuntimeError;
+use crate::io::{IoDriver, IoDriverErrorPolicy, IoDriverHealth};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModbusParity {
+    None,
+    Even,
+    Odd,
+}
+
+impl ModbusParity {
+    fn parse(text: &str) -> Result<Self, RuntimeError> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "none" | "n" => Ok(Self::None),
+            "even" | "e" => Ok(Self::Even),
+            "odd" | "o" => Ok(Self::Odd),
+            _ => Err(RuntimeError::InvalidConfig(
+                format!("io.params.parity '{text}' (expected none/even/odd)").into(),
+            )),
+        }
+    }
+
+    fn serial(self) -> serialport::Parity {
+        match self {
+            Self::None => serialport::Parity::None,
+            Self::Even => serialport::Parity::Even,
+            Self::Odd => serialport::Parity::Odd,
+        }
+    }
+}
+
+/// Register block exchanged with one slave: `count` registers from `start`
+/// copied to or from the process image at byte `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModbusRegisterBlock {
+    pub start: u16,
+    pub count: u16,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModbusRtuSlave {
+    pub unit_id: u8,
+    /// Input registers (function 04) read into `%I`.
+    pub inputs: Vec<ModbusRegisterBlock>,
+    /// Holding registers (function 16) written from `%Q`.
+    pub outputs: Vec<ModbusRegisterBlock>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModbusRtuConfig {
+    pub port: SmolStr,
+    pub baud: u32,
+    pub parity: ModbusParity,
+    pub stop_bits: u8,
+    pub timeout: StdDuration,
+    /// Silent interval enforced between frames (3.5 character times).
+    pub inter_frame: StdDuration,
+    pub on_error: IoDriverErrorPolicy,
+    pub slaves: Vec<ModbusRtuSlave>,
+}
+
+impl ModbusRtuConfig {
+    pub fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
+        let params: RtuToml = value
+            .clone()
+            .try_into()
+            .map_err(|err| RuntimeError::InvalidConfig(format!("io.params: {err}").into()))?;
+        let port = params.port.trim();
+        if port.is_empty() {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.port must not be empty".into(),
+            ));
+        }
+        let baud = params.baud.unwrap_or(19_200);
+        if baud == 0 {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.baud must be >= 1".into(),
+            ));
+        }
+        let parity = params
+            .parity
+            .as_deref()
+            .map(ModbusParity::parse)
+            .transpose()?
+            .unwrap_or(ModbusParity::Even);
+        let stop_bits = params.stop_bits.unwrap_or(1);
+        if !matches!(stop_bits, 1 | 2) {
+            return Err(RuntimeError::InvalidConfig(
+                format!("io.params.stop_bits {stop_bits} (expected 1 or 2)").into(),
+            ));
+        }
+        let inter_frame = match params.inter_frame_us {
+            Some(micros) => StdDuration::from_micros(micros),
+            None => default_inter_frame(baud, parity, stop_bits),
+        };
+        let on_error = params
+            .on_error
+            .as_deref()
+            .map(IoDriverErrorPolicy::parse)
+            .transpose()?
+            .unwrap_or(IoDriverErrorPolicy::Fault);
+        if params.slaves.is_empty() {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.slaves must not be empty".into(),
+            ));
+        }
+        let mut slaves: Vec<ModbusRtuSlave> = Vec::with_capacity(params.slaves.len());
+        for slave in params.slaves {
+            if !(1..=247).contains(&slave.unit_id) {
+                return Err(RuntimeError::InvalidConfig(
+                    format!(
+                        "io.params.slaves.unit_id {} (expected 1..=247)",
+                        slave.unit_id
+                    )
+                    .into(),
+                ));
+            }
+            if slaves.iter().any(|other| other.unit_id == slave.unit_id) {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("io.params.slaves.unit_id {} is duplicated", slave.unit_id).into(),
+                ));
+            }
+            let inputs = parse_blocks(slave.inputs, "inputs", 125)?;
+            let outputs = parse_blocks(slave.outputs, "outputs", 123)?;
+            slaves.push(ModbusRtuSlave {
+                unit_id: slave.unit_id,
+                inputs,
+                outputs,
+            });
+        }
+        Ok(Self {
+            port: SmolStr::new(port),
+            baud,
+            parity,
+            stop_bits,
+            timeout: StdDuration::from_millis(params.timeout_ms.unwrap_or(500)),
+            inter_frame,
+            on_error,
+            slaves,
+        })
+    }
+}
+
+fn parse_blocks(
+    blocks: Vec<BlockToml>,
+    field: &str,
+    max_count: u16,
+) -> Result<Vec<ModbusRegisterBlock>, RuntimeError> {
+    blocks
+        .into_iter()
+        .map(|block| {
+            if !(1..=max_count).contains(&block.count) {
+                return Err(RuntimeError::InvalidConfig(
+                    format!(
+                        "io.params.slaves.{field}.count {} (expected 1..={max_count})",
+                        block.count
+                    )
+                    .into(),
+                ));
+            }
+            Ok(ModbusRegisterBlock {
+                start: block.start,
+                count: block.count,
+                offset: block.offset.unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+/// 3.5 character times; fixed at 1.75 ms above 19200 baud (Modbus over
+/// serial line, 2.5.1.1).
+fn default_inter_frame(baud: u32, parity: ModbusParity, stop_bits: u8) -> StdDuration {
+    if baud > 19_200 {
+        return StdDuration::from_micros(1_750);
+    }
+    let parity_bits = u64::from(parity != ModbusParity::None);
+    let char_bits = 1 + 8 + parity_bits + u64::from(stop_bits);
+    StdDuration::from_micros(char_bits * 3_500_000 / u64::from(baud))
+}
+
+#[derive(Debug, Deserialize)]
+struct RtuToml {
+    port: String,
+    baud: Option<u32>,
+    parity: Option<String>,
+    stop_bits: Option<u8>,
+    timeout_ms: Option<u64>,
+    inter_frame_us: Option<u64>,
+    on_error: Option<String>,
+    #[serde(default)]
+    slaves: Vec<SlaveToml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlaveToml {
+    unit_id: u8,
+    #[serde(default)]
+    inputs: Vec<BlockToml>,
+    #[serde(default)]
+    outputs: Vec<BlockToml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockToml {
+    start: u16,
+    count: u16,
+    offset: Option<usize>,
+}
+
+/// Modbus RTU master polling several slaves on one serial line.
+///
+/// Register data is copied raw (high byte first), like `ModbusTcpDriver`.
+pub struct ModbusRtuDriver {
+    config: ModbusRtuConfig,
+    port: Option<Box<dyn serialport::SerialPort>>,
+    last_frame: Option<Instant>,
+    health: IoDriverHealth,
+}
+
+impl std::fmt::Debug for ModbusRtuDriver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModbusRtuDriver")
+            .field("config", &self.config)
+            .field("open", &self.port.is_some())
+            .field("health", &self.health)
+            .finish()
+    }
+}
+
+impl ModbusRtuDriver {
+    pub fn new(config: ModbusRtuConfig) -> Self {
+        Self {
+            config,
+            port: None,
+            last_frame: None,
+            health: IoDriverHealth::Ok,
+        }
+    }
+
+    pub fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
+        let config = ModbusRtuConfig::from_params(value)?;
+        Ok(Self::new(config))
+    }
+
+    pub fn validate_params(value: &toml::Value) -> Result<(), RuntimeError> {
+        ModbusRtuConfig::from_params(value).map(|_| ())
+    }
+
+    fn ensure_open(&mut self) -> Result<&mut Box<dyn serialport::SerialPort>, RuntimeError> {
+        if self.port.is_none() {
+            let stop_bits = if self.config.stop_bits == 2 {
+                serialport::StopBits::Two
+            } else {
+                serialport::StopBits::One
+            };
+            let port = serialport::new(self.config.port.as_str(), self.config.baud)
+                .data_bits(serialport::DataBits::Eight)
+                .parity(self.config.parity.serial())
+                .stop_bits(stop_bits)
+                .flow_control(serialport::FlowControl::None)
+                .timeout(self.config.timeout)
+                .open()
+                .map_err(|err| {
+                    RuntimeError::IoDriver(
+                        format!("modbus rtu open {}: {err}", self.config.port).into(),
+                    )
+                })?;
+            self.port = Some(port);
+        }
+        self.port
+            .as_mut()
+            .ok_or_else(|| RuntimeError::IoDriver("modbus rtu port not open".into()))
+    }
+
+    fn wait_inter_frame(&self) {
+        if let Some(last) = self.last_frame {
+            let elapsed = last.elapsed();
+            if elapsed < self.config.inter_frame {
+                std::thread::sleep(self.config.inter_frame - elapsed);
+            }
+        }
+    }
+
+    fn transact(&mut self, unit_id: u8, pdu: &[u8]) -> Result<Vec<u8>, RuntimeError> {
+        self.wait_inter_frame();
+        let mut frame = Vec::with_capacity(pdu.len() + 3);
+        frame.push(unit_id);
+        frame.extend_from_slice(pdu);
+        frame.extend_from_slice(&crc16(&frame).to_le_bytes());
+        let result = self.exchange(&frame, pdu[0]);
+        self.last_frame = Some(Instant::now());
+        let response = result?;
+        if response[0] != unit_id {
+            return Err(RuntimeError::IoDriver(
+                format!(
+                    "modbus rtu response from unit {} (expected {unit_id})",
+                    response[0]
+                )
+                .into(),
+            ));
+        }
+        let pdu = &response[1..response.len() - 2];
+        if pdu[0] & 0x80 != 0 {
+            let code = pdu.get(1).copied().unwrap_or(0);
+            return Err(RuntimeError::IoDriver(
+                format!("modbus rtu unit {unit_id} exception code {code}").into(),
+            ));
+        }
+        Ok(pdu.to_vec())
+    }
+
+    /// Send `frame` and read the whole response frame, CRC checked.
+    fn exchange(&mut self, frame: &[u8], function: u8) -> Result<Vec<u8>, RuntimeError> {
+        let port = self.ensure_open()?;
+        let _ = port.clear(serialport::ClearBuffer::Input);
+        port.write_all(frame)
+            .map_err(|err| RuntimeError::IoDriver(format!("modbus rtu write: {err}").into()))?;
+        port.flush().ok();
+        let mut response = vec![0u8; 3];
+        read_exact(port, &mut response)?;
+        // RTU frames carry no length; derive it from the function code.
+        let remaining = if response[1] & 0x80 != 0 {
+            2
+        } else {
+            match function {
+                0x01..=0x04 => response[2] as usize + 2,
+                0x05 | 0x06 | 0x0F | 0x10 => 5,
+                _ => {
+                    return Err(RuntimeError::IoDriver(
+                        format!("modbus rtu unsupported function {function}").into(),
+                    ))
+                }
+            }
+        };
+        let start = response.len();
+        response.resize(start + remaining, 0);
+        read_exact(port, &mut response[start..])?;
+        let body = response.len() - 2;
+        let crc = u16::from_le_bytes([response[body], response[body + 1]]);
+        if crc != crc16(&response[..body]) {
+            return Err(RuntimeError::IoDriver("modbus rtu crc mismatch".into()));
+        }
+        if response[1] & 0x7F != function {
+            return Err(RuntimeError::IoDriver(
+                format!(
+                    "modbus rtu function mismatch {} != {function}",
+                    response[1] & 0x7F
+                )
+                .into(),
+            ));
+        }
+        Ok(response)
+    }
+
+    fn read_block(
+        &mut self,
+        unit_id: u8,
+        block: ModbusRegisterBlock,
+        inputs: &mut [u8],
+    ) -> Result<(), RuntimeError> {
+        let pdu = [
+            0x04,
+            (block.start >> 8) as u8,
+            block.start as u8,
+            (block.count >> 8) as u8,
+            block.count as u8,
+        ];
+        let response = self.transact(unit_id, &pdu)?;
+        let data = &response[2..];
+        if data.len() != usize::from(block.count) * 2 {
+            return Err(RuntimeError::IoDriver(
+                format!("modbus rtu unit {unit_id} returned {} bytes", data.len()).into(),
+            ));
+        }
+        if let Some(target) = inputs.get_mut(block.offset..) {
+            let len = target.len().min(data.len());
+            target[..len].copy_from_slice(&data[..len]);
+        }
+        Ok(())
+    }
+
+    fn write_block(
+        &mut self,
+        unit_id: u8,
+        block: ModbusRegisterBlock,
+        outputs: &[u8],
+    ) -> Result<(), RuntimeError> {
+        let byte_count = usize::from(block.count) * 2;
+        let mut pdu = Vec::with_capacity(6 + byte_count);
+        pdu.push(0x10);
+        pdu.push((block.start >> 8) as u8);
+        pdu.push(block.start as u8);
+        pdu.push((block.count >> 8) as u8);
+        pdu.push(block.count as u8);
+        pdu.push(byte_count as u8);
+        let source = outputs.get(block.offset..).unwrap_or(&[]);
+        let len = source.len().min(byte_count);
+        pdu.extend_from_slice(&source[..len]);
+        pdu.extend(std::iter::repeat_n(0u8, byte_count - len));
+        self.transact(unit_id, &pdu).map(|_| ())
+    }
+
+    fn handle_error(&mut self, err: RuntimeError) -> Result<(), RuntimeError> {
+        let message = SmolStr::new(err.to_string());
+        // Reopen the line after errors so stale bytes are discarded.
+        self.port = None;
+        if matches!(self.config.on_error, IoDriverErrorPolicy::Fault) {
+            self.health = IoDriverHealth::Faulted {
+                error: message.clone(),
+            };
+            return Err(RuntimeError::IoDriver(message));
+        }
+        self.health = IoDriverHealth::Degraded { error: message };
+        Ok(())
+    }
+}
+
+fn read_exact(
+    port: &mut Box<dyn serialport::SerialPort>,
+    buf: &mut [u8],
+) -> Result<(), RuntimeError> {
+    port.read_exact(buf)
+        .map_err(|err| RuntimeError::IoDriver(format!("modbus rtu read: {err}").into()))
+}
+
+/// Modbus CRC-16 (polynomial 0xA001, initial value 0xFFFF).
+pub(crate) fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for byte in data {
+        crc ^= u16::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+impl IoDriver for ModbusRtuDriver {
+    fn read_inputs(&mut self, inputs: &mut [u8]) -> Result<(), RuntimeError> {
+        // Poll every slave even if one fails, so a dead device does not
+        // freeze the values of the others.
+        let mut first_error = None;
+        for idx in 0..self.config.slaves.len() {
+            let unit_id = self.config.slaves[idx].unit_id;
+            for block_idx in 0..self.config.slaves[idx].inputs.len() {
+                let block = self.config.slaves[idx].inputs[block_idx];
+                if let Err(err) = self.read_block(unit_id, block, inputs) {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        match first_error {
+            Some(err) => self.handle_error(err),
+            None => {
+                self.health = IoDriverHealth::Ok;
+                Ok(())
+            }
+        }
+    }
+
+    fn write_outputs(&mut self, outputs: &[u8]) -> Result<(), RuntimeError> {
+        let mut first_error = None;
+        for idx in 0..self.config.slaves.len() {
+            let unit_id = self.config.slaves[idx].unit_id;
+            for block_idx in 0..self.config.slaves[idx].outputs.len() {
+                let block = self.config.slaves[idx].outputs[block_idx];
+                if let Err(err) = self.write_block(unit_id, block, outputs) {
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        match first_error {
+            Some(err) => self.handle_error(err),
+            None => {
+                self.health = IoDriverHealth::Ok;
+                Ok(())
+            }
+        }
+    }
+
+    fn health(&self) -> IoDriverHealth {
+        self.health.clone()
+    }
+}
@@ -0,0 +1,741 @@
+//! Modbus TCP server (slave) I/O driver.
+
+#![allow(missing_docs)]
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use serde::Deserialize;
+use smol_str::SmolStr;
+
+use crate::error::RuntimeError;
+use crate::io::{IoAddress, IoDriver, IoDriverHealth, IoSize};
+use crate::memory::IoArea;
+
+const EXCEPTION_ILLEGAL_FUNCTION: u8 = 0x01;
+const EXCEPTION_ILLEGAL_ADDRESS: u8 = 0x02;
+const EXCEPTION_ILLEGAL_VALUE: u8 = 0x03;
+
+/// Modbus data table served to clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModbusTable {
+    Coils,
+    DiscreteInputs,
+    HoldingRegisters,
+    InputRegisters,
+}
+
+impl ModbusTable {
+    fn parse(text: &str) -> Result<Self, RuntimeError> {
+        match text.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "coil" | "coils" => Ok(Self::Coils),
+            "discrete_input" | "discrete_inputs" => Ok(Self::DiscreteInputs),
+            "holding_register" | "holding_registers" => Ok(Self::HoldingRegisters),
+            "input_register" | "input_registers" => Ok(Self::InputRegisters),
+            _ => Err(RuntimeError::InvalidConfig(
+                format!(
+                    "io.params.map.table '{text}' (expected coils/discrete_inputs/holding_registers/input_registers)"
+                )
+                .into(),
+            )),
+        }
+    }
+
+    fn is_bit(self) -> bool {
+        matches!(self, Self::Coils | Self::DiscreteInputs)
+    }
+
+    fn is_writable(self) -> bool {
+        matches!(self, Self::Coils | Self::HoldingRegisters)
+    }
+}
+
+/// One register map entry: `count` Modbus addresses from `start` onto the
+/// process image starting at `area`/`byte`/`bit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModbusMapEntry {
+    pub table: ModbusTable,
+    pub start: u16,
+    pub count: u16,
+    pub area: IoArea,
+    pub byte: u32,
+    pub bit: u8,
+}
+
+impl ModbusMapEntry {
+    fn contains(&self, address: u16) -> bool {
+        address >= self.start && u32::from(address) < u32::from(self.start) + u32::from(self.count)
+    }
+
+    /// Image location of a bit-table address: (byte, bit).
+    fn bit_location(&self, address: u16) -> (usize, u8) {
+        let index =
+            u64::from(self.byte) * 8 + u64::from(self.bit) + u64::from(address - self.start);
+        ((index / 8) as usize, (index % 8) as u8)
+    }
+
+    /// Image offset of the word behind a register-table address.
+    fn register_offset(&self, address: u16) -> usize {
+        self.byte as usize + usize::from(address - self.start) * 2
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModbusTcpServerConfig {
+    pub listen: SocketAddr,
+    pub unit_id: Option<u8>,
+    pub max_connections: usize,
+    pub timeout: StdDuration,
+    pub map: Vec<ModbusMapEntry>,
+}
+
+impl ModbusTcpServerConfig {
+    pub fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
+        let params: ServerToml = value
+            .clone()
+            .try_into()
+            .map_err(|err| RuntimeError::InvalidConfig(format!("io.params: {err}").into()))?;
+        let listen = params.listen.parse::<SocketAddr>().map_err(|err| {
+            RuntimeError::InvalidConfig(format!("io.params.listen: {err}").into())
+        })?;
+        if params.map.is_empty() {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.map must not be empty".into(),
+            ));
+        }
+        let mut map: Vec<ModbusMapEntry> = Vec::with_capacity(params.map.len());
+        for entry in params.map {
+            let entry = parse_map_entry(entry)?;
+            if let Some(other) = map.iter().find(|other| overlaps(other, &entry)) {
+                return Err(RuntimeError::InvalidConfig(
+                    format!(
+                        "io.params.map: {:?} {}..{} overlaps entry starting at {}",
+                        entry.table,
+                        entry.start,
+                        u32::from(entry.start) + u32::from(entry.count) - 1,
+                        other.start
+                    )
+                    .into(),
+                ));
+            }
+            map.push(entry);
+        }
+        Ok(Self {
+            listen,
+            unit_id: params.unit_id,
+            max_connections: params.max_connections.unwrap_or(8).max(1),
+            timeout: StdDuration::from_millis(params.timeout_ms.unwrap_or(30_000)),
+            map,
+        })
+    }
+}
+
+fn parse_map_entry(entry: MapEntryToml) -> Result<ModbusMapEntry, RuntimeError> {
+    let table = ModbusTable::parse(&entry.table)?;
+    if entry.count == 0 {
+        return Err(RuntimeError::InvalidConfig(
+            "io.params.map.count must be >= 1".into(),
+        ));
+    }
+    if u32::from(entry.start) + u32::from(entry.count) > 0x1_0000 {
+        return Err(RuntimeError::InvalidConfig(
+            format!(
+                "io.params.map: {} + {} exceeds the Modbus address range",
+                entry.start, entry.count
+            )
+            .into(),
+        ));
+    }
+    let address = IoAddress::parse(&entry.address)?;
+    if address.wildcard || address.path.len() > 1 {
+        return Err(RuntimeError::InvalidConfig(
+            format!(
+                "io.params.map.address '{}' must be a flat address",
+                entry.address
+            )
+            .into(),
+        ));
+    }
+    match (table.is_bit(), address.size) {
+        (true, IoSize::Bit) | (false, IoSize::Word) => {}
+        (true, _) => {
+            return Err(RuntimeError::InvalidConfig(
+                format!(
+                    "io.params.map.address '{}' must be a bit address (%IX/%QX/%MX) for {:?}",
+                    entry.address, table
+                )
+                .into(),
+            ))
+        }
+        (false, _) => {
+            return Err(RuntimeError::InvalidConfig(
+                format!(
+                    "io.params.map.address '{}' must be a word address (%IW/%QW/%MW) for {:?}",
+                    entry.address, table
+                )
+                .into(),
+            ))
+        }
+    }
+    if table.is_writable() && matches!(address.area, IoArea::Output) {
+        return Err(RuntimeError::InvalidConfig(
+            format!(
+                "io.params.map.address '{}': {:?} are written by clients and must map to %I or %M",
+                entry.address, table
+            )
+            .into(),
+        ));
+    }
+    Ok(ModbusMapEntry {
+        table,
+        start: entry.start,
+        count: entry.count,
+        area: address.area,
+        byte: address.byte,
+        bit: address.bit,
+    })
+}
+
+fn overlaps(left: &ModbusMapEntry, right: &ModbusMapEntry) -> bool {
+    let left_end = u32::from(left.start) + u32::from(left.count);
+    let right_end = u32::from(right.start) + u32::from(right.count);
+    left.table == right.table
+        && u32::from(left.start) < right_end
+        && u32::from(right.start) < left_end
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerToml {
+    listen: String,
+    unit_id: Option<u8>,
+    max_connections: Option<usize>,
+    timeout_ms: Option<u64>,
+    #[serde(default)]
+    map: Vec<MapEntryToml>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MapEntryToml {
+    table: String,
+    start: u16,
+    count: u16,
+    address: String,
+}
+
+/// Byte-level write from a client, applied to the runtime image at the next
+/// cycle start.
+#[derive(Debug, Clone, Copy)]
+struct PendingWrite {
+    area: IoArea,
+    byte: usize,
+    mask: u8,
+    value: u8,
+}
+
+/// Process image copy served to clients.
+#[derive(Debug, Default)]
+struct ServerImage {
+    inputs: Vec<u8>,
+    outputs: Vec<u8>,
+    memory: Vec<u8>,
+    pending: Vec<PendingWrite>,
+}
+
+impl ServerImage {
+    fn area(&self, area: IoArea) -> &[u8] {
+        match area {
+            IoArea::Input => &self.inputs,
+            IoArea::Output => &self.outputs,
+            IoArea::Memory => &self.memory,
+        }
+    }
+
+    fn area_mut(&mut self, area: IoArea) -> &mut Vec<u8> {
+        match area {
+            IoArea::Input => &mut self.inputs,
+            IoArea::Output => &mut self.outputs,
+            IoArea::Memory => &mut self.memory,
+        }
+    }
+
+    /// Record a client write and show it to later client reads right away.
+    fn write(&mut self, write: PendingWrite) -> bool {
+        let Some(slot) = self.area_mut(write.area).get_mut(write.byte) else {
+            return false;
+        };
+        *slot = (*slot & !write.mask) | (write.value & write.mask);
+        self.pending.push(write);
+        true
+    }
+
+    /// Apply pending client writes for `area` to the runtime image.
+    fn drain_into(&mut self, area: IoArea, image: &mut [u8]) {
+        self.pending.retain(|write| {
+            if write.area != area {
+                return true;
+            }
+            if let Some(slot) = image.get_mut(write.byte) {
+                *slot = (*slot & !write.mask) | (write.value & write.mask);
+            }
+            false
+        });
+    }
+
+    /// Refresh the copy of `area` from the runtime image, keeping client
+    /// writes that the runtime has not consumed yet.
+    fn refresh(&mut self, area: IoArea, image: &[u8]) {
+        let pending: Vec<PendingWrite> = self
+            .pending
+            .iter()
+            .copied()
+            .filter(|write| write.area == area)
+            .collect();
+        let copy = self.area_mut(area);
+        copy.clear();
+        copy.extend_from_slice(image);
+        for write in pending {
+            if let Some(slot) = copy.get_mut(write.byte) {
+                *slot = (*slot & !write.mask) | (write.value & write.mask);
+            }
+        }
+    }
+}
+
+struct ServerShared {
+    image: Mutex<ServerImage>,
+    map: Vec<ModbusMapEntry>,
+    unit_id: Option<u8>,
+    timeout: StdDuration,
+    max_connections: usize,
+    connections: AtomicUsize,
+    stop: AtomicBool,
+    last_error: Mutex<Option<SmolStr>>,
+}
+
+/// Modbus TCP server exposing the process image to SCADA/HMI clients.
+///
+/// Coils and holding registers are writable by clients and map onto `%I` or
+/// `%M`; client writes reach the runtime at the next cycle start. Discrete
+/// inputs and input registers are read-only views of any area. Each register
+/// is one `%xW` word, so a client writing 1234 reads back 1234 from `%MW`.
+pub struct ModbusTcpServerDriver {
+    local_addr: SocketAddr,
+    shared: Arc<ServerShared>,
+    accept: Option<thread::JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for ModbusTcpServerDriver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModbusTcpServerDriver")
+            .field("local_addr", &self.local_addr)
+            .field("map", &self.shared.map)
+            .finish()
+    }
+}
+
+impl ModbusTcpServerDriver {
+    pub fn new(config: ModbusTcpServerConfig) -> Result<Self, RuntimeError> {
+        let listener = TcpListener::bind(config.listen).map_err(|err| {
+            RuntimeError::IoDriver(
+                format!("modbus tcp server bind {}: {err}", config.listen).into(),
+            )
+        })?;
+        let local_addr = listener.local_addr().map_err(|err| {
+            RuntimeError::IoDriver(format!("modbus tcp server address: {err}").into())
+        })?;
+        listener.set_nonblocking(true).map_err(|err| {
+            RuntimeError::IoDriver(format!("modbus tcp server nonblocking: {err}").into())
+        })?;
+        let shared = Arc::new(ServerShared {
+            image: Mutex::new(ServerImage::default()),
+            map: config.map,
+            unit_id: config.unit_id,
+            timeout: config.timeout,
+            max_connections: config.max_connections,
+            connections: AtomicUsize::new(0),
+            stop: AtomicBool::new(false),
+            last_error: Mutex::new(None),
+        });
+        let accept_shared = shared.clone();
+        let accept = thread::Builder::new()
+            .name("modbus-tcp-server".into())
+            .spawn(move || accept_loop(listener, accept_shared))
+            .map_err(|err| RuntimeError::ThreadSpawn(err.to_string().into()))?;
+        Ok(Self {
+            local_addr,
+            shared,
+            accept: Some(accept),
+        })
+    }
+
+    pub fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
+        let config = ModbusTcpServerConfig::from_params(value)?;
+        Self::new(config)
+    }
+
+    pub fn validate_params(value: &toml::Value) -> Result<(), RuntimeError> {
+        ModbusTcpServerConfig::from_params(value).map(|_| ())
+    }
+
+    /// Address the server is listening on.
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    fn image(&self) -> std::sync::MutexGuard<'_, ServerImage> {
+        self.shared
+            .image
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner())
+    }
+}
+
+impl Drop for ModbusTcpServerDriver {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::SeqCst);
+        if let Some(accept) = self.accept.take() {
+            let _ = accept.join();
+        }
+    }
+}
+
+impl IoDriver for ModbusTcpServerDriver {
+    fn read_inputs(&mut self, inputs: &mut [u8]) -> Result<(), RuntimeError> {
+        let mut image = self.image();
+        image.drain_into(IoArea::Input, inputs);
+        image.refresh(IoArea::Input, inputs);
+        Ok(())
+    }
+
+    fn write_outputs(&mut self, outputs: &[u8]) -> Result<(), RuntimeError> {
+        self.image().refresh(IoArea::Output, outputs);
+        Ok(())
+    }
+
+    fn read_memory(&mut self, memory: &mut [u8]) -> Result<(), RuntimeError> {
+        let mut image = self.image();
+        image.drain_into(IoArea::Memory, memory);
+        image.refresh(IoArea::Memory, memory);
+        Ok(())
+    }
+
+    fn write_memory(&mut self, memory: &[u8]) -> Result<(), RuntimeError> {
+        self.image().refresh(IoArea::Memory, memory);
+        Ok(())
+    }
+
+    fn health(&self) -> IoDriverHealth {
+        let error = self
+            .shared
+            .last_error
+            .lock()
+            .unwrap_or_else(|poison| poison.into_inner())
+            .clone();
+        match error {
+            Some(error) => IoDriverHealth::Degraded { error },
+            None => IoDriverHealth::Ok,
+        }
+    }
+}
+
+fn accept_loop(listener: TcpListener, shared: Arc<ServerShared>) {
+    while !shared.stop.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                if shared.connections.load(Ordering::SeqCst) >= shared.max_connections {
+                    set_last_error(
+                        &shared,
+                        format!("modbus tcp server rejected {peer}: connection limit"),
+                    );
+                    continue;
+                }
+                shared.connections.fetch_add(1, Ordering::SeqCst);
+                let connection_shared = shared.clone();
+                let spawned = thread::Builder::new()
+                    .name("modbus-tcp-client".into())
+                    .spawn(move || {
+                        serve_connection(stream, &connection_shared);
+                        connection_shared.connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                match spawned {
+                    Ok(_) => clear_last_error(&shared),
+                    Err(err) => {
+                        shared.connections.fetch_sub(1, Ordering::SeqCst);
+                        set_last_error(&shared, format!("modbus tcp server thread: {err}"));
+                    }
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(StdDuration::from_millis(20));
+            }
+            Err(err) => {
+                set_last_error(&shared, format!("modbus tcp server accept: {err}"));
+                thread::sleep(StdDuration::from_millis(100));
+            }
+        }
+    }
+}
+
+fn set_last_error(shared: &ServerShared, message: String) {
+    *shared
+        .last_error
+        .lock()
+        .unwrap_or_else(|poison| poison.into_inner()) = Some(SmolStr::new(message));
+}
+
+/// Health recovers once the server accepts or serves a client again.
+fn clear_last_error(shared: &ServerShared) {
+    *shared
+        .last_error
+        .lock()
+        .unwrap_or_else(|poison| poison.into_inner()) = None;
+}
+
+fn serve_connection(mut stream: TcpStream, shared: &ServerShared) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_nodelay(true);
+    // Short reads let the thread notice driver shutdown; `idle` enforces the
+    // configured client timeout.
+    let poll = StdDuration::from_millis(100);
+    let _ = stream.set_read_timeout(Some(poll));
+    let _ = stream.set_write_timeout(Some(shared.timeout));
+    let mut idle = StdDuration::ZERO;
+    while !shared.stop.load(Ordering::SeqCst) {
+        let mut header = [0u8; 7];
+        match stream.read(&mut header[..1]) {
+            Ok(0) => return,
+            Ok(_) => idle = StdDuration::ZERO,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                idle += poll;
+                if idle >= shared.timeout {
+                    return;
+                }
+                continue;
+            }
+            Err(_) => return,
+        }
+        let _ = stream.set_read_timeout(Some(shared.timeout));
+        if stream.read_exact(&mut header[1..]).is_err() {
+            return;
+        }
+        let protocol = u16::from_be_bytes([header[2], header[3]]);
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        let unit_id = header[6];
+        if protocol != 0 || !(2..=254).contains(&length) {
+            return;
+        }
+        let mut pdu = vec![0u8; length - 1];
+        if stream.read_exact(&mut pdu).is_err() {
+            return;
+        }
+        let _ = stream.set_read_timeout(Some(poll));
+        if shared.unit_id.is_some_and(|expected| expected != unit_id) {
+            continue;
+        }
+        let response = handle_pdu(shared, &pdu);
+        clear_last_error(shared);
+        let mut frame = Vec::with_capacity(7 + response.len());
+        frame.extend_from_slice(&header[0..4]);
+        frame.extend_from_slice(&((response.len() + 1) as u16).to_be_bytes());
+        frame.push(unit_id);
+        frame.extend_from_slice(&response);
+        if stream.write_all(&frame).is_err() {
+            return;
+        }
+    }
+}
+
+fn handle_pdu(shared: &ServerShared, pdu: &[u8]) -> Vec<u8> {
+    let function = pdu[0];
+    let result = match function {
+        0x01 => read_bits(shared, ModbusTable::Coils, pdu),
+        0x02 => read_bits(shared, ModbusTable::DiscreteInputs, pdu),
+        0x03 => read_registers(shared, ModbusTable::HoldingRegisters, pdu),
+        0x04 => read_registers(shared, ModbusTable::InputRegisters, pdu),
+        0x05 => write_single_coil(shared, pdu),
+        0x06 => write_single_register(shared, pdu),
+        0x0F => write_multiple_coils(shared, pdu),
+        0x10 => write_multiple_registers(shared, pdu),
+        _ => Err(EXCEPTION_ILLEGAL_FUNCTION),
+    };
+    match result {
+        Ok(response) => response,
+        Err(code) => vec![function | 0x80, code],
+    }
+}
+
+fn field(pdu: &[u8], offset: usize) -> Result<u16, u8> {
+    match pdu.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => Err(EXCEPTION_ILLEGAL_VALUE),
+    }
+}
+
+/// Map entry covering `address` in `table`.
+fn lookup(shared: &ServerShared, table: ModbusTable, address: u16) -> Result<&ModbusMapEntry, u8> {
+    shared
+        .map
+        .iter()
+        .find(|entry| entry.table == table && entry.contains(address))
+        .ok_or(EXCEPTION_ILLEGAL_ADDRESS)
+}
+
+fn addresses(start: u16, qty: u16) -> Result<impl Iterator<Item = u16>, u8> {
+    if u32::from(start) + u32::from(qty) > 0x1_0000 {
+        return Err(EXCEPTION_ILLEGAL_ADDRESS);
+    }
+    Ok((0..qty).map(move |offset| start + offset))
+}
+
+fn read_bits(shared: &ServerShared, table: ModbusTable, pdu: &[u8]) -> Result<Vec<u8>, u8> {
+    let start = field(pdu, 1)?;
+    let qty = field(pdu, 3)?;
+    if !(1..=2000).contains(&qty) {
+        return Err(EXCEPTION_ILLEGAL_VALUE);
+    }
+    let image = shared
+        .image
+        .lock()
+        .unwrap_or_else(|poison| poison.into_inner());
+    let mut bits = vec![0u8; usize::from(qty).div_ceil(8)];
+    for (idx, address) in addresses(start, qty)?.enumerate() {
+        let entry = lookup(shared, table, address)?;
+        let (byte, bit) = entry.bit_location(address);
+        let value = image
+            .area(entry.area)
+            .get(byte)
+            .ok_or(EXCEPTION_ILLEGAL_ADDRESS)?;
+        if value & (1 << bit) != 0 {
+            bits[idx / 8] |= 1 << (idx % 8);
+        }
+    }
+    let mut response = vec![pdu[0], bits.len() as u8];
+    response.extend_from_slice(&bits);
+    Ok(response)
+}
+
+fn read_registers(shared: &ServerShared, table: ModbusTable, pdu: &[u8]) -> Result<Vec<u8>, u8> {
+    let start = field(pdu, 1)?;
+    let qty = field(pdu, 3)?;
+    if !(1..=125).contains(&qty) {
+        return Err(EXCEPTION_ILLEGAL_VALUE);
+    }
+    let image = shared
+        .image
+        .lock()
+        .unwrap_or_else(|poison| poison.into_inner());
+    let mut response = vec![pdu[0], (qty * 2) as u8];
+    for address in addresses(start, qty)? {
+        let entry = lookup(shared, table, address)?;
+        let offset = entry.register_offset(address);
+        let bytes = image
+            .area(entry.area)
+            .get(offset..offset + 2)
+            .ok_or(EXCEPTION_ILLEGAL_ADDRESS)?;
+        // Image words are little-endian; Modbus registers are big-endian.
+        response.extend_from_slice(&[bytes[1], bytes[0]]);
+    }
+    Ok(response)
+}
+
+/// Resolve every target first so a rejected request writes nothing.
+fn write_bits(shared: &ServerShared, start: u16, values: &[bool]) -> Result<(), u8> {
+    let mut writes = Vec::with_capacity(values.len());
+    for (address, value) in addresses(start, values.len() as u16)?.zip(values) {
+        let entry = lookup(shared, ModbusTable::Coils, address)?;
+        let (byte, bit) = entry.bit_location(address);
+        writes.push(PendingWrite {
+            area: entry.area,
+            byte,
+            mask: 1 << bit,
+            value: if *value { 1 << bit } else { 0 },
+        });
+    }
+    apply_writes(shared, writes)
+}
+
+fn write_registers(shared: &ServerShared, start: u16, data: &[u8]) -> Result<(), u8> {
+    let qty = (data.len() / 2) as u16;
+    let mut writes = Vec::with_capacity(data.len());
+    for (idx, address) in addresses(start, qty)?.enumerate() {
+        let entry = lookup(shared, ModbusTable::HoldingRegisters, address)?;
+        let offset = entry.register_offset(address);
+        let [lo, hi] = u16::from_be_bytes([data[idx * 2], data[idx * 2 + 1]]).to_le_bytes();
+        for (byte, value) in [(offset, lo), (offset + 1, hi)] {
+            writes.push(PendingWrite {
+                area: entry.area,
+                byte,
+                mask: 0xFF,
+                value,
+            });
+        }
+    }
+    apply_writes(shared, writes)
+}
+
+fn apply_writes(shared: &ServerShared, writes: Vec<PendingWrite>) -> Result<(), u8> {
+    let mut image = shared
+        .image
+        .lock()
+        .unwrap_or_else(|poison| poison.into_inner());
+    if writes
+        .iter()
+        .any(|write| write.byte >= image.area(write.area).len())
+    {
+        return Err(EXCEPTION_ILLEGAL_ADDRESS);
+    }
+    for write in writes {
+        image.write(write);
+    }
+    Ok(())
+}
+
+fn write_single_coil(shared: &ServerShared, pdu: &[u8]) -> Result<Vec<u8>, u8> {
+    let address = field(pdu, 1)?;
+    let value = match field(pdu, 3)? {
+        0xFF00 => true,
+        0x0000 => false,
+        _ => return Err(EXCEPTION_ILLEGAL_VALUE),
+    };
+    write_bits(shared, address, &[value])?;
+    Ok(pdu[..5].to_vec())
+}
+
+fn write_single_register(shared: &ServerShared, pdu: &[u8]) -> Result<Vec<u8>, u8> {
+    let address = field(pdu, 1)?;
+    let value = field(pdu, 3)?;
+    write_registers(shared, address, &value.to_be_bytes())?;
+    Ok(pdu[..5].to_vec())
+}
+
+fn write_multiple_coils(shared: &ServerShared, pdu: &[u8]) -> Result<Vec<u8>, u8> {
+    let start = field(pdu, 1)?;
+    let qty = field(pdu, 3)?;
+    let byte_count = usize::from(*pdu.get(5).ok_or(EXCEPTION_ILLEGAL_VALUE)?);
+    if !(1..=1968).contains(&qty) || byte_count != usize::from(qty).div_ceil(8) {
+        return Err(EXCEPTION_ILLEGAL_VALUE);
+    }
+    let data = pdu.get(6..6 + byte_count).ok_or(EXCEPTION_ILLEGAL_VALUE)?;
+    let values: Vec<bool> = (0..usize::from(qty))
+        .map(|idx| data[idx / 8] & (1 << (idx % 8)) != 0)
+        .collect();
+    write_bits(shared, start, &values)?;
+    Ok(pdu[..5].to_vec())
+}
+
+fn write_multiple_registers(shared: &ServerShared, pdu: &[u8]) -> Result<Vec<u8>, u8> {
+    let start = field(pdu, 1)?;
+    let qty = field(pdu, 3)?;
+    let byte_count = usize::from(*pdu.get(5).ok_or(EXCEPTION_ILLEGAL_VALUE)?);
+    if !(1..=123).contains(&qty) || byte_count != usize::from(qty) * 2 {
+        return Err(EXCEPTION_ILLEGAL_VALUE);
+    }
+    let data = pdu.get(6..6 + byte_count).ok_or(EXCEPTION_ILLEGAL_VALUE)?;
+    write_registers(shared, start, data)?;
+    Ok(pdu[..5].to_vec())
+}
@@ -2,33 +2,52 @@
 
 #![allow(missing_docs)]
 
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration as StdDuration, Instant};
 
-use rumqttc::{Client, Event, MqttOptions, Packet, QoS};
+use rumqttc::{Client, Event, LastWill, MqttOptions, Packet, QoS, SubscribeFilter, Transport};
 use serde::Deserialize;
 use smol_str::SmolStr;
+use trust_hir::TypeId;
 
+use super::mapping::{
+    bind_variable, check_mapped_address, parse_direction, parse_value_type, read_scalar,
+    resolve_value_type, width, write_scalar, NamedVariable, Scalar,
+};
 use crate::error::RuntimeError;
-use crate::io::{IoDriver, IoDriverHealth};
+use crate::io::{IoAddress, IoArea, IoBinding, IoDriver, IoDriverHealth, IoSize};
+use crate::memory::VariableStorage;
 
 #[derive(Debug, Clone)]
 struct BrokerEndpoint {
     host: SmolStr,
     port: u16,
+    tls: bool,
 }
 
 #[derive(Debug, Clone)]
-struct MqttIoConfig {
+pub(super) struct MqttIoConfig {
     endpoint: BrokerEndpoint,
     client_id: SmolStr,
-    topic_in: SmolStr,
-    topic_out: SmolStr,
+    topic_in: Option<SmolStr>,
+    topic_out: Option<SmolStr>,
     username: Option<SmolStr>,
     password: Option<SmolStr>,
-    reconnect: StdDuration,
+    pub(super) reconnect: StdDuration,
+    tls: Option<crate::security::TlsClientPem>,
+    topics: Vec<TopicMapping>,
+    /// Last will registered with the broker on connect.
+    pub(super) will: Option<MqttWill>,
+}
+
+/// Message the broker publishes when the session drops without a disconnect.
+#[derive(Debug, Clone)]
+pub(super) struct MqttWill {
+    pub(super) topic: SmolStr,
+    pub(super) payload: Vec<u8>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -42,27 +61,81 @@ struct MqttToml {
     reconnect_ms: Option<u64>,
     keep_alive_s: Option<u64>,
     tls: Option<bool>,
+    ca_path: Option<PathBuf>,
+    client_cert_path: Option<PathBuf>,
+    client_key_path: Option<PathBuf>,
     allow_insecure_remote: Option<bool>,
+    topics: Option<Vec<TopicToml>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicToml {
+    topic: String,
+    address: Option<String>,
+    variable: Option<String>,
+    direction: Option<String>,
+    #[serde(rename = "type")]
+    value_type: Option<String>,
+    encoding: Option<String>,
+    qos: Option<u8>,
+    retain: Option<bool>,
+    deadband: Option<f64>,
+}
+
+/// Payload format of a mapped topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TopicEncoding {
+    /// JSON scalar (`true`, `42`, `21.5`).
+    Json,
+    /// The value's bytes as they sit in the process image (little-endian).
+    Raw,
+}
+
+#[derive(Debug, Clone)]
+enum TopicTarget {
+    Address(IoAddress),
+    /// ST variable: through its `AT` binding, else by name with the
+    /// configured direction.
+    Variable {
+        name: SmolStr,
+        direction: Option<IoArea>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct TopicMapping {
+    topic: SmolStr,
+    target: TopicTarget,
+    value_type: Option<TypeId>,
+    encoding: TopicEncoding,
+    qos: QoS,
+    retain: bool,
+    deadband: f64,
 }
 
 impl MqttIoConfig {
-    fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
+    pub(super) fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
         let params: MqttToml = value
             .clone()
             .try_into()
             .map_err(|err| RuntimeError::InvalidConfig(format!("io.params: {err}").into()))?;
         let endpoint = parse_broker_endpoint(&params.broker)?;
-        let tls = params.tls.unwrap_or(false);
-        if tls {
+        let tls = params.tls.unwrap_or(endpoint.tls);
+        if !tls
+            && (params.ca_path.is_some()
+                || params.client_cert_path.is_some()
+                || params.client_key_path.is_some())
+        {
             return Err(RuntimeError::InvalidConfig(
-                "mqtt tls=true is not yet supported (set tls=false for now)".into(),
+                "mqtt ca_path/client_cert_path/client_key_path require tls=true".into(),
             ));
         }
+        let tls = if tls { Some(load_tls(&params)?) } else { None };
         let allow_insecure_remote = params.allow_insecure_remote.unwrap_or(false);
-        if !allow_insecure_remote && !is_local_host(endpoint.host.as_str()) {
+        if tls.is_none() && !allow_insecure_remote && !is_local_host(endpoint.host.as_str()) {
             return Err(RuntimeError::InvalidConfig(
                 format!(
-                    "mqtt insecure remote broker '{}' requires allow_insecure_remote=true",
+                    "mqtt insecure remote broker '{}' requires tls=true or allow_insecure_remote=true",
                     endpoint.host
                 )
                 .into(),
@@ -79,14 +152,24 @@ impl MqttIoConfig {
             .client_id
             .map(SmolStr::new)
             .unwrap_or_else(|| SmolStr::new(format!("trust-runtime-{}", std::process::id())));
+        let topics = params
+            .topics
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, entry)| TopicMapping::from_toml(idx, entry))
+            .collect::<Result<Vec<_>, _>>()?;
+        // A mapping table replaces the whole-image topics unless they are
+        // configured explicitly.
+        let image_default = |name: &str| topics.is_empty().then(|| SmolStr::new(name));
         let topic_in = params
             .topic_in
             .map(SmolStr::new)
-            .unwrap_or_else(|| SmolStr::new("trust/io/in"));
+            .or_else(|| image_default("trust/io/in"));
         let topic_out = params
             .topic_out
             .map(SmolStr::new)
-            .unwrap_or_else(|| SmolStr::new("trust/io/out"));
+            .or_else(|| image_default("trust/io/out"));
         let reconnect = StdDuration::from_millis(params.reconnect_ms.unwrap_or(500).max(1));
         let keep_alive_s = params.keep_alive_s.unwrap_or(5).max(1);
         if keep_alive_s > u16::MAX.into() {
@@ -103,34 +186,308 @@ impl MqttIoConfig {
             username,
             password,
             reconnect,
+            tls,
+            topics,
+            will: None,
+        })
+    }
+}
+
+fn load_tls(params: &MqttToml) -> Result<crate::security::TlsClientPem, RuntimeError> {
+    let ca_path = params.ca_path.as_deref().ok_or_else(|| {
+        RuntimeError::InvalidConfig("mqtt tls=true requires io.params.ca_path".into())
+    })?;
+    let client = match (&params.client_cert_path, &params.client_key_path) {
+        (Some(cert), Some(key)) => Some((cert.as_path(), key.as_path())),
+        (None, None) => None,
+        _ => {
+            return Err(RuntimeError::InvalidConfig(
+                "mqtt client_cert_path/client_key_path must be set together".into(),
+            ))
+        }
+    };
+    crate::security::load_tls_client_pem(ca_path, client)
+        .map_err(|err| RuntimeError::InvalidConfig(format!("io.params: {err}").into()))
+}
+
+impl TopicMapping {
+    fn from_toml(idx: usize, entry: TopicToml) -> Result<Self, RuntimeError> {
+        let field = |name: &str| format!("io.params.topics[{idx}].{name}");
+        let topic = entry.topic.trim();
+        if topic.is_empty() || topic.contains(['+', '#']) {
+            return Err(RuntimeError::InvalidConfig(
+                format!("{} must be a topic name without wildcards", field("topic")).into(),
+            ));
+        }
+        let value_type = entry
+            .value_type
+            .as_deref()
+            .map(|name| parse_value_type(name, &field("type")))
+            .transpose()?;
+        let direction = entry
+            .direction
+            .as_deref()
+            .map(|text| parse_direction(text, &field("direction")))
+            .transpose()?;
+        let target = match (entry.address, entry.variable) {
+            (Some(_), None) if direction.is_some() => {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("{} only applies to variable mappings", field("direction")).into(),
+                ))
+            }
+            (Some(address), None) => {
+                let address = IoAddress::parse(&address)?;
+                check_mapped_address(&address, value_type, &field("address"))?;
+                TopicTarget::Address(address)
+            }
+            (None, Some(variable)) if !variable.trim().is_empty() => TopicTarget::Variable {
+                name: SmolStr::new(variable.trim()),
+                direction,
+            },
+            _ => {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("io.params.topics[{idx}] needs exactly one of address or variable")
+                        .into(),
+                ))
+            }
+        };
+        let encoding = match entry
+            .encoding
+            .as_deref()
+            .map(|text| text.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("json") => TopicEncoding::Json,
+            Some("raw") => TopicEncoding::Raw,
+            Some(other) => {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("{} '{other}' must be json or raw", field("encoding")).into(),
+                ))
+            }
+        };
+        let qos = match entry.qos.unwrap_or(0) {
+            0 => QoS::AtMostOnce,
+            1 => QoS::AtLeastOnce,
+            2 => QoS::ExactlyOnce,
+            other => {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("{} {other} must be 0, 1 or 2", field("qos")).into(),
+                ))
+            }
+        };
+        let deadband = entry.deadband.unwrap_or(0.0);
+        if !deadband.is_finite() || deadband < 0.0 {
+            return Err(RuntimeError::InvalidConfig(
+                format!("{} must be >= 0", field("deadband")).into(),
+            ));
+        }
+        let mapping = Self {
+            topic: SmolStr::new(topic),
+            target,
+            value_type,
+            encoding,
+            qos,
+            retain: entry.retain.unwrap_or(false),
+            deadband,
+        };
+        if let TopicTarget::Address(address) = &mapping.target {
+            mapping.check_direction(address.area)?;
+        }
+        Ok(mapping)
+    }
+
+    /// `retain` and `deadband` only apply to published (output) topics.
+    fn check_direction(&self, area: IoArea) -> Result<(), RuntimeError> {
+        if area == IoArea::Input && (self.retain || self.deadband > 0.0) {
+            return Err(RuntimeError::InvalidConfig(
+                format!(
+                    "mqtt topic '{}' is subscribed (%I); retain and deadband apply to %Q topics",
+                    self.topic
+                )
+                .into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bind the mapping to a concrete image location.
+    fn resolve(
+        &self,
+        bindings: &[IoBinding],
+        storage: &VariableStorage,
+    ) -> Result<TopicSlot, RuntimeError> {
+        let (address, bound_type, named) = match &self.target {
+            TopicTarget::Address(address) => (address.clone(), None, None),
+            TopicTarget::Variable { name, direction } => {
+                let bound = bind_variable(name, bindings, storage, self.value_type, *direction)
+                    .map_err(|err| {
+                        RuntimeError::IoDriver(format!("mqtt topic '{}': {err}", self.topic).into())
+                    })?;
+                self.check_direction(bound.address.area)?;
+                (bound.address, bound.value_type, bound.named)
+            }
+        };
+        let value_type = resolve_value_type(&address, self.value_type, bound_type);
+        Ok(TopicSlot {
+            named,
+            topic: self.topic.clone(),
+            address,
+            value_type,
+            encoding: self.encoding,
+            qos: self.qos,
+            retain: self.retain,
+            deadband: self.deadband,
+            value: None,
         })
     }
 }
 
-trait MqttSession: Send {
+/// A mapped topic bound to its place in the process image.
+#[derive(Debug, Clone)]
+struct TopicSlot {
+    /// Set for variables exchanged by name instead of through the image.
+    named: Option<NamedVariable>,
+    topic: SmolStr,
+    address: IoAddress,
+    value_type: TypeId,
+    encoding: TopicEncoding,
+    qos: QoS,
+    retain: bool,
+    deadband: f64,
+    /// Last received value (inputs) or last published value (outputs).
+    value: Option<Scalar>,
+}
+
+impl TopicSlot {
+    fn width(&self) -> usize {
+        width(&self.address)
+    }
+
+    fn read(&self, image: &[u8]) -> Scalar {
+        read_scalar(image, &self.address, self.value_type)
+    }
+
+    fn write(&self, image: &mut [u8], value: Scalar) -> Result<(), RuntimeError> {
+        write_scalar(image, &self.address, self.value_type, value).map_err(|err| self.error(err))
+    }
+
+    fn encode(&self, value: Scalar) -> Vec<u8> {
+        match self.encoding {
+            TopicEncoding::Json => {
+                let json = match value {
+                    Scalar::Bool(flag) => serde_json::Value::from(flag),
+                    Scalar::Int(value) => serde_json::Value::from(value),
+                    Scalar::UInt(value) => serde_json::Value::from(value),
+                    Scalar::Real(value) => serde_json::Value::from(value),
+                };
+                json.to_string().into_bytes()
+            }
+            TopicEncoding::Raw if self.address.size == IoSize::Bit => {
+                vec![u8::from(value == Scalar::Bool(true))]
+            }
+            TopicEncoding::Raw => {
+                let mut image = vec![0u8; self.address.byte as usize + self.width()];
+                let _ = self.write(&mut image, value);
+                image.split_off(self.address.byte as usize)
+            }
+        }
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Scalar, RuntimeError> {
+        match self.encoding {
+            TopicEncoding::Json => {
+                let json: serde_json::Value = serde_json::from_slice(payload)
+                    .map_err(|err| self.error(format!("invalid JSON payload: {err}")))?;
+                match json {
+                    serde_json::Value::Bool(flag) => Ok(Scalar::Bool(flag)),
+                    serde_json::Value::Number(number) => number
+                        .as_u64()
+                        .map(Scalar::UInt)
+                        .or_else(|| number.as_i64().map(Scalar::Int))
+                        .or_else(|| number.as_f64().map(Scalar::Real))
+                        .ok_or_else(|| self.error("unsupported number")),
+                    _ => Err(self.error("payload must be a JSON boolean or number")),
+                }
+            }
+            TopicEncoding::Raw => {
+                if payload.len() != self.width() {
+                    return Err(self.error(format!(
+                        "raw payload must be {} byte(s), got {}",
+                        self.width(),
+                        payload.len()
+                    )));
+                }
+                if self.address.size == IoSize::Bit {
+                    return Ok(Scalar::Bool(payload[0] != 0));
+                }
+                let mut image = vec![0u8; self.address.byte as usize];
+                image.extend_from_slice(payload);
+                Ok(self.read(&image))
+            }
+        }
+    }
+
+    /// Whether an output moved far enough from the last published value.
+    fn changed(&self, value: Scalar) -> bool {
+        match self.value {
+            None => true,
+            Some(last) if self.deadband > 0.0 => {
+                (value.as_f64() - last.as_f64()).abs() > self.deadband
+            }
+            Some(last) => last != value,
+        }
+    }
+
+    fn error(&self, message: impl AsRef<str>) -> RuntimeError {
+        RuntimeError::IoDriver(format!("mqtt topic '{}': {}", self.topic, message.as_ref()).into())
+    }
+}
+
+/// A received message: topic and payload.
+pub(super) type MqttMessage = (SmolStr, Vec<u8>);
+
+pub(super) trait MqttSession: Send {
     fn is_connected(&self) -> bool;
-    fn take_payload(&mut self) -> Option<Vec<u8>>;
-    fn publish(&mut self, topic: &str, payload: &[u8]) -> Result<(), RuntimeError>;
+    /// Messages received since the last call, oldest first.
+    fn take_messages(&mut self) -> Vec<MqttMessage>;
+    fn publish(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+        qos: QoS,
+        retain: bool,
+    ) -> Result<(), RuntimeError>;
     fn last_error(&self) -> Option<SmolStr>;
 }
 
-trait MqttSessionFactory: Send + Sync {
-    fn connect(&self, config: &MqttIoConfig) -> Result<Box<dyn MqttSession>, RuntimeError>;
+pub(super) trait MqttSessionFactory: Send + Sync {
+    fn connect(
+        &self,
+        config: &MqttIoConfig,
+        subscriptions: &[(SmolStr, QoS)],
+    ) -> Result<Box<dyn MqttSession>, RuntimeError>;
 }
 
 #[derive(Debug, Default)]
-struct RumqttSessionFactory;
+pub(super) struct RumqttSessionFactory;
 
 struct RumqttSession {
     client: Client,
-    incoming: Arc<Mutex<Option<Vec<u8>>>>,
+    incoming: Arc<Mutex<Vec<MqttMessage>>>,
     connected: Arc<AtomicBool>,
     last_error: Arc<Mutex<Option<SmolStr>>>,
     _worker: thread::JoinHandle<()>,
 }
 
+/// Upper bound on buffered messages between two cycles; older ones are dropped.
+const MAX_PENDING_MESSAGES: usize = 1024;
+
 impl MqttSessionFactory for RumqttSessionFactory {
-    fn connect(&self, config: &MqttIoConfig) -> Result<Box<dyn MqttSession>, RuntimeError> {
+    fn connect(
+        &self,
+        config: &MqttIoConfig,
+        subscriptions: &[(SmolStr, QoS)],
+    ) -> Result<Box<dyn MqttSession>, RuntimeError> {
         let mut options = MqttOptions::new(
             config.client_id.as_str(),
             config.endpoint.host.as_str(),
@@ -140,18 +497,42 @@ impl MqttSessionFactory for RumqttSessionFactory {
         if let (Some(username), Some(password)) = (&config.username, &config.password) {
             options.set_credentials(username.as_str(), password.as_str());
         }
+        if let Some(will) = &config.will {
+            options.set_last_will(LastWill::new(
+                will.topic.as_str(),
+                will.payload.clone(),
+                QoS::AtLeastOnce,
+                false,
+            ));
+        }
+        if let Some(tls) = &config.tls {
+            options.set_transport(Transport::tls(
+                tls.ca_pem.clone(),
+                tls.client_auth.clone(),
+                None,
+            ));
+        }
         let (client, mut connection) = Client::new(options, 64);
-        client
-            .subscribe(config.topic_in.as_str(), QoS::AtMostOnce)
-            .map_err(|err| RuntimeError::IoDriver(format!("mqtt subscribe: {err}").into()))?;
+        if !subscriptions.is_empty() {
+            client
+                .subscribe_many(
+                    subscriptions
+                        .iter()
+                        .map(|(topic, qos)| SubscribeFilter::new(topic.to_string(), *qos)),
+                )
+                .map_err(|err| RuntimeError::IoDriver(format!("mqtt subscribe: {err}").into()))?;
+        }
 
-        let incoming = Arc::new(Mutex::new(None));
+        let incoming = Arc::new(Mutex::new(Vec::new()));
         let connected = Arc::new(AtomicBool::new(false));
         let last_error = Arc::new(Mutex::new(None));
         let incoming_ref = Arc::clone(&incoming);
         let connected_ref = Arc::clone(&connected);
         let last_error_ref = Arc::clone(&last_error);
-        let topic_in = config.topic_in.clone();
+        let topics = subscriptions
+            .iter()
+            .map(|(topic, _)| topic.clone())
+            .collect::<Vec<_>>();
         let worker = thread::spawn(move || {
             for event in connection.iter() {
                 match event {
@@ -162,9 +543,12 @@ impl MqttSessionFactory for RumqttSessionFactory {
                     }
                     Ok(Event::Incoming(Packet::Publish(publish))) => {
                         connected_ref.store(true, Ordering::SeqCst);
-                        if publish.topic == topic_in {
+                        if topics.iter().any(|topic| *topic == publish.topic) {
                             let mut guard = incoming_ref.lock().unwrap_or_else(|e| e.into_inner());
-                            *guard = Some(publish.payload.to_vec());
+                            if guard.len() >= MAX_PENDING_MESSAGES {
+                                guard.remove(0);
+                            }
+                            guard.push((SmolStr::new(&publish.topic), publish.payload.to_vec()));
                         }
                     }
                     Ok(_) => {}
@@ -198,14 +582,20 @@ impl MqttSession for RumqttSession {
         self.connected.load(Ordering::SeqCst)
     }
 
-    fn take_payload(&mut self) -> Option<Vec<u8>> {
+    fn take_messages(&mut self) -> Vec<MqttMessage> {
         let mut guard = self.incoming.lock().unwrap_or_else(|e| e.into_inner());
-        guard.take()
+        std::mem::take(&mut *guard)
     }
 
-    fn publish(&mut self, topic: &str, payload: &[u8]) -> Result<(), RuntimeError> {
+    fn publish(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+        qos: QoS,
+        retain: bool,
+    ) -> Result<(), RuntimeError> {
         self.client
-            .publish(topic, QoS::AtMostOnce, false, payload.to_vec())
+            .publish(topic, qos, retain, payload.to_vec())
             .map_err(|err| RuntimeError::IoDriver(format!("mqtt publish: {err}").into()))
     }
 
@@ -221,6 +611,10 @@ pub struct MqttIoDriver {
     session: Option<Box<dyn MqttSession>>,
     health: IoDriverHealth,
     next_reconnect: Instant,
+    inputs: Vec<TopicSlot>,
+    outputs: Vec<TopicSlot>,
+    /// Set when a variable mapping could not be resolved against the program.
+    unresolved: Option<RuntimeError>,
 }
 
 impl MqttIoDriver {
@@ -233,7 +627,7 @@ impl MqttIoDriver {
         factory: Arc<dyn MqttSessionFactory>,
     ) -> Result<Self, RuntimeError> {
         let config = MqttIoConfig::from_params(value)?;
-        Ok(Self {
+        let mut driver = Self {
             config,
             factory,
             session: None,
@@ -241,7 +635,14 @@ impl MqttIoDriver {
                 error: SmolStr::new("mqtt initializing"),
             },
             next_reconnect: Instant::now(),
-        })
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            unresolved: None,
+        };
+        // Address mappings resolve now; variable mappings once the runtime
+        // registers the driver with its bindings.
+        driver.bind_variables(&[], &VariableStorage::new());
+        Ok(driver)
     }
 
     pub fn validate_params(value: &toml::Value) -> Result<(), RuntimeError> {
@@ -255,6 +656,19 @@ impl MqttIoDriver {
         };
     }
 
+    fn subscriptions(&self) -> Vec<(SmolStr, QoS)> {
+        self.config
+            .topic_in
+            .iter()
+            .map(|topic| (topic.clone(), QoS::AtMostOnce))
+            .chain(
+                self.inputs
+                    .iter()
+                    .map(|slot| (slot.topic.clone(), slot.qos)),
+            )
+            .collect()
+    }
+
     fn ensure_session(&mut self) {
         let now = Instant::now();
         if let Some(session) = self.session.as_mut() {
@@ -277,9 +691,13 @@ impl MqttIoDriver {
         if now < self.next_reconnect {
             return;
         }
-        match self.factory.connect(&self.config) {
+        match self.factory.connect(&self.config, &self.subscriptions()) {
             Ok(session) => {
                 self.session = Some(session);
+                // Republish every mapped output on the new connection.
+                for slot in &mut self.outputs {
+                    slot.value = None;
+                }
                 self.set_degraded("mqtt connecting");
             }
             Err(err) => {
@@ -289,50 +707,164 @@ impl MqttIoDriver {
             }
         }
     }
+
+    fn drop_session(&mut self, err: RuntimeError) {
+        self.set_degraded(err.to_string());
+        self.session = None;
+        self.next_reconnect = Instant::now() + self.config.reconnect;
+    }
 }
 
 impl IoDriver for MqttIoDriver {
     fn read_inputs(&mut self, inputs: &mut [u8]) -> Result<(), RuntimeError> {
+        if let Some(err) = &self.unresolved {
+            return Err(err.clone());
+        }
         self.ensure_session();
+        let mut payload_error = None;
         if let Some(session) = self.session.as_mut() {
-            if let Some(payload) = session.take_payload() {
-                inputs.fill(0);
-                for (dst, src) in inputs.iter_mut().zip(payload.iter()) {
-                    *dst = *src;
+            for (topic, payload) in session.take_messages() {
+                if self.config.topic_in.as_ref() == Some(&topic) {
+                    inputs.fill(0);
+                    for (dst, src) in inputs.iter_mut().zip(payload.iter()) {
+                        *dst = *src;
+                    }
+                }
+                for slot in self.inputs.iter_mut().filter(|slot| slot.topic == topic) {
+                    match slot.decode(&payload) {
+                        Ok(value) => slot.value = Some(value),
+                        Err(err) => payload_error = Some(err),
+                    }
                 }
             }
             if session.is_connected() {
                 self.health = IoDriverHealth::Ok;
             }
         }
+        // Mapped inputs hold their last received value every cycle.
+        for slot in &mut self.inputs {
+            let Some(value) = slot.value else {
+                continue;
+            };
+            let image = match slot.named.as_mut() {
+                Some(named) => named.image_mut(),
+                None => &mut *inputs,
+            };
+            if let Err(err) = write_scalar(image, &slot.address, slot.value_type, value) {
+                payload_error = Some(slot.error(err));
+            }
+        }
+        if let Some(err) = payload_error {
+            self.set_degraded(err.to_string());
+        }
         Ok(())
     }
 
     fn write_outputs(&mut self, outputs: &[u8]) -> Result<(), RuntimeError> {
+        if let Some(err) = &self.unresolved {
+            return Err(err.clone());
+        }
         self.ensure_session();
-        if let Some(session) = self.session.as_mut() {
-            if let Err(err) = session.publish(self.config.topic_out.as_str(), outputs) {
-                self.set_degraded(err.to_string());
-                self.session = None;
-                self.next_reconnect = Instant::now() + self.config.reconnect;
-            } else if session.is_connected() {
-                self.health = IoDriverHealth::Ok;
+        let Some(session) = self.session.as_mut() else {
+            return Ok(());
+        };
+        let mut result = Ok(());
+        if let Some(topic) = &self.config.topic_out {
+            result = session.publish(topic.as_str(), outputs, QoS::AtMostOnce, false);
+        }
+        for slot in &mut self.outputs {
+            if result.is_err() {
+                break;
+            }
+            let value = slot.read(slot.named.as_ref().map_or(outputs, NamedVariable::image));
+            if !slot.changed(value) {
+                continue;
+            }
+            result = session.publish(
+                slot.topic.as_str(),
+                &slot.encode(value),
+                slot.qos,
+                slot.retain,
+            );
+            if result.is_ok() {
+                slot.value = Some(value);
             }
         }
+        match result {
+            Err(err) => self.drop_session(err),
+            Ok(()) if session.is_connected() => self.health = IoDriverHealth::Ok,
+            Ok(()) => {}
+        }
         Ok(())
     }
 
     fn health(&self) -> IoDriverHealth {
         self.health.clone()
     }
+
+    fn read_variables(&mut self, storage: &mut VariableStorage) -> Result<(), RuntimeError> {
+        let mut error = None;
+        for slot in self.inputs.iter().filter(|slot| slot.value.is_some()) {
+            if let Some(named) = &slot.named {
+                if let Err(err) = named.store(storage, &slot.address, slot.value_type) {
+                    error = Some(slot.error(err));
+                }
+            }
+        }
+        if let Some(err) = error {
+            self.set_degraded(err.to_string());
+        }
+        Ok(())
+    }
+
+    fn write_variables(&mut self, storage: &VariableStorage) -> Result<(), RuntimeError> {
+        let mut error = None;
+        for slot in &mut self.outputs {
+            if let Some(named) = slot.named.as_mut() {
+                if let Err(err) = named.load(storage, &slot.address, slot.value_type) {
+                    error = Some(slot.error(err));
+                }
+            }
+        }
+        if let Some(err) = error {
+            self.set_degraded(err.to_string());
+        }
+        Ok(())
+    }
+
+    fn bind_variables(&mut self, bindings: &[IoBinding], storage: &VariableStorage) {
+        self.inputs.clear();
+        self.outputs.clear();
+        self.unresolved = None;
+        for mapping in &self.config.topics {
+            match mapping.resolve(bindings, storage) {
+                Ok(slot) if slot.address.area == IoArea::Input => self.inputs.push(slot),
+                Ok(slot) => self.outputs.push(slot),
+                Err(err) => {
+                    self.unresolved.get_or_insert(err);
+                }
+            }
+        }
+        // Subscriptions may have changed; reconnect with the new set.
+        self.session = None;
+        self.next_reconnect = Instant::now();
+    }
 }
 
 fn parse_broker_endpoint(text: &str) -> Result<BrokerEndpoint, RuntimeError> {
     let trimmed = text.trim();
-    let stripped = trimmed
-        .strip_prefix("tcp://")
-        .or_else(|| trimmed.strip_prefix("mqtt://"))
-        .unwrap_or(trimmed);
+    let (stripped, tls) = if let Some(rest) = trimmed
+        .strip_prefix("mqtts://")
+        .or_else(|| trimmed.strip_prefix("ssl://"))
+    {
+        (rest, true)
+    } else {
+        let rest = trimmed
+            .strip_prefix("tcp://")
+            .or_else(|| trimmed.strip_prefix("mqtt://"))
+            .unwrap_or(trimmed);
+        (rest, false)
+    };
     if let Some(rest) = stripped.strip_prefix('[') {
         let (host, port) = rest.split_once("]:").ok_or_else(|| {
             RuntimeError::InvalidConfig(
@@ -342,6 +874,7 @@ fn parse_broker_endpoint(text: &str) -> Result<BrokerEndpoint, RuntimeError> {
         return Ok(BrokerEndpoint {
             host: SmolStr::new(host),
             port: parse_port(port, text)?,
+            tls,
         });
     }
     let (host, port) = stripped.rsplit_once(':').ok_or_else(|| {
@@ -355,6 +888,7 @@ fn parse_broker_endpoint(text: &str) -> Result<BrokerEndpoint, RuntimeError> {
     Ok(BrokerEndpoint {
         host: SmolStr::new(host.trim()),
         port: parse_port(port, text)?,
+        tls,
     })
 }
 
@@ -379,6 +913,8 @@ fn is_local_host(host: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::io::IoTarget;
+    use crate::value::Value;
     use std::collections::VecDeque;
     use std::sync::atomic::AtomicUsize;
 
@@ -386,8 +922,10 @@ mod tests {
     struct MockState {
         connected: bool,
         last_error: Option<SmolStr>,
-        payloads: VecDeque<Vec<u8>>,
-        published: Vec<Vec<u8>>,
+        payloads: VecDeque<(SmolStr, Vec<u8>)>,
+        published: Vec<(SmolStr, Vec<u8>)>,
+        retained: Vec<SmolStr>,
+        subscriptions: Vec<(SmolStr, QoS)>,
         fail_publish_once: bool,
     }
 
@@ -401,19 +939,30 @@ mod tests {
             guard.connected
         }
 
-        fn take_payload(&mut self) -> Option<Vec<u8>> {
+        fn take_messages(&mut self) -> Vec<MqttMessage> {
             let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
-            guard.payloads.pop_front()
+            guard.payloads.drain(..).collect()
         }
 
-        fn publish(&mut self, _topic: &str, payload: &[u8]) -> Result<(), RuntimeError> {
+        fn publish(
+            &mut self,
+            topic: &str,
+            payload: &[u8],
+            _qos: QoS,
+            retain: bool,
+        ) -> Result<(), RuntimeError> {
             let mut guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
             if guard.fail_publish_once {
                 guard.fail_publish_once = false;
                 guard.last_error = Some(SmolStr::new("publish failed"));
                 return Err(RuntimeError::IoDriver("publish failed".into()));
             }
-            guard.published.push(payload.to_vec());
+            guard
+                .published
+                .push((SmolStr::new(topic), payload.to_vec()));
+            if retain {
+                guard.retained.push(SmolStr::new(topic));
+            }
             Ok(())
         }
 
@@ -431,11 +980,19 @@ mod tests {
     }
 
     impl MqttSessionFactory for MockFactory {
-        fn connect(&self, _config: &MqttIoConfig) -> Result<Box<dyn MqttSession>, RuntimeError> {
+        fn connect(
+            &self,
+            _config: &MqttIoConfig,
+            subscriptions: &[(SmolStr, QoS)],
+        ) -> Result<Box<dyn MqttSession>, RuntimeError> {
             let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
             if self.always_fail || (self.fail_first && attempt == 0) {
                 return Err(RuntimeError::IoDriver("connect failed".into()));
             }
+            self.state
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .subscriptions = subscriptions.to_vec();
             Ok(Box::new(MockSession {
                 state: Arc::clone(&self.state),
             }))
@@ -446,11 +1003,20 @@ mod tests {
         toml::from_str(text).expect("parse toml params")
     }
 
+    fn connected_factory(state: &Arc<Mutex<MockState>>) -> Arc<MockFactory> {
+        Arc::new(MockFactory {
+            state: Arc::clone(state),
+            attempts: Arc::new(AtomicUsize::new(0)),
+            fail_first: false,
+            always_fail: false,
+        })
+    }
+
     #[test]
     fn contract_test_reads_and_writes_payloads() {
         let state = Arc::new(Mutex::new(MockState {
             connected: true,
-            payloads: VecDeque::from([vec![1, 0, 1]]),
+            payloads: VecDeque::from([(SmolStr::new("line/in"), vec![1, 0, 1])]),
             ..MockState::default()
         }));
         let attempts = Arc::new(AtomicUsize::new(0));
@@ -480,7 +1046,267 @@ topic_out = "line/out"
         assert!(matches!(driver.health(), IoDriverHealth::Ok));
 
         let guard = state.lock().unwrap_or_else(|e| e.into_inner());
-        assert_eq!(guard.published, vec![vec![9, 8, 7]]);
+        assert_eq!(
+            guard.published,
+            vec![(SmolStr::new("line/out"), vec![9, 8, 7])]
+        );
+    }
+
+    #[test]
+    fn mapped_topics_decode_inputs_and_publish_outputs_on_change() {
+        let state = Arc::new(Mutex::new(MockState {
+            connected: true,
+            payloads: VecDeque::from([
+                (SmolStr::new("plant/run"), b"true".to_vec()),
+                (SmolStr::new("plant/level"), vec![0xfe, 0xff]),
+            ]),
+            ..MockState::default()
+        }));
+        let mut driver = MqttIoDriver::from_params_with_factory(
+            &params(
+                r#"
+broker = "127.0.0.1:1883"
+
+[[topics]]
+topic = "plant/run"
+address = "%IX0.1"
+
+[[topics]]
+topic = "plant/level"
+address = "%IW2"
+type = "INT"
+encoding = "raw"
+qos = 1
+
+[[topics]]
+topic = "plant/speed"
+address = "%QD0"
+type = "REAL"
+retain = true
+deadband = 0.5
+"#,
+            ),
+            connected_factory(&state),
+        )
+        .expect("construct mqtt driver");
+
+        let mut inputs = [0u8; 4];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert_eq!(inputs, [0b10, 0, 0xfe, 0xff]);
+        // Mapped inputs keep their value until the next message.
+        inputs.fill(0);
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert_eq!(inputs, [0b10, 0, 0xfe, 0xff]);
+
+        for speed in [10.0f32, 10.3, 10.6, 10.6] {
+            driver
+                .write_outputs(&speed.to_le_bytes())
+                .expect("write outputs");
+        }
+        assert!(matches!(driver.health(), IoDriverHealth::Ok));
+
+        let guard = state.lock().unwrap_or_else(|e| e.into_inner());
+        assert_eq!(
+            guard.subscriptions,
+            vec![
+                (SmolStr::new("plant/run"), QoS::AtMostOnce),
+                (SmolStr::new("plant/level"), QoS::AtLeastOnce),
+            ]
+        );
+        let published = guard
+            .published
+            .iter()
+            .map(|(topic, payload)| (topic.as_str(), String::from_utf8_lossy(payload)))
+            .collect::<Vec<_>>();
+        assert_eq!(published.len(), 2, "{published:?}");
+        assert_eq!(published[0].0, "plant/speed");
+        assert_eq!(published[0].1, "10.0");
+        assert!(published[1].1.starts_with("10.6"), "{published:?}");
+        assert_eq!(guard.retained.len(), 2);
+    }
+
+    #[test]
+    fn variable_topics_resolve_against_program_bindings() {
+        let state = Arc::new(Mutex::new(MockState {
+            connected: true,
+            payloads: VecDeque::from([(SmolStr::new("plant/setpoint"), b"-12".to_vec())]),
+            ..MockState::default()
+        }));
+        let mut driver = MqttIoDriver::from_params_with_factory(
+            &params(
+                r#"
+broker = "127.0.0.1:1883"
+
+[[topics]]
+topic = "plant/setpoint"
+variable = "Main.setpoint"
+"#,
+            ),
+            connected_factory(&state),
+        )
+        .expect("construct mqtt driver");
+
+        let mut inputs = [0u8; 4];
+        let err = driver
+            .read_inputs(&mut inputs)
+            .expect_err("unbound variable");
+        assert!(err.to_string().contains("Main.setpoint"), "{err}");
+
+        driver.bind_variables(
+            &[IoBinding {
+                target: IoTarget::Name(SmolStr::new("setpoint")),
+                address: IoAddress::parse("%IW2").expect("address"),
+                value_type: Some(TypeId::INT),
+                display_name: Some(SmolStr::new("Main.setpoint")),
+            }],
+            &VariableStorage::new(),
+        );
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert_eq!(&inputs[2..], &(-12i16).to_le_bytes());
+
+        state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .payloads
+            .push_back((SmolStr::new("plant/setpoint"), b"70000".to_vec()));
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert!(matches!(driver.health(), IoDriverHealth::Degraded { .. }));
+        assert_eq!(&inputs[2..], &(-12i16).to_le_bytes());
+    }
+
+    #[test]
+    fn variable_topics_without_at_binding_exchange_through_storage() {
+        let state = Arc::new(Mutex::new(MockState {
+            connected: true,
+            payloads: VecDeque::from([(SmolStr::new("plant/setpoint"), b"-12".to_vec())]),
+            ..MockState::default()
+        }));
+        let mut driver = MqttIoDriver::from_params_with_factory(
+            &params(
+                r#"
+broker = "127.0.0.1:1883"
+
+[[topics]]
+topic = "plant/setpoint"
+variable = "Main.setpoint"
+direction = "input"
+
+[[topics]]
+topic = "plant/speed"
+variable = "Main.speed"
+direction = "output"
+"#,
+            ),
+            connected_factory(&state),
+        )
+        .expect("construct mqtt driver");
+
+        let mut storage = VariableStorage::new();
+        let main = storage.create_instance("Main");
+        storage.set_instance_var(main, "setpoint", Value::Int(0));
+        storage.set_instance_var(main, "speed", Value::Real(4.5));
+        storage.set_global("Main", Value::Instance(main));
+        driver.bind_variables(&[], &storage);
+
+        let mut inputs = [0u8; 4];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        driver.read_variables(&mut storage).expect("read variables");
+        // Name-mapped inputs bypass the process image.
+        assert_eq!(inputs, [0; 4]);
+        assert_eq!(
+            storage.get_instance_var(main, "setpoint"),
+            Some(&Value::Int(-12))
+        );
+
+        driver.write_variables(&storage).expect("write variables");
+        driver.write_outputs(&[0; 4]).expect("write outputs");
+        assert!(matches!(driver.health(), IoDriverHealth::Ok));
+        let guard = state.lock().unwrap_or_else(|e| e.into_inner());
+        assert_eq!(
+            guard.published,
+            vec![(SmolStr::new("plant/speed"), b"4.5".to_vec())]
+        );
+    }
+
+    #[test]
+    fn mapped_topic_validation_rejects_bad_entries() {
+        for (entry, expected) in [
+            ("topic = \"a/#\"\naddress = \"%IX0.0\"", "wildcards"),
+            ("topic = \"a\"\naddress = \"%MX0.0\"", "plain %I or %Q"),
+            (
+                "topic = \"a\"\naddress = \"%IW0\"\ntype = \"REAL\"",
+                "address size",
+            ),
+            ("topic = \"a\"\naddress = \"%IW0\"\nretain = true", "retain"),
+            ("topic = \"a\"\naddress = \"%QW0\"\nqos = 3", "qos"),
+            (
+                "topic = \"a\"\naddress = \"%QW0\"\nencoding = \"xml\"",
+                "json or raw",
+            ),
+            ("topic = \"a\"", "exactly one of address or variable"),
+            (
+                "topic = \"a\"\naddress = \"%IW0\"\ndirection = \"input\"",
+                "only applies to variable mappings",
+            ),
+            (
+                "topic = \"a\"\nvariable = \"x\"\ndirection = \"both\"",
+                "direction",
+            ),
+        ] {
+            let text = format!("broker = \"127.0.0.1:1883\"\n[[topics]]\n{entry}\n");
+            let err = match MqttIoDriver::from_params(&params(&text)) {
+                Ok(_) => panic!("expected validation failure for {entry}"),
+                Err(err) => err.to_string(),
+            };
+            assert!(err.contains(expected), "{entry}: {err}");
+        }
+    }
+
+    #[test]
+    fn tls_settings_load_ca_and_client_certificates() {
+        let cert = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/tls/server-cert.pem"
+        );
+        let key = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/tls/server-key.pem"
+        );
+        let config = MqttIoConfig::from_params(&params(&format!(
+            r#"
+broker = "mqtts://broker.plant.local:8883"
+ca_path = "{cert}"
+client_cert_path = "{cert}"
+client_key_path = "{key}"
+"#
+        )))
+        .expect("tls config");
+        let tls = config.tls.expect("tls material");
+        assert!(!tls.ca_pem.is_empty());
+        assert!(tls.client_auth.is_some());
+        assert!(config.endpoint.tls);
+
+        let err = match MqttIoConfig::from_params(&params(
+            r#"
+broker = "broker.plant.local:8883"
+tls = true
+"#,
+        )) {
+            Ok(_) => panic!("expected missing ca_path failure"),
+            Err(err) => err.to_string(),
+        };
+        assert!(err.contains("ca_path"), "{err}");
+
+        let err = match MqttIoConfig::from_params(&params(&format!(
+            r#"
+broker = "127.0.0.1:1883"
+ca_path = "{cert}"
+"#
+        ))) {
+            Ok(_) => panic!("expected tls=false failure"),
+            Err(err) => err.to_string(),
+        };
+        assert!(err.contains("require tls=true"), "{err}");
     }
 
     #[test]
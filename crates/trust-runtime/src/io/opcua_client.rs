@@ -0,0 +1,1135 @@
+//! OPC UA client I/O driver (remote server nodes <-> process image).
+
+#![allow(missing_docs)]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration as StdDuration, Instant};
+
+use serde::Deserialize;
+use smol_str::SmolStr;
+use trust_hir::TypeId;
+
+use super::mapping::{
+    bind_variable, check_mapped_address, parse_direction, parse_value_type, read_scalar,
+    resolve_value_type, write_scalar, NamedVariable, Scalar,
+};
+use crate::error::RuntimeError;
+use crate::io::{IoAddress, IoArea, IoBinding, IoDriver, IoDriverHealth};
+use crate::memory::VariableStorage;
+use crate::opcua::{
+    OpcUaMessageSecurityMode, OpcUaSecurityPolicy, OpcUaSecurityProfile, OpcUaVariant,
+};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "opcua-wire"), allow(dead_code))]
+struct OpcUaClientConfig {
+    endpoint_url: SmolStr,
+    security: OpcUaSecurityProfile,
+    username: Option<SmolStr>,
+    password: Option<SmolStr>,
+    trust_server_certificate: bool,
+    pki_dir: PathBuf,
+    publish_interval: StdDuration,
+    reconnect: StdDuration,
+    nodes: Vec<NodeMapping>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpcUaClientToml {
+    endpoint: String,
+    security_policy: Option<String>,
+    security_mode: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    trust_server_certificate: Option<bool>,
+    pki_dir: Option<PathBuf>,
+    publish_interval_ms: Option<u64>,
+    reconnect_ms: Option<u64>,
+    nodes: Option<Vec<NodeToml>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeToml {
+    node_id: String,
+    address: Option<String>,
+    variable: Option<String>,
+    direction: Option<String>,
+    #[serde(rename = "type")]
+    value_type: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum NodeTarget {
+    Address(IoAddress),
+    /// ST variable: through its `AT` binding, else by name with the
+    /// configured direction.
+    Variable {
+        name: SmolStr,
+        direction: Option<IoArea>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct NodeMapping {
+    node_id: SmolStr,
+    target: NodeTarget,
+    value_type: Option<TypeId>,
+}
+
+impl OpcUaClientConfig {
+    fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
+        let params: OpcUaClientToml = value
+            .clone()
+            .try_into()
+            .map_err(|err| RuntimeError::InvalidConfig(format!("io.params: {err}").into()))?;
+        let endpoint_url = params.endpoint.trim();
+        if !endpoint_url.starts_with("opc.tcp://") || endpoint_url.len() <= "opc.tcp://".len() {
+            return Err(RuntimeError::InvalidConfig(
+                format!("io.params.endpoint '{endpoint_url}' must be an opc.tcp:// URL").into(),
+            ));
+        }
+        let defaults = OpcUaSecurityProfile::default();
+        let security = OpcUaSecurityProfile {
+            policy: match params.security_policy.as_deref() {
+                Some(text) => OpcUaSecurityPolicy::parse(text).ok_or_else(|| {
+                    RuntimeError::InvalidConfig(
+                        format!("invalid io.params.security_policy '{text}'").into(),
+                    )
+                })?,
+                None => defaults.policy,
+            },
+            mode: match params.security_mode.as_deref() {
+                Some(text) => OpcUaMessageSecurityMode::parse(text).ok_or_else(|| {
+                    RuntimeError::InvalidConfig(
+                        format!("invalid io.params.security_mode '{text}'").into(),
+                    )
+                })?,
+                None => defaults.mode,
+            },
+            allow_anonymous: params.username.is_none(),
+        };
+        crate::opcua::validate_security_profile(&security).map_err(|_| {
+            RuntimeError::InvalidConfig(
+                format!(
+                    "io.params security_policy/security_mode {:?}/{:?} is not a supported profile",
+                    security.policy, security.mode
+                )
+                .into(),
+            )
+        })?;
+        let username = params.username.map(SmolStr::new);
+        let password = params.password.map(SmolStr::new);
+        if username.is_some() ^ password.is_some() {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.username/password must be set together".into(),
+            ));
+        }
+        let publish_interval_ms = params.publish_interval_ms.unwrap_or(250);
+        if publish_interval_ms == 0 {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.publish_interval_ms must be >= 1".into(),
+            ));
+        }
+        let nodes = params
+            .nodes
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, entry)| NodeMapping::from_toml(idx, entry))
+            .collect::<Result<Vec<_>, _>>()?;
+        if nodes.is_empty() {
+            return Err(RuntimeError::InvalidConfig(
+                "io.params.nodes must map at least one node".into(),
+            ));
+        }
+        Ok(Self {
+            endpoint_url: SmolStr::new(endpoint_url),
+            security,
+            username,
+            password,
+            trust_server_certificate: params.trust_server_certificate.unwrap_or(false),
+            pki_dir: params
+                .pki_dir
+                .unwrap_or_else(|| std::env::temp_dir().join("trust-runtime-opcua-client")),
+            publish_interval: StdDuration::from_millis(publish_interval_ms),
+            reconnect: StdDuration::from_millis(params.reconnect_ms.unwrap_or(1000).max(1)),
+            nodes,
+        })
+    }
+}
+
+impl NodeMapping {
+    fn from_toml(idx: usize, entry: NodeToml) -> Result<Self, RuntimeError> {
+        let field = |name: &str| format!("io.params.nodes[{idx}].{name}");
+        let node_id = entry.node_id.trim();
+        if !is_node_id_text(node_id) {
+            return Err(RuntimeError::InvalidConfig(
+                format!(
+                    "{} '{node_id}' must look like ns=<index>;s=<name> or i=<number>",
+                    field("node_id")
+                )
+                .into(),
+            ));
+        }
+        let value_type = entry
+            .value_type
+            .as_deref()
+            .map(|name| parse_value_type(name, &field("type")))
+            .transpose()?;
+        let direction = entry
+            .direction
+            .as_deref()
+            .map(|text| parse_direction(text, &field("direction")))
+            .transpose()?;
+        let target = match (entry.address, entry.variable) {
+            (Some(_), None) if direction.is_some() => {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("{} only applies to variable mappings", field("direction")).into(),
+                ))
+            }
+            (Some(address), None) => {
+                let address = IoAddress::parse(&address)?;
+                check_mapped_address(&address, value_type, &field("address"))?;
+                NodeTarget::Address(address)
+            }
+            (None, Some(variable)) if !variable.trim().is_empty() => NodeTarget::Variable {
+                name: SmolStr::new(variable.trim()),
+                direction,
+            },
+            _ => {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("io.params.nodes[{idx}] needs exactly one of address or variable")
+                        .into(),
+                ))
+            }
+        };
+        Ok(Self {
+            node_id: SmolStr::new(node_id),
+            target,
+            value_type,
+        })
+    }
+
+    /// Bind the mapping to a concrete image location.
+    fn resolve(
+        &self,
+        bindings: &[IoBinding],
+        storage: &VariableStorage,
+    ) -> Result<NodeSlot, RuntimeError> {
+        let (address, bound_type, named) = match &self.target {
+            NodeTarget::Address(address) => (address.clone(), None, None),
+            NodeTarget::Variable { name, direction } => {
+                let bound = bind_variable(name, bindings, storage, self.value_type, *direction)
+                    .map_err(|err| {
+                        RuntimeError::IoDriver(
+                            format!("opcua node '{}': {err}", self.node_id).into(),
+                        )
+                    })?;
+                (bound.address, bound.value_type, bound.named)
+            }
+        };
+        let value_type = resolve_value_type(&address, self.value_type, bound_type);
+        Ok(NodeSlot {
+            named,
+            node_id: self.node_id.clone(),
+            address,
+            value_type,
+            value: None,
+        })
+    }
+}
+
+/// Loose syntax check; the wire layer parses the full NodeId grammar.
+fn is_node_id_text(text: &str) -> bool {
+    let identifier = match text.split_once(';') {
+        Some((namespace, rest)) => {
+            let Some(index) = namespace.strip_prefix("ns=") else {
+                return false;
+            };
+            if index.parse::<u16>().is_err() {
+                return false;
+            }
+            rest
+        }
+        None => text,
+    };
+    match identifier.split_once('=') {
+        Some(("i", number)) => number.parse::<u32>().is_ok(),
+        Some(("s" | "g" | "b", value)) => !value.is_empty(),
+        _ => false,
+    }
+}
+
+/// A mapped node bound to its place in the process image.
+#[derive(Debug, Clone)]
+struct NodeSlot {
+    /// Set for variables exchanged by name instead of through the image.
+    named: Option<NamedVariable>,
+    node_id: SmolStr,
+    address: IoAddress,
+    value_type: TypeId,
+    /// Last received value (inputs, cleared while the session is down) or
+    /// last written value (outputs).
+    value: Option<Scalar>,
+}
+
+impl NodeSlot {
+    fn error(&self, message: impl AsRef<str>) -> RuntimeError {
+        RuntimeError::IoDriver(
+            format!("opcua node '{}': {}", self.node_id, message.as_ref()).into(),
+        )
+    }
+
+    /// Variant of the IEC type, matching the runtime's own server mapping.
+    fn encode_variant(&self, value: Scalar) -> OpcUaVariant {
+        let int = match value {
+            Scalar::Bool(flag) => i128::from(flag),
+            Scalar::Int(value) => i128::from(value),
+            Scalar::UInt(value) => i128::from(value),
+            Scalar::Real(value) => value as i128,
+        };
+        match self.value_type {
+            TypeId::BOOL => OpcUaVariant::Boolean(value == Scalar::Bool(true)),
+            TypeId::SINT | TypeId::INT => OpcUaVariant::Int16(int as i16),
+            TypeId::DINT => OpcUaVariant::Int32(int as i32),
+            TypeId::LINT => OpcUaVariant::Int64(int as i64),
+            TypeId::UDINT | TypeId::DWORD => OpcUaVariant::UInt32(int as u32),
+            TypeId::ULINT | TypeId::LWORD => OpcUaVariant::UInt64(int as u64),
+            TypeId::REAL => OpcUaVariant::Float(value.as_f64() as f32),
+            TypeId::LREAL => OpcUaVariant::Double(value.as_f64()),
+            _ => OpcUaVariant::UInt16(int as u16),
+        }
+    }
+
+    fn decode_variant(&self, value: &OpcUaVariant) -> Result<Scalar, RuntimeError> {
+        Ok(match value {
+            OpcUaVariant::Boolean(flag) => Scalar::Bool(*flag),
+            OpcUaVariant::Int16(value) => Scalar::Int(i64::from(*value)),
+            OpcUaVariant::Int32(value) => Scalar::Int(i64::from(*value)),
+            OpcUaVariant::Int64(value) => Scalar::Int(*value),
+            OpcUaVariant::UInt16(value) => Scalar::UInt(u64::from(*value)),
+            OpcUaVariant::UInt32(value) => Scalar::UInt(u64::from(*value)),
+            OpcUaVariant::UInt64(value) => Scalar::UInt(*value),
+            OpcUaVariant::Float(value) => Scalar::Real(f64::from(*value)),
+            OpcUaVariant::Double(value) => Scalar::Real(*value),
+            OpcUaVariant::String(_) | OpcUaVariant::Array(_) | OpcUaVariant::Structure(_) => {
+                return Err(self.error("only scalar numeric values map to the process image"))
+            }
+        })
+    }
+}
+
+/// A data change notification: node id and new value.
+type NodeChange = (SmolStr, OpcUaVariant);
+
+trait OpcUaSession: Send {
+    fn is_connected(&self) -> bool;
+    /// Data changes received since the last call, oldest first.
+    fn take_changes(&mut self) -> Vec<NodeChange>;
+    /// Queue writes; failures are reported through `take_fault`.
+    fn write(&mut self, values: Vec<NodeChange>) -> Result<(), RuntimeError>;
+    /// Latest write or subscription fault that did not close the session.
+    fn take_fault(&mut self) -> Option<SmolStr>;
+    fn last_error(&self) -> Option<SmolStr>;
+}
+
+trait OpcUaSessionFactory: Send + Sync {
+    /// Start a session that monitors `monitored` node ids. Must not block the
+    /// caller on the network; connection progress shows in `is_connected`.
+    fn connect(
+        &self,
+        config: &OpcUaClientConfig,
+        monitored: &[SmolStr],
+    ) -> Result<Box<dyn OpcUaSession>, RuntimeError>;
+}
+
+pub struct OpcUaClientDriver {
+    config: OpcUaClientConfig,
+    factory: Arc<dyn OpcUaSessionFactory>,
+    session: Option<Box<dyn OpcUaSession>>,
+    health: IoDriverHealth,
+    next_reconnect: Instant,
+    inputs: Vec<NodeSlot>,
+    outputs: Vec<NodeSlot>,
+    /// First variable mapping without a matching `AT` binding.
+    unresolved: Option<RuntimeError>,
+}
+
+impl OpcUaClientDriver {
+    pub fn from_params(value: &toml::Value) -> Result<Self, RuntimeError> {
+        #[cfg(feature = "opcua-wire")]
+        {
+            Self::from_params_with_factory(value, Arc::new(wire::WireSessionFactory))
+        }
+        #[cfg(not(feature = "opcua-wire"))]
+        {
+            let _ = OpcUaClientConfig::from_params(value)?;
+            Err(RuntimeError::InvalidConfig(
+                "io.driver 'opcua-client' requires feature 'opcua-wire'".into(),
+            ))
+        }
+    }
+
+    #[cfg_attr(not(feature = "opcua-wire"), allow(dead_code))]
+    fn from_params_with_factory(
+        value: &toml::Value,
+        factory: Arc<dyn OpcUaSessionFactory>,
+    ) -> Result<Self, RuntimeError> {
+        let config = OpcUaClientConfig::from_params(value)?;
+        let mut driver = Self {
+            config,
+            factory,
+            session: None,
+            health: IoDriverHealth::Degraded {
+                error: SmolStr::new("opcua initializing"),
+            },
+            next_reconnect: Instant::now(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            unresolved: None,
+        };
+        driver.bind_variables(&[], &VariableStorage::new());
+        Ok(driver)
+    }
+
+    pub fn validate_params(value: &toml::Value) -> Result<(), RuntimeError> {
+        let _ = OpcUaClientConfig::from_params(value)?;
+        Ok(())
+    }
+
+    fn set_degraded(&mut self, message: impl AsRef<str>) {
+        self.health = IoDriverHealth::Degraded {
+            error: SmolStr::new(message.as_ref()),
+        };
+    }
+
+    fn ensure_session(&mut self) {
+        let now = Instant::now();
+        if let Some(session) = self.session.as_mut() {
+            if session.is_connected() {
+                return;
+            }
+            if let Some(error) = session.last_error() {
+                self.set_degraded(format!("opcua session lost: {error}"));
+                if now < self.next_reconnect {
+                    return;
+                }
+                self.session = None;
+            } else {
+                self.set_degraded("opcua connecting");
+                return;
+            }
+        }
+
+        if now < self.next_reconnect {
+            return;
+        }
+        // Retry no earlier than one backoff after this attempt.
+        self.next_reconnect = now + self.config.reconnect;
+        let monitored = self
+            .inputs
+            .iter()
+            .map(|slot| slot.node_id.clone())
+            .collect::<Vec<_>>();
+        match self.factory.connect(&self.config, &monitored) {
+            Ok(session) => {
+                self.session = Some(session);
+                // Rewrite every output and wait for fresh input values on the
+                // new session.
+                for slot in self.inputs.iter_mut().chain(&mut self.outputs) {
+                    slot.value = None;
+                }
+                self.set_degraded("opcua connecting");
+            }
+            Err(err) => self.set_degraded(format!("opcua connect failed: {err}")),
+        }
+    }
+}
+
+impl IoDriver for OpcUaClientDriver {
+    fn read_inputs(&mut self, inputs: &mut [u8]) -> Result<(), RuntimeError> {
+        if let Some(err) = &self.unresolved {
+            return Err(err.clone());
+        }
+        self.ensure_session();
+        let mut fault = None;
+        if let Some(session) = self.session.as_mut() {
+            for (node_id, value) in session.take_changes() {
+                for slot in self
+                    .inputs
+                    .iter_mut()
+                    .filter(|slot| slot.node_id == node_id)
+                {
+                    match slot.decode_variant(&value) {
+                        Ok(value) => slot.value = Some(value),
+                        Err(err) => fault = Some(err.to_string()),
+                    }
+                }
+            }
+            if session.is_connected() {
+                self.health = IoDriverHealth::Ok;
+            }
+            if let Some(error) = session.take_fault() {
+                fault = Some(error.to_string());
+            }
+        }
+        if !self
+            .session
+            .as_ref()
+            .is_some_and(|session| session.is_connected())
+        {
+            for slot in &mut self.inputs {
+                slot.value = None;
+            }
+        }
+        // Inputs hold the last notified value every cycle and fall back to
+        // zero (FALSE) until the session delivers one.
+        for slot in &mut self.inputs {
+            let value = slot.value.unwrap_or(Scalar::UInt(0));
+            let image = match slot.named.as_mut() {
+                Some(named) => named.image_mut(),
+                None => &mut *inputs,
+            };
+            if let Err(err) = write_scalar(image, &slot.address, slot.value_type, value) {
+                fault = Some(slot.error(err).to_string());
+            }
+        }
+        if let Some(fault) = fault {
+            self.set_degraded(fault);
+        }
+        Ok(())
+    }
+
+    fn write_outputs(&mut self, outputs: &[u8]) -> Result<(), RuntimeError> {
+        if let Some(err) = &self.unresolved {
+            return Err(err.clone());
+        }
+        self.ensure_session();
+        let Some(session) = self.session.as_mut() else {
+            return Ok(());
+        };
+        if !session.is_connected() {
+            return Ok(());
+        }
+        let mut changed = Vec::new();
+        for slot in &mut self.outputs {
+            let image = slot.named.as_ref().map_or(outputs, NamedVariable::image);
+            let value = read_scalar(image, &slot.address, slot.value_type);
+            if slot.value != Some(value) {
+                changed.push((slot.node_id.clone(), slot.encode_variant(value)));
+                slot.value = Some(value);
+            }
+        }
+        if changed.is_empty() {
+            return Ok(());
+        }
+        if let Err(err) = session.write(changed) {
+            self.set_degraded(err.to_string());
+            self.session = None;
+            self.next_reconnect = Instant::now() + self.config.reconnect;
+        }
+        Ok(())
+    }
+
+    fn health(&self) -> IoDriverHealth {
+        self.health.clone()
+    }
+
+    fn read_variables(&mut self, storage: &mut VariableStorage) -> Result<(), RuntimeError> {
+        let mut error = None;
+        for slot in &self.inputs {
+            if let Some(named) = &slot.named {
+                if let Err(err) = named.store(storage, &slot.address, slot.value_type) {
+                    error = Some(slot.error(err));
+                }
+            }
+        }
+        if let Some(err) = error {
+            self.set_degraded(err.to_string());
+        }
+        Ok(())
+    }
+
+    fn write_variables(&mut self, storage: &VariableStorage) -> Result<(), RuntimeError> {
+        let mut error = None;
+        for slot in &mut self.outputs {
+            if let Some(named) = slot.named.as_mut() {
+                if let Err(err) = named.load(storage, &slot.address, slot.value_type) {
+                    error = Some(slot.error(err));
+                }
+            }
+        }
+        if let Some(err) = error {
+            self.set_degraded(err.to_string());
+        }
+        Ok(())
+    }
+
+    fn bind_variables(&mut self, bindings: &[IoBinding], storage: &VariableStorage) {
+        self.inputs.clear();
+        self.outputs.clear();
+        self.unresolved = None;
+        for mapping in &self.config.nodes {
+            match mapping.resolve(bindings, storage) {
+                Ok(slot) if slot.address.area == IoArea::Input => self.inputs.push(slot),
+                Ok(slot) => self.outputs.push(slot),
+                Err(err) => {
+                    self.unresolved.get_or_insert(err);
+                }
+            }
+        }
+        // Monitored items may have changed; reconnect with the new set.
+        self.session = None;
+        self.next_reconnect = Instant::now();
+    }
+}
+
+#[cfg(feature = "opcua-wire")]
+mod wire {
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration as StdDuration;
+
+    use ::opcua::client::prelude::{
+        AttributeService, DataChangeCallback, MonitoredItemService, Session, SessionCommand,
+        SubscriptionService,
+    };
+    use ::opcua::types::{
+        AttributeId, DataValue, DateTime, MonitoredItemCreateRequest, NodeId, StatusCode,
+        TimestampsToReturn, UAString, WriteValue,
+    };
+    use smol_str::SmolStr;
+
+    use super::{NodeChange, OpcUaClientConfig, OpcUaSession, OpcUaSessionFactory};
+    use crate::error::RuntimeError;
+    use crate::opcua::{
+        connect_client_session, from_wire_variant, opcua_status_error, to_wire_variant,
+        OpcUaClientIdentity, OpcUaClientOptions,
+    };
+
+    /// Sessions run on a worker thread so connects and writes never block
+    /// the resource cycle.
+    pub(super) struct WireSessionFactory;
+
+    struct WireSession {
+        changes: Arc<Mutex<Vec<NodeChange>>>,
+        connected: Arc<AtomicBool>,
+        fault: Arc<Mutex<Option<SmolStr>>>,
+        last_error: Arc<Mutex<Option<SmolStr>>>,
+        writes: Sender<Vec<NodeChange>>,
+        _worker: thread::JoinHandle<()>,
+    }
+
+    #[derive(Clone)]
+    struct Shared {
+        changes: Arc<Mutex<Vec<NodeChange>>>,
+        connected: Arc<AtomicBool>,
+        fault: Arc<Mutex<Option<SmolStr>>>,
+    }
+
+    impl OpcUaSessionFactory for WireSessionFactory {
+        fn connect(
+            &self,
+            config: &OpcUaClientConfig,
+            monitored: &[SmolStr],
+        ) -> Result<Box<dyn OpcUaSession>, RuntimeError> {
+            let shared = Shared {
+                changes: Arc::new(Mutex::new(Vec::new())),
+                connected: Arc::new(AtomicBool::new(false)),
+                fault: Arc::new(Mutex::new(None)),
+            };
+            let last_error = Arc::new(Mutex::new(None));
+            let (writes, writes_rx) = mpsc::channel();
+            let config = config.clone();
+            let monitored = monitored.to_vec();
+            let worker_shared = shared.clone();
+            let worker_error = last_error.clone();
+            let worker = thread::Builder::new()
+                .name("trust-runtime-opcua-client".into())
+                .spawn(move || {
+                    let result = run_session(&config, &monitored, &worker_shared, &writes_rx);
+                    worker_shared.connected.store(false, Ordering::SeqCst);
+                    let mut guard = worker_error.lock().unwrap_or_else(|e| e.into_inner());
+                    *guard = Some(match result {
+                        Ok(()) => SmolStr::new("opcua session closed"),
+                        Err(err) => SmolStr::new(err.to_string()),
+                    });
+                })
+                .map_err(|err| {
+                    RuntimeError::ThreadSpawn(format!("OPC UA client thread: {err}").into())
+                })?;
+            Ok(Box::new(WireSession {
+                changes: shared.changes,
+                connected: shared.connected,
+                fault: shared.fault,
+                last_error,
+                writes,
+                _worker: worker,
+            }))
+        }
+    }
+
+    impl OpcUaSession for WireSession {
+        fn is_connected(&self) -> bool {
+            self.connected.load(Ordering::SeqCst)
+        }
+
+        fn take_changes(&mut self) -> Vec<NodeChange> {
+            let mut guard = self.changes.lock().unwrap_or_else(|e| e.into_inner());
+            std::mem::take(&mut *guard)
+        }
+
+        fn write(&mut self, values: Vec<NodeChange>) -> Result<(), RuntimeError> {
+            self.writes
+                .send(values)
+                .map_err(|_| RuntimeError::IoDriver("opcua session worker stopped".into()))
+        }
+
+        fn take_fault(&mut self) -> Option<SmolStr> {
+            self.fault.lock().unwrap_or_else(|e| e.into_inner()).take()
+        }
+
+        fn last_error(&self) -> Option<SmolStr> {
+            self.last_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone()
+        }
+    }
+
+    fn parse_node_id(text: &str) -> Result<NodeId, RuntimeError> {
+        NodeId::from_str(text)
+            .map_err(|_| RuntimeError::IoDriver(format!("invalid OPC UA node id '{text}'").into()))
+    }
+
+    fn run_session(
+        config: &OpcUaClientConfig,
+        monitored: &[SmolStr],
+        shared: &Shared,
+        writes: &Receiver<Vec<NodeChange>>,
+    ) -> Result<(), RuntimeError> {
+        let identity = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                OpcUaClientIdentity::UserName { username, password }
+            }
+            _ => OpcUaClientIdentity::Anonymous,
+        };
+        let session = connect_client_session(
+            "io",
+            config.endpoint_url.as_str(),
+            config.security,
+            identity,
+            config.pki_dir.clone(),
+            OpcUaClientOptions {
+                trust_server_certificate: config.trust_server_certificate,
+            },
+        )?;
+        if !monitored.is_empty() {
+            let lookup = monitored
+                .iter()
+                .map(|text| Ok((parse_node_id(text)?, text.clone())))
+                .collect::<Result<HashMap<_, _>, RuntimeError>>()?;
+            let requests = lookup
+                .keys()
+                .cloned()
+                .map(MonitoredItemCreateRequest::from)
+                .collect::<Vec<_>>();
+            let sink = shared.changes.clone();
+            let session_guard = session.read();
+            let subscription_id = session_guard
+                .create_subscription(
+                    config.publish_interval.as_secs_f64() * 1000.0,
+                    30,
+                    10,
+                    0,
+                    0,
+                    true,
+                    DataChangeCallback::new(move |items| {
+                        let mut guard = sink.lock().unwrap_or_else(|e| e.into_inner());
+                        for item in items {
+                            let Some(name) = lookup.get(&item.item_to_monitor().node_id) else {
+                                continue;
+                            };
+                            if let Some(value) =
+                                item.last_value().value.as_ref().and_then(from_wire_variant)
+                            {
+                                guard.push((name.clone(), value));
+                            }
+                        }
+                    }),
+                )
+                .map_err(opcua_status_error)?;
+            let results = session_guard
+                .create_monitored_items(subscription_id, TimestampsToReturn::Both, &requests)
+                .map_err(opcua_status_error)?;
+            if let Some((request, result)) = requests
+                .iter()
+                .zip(results.iter())
+                .find(|(_, result)| !result.status_code.is_good())
+            {
+                return Err(RuntimeError::IoDriver(
+                    format!(
+                        "opcua monitor '{}': {}",
+                        request.item_to_monitor.node_id, result.status_code
+                    )
+                    .into(),
+                ));
+            }
+        }
+        shared.connected.store(true, Ordering::SeqCst);
+        let stop = Session::run_async(session.clone());
+
+        let result = loop {
+            match writes.recv_timeout(StdDuration::from_millis(100)) {
+                Ok(batch) => {
+                    if let Err(err) = write_batch(&session, &batch) {
+                        let mut guard = shared.fault.lock().unwrap_or_else(|e| e.into_inner());
+                        *guard = Some(SmolStr::new(err.to_string()));
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                // The driver dropped the session.
+                Err(RecvTimeoutError::Disconnected) => break Ok(()),
+            }
+            if !session.read().is_connected() {
+                break Err(RuntimeError::IoDriver("opcua session lost".into()));
+            }
+        };
+        let _ = stop.send(SessionCommand::Stop);
+        session.read().disconnect();
+        result
+    }
+
+    fn write_batch(
+        session: &Arc<::opcua::sync::RwLock<Session>>,
+        batch: &[NodeChange],
+    ) -> Result<(), RuntimeError> {
+        let values = batch
+            .iter()
+            .map(|(node_id, value)| {
+                Ok(WriteValue {
+                    node_id: parse_node_id(node_id)?,
+                    attribute_id: AttributeId::Value as u32,
+                    index_range: UAString::null(),
+                    value: DataValue {
+                        // Process image values are scalar, so the structure
+                        // encoding namespace is never consulted.
+                        value: Some(to_wire_variant(value, 0)),
+                        status: Some(StatusCode::Good),
+                        source_timestamp: Some(DateTime::now()),
+                        ..Default::default()
+                    },
+                })
+            })
+            .collect::<Result<Vec<_>, RuntimeError>>()?;
+        let statuses = session.read().write(&values).map_err(opcua_status_error)?;
+        match batch
+            .iter()
+            .zip(statuses.iter())
+            .find(|(_, status)| !status.is_good())
+        {
+            Some(((node_id, _), status)) => Err(RuntimeError::IoDriver(
+                format!("opcua write '{node_id}': {status}").into(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::IoTarget;
+    use crate::value::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct FakeServer {
+        connected: bool,
+        last_error: Option<SmolStr>,
+        fault: Option<SmolStr>,
+        changes: Vec<NodeChange>,
+        writes: Vec<NodeChange>,
+        monitored: Vec<SmolStr>,
+    }
+
+    struct FakeSession {
+        server: Arc<Mutex<FakeServer>>,
+    }
+
+    impl OpcUaSession for FakeSession {
+        fn is_connected(&self) -> bool {
+            self.server.lock().unwrap().connected
+        }
+
+        fn take_changes(&mut self) -> Vec<NodeChange> {
+            std::mem::take(&mut self.server.lock().unwrap().changes)
+        }
+
+        fn write(&mut self, values: Vec<NodeChange>) -> Result<(), RuntimeError> {
+            self.server.lock().unwrap().writes.extend(values);
+            Ok(())
+        }
+
+        fn take_fault(&mut self) -> Option<SmolStr> {
+            self.server.lock().unwrap().fault.take()
+        }
+
+        fn last_error(&self) -> Option<SmolStr> {
+            self.server.lock().unwrap().last_error.clone()
+        }
+    }
+
+    struct FakeFactory {
+        server: Arc<Mutex<FakeServer>>,
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl OpcUaSessionFactory for FakeFactory {
+        fn connect(
+            &self,
+            _config: &OpcUaClientConfig,
+            monitored: &[SmolStr],
+        ) -> Result<Box<dyn OpcUaSession>, RuntimeError> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            let mut server = self.server.lock().unwrap();
+            server.connected = true;
+            server.last_error = None;
+            server.monitored = monitored.to_vec();
+            Ok(Box::new(FakeSession {
+                server: Arc::clone(&self.server),
+            }))
+        }
+    }
+
+    fn params(text: &str) -> toml::Value {
+        toml::from_str(text).expect("parse toml params")
+    }
+
+    const ROBOT: &str = r#"
+endpoint = "opc.tcp://robot.local:4840/"
+security_policy = "none"
+security_mode = "none"
+reconnect_ms = 1
+
+[[nodes]]
+node_id = "ns=2;s=Robot.Ready"
+address = "%IX0.0"
+
+[[nodes]]
+node_id = "ns=2;s=Robot.Position"
+address = "%ID4"
+type = "REAL"
+
+[[nodes]]
+node_id = "ns=2;s=Robot.Speed"
+address = "%QW0"
+type = "INT"
+"#;
+
+    fn driver(text: &str) -> (OpcUaClientDriver, Arc<Mutex<FakeServer>>, Arc<AtomicUsize>) {
+        let server = Arc::new(Mutex::new(FakeServer::default()));
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let factory = Arc::new(FakeFactory {
+            server: Arc::clone(&server),
+            attempts: Arc::clone(&attempts),
+        });
+        let driver = OpcUaClientDriver::from_params_with_factory(&params(text), factory)
+            .expect("construct opcua driver");
+        (driver, server, attempts)
+    }
+
+    #[test]
+    fn monitored_nodes_update_inputs_and_outputs_write_on_change() {
+        let (mut driver, server, _) = driver(ROBOT);
+        let mut inputs = [0u8; 8];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert!(matches!(driver.health(), IoDriverHealth::Ok));
+        assert_eq!(
+            server.lock().unwrap().monitored,
+            vec![
+                SmolStr::new("ns=2;s=Robot.Ready"),
+                SmolStr::new("ns=2;s=Robot.Position"),
+            ]
+        );
+
+        server.lock().unwrap().changes = vec![
+            (
+                SmolStr::new("ns=2;s=Robot.Ready"),
+                OpcUaVariant::Boolean(true),
+            ),
+            (
+                SmolStr::new("ns=2;s=Robot.Position"),
+                OpcUaVariant::Double(12.5),
+            ),
+        ];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert_eq!(inputs[0], 1);
+        assert_eq!(&inputs[4..], &12.5f32.to_le_bytes());
+        // Values persist until the next notification.
+        inputs.fill(0);
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert_eq!(&inputs[4..], &12.5f32.to_le_bytes());
+
+        for speed in [-40i16, -40, 55] {
+            driver
+                .write_outputs(&speed.to_le_bytes())
+                .expect("write outputs");
+        }
+        assert_eq!(
+            server.lock().unwrap().writes,
+            vec![
+                (SmolStr::new("ns=2;s=Robot.Speed"), OpcUaVariant::Int16(-40)),
+                (SmolStr::new("ns=2;s=Robot.Speed"), OpcUaVariant::Int16(55)),
+            ]
+        );
+    }
+
+    #[test]
+    fn session_loss_degrades_health_and_reconnects() {
+        let (mut driver, server, attempts) = driver(ROBOT);
+        let mut inputs = [0u8; 8];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        driver.write_outputs(&[1, 0]).expect("write outputs");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        server.lock().unwrap().changes = vec![
+            (
+                SmolStr::new("ns=2;s=Robot.Ready"),
+                OpcUaVariant::Boolean(true),
+            ),
+            (
+                SmolStr::new("ns=2;s=Robot.Position"),
+                OpcUaVariant::Double(12.5),
+            ),
+        ];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert_eq!(inputs[0], 1);
+
+        {
+            let mut guard = server.lock().unwrap();
+            guard.connected = false;
+            guard.last_error = Some(SmolStr::new("BadConnectionClosed"));
+        }
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        // Inputs fall back to zero instead of holding stale values.
+        assert_eq!(inputs, [0; 8]);
+        assert!(matches!(driver.health(), IoDriverHealth::Degraded { .. }));
+
+        std::thread::sleep(StdDuration::from_millis(5));
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(inputs, [0; 8]);
+        driver.write_outputs(&[1, 0]).expect("write outputs");
+        // Outputs are rewritten on the new session.
+        assert_eq!(server.lock().unwrap().writes.len(), 2);
+
+        server.lock().unwrap().fault = Some(SmolStr::new("opcua write: BadTypeMismatch"));
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        match driver.health() {
+            IoDriverHealth::Degraded { error } => assert!(error.contains("BadTypeMismatch")),
+            other => panic!("expected degraded health, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn variable_nodes_resolve_against_program_bindings() {
+        let (mut driver, _, _) = driver(
+            r#"
+endpoint = "opc.tcp://camera.local:4840"
+security_policy = "none"
+security_mode = "none"
+
+[[nodes]]
+node_id = "ns=3;i=1001"
+variable = "Main.part_ok"
+"#,
+        );
+        let mut inputs = [0u8; 1];
+        let err = driver
+            .read_inputs(&mut inputs)
+            .expect_err("unbound variable");
+        assert!(err.to_string().contains("Main.part_ok"), "{err}");
+
+        driver.bind_variables(
+            &[IoBinding {
+                target: IoTarget::Name(SmolStr::new("part_ok")),
+                address: IoAddress::parse("%IX0.3").expect("address"),
+                value_type: Some(TypeId::BOOL),
+                display_name: Some(SmolStr::new("Main.part_ok")),
+            }],
+            &VariableStorage::new(),
+        );
+        driver.read_inputs(&mut inputs).expect("read inputs");
+    }
+
+    #[test]
+    fn variable_nodes_without_at_binding_exchange_through_storage() {
+        let (mut driver, server, _) = driver(
+            r#"
+endpoint = "opc.tcp://camera.local:4840"
+security_policy = "none"
+security_mode = "none"
+
+[[nodes]]
+node_id = "ns=3;i=1001"
+variable = "part_count"
+direction = "input"
+
+[[nodes]]
+node_id = "ns=3;i=1002"
+variable = "reject"
+direction = "output"
+"#,
+        );
+        let mut storage = VariableStorage::new();
+        storage.set_global("part_count", Value::DInt(-1));
+        storage.set_global("reject", Value::Bool(true));
+        driver.bind_variables(&[], &storage);
+
+        let mut inputs = [0u8; 1];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        server.lock().unwrap().changes =
+            vec![(SmolStr::new("ns=3;i=1001"), OpcUaVariant::Int32(42))];
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        driver.read_variables(&mut storage).expect("read variables");
+        assert_eq!(storage.get_global("part_count"), Some(&Value::DInt(42)));
+        assert_eq!(inputs, [0]);
+
+        driver.write_variables(&storage).expect("write variables");
+        driver.write_outputs(&[]).expect("write outputs");
+        assert_eq!(
+            server.lock().unwrap().writes,
+            vec![(SmolStr::new("ns=3;i=1002"), OpcUaVariant::Boolean(true))]
+        );
+
+        server.lock().unwrap().connected = false;
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        driver.read_variables(&mut storage).expect("read variables");
+        assert_eq!(storage.get_global("part_count"), Some(&Value::DInt(0)));
+    }
+
+    #[test]
+    fn config_validation_rejects_bad_entries() {
+        for (text, expected) in [
+            ("endpoint = \"tcp://x:4840\"\n[[nodes]]\nnode_id = \"i=1\"\naddress = \"%IX0.0\"", "opc.tcp://"),
+            ("endpoint = \"opc.tcp://x:4840\"", "at least one node"),
+            ("endpoint = \"opc.tcp://x:4840\"\n[[nodes]]\nnode_id = \"Robot.Ready\"\naddress = \"%IX0.0\"", "node_id"),
+            ("endpoint = \"opc.tcp://x:4840\"\n[[nodes]]\nnode_id = \"i=1\"\naddress = \"%MW0\"", "plain %I or %Q"),
+            ("endpoint = \"opc.tcp://x:4840\"\n[[nodes]]\nnode_id = \"i=1\"\naddress = \"%IW0\"\ntype = \"LREAL\"", "address size"),
+            ("endpoint = \"opc.tcp://x:4840\"\nsecurity_policy = \"none\"\nsecurity_mode = \"sign\"\n[[nodes]]\nnode_id = \"i=1\"\naddress = \"%IX0.0\"", "supported profile"),
+            ("endpoint = \"opc.tcp://x:4840\"\nusername = \"op\"\n[[nodes]]\nnode_id = \"i=1\"\naddress = \"%IX0.0\"", "username/password"),
+        ] {
+            let err = match OpcUaClientDriver::validate_params(&params(text)) {
+                Ok(()) => panic!("expected validation failure for {text}"),
+                Err(err) => err.to_string(),
+            };
+            assert!(err.contains(expected), "{text}: {err}");
+        }
+    }
+}
@@ -8,8 +8,8 @@ use smol_str::SmolStr;
 use crate::error::RuntimeError;
 
 use super::{
-    EthercatIoDriver, GpioDriver, IoDriver, LoopbackIoDriver, ModbusTcpDriver, MqttIoDriver,
-    SimulatedIoDriver,
+    EthercatIoDriver, GpioDriver, IoDriver, LoopbackIoDriver, ModbusRtuDriver, ModbusTcpDriver,
+    ModbusTcpServerDriver, MqttIoDriver, OpcUaClientDriver, SimulatedIoDriver,
 };
 
 pub struct IoDriverRegistry {
@@ -55,10 +55,23 @@ impl IoDriverRegistry {
 
         registry.register("modbus-tcp", create_modbus_tcp, validate_modbus_tcp);
         registry.register_alias("modbus_tcp", "modbus-tcp");
+        registry.register(
+            "modbus-tcp-server",
+            create_modbus_tcp_server,
+            validate_modbus_tcp_server,
+        );
+        registry.register_alias("modbus_tcp_server", "modbus-tcp-server");
+        registry.register_alias("modbus-server", "modbus-tcp-server");
+        registry.register("modbus-rtu", create_modbus_rtu, validate_modbus_rtu);
+        registry.register_alias("modbus_rtu", "modbus-rtu");
 
         registry.register("mqtt", create_mqtt, validate_mqtt);
         registry.register_alias("mqtt-tcp", "mqtt");
 
+        registry.register("opcua-client", create_opcua_client, validate_opcua_client);
+        registry.register_alias("opcua", "opcua-client");
+        registry.register_alias("opcua_client", "opcua-client");
+
         registry.register("ethercat", create_ethercat, validate_ethercat);
         registry.register_alias("ether-cat", "ethercat");
         registry.register_alias("ecat", "ethercat");
@@ -174,6 +187,26 @@ fn create_modbus_tcp(params: &toml::Value) -> Result<Box<dyn IoDriver>, RuntimeE
     Ok(Box::new(driver))
 }
 
+fn validate_modbus_rtu(params: &toml::Value) -> Result<(), RuntimeError> {
+    ModbusRtuDriver::validate_params(params)?;
+    Ok(())
+}
+
+fn create_modbus_rtu(params: &toml::Value) -> Result<Box<dyn IoDriver>, RuntimeError> {
+    let driver = ModbusRtuDriver::from_params(params)?;
+    Ok(Box::new(driver))
+}
+
+fn validate_modbus_tcp_server(params: &toml::Value) -> Result<(), RuntimeError> {
+    ModbusTcpServerDriver::validate_params(params)?;
+    Ok(())
+}
+
+fn create_modbus_tcp_server(params: &toml::Value) -> Result<Box<dyn IoDriver>, RuntimeError> {
+    let driver = ModbusTcpServerDriver::from_params(params)?;
+    Ok(Box::new(driver))
+}
+
 fn validate_mqtt(params: &toml::Value) -> Result<(), RuntimeError> {
     MqttIoDriver::validate_params(params)?;
     Ok(())
@@ -184,6 +217,16 @@ fn create_mqtt(params: &toml::Value) -> Result<Box<dyn IoDriver>, RuntimeError>
     Ok(Box::new(driver))
 }
 
+fn validate_opcua_client(params: &toml::Value) -> Result<(), RuntimeError> {
+    OpcUaClientDriver::validate_params(params)?;
+    Ok(())
+}
+
+fn create_opcua_client(params: &toml::Value) -> Result<Box<dyn IoDriver>, RuntimeError> {
+    let driver = OpcUaClientDriver::from_params(params)?;
+    Ok(Box::new(driver))
+}
+
 fn validate_ethercat(params: &toml::Value) -> Result<(), RuntimeError> {
     EthercatIoDriver::validate_params(params)?;
     Ok(())
@@ -208,8 +251,11 @@ mod tests {
                 "ethercat".to_string(),
                 "gpio".to_string(),
                 "loopback".to_string(),
+                "modbus-rtu".to_string(),
                 "modbus-tcp".to_string(),
+                "modbus-tcp-server".to_string(),
                 "mqtt".to_string(),
+                "opcua-client".to_string(),
                 "simulated".to_string(),
             ]
         );
@@ -0,0 +1,1224 @@
+//! MQTT Sparkplug B edge node.
+//!
+//! Publishes program variables as Sparkplug metrics: NBIRTH/DBIRTH with the
+//! full metric set, DDATA for changed values, and NDEATH as the MQTT last
+//! will. DCMD writes are returned to the caller, which routes them through the
+//! `hmi.write` control path. Payloads use the Sparkplug B protobuf schema,
+//! encoded by hand (only the fields the edge node uses).
+
+#![allow(missing_docs)]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration as StdDuration, Instant};
+
+use glob::Pattern;
+use indexmap::IndexMap;
+use rumqttc::QoS;
+use serde::Deserialize;
+use serde_json::json;
+use smol_str::SmolStr;
+
+use super::mqtt::{MqttIoConfig, MqttSession, MqttSessionFactory, MqttWill, RumqttSessionFactory};
+use crate::control::{handle_request_value, ControlState};
+use crate::error::RuntimeError;
+use crate::io::IoDriverHealth;
+
+const NAMESPACE: &str = "spBv1.0";
+const BD_SEQ: &str = "bdSeq";
+const REBIRTH: &str = "Node Control/Rebirth";
+
+/// Sparkplug B metric data types (subset used for IEC elementary types).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SparkplugDataType {
+    Int8 = 1,
+    Int16 = 2,
+    Int32 = 3,
+    Int64 = 4,
+    UInt8 = 5,
+    UInt16 = 6,
+    UInt32 = 7,
+    UInt64 = 8,
+    Float = 9,
+    Double = 10,
+    Boolean = 11,
+    String = 12,
+}
+
+impl SparkplugDataType {
+    /// Map an IEC type name (as reported by the HMI schema) to a metric type.
+    #[must_use]
+    fn from_iec(name: &str) -> Option<Self> {
+        Some(match name.trim().to_ascii_uppercase().as_str() {
+            "BOOL" => Self::Boolean,
+            "SINT" => Self::Int8,
+            "INT" => Self::Int16,
+            "DINT" => Self::Int32,
+            "LINT" | "TIME" | "LTIME" => Self::Int64,
+            "USINT" | "BYTE" => Self::UInt8,
+            "UINT" | "WORD" => Self::UInt16,
+            "UDINT" | "DWORD" => Self::UInt32,
+            "ULINT" | "LWORD" => Self::UInt64,
+            "REAL" => Self::Float,
+            "LREAL" => Self::Double,
+            "STRING" | "WSTRING" => Self::String,
+            _ => return None,
+        })
+    }
+
+    fn from_code(code: u64) -> Option<Self> {
+        Some(match code {
+            1 => Self::Int8,
+            2 => Self::Int16,
+            3 => Self::Int32,
+            4 => Self::Int64,
+            5 => Self::UInt8,
+            6 => Self::UInt16,
+            7 => Self::UInt32,
+            8 => Self::UInt64,
+            9 => Self::Float,
+            10 => Self::Double,
+            11 => Self::Boolean,
+            12 => Self::String,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum SparkplugValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Null,
+}
+
+impl SparkplugValue {
+    /// Convert an HMI JSON value into the representation of `data_type`.
+    #[must_use]
+    fn from_json(value: &serde_json::Value, data_type: SparkplugDataType) -> Self {
+        use SparkplugDataType as T;
+        match (data_type, value) {
+            (T::Boolean, serde_json::Value::Bool(flag)) => Self::Bool(*flag),
+            (T::Int8 | T::Int16 | T::Int32 | T::Int64, value) => {
+                value.as_i64().map_or(Self::Null, Self::Int)
+            }
+            (T::UInt8 | T::UInt16 | T::UInt32 | T::UInt64, value) => {
+                value.as_u64().map_or(Self::Null, Self::UInt)
+            }
+            (T::Float | T::Double, value) => value.as_f64().map_or(Self::Null, Self::Float),
+            (T::String, serde_json::Value::String(text)) => Self::String(text.clone()),
+            _ => Self::Null,
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Bool(flag) => json!(flag),
+            Self::Int(value) => json!(value),
+            Self::UInt(value) => json!(value),
+            Self::Float(value) => json!(value),
+            Self::String(text) => json!(text),
+            Self::Null => serde_json::Value::Null,
+        }
+    }
+}
+
+/// One metric of the edge node's device.
+#[derive(Debug, Clone, PartialEq)]
+struct SparkplugMetric {
+    name: SmolStr,
+    data_type: SparkplugDataType,
+    value: SparkplugValue,
+}
+
+/// A DCMD write received from the host application.
+#[derive(Debug, Clone, PartialEq)]
+struct SparkplugWrite {
+    metric: SmolStr,
+    value: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct SparkplugConfig {
+    mqtt: MqttIoConfig,
+    group_id: SmolStr,
+    edge_node_id: SmolStr,
+    device_id: SmolStr,
+    publish_interval: StdDuration,
+    command_token: Option<SmolStr>,
+    metrics: Vec<Pattern>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SparkplugToml {
+    enabled: Option<bool>,
+    group_id: Option<String>,
+    edge_node_id: Option<String>,
+    device_id: Option<String>,
+    publish_interval_ms: Option<u64>,
+    command_token: Option<String>,
+    metrics: Option<Vec<String>>,
+}
+
+impl SparkplugConfig {
+    /// Parse `[runtime.sparkplug]`; `None` when the edge node is disabled.
+    /// Broker settings use the `mqtt` driver keys (`broker`, `tls`, `ca_path`,
+    /// `username`, ...). `device_id` defaults to `default_device` (the
+    /// resource name).
+    pub fn from_section(
+        value: &toml::Value,
+        default_device: &str,
+    ) -> Result<Option<Self>, RuntimeError> {
+        let params: SparkplugToml = value.clone().try_into().map_err(|err| {
+            RuntimeError::InvalidConfig(format!("runtime.sparkplug: {err}").into())
+        })?;
+        if !params.enabled.unwrap_or(false) {
+            return Ok(None);
+        }
+        let wrap = |err: RuntimeError| {
+            RuntimeError::InvalidConfig(format!("runtime.sparkplug: {err}").into())
+        };
+        let mut table = value.clone();
+        if let Some(table) = table.as_table_mut() {
+            // Topic mappings belong to the I/O driver, not the edge node.
+            table.remove("topics");
+        }
+        let mqtt = MqttIoConfig::from_params(&table).map_err(wrap)?;
+        let id = |name: &str, text: Option<&str>| {
+            let text = text.unwrap_or_default().trim();
+            if text.is_empty() || text.contains(['/', '+', '#']) {
+                return Err(RuntimeError::InvalidConfig(
+                    format!("runtime.sparkplug.{name} must be non-empty without '/', '+' or '#'")
+                        .into(),
+                ));
+            }
+            Ok(SmolStr::new(text))
+        };
+        let group_id = id("group_id", params.group_id.as_deref())?;
+        let edge_node_id = id("edge_node_id", params.edge_node_id.as_deref())?;
+        let device_id = id(
+            "device_id",
+            Some(params.device_id.as_deref().unwrap_or(default_device)),
+        )?;
+        let publish_interval_ms = params.publish_interval_ms.unwrap_or(1000);
+        if publish_interval_ms == 0 {
+            return Err(RuntimeError::InvalidConfig(
+                "runtime.sparkplug.publish_interval_ms must be >= 1".into(),
+            ));
+        }
+        let metrics = params
+            .metrics
+            .unwrap_or_default()
+            .iter()
+            .map(|pattern| {
+                Pattern::new(pattern.trim()).map_err(|err| {
+                    RuntimeError::InvalidConfig(
+                        format!("runtime.sparkplug.metrics invalid pattern '{pattern}': {err}")
+                            .into(),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(Self {
+            mqtt,
+            group_id,
+            edge_node_id,
+            device_id,
+            publish_interval: StdDuration::from_millis(publish_interval_ms),
+            command_token: params.command_token.map(SmolStr::new),
+            metrics,
+        }))
+    }
+
+    fn node_topic(&self, kind: &str) -> String {
+        format!("{NAMESPACE}/{}/{kind}/{}", self.group_id, self.edge_node_id)
+    }
+
+    fn device_topic(&self, kind: &str) -> String {
+        format!(
+            "{NAMESPACE}/{}/{kind}/{}/{}",
+            self.group_id, self.edge_node_id, self.device_id
+        )
+    }
+
+    /// Whether a variable path (`Main.speed`) is published as a metric.
+    fn exposes(&self, path: &str) -> bool {
+        self.metrics.is_empty() || self.metrics.iter().any(|pattern| pattern.matches(path))
+    }
+}
+
+/// Sparkplug session state machine over an [`MqttSession`].
+struct SparkplugEdgeNode {
+    config: SparkplugConfig,
+    factory: Arc<dyn MqttSessionFactory>,
+    session: Option<Box<dyn MqttSession>>,
+    next_reconnect: Instant,
+    health: IoDriverHealth,
+    /// Birth/death sequence of the current session.
+    bd_seq: u64,
+    /// bdSeq for the next session.
+    next_bd_seq: u64,
+    seq: u64,
+    born: bool,
+    /// Metrics as last published (birth or data).
+    published: IndexMap<SmolStr, SparkplugMetric>,
+}
+
+impl SparkplugEdgeNode {
+    fn new(config: SparkplugConfig) -> Self {
+        Self::with_factory(config, Arc::new(RumqttSessionFactory))
+    }
+
+    fn with_factory(config: SparkplugConfig, factory: Arc<dyn MqttSessionFactory>) -> Self {
+        Self {
+            config,
+            factory,
+            session: None,
+            next_reconnect: Instant::now(),
+            health: IoDriverHealth::Degraded {
+                error: SmolStr::new("sparkplug initializing"),
+            },
+            bd_seq: 0,
+            next_bd_seq: 0,
+            seq: 0,
+            born: false,
+            published: IndexMap::new(),
+        }
+    }
+
+    fn health(&self) -> IoDriverHealth {
+        self.health.clone()
+    }
+
+    /// Publish births or changed metrics and collect received DCMD writes.
+    /// Never blocks on the broker; call once per publish interval.
+    fn poll(&mut self, metrics: &[SparkplugMetric]) -> Vec<SparkplugWrite> {
+        self.ensure_session();
+        let Some(session) = self.session.as_mut() else {
+            return Vec::new();
+        };
+        if !session.is_connected() {
+            return Vec::new();
+        }
+        let mut writes = Vec::new();
+        let ncmd = self.config.node_topic("NCMD");
+        let dcmd = self.config.device_topic("DCMD");
+        for (topic, payload) in session.take_messages() {
+            let Ok(payload) = decode_payload(&payload) else {
+                continue;
+            };
+            if topic == ncmd {
+                let rebirth = payload.iter().any(|metric| {
+                    metric.name == REBIRTH && metric.value == SparkplugValue::Bool(true)
+                });
+                if rebirth {
+                    self.born = false;
+                }
+            } else if topic == dcmd {
+                writes.extend(payload.into_iter().filter_map(|metric| {
+                    (metric.value != SparkplugValue::Null).then(|| SparkplugWrite {
+                        value: metric.value.to_json(),
+                        metric: metric.name,
+                    })
+                }));
+            }
+        }
+        // A changed metric set (online change, new variables) needs a rebirth.
+        if self.born
+            && (metrics.len() != self.published.len()
+                || metrics.iter().any(|metric| {
+                    self.published
+                        .get(&metric.name)
+                        .is_none_or(|last| last.data_type != metric.data_type)
+                }))
+        {
+            self.born = false;
+        }
+        let result = if self.born {
+            self.publish_data(metrics)
+        } else {
+            self.publish_births(metrics)
+        };
+        match result {
+            Ok(()) => self.health = IoDriverHealth::Ok,
+            Err(err) => self.drop_session(err),
+        }
+        writes
+    }
+
+    /// Announce a clean shutdown (DDEATH, NDEATH) and close the session.
+    fn shutdown(&mut self) {
+        if self.born {
+            let ddeath = self.encode(&[]);
+            let topic = self.config.device_topic("DDEATH");
+            let ndeath = self.death_payload();
+            let ntopic = self.config.node_topic("NDEATH");
+            if let Some(session) = self.session.as_mut() {
+                let _ = session.publish(&topic, &ddeath, QoS::AtMostOnce, false);
+                let _ = session.publish(&ntopic, &ndeath, QoS::AtMostOnce, false);
+            }
+        }
+        self.session = None;
+        self.born = false;
+    }
+
+    fn ensure_session(&mut self) {
+        let now = Instant::now();
+        if let Some(session) = self.session.as_ref() {
+            if session.is_connected() {
+                return;
+            }
+            match session.last_error() {
+                Some(error) => {
+                    self.health = IoDriverHealth::Degraded {
+                        error: SmolStr::new(format!("sparkplug disconnected: {error}")),
+                    };
+                    if now < self.next_reconnect {
+                        return;
+                    }
+                    self.session = None;
+                }
+                None => return,
+            }
+        }
+        if now < self.next_reconnect {
+            return;
+        }
+        // Each session announces a new bdSeq in its will and NBIRTH.
+        self.bd_seq = self.next_bd_seq;
+        self.next_bd_seq = (self.next_bd_seq + 1) % 256;
+        let mut mqtt = self.config.mqtt.clone();
+        mqtt.will = Some(MqttWill {
+            topic: SmolStr::new(self.config.node_topic("NDEATH")),
+            payload: self.death_payload(),
+        });
+        let subscriptions = [
+            (
+                SmolStr::new(self.config.node_topic("NCMD")),
+                QoS::AtMostOnce,
+            ),
+            (
+                SmolStr::new(self.config.device_topic("DCMD")),
+                QoS::AtMostOnce,
+            ),
+        ];
+        match self.factory.connect(&mqtt, &subscriptions) {
+            Ok(session) => {
+                self.session = Some(session);
+                self.born = false;
+                self.health = IoDriverHealth::Degraded {
+                    error: SmolStr::new("sparkplug connecting"),
+                };
+            }
+            Err(err) => {
+                self.health = IoDriverHealth::Degraded {
+                    error: SmolStr::new(format!("sparkplug connect failed: {err}")),
+                };
+                self.next_reconnect = now + self.config.mqtt.reconnect;
+            }
+        }
+    }
+
+    fn drop_session(&mut self, err: RuntimeError) {
+        self.health = IoDriverHealth::Degraded {
+            error: SmolStr::new(err.to_string()),
+        };
+        self.session = None;
+        self.born = false;
+        self.next_reconnect = Instant::now() + self.config.mqtt.reconnect;
+    }
+
+    fn publish_births(&mut self, metrics: &[SparkplugMetric]) -> Result<(), RuntimeError> {
+        self.seq = 0;
+        let node = [
+            SparkplugMetric {
+                name: SmolStr::new(BD_SEQ),
+                data_type: SparkplugDataType::UInt64,
+                value: SparkplugValue::UInt(self.bd_seq),
+            },
+            SparkplugMetric {
+                name: SmolStr::new(REBIRTH),
+                data_type: SparkplugDataType::Boolean,
+                value: SparkplugValue::Bool(false),
+            },
+        ];
+        let nbirth = self.encode(&node);
+        self.publish(&self.config.node_topic("NBIRTH"), &nbirth)?;
+        let dbirth = self.encode(metrics);
+        self.publish(&self.config.device_topic("DBIRTH"), &dbirth)?;
+        self.published = metrics
+            .iter()
+            .map(|metric| (metric.name.clone(), metric.clone()))
+            .collect();
+        self.born = true;
+        Ok(())
+    }
+
+    fn publish_data(&mut self, metrics: &[SparkplugMetric]) -> Result<(), RuntimeError> {
+        let changed = metrics
+            .iter()
+            .filter(|metric| self.published.get(&metric.name) != Some(*metric))
+            .cloned()
+            .collect::<Vec<_>>();
+        if changed.is_empty() {
+            return Ok(());
+        }
+        let ddata = self.encode(&changed);
+        self.publish(&self.config.device_topic("DDATA"), &ddata)?;
+        for metric in changed {
+            self.published.insert(metric.name.clone(), metric);
+        }
+        Ok(())
+    }
+
+    fn publish(&mut self, topic: &str, payload: &[u8]) -> Result<(), RuntimeError> {
+        let session = self
+            .session
+            .as_mut()
+            .ok_or_else(|| RuntimeError::IoDriver("sparkplug session closed".into()))?;
+        session.publish(topic, payload, QoS::AtMostOnce, false)
+    }
+
+    /// Encode a payload with the next message sequence number.
+    fn encode(&mut self, metrics: &[SparkplugMetric]) -> Vec<u8> {
+        let seq = self.seq;
+        self.seq = (self.seq + 1) % 256;
+        encode_payload(now_ms(), Some(seq), metrics)
+    }
+
+    fn death_payload(&self) -> Vec<u8> {
+        encode_payload(
+            now_ms(),
+            None,
+            &[SparkplugMetric {
+                name: SmolStr::new(BD_SEQ),
+                data_type: SparkplugDataType::UInt64,
+                value: SparkplugValue::UInt(self.bd_seq),
+            }],
+        )
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// --- Sparkplug B protobuf (Payload / Payload.Metric) ---
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_key(out: &mut Vec<u8>, field: u32, wire: u8) {
+    put_varint(out, (u64::from(field) << 3) | u64::from(wire));
+}
+
+fn put_bytes(out: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    put_key(out, field, 2);
+    put_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn encode_payload(timestamp: u64, seq: Option<u64>, metrics: &[SparkplugMetric]) -> Vec<u8> {
+    let mut out = Vec::new();
+    put_key(&mut out, 1, 0);
+    put_varint(&mut out, timestamp);
+    for metric in metrics {
+        put_bytes(&mut out, 2, &encode_metric(metric, timestamp));
+    }
+    if let Some(seq) = seq {
+        put_key(&mut out, 3, 0);
+        put_varint(&mut out, seq);
+    }
+    out
+}
+
+fn encode_metric(metric: &SparkplugMetric, timestamp: u64) -> Vec<u8> {
+    use SparkplugDataType as T;
+    let mut out = Vec::new();
+    put_bytes(&mut out, 1, metric.name.as_bytes());
+    put_key(&mut out, 3, 0);
+    put_varint(&mut out, timestamp);
+    put_key(&mut out, 4, 0);
+    put_varint(&mut out, metric.data_type as u64);
+    match (metric.data_type, &metric.value) {
+        (_, SparkplugValue::Null) => {
+            put_key(&mut out, 7, 0);
+            put_varint(&mut out, 1);
+        }
+        (T::Int8 | T::Int16 | T::Int32, SparkplugValue::Int(value)) => {
+            // Signed 32-bit and smaller travel as the two's complement uint32.
+            put_key(&mut out, 10, 0);
+            put_varint(&mut out, u64::from(*value as i32 as u32));
+        }
+        (T::UInt8 | T::UInt16 | T::UInt32, SparkplugValue::UInt(value)) => {
+            put_key(&mut out, 10, 0);
+            put_varint(&mut out, *value & u64::from(u32::MAX));
+        }
+        (T::Int64, SparkplugValue::Int(value)) => {
+            put_key(&mut out, 11, 0);
+            put_varint(&mut out, *value as u64);
+        }
+        (T::UInt64, SparkplugValue::UInt(value)) => {
+            put_key(&mut out, 11, 0);
+            put_varint(&mut out, *value);
+        }
+        (T::Float, SparkplugValue::Float(value)) => {
+            put_key(&mut out, 12, 5);
+            out.extend_from_slice(&(*value as f32).to_le_bytes());
+        }
+        (T::Double, SparkplugValue::Float(value)) => {
+            put_key(&mut out, 13, 1);
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        (T::Boolean, SparkplugValue::Bool(flag)) => {
+            put_key(&mut out, 14, 0);
+            put_varint(&mut out, u64::from(*flag));
+        }
+        (T::String, SparkplugValue::String(text)) => put_bytes(&mut out, 15, text.as_bytes()),
+        _ => {
+            put_key(&mut out, 7, 0);
+            put_varint(&mut out, 1);
+        }
+    }
+    out
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn varint(&mut self) -> Result<u64, RuntimeError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self
+                .bytes
+                .split_first()
+                .ok_or_else(|| RuntimeError::IoDriver("sparkplug payload truncated".into()))?;
+            self.bytes = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(RuntimeError::IoDriver("sparkplug varint too long".into()))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], RuntimeError> {
+        if self.bytes.len() < len {
+            return Err(RuntimeError::IoDriver("sparkplug payload truncated".into()));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    /// Next field as (number, wire type, raw value); varints are returned as
+    /// their little-endian bytes.
+    fn field(&mut self) -> Result<Option<(u64, u8, Vec<u8>)>, RuntimeError> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let wire = (key & 7) as u8;
+        let value = match wire {
+            0 => self.varint()?.to_le_bytes().to_vec(),
+            1 => self.take(8)?.to_vec(),
+            2 => {
+                let len = self.varint()? as usize;
+                self.take(len)?.to_vec()
+            }
+            5 => self.take(4)?.to_vec(),
+            _ => {
+                return Err(RuntimeError::IoDriver(
+                    "sparkplug unsupported wire type".into(),
+                ))
+            }
+        };
+        Ok(Some((key >> 3, wire, value)))
+    }
+}
+
+fn le_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf[..bytes.len().min(8)].copy_from_slice(&bytes[..bytes.len().min(8)]);
+    u64::from_le_bytes(buf)
+}
+
+fn decode_payload(bytes: &[u8]) -> Result<Vec<SparkplugMetric>, RuntimeError> {
+    let mut reader = Reader { bytes };
+    let mut metrics = Vec::new();
+    while let Some((field, wire, value)) = reader.field()? {
+        if field == 2 && wire == 2 {
+            if let Some(metric) = decode_metric(&value)? {
+                metrics.push(metric);
+            }
+        }
+    }
+    Ok(metrics)
+}
+
+fn decode_metric(bytes: &[u8]) -> Result<Option<SparkplugMetric>, RuntimeError> {
+    use SparkplugDataType as T;
+    let mut reader = Reader { bytes };
+    let mut name = None;
+    let mut data_type = None;
+    let mut raw = None;
+    while let Some((field, wire, value)) = reader.field()? {
+        match (field, wire) {
+            (1, 2) => name = Some(SmolStr::new(String::from_utf8_lossy(&value))),
+            (4, 0) => data_type = T::from_code(le_u64(&value)),
+            (10..=15, _) => raw = Some((field, value)),
+            _ => {}
+        }
+    }
+    let (Some(name), Some(data_type)) = (name, data_type) else {
+        return Ok(None);
+    };
+    let value = match (data_type, raw) {
+        (_, None) => SparkplugValue::Null,
+        (T::Int8, Some((10, value))) => SparkplugValue::Int(i64::from(le_u64(&value) as u8 as i8)),
+        (T::Int16, Some((10, value))) => {
+            SparkplugValue::Int(i64::from(le_u64(&value) as u16 as i16))
+        }
+        (T::Int32, Some((10, value))) => {
+            SparkplugValue::Int(i64::from(le_u64(&value) as u32 as i32))
+        }
+        (T::UInt8 | T::UInt16 | T::UInt32, Some((10, value))) => {
+            SparkplugValue::UInt(le_u64(&value) & u64::from(u32::MAX))
+        }
+        (T::Int64, Some((11, value))) => SparkplugValue::Int(le_u64(&value) as i64),
+        (T::UInt64, Some((11, value))) => SparkplugValue::UInt(le_u64(&value)),
+        (T::Float, Some((12, value))) => {
+            SparkplugValue::Float(f64::from(f32::from_bits(le_u64(&value) as u32)))
+        }
+        (T::Double, Some((13, value))) => SparkplugValue::Float(f64::from_bits(le_u64(&value))),
+        (T::Boolean, Some((14, value))) => SparkplugValue::Bool(le_u64(&value) != 0),
+        (T::String, Some((15, value))) => {
+            SparkplugValue::String(String::from_utf8_lossy(&value).into_owned())
+        }
+        _ => SparkplugValue::Null,
+    };
+    Ok(Some(SparkplugMetric {
+        name,
+        data_type,
+        value,
+    }))
+}
+
+// --- Runtime service ---
+
+/// Handle to a running Sparkplug edge node thread.
+pub struct SparkplugService {
+    stop: Arc<AtomicBool>,
+    health: Arc<Mutex<IoDriverHealth>>,
+    join: Option<thread::JoinHandle<()>>,
+}
+
+impl SparkplugService {
+    /// Broker session health of the edge node.
+    #[must_use]
+    pub fn health(&self) -> IoDriverHealth {
+        self.health
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or(IoDriverHealth::Faulted {
+                error: SmolStr::new("sparkplug health unavailable"),
+            })
+    }
+
+    /// Stop the edge node after announcing its death.
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(join) = self.join.take() {
+            let _ = join.join();
+        }
+    }
+}
+
+impl Drop for SparkplugService {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Run the edge node against the control state. Metrics are the HMI points
+/// (program variables and globals) of the running resource; DCMD writes are
+/// submitted as `hmi.write` requests with `command_token`, so they pass the
+/// same role, write-enable and allowlist checks as HMI writes.
+pub fn start_sparkplug(
+    config: SparkplugConfig,
+    state: Arc<ControlState>,
+) -> Result<SparkplugService, RuntimeError> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_flag = stop.clone();
+    let health = Arc::new(Mutex::new(IoDriverHealth::Degraded {
+        error: SmolStr::new("sparkplug initializing"),
+    }));
+    let health_ref = health.clone();
+    let join = thread::Builder::new()
+        .name("sparkplug".into())
+        .spawn(move || {
+            let interval = config.publish_interval;
+            let mut source = MetricSource::new(config.command_token.clone());
+            let mut node = SparkplugEdgeNode::new(config);
+            while !stop_flag.load(Ordering::SeqCst) {
+                let metrics = source.collect(&state, &node.config);
+                for write in node.poll(&metrics) {
+                    source.write(&state, write);
+                }
+                if let Ok(mut guard) = health_ref.lock() {
+                    *guard = node.health();
+                }
+                thread::sleep(interval);
+            }
+            node.shutdown();
+        })
+        .map_err(|err| RuntimeError::ThreadSpawn(err.to_string().into()))?;
+    Ok(SparkplugService {
+        stop,
+        health,
+        join: Some(join),
+    })
+}
+
+/// Reads metric values and submits writes through the control request path.
+struct MetricSource {
+    token: Option<SmolStr>,
+    next_id: u64,
+    /// Metric name -> (HMI point id, data type).
+    points: IndexMap<SmolStr, (String, SparkplugDataType)>,
+}
+
+impl MetricSource {
+    fn new(token: Option<SmolStr>) -> Self {
+        Self {
+            token,
+            next_id: 1,
+            points: IndexMap::new(),
+        }
+    }
+
+    fn request(
+        &mut self,
+        state: &ControlState,
+        kind: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        self.next_id += 1;
+        let mut request = json!({ "id": self.next_id, "type": kind, "params": params });
+        if let Some(token) = &self.token {
+            request["auth"] = json!(token.as_str());
+        }
+        let response =
+            serde_json::to_value(handle_request_value(request, state, Some("sparkplug")))
+                .unwrap_or_default();
+        if response.get("ok").and_then(serde_json::Value::as_bool) == Some(true) {
+            Ok(response.get("result").cloned().unwrap_or_default())
+        } else {
+            Err(response
+                .get("error")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("request failed")
+                .to_string())
+        }
+    }
+
+    fn collect(&mut self, state: &ControlState, config: &SparkplugConfig) -> Vec<SparkplugMetric> {
+        let Ok(values) = self.request(state, "hmi.values.get", json!({})) else {
+            return Vec::new();
+        };
+        let values = values
+            .get("values")
+            .and_then(serde_json::Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let known = values.len() == self.points.len()
+            && self
+                .points
+                .values()
+                .all(|(id, _)| values.contains_key(id.as_str()));
+        if !known {
+            self.refresh_points(state, config);
+        }
+        self.points
+            .iter()
+            .map(|(name, (id, data_type))| SparkplugMetric {
+                name: name.clone(),
+                data_type: *data_type,
+                value: values
+                    .get(id.as_str())
+                    .and_then(|record| record.get("v"))
+                    .map_or(SparkplugValue::Null, |value| {
+                        SparkplugValue::from_json(value, *data_type)
+                    }),
+            })
+            .collect()
+    }
+
+    fn refresh_points(&mut self, state: &ControlState, config: &SparkplugConfig) {
+        let Ok(schema) = self.request(state, "hmi.schema.get", serde_json::Value::Null) else {
+            return;
+        };
+        self.points.clear();
+        let widgets = schema
+            .get("widgets")
+            .and_then(serde_json::Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        for widget in widgets {
+            let field = |key: &str| widget.get(key).and_then(serde_json::Value::as_str);
+            let (Some(id), Some(path), Some(data_type)) =
+                (field("id"), field("path"), field("data_type"))
+            else {
+                continue;
+            };
+            let Some(data_type) = SparkplugDataType::from_iec(data_type) else {
+                continue;
+            };
+            if !config.exposes(path) {
+                continue;
+            }
+            // Sparkplug hosts show '/' as folders: `Main.speed` -> `Main/speed`.
+            let name = SmolStr::new(path.replace('.', "/"));
+            self.points.insert(name, (id.to_string(), data_type));
+        }
+    }
+
+    fn write(&mut self, state: &ControlState, write: SparkplugWrite) {
+        let Some((id, _)) = self.points.get(&write.metric).cloned() else {
+            return;
+        };
+        // Rejections are audited by the control path; nothing to report back.
+        let _ = self.request(
+            state,
+            "hmi.write",
+            json!({ "id": id, "value": write.value }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::mqtt::MqttMessage;
+
+    #[derive(Default)]
+    struct FakeBroker {
+        connected: bool,
+        last_error: Option<SmolStr>,
+        inbox: Vec<MqttMessage>,
+        published: Vec<MqttMessage>,
+        wills: Vec<MqttWill>,
+        subscriptions: Vec<SmolStr>,
+    }
+
+    struct FakeSession {
+        broker: Arc<Mutex<FakeBroker>>,
+    }
+
+    impl MqttSession for FakeSession {
+        fn is_connected(&self) -> bool {
+            self.broker.lock().unwrap().connected
+        }
+
+        fn take_messages(&mut self) -> Vec<MqttMessage> {
+            std::mem::take(&mut self.broker.lock().unwrap().inbox)
+        }
+
+        fn publish(
+            &mut self,
+            topic: &str,
+            payload: &[u8],
+            _qos: QoS,
+            _retain: bool,
+        ) -> Result<(), RuntimeError> {
+            let mut broker = self.broker.lock().unwrap();
+            broker
+                .published
+                .push((SmolStr::new(topic), payload.to_vec()));
+            Ok(())
+        }
+
+        fn last_error(&self) -> Option<SmolStr> {
+            self.broker.lock().unwrap().last_error.clone()
+        }
+    }
+
+    struct FakeFactory {
+        broker: Arc<Mutex<FakeBroker>>,
+    }
+
+    impl MqttSessionFactory for FakeFactory {
+        fn connect(
+            &self,
+            config: &MqttIoConfig,
+            subscriptions: &[(SmolStr, QoS)],
+        ) -> Result<Box<dyn MqttSession>, RuntimeError> {
+            let mut broker = self.broker.lock().unwrap();
+            broker.connected = true;
+            broker.last_error = None;
+            broker.wills.extend(config.will.clone());
+            broker.subscriptions = subscriptions
+                .iter()
+                .map(|(topic, _)| topic.clone())
+                .collect();
+            Ok(Box::new(FakeSession {
+                broker: Arc::clone(&self.broker),
+            }))
+        }
+    }
+
+    fn config() -> SparkplugConfig {
+        let section: toml::Value = toml::from_str(
+            r#"
+enabled = true
+broker = "127.0.0.1:1883"
+group_id = "Plant"
+edge_node_id = "Line1"
+reconnect_ms = 1
+"#,
+        )
+        .expect("parse section");
+        SparkplugConfig::from_section(&section, "RESOURCE")
+            .expect("sparkplug config")
+            .expect("enabled")
+    }
+
+    fn edge_node() -> (SparkplugEdgeNode, Arc<Mutex<FakeBroker>>) {
+        let broker = Arc::new(Mutex::new(FakeBroker::default()));
+        let factory = Arc::new(FakeFactory {
+            broker: Arc::clone(&broker),
+        });
+        (SparkplugEdgeNode::with_factory(config(), factory), broker)
+    }
+
+    fn metric(name: &str, data_type: SparkplugDataType, value: SparkplugValue) -> SparkplugMetric {
+        SparkplugMetric {
+            name: SmolStr::new(name),
+            data_type,
+            value,
+        }
+    }
+
+    fn speed(value: f64) -> SparkplugMetric {
+        metric(
+            "Main/speed",
+            SparkplugDataType::Float,
+            SparkplugValue::Float(value),
+        )
+    }
+
+    fn run(flag: bool) -> SparkplugMetric {
+        metric(
+            "Main/run",
+            SparkplugDataType::Boolean,
+            SparkplugValue::Bool(flag),
+        )
+    }
+
+    fn take_published(
+        broker: &Arc<Mutex<FakeBroker>>,
+    ) -> Vec<(String, Vec<SparkplugMetric>, Option<u64>)> {
+        std::mem::take(&mut broker.lock().unwrap().published)
+            .into_iter()
+            .map(|(topic, payload)| {
+                let metrics = decode_payload(&payload).expect("decode payload");
+                (topic.to_string(), metrics, payload_seq(&payload))
+            })
+            .collect()
+    }
+
+    fn payload_seq(bytes: &[u8]) -> Option<u64> {
+        let mut reader = Reader { bytes };
+        let mut seq = None;
+        while let Some((field, _, value)) = reader.field().expect("field") {
+            if field == 3 {
+                seq = Some(le_u64(&value));
+            }
+        }
+        seq
+    }
+
+    fn bd_seq(metrics: &[SparkplugMetric]) -> Option<SparkplugValue> {
+        metrics
+            .iter()
+            .find(|metric| metric.name == BD_SEQ)
+            .map(|metric| metric.value.clone())
+    }
+
+    fn command(topic: &str, metrics: &[SparkplugMetric]) -> MqttMessage {
+        (SmolStr::new(topic), encode_payload(0, None, metrics))
+    }
+
+    #[test]
+    fn births_then_data_on_change_with_sequence_numbers() {
+        let (mut node, broker) = edge_node();
+        assert!(node.poll(&[run(true), speed(1.5)]).is_empty());
+        assert!(matches!(node.health(), IoDriverHealth::Ok));
+        {
+            let guard = broker.lock().unwrap();
+            assert_eq!(
+                guard.subscriptions,
+                vec![
+                    SmolStr::new("spBv1.0/Plant/NCMD/Line1"),
+                    SmolStr::new("spBv1.0/Plant/DCMD/Line1/RESOURCE"),
+                ]
+            );
+            let will = &guard.wills[0];
+            assert_eq!(will.topic, "spBv1.0/Plant/NDEATH/Line1");
+            let will = decode_payload(&will.payload).expect("decode will");
+            assert_eq!(bd_seq(&will), Some(SparkplugValue::UInt(0)));
+        }
+
+        let births = take_published(&broker);
+        assert_eq!(births.len(), 2);
+        assert_eq!(births[0].0, "spBv1.0/Plant/NBIRTH/Line1");
+        assert_eq!(births[0].2, Some(0));
+        assert_eq!(bd_seq(&births[0].1), Some(SparkplugValue::UInt(0)));
+        assert!(births[0].1.iter().any(|metric| metric.name == REBIRTH));
+        assert_eq!(births[1].0, "spBv1.0/Plant/DBIRTH/Line1/RESOURCE");
+        assert_eq!(births[1].1, vec![run(true), speed(1.5)]);
+        assert_eq!(births[1].2, Some(1));
+
+        node.poll(&[run(true), speed(1.5)]);
+        assert!(take_published(&broker).is_empty(), "unchanged values");
+
+        node.poll(&[run(true), speed(2.0)]);
+        let data = take_published(&broker);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].0, "spBv1.0/Plant/DDATA/Line1/RESOURCE");
+        assert_eq!(data[0].1, vec![speed(2.0)]);
+        assert_eq!(data[0].2, Some(2));
+
+        // A new metric set is announced with a fresh DBIRTH.
+        node.poll(&[
+            run(true),
+            speed(2.0),
+            metric(
+                "Main/count",
+                SparkplugDataType::Int16,
+                SparkplugValue::Int(-3),
+            ),
+        ]);
+        let rebirth = take_published(&broker);
+        assert_eq!(rebirth.len(), 2);
+        assert_eq!(rebirth[0].2, Some(0));
+        assert_eq!(rebirth[1].1.len(), 3);
+        assert_eq!(
+            rebirth[1].1[2].value,
+            SparkplugValue::Int(-3),
+            "negative Int16 survives the uint32 wire encoding"
+        );
+    }
+
+    #[test]
+    fn node_and_device_commands_trigger_rebirth_and_writes() {
+        let (mut node, broker) = edge_node();
+        node.poll(&[run(false)]);
+        take_published(&broker);
+
+        broker.lock().unwrap().inbox = vec![
+            command(
+                "spBv1.0/Plant/NCMD/Line1",
+                &[metric(
+                    REBIRTH,
+                    SparkplugDataType::Boolean,
+                    SparkplugValue::Bool(true),
+                )],
+            ),
+            command(
+                "spBv1.0/Plant/DCMD/Line1/RESOURCE",
+                &[run(true), speed(4.25)],
+            ),
+            command("spBv1.0/Plant/DCMD/Line1/OTHER", &[run(false)]),
+        ];
+        let writes = node.poll(&[run(false)]);
+        assert_eq!(
+            writes,
+            vec![
+                SparkplugWrite {
+                    metric: SmolStr::new("Main/run"),
+                    value: json!(true),
+                },
+                SparkplugWrite {
+                    metric: SmolStr::new("Main/speed"),
+                    value: json!(4.25),
+                },
+            ]
+        );
+        let topics = take_published(&broker)
+            .into_iter()
+            .map(|(topic, _, _)| topic)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            topics,
+            vec![
+                "spBv1.0/Plant/NBIRTH/Line1",
+                "spBv1.0/Plant/DBIRTH/Line1/RESOURCE"
+            ]
+        );
+    }
+
+    #[test]
+    fn reconnect_advances_bd_seq_and_shutdown_publishes_deaths() {
+        let (mut node, broker) = edge_node();
+        node.poll(&[run(true)]);
+        take_published(&broker);
+
+        {
+            let mut guard = broker.lock().unwrap();
+            guard.connected = false;
+            guard.last_error = Some(SmolStr::new("connection reset"));
+        }
+        node.poll(&[run(true)]);
+        let births = take_published(&broker);
+        assert_eq!(births[0].0, "spBv1.0/Plant/NBIRTH/Line1");
+        assert_eq!(bd_seq(&births[0].1), Some(SparkplugValue::UInt(1)));
+        let wills = broker.lock().unwrap().wills.clone();
+        assert_eq!(wills.len(), 2);
+        assert_eq!(
+            bd_seq(&decode_payload(&wills[1].payload).expect("decode will")),
+            Some(SparkplugValue::UInt(1))
+        );
+
+        node.shutdown();
+        let deaths = take_published(&broker);
+        assert_eq!(deaths[0].0, "spBv1.0/Plant/DDEATH/Line1/RESOURCE");
+        assert_eq!(deaths[1].0, "spBv1.0/Plant/NDEATH/Line1");
+        assert_eq!(bd_seq(&deaths[1].1), Some(SparkplugValue::UInt(1)));
+    }
+
+    #[test]
+    fn section_validation() {
+        let parse = |text: &str| {
+            let section: toml::Value = toml::from_str(text).expect("parse section");
+            SparkplugConfig::from_section(&section, "RESOURCE")
+        };
+        assert!(parse("enabled = false").expect("disabled").is_none());
+        let err = parse(
+            "enabled = true\nbroker = \"127.0.0.1:1883\"\ngroup_id = \"a/b\"\nedge_node_id = \"n\"",
+        )
+        .expect_err("invalid group id");
+        assert!(err.to_string().contains("group_id"), "{err}");
+        let config = parse(
+            "enabled = true\nbroker = \"127.0.0.1:1883\"\ngroup_id = \"g\"\nedge_node_id = \"n\"\nmetrics = [\"Main.*\"]",
+        )
+        .expect("config")
+        .expect("enabled");
+        assert!(config.exposes("Main.speed"));
+        assert!(!config.exposes("global.alarm"));
+    }
+}
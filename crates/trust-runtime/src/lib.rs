@@ -77,4 +77,8 @@ pub mod web;
 mod runtime;
 
 pub(crate) use runtime::types::GlobalInitValue;
-pub use runtime::{RestartMode, RetainPolicy, RetainSnapshot, Runtime, RuntimeMetadata};
+pub use runtime::{
+    OnlineChangeConversion, OnlineChangeReport, OnlineChangeReset, RestartMode, RetainAlias,
+    RetainConversion, RetainDrop, RetainMigrationReport, RetainPolicy, RetainSnapshot, Runtime,
+    RuntimeMetadata,
+};
@@ -2,6 +2,8 @@
 
 #![allow(missing_docs)]
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use indexmap::IndexMap;
 use rustc_hash::FxHashMap;
 use smol_str::SmolStr;
@@ -60,6 +62,32 @@ pub struct InstanceData {
     pub parent: Option<InstanceId>,
 }
 
+/// Accesses recorded for a watched storage location since the last reset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchAccess {
+    pub target: ValueRef,
+    pub read: bool,
+    pub written: bool,
+}
+
+/// Watched location with access flags (reads only hold `&self`).
+#[derive(Debug)]
+struct AccessWatch {
+    target: ValueRef,
+    read: AtomicBool,
+    written: AtomicBool,
+}
+
+impl Clone for AccessWatch {
+    fn clone(&self) -> Self {
+        Self {
+            target: self.target.clone(),
+            read: AtomicBool::new(self.read.load(Ordering::Relaxed)),
+            written: AtomicBool::new(self.written.load(Ordering::Relaxed)),
+        }
+    }
+}
+
 /// Storage for runtime variables.
 #[derive(Debug, Default, Clone)]
 pub struct VariableStorage {
@@ -69,6 +97,7 @@ pub struct VariableStorage {
     retain: IndexMap<SmolStr, Value>,
     next_frame_id: u32,
     next_instance_id: u32,
+    watches: Vec<AccessWatch>,
 }
 
 impl VariableStorage {
@@ -78,7 +107,8 @@ impl VariableStorage {
     }
 
     pub fn set_global(&mut self, name: impl Into<SmolStr>, value: Value) {
-        self.globals.insert(name.into(), value);
+        let (offset, _) = self.globals.insert_full(name.into(), value);
+        self.note_access(MemoryLocation::Global, offset, &[], true);
     }
 
     #[must_use]
@@ -88,7 +118,9 @@ impl VariableStorage {
 
     #[must_use]
     pub fn get_global(&self, name: &str) -> Option<&Value> {
-        self.globals.get(name)
+        let (offset, _, value) = self.globals.get_full(name)?;
+        self.note_access(MemoryLocation::Global, offset, &[], false);
+        Some(value)
     }
 
     pub fn set_retain(&mut self, name: impl Into<SmolStr>, value: Value) {
@@ -212,6 +244,74 @@ impl VariableStorage {
         id
     }
 
+    /// Storage holding `globals` and `instances`, e.g. an image decoded from
+    /// bytecode; new instances are numbered from `next_instance_id`.
+    #[must_use]
+    pub(crate) fn from_image(
+        globals: IndexMap<SmolStr, Value>,
+        instances: FxHashMap<InstanceId, InstanceData>,
+        next_instance_id: u32,
+    ) -> Self {
+        Self {
+            globals,
+            instances,
+            next_instance_id,
+            ..Self::default()
+        }
+    }
+
+    /// Id the next created instance receives.
+    #[must_use]
+    pub(crate) fn next_instance_id(&self) -> u32 {
+        self.next_instance_id
+    }
+
+    /// Copy of the variable image without call frames or access watches.
+    #[must_use]
+    pub(crate) fn image(&self) -> Self {
+        Self {
+            globals: self.globals.clone(),
+            frames: Vec::new(),
+            instances: self.instances.clone(),
+            retain: self.retain.clone(),
+            next_frame_id: 0,
+            next_instance_id: self.next_instance_id,
+            watches: Vec::new(),
+        }
+    }
+
+    /// Replace globals and instances with those of `image`, keeping the
+    /// retain area and access watches.
+    pub(crate) fn reset_to_image(&mut self, image: &Self) {
+        self.globals = image.globals.clone();
+        self.instances = image.instances.clone();
+        self.next_instance_id = image.next_instance_id;
+        self.frames.clear();
+        self.next_frame_id = 0;
+    }
+
+    /// Copy the instances reachable from `value` (nested instances and
+    /// parents) from `source`, keeping their ids.
+    pub(crate) fn copy_instances_from(&mut self, source: &Self, value: &Value) {
+        let mut pending = Vec::new();
+        let mut copied = rustc_hash::FxHashSet::default();
+        collect_instance_ids(value, &mut pending);
+        while let Some(id) = pending.pop() {
+            if !copied.insert(id) {
+                continue;
+            }
+            let Some(instance) = source.instances.get(&id) else {
+                continue;
+            };
+            for value in instance.variables.values() {
+                collect_instance_ids(value, &mut pending);
+            }
+            pending.extend(instance.parent);
+            self.instances.insert(id, instance.clone());
+            self.next_instance_id = self.next_instance_id.max(id.0.saturating_add(1));
+        }
+    }
+
     #[must_use]
     pub fn get_instance(&self, id: InstanceId) -> Option<&InstanceData> {
         self.instances.get(&id)
@@ -233,7 +333,8 @@ impl VariableStorage {
         value: Value,
     ) -> bool {
         if let Some(instance) = self.instances.get_mut(&id) {
-            instance.variables.insert(name.into(), value);
+            let (offset, _) = instance.variables.insert_full(name.into(), value);
+            self.note_access(MemoryLocation::Instance(id), offset, &[], true);
             true
         } else {
             false
@@ -242,9 +343,9 @@ impl VariableStorage {
 
     #[must_use]
     pub fn get_instance_var(&self, id: InstanceId, name: &str) -> Option<&Value> {
-        self.instances
-            .get(&id)
-            .and_then(|instance| instance.variables.get(name))
+        let (offset, _, value) = self.instances.get(&id)?.variables.get_full(name)?;
+        self.note_access(MemoryLocation::Instance(id), offset, &[], false);
+        Some(value)
     }
 
     #[must_use]
@@ -295,6 +396,7 @@ impl VariableStorage {
     }
 
     pub fn read_by_ref(&self, value_ref: crate::value::ValueRef) -> Option<&Value> {
+        self.note_access(value_ref.location, value_ref.offset, &value_ref.path, false);
         let root = match value_ref.location {
             MemoryLocation::Global => self.globals.get_index(value_ref.offset).map(|(_, v)| v),
             MemoryLocation::Local(frame_id) => self
@@ -317,6 +419,7 @@ impl VariableStorage {
     }
 
     pub fn write_by_ref(&mut self, value_ref: crate::value::ValueRef, value: Value) -> bool {
+        self.note_access(value_ref.location, value_ref.offset, &value_ref.path, true);
         match value_ref.location {
             MemoryLocation::Global => {
                 let Some((_, slot)) = self.globals.get_index_mut(value_ref.offset) else {
@@ -350,6 +453,81 @@ impl VariableStorage {
             MemoryLocation::Io(_) | MemoryLocation::Retain => false,
         }
     }
+
+    /// Replace the set of locations whose reads and writes are recorded.
+    pub fn set_access_watches(&mut self, targets: Vec<ValueRef>) {
+        self.watches = targets
+            .into_iter()
+            .map(|target| AccessWatch {
+                target,
+                read: AtomicBool::new(false),
+                written: AtomicBool::new(false),
+            })
+            .collect();
+    }
+
+    /// Returns whether exactly `targets` are currently watched (in order).
+    #[must_use]
+    pub fn has_access_watches(&self, targets: &[ValueRef]) -> bool {
+        self.watches.len() == targets.len()
+            && self
+                .watches
+                .iter()
+                .zip(targets)
+                .all(|(watch, target)| watch.target == *target)
+    }
+
+    /// Return watched locations accessed since the last reset and clear their flags.
+    pub fn take_watch_accesses(&mut self) -> Vec<WatchAccess> {
+        self.watches
+            .iter()
+            .filter_map(|watch| {
+                let read = watch.read.swap(false, Ordering::Relaxed);
+                let written = watch.written.swap(false, Ordering::Relaxed);
+                (read || written).then(|| WatchAccess {
+                    target: watch.target.clone(),
+                    read,
+                    written,
+                })
+            })
+            .collect()
+    }
+
+    /// Forget accesses recorded so far (e.g. debugger or I/O traffic).
+    pub fn clear_watch_accesses(&self) {
+        for watch in &self.watches {
+            watch.read.store(false, Ordering::Relaxed);
+            watch.written.store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn note_access(
+        &self,
+        location: MemoryLocation,
+        offset: usize,
+        path: &[RefSegment],
+        write: bool,
+    ) {
+        for watch in &self.watches {
+            let target = &watch.target;
+            if target.location != location || target.offset != offset {
+                continue;
+            }
+            // Writing a whole struct touches its fields and vice versa.
+            let overlaps = path
+                .iter()
+                .zip(&target.path)
+                .all(|(left, right)| left == right);
+            if !overlaps {
+                continue;
+            }
+            if write {
+                watch.written.store(true, Ordering::Relaxed);
+            } else {
+                watch.read.store(true, Ordering::Relaxed);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -380,6 +558,23 @@ impl AccessMap {
     }
 }
 
+fn collect_instance_ids(value: &Value, ids: &mut Vec<InstanceId>) {
+    match value {
+        Value::Instance(id) => ids.push(*id),
+        Value::Array(array) => {
+            for element in &array.elements {
+                collect_instance_ids(element, ids);
+            }
+        }
+        Value::Struct(value) => {
+            for field in value.fields.values() {
+                collect_instance_ids(field, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
 fn ref_for_map(
     map: &IndexMap<SmolStr, Value>,
     location: MemoryLocation,
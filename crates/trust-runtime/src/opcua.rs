@@ -9,8 +9,22 @@ use smol_str::SmolStr;
 
 use crate::debug::DebugSnapshot;
 use crate::error::RuntimeError;
+use crate::runtime::RuntimeMetadata;
 use crate::value::Value;
 
+mod alarms;
+mod structured;
+pub use alarms::{
+    OpcUaAlarmAcknowledge, OpcUaAlarmCondition, OpcUaAlarmSource, OpcUaAlarmView,
+    OPCUA_ALARM_SEVERITY,
+};
+pub use structured::{
+    decode_opcua_structure, encode_opcua_structure, iec_value_from_opcua, map_iec_type,
+    opcua_binary_schema, opcua_method_input_types, opcua_method_signatures,
+    opcua_struct_definitions, OpcUaArgument, OpcUaArrayValue, OpcUaMethodSignature,
+    OpcUaStructDefinition, OpcUaStructField, OpcUaStructValue,
+};
+
 #[cfg(feature = "opcua-wire")]
 use ::opcua::client::prelude::{AttributeService, ViewService};
 #[cfg(feature = "opcua-wire")]
@@ -140,6 +154,13 @@ pub enum OpcUaDataType {
     Float,
     Double,
     String,
+    /// Array of a scalar or structure type (IEC ARRAY).
+    Array {
+        element: Box<OpcUaDataType>,
+        dimensions: Vec<u32>,
+    },
+    /// Structured DataType named after the IEC STRUCT type.
+    Structure(SmolStr),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -154,6 +175,8 @@ pub enum OpcUaVariant {
     Float(f32),
     Double(f64),
     String(String),
+    Array(OpcUaArrayValue),
+    Structure(OpcUaStructValue),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -169,6 +192,22 @@ pub struct OpcUaExposedNode {
     pub data_type: OpcUaDataType,
 }
 
+/// A function block METHOD published as an OPC UA Method node.
+#[derive(Debug, Clone)]
+pub struct OpcUaExposedMethod {
+    /// Instance path of the owning function block.
+    pub instance: SmolStr,
+    pub name: SmolStr,
+    pub object_id: String,
+    pub method_id: String,
+    pub signature: OpcUaMethodSignature,
+}
+
+/// Invokes `method` on the FB instance at `instance` inside the runtime; see
+/// [`crate::Runtime::call_instance_method`].
+pub type OpcUaMethodCaller =
+    Arc<dyn Fn(&str, &str, Vec<Value>) -> Result<Vec<Value>, RuntimeError> + Send + Sync>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OpcUaClientIdentity<'a> {
     Anonymous,
@@ -200,10 +239,23 @@ pub struct OpcUaLoadReport {
     pub elapsed_ms: u128,
 }
 
+/// Alarm condition event as received by a subscribed client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcUaAlarmEvent {
+    /// HMI alarm id of the condition that raised the event.
+    pub alarm_id: String,
+    pub event_id: Vec<u8>,
+    pub acknowledged: bool,
+}
+
 pub struct OpcUaWireServer {
     endpoint_url: String,
     security: OpcUaSecurityProfile,
     exposed_nodes: Vec<OpcUaExposedNode>,
+    exposed_methods: Vec<OpcUaExposedMethod>,
+    struct_definitions: Vec<OpcUaStructDefinition>,
+    #[cfg(feature = "opcua-wire")]
+    namespace: u16,
     #[cfg(feature = "opcua-wire")]
     node_ids: HashMap<SmolStr, ::opcua::types::NodeId>,
     #[cfg(feature = "opcua-wire")]
@@ -220,6 +272,7 @@ impl std::fmt::Debug for OpcUaWireServer {
             .field("endpoint_url", &self.endpoint_url)
             .field("security", &self.security)
             .field("exposed_nodes", &self.exposed_nodes)
+            .field("exposed_methods", &self.exposed_methods)
             .finish()
     }
 }
@@ -246,6 +299,17 @@ impl OpcUaWireServer {
         self.exposed_nodes.as_slice()
     }
 
+    #[must_use]
+    pub fn exposed_methods(&self) -> &[OpcUaExposedMethod] {
+        self.exposed_methods.as_slice()
+    }
+
+    /// Structured data types published in the type dictionary.
+    #[must_use]
+    pub fn struct_definitions(&self) -> &[OpcUaStructDefinition] {
+        self.struct_definitions.as_slice()
+    }
+
     #[cfg(feature = "opcua-wire")]
     pub fn stop(&mut self) {
         if let Some(join) = self.server_thread.take() {
@@ -300,7 +364,7 @@ impl OpcUaWireServer {
                 .ok_or_else(|| RuntimeError::ControlError("OPC UA read returned no value".into()))?
         };
         session.read().disconnect();
-        from_wire_variant(&value).ok_or_else(|| {
+        from_wire_variant_with(&value, &self.struct_definitions).ok_or_else(|| {
             RuntimeError::ControlError(format!("unsupported OPC UA variant: {value:?}").into())
         })
     }
@@ -402,6 +466,246 @@ impl OpcUaWireServer {
         Err(opcua_wire_feature_error())
     }
 
+    /// Call an exposed FB method (`instance` path and method name) through a
+    /// client session and return its output arguments.
+    #[cfg(feature = "opcua-wire")]
+    pub fn probe_call(
+        &self,
+        instance: &str,
+        method: &str,
+        inputs: &[OpcUaVariant],
+        identity: OpcUaClientIdentity<'_>,
+    ) -> Result<Vec<OpcUaVariant>, RuntimeError> {
+        use ::opcua::client::prelude::MethodService;
+        use std::str::FromStr;
+
+        let exposed = self
+            .exposed_methods
+            .iter()
+            .find(|candidate| {
+                candidate.instance.eq_ignore_ascii_case(instance)
+                    && candidate.name.eq_ignore_ascii_case(method)
+            })
+            .ok_or_else(|| {
+                RuntimeError::ControlError(
+                    format!("unknown OPC UA method '{instance}.{method}'").into(),
+                )
+            })?;
+        let object_id = ::opcua::types::NodeId::from_str(exposed.object_id.as_str())
+            .map_err(opcua_status_error)?;
+        let method_id = ::opcua::types::NodeId::from_str(exposed.method_id.as_str())
+            .map_err(opcua_status_error)?;
+        let namespace = object_id.namespace;
+        let arguments = inputs
+            .iter()
+            .map(|value| to_wire_variant(value, namespace))
+            .collect::<Vec<_>>();
+        let session = self.connect_session(identity, OpcUaClientOptions::default())?;
+        let result = session
+            .read()
+            .call_method((object_id, method_id, Some(arguments)))
+            .map_err(opcua_status_error);
+        session.read().disconnect();
+        let result = result?;
+        if !result.status_code.is_good() {
+            return Err(opcua_status_error(result.status_code));
+        }
+        result
+            .output_arguments
+            .unwrap_or_default()
+            .iter()
+            .map(|value| {
+                from_wire_variant_with(value, &self.struct_definitions).ok_or_else(|| {
+                    RuntimeError::ControlError(
+                        format!("unsupported OPC UA variant: {value:?}").into(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "opcua-wire"))]
+    pub fn probe_call(
+        &self,
+        _instance: &str,
+        _method: &str,
+        _inputs: &[OpcUaVariant],
+        _identity: OpcUaClientIdentity<'_>,
+    ) -> Result<Vec<OpcUaVariant>, RuntimeError> {
+        Err(opcua_wire_feature_error())
+    }
+
+    /// Subscribe to events on the Server object for `wait` and return the
+    /// alarm condition events received.
+    #[cfg(feature = "opcua-wire")]
+    pub fn probe_alarm_events(
+        &self,
+        wait: StdDuration,
+        identity: OpcUaClientIdentity<'_>,
+    ) -> Result<Vec<OpcUaAlarmEvent>, RuntimeError> {
+        use ::opcua::client::prelude::{
+            EventCallback, MonitoredItemService, Session, SessionCommand, SubscriptionService,
+        };
+        use ::opcua::types::{
+            AttributeId, ContentFilter, EventFilter, ExtensionObject, Identifier,
+            MonitoredItemCreateRequest, MonitoringMode, MonitoringParameters, ObjectId,
+            ObjectTypeId, QualifiedName, ReadValueId, SimpleAttributeOperand, TimestampsToReturn,
+            UAString, Variant,
+        };
+
+        let field = |name: &str| SimpleAttributeOperand {
+            type_definition_id: ObjectTypeId::BaseEventType.into(),
+            browse_path: Some(vec![QualifiedName::from(name)]),
+            attribute_id: AttributeId::Value as u32,
+            index_range: UAString::null(),
+        };
+        let filter = EventFilter {
+            select_clauses: Some(vec![
+                field("EventId"),
+                field("SourceNode"),
+                field("AckedState"),
+            ]),
+            where_clause: ContentFilter { elements: None },
+        };
+        let request = MonitoredItemCreateRequest {
+            item_to_monitor: ReadValueId {
+                node_id: ObjectId::Server.into(),
+                attribute_id: AttributeId::EventNotifier as u32,
+                index_range: UAString::null(),
+                data_encoding: QualifiedName::null(),
+            },
+            monitoring_mode: MonitoringMode::Reporting,
+            requested_parameters: MonitoringParameters {
+                client_handle: 0,
+                sampling_interval: 0.0,
+                filter: ExtensionObject::from_encodable(
+                    ObjectId::EventFilter_Encoding_DefaultBinary,
+                    &filter,
+                ),
+                queue_size: 100,
+                discard_oldest: true,
+            },
+        };
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let session = self.connect_session(identity, OpcUaClientOptions::default())?;
+        let result = {
+            let session_guard = session.read();
+            session_guard
+                .create_subscription(
+                    50.0,
+                    30,
+                    10,
+                    0,
+                    0,
+                    true,
+                    EventCallback::new(move |events| {
+                        let mut guard = sink.lock().unwrap_or_else(|e| e.into_inner());
+                        for fields in events.events.iter().flatten() {
+                            let Some([event_id, source, acked]) = fields.event_fields.as_deref()
+                            else {
+                                continue;
+                            };
+                            let (
+                                Variant::ByteString(event_id),
+                                Variant::NodeId(source),
+                                Variant::LocalizedText(acked),
+                            ) = (event_id, source, acked)
+                            else {
+                                continue;
+                            };
+                            let Identifier::String(identifier) = &source.identifier else {
+                                continue;
+                            };
+                            let Some(alarm_id) = identifier.as_ref().strip_prefix("Alarm.") else {
+                                continue;
+                            };
+                            guard.push(OpcUaAlarmEvent {
+                                alarm_id: alarm_id.to_string(),
+                                event_id: event_id.as_ref().to_vec(),
+                                acknowledged: acked.text.as_ref() == "Acknowledged",
+                            });
+                        }
+                    }),
+                )
+                .and_then(|subscription_id| {
+                    session_guard.create_monitored_items(
+                        subscription_id,
+                        TimestampsToReturn::Both,
+                        &[request],
+                    )
+                })
+                .map_err(opcua_status_error)
+        };
+        if let Err(err) = result {
+            session.read().disconnect();
+            return Err(err);
+        }
+        let stop = Session::run_async(session.clone());
+        std::thread::sleep(wait);
+        let _ = stop.send(SessionCommand::Stop);
+        session.read().disconnect();
+        let events = received.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        Ok(events)
+    }
+
+    #[cfg(not(feature = "opcua-wire"))]
+    pub fn probe_alarm_events(
+        &self,
+        _wait: std::time::Duration,
+        _identity: OpcUaClientIdentity<'_>,
+    ) -> Result<Vec<OpcUaAlarmEvent>, RuntimeError> {
+        Err(opcua_wire_feature_error())
+    }
+
+    /// Call Acknowledge (or Confirm with `confirm`) on an alarm condition,
+    /// naming the event being acknowledged by its EventId.
+    #[cfg(feature = "opcua-wire")]
+    pub fn probe_acknowledge_alarm(
+        &self,
+        alarm_id: &str,
+        event_id: &[u8],
+        confirm: bool,
+        identity: OpcUaClientIdentity<'_>,
+    ) -> Result<(), RuntimeError> {
+        use ::opcua::client::prelude::MethodService;
+        use ::opcua::types::{ByteString, LocalizedText, MethodId, NodeId, Variant};
+
+        let object_id = NodeId::new(self.namespace, format!("Alarm.{alarm_id}"));
+        let method_id = NodeId::from(if confirm {
+            &MethodId::AcknowledgeableConditionType_Confirm
+        } else {
+            &MethodId::AcknowledgeableConditionType_Acknowledge
+        });
+        let arguments = vec![
+            Variant::ByteString(ByteString::from(event_id)),
+            Variant::LocalizedText(Box::new(LocalizedText::new("", ""))),
+        ];
+        let session = self.connect_session(identity, OpcUaClientOptions::default())?;
+        let result = session
+            .read()
+            .call((object_id, method_id, Some(arguments)))
+            .map_err(opcua_status_error);
+        session.read().disconnect();
+        let result = result?;
+        if !result.status_code.is_good() {
+            return Err(opcua_status_error(result.status_code));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "opcua-wire"))]
+    pub fn probe_acknowledge_alarm(
+        &self,
+        _alarm_id: &str,
+        _event_id: &[u8],
+        _confirm: bool,
+        _identity: OpcUaClientIdentity<'_>,
+    ) -> Result<(), RuntimeError> {
+        Err(opcua_wire_feature_error())
+    }
+
     #[cfg(feature = "opcua-wire")]
     fn connect_session(
         &self,
@@ -413,55 +717,14 @@ impl OpcUaWireServer {
         } else {
             self.client_pki_dir.join("strict")
         };
-        std::fs::create_dir_all(&client_pki_dir).map_err(|err| {
-            RuntimeError::ControlError(format!("create OPC UA client PKI: {err}").into())
-        })?;
-
-        let mut client = ::opcua::client::prelude::ClientBuilder::new()
-            .application_name("truST OPC UA probe")
-            .application_uri("urn:trust:runtime:opcua:probe")
-            .product_uri("urn:trust:runtime")
-            .pki_dir(client_pki_dir)
-            .create_sample_keypair(true)
-            .trust_server_certs(options.trust_server_certificate)
-            .verify_server_certs(!options.trust_server_certificate)
-            .session_retry_limit(1)
-            .client()
-            .ok_or_else(|| RuntimeError::ControlError("failed to build OPC UA client".into()))?;
-
-        let security_policy = to_wire_security_policy(self.security.policy);
-        let security_mode = to_wire_security_mode(self.security.mode);
-        let endpoints = client
-            .get_server_endpoints_from_url(self.endpoint_url.as_str())
-            .map_err(opcua_status_error)?;
-        let endpoint = ::opcua::client::prelude::Client::find_matching_endpoint(
-            endpoints.as_slice(),
+        connect_client_session(
+            "probe",
             self.endpoint_url.as_str(),
-            security_policy,
-            security_mode,
+            self.security,
+            identity,
+            client_pki_dir,
+            options,
         )
-        .ok_or_else(|| {
-            RuntimeError::ControlError(
-                format!(
-                    "no matching OPC UA endpoint for {} / {:?}",
-                    security_policy.to_uri(),
-                    security_mode
-                )
-                .into(),
-            )
-        })?;
-        let token = match identity {
-            OpcUaClientIdentity::Anonymous => ::opcua::client::prelude::IdentityToken::Anonymous,
-            OpcUaClientIdentity::UserName { username, password } => {
-                ::opcua::client::prelude::IdentityToken::UserName(
-                    username.to_string(),
-                    password.to_string(),
-                )
-            }
-        };
-        client
-            .connect_to_endpoint(endpoint, token)
-            .map_err(opcua_status_error)
     }
 
     #[cfg(feature = "opcua-wire")]
@@ -472,6 +735,68 @@ impl OpcUaWireServer {
     }
 }
 
+/// Open a client session to `endpoint_url` with the given security profile.
+/// `role` names the client application (`urn:trust:runtime:opcua:<role>`).
+#[cfg(feature = "opcua-wire")]
+pub(crate) fn connect_client_session(
+    role: &str,
+    endpoint_url: &str,
+    security: OpcUaSecurityProfile,
+    identity: OpcUaClientIdentity<'_>,
+    client_pki_dir: PathBuf,
+    options: OpcUaClientOptions,
+) -> Result<Arc<::opcua::sync::RwLock<::opcua::client::prelude::Session>>, RuntimeError> {
+    std::fs::create_dir_all(&client_pki_dir).map_err(|err| {
+        RuntimeError::ControlError(format!("create OPC UA client PKI: {err}").into())
+    })?;
+
+    let mut client = ::opcua::client::prelude::ClientBuilder::new()
+        .application_name(format!("truST OPC UA {role}"))
+        .application_uri(format!("urn:trust:runtime:opcua:{role}"))
+        .product_uri("urn:trust:runtime")
+        .pki_dir(client_pki_dir)
+        .create_sample_keypair(true)
+        .trust_server_certs(options.trust_server_certificate)
+        .verify_server_certs(!options.trust_server_certificate)
+        .session_retry_limit(1)
+        .client()
+        .ok_or_else(|| RuntimeError::ControlError("failed to build OPC UA client".into()))?;
+
+    let security_policy = to_wire_security_policy(security.policy);
+    let security_mode = to_wire_security_mode(security.mode);
+    let endpoints = client
+        .get_server_endpoints_from_url(endpoint_url)
+        .map_err(opcua_status_error)?;
+    let endpoint = ::opcua::client::prelude::Client::find_matching_endpoint(
+        endpoints.as_slice(),
+        endpoint_url,
+        security_policy,
+        security_mode,
+    )
+    .ok_or_else(|| {
+        RuntimeError::ControlError(
+            format!(
+                "no matching OPC UA endpoint for {} / {:?}",
+                security_policy.to_uri(),
+                security_mode
+            )
+            .into(),
+        )
+    })?;
+    let token = match identity {
+        OpcUaClientIdentity::Anonymous => ::opcua::client::prelude::IdentityToken::Anonymous,
+        OpcUaClientIdentity::UserName { username, password } => {
+            ::opcua::client::prelude::IdentityToken::UserName(
+                username.to_string(),
+                password.to_string(),
+            )
+        }
+    };
+    client
+        .connect_to_endpoint(endpoint, token)
+        .map_err(opcua_status_error)
+}
+
 #[cfg(not(feature = "opcua-wire"))]
 fn opcua_wire_feature_error() -> RuntimeError {
     RuntimeError::ControlError(
@@ -558,16 +883,32 @@ pub fn map_iec_value(value: &Value) -> Option<OpcUaValue> {
             data_type: OpcUaDataType::UInt64,
             value: OpcUaVariant::UInt64(*value),
         }),
+        Value::Array(value) => {
+            structured::map_array(value).map(|(data_type, value)| OpcUaValue { data_type, value })
+        }
+        Value::Struct(value) => {
+            structured::map_struct(value).map(|(data_type, value)| OpcUaValue { data_type, value })
+        }
         _ => None,
     }
 }
 
+/// Start the OPC UA server for the exposed runtime globals.
+///
+/// With `metadata`, STRUCT types of exposed values are published as
+/// structured DataTypes with a binary type dictionary, and exposed FB
+/// instances become objects whose METHODs are invoked through
+/// `method_caller`. With `alarms`, HMI alarms are published as
+/// AlarmConditionType events.
 #[cfg(feature = "opcua-wire")]
 pub fn start_wire_server(
     resource_name: &str,
     config: &OpcUaRuntimeConfig,
     snapshot_provider: Arc<dyn Fn() -> Option<DebugSnapshot> + Send + Sync>,
     runtime_root: Option<&Path>,
+    metadata: Option<&RuntimeMetadata>,
+    method_caller: Option<OpcUaMethodCaller>,
+    alarms: Option<OpcUaAlarmSource>,
 ) -> Result<Option<OpcUaWireServer>, RuntimeError> {
     if !config.enabled {
         return Ok(None);
@@ -597,6 +938,27 @@ pub fn start_wire_server(
         RuntimeError::ControlError("runtime snapshot unavailable for OPC UA startup".into())
     })?;
     let candidates = collect_exposed_nodes(&initial_snapshot, config)?;
+    let instances = match (metadata, method_caller.as_ref()) {
+        (Some(metadata), Some(_)) => {
+            collect_exposed_instances(&initial_snapshot, config, metadata)?
+        }
+        _ => Vec::new(),
+    };
+    let struct_definitions = metadata
+        .map(|metadata| {
+            let arguments = instances
+                .iter()
+                .flat_map(|instance| &instance.methods)
+                .flat_map(|method| method.inputs.iter().chain(&method.outputs))
+                .map(|argument| argument.data_type.clone());
+            let roots = candidates
+                .iter()
+                .map(|node| node.data_type.clone())
+                .chain(arguments)
+                .collect::<Vec<_>>();
+            opcua_struct_definitions(metadata, &roots)
+        })
+        .unwrap_or_default();
     let (user_token_ids, user_credentials) = user_tokens(config)?;
     let mut builder = ::opcua::server::prelude::ServerBuilder::new()
         .application_name(format!("truST Runtime {resource_name}"))
@@ -624,7 +986,8 @@ pub fn start_wire_server(
     let address_space = server.address_space();
     let mut node_ids = HashMap::<SmolStr, ::opcua::types::NodeId>::new();
     let mut exposed_nodes = Vec::<OpcUaExposedNode>::new();
-    {
+    let mut exposed_methods = Vec::<OpcUaExposedMethod>::new();
+    let (namespace, folder_id) = {
         let mut address_space_guard = ::opcua::trace_write_lock!(address_space);
         let namespace = address_space_guard
             .register_namespace(config.namespace_uri.as_str())
@@ -640,6 +1003,14 @@ pub fn start_wire_server(
             .map_err(|_| {
                 RuntimeError::ControlError("failed to create OPC UA root folder".into())
             })?;
+        if !struct_definitions.is_empty() {
+            add_wire_data_types(
+                &mut address_space_guard,
+                namespace,
+                config.namespace_uri.as_str(),
+                &struct_definitions,
+            )?;
+        }
         let mut variables = Vec::new();
         for node in candidates {
             let ExposedNodeCandidate {
@@ -653,8 +1024,14 @@ pub fn start_wire_server(
                 &node_id,
                 browse_name.as_str(),
                 browse_name.as_str(),
-                to_wire_variant(&value),
+                to_wire_variant(&value, namespace),
             );
+            let (value_rank, array_dimensions) = wire_value_rank(&data_type);
+            variable.set_data_type(wire_data_type_id(&data_type, namespace));
+            variable.set_value_rank(value_rank);
+            if let Some(dimensions) = array_dimensions {
+                variable.set_array_dimensions(&dimensions);
+            }
             variable.set_writable(true);
             variable.set_user_access_level(
                 variable.user_access_level()
@@ -676,7 +1053,25 @@ pub fn start_wire_server(
                 ));
             }
         }
-    }
+        if let (Some(metadata), Some(caller)) = (metadata, method_caller.as_ref()) {
+            let context = WireMethodContext {
+                registry: Arc::new(metadata.registry().clone()),
+                definitions: Arc::new(struct_definitions.clone()),
+                caller: caller.clone(),
+                namespace,
+            };
+            for instance in instances {
+                exposed_methods.extend(add_wire_instance(
+                    &mut address_space_guard,
+                    &folder_id,
+                    instance,
+                    metadata,
+                    &context,
+                )?);
+            }
+        }
+        (namespace, folder_id)
+    };
 
     if !node_ids.is_empty() {
         let refresh_space = address_space.clone();
@@ -697,13 +1092,22 @@ pub fn start_wire_server(
                 };
                 address_space_guard.set_variable_value(
                     node_id.clone(),
-                    to_wire_variant(&mapped.value),
+                    to_wire_variant(&mapped.value, namespace),
                     &now,
                     &now,
                 );
             }
         });
     }
+    if let Some(alarms) = alarms {
+        add_wire_alarms(
+            &mut server,
+            namespace,
+            folder_id,
+            alarms,
+            config.publish_interval_ms,
+        );
+    }
 
     let server = Arc::new(::opcua::sync::RwLock::new(server));
     let server_task = server.clone();
@@ -719,6 +1123,9 @@ pub fn start_wire_server(
         endpoint_url,
         security: config.security,
         exposed_nodes,
+        exposed_methods,
+        struct_definitions,
+        namespace,
         node_ids,
         client_pki_dir,
         server,
@@ -749,6 +1156,9 @@ pub fn start_wire_server(
     config: &OpcUaRuntimeConfig,
     _snapshot_provider: Arc<dyn Fn() -> Option<DebugSnapshot> + Send + Sync>,
     _runtime_root: Option<&Path>,
+    _metadata: Option<&RuntimeMetadata>,
+    _method_caller: Option<OpcUaMethodCaller>,
+    _alarms: Option<OpcUaAlarmSource>,
 ) -> Result<Option<OpcUaWireServer>, RuntimeError> {
     if !config.enabled {
         return Ok(None);
@@ -794,6 +1204,509 @@ fn collect_exposed_nodes(
     Ok(nodes)
 }
 
+/// An exposed FB instance global with callable methods.
+#[cfg(feature = "opcua-wire")]
+#[derive(Debug, Clone)]
+struct ExposedInstanceCandidate {
+    name: SmolStr,
+    type_name: SmolStr,
+    methods: Vec<OpcUaMethodSignature>,
+}
+
+#[cfg(feature = "opcua-wire")]
+fn collect_exposed_instances(
+    snapshot: &DebugSnapshot,
+    config: &OpcUaRuntimeConfig,
+    metadata: &RuntimeMetadata,
+) -> Result<Vec<ExposedInstanceCandidate>, RuntimeError> {
+    let patterns = compile_exposure_patterns(config.expose.as_slice())?;
+    let mut instances = Vec::new();
+    for (name, value) in snapshot.storage.globals() {
+        if !patterns.is_empty()
+            && !patterns
+                .iter()
+                .any(|pattern| pattern.matches(name.as_str()))
+        {
+            continue;
+        }
+        let Value::Instance(id) = value else {
+            continue;
+        };
+        let Some(instance) = snapshot.storage.get_instance(*id) else {
+            continue;
+        };
+        let methods = opcua_method_signatures(metadata, instance.type_name.as_str());
+        if methods.is_empty() {
+            continue;
+        }
+        instances.push(ExposedInstanceCandidate {
+            name: name.clone(),
+            type_name: instance.type_name.clone(),
+            methods,
+        });
+    }
+    Ok(instances)
+}
+
+/// Publish structured DataTypes, their binary encodings and the OPC Binary
+/// type dictionary describing them.
+#[cfg(feature = "opcua-wire")]
+fn add_wire_data_types(
+    address_space: &mut ::opcua::server::prelude::AddressSpace,
+    namespace: u16,
+    namespace_uri: &str,
+    definitions: &[OpcUaStructDefinition],
+) -> Result<(), RuntimeError> {
+    use ::opcua::server::prelude::{DataTypeBuilder, ObjectBuilder, VariableBuilder};
+    use ::opcua::types::{
+        ByteString, DataTypeId, NodeId, ObjectId, ObjectTypeId, ReferenceTypeId, VariableTypeId,
+    };
+
+    let publish_error =
+        |what: &str| RuntimeError::ControlError(format!("failed to publish OPC UA {what}").into());
+    let dictionary_id = NodeId::new(namespace, "TypeDictionary");
+    let schema = opcua_binary_schema(namespace_uri, definitions);
+    if !VariableBuilder::new(&dictionary_id, "TypeDictionary", "TypeDictionary")
+        .data_type(DataTypeId::ByteString)
+        .value(ByteString::from(schema.into_bytes()))
+        .has_type_definition(VariableTypeId::DataTypeDictionaryType)
+        .component_of(ObjectId::OPCBinarySchema_TypeSystem)
+        .insert(address_space)
+    {
+        return Err(publish_error("type dictionary"));
+    }
+    for definition in definitions {
+        let name = definition.name.as_str();
+        let data_type_id = wire_data_type_id(
+            &OpcUaDataType::Structure(definition.name.clone()),
+            namespace,
+        );
+        let encoding_id = wire_encoding_id(namespace, name);
+        let description_id = NodeId::new(namespace, format!("TypeDictionary.{name}"));
+        let inserted = DataTypeBuilder::new(&data_type_id, name, name)
+            .subtype_of(DataTypeId::Structure)
+            .is_abstract(false)
+            .insert(address_space)
+            && ObjectBuilder::new(&encoding_id, "Default Binary", "Default Binary")
+                .has_type_definition(ObjectTypeId::DataTypeEncodingType)
+                .insert(address_space)
+            && VariableBuilder::new(&description_id, name, name)
+                .data_type(DataTypeId::String)
+                .value(name)
+                .has_type_definition(VariableTypeId::DataTypeDescriptionType)
+                .component_of(dictionary_id.clone())
+                .insert(address_space);
+        if !inserted {
+            return Err(publish_error(&format!("data type '{name}'")));
+        }
+        address_space.insert_reference(&data_type_id, &encoding_id, ReferenceTypeId::HasEncoding);
+        address_space.insert_reference(
+            &encoding_id,
+            &description_id,
+            ReferenceTypeId::HasDescription,
+        );
+    }
+    Ok(())
+}
+
+/// Publish an FB instance as an object with one Method node per METHOD.
+#[cfg(feature = "opcua-wire")]
+fn add_wire_instance(
+    address_space: &mut ::opcua::server::prelude::AddressSpace,
+    folder_id: &::opcua::types::NodeId,
+    instance: ExposedInstanceCandidate,
+    metadata: &RuntimeMetadata,
+    context: &WireMethodContext,
+) -> Result<Vec<OpcUaExposedMethod>, RuntimeError> {
+    use ::opcua::server::prelude::{MethodBuilder, ObjectBuilder};
+    use ::opcua::types::{NodeId, ObjectTypeId};
+
+    let namespace = context.namespace;
+    let ExposedInstanceCandidate {
+        name,
+        type_name,
+        methods,
+    } = instance;
+    let object_id = NodeId::new(namespace, name.to_string());
+    if !ObjectBuilder::new(&object_id, name.as_str(), name.as_str())
+        .has_type_definition(ObjectTypeId::BaseObjectType)
+        .organized_by(folder_id.clone())
+        .insert(address_space)
+    {
+        return Err(RuntimeError::ControlError(
+            format!("failed to publish OPC UA object '{name}'").into(),
+        ));
+    }
+    let mut exposed = Vec::with_capacity(methods.len());
+    for signature in methods {
+        let method_id = NodeId::new(namespace, format!("{name}.{}", signature.name));
+        let input_types =
+            opcua_method_input_types(metadata, type_name.as_str(), signature.name.as_str())
+                .unwrap_or_default();
+        let callback = WireMethod {
+            instance: name.clone(),
+            method: signature.name.clone(),
+            input_types,
+            context: context.clone(),
+        };
+        let inserted =
+            MethodBuilder::new(&method_id, signature.name.as_str(), signature.name.as_str())
+                .component_of(object_id.clone())
+                .input_args(address_space, &wire_arguments(&signature.inputs, namespace))
+                .output_args(
+                    address_space,
+                    &wire_arguments(&signature.outputs, namespace),
+                )
+                .callback(Box::new(callback))
+                .insert(address_space);
+        if !inserted {
+            return Err(RuntimeError::ControlError(
+                format!(
+                    "failed to publish OPC UA method '{name}.{}'",
+                    signature.name
+                )
+                .into(),
+            ));
+        }
+        exposed.push(OpcUaExposedMethod {
+            instance: name.clone(),
+            name: signature.name.clone(),
+            object_id: object_id.to_string(),
+            method_id: method_id.to_string(),
+            signature,
+        });
+    }
+    Ok(exposed)
+}
+
+#[cfg(feature = "opcua-wire")]
+fn wire_arguments(arguments: &[OpcUaArgument], namespace: u16) -> Vec<::opcua::types::Argument> {
+    arguments
+        .iter()
+        .map(|argument| {
+            let (value_rank, array_dimensions) = wire_value_rank(&argument.data_type);
+            ::opcua::types::Argument {
+                name: argument.name.as_str().into(),
+                data_type: wire_data_type_id(&argument.data_type, namespace),
+                value_rank,
+                array_dimensions,
+                description: ::opcua::types::LocalizedText::new("", argument.name.as_str()),
+            }
+        })
+        .collect()
+}
+
+/// State shared by the handlers of all published FB methods.
+#[cfg(feature = "opcua-wire")]
+#[derive(Clone)]
+struct WireMethodContext {
+    registry: Arc<trust_hir::types::TypeRegistry>,
+    definitions: Arc<Vec<OpcUaStructDefinition>>,
+    caller: OpcUaMethodCaller,
+    namespace: u16,
+}
+
+/// Server-side handler of one published FB method.
+#[cfg(feature = "opcua-wire")]
+struct WireMethod {
+    instance: SmolStr,
+    method: SmolStr,
+    input_types: Vec<trust_hir::TypeId>,
+    context: WireMethodContext,
+}
+
+#[cfg(feature = "opcua-wire")]
+impl ::opcua::server::callbacks::Method for WireMethod {
+    fn call(
+        &mut self,
+        _session_id: &::opcua::types::NodeId,
+        _session_manager: Arc<::opcua::sync::RwLock<::opcua::server::session::SessionManager>>,
+        request: &::opcua::types::CallMethodRequest,
+    ) -> Result<::opcua::types::CallMethodResult, ::opcua::types::StatusCode> {
+        use ::opcua::types::StatusCode;
+
+        let arguments = request.input_arguments.as_deref().unwrap_or_default();
+        if arguments.len() < self.input_types.len() {
+            return Err(StatusCode::BadArgumentsMissing);
+        }
+        if arguments.len() > self.input_types.len() {
+            return Err(StatusCode::BadTooManyArguments);
+        }
+        let mut inputs = Vec::with_capacity(arguments.len());
+        let mut input_results = Vec::with_capacity(arguments.len());
+        for (argument, type_id) in arguments.iter().zip(&self.input_types) {
+            let value = from_wire_variant_with(argument, &self.context.definitions)
+                .and_then(|value| iec_value_from_opcua(&self.context.registry, *type_id, &value));
+            input_results.push(if value.is_some() {
+                StatusCode::Good
+            } else {
+                StatusCode::BadTypeMismatch
+            });
+            inputs.extend(value);
+        }
+        if inputs.len() != self.input_types.len() {
+            return Ok(::opcua::types::CallMethodResult {
+                status_code: StatusCode::BadInvalidArgument,
+                input_argument_results: Some(input_results),
+                input_argument_diagnostic_infos: None,
+                output_arguments: None,
+            });
+        }
+        let outputs = (self.context.caller)(self.instance.as_str(), self.method.as_str(), inputs)
+            .map_err(|_| StatusCode::BadInternalError)?;
+        let outputs = outputs
+            .iter()
+            .map(|value| {
+                map_iec_value(value)
+                    .map(|mapped| to_wire_variant(&mapped.value, self.context.namespace))
+                    .unwrap_or(::opcua::types::Variant::Empty)
+            })
+            .collect();
+        Ok(::opcua::types::CallMethodResult {
+            status_code: StatusCode::Good,
+            input_argument_results: Some(input_results),
+            input_argument_diagnostic_infos: None,
+            output_arguments: Some(outputs),
+        })
+    }
+}
+
+/// How long raised alarm events stay in the address space.
+#[cfg(feature = "opcua-wire")]
+const ALARM_EVENT_RETENTION: StdDuration = StdDuration::from_secs(600);
+
+/// EventId of the latest event raised for each alarm, keyed by alarm id.
+/// Acknowledge/Confirm must name the current one.
+#[cfg(feature = "opcua-wire")]
+type WireAlarmEventIds = Arc<std::sync::Mutex<HashMap<String, ::opcua::types::ByteString>>>;
+
+/// Poll the HMI alarm feed and raise an AlarmConditionType event for every
+/// condition change. Each alarm gets a condition object under the `truST`
+/// folder that answers the standard Acknowledge and Confirm methods.
+#[cfg(feature = "opcua-wire")]
+fn add_wire_alarms(
+    server: &mut ::opcua::server::prelude::Server,
+    namespace: u16,
+    folder_id: ::opcua::types::NodeId,
+    source: OpcUaAlarmSource,
+    interval_ms: u64,
+) {
+    let address_space = server.address_space();
+    let tracker = std::sync::Mutex::new(alarms::AlarmConditionTracker::default());
+    let event_ids = WireAlarmEventIds::default();
+    server.add_polling_action(interval_ms, move || {
+        let Some(view) = (source.view)() else {
+            return;
+        };
+        let Ok(changed) = tracker.lock().map(|mut tracker| tracker.update(&view)) else {
+            return;
+        };
+        if changed.is_empty() {
+            return;
+        }
+        let mut address_space_guard = ::opcua::trace_write_lock!(address_space);
+        for condition in &changed {
+            let condition_id = ensure_wire_condition(
+                &mut address_space_guard,
+                namespace,
+                &folder_id,
+                condition,
+                &source,
+                &event_ids,
+            );
+            if let Some(event_id) = raise_wire_alarm_event(
+                &mut address_space_guard,
+                namespace,
+                &condition_id,
+                condition,
+            ) {
+                if let Ok(mut event_ids) = event_ids.lock() {
+                    event_ids.insert(condition.id.clone(), event_id);
+                }
+            }
+            let retention_ticks =
+                i64::try_from(ALARM_EVENT_RETENTION.as_micros() * 10).unwrap_or(i64::MAX);
+            let happened_before = ::opcua::types::DateTime::from(
+                ::opcua::types::DateTime::now().ticks() - retention_ticks,
+            )
+            .as_chrono();
+            ::opcua::server::events::event::purge_events(
+                condition_id,
+                ::opcua::types::ObjectTypeId::AlarmConditionType,
+                &mut address_space_guard,
+                &happened_before,
+            );
+        }
+    });
+}
+
+#[cfg(feature = "opcua-wire")]
+fn ensure_wire_condition(
+    address_space: &mut ::opcua::server::prelude::AddressSpace,
+    namespace: u16,
+    folder_id: &::opcua::types::NodeId,
+    condition: &OpcUaAlarmCondition,
+    source: &OpcUaAlarmSource,
+    event_ids: &WireAlarmEventIds,
+) -> ::opcua::types::NodeId {
+    use ::opcua::server::prelude::ObjectBuilder;
+    use ::opcua::types::{MethodId, NodeId, ObjectTypeId, ReferenceTypeId};
+
+    let condition_id = NodeId::new(namespace, format!("Alarm.{}", condition.id));
+    if address_space.node_exists(&condition_id) {
+        return condition_id;
+    }
+    ObjectBuilder::new(
+        &condition_id,
+        condition.id.as_str(),
+        condition.label.as_str(),
+    )
+    .has_type_definition(ObjectTypeId::AlarmConditionType)
+    .organized_by(folder_id.clone())
+    .insert(address_space);
+    for method_id in [
+        MethodId::AcknowledgeableConditionType_Acknowledge,
+        MethodId::AcknowledgeableConditionType_Confirm,
+    ] {
+        let method_id = NodeId::from(method_id);
+        address_space.insert_reference(&condition_id, &method_id, ReferenceTypeId::HasComponent);
+        address_space.register_method_handler(
+            condition_id.clone(),
+            method_id,
+            Box::new(WireAlarmMethod {
+                alarm_id: condition.id.clone(),
+                acknowledge: source.acknowledge.clone(),
+                event_ids: event_ids.clone(),
+            }),
+        );
+    }
+    condition_id
+}
+
+/// Raise the event on the Server object so subscriptions on it (the usual
+/// SCADA alarm list setup) receive every condition. Returns the EventId of
+/// the raised event.
+#[cfg(feature = "opcua-wire")]
+fn raise_wire_alarm_event(
+    address_space: &mut ::opcua::server::prelude::AddressSpace,
+    namespace: u16,
+    condition_id: &::opcua::types::NodeId,
+    condition: &OpcUaAlarmCondition,
+) -> Option<::opcua::types::ByteString> {
+    use ::opcua::server::events::event::{BaseEventType, Event};
+    use ::opcua::types::{DateTime, LocalizedText, NodeId, ObjectId, ObjectTypeId};
+
+    let two_state =
+        |flag: bool, on: &str, off: &str| LocalizedText::new("", if flag { on } else { off });
+    let mut event = BaseEventType::new(
+        NodeId::next_numeric(namespace),
+        ObjectTypeId::AlarmConditionType,
+        "AlarmConditionType",
+        "AlarmConditionType",
+        ObjectId::Server,
+        DateTime::now(),
+    )
+    .source_node(condition_id.clone())
+    .source_name(condition.source.as_str())
+    .message(LocalizedText::new("", condition.message().as_str()))
+    .severity(condition.severity);
+    event.add_property("ConditionName", "ConditionName", condition.label.as_str());
+    event.add_property("Retain", "Retain", condition.retain);
+    event.add_property(
+        "EnabledState",
+        "EnabledState",
+        two_state(true, "Enabled", "Disabled"),
+    );
+    event.add_property(
+        "ActiveState",
+        "ActiveState",
+        two_state(condition.active, "Active", "Inactive"),
+    );
+    event.add_property(
+        "AckedState",
+        "AckedState",
+        two_state(condition.acknowledged, "Acknowledged", "Unacknowledged"),
+    );
+    event.add_property(
+        "ConfirmedState",
+        "ConfirmedState",
+        two_state(condition.acknowledged, "Confirmed", "Unconfirmed"),
+    );
+    let event_node = event.raise(address_space).ok()?;
+    wire_event_id(address_space, &event_node)
+}
+
+/// Read back the EventId property of a raised event node; the event type
+/// generates it when the event is built.
+#[cfg(feature = "opcua-wire")]
+fn wire_event_id(
+    address_space: &::opcua::server::prelude::AddressSpace,
+    event_node: &::opcua::types::NodeId,
+) -> Option<::opcua::types::ByteString> {
+    use ::opcua::server::prelude::NodeBase;
+    use ::opcua::types::Variant;
+
+    address_space
+        .find_aggregates_of(event_node)?
+        .iter()
+        .filter_map(|property| address_space.find_variable_by_ref(property))
+        .find(|property| property.browse_name().name.as_ref() == "EventId")
+        .and_then(|property| address_space.get_variable_value(property.node_id()).ok())
+        .and_then(|value| match value.value {
+            Some(Variant::ByteString(event_id)) => Some(event_id),
+            _ => None,
+        })
+}
+
+/// Acknowledge/Confirm on a condition object; both acknowledge the HMI
+/// alarm, which has a single acknowledgement.
+#[cfg(feature = "opcua-wire")]
+struct WireAlarmMethod {
+    alarm_id: String,
+    acknowledge: OpcUaAlarmAcknowledge,
+    event_ids: WireAlarmEventIds,
+}
+
+#[cfg(feature = "opcua-wire")]
+impl ::opcua::server::callbacks::Method for WireAlarmMethod {
+    fn call(
+        &mut self,
+        _session_id: &::opcua::types::NodeId,
+        _session_manager: Arc<::opcua::sync::RwLock<::opcua::server::session::SessionManager>>,
+        request: &::opcua::types::CallMethodRequest,
+    ) -> Result<::opcua::types::CallMethodResult, ::opcua::types::StatusCode> {
+        use ::opcua::types::{StatusCode, Variant};
+
+        // (EventId, Comment)
+        let arguments = request.input_arguments.as_deref().unwrap_or_default();
+        if arguments.len() < 2 {
+            return Err(StatusCode::BadArgumentsMissing);
+        }
+        if arguments.len() > 2 {
+            return Err(StatusCode::BadTooManyArguments);
+        }
+        let Variant::ByteString(event_id) = &arguments[0] else {
+            return Err(StatusCode::BadTypeMismatch);
+        };
+        let current = self
+            .event_ids
+            .lock()
+            .map_err(|_| StatusCode::BadInternalError)?
+            .get(&self.alarm_id)
+            .cloned();
+        if current.as_ref() != Some(event_id) {
+            return Err(StatusCode::BadEventIdUnknown);
+        }
+        (self.acknowledge)(self.alarm_id.as_str()).map_err(|_| StatusCode::BadInvalidState)?;
+        Ok(::opcua::types::CallMethodResult {
+            status_code: StatusCode::Good,
+            input_argument_results: None,
+            input_argument_diagnostic_infos: None,
+            output_arguments: None,
+        })
+    }
+}
+
 #[cfg(feature = "opcua-wire")]
 fn compile_exposure_patterns(patterns: &[SmolStr]) -> Result<Vec<Pattern>, RuntimeError> {
     patterns
@@ -880,8 +1793,9 @@ fn build_server_endpoint(
     Ok(endpoint)
 }
 
-#[cfg(any(feature = "opcua-wire", test))]
-fn validate_security_profile(profile: &OpcUaSecurityProfile) -> Result<(), RuntimeError> {
+pub(crate) fn validate_security_profile(
+    profile: &OpcUaSecurityProfile,
+) -> Result<(), RuntimeError> {
     match (profile.policy, profile.mode) {
         (OpcUaSecurityPolicy::None, OpcUaMessageSecurityMode::None)
         | (OpcUaSecurityPolicy::Basic256Sha256, OpcUaMessageSecurityMode::Sign)
@@ -972,8 +1886,10 @@ fn to_wire_security_mode(mode: OpcUaMessageSecurityMode) -> ::opcua::types::Mess
     }
 }
 
+/// Convert to a wire variant. Structures are encoded as binary
+/// ExtensionObjects whose encoding node lives in `namespace`.
 #[cfg(feature = "opcua-wire")]
-fn to_wire_variant(value: &OpcUaVariant) -> ::opcua::types::Variant {
+pub(crate) fn to_wire_variant(value: &OpcUaVariant, namespace: u16) -> ::opcua::types::Variant {
     match value {
         OpcUaVariant::Boolean(value) => ::opcua::types::Variant::Boolean(*value),
         OpcUaVariant::Int16(value) => ::opcua::types::Variant::Int16(*value),
@@ -985,11 +1901,43 @@ fn to_wire_variant(value: &OpcUaVariant) -> ::opcua::types::Variant {
         OpcUaVariant::Float(value) => ::opcua::types::Variant::Float(*value),
         OpcUaVariant::Double(value) => ::opcua::types::Variant::Double(*value),
         OpcUaVariant::String(value) => ::opcua::types::Variant::String(value.as_str().into()),
+        OpcUaVariant::Array(array) => {
+            let values = array
+                .values
+                .iter()
+                .map(|item| to_wire_variant(item, namespace))
+                .collect::<Vec<_>>();
+            ::opcua::types::Array::new_multi(
+                wire_variant_type(&array.element),
+                values,
+                array.dimensions.clone(),
+            )
+            .map(|array| ::opcua::types::Variant::Array(Box::new(array)))
+            .unwrap_or(::opcua::types::Variant::Empty)
+        }
+        OpcUaVariant::Structure(value) => {
+            ::opcua::types::Variant::ExtensionObject(Box::new(::opcua::types::ExtensionObject {
+                node_id: wire_encoding_id(namespace, &value.type_name),
+                body: ::opcua::types::ExtensionObjectEncoding::ByteString(
+                    ::opcua::types::ByteString::from(encode_opcua_structure(value)),
+                ),
+            }))
+        }
     }
 }
 
 #[cfg(feature = "opcua-wire")]
-fn from_wire_variant(value: &::opcua::types::Variant) -> Option<OpcUaVariant> {
+pub(crate) fn from_wire_variant(value: &::opcua::types::Variant) -> Option<OpcUaVariant> {
+    from_wire_variant_with(value, &[])
+}
+
+/// Convert from a wire variant, decoding ExtensionObjects of the given
+/// structure definitions.
+#[cfg(feature = "opcua-wire")]
+fn from_wire_variant_with(
+    value: &::opcua::types::Variant,
+    definitions: &[OpcUaStructDefinition],
+) -> Option<OpcUaVariant> {
     match value {
         ::opcua::types::Variant::Boolean(value) => Some(OpcUaVariant::Boolean(*value)),
         ::opcua::types::Variant::Int16(value) => Some(OpcUaVariant::Int16(*value)),
@@ -1001,12 +1949,101 @@ fn from_wire_variant(value: &::opcua::types::Variant) -> Option<OpcUaVariant> {
         ::opcua::types::Variant::Float(value) => Some(OpcUaVariant::Float(*value)),
         ::opcua::types::Variant::Double(value) => Some(OpcUaVariant::Double(*value)),
         ::opcua::types::Variant::String(value) => Some(OpcUaVariant::String(value.to_string())),
+        ::opcua::types::Variant::Array(array) => {
+            let values = array
+                .values
+                .iter()
+                .map(|item| from_wire_variant_with(item, definitions))
+                .collect::<Option<Vec<_>>>()?;
+            let element = values.first().map(structured::variant_data_type)?;
+            let dimensions = if array.dimensions.is_empty() {
+                vec![u32::try_from(values.len()).ok()?]
+            } else {
+                array.dimensions.clone()
+            };
+            Some(OpcUaVariant::Array(OpcUaArrayValue {
+                element,
+                dimensions,
+                values,
+            }))
+        }
+        ::opcua::types::Variant::ExtensionObject(object) => {
+            let ::opcua::types::Identifier::String(id) = &object.node_id.identifier else {
+                return None;
+            };
+            let type_name = id.as_ref().strip_suffix(".DefaultBinary")?;
+            let ::opcua::types::ExtensionObjectEncoding::ByteString(body) = &object.body else {
+                return None;
+            };
+            decode_opcua_structure(definitions, type_name, body.value.as_deref()?)
+                .ok()
+                .map(OpcUaVariant::Structure)
+        }
         _ => None,
     }
 }
 
 #[cfg(feature = "opcua-wire")]
-fn opcua_status_error(status: ::opcua::types::StatusCode) -> RuntimeError {
+fn wire_variant_type(data_type: &OpcUaDataType) -> ::opcua::types::VariantTypeId {
+    use ::opcua::types::VariantTypeId;
+    match data_type {
+        OpcUaDataType::Boolean => VariantTypeId::Boolean,
+        OpcUaDataType::Int16 => VariantTypeId::Int16,
+        OpcUaDataType::Int32 => VariantTypeId::Int32,
+        OpcUaDataType::Int64 => VariantTypeId::Int64,
+        OpcUaDataType::UInt16 => VariantTypeId::UInt16,
+        OpcUaDataType::UInt32 => VariantTypeId::UInt32,
+        OpcUaDataType::UInt64 => VariantTypeId::UInt64,
+        OpcUaDataType::Float => VariantTypeId::Float,
+        OpcUaDataType::Double => VariantTypeId::Double,
+        OpcUaDataType::String => VariantTypeId::String,
+        OpcUaDataType::Structure(_) => VariantTypeId::ExtensionObject,
+        OpcUaDataType::Array { element, .. } => wire_variant_type(element),
+    }
+}
+
+/// DataType node of a mapped type; structures live in the runtime namespace.
+#[cfg(feature = "opcua-wire")]
+fn wire_data_type_id(data_type: &OpcUaDataType, namespace: u16) -> ::opcua::types::NodeId {
+    use ::opcua::types::DataTypeId;
+    match data_type {
+        OpcUaDataType::Boolean => DataTypeId::Boolean.into(),
+        OpcUaDataType::Int16 => DataTypeId::Int16.into(),
+        OpcUaDataType::Int32 => DataTypeId::Int32.into(),
+        OpcUaDataType::Int64 => DataTypeId::Int64.into(),
+        OpcUaDataType::UInt16 => DataTypeId::UInt16.into(),
+        OpcUaDataType::UInt32 => DataTypeId::UInt32.into(),
+        OpcUaDataType::UInt64 => DataTypeId::UInt64.into(),
+        OpcUaDataType::Float => DataTypeId::Float.into(),
+        OpcUaDataType::Double => DataTypeId::Double.into(),
+        OpcUaDataType::String => DataTypeId::String.into(),
+        OpcUaDataType::Structure(name) => {
+            ::opcua::types::NodeId::new(namespace, format!("DataType.{name}"))
+        }
+        OpcUaDataType::Array { element, .. } => wire_data_type_id(element, namespace),
+    }
+}
+
+#[cfg(feature = "opcua-wire")]
+fn wire_encoding_id(namespace: u16, type_name: &str) -> ::opcua::types::NodeId {
+    ::opcua::types::NodeId::new(namespace, format!("{type_name}.DefaultBinary"))
+}
+
+/// ValueRank and ArrayDimensions attributes of a mapped type.
+#[cfg(feature = "opcua-wire")]
+fn wire_value_rank(data_type: &OpcUaDataType) -> (i32, Option<Vec<u32>>) {
+    match data_type {
+        OpcUaDataType::Array { dimensions, .. } => (
+            i32::try_from(dimensions.len()).unwrap_or(1),
+            Some(dimensions.clone()),
+        ),
+        // -1: scalar.
+        _ => (-1, None),
+    }
+}
+
+#[cfg(feature = "opcua-wire")]
+pub(crate) fn opcua_status_error(status: ::opcua::types::StatusCode) -> RuntimeError {
     RuntimeError::ControlError(format!("OPC UA status: {status}").into())
 }
 
@@ -1054,6 +2091,194 @@ mod tests {
         assert!(map_iec_value(&Value::Time(crate::value::Duration::from_millis(10))).is_none());
     }
 
+    #[test]
+    fn maps_arrays_and_structures() {
+        let array = Value::Array(crate::value::ArrayValue {
+            elements: vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(4)],
+            dimensions: vec![(1, 2), (0, 1)],
+        });
+        let mapped = map_iec_value(&array).expect("array maps");
+        assert_eq!(
+            mapped.data_type,
+            OpcUaDataType::Array {
+                element: Box::new(OpcUaDataType::Int16),
+                dimensions: vec![2, 2],
+            }
+        );
+
+        let mut fields = indexmap::IndexMap::new();
+        fields.insert(SmolStr::new("speed"), Value::DInt(1200));
+        fields.insert(SmolStr::new("enabled"), Value::Bool(true));
+        let record = Value::Struct(crate::value::StructValue {
+            type_name: SmolStr::new("Recipe"),
+            fields,
+        });
+        let mapped = map_iec_value(&record).expect("struct maps");
+        assert_eq!(mapped.data_type, OpcUaDataType::Structure("Recipe".into()));
+        assert_eq!(
+            mapped.value,
+            OpcUaVariant::Structure(OpcUaStructValue {
+                type_name: "Recipe".into(),
+                fields: vec![
+                    ("speed".into(), OpcUaVariant::Int32(1200)),
+                    ("enabled".into(), OpcUaVariant::Boolean(true)),
+                ],
+            })
+        );
+
+        let mut fields = indexmap::IndexMap::new();
+        fields.insert(
+            SmolStr::new("elapsed"),
+            Value::Time(crate::value::Duration::ZERO),
+        );
+        let unmapped = Value::Struct(crate::value::StructValue {
+            type_name: SmolStr::new("Timing"),
+            fields,
+        });
+        assert!(map_iec_value(&unmapped).is_none());
+    }
+
+    #[test]
+    fn structure_binary_encoding_round_trips() {
+        let definitions = vec![OpcUaStructDefinition {
+            name: "Recipe".into(),
+            fields: vec![
+                OpcUaStructField {
+                    name: "speed".into(),
+                    data_type: OpcUaDataType::Int32,
+                },
+                OpcUaStructField {
+                    name: "label".into(),
+                    data_type: OpcUaDataType::String,
+                },
+                OpcUaStructField {
+                    name: "setpoints".into(),
+                    data_type: OpcUaDataType::Array {
+                        element: Box::new(OpcUaDataType::Int16),
+                        dimensions: vec![2],
+                    },
+                },
+            ],
+        }];
+        let value = OpcUaStructValue {
+            type_name: "Recipe".into(),
+            fields: vec![
+                ("speed".into(), OpcUaVariant::Int32(-5)),
+                ("label".into(), OpcUaVariant::String("mix".into())),
+                (
+                    "setpoints".into(),
+                    OpcUaVariant::Array(OpcUaArrayValue {
+                        element: OpcUaDataType::Int16,
+                        dimensions: vec![2],
+                        values: vec![OpcUaVariant::Int16(10), OpcUaVariant::Int16(20)],
+                    }),
+                ),
+            ],
+        };
+        let bytes = encode_opcua_structure(&value);
+        assert_eq!(&bytes[..4], &(-5i32).to_le_bytes());
+        assert_eq!(&bytes[4..11], &[3, 0, 0, 0, b'm', b'i', b'x']);
+        assert_eq!(
+            decode_opcua_structure(&definitions, "Recipe", &bytes).expect("decode"),
+            value
+        );
+        assert!(decode_opcua_structure(&definitions, "Recipe", &bytes[..6]).is_err());
+        assert!(decode_opcua_structure(&definitions, "Other", &bytes).is_err());
+    }
+
+    #[test]
+    fn type_dictionary_and_method_signatures_follow_metadata() {
+        let source = r#"
+TYPE Limits :
+STRUCT
+    low : REAL;
+    high : REAL;
+END_STRUCT
+END_TYPE
+
+TYPE Recipe :
+STRUCT
+    speed : DINT;
+    limits : Limits;
+    setpoints : ARRAY[1..3] OF INT;
+END_STRUCT
+END_TYPE
+
+FUNCTION_BLOCK Doser
+VAR
+    total : DINT;
+END_VAR
+METHOD PUBLIC Dose : DINT
+VAR_INPUT
+    amount : DINT;
+    recipe : Recipe;
+END_VAR
+VAR_OUTPUT
+    accepted : BOOL;
+END_VAR
+    Dose := total;
+END_METHOD
+END_FUNCTION_BLOCK
+
+PROGRAM Main
+VAR
+    fb : Doser;
+END_VAR
+END_PROGRAM
+"#;
+        let harness = crate::harness::TestHarness::from_source(source).expect("compile");
+        let metadata = harness.runtime().metadata_snapshot();
+        let recipe = metadata.registry().lookup("Recipe").expect("Recipe type");
+        let data_type = map_iec_type(metadata.registry(), recipe).expect("Recipe maps");
+
+        let definitions = opcua_struct_definitions(&metadata, std::slice::from_ref(&data_type));
+        let names = definitions
+            .iter()
+            .map(|definition| definition.name.to_ascii_uppercase())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["LIMITS", "RECIPE"]);
+        let schema = opcua_binary_schema("urn:test", &definitions);
+        assert!(schema.contains("TargetNamespace=\"urn:test\""), "{schema}");
+        assert!(
+            schema.contains("<opc:Field Name=\"NoOfsetpoints\" TypeName=\"opc:Int32\"/>"),
+            "{schema}"
+        );
+        assert!(schema.contains("LengthField=\"NoOfsetpoints\""), "{schema}");
+
+        let signatures = opcua_method_signatures(&metadata, "Doser");
+        assert_eq!(signatures.len(), 1);
+        let dose = &signatures[0];
+        assert_eq!(
+            dose.inputs
+                .iter()
+                .map(|argument| argument.name.to_ascii_lowercase())
+                .collect::<Vec<_>>(),
+            ["amount", "recipe"]
+        );
+        assert_eq!(dose.inputs[1].data_type, data_type);
+        assert_eq!(
+            dose.outputs
+                .iter()
+                .map(|argument| argument.data_type.clone())
+                .collect::<Vec<_>>(),
+            [OpcUaDataType::Int32, OpcUaDataType::Boolean]
+        );
+        let input_types =
+            opcua_method_input_types(&metadata, "Doser", "dose").expect("input types");
+        assert_eq!(
+            iec_value_from_opcua(metadata.registry(), input_types[0], &OpcUaVariant::Int64(7)),
+            Some(Value::DInt(7))
+        );
+        assert_eq!(
+            iec_value_from_opcua(
+                metadata.registry(),
+                input_types[0],
+                &OpcUaVariant::Int64(i64::MAX)
+            ),
+            None
+        );
+    }
+
     #[test]
     fn secure_profile_defaults_to_signed_and_encrypted_policy() {
         assert_eq!(
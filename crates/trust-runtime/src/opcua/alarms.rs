@@ -0,0 +1,203 @@
+//! OPC UA Alarms & Conditions view of the HMI alarm state machine.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::control::{acknowledge_hmi_alarm, refresh_hmi_alarms, ControlState};
+use crate::hmi::{HmiAlarmRecord, HmiAlarmResult};
+
+/// Severity reported for HMI limit alarms (OPC UA range 1..=1000).
+pub const OPCUA_ALARM_SEVERITY: u16 = 500;
+
+/// Reads the current HMI alarm view.
+pub type OpcUaAlarmView = Arc<dyn Fn() -> Option<HmiAlarmResult> + Send + Sync>;
+
+/// Acknowledges an HMI alarm by id.
+pub type OpcUaAlarmAcknowledge = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// HMI alarm feed published by the OPC UA server as AlarmConditionType
+/// events.
+#[derive(Clone)]
+pub struct OpcUaAlarmSource {
+    pub view: OpcUaAlarmView,
+    pub acknowledge: OpcUaAlarmAcknowledge,
+}
+
+impl std::fmt::Debug for OpcUaAlarmSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpcUaAlarmSource").finish_non_exhaustive()
+    }
+}
+
+impl OpcUaAlarmSource {
+    /// Feed backed by the control state shared with the web HMI, so both
+    /// see the same alarms and acknowledgements.
+    #[must_use]
+    pub fn from_control_state(state: Arc<ControlState>) -> Self {
+        let ack_state = state.clone();
+        Self {
+            view: Arc::new(move || refresh_hmi_alarms(&state, 1).ok()),
+            acknowledge: Arc::new(move |alarm_id| {
+                acknowledge_hmi_alarm(&ack_state, alarm_id).map(|_| ())
+            }),
+        }
+    }
+}
+
+/// Condition state of one HMI alarm.
+///
+/// HMI alarms have a single acknowledgement, so `AckedState` and
+/// `ConfirmedState` move together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpcUaAlarmCondition {
+    /// HMI alarm id (the widget id).
+    pub id: String,
+    /// Variable path the alarm watches.
+    pub source: String,
+    pub label: String,
+    pub active: bool,
+    pub acknowledged: bool,
+    /// Whether clients should keep the condition in their alarm list.
+    pub retain: bool,
+    pub severity: u16,
+    pub value: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub timestamp_ms: u128,
+}
+
+impl OpcUaAlarmCondition {
+    #[cfg_attr(not(feature = "opcua-wire"), allow(dead_code))]
+    fn from_record(record: &HmiAlarmRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            source: record.path.clone(),
+            label: record.label.clone(),
+            active: true,
+            acknowledged: record.acknowledged,
+            retain: true,
+            severity: OPCUA_ALARM_SEVERITY,
+            value: record.value,
+            min: record.min,
+            max: record.max,
+            timestamp_ms: record.last_change_ms,
+        }
+    }
+
+    /// Event message, e.g. `Tank level out of range (12.5 > 10)`.
+    #[must_use]
+    pub fn message(&self) -> String {
+        let limit = match (self.min, self.max) {
+            (Some(min), _) if self.value < min => format!(" ({} < {min})", self.value),
+            (_, Some(max)) if self.value > max => format!(" ({} > {max})", self.value),
+            _ => String::new(),
+        };
+        if self.active {
+            format!("{} out of range{limit}", self.label)
+        } else {
+            format!("{} back in range", self.label)
+        }
+    }
+}
+
+/// Tracks published conditions and reports the transitions of each HMI
+/// alarm view as condition events.
+#[derive(Debug, Default)]
+#[cfg_attr(not(feature = "opcua-wire"), allow(dead_code))]
+pub(super) struct AlarmConditionTracker {
+    conditions: BTreeMap<String, OpcUaAlarmCondition>,
+}
+
+#[cfg_attr(not(feature = "opcua-wire"), allow(dead_code))]
+impl AlarmConditionTracker {
+    /// Apply an alarm view and return the conditions whose state changed:
+    /// raised, acknowledged, or cleared (reported once with `retain = false`).
+    pub(super) fn update(&mut self, view: &HmiAlarmResult) -> Vec<OpcUaAlarmCondition> {
+        let mut changed = Vec::new();
+        for record in &view.active {
+            let condition = OpcUaAlarmCondition::from_record(record);
+            let is_new_state = self.conditions.get(&record.id).is_none_or(|previous| {
+                previous.acknowledged != condition.acknowledged
+                    || previous.timestamp_ms != condition.timestamp_ms
+            });
+            if is_new_state {
+                changed.push(condition.clone());
+            }
+            self.conditions.insert(record.id.clone(), condition);
+        }
+        let cleared = self
+            .conditions
+            .keys()
+            .filter(|id| !view.active.iter().any(|record| &record.id == *id))
+            .cloned()
+            .collect::<Vec<_>>();
+        for id in cleared {
+            if let Some(mut condition) = self.conditions.remove(&id) {
+                condition.active = false;
+                condition.retain = false;
+                condition.timestamp_ms = view.timestamp_ms;
+                changed.push(condition);
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, acknowledged: bool, last_change_ms: u128) -> HmiAlarmRecord {
+        HmiAlarmRecord {
+            id: id.to_string(),
+            widget_id: id.to_string(),
+            path: format!("Main.{id}"),
+            label: "Tank level".to_string(),
+            state: if acknowledged {
+                "acknowledged"
+            } else {
+                "raised"
+            },
+            acknowledged,
+            raised_at_ms: 1_000,
+            last_change_ms,
+            value: 12.5,
+            min: None,
+            max: Some(10.0),
+        }
+    }
+
+    fn view(active: Vec<HmiAlarmRecord>, timestamp_ms: u128) -> HmiAlarmResult {
+        HmiAlarmResult {
+            connected: true,
+            timestamp_ms,
+            active,
+            history: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn tracker_reports_raise_acknowledge_and_clear_once() {
+        let mut tracker = AlarmConditionTracker::default();
+        assert!(tracker.update(&view(Vec::new(), 500)).is_empty());
+
+        let raised = tracker.update(&view(vec![record("level", false, 1_000)], 1_000));
+        assert_eq!(raised.len(), 1);
+        assert!(raised[0].active && !raised[0].acknowledged && raised[0].retain);
+        assert_eq!(raised[0].message(), "Tank level out of range (12.5 > 10)");
+        assert!(tracker
+            .update(&view(vec![record("level", false, 1_000)], 1_100))
+            .is_empty());
+
+        let acked = tracker.update(&view(vec![record("level", true, 2_000)], 2_000));
+        assert_eq!(acked.len(), 1);
+        assert!(acked[0].acknowledged);
+
+        let cleared = tracker.update(&view(Vec::new(), 3_000));
+        assert_eq!(cleared.len(), 1);
+        assert!(!cleared[0].active && !cleared[0].retain);
+        assert_eq!(cleared[0].timestamp_ms, 3_000);
+        assert_eq!(cleared[0].message(), "Tank level back in range");
+        assert!(tracker.update(&view(Vec::new(), 4_000)).is_empty());
+    }
+}
@@ -0,0 +1,718 @@
+//! Structured OPC UA types: STRUCT/ARRAY mapping, the OPC Binary type
+//! dictionary, structure body encoding, and function block method signatures.
+
+use smol_str::SmolStr;
+use trust_hir::symbols::ParamDirection;
+use trust_hir::types::{Type, TypeRegistry};
+use trust_hir::TypeId;
+
+use crate::error::RuntimeError;
+use crate::eval::MethodDef;
+use crate::runtime::{is_implicit_param, method_tables, RuntimeMetadata};
+use crate::value::{ArrayValue, StructValue, Value};
+
+use super::{OpcUaDataType, OpcUaVariant};
+
+/// Nesting limit for structure definitions and base type chains.
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpcUaArrayValue {
+    pub element: OpcUaDataType,
+    pub dimensions: Vec<u32>,
+    /// Elements in row-major order.
+    pub values: Vec<OpcUaVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpcUaStructValue {
+    pub type_name: SmolStr,
+    pub fields: Vec<(SmolStr, OpcUaVariant)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcUaStructField {
+    pub name: SmolStr,
+    pub data_type: OpcUaDataType,
+}
+
+/// A STRUCT type as published in the server's type dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcUaStructDefinition {
+    pub name: SmolStr,
+    pub fields: Vec<OpcUaStructField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcUaArgument {
+    pub name: SmolStr,
+    pub data_type: OpcUaDataType,
+}
+
+/// A function block METHOD exposed as an OPC UA Method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcUaMethodSignature {
+    pub name: SmolStr,
+    /// VAR_INPUT and VAR_IN_OUT parameters in declaration order.
+    pub inputs: Vec<OpcUaArgument>,
+    /// Method result (named `Result`), then VAR_OUTPUT and VAR_IN_OUT.
+    pub outputs: Vec<OpcUaArgument>,
+}
+
+pub(super) fn map_array(value: &ArrayValue) -> Option<(OpcUaDataType, OpcUaVariant)> {
+    let mut element = None;
+    let mut values = Vec::with_capacity(value.elements.len());
+    for item in &value.elements {
+        let mapped = super::map_iec_value(item)?;
+        if matches!(mapped.data_type, OpcUaDataType::Array { .. }) {
+            return None;
+        }
+        match &element {
+            None => element = Some(mapped.data_type),
+            Some(existing) if *existing == mapped.data_type => {}
+            Some(_) => return None,
+        }
+        values.push(mapped.value);
+    }
+    let element = element?;
+    let dimensions = value
+        .dimensions
+        .iter()
+        .map(|(lower, upper)| u32::try_from(upper - lower + 1).ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some((
+        OpcUaDataType::Array {
+            element: Box::new(element.clone()),
+            dimensions: dimensions.clone(),
+        },
+        OpcUaVariant::Array(OpcUaArrayValue {
+            element,
+            dimensions,
+            values,
+        }),
+    ))
+}
+
+pub(super) fn map_struct(value: &StructValue) -> Option<(OpcUaDataType, OpcUaVariant)> {
+    let fields = value
+        .fields
+        .iter()
+        .map(|(name, field)| Some((name.clone(), super::map_iec_value(field)?.value)))
+        .collect::<Option<Vec<_>>>()?;
+    Some((
+        OpcUaDataType::Structure(value.type_name.clone()),
+        OpcUaVariant::Structure(OpcUaStructValue {
+            type_name: value.type_name.clone(),
+            fields,
+        }),
+    ))
+}
+
+/// Data type carried by a mapped value.
+#[cfg_attr(not(feature = "opcua-wire"), allow(dead_code))]
+pub(super) fn variant_data_type(value: &OpcUaVariant) -> OpcUaDataType {
+    match value {
+        OpcUaVariant::Boolean(_) => OpcUaDataType::Boolean,
+        OpcUaVariant::Int16(_) => OpcUaDataType::Int16,
+        OpcUaVariant::Int32(_) => OpcUaDataType::Int32,
+        OpcUaVariant::Int64(_) => OpcUaDataType::Int64,
+        OpcUaVariant::UInt16(_) => OpcUaDataType::UInt16,
+        OpcUaVariant::UInt32(_) => OpcUaDataType::UInt32,
+        OpcUaVariant::UInt64(_) => OpcUaDataType::UInt64,
+        OpcUaVariant::Float(_) => OpcUaDataType::Float,
+        OpcUaVariant::Double(_) => OpcUaDataType::Double,
+        OpcUaVariant::String(_) => OpcUaDataType::String,
+        OpcUaVariant::Array(array) => OpcUaDataType::Array {
+            element: Box::new(array.element.clone()),
+            dimensions: array.dimensions.clone(),
+        },
+        OpcUaVariant::Structure(value) => OpcUaDataType::Structure(value.type_name.clone()),
+    }
+}
+
+/// OPC UA data type of an IEC type, following `map_iec_value`.
+#[must_use]
+pub fn map_iec_type(registry: &TypeRegistry, type_id: TypeId) -> Option<OpcUaDataType> {
+    map_type(registry, type_id, 0)
+}
+
+fn map_type(registry: &TypeRegistry, type_id: TypeId, depth: usize) -> Option<OpcUaDataType> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let data_type = match registry.get(type_id)? {
+        Type::Bool => OpcUaDataType::Boolean,
+        Type::SInt | Type::Int => OpcUaDataType::Int16,
+        Type::DInt => OpcUaDataType::Int32,
+        Type::LInt => OpcUaDataType::Int64,
+        Type::USInt | Type::Byte | Type::UInt | Type::Word => OpcUaDataType::UInt16,
+        Type::UDInt | Type::DWord => OpcUaDataType::UInt32,
+        Type::ULInt | Type::LWord => OpcUaDataType::UInt64,
+        Type::Real => OpcUaDataType::Float,
+        Type::LReal => OpcUaDataType::Double,
+        Type::String { .. } | Type::WString { .. } | Type::Char | Type::WChar => {
+            OpcUaDataType::String
+        }
+        Type::Array {
+            element,
+            dimensions,
+        } => {
+            let element = map_type(registry, *element, depth + 1)?;
+            if matches!(element, OpcUaDataType::Array { .. }) {
+                return None;
+            }
+            OpcUaDataType::Array {
+                element: Box::new(element),
+                dimensions: dimensions
+                    .iter()
+                    .map(|(lower, upper)| u32::try_from(upper - lower + 1).ok())
+                    .collect::<Option<Vec<_>>>()?,
+            }
+        }
+        Type::Struct { name, fields } => {
+            // Only structures whose fields all map are published.
+            for field in fields {
+                map_type(registry, field.type_id, depth + 1)?;
+            }
+            OpcUaDataType::Structure(name.clone())
+        }
+        Type::Subrange { base, .. } => map_type(registry, *base, depth + 1)?,
+        Type::Alias { target, .. } => map_type(registry, *target, depth + 1)?,
+        _ => return None,
+    };
+    Some(data_type)
+}
+
+/// Structure definitions used by `roots`, dependencies first.
+///
+/// This is the content of the server's type dictionary; it is generated from
+/// the STRUCT declarations in the runtime metadata.
+#[must_use]
+pub fn opcua_struct_definitions(
+    metadata: &RuntimeMetadata,
+    roots: &[OpcUaDataType],
+) -> Vec<OpcUaStructDefinition> {
+    let mut out = Vec::new();
+    for root in roots {
+        collect_definitions(metadata.registry(), root, &mut out, 0);
+    }
+    out
+}
+
+fn collect_definitions(
+    registry: &TypeRegistry,
+    data_type: &OpcUaDataType,
+    out: &mut Vec<OpcUaStructDefinition>,
+    depth: usize,
+) {
+    if depth > MAX_DEPTH {
+        return;
+    }
+    let name = match data_type {
+        OpcUaDataType::Array { element, .. } => {
+            collect_definitions(registry, element, out, depth + 1);
+            return;
+        }
+        OpcUaDataType::Structure(name) => name,
+        _ => return,
+    };
+    if out.iter().any(|def| def.name.eq_ignore_ascii_case(name)) {
+        return;
+    }
+    let Some(Type::Struct { name, fields }) = registry.lookup(name).and_then(|id| registry.get(id))
+    else {
+        return;
+    };
+    let Some(fields) = fields
+        .iter()
+        .map(|field| {
+            Some(OpcUaStructField {
+                name: field.name.clone(),
+                data_type: map_iec_type(registry, field.type_id)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+    else {
+        return;
+    };
+    for field in &fields {
+        collect_definitions(registry, &field.data_type, out, depth + 1);
+    }
+    out.push(OpcUaStructDefinition {
+        name: name.clone(),
+        fields,
+    });
+}
+
+/// OPC Binary type dictionary (OPC UA Part 3, Annex D) for `definitions`.
+#[must_use]
+pub fn opcua_binary_schema(namespace_uri: &str, definitions: &[OpcUaStructDefinition]) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str(&format!(
+        "<opc:TypeDictionary xmlns:opc=\"http://opcfoundation.org/BinarySchema/\" \
+         xmlns:ua=\"http://opcfoundation.org/UA/\" xmlns:tns=\"{ns}\" \
+         DefaultByteOrder=\"LittleEndian\" TargetNamespace=\"{ns}\">\n",
+        ns = xml_escape(namespace_uri)
+    ));
+    xml.push_str("  <opc:Import Namespace=\"http://opcfoundation.org/UA/\"/>\n");
+    for def in definitions {
+        xml.push_str(&format!(
+            "  <opc:StructuredType Name=\"{}\" BaseType=\"ua:ExtensionObject\">\n",
+            xml_escape(&def.name)
+        ));
+        for field in &def.fields {
+            let name = xml_escape(&field.name);
+            match &field.data_type {
+                OpcUaDataType::Array { element, .. } => {
+                    xml.push_str(&format!(
+                        "    <opc:Field Name=\"NoOf{name}\" TypeName=\"opc:Int32\"/>\n"
+                    ));
+                    xml.push_str(&format!(
+                        "    <opc:Field Name=\"{name}\" TypeName=\"{}\" LengthField=\"NoOf{name}\"/>\n",
+                        schema_type_name(element)
+                    ));
+                }
+                other => xml.push_str(&format!(
+                    "    <opc:Field Name=\"{name}\" TypeName=\"{}\"/>\n",
+                    schema_type_name(other)
+                )),
+            }
+        }
+        xml.push_str("  </opc:StructuredType>\n");
+    }
+    xml.push_str("</opc:TypeDictionary>\n");
+    xml
+}
+
+fn schema_type_name(data_type: &OpcUaDataType) -> String {
+    match data_type {
+        OpcUaDataType::Boolean => "opc:Boolean".into(),
+        OpcUaDataType::Int16 => "opc:Int16".into(),
+        OpcUaDataType::Int32 => "opc:Int32".into(),
+        OpcUaDataType::Int64 => "opc:Int64".into(),
+        OpcUaDataType::UInt16 => "opc:UInt16".into(),
+        OpcUaDataType::UInt32 => "opc:UInt32".into(),
+        OpcUaDataType::UInt64 => "opc:UInt64".into(),
+        OpcUaDataType::Float => "opc:Float".into(),
+        OpcUaDataType::Double => "opc:Double".into(),
+        OpcUaDataType::String => "opc:String".into(),
+        OpcUaDataType::Structure(name) => format!("tns:{}", xml_escape(name)),
+        OpcUaDataType::Array { element, .. } => schema_type_name(element),
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Encode a structure body with the OPC UA binary encoding (Part 6, 5.2):
+/// fields in order, arrays as an Int32 length followed by the elements.
+#[must_use]
+pub fn encode_opcua_structure(value: &OpcUaStructValue) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (_, field) in &value.fields {
+        encode_variant(field, &mut out);
+    }
+    out
+}
+
+fn encode_variant(value: &OpcUaVariant, out: &mut Vec<u8>) {
+    match value {
+        OpcUaVariant::Boolean(value) => out.push(u8::from(*value)),
+        OpcUaVariant::Int16(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::Int32(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::Int64(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::UInt16(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::UInt32(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::UInt64(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::Float(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::Double(value) => out.extend_from_slice(&value.to_le_bytes()),
+        OpcUaVariant::String(value) => {
+            let len = i32::try_from(value.len()).unwrap_or(i32::MAX);
+            out.extend_from_slice(&len.to_le_bytes());
+            out.extend_from_slice(value.as_bytes());
+        }
+        OpcUaVariant::Array(array) => {
+            let len = i32::try_from(array.values.len()).unwrap_or(i32::MAX);
+            out.extend_from_slice(&len.to_le_bytes());
+            for item in &array.values {
+                encode_variant(item, out);
+            }
+        }
+        OpcUaVariant::Structure(value) => {
+            for (_, field) in &value.fields {
+                encode_variant(field, out);
+            }
+        }
+    }
+}
+
+/// Decode a structure body produced by `encode_opcua_structure`.
+pub fn decode_opcua_structure(
+    definitions: &[OpcUaStructDefinition],
+    type_name: &str,
+    bytes: &[u8],
+) -> Result<OpcUaStructValue, RuntimeError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.structure(definitions, type_name, 0)?;
+    if reader.pos != bytes.len() {
+        return Err(decode_error(format!(
+            "{} trailing byte(s) after '{type_name}'",
+            bytes.len() - reader.pos
+        )));
+    }
+    Ok(value)
+}
+
+fn decode_error(message: impl AsRef<str>) -> RuntimeError {
+    RuntimeError::ControlError(format!("OPC UA structure: {}", message.as_ref()).into())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], RuntimeError> {
+        let chunk = self
+            .bytes
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| decode_error("unexpected end of body"))?;
+        self.pos += N;
+        let mut out = [0u8; N];
+        out.copy_from_slice(chunk);
+        Ok(out)
+    }
+
+    fn length(&mut self) -> Result<usize, RuntimeError> {
+        let len = i32::from_le_bytes(self.take()?);
+        // -1 encodes a null string/array.
+        Ok(usize::try_from(len).unwrap_or(0))
+    }
+
+    fn structure(
+        &mut self,
+        definitions: &[OpcUaStructDefinition],
+        type_name: &str,
+        depth: usize,
+    ) -> Result<OpcUaStructValue, RuntimeError> {
+        if depth > MAX_DEPTH {
+            return Err(decode_error("structure nesting too deep"));
+        }
+        let def = definitions
+            .iter()
+            .find(|def| def.name.eq_ignore_ascii_case(type_name))
+            .ok_or_else(|| decode_error(format!("unknown structure type '{type_name}'")))?;
+        let mut fields = Vec::with_capacity(def.fields.len());
+        for field in &def.fields {
+            fields.push((
+                field.name.clone(),
+                self.value(definitions, &field.data_type, depth)?,
+            ));
+        }
+        Ok(OpcUaStructValue {
+            type_name: def.name.clone(),
+            fields,
+        })
+    }
+
+    fn value(
+        &mut self,
+        definitions: &[OpcUaStructDefinition],
+        data_type: &OpcUaDataType,
+        depth: usize,
+    ) -> Result<OpcUaVariant, RuntimeError> {
+        Ok(match data_type {
+            OpcUaDataType::Boolean => OpcUaVariant::Boolean(self.take::<1>()?[0] != 0),
+            OpcUaDataType::Int16 => OpcUaVariant::Int16(i16::from_le_bytes(self.take()?)),
+            OpcUaDataType::Int32 => OpcUaVariant::Int32(i32::from_le_bytes(self.take()?)),
+            OpcUaDataType::Int64 => OpcUaVariant::Int64(i64::from_le_bytes(self.take()?)),
+            OpcUaDataType::UInt16 => OpcUaVariant::UInt16(u16::from_le_bytes(self.take()?)),
+            OpcUaDataType::UInt32 => OpcUaVariant::UInt32(u32::from_le_bytes(self.take()?)),
+            OpcUaDataType::UInt64 => OpcUaVariant::UInt64(u64::from_le_bytes(self.take()?)),
+            OpcUaDataType::Float => OpcUaVariant::Float(f32::from_le_bytes(self.take()?)),
+            OpcUaDataType::Double => OpcUaVariant::Double(f64::from_le_bytes(self.take()?)),
+            OpcUaDataType::String => {
+                let len = self.length()?;
+                let bytes = self
+                    .bytes
+                    .get(self.pos..self.pos + len)
+                    .ok_or_else(|| decode_error("unexpected end of body"))?;
+                self.pos += len;
+                let text = std::str::from_utf8(bytes)
+                    .map_err(|_| decode_error("string is not valid UTF-8"))?;
+                OpcUaVariant::String(text.to_string())
+            }
+            OpcUaDataType::Array {
+                element,
+                dimensions,
+            } => {
+                let len = self.length()?;
+                let expected = dimensions
+                    .iter()
+                    .map(|dim| *dim as usize)
+                    .product::<usize>();
+                if len != expected {
+                    return Err(decode_error(format!(
+                        "array length {len} does not match {expected}"
+                    )));
+                }
+                let values = (0..len)
+                    .map(|_| self.value(definitions, element, depth + 1))
+                    .collect::<Result<Vec<_>, _>>()?;
+                OpcUaVariant::Array(OpcUaArrayValue {
+                    element: (**element).clone(),
+                    dimensions: dimensions.clone(),
+                    values,
+                })
+            }
+            OpcUaDataType::Structure(name) => {
+                OpcUaVariant::Structure(self.structure(definitions, name, depth + 1)?)
+            }
+        })
+    }
+}
+
+/// Convert an OPC UA value to an IEC value of `type_id` (method inputs).
+/// Integers convert when they fit the target type.
+#[must_use]
+pub fn iec_value_from_opcua(
+    registry: &TypeRegistry,
+    type_id: TypeId,
+    value: &OpcUaVariant,
+) -> Option<Value> {
+    from_opcua(registry, type_id, value, 0)
+}
+
+fn from_opcua(
+    registry: &TypeRegistry,
+    type_id: TypeId,
+    value: &OpcUaVariant,
+    depth: usize,
+) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let int = || -> Option<i128> {
+        Some(match value {
+            OpcUaVariant::Int16(value) => i128::from(*value),
+            OpcUaVariant::Int32(value) => i128::from(*value),
+            OpcUaVariant::Int64(value) => i128::from(*value),
+            OpcUaVariant::UInt16(value) => i128::from(*value),
+            OpcUaVariant::UInt32(value) => i128::from(*value),
+            OpcUaVariant::UInt64(value) => i128::from(*value),
+            _ => return None,
+        })
+    };
+    let text = || match value {
+        OpcUaVariant::String(text) => Some(text.as_str()),
+        _ => None,
+    };
+    Some(match registry.get(type_id)? {
+        Type::Bool => match value {
+            OpcUaVariant::Boolean(flag) => Value::Bool(*flag),
+            _ => return None,
+        },
+        Type::SInt => Value::SInt(int()?.try_into().ok()?),
+        Type::Int => Value::Int(int()?.try_into().ok()?),
+        Type::DInt => Value::DInt(int()?.try_into().ok()?),
+        Type::LInt => Value::LInt(int()?.try_into().ok()?),
+        Type::USInt => Value::USInt(int()?.try_into().ok()?),
+        Type::UInt => Value::UInt(int()?.try_into().ok()?),
+        Type::UDInt => Value::UDInt(int()?.try_into().ok()?),
+        Type::ULInt => Value::ULInt(int()?.try_into().ok()?),
+        Type::Byte => Value::Byte(int()?.try_into().ok()?),
+        Type::Word => Value::Word(int()?.try_into().ok()?),
+        Type::DWord => Value::DWord(int()?.try_into().ok()?),
+        Type::LWord => Value::LWord(int()?.try_into().ok()?),
+        Type::Real => match value {
+            OpcUaVariant::Float(value) => Value::Real(*value),
+            _ => return None,
+        },
+        Type::LReal => match value {
+            OpcUaVariant::Double(value) => Value::LReal(*value),
+            OpcUaVariant::Float(value) => Value::LReal(f64::from(*value)),
+            _ => return None,
+        },
+        Type::String { max_len } => {
+            let text = text()?;
+            if max_len.is_some_and(|max| text.len() > max as usize) {
+                return None;
+            }
+            Value::String(SmolStr::new(text))
+        }
+        Type::WString { max_len } => {
+            let text = text()?;
+            if max_len.is_some_and(|max| text.encode_utf16().count() > max as usize) {
+                return None;
+            }
+            Value::WString(text.to_string())
+        }
+        Type::Char => {
+            let mut chars = text()?.chars();
+            let ch = chars.next().filter(char::is_ascii)?;
+            if chars.next().is_some() {
+                return None;
+            }
+            Value::Char(ch as u8)
+        }
+        Type::WChar => {
+            let mut units = text()?.encode_utf16();
+            let unit = units.next()?;
+            if units.next().is_some() {
+                return None;
+            }
+            Value::WChar(unit)
+        }
+        Type::Array {
+            element,
+            dimensions,
+        } => {
+            let OpcUaVariant::Array(array) = value else {
+                return None;
+            };
+            let expected = dimensions
+                .iter()
+                .map(|(lower, upper)| usize::try_from(upper - lower + 1).ok())
+                .product::<Option<usize>>()?;
+            if array.values.len() != expected {
+                return None;
+            }
+            Value::Array(ArrayValue {
+                elements: array
+                    .values
+                    .iter()
+                    .map(|item| from_opcua(registry, *element, item, depth + 1))
+                    .collect::<Option<Vec<_>>>()?,
+                dimensions: dimensions.clone(),
+            })
+        }
+        Type::Struct { name, fields } => {
+            let OpcUaVariant::Structure(structure) = value else {
+                return None;
+            };
+            let mut out = indexmap::IndexMap::new();
+            for field in fields {
+                let (_, item) = structure
+                    .fields
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&field.name))?;
+                out.insert(
+                    field.name.clone(),
+                    from_opcua(registry, field.type_id, item, depth + 1)?,
+                );
+            }
+            Value::Struct(StructValue {
+                type_name: name.clone(),
+                fields: out,
+            })
+        }
+        Type::Subrange { base, lower, upper } => {
+            let value = from_opcua(registry, *base, value, depth + 1)?;
+            let number = match &value {
+                Value::SInt(v) => i64::from(*v),
+                Value::Int(v) => i64::from(*v),
+                Value::DInt(v) => i64::from(*v),
+                Value::LInt(v) => *v,
+                Value::USInt(v) => i64::from(*v),
+                Value::UInt(v) => i64::from(*v),
+                Value::UDInt(v) => i64::from(*v),
+                Value::ULInt(v) => i64::try_from(*v).ok()?,
+                _ => return None,
+            };
+            if number < *lower || number > *upper {
+                return None;
+            }
+            value
+        }
+        Type::Alias { target, .. } => from_opcua(registry, *target, value, depth + 1)?,
+        _ => return None,
+    })
+}
+
+/// OPC UA method signatures for the METHODs of function block `type_name`,
+/// including inherited ones. Methods with unmappable parameters are skipped.
+#[must_use]
+pub fn opcua_method_signatures(
+    metadata: &RuntimeMetadata,
+    type_name: &str,
+) -> Vec<OpcUaMethodSignature> {
+    let mut seen = Vec::<&SmolStr>::new();
+    let mut out = Vec::new();
+    for method in method_tables(metadata.function_blocks(), metadata.classes(), type_name)
+        .into_iter()
+        .flatten()
+    {
+        // Overrides in derived types shadow their base methods.
+        if seen
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(&method.name))
+        {
+            continue;
+        }
+        seen.push(&method.name);
+        if let Some(signature) = method_signature(metadata.registry(), method) {
+            out.push(signature);
+        }
+    }
+    out
+}
+
+fn method_signature(registry: &TypeRegistry, method: &MethodDef) -> Option<OpcUaMethodSignature> {
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    if let Some(return_type) = method.return_type {
+        outputs.push(OpcUaArgument {
+            name: SmolStr::new("Result"),
+            data_type: map_iec_type(registry, return_type)?,
+        });
+    }
+    for param in &method.params {
+        if is_implicit_param(&param.name) {
+            continue;
+        }
+        let argument = OpcUaArgument {
+            name: param.name.clone(),
+            data_type: map_iec_type(registry, param.type_id)?,
+        };
+        match param.direction {
+            ParamDirection::In => inputs.push(argument),
+            ParamDirection::Out => outputs.push(argument),
+            ParamDirection::InOut => {
+                inputs.push(argument.clone());
+                outputs.push(argument);
+            }
+        }
+    }
+    Some(OpcUaMethodSignature {
+        name: method.name.clone(),
+        inputs,
+        outputs,
+    })
+}
+
+/// Parameter types of a METHOD, in the order of `OpcUaMethodSignature::inputs`.
+#[must_use]
+pub fn opcua_method_input_types(
+    metadata: &RuntimeMetadata,
+    type_name: &str,
+    method: &str,
+) -> Option<Vec<TypeId>> {
+    let def = method_tables(metadata.function_blocks(), metadata.classes(), type_name)
+        .into_iter()
+        .flatten()
+        .find(|candidate| candidate.name.eq_ignore_ascii_case(method))?;
+    Some(
+        def.params
+            .iter()
+            .filter(|param| !is_implicit_param(&param.name))
+            .filter(|param| !matches!(param.direction, ParamDirection::Out))
+            .map(|param| param.type_id)
+            .collect(),
+    )
+}
@@ -10,20 +10,39 @@ use indexmap::IndexMap;
 use smol_str::SmolStr;
 
 use crate::error::RuntimeError;
-use crate::runtime::RetainSnapshot;
+use crate::runtime::{RetainMigrationReport, RetainSnapshot};
 use crate::value::{
     ArrayValue, DateTimeValue, DateValue, Duration, EnumValue, LDateTimeValue, LDateValue,
     LTimeOfDayValue, StructValue, TimeOfDayValue, Value,
 };
+use crate::watchdog::FaultInfo;
 use crate::Runtime;
 
 const RETAIN_MAGIC: &[u8; 4] = b"STRN";
-const RETAIN_VERSION: u16 = 1;
+const RETAIN_VERSION: u16 = 2;
+const RETAIN_VERSION_LEGACY: u16 = 1;
 
 /// Retain storage backend.
 pub trait RetainStore: Send {
     fn load(&self) -> Result<RetainSnapshot, RuntimeError>;
     fn store(&self, snapshot: &RetainSnapshot) -> Result<(), RuntimeError>;
+
+    /// Load the latest usable snapshot, reporting lost or damaged copies.
+    fn recover(&self) -> Result<RetainLoad, RuntimeError> {
+        Ok(RetainLoad {
+            snapshot: self.load()?,
+            fault: None,
+        })
+    }
+}
+
+/// Result of loading retained values at startup.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetainLoad {
+    pub snapshot: RetainSnapshot,
+    /// Set when the latest snapshot was unusable and an older copy (or a
+    /// cold start) was used instead.
+    pub fault: Option<FaultInfo>,
 }
 
 pub struct RetainManager {
@@ -32,6 +51,8 @@ pub struct RetainManager {
     last_save: Duration,
     dirty: bool,
     last_snapshot: Option<RetainSnapshot>,
+    fault: Option<FaultInfo>,
+    migration: Option<RetainMigrationReport>,
 }
 
 impl Default for RetainManager {
@@ -42,6 +63,8 @@ impl Default for RetainManager {
             last_save: Duration::ZERO,
             dirty: false,
             last_snapshot: None,
+            fault: None,
+            migration: None,
         }
     }
 }
@@ -54,6 +77,8 @@ impl std::fmt::Debug for RetainManager {
             .field("last_save", &self.last_save)
             .field("dirty", &self.dirty)
             .field("has_snapshot", &self.last_snapshot.is_some())
+            .field("fault", &self.fault)
+            .field("migration", &self.migration)
             .finish()
     }
 }
@@ -70,6 +95,8 @@ impl RetainManager {
         self.last_save = now;
         self.dirty = false;
         self.last_snapshot = None;
+        self.fault = None;
+        self.migration = None;
     }
 
     pub fn set_save_interval(&mut self, interval: Option<Duration>) {
@@ -84,11 +111,27 @@ impl RetainManager {
         self.store.is_some()
     }
 
-    pub fn load(&self) -> Result<RetainSnapshot, RuntimeError> {
+    pub fn load(&mut self) -> Result<RetainSnapshot, RuntimeError> {
         let Some(store) = self.store.as_ref() else {
             return Ok(RetainSnapshot::default());
         };
-        store.load()
+        let RetainLoad { snapshot, fault } = store.recover()?;
+        self.fault = fault;
+        Ok(snapshot)
+    }
+
+    /// Fault recorded by the last load, if retained values were lost.
+    pub fn fault(&self) -> Option<&FaultInfo> {
+        self.fault.as_ref()
+    }
+
+    pub fn set_migration(&mut self, report: RetainMigrationReport) {
+        self.migration = Some(report);
+    }
+
+    /// Migration report of the last applied snapshot.
+    pub fn migration(&self) -> Option<&RetainMigrationReport> {
+        self.migration.as_ref()
     }
 
     pub fn should_save(&self, now: Duration) -> bool {
@@ -133,6 +176,10 @@ impl RetainSnapshot {
 }
 
 /// File-based retain store.
+///
+/// Snapshots are written to `<path>.tmp`, flushed, and renamed over `<path>`;
+/// the previous snapshot is kept as `<path>.bak` so a damaged latest copy
+/// can fall back to the last good one.
 #[derive(Debug, Clone)]
 pub struct FileRetainStore {
     path: PathBuf,
@@ -144,11 +191,33 @@ impl FileRetainStore {
         Self { path: path.into() }
     }
 
-    fn write_bytes(path: &Path, bytes: &[u8]) -> Result<(), RuntimeError> {
-        let mut file = fs::File::create(path)
-            .map_err(|err| RuntimeError::RetainStore(format!("create {path:?}: {err}").into()))?;
+    /// Path of the previous snapshot copy.
+    #[must_use]
+    pub fn backup_path(&self) -> PathBuf {
+        sibling_path(&self.path, "bak")
+    }
+
+    fn write_bytes(&self, bytes: &[u8]) -> Result<(), RuntimeError> {
+        let path = self.path.as_path();
+        let temp = sibling_path(path, "tmp");
+        let mut file = fs::File::create(&temp)
+            .map_err(|err| RuntimeError::RetainStore(format!("create {temp:?}: {err}").into()))?;
         file.write_all(bytes)
-            .map_err(|err| RuntimeError::RetainStore(format!("write {path:?}: {err}").into()))
+            .map_err(|err| RuntimeError::RetainStore(format!("write {temp:?}: {err}").into()))?;
+        file.sync_all()
+            .map_err(|err| RuntimeError::RetainStore(format!("sync {temp:?}: {err}").into()))?;
+        drop(file);
+        if path.exists() {
+            let backup = self.backup_path();
+            fs::rename(path, &backup).map_err(|err| {
+                RuntimeError::RetainStore(format!("rename {path:?} to {backup:?}: {err}").into())
+            })?;
+        }
+        fs::rename(&temp, path).map_err(|err| {
+            RuntimeError::RetainStore(format!("rename {temp:?} to {path:?}: {err}").into())
+        })?;
+        sync_parent_dir(path);
+        Ok(())
     }
 
     fn read_bytes(path: &Path) -> Result<Vec<u8>, RuntimeError> {
@@ -163,28 +232,103 @@ impl FileRetainStore {
 
 impl RetainStore for FileRetainStore {
     fn load(&self) -> Result<RetainSnapshot, RuntimeError> {
-        if !self.path.exists() {
-            return Ok(RetainSnapshot::default());
-        }
-        let bytes = Self::read_bytes(&self.path)?;
-        decode_snapshot(&bytes)
+        Ok(self.recover()?.snapshot)
     }
 
     fn store(&self, snapshot: &RetainSnapshot) -> Result<(), RuntimeError> {
         let bytes = encode_snapshot(snapshot)?;
-        Self::write_bytes(&self.path, &bytes)
+        self.write_bytes(&bytes)
+    }
+
+    fn recover(&self) -> Result<RetainLoad, RuntimeError> {
+        let latest = match self.read_snapshot(&self.path) {
+            None => None,
+            Some(Ok(snapshot)) => {
+                return Ok(RetainLoad {
+                    snapshot,
+                    fault: None,
+                })
+            }
+            Some(Err(err)) => Some(err),
+        };
+        let backup_path = self.backup_path();
+        let backup = self.read_snapshot(&backup_path);
+        let reason = match (latest, backup) {
+            (None, None) => return Ok(RetainLoad::default()),
+            (latest, Some(Ok(snapshot))) => {
+                let cause = match latest {
+                    Some(err) => format!("retain snapshot {:?} is damaged ({err})", self.path),
+                    None => format!("retain snapshot {:?} is missing", self.path),
+                };
+                return Ok(RetainLoad {
+                    snapshot,
+                    fault: Some(FaultInfo {
+                        reason: format!("{cause}; restored previous copy {backup_path:?}").into(),
+                    }),
+                });
+            }
+            (Some(err), None) => format!("retain snapshot {:?} is damaged ({err})", self.path),
+            (_, Some(Err(err))) => format!(
+                "retain snapshot {:?} and previous copy are damaged ({err})",
+                self.path
+            ),
+        };
+        Ok(RetainLoad {
+            snapshot: RetainSnapshot::default(),
+            fault: Some(FaultInfo {
+                reason: format!("{reason}; retained values lost").into(),
+            }),
+        })
     }
 }
 
+impl FileRetainStore {
+    /// `None` when the file does not exist.
+    fn read_snapshot(&self, path: &Path) -> Option<Result<RetainSnapshot, RuntimeError>> {
+        if !path.exists() {
+            return None;
+        }
+        Some(Self::read_bytes(path).and_then(|bytes| decode_snapshot(&bytes)))
+    }
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Flush the directory entry after a rename; best effort where unsupported.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let parent = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
 fn encode_snapshot(snapshot: &RetainSnapshot) -> Result<Vec<u8>, RuntimeError> {
-    let mut out = Vec::new();
-    out.extend_from_slice(RETAIN_MAGIC);
-    out.extend_from_slice(&RETAIN_VERSION.to_le_bytes());
-    out.extend_from_slice(&(snapshot.values.len() as u32).to_le_bytes());
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&(snapshot.values.len() as u32).to_le_bytes());
     for (name, value) in &snapshot.values {
-        encode_string(&mut out, name.as_str());
-        encode_value(&mut out, value)?;
+        encode_string(&mut payload, name.as_str());
+        encode_value(&mut payload, value)?;
     }
+    let mut out = Vec::with_capacity(payload.len() + 14);
+    out.extend_from_slice(RETAIN_MAGIC);
+    out.extend_from_slice(&RETAIN_VERSION.to_le_bytes());
+    out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    out.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    out.extend_from_slice(&payload);
     Ok(out)
 }
 
@@ -195,16 +339,40 @@ fn decode_snapshot(bytes: &[u8]) -> Result<RetainSnapshot, RuntimeError> {
         return Err(RuntimeError::RetainStore("invalid retain magic".into()));
     }
     let version = reader.read_u16()?;
-    if version != RETAIN_VERSION {
-        return Err(RuntimeError::RetainStore(
+    match version {
+        RETAIN_VERSION => {
+            let len = reader.read_u32()? as usize;
+            let expected = reader.read_u32()?;
+            let payload = reader.read_bytes(len)?;
+            if reader.offset != bytes.len() {
+                return Err(RuntimeError::RetainStore(
+                    "trailing bytes after retain snapshot".into(),
+                ));
+            }
+            let actual = crc32fast::hash(payload);
+            if actual != expected {
+                return Err(RuntimeError::RetainStore(
+                    format!(
+                        "retain checksum mismatch (expected {expected:#010x}, got {actual:#010x})"
+                    )
+                    .into(),
+                ));
+            }
+            decode_payload(&mut RetainReader::new(payload))
+        }
+        RETAIN_VERSION_LEGACY => decode_payload(&mut reader),
+        _ => Err(RuntimeError::RetainStore(
             format!("unsupported retain version {version}").into(),
-        ));
+        )),
     }
+}
+
+fn decode_payload(reader: &mut RetainReader<'_>) -> Result<RetainSnapshot, RuntimeError> {
     let count = reader.read_u32()? as usize;
     let mut values = IndexMap::new();
     for _ in 0..count {
         let name = SmolStr::new(reader.read_string()?);
-        let value = decode_value(&mut reader)?;
+        let value = decode_value(reader)?;
         values.insert(name, value);
     }
     Ok(RetainSnapshot { values })
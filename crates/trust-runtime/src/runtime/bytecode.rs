@@ -61,6 +61,133 @@ impl Runtime {
         self.apply_bytecode_module(&module, resource_name)
     }
 
+    /// Build a runtime from a bytecode module alone, without ST sources.
+    ///
+    /// Declarations, instance layouts, the initial variable image and IO
+    /// bindings come from the module's POU_DECLS and INIT_IMAGE sections, and
+    /// every POU body executes on the bytecode VM.
+    pub fn from_bytecode_module(
+        module: &crate::bytecode::BytecodeModule,
+        resource_name: Option<&str>,
+    ) -> Result<Self, error::RuntimeError> {
+        let invalid = |err: crate::bytecode::BytecodeError| {
+            error::RuntimeError::InvalidBytecode(err.to_string().into())
+        };
+        let mut runtime = Self::new();
+        let restored = module
+            .restore_program(&mut runtime.registry)
+            .map_err(invalid)?;
+        for function in restored.functions {
+            runtime.register_function(function);
+        }
+        for function_block in restored.function_blocks {
+            runtime.register_function_block(function_block);
+        }
+        for class in restored.classes {
+            runtime.register_class(class);
+        }
+        for program in restored.programs {
+            runtime.programs.insert(program.name.clone(), program);
+        }
+        for global in restored.globals {
+            runtime.register_global_meta(
+                global.name,
+                global.type_id,
+                global.retain,
+                global.init,
+                Vec::new(),
+            );
+        }
+        runtime.storage = restored.storage;
+        let metadata = module.metadata().map_err(invalid)?;
+        runtime.apply_bytecode_metadata(&metadata, resource_name)?;
+        for binding in restored.io_bindings {
+            let crate::io::IoTarget::Reference(reference) = binding.target else {
+                continue;
+            };
+            let io = runtime.io_mut();
+            match (binding.value_type, binding.display_name) {
+                (Some(value_type), Some(name)) => {
+                    io.bind_ref_named_typed(reference, binding.address, value_type, name)
+                }
+                (Some(value_type), None) => {
+                    io.bind_ref_typed(reference, binding.address, value_type)
+                }
+                (None, _) => io.bind_ref(reference, binding.address),
+            }
+        }
+        runtime.load_bytecode_vm(restored.vm);
+        runtime.capture_initial_state();
+        Ok(runtime)
+    }
+
+    /// Decode a bytecode container and build a runtime from it alone.
+    pub fn from_bytecode_bytes(
+        bytes: &[u8],
+        resource_name: Option<&str>,
+    ) -> Result<Self, error::RuntimeError> {
+        let module = crate::bytecode::BytecodeModule::decode(bytes)
+            .map_err(|err| error::RuntimeError::InvalidBytecode(err.to_string().into()))?;
+        Self::from_bytecode_module(&module, resource_name)
+    }
+
+    /// Execute program bodies from bytecode instead of the evaluator.
+    ///
+    /// Programs without a complete bytecode body keep running on the evaluator.
+    pub fn load_bytecode_vm(&mut self, vm: crate::bytecode::BytecodeVm) {
+        self.bytecode_vm = Some(vm);
+    }
+
+    /// Build a bytecode VM from a module and use it for program bodies.
+    pub fn load_bytecode_bodies(
+        &mut self,
+        module: &crate::bytecode::BytecodeModule,
+    ) -> Result<(), error::RuntimeError> {
+        let vm = crate::bytecode::BytecodeVm::new(module)
+            .map_err(|err| error::RuntimeError::InvalidBytecode(err.to_string().into()))?;
+        self.load_bytecode_vm(vm);
+        Ok(())
+    }
+
+    /// Execute program bodies from a bundle's bytecode module.
+    ///
+    /// The module must have been encoded from the sources this runtime was
+    /// compiled from. A stale module is rejected instead of executing against
+    /// a mismatched storage layout. `sources` and `paths` map debug entries
+    /// back to the runtime's source files.
+    pub fn load_bytecode_program(
+        &mut self,
+        module: &crate::bytecode::BytecodeModule,
+        sources: &[&str],
+        paths: &[&str],
+    ) -> Result<(), error::RuntimeError> {
+        let invalid = |err: crate::bytecode::BytecodeError| {
+            error::RuntimeError::InvalidBytecode(err.to_string().into())
+        };
+        let vm = crate::bytecode::BytecodeVm::with_sources_and_paths(module, sources, paths)
+            .map_err(invalid)?;
+        let expected = crate::bytecode::BytecodeModule::from_runtime(self).map_err(invalid)?;
+        let expected = crate::bytecode::BytecodeVm::new(&expected).map_err(invalid)?;
+        if !vm.layout_matches(&expected) {
+            return Err(error::RuntimeError::InvalidBytecode(
+                "bytecode does not match the compiled sources".into(),
+            ));
+        }
+        self.load_bytecode_vm(vm);
+        Ok(())
+    }
+
+    /// Access the loaded bytecode VM, if any.
+    #[must_use]
+    pub fn bytecode_vm(&self) -> Option<&crate::bytecode::BytecodeVm> {
+        self.bytecode_vm.as_ref()
+    }
+
+    /// Return to evaluator-only execution.
+    pub fn clear_bytecode_vm(&mut self) {
+        self.bytecode_vm = None;
+    }
+
     /// Apply a single resource metadata payload.
     pub fn apply_resource_metadata(
         &mut self,
@@ -83,7 +210,7 @@ impl Runtime {
         Ok(())
     }
 
-    fn validate_task(&self, task: &TaskConfig) -> Result<(), error::RuntimeError> {
+    pub(super) fn validate_task(&self, task: &TaskConfig) -> Result<(), error::RuntimeError> {
         for program in &task.programs {
             let exists = self
                 .programs
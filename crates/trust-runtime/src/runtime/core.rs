@@ -23,19 +23,21 @@ use super::faults::FaultSubsystem;
 use super::io_subsystem::IoSubsystem;
 use super::metadata::{resolve_using_for_frame, RuntimeMetadata};
 use super::metrics_subsystem::MetricsSubsystem;
-use super::types::{GlobalInitValue, GlobalVarMeta, RetainPolicy};
+use super::types::{GlobalInitValue, GlobalVarMeta, RetainAlias, RetainPolicy};
 use super::watchdog_subsystem::WatchdogSubsystem;
 
 /// Minimal runtime entry point (extended later).
 pub struct Runtime {
     pub(super) profile: DateTimeProfile,
     pub(super) storage: VariableStorage,
+    pub(super) initial_storage: Option<VariableStorage>,
     pub(super) registry: TypeRegistry,
     pub(super) io: IoSubsystem,
     pub(super) access: AccessMap,
     pub(super) stdlib: StandardLibrary,
     pub(super) debug: Option<DebugControl>,
     pub(super) statement_index: IndexMap<u32, Vec<crate::debug::SourceLocation>>,
+    pub(super) bytecode_vm: Option<crate::bytecode::BytecodeVm>,
     pub(super) functions: IndexMap<SmolStr, FunctionDef>,
     pub(super) function_blocks: IndexMap<SmolStr, FunctionBlockDef>,
     pub(super) classes: IndexMap<SmolStr, ClassDef>,
@@ -47,6 +49,7 @@ pub struct Runtime {
     pub(super) task_thread_ids: IndexMap<SmolStr, u32>,
     pub(super) next_thread_id: u32,
     pub(super) background_thread_id: Option<u32>,
+    pub(super) tasks_detached: bool,
     pub(super) current_time: Duration,
     pub(super) cycle_counter: u64,
     pub(super) retain: RetainManager,
@@ -67,6 +70,7 @@ impl std::fmt::Debug for Runtime {
             .field("stdlib", &self.stdlib)
             .field("debug", &self.debug.is_some())
             .field("statement_index", &self.statement_index)
+            .field("bytecode_vm", &self.bytecode_vm.is_some())
             .field("functions", &self.functions)
             .field("function_blocks", &self.function_blocks)
             .field("classes", &self.classes)
@@ -75,6 +79,7 @@ impl std::fmt::Debug for Runtime {
             .field("globals", &self.globals)
             .field("tasks", &self.tasks)
             .field("task_state", &self.task_state)
+            .field("tasks_detached", &self.tasks_detached)
             .field("current_time", &self.current_time)
             .field("cycle_counter", &self.cycle_counter)
             .field("faulted", &self.faults.is_faulted())
@@ -89,12 +94,14 @@ impl Runtime {
         let mut runtime = Self {
             profile: DateTimeProfile::default(),
             storage: VariableStorage::default(),
+            initial_storage: None,
             registry: TypeRegistry::new(),
             io: IoSubsystem::new(),
             access: AccessMap::default(),
             stdlib: StandardLibrary::new(),
             debug: None,
             statement_index: IndexMap::new(),
+            bytecode_vm: None,
             functions: IndexMap::new(),
             function_blocks: IndexMap::new(),
             classes: IndexMap::new(),
@@ -106,6 +113,7 @@ impl Runtime {
             task_thread_ids: IndexMap::new(),
             next_thread_id: 1,
             background_thread_id: None,
+            tasks_detached: false,
             current_time: Duration::ZERO,
             cycle_counter: 0,
             retain: RetainManager::default(),
@@ -163,6 +171,7 @@ impl Runtime {
                 .collect(),
             background_thread_id: self.background_thread_id,
             statement_index: self.statement_index.clone(),
+            io_bindings: self.io.interface().bindings().to_vec(),
         }
     }
 
@@ -234,6 +243,17 @@ impl Runtime {
         self.retain.mark_dirty();
     }
 
+    /// Whether the last cycle (or task run, on a task thread) ran past the
+    /// watchdog timeout. Runs the debugger paused in do not count.
+    #[must_use]
+    pub fn watchdog_expired(&self) -> bool {
+        let pauses = self
+            .debug
+            .as_ref()
+            .map_or(0, crate::debug::DebugControl::pause_count);
+        self.watchdog.expired(pauses)
+    }
+
     /// Record a watchdog timeout fault.
     pub fn watchdog_timeout(&mut self) -> error::RuntimeError {
         let err = error::RuntimeError::WatchdogTimeout;
@@ -431,7 +451,7 @@ impl Runtime {
 
     /// Register an I/O driver invoked at cycle boundaries.
     pub fn add_io_driver(&mut self, name: impl Into<SmolStr>, driver: Box<dyn IoDriver>) {
-        self.io.add_driver(name, driver);
+        self.io.add_driver(name, driver, &self.storage);
     }
 
     /// Clear all registered I/O drivers.
@@ -657,9 +677,24 @@ impl Runtime {
         self.storage
             .set_global(program.name.clone(), Value::Instance(instance_id));
         self.programs.insert(program.name.clone(), program);
+        self.initial_storage = None;
         Ok(())
     }
 
+    /// Record the current variables as the image a restart returns to.
+    ///
+    /// Restarting from the image keeps instance ids stable, so I/O bindings,
+    /// access paths and bytecode references stay valid.
+    pub(crate) fn capture_initial_state(&mut self) {
+        self.initial_storage = Some(self.storage.image());
+    }
+
+    /// Variables as they were when the program was built, if recorded.
+    #[must_use]
+    pub(crate) fn initial_storage(&self) -> Option<&VariableStorage> {
+        self.initial_storage.as_ref()
+    }
+
     /// Register metadata for a global variable.
     pub(crate) fn register_global_meta(
         &mut self,
@@ -667,13 +702,16 @@ impl Runtime {
         type_id: trust_hir::TypeId,
         retain: RetainPolicy,
         init: GlobalInitValue,
+        retain_aliases: Vec<RetainAlias>,
     ) {
+        self.initial_storage = None;
         self.globals.insert(
             name,
             GlobalVarMeta {
                 type_id,
                 retain,
                 init,
+                retain_aliases,
             },
         );
     }
@@ -55,28 +55,32 @@ impl Runtime {
                 time: self.current_time,
             });
         }
+        self.start_watchdog_window();
 
         if let Err(err) = self.read_cycle_inputs() {
             return Err(self.record_fault(err));
         }
+        // With task threads the programs run on forks; the resource storage
+        // only sees their merged image, so there is no per-cycle delta to
+        // record.
+        let record_history = !self.tasks_detached;
+        if let Some(debug) = self
+            .debug
+            .as_ref()
+            .filter(|debug| record_history && debug.history_enabled())
+        {
+            debug.record_cycle_start(
+                self.cycle_counter,
+                self.current_time,
+                self.io.snapshot().inputs,
+                &self.storage,
+            );
+        }
 
-        let mut ready = match self.collect_ready_tasks() {
-            Ok(ready) => ready,
-            Err(err) => return Err(self.record_fault(err)),
-        };
-        ready.sort_by_key(|entry| {
-            let task = &self.tasks[entry.index];
-            (task.priority, entry.due_at.as_nanos(), entry.index)
-        });
-        for entry in ready {
-            let task = self.tasks[entry.index].clone();
-            let task_timer = self.metrics.start_timer();
-            if let Err(err) = self.execute_task(&task) {
+        if !self.tasks_detached {
+            if let Err(err) = self.run_ready_tasks() {
                 return Err(self.record_fault(err));
             }
-            if let Some(start) = task_timer {
-                self.metrics.record_task(&task.name, start.elapsed());
-            }
         }
         if let Err(err) = self.execute_background_programs() {
             return Err(self.record_fault(err));
@@ -85,6 +89,9 @@ impl Runtime {
         if let Err(err) = self.write_cycle_outputs() {
             return Err(self.record_fault(err));
         }
+        if let Some(debug) = self.debug.as_ref().filter(|_| record_history) {
+            debug.record_cycle_end(&self.storage);
+        }
 
         if self.retain.has_store() {
             self.retain.mark_dirty();
@@ -106,6 +113,50 @@ impl Runtime {
         Ok(())
     }
 
+    /// Run the tasks that are due at the current time, without I/O exchange,
+    /// background programs or retain saving.
+    ///
+    /// Used by task threads; returns whether any task ran.
+    pub fn execute_ready_tasks(&mut self) -> Result<bool, error::RuntimeError> {
+        if self.faults.is_faulted() {
+            return Err(error::RuntimeError::ResourceFaulted);
+        }
+        self.start_watchdog_window();
+        self.run_ready_tasks().map_err(|err| self.record_fault(err))
+    }
+
+    /// Start the watchdog window of the cycle or task run about to execute
+    /// on this thread and arm the debugger's watchdog filter with its deadline.
+    fn start_watchdog_window(&mut self) {
+        let pauses = self
+            .debug
+            .as_ref()
+            .map_or(0, crate::debug::DebugControl::pause_count);
+        if let Some(deadline) = self.watchdog.start_window(pauses) {
+            if let Some(debug) = &self.debug {
+                debug.start_watchdog_window(deadline);
+            }
+        }
+    }
+
+    fn run_ready_tasks(&mut self) -> Result<bool, error::RuntimeError> {
+        let mut ready = self.collect_ready_tasks()?;
+        ready.sort_by_key(|entry| {
+            let task = &self.tasks[entry.index];
+            (task.priority, entry.due_at.as_nanos(), entry.index)
+        });
+        let ran = !ready.is_empty();
+        for entry in ready {
+            let task = self.tasks[entry.index].clone();
+            let task_timer = self.metrics.start_timer();
+            self.execute_task(&task)?;
+            if let Some(start) = task_timer {
+                self.metrics.record_task(&task.name, start.elapsed());
+            }
+        }
+        Ok(ran)
+    }
+
     fn apply_forced_values(
         &mut self,
         debug: &crate::debug::DebugControl,
@@ -158,6 +209,8 @@ impl Runtime {
             pause_requested: false,
             execution_deadline: self.execution_deadline,
         };
+        // Input latching and debugger writes are not attributed to the program.
+        ctx.storage.clear_watch_accesses();
         let mut has_frame = false;
         if instance_id.is_some() || !program.temps.is_empty() {
             if let Some(instance_id) = instance_id {
@@ -175,7 +228,17 @@ impl Runtime {
             }
             has_frame = true;
         }
-        let result = match eval::exec_block(&mut ctx, &program.body) {
+        let vm = self
+            .bytecode_vm
+            .as_ref()
+            .filter(|vm| vm.has_program_body(&program.name));
+        let result = match vm {
+            Some(vm) => vm
+                .execute_program(&mut ctx, &program.name)
+                .map(|()| eval::stmt::StmtResult::Continue),
+            None => eval::exec_block(&mut ctx, &program.body),
+        };
+        let result = match result {
             Ok(result) => result,
             Err(err) => {
                 if has_frame {
@@ -185,6 +248,11 @@ impl Runtime {
                 return Err(err);
             }
         };
+        #[cfg(feature = "debug")]
+        if let Some(hook) = ctx.debug.take() {
+            hook.on_program_end(&mut ctx);
+            ctx.debug = Some(hook);
+        }
         if has_frame {
             ctx.storage.pop_frame();
         }
@@ -213,7 +281,7 @@ impl Runtime {
     fn execute_task(&mut self, task: &TaskConfig) -> Result<(), error::RuntimeError> {
         if let Some(debug) = &self.debug {
             let thread_id = self.task_thread_ids.get(&task.name).copied();
-            debug.set_current_thread(thread_id);
+            debug.set_current_task(thread_id, &task.name);
             debug.push_runtime_event(crate::debug::RuntimeEvent::TaskStart {
                 name: task.name.clone(),
                 priority: task.priority,
@@ -256,7 +324,7 @@ impl Runtime {
         let debug = self.debug.clone();
         let thread_id = self.ensure_background_thread_id();
         if let Some(debug) = debug {
-            debug.set_current_thread(thread_id);
+            debug.set_current_task(thread_id, "Background");
         }
         for program in background {
             self.execute_program(&program)?;
@@ -358,6 +426,7 @@ impl Runtime {
             pause_requested: false,
             execution_deadline: self.execution_deadline,
         };
+        ctx.storage.clear_watch_accesses();
         ctx.storage
             .push_frame_with_instance(fb.name.clone(), instance_id);
 
@@ -386,6 +455,11 @@ impl Runtime {
             crate::eval::exec_block(&mut ctx, &fb.body).map(|_| ())
         };
 
+        #[cfg(feature = "debug")]
+        if let Some(hook) = ctx.debug.take() {
+            hook.on_program_end(&mut ctx);
+            ctx.debug = Some(hook);
+        }
         ctx.storage.pop_frame();
         self.debug = debug;
         if let Some(start) = timer {
@@ -400,6 +474,7 @@ impl Runtime {
             let (interface, drivers) = self.io.interface_and_drivers_mut();
             for entry in drivers {
                 entry.driver.read_inputs(interface.inputs_mut())?;
+                entry.driver.read_memory(interface.memory_mut())?;
             }
         }
         if let Some(debug) = self.debug.clone() {
@@ -409,6 +484,12 @@ impl Runtime {
             self.apply_forced_values(&debug)?;
         }
         self.io.interface_mut().read_inputs(&mut self.storage)?;
+        {
+            let (_, drivers) = self.io.interface_and_drivers_mut();
+            for entry in drivers {
+                entry.driver.read_variables(&mut self.storage)?;
+            }
+        }
         #[cfg(feature = "debug")]
         self.emit_io_snapshot();
         self.update_io_health();
@@ -425,7 +506,9 @@ impl Runtime {
         {
             let (interface, drivers) = self.io.interface_and_drivers_mut();
             for entry in drivers {
+                entry.driver.write_variables(&self.storage)?;
                 entry.driver.write_outputs(interface.outputs())?;
+                entry.driver.write_memory(interface.memory())?;
             }
         }
         self.update_io_health();
@@ -6,6 +6,7 @@ use smol_str::SmolStr;
 
 use crate::error::RuntimeError;
 use crate::io::{IoDriver, IoDriverStatus, IoInterface, IoSafeState, IoSnapshot};
+use crate::memory::VariableStorage;
 
 pub(super) struct IoSubsystem {
     interface: IoInterface,
@@ -48,7 +49,13 @@ impl IoSubsystem {
         self.interface.resize(inputs, outputs, memory);
     }
 
-    pub(super) fn add_driver(&mut self, name: impl Into<SmolStr>, driver: Box<dyn IoDriver>) {
+    pub(super) fn add_driver(
+        &mut self,
+        name: impl Into<SmolStr>,
+        mut driver: Box<dyn IoDriver>,
+        storage: &VariableStorage,
+    ) {
+        driver.bind_variables(self.interface.bindings(), storage);
         self.drivers.push(IoDriverEntry {
             name: name.into(),
             driver,
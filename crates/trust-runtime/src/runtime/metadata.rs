@@ -5,8 +5,9 @@
 use indexmap::IndexMap;
 use smol_str::SmolStr;
 
-use crate::debug::SourceLocation;
+use crate::debug::{FunctionBreakpointTarget, SourceLocation};
 use crate::eval::{ClassDef, FunctionBlockDef, FunctionDef, InterfaceDef};
+use crate::io::IoBinding;
 use crate::memory::{AccessMap, FrameId, LocalFrame, VariableStorage};
 use crate::stdlib::StandardLibrary;
 use crate::task::{ProgramDef, TaskConfig};
@@ -29,6 +30,7 @@ pub struct RuntimeMetadata {
     pub(super) task_thread_ids: IndexMap<SmolStr, u32>,
     pub(super) background_thread_id: Option<u32>,
     pub(super) statement_index: IndexMap<u32, Vec<SourceLocation>>,
+    pub(super) io_bindings: Vec<IoBinding>,
 }
 
 impl RuntimeMetadata {
@@ -62,6 +64,12 @@ impl RuntimeMetadata {
         &self.tasks
     }
 
+    /// Access direct-address (`AT %I/%Q/%M`) bindings.
+    #[must_use]
+    pub fn io_bindings(&self) -> &[IoBinding] {
+        &self.io_bindings
+    }
+
     /// Access program definitions.
     #[must_use]
     pub fn programs(&self) -> &IndexMap<SmolStr, ProgramDef> {
@@ -146,6 +154,23 @@ impl RuntimeMetadata {
         Some((location, resolved_line, resolved_col))
     }
 
+    /// Resolve a function breakpoint name to the entry statement of a POU body.
+    #[must_use]
+    pub fn resolve_function_breakpoint(
+        &self,
+        storage: &VariableStorage,
+        name: &str,
+    ) -> Option<FunctionBreakpointTarget> {
+        crate::debug::resolve_function_breakpoint(
+            name,
+            storage,
+            &self.functions,
+            &self.function_blocks,
+            &self.classes,
+            &self.programs,
+        )
+    }
+
     /// Access the profile snapshot.
     #[must_use]
     pub fn profile(&self) -> DateTimeProfile {
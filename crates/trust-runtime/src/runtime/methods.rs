@@ -0,0 +1,205 @@
+//! External method calls on function block instances.
+
+#![allow(missing_docs)]
+
+use indexmap::IndexMap;
+use smol_str::SmolStr;
+use trust_hir::symbols::ParamDirection;
+
+use crate::error::RuntimeError;
+use crate::eval::expr::{Expr, LValue};
+use crate::eval::{
+    self, ArgValue, CallArg, ClassDef, EvalContext, FunctionBlockBase, FunctionBlockDef, MethodDef,
+};
+use crate::memory::InstanceId;
+use crate::value::{default_value_for_type_id, Value};
+
+use super::core::Runtime;
+
+/// Frame owner used for the temporary output locals of an external call.
+const CALL_FRAME: &str = "__external_call";
+
+/// Guard against cyclic base type chains.
+const MAX_BASE_DEPTH: usize = 32;
+
+impl Runtime {
+    /// Call `method` on the function block instance at `instance` (a global
+    /// name, optionally followed by `.field` segments).
+    ///
+    /// `inputs` are the VAR_INPUT and VAR_IN_OUT values in declaration order.
+    /// Returns the method result (if any) followed by the VAR_OUTPUT and
+    /// VAR_IN_OUT values in declaration order.
+    pub fn call_instance_method(
+        &mut self,
+        instance: &str,
+        method: &str,
+        inputs: Vec<Value>,
+    ) -> Result<Vec<Value>, RuntimeError> {
+        let instance_id = self.resolve_instance_path(instance)?;
+        let type_name = self
+            .storage
+            .get_instance(instance_id)
+            .map(|data| data.type_name.clone())
+            .ok_or_else(|| RuntimeError::UndefinedVariable(SmolStr::new(instance)))?;
+        let method_def = method_tables(&self.function_blocks, &self.classes, &type_name)
+            .into_iter()
+            .flatten()
+            .find(|candidate| candidate.name.eq_ignore_ascii_case(method))
+            .cloned()
+            .ok_or_else(|| RuntimeError::UndefinedFunction(SmolStr::new(method)))?;
+
+        let params = method_def
+            .params
+            .iter()
+            .filter(|param| !is_implicit_param(&param.name))
+            .collect::<Vec<_>>();
+        let expected = params
+            .iter()
+            .filter(|param| !matches!(param.direction, ParamDirection::Out))
+            .count();
+        if inputs.len() != expected {
+            return Err(RuntimeError::ControlError(
+                format!(
+                    "method '{}' expects {expected} input argument(s), got {}",
+                    method_def.name,
+                    inputs.len()
+                )
+                .into(),
+            ));
+        }
+
+        let mut inputs = inputs.into_iter();
+        let mut args = Vec::with_capacity(params.len());
+        let mut locals = Vec::new();
+        for (idx, param) in params.iter().enumerate() {
+            let value = match param.direction {
+                ParamDirection::In => {
+                    args.push(CallArg {
+                        name: Some(param.name.clone()),
+                        value: ArgValue::Expr(Expr::Literal(inputs.next().unwrap_or(Value::Null))),
+                    });
+                    continue;
+                }
+                ParamDirection::InOut => inputs.next().unwrap_or(Value::Null),
+                ParamDirection::Out => {
+                    default_value_for_type_id(param.type_id, &self.registry, &self.profile)
+                        .unwrap_or(Value::Null)
+                }
+            };
+            let local = SmolStr::new(format!("__out{idx}"));
+            args.push(CallArg {
+                name: Some(param.name.clone()),
+                value: ArgValue::Target(LValue::Name(local.clone())),
+            });
+            locals.push((local, value));
+        }
+
+        let vm = self.bytecode_vm.as_ref();
+        let mut ctx = EvalContext {
+            storage: &mut self.storage,
+            registry: &self.registry,
+            profile: self.profile,
+            now: self.current_time,
+            debug: None,
+            call_depth: 0,
+            functions: Some(&self.functions),
+            stdlib: Some(&self.stdlib),
+            function_blocks: Some(&self.function_blocks),
+            classes: Some(&self.classes),
+            using: None,
+            access: Some(&self.access),
+            current_instance: None,
+            return_name: None,
+            loop_depth: 0,
+            pause_requested: false,
+            execution_deadline: self.execution_deadline,
+        };
+        ctx.storage.push_frame(CALL_FRAME);
+        for (name, value) in &locals {
+            ctx.storage.set_local(name.clone(), value.clone());
+        }
+        let result = vm
+            .and_then(|vm| vm.call_method_by_name(&mut ctx, &method_def, instance_id, &args))
+            .unwrap_or_else(|| eval::call_method(&mut ctx, &method_def, instance_id, &args));
+        let frame = ctx.storage.pop_frame();
+        let result = result?;
+
+        let mut outputs = Vec::with_capacity(locals.len() + 1);
+        if method_def.return_type.is_some() {
+            outputs.push(result);
+        }
+        for (name, _) in &locals {
+            outputs.push(
+                frame
+                    .as_ref()
+                    .and_then(|frame| frame.variables.get(name))
+                    .cloned()
+                    .unwrap_or(Value::Null),
+            );
+        }
+        Ok(outputs)
+    }
+
+    fn resolve_instance_path(&self, path: &str) -> Result<InstanceId, RuntimeError> {
+        let undefined = || RuntimeError::UndefinedVariable(SmolStr::new(path));
+        let mut segments = path.split('.');
+        let root = segments.next().unwrap_or_default();
+        let mut value = self.storage.get_global(root).ok_or_else(undefined)?;
+        for segment in segments {
+            let Value::Instance(id) = value else {
+                return Err(undefined());
+            };
+            value = self
+                .storage
+                .get_instance(*id)
+                .and_then(|data| {
+                    data.variables
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(segment))
+                        .map(|(_, value)| value)
+                })
+                .ok_or_else(undefined)?;
+        }
+        match value {
+            Value::Instance(id) => Ok(*id),
+            _ => Err(RuntimeError::TypeMismatch),
+        }
+    }
+}
+
+/// Method tables of a function block or class type and its bases, most
+/// derived first.
+pub(crate) fn method_tables<'a>(
+    function_blocks: &'a IndexMap<SmolStr, FunctionBlockDef>,
+    classes: &'a IndexMap<SmolStr, ClassDef>,
+    type_name: &str,
+) -> Vec<&'a [MethodDef]> {
+    let mut tables = Vec::new();
+    let mut current = Some(SmolStr::new(type_name.to_ascii_uppercase()));
+    while let Some(key) = current.take() {
+        if tables.len() > MAX_BASE_DEPTH {
+            break;
+        }
+        if let Some(fb) = function_blocks.get(&key) {
+            tables.push(fb.methods.as_slice());
+            current = match &fb.base {
+                Some(FunctionBlockBase::FunctionBlock(name) | FunctionBlockBase::Class(name)) => {
+                    Some(SmolStr::new(name.to_ascii_uppercase()))
+                }
+                None => None,
+            };
+        } else if let Some(class) = classes.get(&key) {
+            tables.push(class.methods.as_slice());
+            current = class
+                .base
+                .as_ref()
+                .map(|name| SmolStr::new(name.to_ascii_uppercase()));
+        }
+    }
+    tables
+}
+
+/// `EN`/`ENO` are not part of an external call signature.
+pub(crate) fn is_implicit_param(name: &str) -> bool {
+    name.eq_ignore_ascii_case("EN") || name.eq_ignore_ascii_case("ENO")
+}
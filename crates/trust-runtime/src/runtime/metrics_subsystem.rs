@@ -7,6 +7,7 @@ use smol_str::SmolStr;
 
 use crate::metrics::RuntimeMetrics;
 
+#[derive(Clone)]
 pub(super) struct MetricsSubsystem {
     sink: Option<Arc<Mutex<RuntimeMetrics>>>,
 }
@@ -9,12 +9,20 @@ mod faults;
 mod io_subsystem;
 mod mesh;
 mod metadata;
+mod methods;
 mod metrics_subsystem;
+mod online_change;
 mod restart;
+mod retain_migration;
 mod retain_store;
+mod task_threads;
 pub(crate) mod types;
 mod watchdog_subsystem;
 
 pub use core::Runtime;
 pub use metadata::RuntimeMetadata;
-pub use types::{RestartMode, RetainPolicy, RetainSnapshot};
+pub(crate) use methods::{is_implicit_param, method_tables};
+pub use types::{
+    OnlineChangeConversion, OnlineChangeReport, OnlineChangeReset, RestartMode, RetainAlias,
+    RetainConversion, RetainDrop, RetainMigrationReport, RetainPolicy, RetainSnapshot,
+};
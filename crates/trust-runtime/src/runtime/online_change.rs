@@ -0,0 +1,246 @@
+//! Online change: swap in a recompiled program while keeping its state.
+
+#![allow(missing_docs)]
+
+use crate::error::RuntimeError;
+use crate::memory::{InstanceId, VariableStorage};
+use crate::value::{ArrayValue, StructValue, Value};
+
+use super::core::Runtime;
+use super::restart::value_is_retainable;
+use super::retain_migration::{find_key_in, migrate_value};
+use super::types::{OnlineChangeConversion, OnlineChangeReport, OnlineChangeReset};
+
+impl Runtime {
+    /// Replace the program with `next`, a runtime compiled from changed
+    /// sources, keeping the state of every variable and FB instance that
+    /// still exists.
+    ///
+    /// Globals, program variables and nested instance variables are matched
+    /// by qualified path and converted like retained values. I/O drivers,
+    /// process images, tasks, retain store, debug control and time stay with
+    /// this runtime. A runtime executing from bytecode keeps doing so: it
+    /// takes the VM loaded into `next`, or one encoded from `next`. Call
+    /// between cycles; nothing changes if the tasks do not validate against
+    /// the new program or its bytecode cannot be built.
+    pub fn apply_online_change(
+        &mut self,
+        mut next: Runtime,
+    ) -> Result<OnlineChangeReport, RuntimeError> {
+        for task in &self.tasks {
+            next.validate_task(task)?;
+        }
+        let bytecode_vm = match (self.bytecode_vm.is_some(), next.bytecode_vm.take()) {
+            (false, _) => None,
+            (true, Some(vm)) => Some(vm),
+            (true, None) => {
+                let invalid = |err: crate::bytecode::BytecodeError| {
+                    RuntimeError::InvalidBytecode(err.to_string().into())
+                };
+                let module =
+                    crate::bytecode::BytecodeModule::from_runtime(&next).map_err(invalid)?;
+                Some(crate::bytecode::BytecodeVm::new(&module).map_err(invalid)?)
+            }
+        };
+        let mut report = OnlineChangeReport::default();
+        let names = next.storage.globals().keys().cloned().collect::<Vec<_>>();
+        for name in &names {
+            let Some(old_name) = find_key_in(self.storage.globals().keys(), name) else {
+                report.added.push(name.clone());
+                continue;
+            };
+            let old = self.storage.globals()[&old_name].clone();
+            let current = next.storage.globals()[name].clone();
+            let mut transfer = Transfer {
+                old: &self.storage,
+                next: &mut next.storage,
+                report: &mut report,
+            };
+            if let Some(value) = transfer.value(name, &old, &current) {
+                next.storage.set_global(name.clone(), value);
+            }
+        }
+        for name in self.storage.globals().keys() {
+            if find_key_in(names.iter(), name).is_none() {
+                report.removed.push(name.clone());
+            }
+        }
+
+        let (inputs, outputs, memory) = {
+            let interface = self.io.interface();
+            (
+                interface.inputs().to_vec(),
+                interface.outputs().to_vec(),
+                interface.memory().to_vec(),
+            )
+        };
+        let interface = self.io.interface_mut();
+        *interface = std::mem::take(next.io.interface_mut());
+        interface.resize(inputs.len(), outputs.len(), memory.len());
+        interface.inputs_mut().copy_from_slice(&inputs);
+        interface.outputs_mut().copy_from_slice(&outputs);
+        interface.memory_mut().copy_from_slice(&memory);
+
+        self.storage = next.storage;
+        self.initial_storage = next.initial_storage;
+        self.registry = next.registry;
+        self.access = next.access;
+        self.stdlib = next.stdlib;
+        self.statement_index = next.statement_index;
+        self.bytecode_vm = bytecode_vm;
+        self.functions = next.functions;
+        self.function_blocks = next.function_blocks;
+        self.classes = next.classes;
+        self.interfaces = next.interfaces;
+        self.programs = next.programs;
+        self.globals = next.globals;
+        Ok(report)
+    }
+}
+
+/// Copies values from the running storage into the storage of the new
+/// program, which already holds every variable at its initial value.
+struct Transfer<'a> {
+    old: &'a VariableStorage,
+    next: &'a mut VariableStorage,
+    report: &'a mut OnlineChangeReport,
+}
+
+impl Transfer<'_> {
+    /// Value to store at `path` in the new program, or `None` to keep the
+    /// initial value (instances are updated in place).
+    fn value(&mut self, path: &str, old: &Value, current: &Value) -> Option<Value> {
+        match (old, current) {
+            (Value::Instance(old_id), Value::Instance(new_id)) => {
+                self.instance(path, *old_id, *new_id);
+                None
+            }
+            (Value::Array(old), Value::Array(current))
+                if !current.elements.iter().all(value_is_retainable) =>
+            {
+                self.array(path, old, current)
+            }
+            (Value::Struct(old), Value::Struct(current))
+                if !current.fields.values().all(value_is_retainable) =>
+            {
+                self.structure(path, old, current)
+            }
+            _ if !value_is_retainable(old) || !value_is_retainable(current) => {
+                self.reset(path, "holds a reference");
+                None
+            }
+            // Generic (ANY_INT) FB variables stay NULL until the first call.
+            (_, Value::Null) => {
+                self.report.preserved.push(path.into());
+                Some(old.clone())
+            }
+            _ => match migrate_value(old, current) {
+                Ok((value, None)) => {
+                    self.report.preserved.push(path.into());
+                    Some(value)
+                }
+                Ok((value, Some(detail))) => {
+                    self.report.converted.push(OnlineChangeConversion {
+                        path: path.into(),
+                        detail: detail.into(),
+                    });
+                    Some(value)
+                }
+                Err(reason) => {
+                    self.reset(path, &reason);
+                    None
+                }
+            },
+        }
+    }
+
+    fn instance(&mut self, path: &str, old_id: InstanceId, new_id: InstanceId) {
+        let (Some(old), Some(current)) = (
+            self.old.get_instance(old_id),
+            self.next.get_instance(new_id),
+        ) else {
+            return;
+        };
+        if !old.type_name.eq_ignore_ascii_case(&current.type_name) {
+            let reason = format!(
+                "type changed from {} to {}",
+                old.type_name, current.type_name
+            );
+            self.reset(path, &reason);
+            return;
+        }
+        // Standard FBs create their state (CV, edge memories, timer clocks)
+        // on first call, so it only exists in the running instance.
+        let builtin = crate::stdlib::fbs::builtin_kind(&current.type_name).is_some();
+        let old_vars = old.variables.clone();
+        let new_vars = current.variables.clone();
+        let parents = (old.parent, current.parent);
+        for (name, value) in &new_vars {
+            let child = format!("{path}.{name}");
+            let Some(old_name) = find_key_in(old_vars.keys(), name) else {
+                self.report.added.push(child.into());
+                continue;
+            };
+            if let Some(value) = self.value(&child, &old_vars[&old_name], value) {
+                self.next.set_instance_var(new_id, name.clone(), value);
+            }
+        }
+        for (name, value) in &old_vars {
+            if find_key_in(new_vars.keys(), name).is_some() {
+                continue;
+            }
+            if builtin && value_is_retainable(value) {
+                self.report.preserved.push(format!("{path}.{name}").into());
+                self.next
+                    .set_instance_var(new_id, name.clone(), value.clone());
+            } else {
+                self.report.removed.push(format!("{path}.{name}").into());
+            }
+        }
+        if let (Some(old_parent), Some(new_parent)) = parents {
+            self.instance(path, old_parent, new_parent);
+        }
+    }
+
+    fn array(&mut self, path: &str, old: &ArrayValue, current: &ArrayValue) -> Option<Value> {
+        if old.dimensions != current.dimensions {
+            self.reset(path, "array bounds changed");
+            return None;
+        }
+        let mut elements = current.elements.clone();
+        for (idx, (old, slot)) in old.elements.iter().zip(elements.iter_mut()).enumerate() {
+            if let Some(value) = self.value(&format!("{path}[{idx}]"), old, slot) {
+                *slot = value;
+            }
+        }
+        Some(Value::Array(ArrayValue {
+            elements,
+            dimensions: current.dimensions.clone(),
+        }))
+    }
+
+    fn structure(&mut self, path: &str, old: &StructValue, current: &StructValue) -> Option<Value> {
+        let mut fields = current.fields.clone();
+        for (name, slot) in fields.iter_mut() {
+            let child = format!("{path}.{name}");
+            let Some(old_name) = find_key_in(old.fields.keys(), name) else {
+                self.report.added.push(child.into());
+                continue;
+            };
+            if let Some(value) = self.value(&child, &old.fields[&old_name], slot) {
+                *slot = value;
+            }
+        }
+        Some(Value::Struct(StructValue {
+            type_name: current.type_name.clone(),
+            fields,
+        }))
+    }
+
+    fn reset(&mut self, path: &str, reason: &str) {
+        self.report.reinitialized.push(OnlineChangeReset {
+            path: path.into(),
+            reason: reason.into(),
+        });
+    }
+}
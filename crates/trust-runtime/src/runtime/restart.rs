@@ -10,7 +10,10 @@ use crate::task::TaskState;
 use crate::value::{Duration, Value};
 
 use super::core::Runtime;
-use super::types::{GlobalInitValue, RestartMode, RetainPolicy, RetainSnapshot};
+use super::types::{
+    GlobalInitValue, GlobalVarMeta, RestartMode, RetainMigrationReport, RetainPolicy,
+    RetainSnapshot,
+};
 
 impl Runtime {
     /// Restart the runtime in the given mode (cold or warm).
@@ -49,6 +52,42 @@ impl Runtime {
             }
         }
 
+        if let Some(image) = self.initial_storage.take() {
+            let previous = self.storage.image();
+            self.storage.reset_to_image(&image);
+            for (name, value) in retained {
+                self.storage.copy_instances_from(&previous, &value);
+                self.storage.set_global(name, value);
+            }
+            self.initial_storage = Some(image);
+        } else {
+            self.reinit_variables(mode, globals, retained)?;
+        }
+        for (program_name, var_name, value) in retained_program_vars {
+            let Some(Value::Instance(id)) = self.storage.get_global(program_name.as_ref()) else {
+                continue;
+            };
+            self.storage.set_instance_var(*id, var_name, value);
+        }
+
+        self.storage.clear_frames();
+        self.current_time = Duration::ZERO;
+        for state in self.task_state.values_mut() {
+            *state = TaskState::new(self.current_time);
+        }
+        self.faults.clear();
+        self.cycle_counter = 0;
+        Ok(())
+    }
+
+    /// Re-create globals and program instances from their declarations, for
+    /// runtimes assembled without a recorded initial image.
+    fn reinit_variables(
+        &mut self,
+        mode: RestartMode,
+        globals: IndexMap<SmolStr, GlobalVarMeta>,
+        retained: IndexMap<SmolStr, Value>,
+    ) -> Result<(), error::RuntimeError> {
         for (name, meta) in globals {
             let keep = matches!(mode, RestartMode::Warm) && retain_on_warm(meta.retain);
             if keep {
@@ -117,20 +156,6 @@ impl Runtime {
             self.storage
                 .set_global(program.name.clone(), Value::Instance(instance_id));
         }
-        for (program_name, var_name, value) in retained_program_vars {
-            let Some(Value::Instance(id)) = self.storage.get_global(program_name.as_ref()) else {
-                continue;
-            };
-            self.storage.set_instance_var(*id, var_name, value);
-        }
-
-        self.storage.clear_frames();
-        self.current_time = Duration::ZERO;
-        for state in self.task_state.values_mut() {
-            *state = TaskState::new(self.current_time);
-        }
-        self.faults.clear();
-        self.cycle_counter = 0;
         Ok(())
     }
 
@@ -152,24 +177,24 @@ impl Runtime {
         snapshot
     }
 
-    /// Apply a retained snapshot to the current runtime.
-    pub fn apply_retain_snapshot(&mut self, snapshot: &RetainSnapshot) {
-        for (name, value) in &snapshot.values {
-            let Some(meta) = self.globals.get(name) else {
-                continue;
-            };
-            if retain_on_warm(meta.retain) && value_is_retainable(value) {
-                self.storage.set_global(name.clone(), value.clone());
+    /// Apply a retained snapshot to the current runtime, migrating values
+    /// whose variables were renamed or retyped.
+    pub fn apply_retain_snapshot(&mut self, snapshot: &RetainSnapshot) -> RetainMigrationReport {
+        let (migrated, report) = self.plan_retain_migration(snapshot);
+        for (name, value) in migrated.values {
+            if value_is_retainable(&value) {
+                self.storage.set_global(name, value);
             }
         }
+        report
     }
 }
 
-fn retain_on_warm(policy: RetainPolicy) -> bool {
+pub(super) fn retain_on_warm(policy: RetainPolicy) -> bool {
     matches!(policy, RetainPolicy::Retain | RetainPolicy::Persistent)
 }
 
-fn value_is_retainable(value: &Value) -> bool {
+pub(super) fn value_is_retainable(value: &Value) -> bool {
     match value {
         Value::Array(array) => array.elements.iter().all(value_is_retainable),
         Value::Struct(value) => value.fields.values().all(value_is_retainable),
@@ -0,0 +1,419 @@
+//! Mapping stored retain snapshots onto a changed program.
+
+#![allow(missing_docs)]
+
+use std::collections::HashSet;
+
+use smol_str::SmolStr;
+
+use crate::value::{value_type_name, ArrayValue, StructValue, Value};
+
+use super::core::Runtime;
+use super::restart::{retain_on_warm, value_is_retainable};
+use super::types::{RetainConversion, RetainDrop, RetainMigrationReport, RetainSnapshot};
+
+impl Runtime {
+    /// Map a stored snapshot onto the retained globals of the current program.
+    ///
+    /// Values are matched by name, then by `retain_alias` attributes, and
+    /// coerced to the declared type where no information is lost. The
+    /// returned snapshot is keyed by current variable names.
+    #[must_use]
+    pub fn plan_retain_migration(
+        &self,
+        snapshot: &RetainSnapshot,
+    ) -> (RetainSnapshot, RetainMigrationReport) {
+        let mut migrated = RetainSnapshot::default();
+        let mut report = RetainMigrationReport::default();
+        let mut used = HashSet::new();
+        for (name, meta) in &self.globals {
+            if !retain_on_warm(meta.retain) {
+                continue;
+            }
+            let Some(current) = self.storage.get_global(name.as_ref()) else {
+                continue;
+            };
+            if !value_is_retainable(current) {
+                continue;
+            }
+            let mut value = None;
+            let direct = find_key(snapshot, name).map(|key| (key, None));
+            let renamed = || {
+                meta.retain_aliases
+                    .iter()
+                    .filter(|alias| alias.field.is_none())
+                    .find_map(|alias| {
+                        lookup(snapshot, &alias.source).map(|(key, _)| (key, Some(alias)))
+                    })
+            };
+            if let Some((key, alias)) = direct.or_else(renamed) {
+                used.insert(key.clone());
+                let (source, old) = match alias {
+                    Some(alias) => (
+                        alias.source.clone(),
+                        lookup(snapshot, &alias.source).map(|(_, value)| value),
+                    ),
+                    None => (key.clone(), snapshot.values.get(&key)),
+                };
+                match old.map(|old| migrate_value(old, current)) {
+                    Some(Ok((new_value, detail))) => {
+                        let detail = match (alias, detail) {
+                            (None, None) => None,
+                            (None, Some(detail)) => Some(detail),
+                            (Some(_), None) => Some("renamed".to_string()),
+                            (Some(_), Some(detail)) => Some(format!("renamed, {detail}")),
+                        };
+                        match detail {
+                            Some(detail) => report.converted.push(RetainConversion {
+                                source,
+                                target: name.clone(),
+                                detail: detail.into(),
+                            }),
+                            None => report.restored.push(name.clone()),
+                        }
+                        value = Some(new_value);
+                    }
+                    Some(Err(reason)) => report.dropped.push(RetainDrop {
+                        source,
+                        reason: reason.into(),
+                    }),
+                    None => {}
+                }
+            }
+            for alias in &meta.retain_aliases {
+                let Some(field) = alias.field.as_ref() else {
+                    continue;
+                };
+                let Some((key, old)) = lookup(snapshot, &alias.source) else {
+                    continue;
+                };
+                used.insert(key);
+                let base = value.get_or_insert_with(|| current.clone());
+                match migrate_field(base, field, old) {
+                    Ok(detail) => report.converted.push(RetainConversion {
+                        source: alias.source.clone(),
+                        target: format!("{name}.{field}").into(),
+                        detail: detail.map_or_else(
+                            || "moved".into(),
+                            |detail| format!("moved, {detail}").into(),
+                        ),
+                    }),
+                    Err(reason) => report.dropped.push(RetainDrop {
+                        source: alias.source.clone(),
+                        reason: reason.into(),
+                    }),
+                }
+            }
+            if let Some(value) = value {
+                migrated.values.insert(name.clone(), value);
+            }
+        }
+        for key in snapshot.values.keys() {
+            if used.contains(key) {
+                continue;
+            }
+            let reason = match find_key_in(self.globals.keys(), key) {
+                Some(_) => format!("'{key}' is no longer retained"),
+                None => format!("no variable '{key}' in the current program"),
+            };
+            report.dropped.push(RetainDrop {
+                source: key.clone(),
+                reason: reason.into(),
+            });
+        }
+        (migrated, report)
+    }
+}
+
+fn find_key(snapshot: &RetainSnapshot, name: &str) -> Option<SmolStr> {
+    find_key_in(snapshot.values.keys(), name)
+}
+
+pub(super) fn find_key_in<'k>(
+    keys: impl Iterator<Item = &'k SmolStr>,
+    name: &str,
+) -> Option<SmolStr> {
+    let mut fallback = None;
+    for key in keys {
+        if key == name {
+            return Some(key.clone());
+        }
+        if fallback.is_none() && key.eq_ignore_ascii_case(name) {
+            fallback = Some(key.clone());
+        }
+    }
+    fallback
+}
+
+/// Resolve a name or dotted path in a snapshot to its root key and value.
+fn lookup<'s>(snapshot: &'s RetainSnapshot, path: &str) -> Option<(SmolStr, &'s Value)> {
+    let mut parts = path.split('.');
+    let key = find_key(snapshot, parts.next()?)?;
+    let mut value = snapshot.values.get(&key)?;
+    for part in parts {
+        let Value::Struct(StructValue { fields, .. }) = value else {
+            return None;
+        };
+        let field = find_key_in(fields.keys(), part)?;
+        value = fields.get(&field)?;
+    }
+    Some((key, value))
+}
+
+fn migrate_field(base: &mut Value, path: &str, old: &Value) -> Result<Option<String>, String> {
+    let mut target = base;
+    for part in path.split('.') {
+        let Value::Struct(StructValue { fields, .. }) = target else {
+            return Err(format!("'{path}' is not a struct field"));
+        };
+        let field = find_key_in(fields.keys(), part).ok_or_else(|| format!("no field '{path}'"))?;
+        target = fields
+            .get_mut(&field)
+            .ok_or_else(|| format!("no field '{path}'"))?;
+    }
+    let (value, detail) = migrate_value(old, target)?;
+    *target = value;
+    Ok(detail)
+}
+
+/// Coerce a stored value to the shape of the current one.
+///
+/// Returns a description when the value had to be converted, or the reason
+/// the value cannot be restored.
+pub(super) fn migrate_value(
+    old: &Value,
+    current: &Value,
+) -> Result<(Value, Option<String>), String> {
+    match (old, current) {
+        (Value::Struct(old), Value::Struct(current)) => migrate_struct(old, current),
+        (Value::Array(old), Value::Array(current)) => migrate_array(old, current),
+        (Value::Enum(old_enum), Value::Enum(current_enum)) => {
+            if old_enum
+                .type_name
+                .eq_ignore_ascii_case(&current_enum.type_name)
+            {
+                Ok((old.clone(), None))
+            } else {
+                Err(type_changed(old, current))
+            }
+        }
+        _ if std::mem::discriminant(old) == std::mem::discriminant(current) => {
+            Ok((old.clone(), None))
+        }
+        _ => match convert_scalar(old, current) {
+            Some(Ok(value)) => Ok((value, Some(conversion(old, current)))),
+            Some(Err(reason)) => Err(reason),
+            None => Err(type_changed(old, current)),
+        },
+    }
+}
+
+fn migrate_struct(
+    old: &StructValue,
+    current: &StructValue,
+) -> Result<(Value, Option<String>), String> {
+    let mut fields = current.fields.clone();
+    let mut changes = Vec::new();
+    for (name, value) in fields.iter_mut() {
+        let Some(key) = find_key_in(old.fields.keys(), name) else {
+            changes.push(format!("added {name}"));
+            continue;
+        };
+        match migrate_value(&old.fields[&key], value) {
+            Ok((migrated, detail)) => {
+                *value = migrated;
+                if let Some(detail) = detail {
+                    changes.push(format!("{name}: {detail}"));
+                }
+            }
+            Err(reason) => changes.push(format!("{name} reset ({reason})")),
+        }
+    }
+    for name in old.fields.keys() {
+        if find_key_in(current.fields.keys(), name).is_none() {
+            changes.push(format!("removed {name}"));
+        }
+    }
+    if !old.type_name.eq_ignore_ascii_case(&current.type_name) {
+        changes.insert(0, format!("{} -> {}", old.type_name, current.type_name));
+    }
+    let value = Value::Struct(StructValue {
+        type_name: current.type_name.clone(),
+        fields,
+    });
+    Ok((value, (!changes.is_empty()).then(|| changes.join(", "))))
+}
+
+fn migrate_array(
+    old: &ArrayValue,
+    current: &ArrayValue,
+) -> Result<(Value, Option<String>), String> {
+    if old.dimensions.len() != current.dimensions.len() {
+        return Err(format!(
+            "array rank changed from {} to {}",
+            old.dimensions.len(),
+            current.dimensions.len()
+        ));
+    }
+    let mut elements = current.elements.clone();
+    let mut converted = false;
+    for (offset, slot) in elements.iter_mut().enumerate() {
+        let index = array_index(&current.dimensions, offset);
+        let Some(old_offset) = array_offset(&old.dimensions, &index) else {
+            continue;
+        };
+        let Some(old_value) = old.elements.get(old_offset) else {
+            continue;
+        };
+        let (value, detail) = migrate_value(old_value, slot)?;
+        converted |= detail.is_some();
+        *slot = value;
+    }
+    let mut changes = Vec::new();
+    if old.dimensions != current.dimensions {
+        changes.push(format!(
+            "bounds {} -> {}",
+            format_bounds(&old.dimensions),
+            format_bounds(&current.dimensions)
+        ));
+    }
+    if converted {
+        changes.push("elements converted".to_string());
+    }
+    let value = Value::Array(ArrayValue {
+        elements,
+        dimensions: current.dimensions.clone(),
+    });
+    Ok((value, (!changes.is_empty()).then(|| changes.join(", "))))
+}
+
+/// Row-major index of an element offset.
+fn array_index(dimensions: &[(i64, i64)], mut offset: usize) -> Vec<i64> {
+    let mut index = vec![0; dimensions.len()];
+    for (slot, (lower, upper)) in index.iter_mut().zip(dimensions).rev() {
+        let len = (upper - lower + 1).max(1) as usize;
+        *slot = lower + (offset % len) as i64;
+        offset /= len;
+    }
+    index
+}
+
+fn array_offset(dimensions: &[(i64, i64)], index: &[i64]) -> Option<usize> {
+    let mut offset = 0usize;
+    for ((lower, upper), value) in dimensions.iter().zip(index) {
+        if value < lower || value > upper {
+            return None;
+        }
+        let len = (upper - lower + 1) as usize;
+        offset = offset * len + (value - lower) as usize;
+    }
+    Some(offset)
+}
+
+fn format_bounds(dimensions: &[(i64, i64)]) -> String {
+    let bounds = dimensions
+        .iter()
+        .map(|(lower, upper)| format!("{lower}..{upper}"))
+        .collect::<Vec<_>>();
+    format!("[{}]", bounds.join(", "))
+}
+
+/// Lossless conversions between numeric types; `None` for unrelated types.
+fn convert_scalar(old: &Value, current: &Value) -> Option<Result<Value, String>> {
+    if let (Some(value), true) = (integer_value(old), is_integer(current)) {
+        return Some(
+            integer_as(value, current)
+                .ok_or_else(|| format!("value {value} out of range for {}", type_label(current))),
+        );
+    }
+    if let (Some(value), true) = (bit_value(old), is_bit_string(current)) {
+        return Some(
+            bits_as(value, current)
+                .ok_or_else(|| format!("value {value:#x} does not fit {}", type_label(current))),
+        );
+    }
+    match (old, current) {
+        (Value::Real(value), Value::LReal(_)) => Some(Ok(Value::LReal(f64::from(*value)))),
+        (_, Value::LReal(_)) => integer_value(old)
+            .filter(|value| value.unsigned_abs() <= 1 << 53)
+            .map(|value| Ok(Value::LReal(value as f64))),
+        (_, Value::Real(_)) => integer_value(old)
+            .filter(|value| value.unsigned_abs() <= 1 << 24)
+            .map(|value| Ok(Value::Real(value as f32))),
+        (Value::String(value), Value::WString(_)) => Some(Ok(Value::WString(value.to_string()))),
+        (Value::Time(value), Value::LTime(_)) => Some(Ok(Value::LTime(*value))),
+        _ => None,
+    }
+}
+
+fn integer_value(value: &Value) -> Option<i128> {
+    Some(match value {
+        Value::SInt(v) => i128::from(*v),
+        Value::Int(v) => i128::from(*v),
+        Value::DInt(v) => i128::from(*v),
+        Value::LInt(v) => i128::from(*v),
+        Value::USInt(v) => i128::from(*v),
+        Value::UInt(v) => i128::from(*v),
+        Value::UDInt(v) => i128::from(*v),
+        Value::ULInt(v) => i128::from(*v),
+        _ => return None,
+    })
+}
+
+fn is_integer(value: &Value) -> bool {
+    integer_value(value).is_some()
+}
+
+fn integer_as(value: i128, target: &Value) -> Option<Value> {
+    Some(match target {
+        Value::SInt(_) => Value::SInt(i8::try_from(value).ok()?),
+        Value::Int(_) => Value::Int(i16::try_from(value).ok()?),
+        Value::DInt(_) => Value::DInt(i32::try_from(value).ok()?),
+        Value::LInt(_) => Value::LInt(i64::try_from(value).ok()?),
+        Value::USInt(_) => Value::USInt(u8::try_from(value).ok()?),
+        Value::UInt(_) => Value::UInt(u16::try_from(value).ok()?),
+        Value::UDInt(_) => Value::UDInt(u32::try_from(value).ok()?),
+        Value::ULInt(_) => Value::ULInt(u64::try_from(value).ok()?),
+        _ => return None,
+    })
+}
+
+fn bit_value(value: &Value) -> Option<u64> {
+    Some(match value {
+        Value::Byte(v) => u64::from(*v),
+        Value::Word(v) => u64::from(*v),
+        Value::DWord(v) => u64::from(*v),
+        Value::LWord(v) => *v,
+        _ => return None,
+    })
+}
+
+fn is_bit_string(value: &Value) -> bool {
+    bit_value(value).is_some()
+}
+
+fn bits_as(value: u64, target: &Value) -> Option<Value> {
+    Some(match target {
+        Value::Byte(_) => Value::Byte(u8::try_from(value).ok()?),
+        Value::Word(_) => Value::Word(u16::try_from(value).ok()?),
+        Value::DWord(_) => Value::DWord(u32::try_from(value).ok()?),
+        Value::LWord(_) => Value::LWord(value),
+        _ => return None,
+    })
+}
+
+fn type_label(value: &Value) -> String {
+    value_type_name(value).unwrap_or_default()
+}
+
+fn conversion(old: &Value, current: &Value) -> String {
+    format!("{} -> {}", type_label(old), type_label(current))
+}
+
+fn type_changed(old: &Value, current: &Value) -> String {
+    format!(
+        "type changed from {} to {}",
+        type_label(old),
+        type_label(current)
+    )
+}
@@ -3,7 +3,8 @@
 #![allow(missing_docs)]
 
 use crate::error::RuntimeError;
-use crate::RetainSnapshot;
+use crate::watchdog::FaultInfo;
+use crate::{RetainMigrationReport, RetainSnapshot};
 
 use super::core::Runtime;
 
@@ -11,10 +12,22 @@ impl Runtime {
     /// Load retained values from the configured store.
     pub fn load_retain_store(&mut self) -> Result<(), RuntimeError> {
         let snapshot = self.retain.load()?;
-        self.apply_retain_snapshot(&snapshot);
+        let report = self.apply_retain_snapshot(&snapshot);
+        self.retain.set_migration(report);
         Ok(())
     }
 
+    /// How the last loaded snapshot was mapped onto the current program.
+    pub fn retain_migration(&self) -> Option<&RetainMigrationReport> {
+        self.retain.migration()
+    }
+
+    /// Fault recorded when the last load lost retained values or fell back
+    /// to an older snapshot.
+    pub fn retain_fault(&self) -> Option<&FaultInfo> {
+        self.retain.fault()
+    }
+
     /// Persist retained values to the configured store.
     pub fn save_retain_store(&mut self) -> Result<(), RuntimeError> {
         let snapshot = RetainSnapshot::from_runtime(self);
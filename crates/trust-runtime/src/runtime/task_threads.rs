@@ -0,0 +1,89 @@
+//! Per-task runtime forks for threaded task execution.
+
+use smol_str::SmolStr;
+
+use crate::error::RuntimeError;
+use crate::retain::RetainManager;
+
+use super::core::Runtime;
+use super::faults::FaultSubsystem;
+use super::io_subsystem::IoSubsystem;
+use super::watchdog_subsystem::WatchdogSubsystem;
+
+impl Runtime {
+    /// Build a runtime that executes only the named task.
+    ///
+    /// The fork copies the current storage and all definitions, keeps only the
+    /// task's programs and shares the debug and metrics sinks. It has no I/O
+    /// drivers and no retain store: those stay with the resource runtime,
+    /// which exchanges values with task forks through `SharedGlobals`.
+    pub fn fork_task(&self, name: &str) -> Result<Runtime, RuntimeError> {
+        let task = self
+            .tasks
+            .iter()
+            .find(|task| task.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| RuntimeError::UndefinedTask(SmolStr::new(name)))?;
+        let programs = self
+            .programs
+            .iter()
+            .filter(|(program, _)| task.programs.contains(program))
+            .map(|(program, def)| (program.clone(), def.clone()))
+            .collect();
+        let task_state = self
+            .task_state
+            .iter()
+            .filter(|(state, _)| **state == task.name)
+            .map(|(state, def)| (state.clone(), def.clone()))
+            .collect();
+        let mut watchdog = WatchdogSubsystem::new();
+        watchdog.set_policy(self.watchdog.policy());
+        let mut faults = FaultSubsystem::new();
+        faults.set_policy(self.faults.policy());
+        Ok(Runtime {
+            profile: self.profile,
+            storage: self.storage.clone(),
+            initial_storage: self.initial_storage.clone(),
+            registry: self.registry.clone(),
+            io: IoSubsystem::new(),
+            access: self.access.clone(),
+            stdlib: self.stdlib.clone(),
+            debug: self.debug.clone(),
+            statement_index: self.statement_index.clone(),
+            bytecode_vm: self.bytecode_vm.clone(),
+            functions: self.functions.clone(),
+            function_blocks: self.function_blocks.clone(),
+            classes: self.classes.clone(),
+            interfaces: self.interfaces.clone(),
+            programs,
+            globals: self.globals.clone(),
+            tasks: vec![task],
+            task_state,
+            task_thread_ids: self.task_thread_ids.clone(),
+            next_thread_id: self.next_thread_id,
+            background_thread_id: self.background_thread_id,
+            tasks_detached: false,
+            current_time: self.current_time,
+            cycle_counter: self.cycle_counter,
+            retain: RetainManager::default(),
+            metrics: self.metrics.clone(),
+            watchdog,
+            faults,
+            execution_deadline: None,
+        })
+    }
+
+    /// Stop running configured tasks from `execute_cycle`.
+    ///
+    /// Set on the resource runtime once every task runs on its own thread; the
+    /// cycle then only exchanges I/O, runs background programs and saves retain.
+    pub fn set_tasks_detached(&mut self, detached: bool) {
+        self.tasks_detached = detached;
+    }
+
+    /// Whether configured tasks run outside `execute_cycle`.
+    #[must_use]
+    pub fn tasks_detached(&self) -> bool {
+        self.tasks_detached
+    }
+}
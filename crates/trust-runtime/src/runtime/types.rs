@@ -53,6 +53,111 @@ impl RetainSnapshot {
     }
 }
 
+/// Earlier name of a retained variable, from `{attribute 'retain_alias' := '...'}`.
+///
+/// `source` is a name or dotted path in the stored snapshot; `field` selects
+/// a struct field of the declared variable to restore it into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainAlias {
+    pub field: Option<SmolStr>,
+    pub source: SmolStr,
+}
+
+/// How a stored snapshot was mapped onto the current program.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetainMigrationReport {
+    /// Variables restored unchanged.
+    pub restored: Vec<SmolStr>,
+    /// Values restored after a rename or type conversion.
+    pub converted: Vec<RetainConversion>,
+    /// Stored values that could not be restored.
+    pub dropped: Vec<RetainDrop>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainConversion {
+    pub source: SmolStr,
+    pub target: SmolStr,
+    pub detail: SmolStr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainDrop {
+    pub source: SmolStr,
+    pub reason: SmolStr,
+}
+
+impl RetainMigrationReport {
+    /// True when every stored value was restored without changes.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.converted.is_empty() && self.dropped.is_empty()
+    }
+}
+
+impl std::fmt::Display for RetainMigrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "restored: {}", self.restored.len())?;
+        for entry in &self.converted {
+            writeln!(
+                f,
+                "converted: {} -> {} ({})",
+                entry.source, entry.target, entry.detail
+            )?;
+        }
+        for entry in &self.dropped {
+            writeln!(f, "dropped: {} ({})", entry.source, entry.reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// What an online change kept, converted or reset, by qualified path.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OnlineChangeReport {
+    /// Variables carried over unchanged.
+    pub preserved: Vec<SmolStr>,
+    /// Values carried over after a type conversion.
+    pub converted: Vec<OnlineChangeConversion>,
+    /// Variables and instances that only exist in the new program.
+    pub added: Vec<SmolStr>,
+    /// Variables and instances that only existed in the old program.
+    pub removed: Vec<SmolStr>,
+    /// Variables and instances that restarted from their initial value.
+    pub reinitialized: Vec<OnlineChangeReset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnlineChangeConversion {
+    pub path: SmolStr,
+    pub detail: SmolStr,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnlineChangeReset {
+    pub path: SmolStr,
+    pub reason: SmolStr,
+}
+
+impl std::fmt::Display for OnlineChangeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "preserved: {}", self.preserved.len())?;
+        for entry in &self.converted {
+            writeln!(f, "converted: {} ({})", entry.path, entry.detail)?;
+        }
+        for path in &self.added {
+            writeln!(f, "added: {path}")?;
+        }
+        for path in &self.removed {
+            writeln!(f, "removed: {path}")?;
+        }
+        for entry in &self.reinitialized {
+            writeln!(f, "reinitialized: {} ({})", entry.path, entry.reason)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) enum GlobalInitValue {
     Value(Value),
@@ -67,4 +172,5 @@ pub(crate) struct GlobalVarMeta {
     pub type_id: trust_hir::TypeId,
     pub retain: RetainPolicy,
     pub init: GlobalInitValue,
+    pub retain_aliases: Vec<RetainAlias>,
 }
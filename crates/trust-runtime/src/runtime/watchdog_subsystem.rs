@@ -1,15 +1,27 @@
 //! Watchdog policy management.
 
+use std::time::{Duration, Instant};
+
 use crate::watchdog::{FaultDecision, WatchdogPolicy};
 
 pub(super) struct WatchdogSubsystem {
     policy: WatchdogPolicy,
+    window: Option<WatchdogWindow>,
+}
+
+/// Watchdog window of the cycle or task run in progress.
+#[derive(Clone, Copy)]
+struct WatchdogWindow {
+    start: Instant,
+    /// Debugger pause count when the window started.
+    pauses: u64,
 }
 
 impl WatchdogSubsystem {
     pub(super) fn new() -> Self {
         Self {
             policy: WatchdogPolicy::default(),
+            window: None,
         }
     }
 
@@ -24,4 +36,29 @@ impl WatchdogSubsystem {
     pub(super) fn decision(&self) -> FaultDecision {
         FaultDecision::from_watchdog(self.policy.action)
     }
+
+    /// Start the window of the work about to run; returns its deadline when
+    /// the watchdog is enabled.
+    pub(super) fn start_window(&mut self, pauses: u64) -> Option<Instant> {
+        if !self.policy.enabled {
+            self.window = None;
+            return None;
+        }
+        let start = Instant::now();
+        self.window = Some(WatchdogWindow { start, pauses });
+        Some(start + self.timeout())
+    }
+
+    /// Whether the current window ran past the timeout. A window the
+    /// debugger paused in (`pauses` moved on) never expires.
+    pub(super) fn expired(&self, pauses: u64) -> bool {
+        self.policy.enabled
+            && self.window.is_some_and(|window| {
+                window.pauses == pauses && window.start.elapsed() > self.timeout()
+            })
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_nanos(u64::try_from(self.policy.timeout.as_nanos()).unwrap_or(0))
+    }
 }
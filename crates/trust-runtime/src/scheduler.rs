@@ -7,14 +7,20 @@ use std::sync::{Arc, Condvar, Mutex};
 use std::thread;
 
 use indexmap::IndexMap;
+use rustc_hash::FxHashMap;
 use smol_str::SmolStr;
 
 use crate::error::RuntimeError;
+use crate::memory::InstanceId;
 use crate::value::Duration;
 use crate::value::Value;
 use crate::Runtime;
 use crate::RuntimeMetadata;
 
+mod task_threads;
+
+use task_threads::TaskThreads;
+
 /// Clock interface for resource scheduling.
 pub trait Clock: Send + Sync + 'static {
     /// Return the current time for scheduling.
@@ -225,6 +231,23 @@ pub enum ResourceState {
     Stopped,
 }
 
+/// Recompiled program carried by [`ResourceCommand::OnlineChange`].
+///
+/// Clones share the program; the first resource to apply it takes it.
+#[derive(Debug, Clone)]
+pub struct OnlineChangeProgram(Arc<Mutex<Option<Box<Runtime>>>>);
+
+impl OnlineChangeProgram {
+    #[must_use]
+    pub fn new(runtime: Runtime) -> Self {
+        Self(Arc::new(Mutex::new(Some(Box::new(runtime)))))
+    }
+
+    fn take(&self) -> Option<Runtime> {
+        self.0.lock().ok()?.take().map(|runtime| *runtime)
+    }
+}
+
 /// Commands applied to a running resource.
 #[derive(Debug, Clone)]
 pub enum ResourceCommand {
@@ -234,10 +257,21 @@ pub enum ResourceCommand {
     UpdateFaultPolicy(crate::watchdog::FaultPolicy),
     UpdateRetainSaveInterval(Option<Duration>),
     UpdateIoSafeState(crate::io::IoSafeState),
+    /// Apply task and process-image metadata from a bytecode container and
+    /// warm-restart. The container holds no typed program to migrate state
+    /// into; `OnlineChange` is the state-preserving path.
     ReloadBytecode {
         bytes: Vec<u8>,
         respond_to: std::sync::mpsc::Sender<Result<RuntimeMetadata, RuntimeError>>,
     },
+    /// Swap in a recompiled program between cycles, keeping the state of
+    /// variables and FB instances (`Runtime::apply_online_change`).
+    OnlineChange {
+        program: OnlineChangeProgram,
+        respond_to: std::sync::mpsc::Sender<
+            Result<(RuntimeMetadata, crate::OnlineChangeReport), RuntimeError>,
+        >,
+    },
     MeshSnapshot {
         names: Vec<SmolStr>,
         respond_to: std::sync::mpsc::Sender<IndexMap<SmolStr, Value>>,
@@ -248,6 +282,14 @@ pub enum ResourceCommand {
     Snapshot {
         respond_to: std::sync::mpsc::Sender<crate::debug::DebugSnapshot>,
     },
+    /// Call a method on a function block instance between cycles
+    /// (`Runtime::call_instance_method`).
+    CallMethod {
+        instance: SmolStr,
+        method: SmolStr,
+        inputs: Vec<Value>,
+        respond_to: std::sync::mpsc::Sender<Result<Vec<Value>, RuntimeError>>,
+    },
 }
 
 /// Gate that blocks resource execution until opened.
@@ -299,6 +341,7 @@ pub struct ResourceRunner<C: Clock + Clone> {
     start_gate: Option<Arc<StartGate>>,
     command_rx: Option<std::sync::mpsc::Receiver<ResourceCommand>>,
     simulation: Option<crate::simulation::SimulationController>,
+    task_threads: bool,
 }
 
 impl<C: Clock + Clone> ResourceRunner<C> {
@@ -313,6 +356,7 @@ impl<C: Clock + Clone> ResourceRunner<C> {
             start_gate: None,
             command_rx: None,
             simulation: None,
+            task_threads: false,
         }
     }
 
@@ -344,6 +388,17 @@ impl<C: Clock + Clone> ResourceRunner<C> {
         self
     }
 
+    /// Run each configured task on its own OS thread.
+    ///
+    /// Tasks exchange globals and instance state through a shared image at
+    /// task start and end; the resource thread keeps I/O, background programs
+    /// and retain.
+    #[must_use]
+    pub fn with_task_threads(mut self, enabled: bool) -> Self {
+        self.task_threads = enabled;
+        self
+    }
+
     /// Access the underlying runtime.
     #[must_use]
     pub fn runtime(&self) -> &Runtime {
@@ -366,16 +421,20 @@ impl<C: Clock + Clone> ResourceRunner<C> {
     pub fn tick_with_shared(&mut self, shared: &SharedGlobals) -> Result<(), RuntimeError> {
         let now = self.clock.now();
         self.runtime.set_current_time(now);
-        shared.with_lock(|globals| {
-            shared.sync_into_locked(globals, &mut self.runtime)?;
+        shared.with_lock(|image| {
+            shared.sync_into_locked(image, &mut self.runtime)?;
             let result = self.runtime.execute_cycle();
-            shared.sync_from_locked(globals, &self.runtime)?;
+            shared.sync_from_locked(image, &self.runtime)?;
             result
         })
     }
 
     /// Spawn the runner in a dedicated OS thread.
     pub fn spawn(self, name: impl Into<String>) -> Result<ResourceHandle<C>, RuntimeError> {
+        if self.task_threads {
+            let shared = SharedGlobals::task_image(&self.runtime)?;
+            return self.spawn_shared(name, shared, true);
+        }
         let stop = Arc::new(AtomicBool::new(false));
         let state = Arc::new(Mutex::new(ResourceState::Boot));
         let last_error = Arc::new(Mutex::new(None));
@@ -415,6 +474,20 @@ impl<C: Clock + Clone> ResourceRunner<C> {
         self,
         name: impl Into<String>,
         shared: SharedGlobals,
+    ) -> Result<ResourceHandle<C>, RuntimeError> {
+        if self.task_threads {
+            return Err(RuntimeError::InvalidConfig(
+                "task threads cannot be combined with cross-resource shared globals".into(),
+            ));
+        }
+        self.spawn_shared(name, shared, false)
+    }
+
+    fn spawn_shared(
+        self,
+        name: impl Into<String>,
+        shared: SharedGlobals,
+        threaded: bool,
     ) -> Result<ResourceHandle<C>, RuntimeError> {
         let stop = Arc::new(AtomicBool::new(false));
         let state = Arc::new(Mutex::new(ResourceState::Boot));
@@ -440,6 +513,7 @@ impl<C: Clock + Clone> ResourceRunner<C> {
                     state_thread,
                     last_error_thread,
                     shared_thread,
+                    threaded,
                 );
             })
             .map_err(|err| RuntimeError::ThreadSpawn(err.to_string().into()))?;
@@ -511,7 +585,9 @@ fn run_resource_loop<C: Clock + Clone>(
                         paused = false;
                         *state.lock().expect("resource state poisoned") = ResourceState::Running;
                     }
-                    other => apply_resource_command(&mut runner.runtime, other),
+                    other => {
+                        apply_resource_command(&mut runner.runtime, other);
+                    }
                 }
             }
         }
@@ -552,7 +628,6 @@ fn run_resource_loop<C: Clock + Clone>(
         let now_raw = runner.clock.now();
         let now = scaled_time(now_raw, runner.time_scale);
         runner.runtime.set_current_time(now);
-        let wall_start = std::time::Instant::now();
         if let Some(simulation) = runner.simulation.as_mut() {
             if let Err(err) = simulation.apply_pre_cycle(now, &mut runner.runtime) {
                 if matches!(
@@ -594,22 +669,19 @@ fn run_resource_loop<C: Clock + Clone>(
             break;
         }
 
-        let watchdog = runner.runtime.watchdog_policy();
-        if watchdog.enabled {
-            let elapsed = i64::try_from(wall_start.elapsed().as_nanos()).unwrap_or(i64::MAX);
-            if elapsed > watchdog.timeout.as_nanos() {
-                if matches!(watchdog.action, crate::watchdog::WatchdogAction::Restart) {
-                    if let Err(restart_err) = runner.runtime.restart(crate::RestartMode::Warm) {
-                        *last_error.lock().expect("resource error poisoned") = Some(restart_err);
-                        *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
-                        break;
-                    }
-                } else {
-                    let err = runner.runtime.watchdog_timeout();
-                    *last_error.lock().expect("resource error poisoned") = Some(err);
+        if runner.runtime.watchdog_expired() {
+            let watchdog = runner.runtime.watchdog_policy();
+            if matches!(watchdog.action, crate::watchdog::WatchdogAction::Restart) {
+                if let Err(restart_err) = runner.runtime.restart(crate::RestartMode::Warm) {
+                    *last_error.lock().expect("resource error poisoned") = Some(restart_err);
                     *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
                     break;
                 }
+            } else {
+                let err = runner.runtime.watchdog_timeout();
+                *last_error.lock().expect("resource error poisoned") = Some(err);
+                *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
+                break;
             }
         }
 
@@ -631,6 +703,7 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
     state: Arc<Mutex<ResourceState>>,
     last_error: Arc<Mutex<Option<RuntimeError>>>,
     shared: SharedGlobals,
+    threaded: bool,
 ) {
     let mut paused = false;
     if let Some(gate) = runner.start_gate.as_ref() {
@@ -640,9 +713,34 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
             return;
         }
     }
+    let mut tasks = None;
+    if threaded {
+        match TaskThreads::spawn(
+            &mut runner.runtime,
+            shared.clone(),
+            &runner.clock,
+            runner.cycle_interval,
+            runner.time_scale,
+        ) {
+            Ok(threads) => tasks = Some(threads),
+            Err(err) => {
+                *last_error.lock().expect("resource error poisoned") = Some(err);
+                *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
+                return;
+            }
+        }
+    }
+    // Set whenever the runtime restarts; task forks are rebuilt from it.
+    let mut restarted = false;
+    // Set after an online change; task forks migrate their own state.
+    let mut online_changed = false;
     *state.lock().expect("resource state poisoned") = ResourceState::Running;
     loop {
         if stop.load(Ordering::SeqCst) {
+            if tasks.is_some() && !restarted {
+                let _ =
+                    shared.with_lock(|image| shared.sync_into_locked(image, &mut runner.runtime));
+            }
             let _ = runner.runtime.save_retain_store();
             *state.lock().expect("resource state poisoned") = ResourceState::Stopped;
             break;
@@ -653,13 +751,40 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
                 match command {
                     ResourceCommand::Pause => {
                         paused = true;
+                        if let Some(tasks) = tasks.as_ref() {
+                            tasks.set_paused(true);
+                        }
                         *state.lock().expect("resource state poisoned") = ResourceState::Paused;
                     }
                     ResourceCommand::Resume => {
                         paused = false;
+                        if let Some(tasks) = tasks.as_ref() {
+                            tasks.set_paused(false);
+                        }
                         *state.lock().expect("resource state poisoned") = ResourceState::Running;
                     }
-                    other => apply_resource_command(&mut runner.runtime, other),
+                    other @ ResourceCommand::CallMethod { .. } => {
+                        // The method runs against the shared image and what
+                        // it writes is published back before the next cycle
+                        // or task fork pulls the image again.
+                        let _ = shared.with_lock(|image| {
+                            shared.sync_into_locked(image, &mut runner.runtime)?;
+                            apply_resource_command(&mut runner.runtime, other);
+                            shared.sync_from_locked(image, &runner.runtime)
+                        });
+                    }
+                    other => {
+                        restarted |= matches!(other, ResourceCommand::ReloadBytecode { .. });
+                        if tasks.is_some() && matches!(other, ResourceCommand::OnlineChange { .. })
+                        {
+                            // Task forks publish into the image; bring the
+                            // resource up to date before it is migrated.
+                            let _ = shared.with_lock(|image| {
+                                shared.sync_into_locked(image, &mut runner.runtime)
+                            });
+                        }
+                        online_changed |= apply_resource_command(&mut runner.runtime, other);
+                    }
                 }
             }
         }
@@ -677,6 +802,27 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
                         *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
                         break;
                     }
+                    restarted = true;
+                }
+            }
+        }
+
+        if std::mem::take(&mut online_changed) && !restarted {
+            if let Some(tasks) = tasks.as_ref() {
+                if let Err(err) = tasks.online_change(&runner.runtime) {
+                    *last_error.lock().expect("resource error poisoned") = Some(err);
+                    *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
+                    break;
+                }
+            }
+        }
+
+        if std::mem::take(&mut restarted) {
+            if let Some(tasks) = tasks.as_ref() {
+                if let Err(err) = tasks.reload(&runner.runtime) {
+                    *last_error.lock().expect("resource error poisoned") = Some(err);
+                    *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
+                    break;
                 }
             }
         }
@@ -697,10 +843,37 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
             continue;
         }
 
+        if let Some(err) = tasks.as_ref().and_then(TaskThreads::take_error) {
+            // A task overrun follows the watchdog action, other faults the
+            // fault policy.
+            let restart = if matches!(err, RuntimeError::WatchdogTimeout) {
+                matches!(
+                    runner.runtime.watchdog_policy().action,
+                    crate::watchdog::WatchdogAction::Restart
+                )
+            } else {
+                matches!(
+                    runner.runtime.fault_policy(),
+                    crate::watchdog::FaultPolicy::Restart
+                )
+            };
+            if restart {
+                if let Err(restart_err) = runner.runtime.restart(crate::RestartMode::Warm) {
+                    *last_error.lock().expect("resource error poisoned") = Some(restart_err);
+                    *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
+                    break;
+                }
+                restarted = true;
+                continue;
+            }
+            *last_error.lock().expect("resource error poisoned") = Some(err);
+            *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
+            break;
+        }
+
         let now_raw = runner.clock.now();
         let now = scaled_time(now_raw, runner.time_scale);
         runner.runtime.set_current_time(now);
-        let wall_start = std::time::Instant::now();
         if let Some(simulation) = runner.simulation.as_mut() {
             if let Err(err) = simulation.apply_pre_cycle(now, &mut runner.runtime) {
                 if matches!(
@@ -712,6 +885,7 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
                         *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
                         break;
                     }
+                    restarted = true;
                     continue;
                 }
                 *last_error.lock().expect("resource error poisoned") = Some(err);
@@ -719,10 +893,10 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
                 break;
             }
         }
-        let mut result = shared.with_lock(|globals| {
-            shared.sync_into_locked(globals, &mut runner.runtime)?;
+        let mut result = shared.with_lock(|image| {
+            shared.sync_into_locked(image, &mut runner.runtime)?;
             let result = runner.runtime.execute_cycle();
-            shared.sync_from_locked(globals, &runner.runtime)?;
+            shared.sync_from_locked(image, &runner.runtime)?;
             result
         });
         if result.is_ok() {
@@ -740,28 +914,27 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
                     *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
                     break;
                 }
+                restarted = true;
                 continue;
             }
             *last_error.lock().expect("resource error poisoned") = Some(err);
             *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
             break;
         }
-        let watchdog = runner.runtime.watchdog_policy();
-        if watchdog.enabled {
-            let elapsed = i64::try_from(wall_start.elapsed().as_nanos()).unwrap_or(i64::MAX);
-            if elapsed > watchdog.timeout.as_nanos() {
-                if matches!(watchdog.action, crate::watchdog::WatchdogAction::Restart) {
-                    if let Err(restart_err) = runner.runtime.restart(crate::RestartMode::Warm) {
-                        *last_error.lock().expect("resource error poisoned") = Some(restart_err);
-                        *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
-                        break;
-                    }
-                } else {
-                    let err = runner.runtime.watchdog_timeout();
-                    *last_error.lock().expect("resource error poisoned") = Some(err);
+        if runner.runtime.watchdog_expired() {
+            let watchdog = runner.runtime.watchdog_policy();
+            if matches!(watchdog.action, crate::watchdog::WatchdogAction::Restart) {
+                if let Err(restart_err) = runner.runtime.restart(crate::RestartMode::Warm) {
+                    *last_error.lock().expect("resource error poisoned") = Some(restart_err);
                     *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
                     break;
                 }
+                restarted = true;
+            } else {
+                let err = runner.runtime.watchdog_timeout();
+                *last_error.lock().expect("resource error poisoned") = Some(err);
+                *state.lock().expect("resource state poisoned") = ResourceState::Faulted;
+                break;
             }
         }
 
@@ -775,9 +948,14 @@ fn run_resource_loop_with_shared<C: Clock + Clone>(
             Duration::from_nanos(now_raw.as_nanos().saturating_add(sleep_interval.as_nanos()));
         runner.clock.sleep_until(deadline);
     }
+    if let Some(tasks) = tasks.as_mut() {
+        tasks.shutdown();
+    }
 }
 
-fn apply_resource_command(runtime: &mut Runtime, command: ResourceCommand) {
+/// Apply a command between cycles. Returns true when an online change
+/// replaced the program.
+fn apply_resource_command(runtime: &mut Runtime, command: ResourceCommand) -> bool {
     match command {
         ResourceCommand::Pause | ResourceCommand::Resume => {}
         ResourceCommand::UpdateWatchdog(policy) => runtime.set_watchdog_policy(policy),
@@ -794,6 +972,22 @@ fn apply_resource_command(runtime: &mut Runtime, command: ResourceCommand) {
                 .map(|_| runtime.metadata_snapshot());
             let _ = respond_to.send(result);
         }
+        ResourceCommand::OnlineChange {
+            program,
+            respond_to,
+        } => {
+            let result = match program.take() {
+                Some(next) => runtime
+                    .apply_online_change(next)
+                    .map(|report| (runtime.metadata_snapshot(), report)),
+                None => Err(RuntimeError::ControlError(
+                    "online change program already applied".into(),
+                )),
+            };
+            let changed = result.is_ok();
+            let _ = respond_to.send(result);
+            return changed;
+        }
         ResourceCommand::MeshSnapshot { names, respond_to } => {
             let snapshot = runtime.snapshot_globals(&names);
             let _ = respond_to.send(snapshot);
@@ -806,7 +1000,17 @@ fn apply_resource_command(runtime: &mut Runtime, command: ResourceCommand) {
             };
             let _ = respond_to.send(snapshot);
         }
+        ResourceCommand::CallMethod {
+            instance,
+            method,
+            inputs,
+            respond_to,
+        } => {
+            let result = runtime.call_instance_method(&instance, &method, inputs);
+            let _ = respond_to.send(result);
+        }
     }
+    false
 }
 
 /// Handle to a running resource thread.
@@ -946,27 +1150,61 @@ impl<C: Clock + Clone> ResourceControl<C> {
 #[derive(Debug, Clone)]
 pub struct SharedGlobals {
     names: Vec<SmolStr>,
-    inner: Arc<Mutex<IndexMap<SmolStr, Value>>>,
+    /// Also exchange program and function block instance state.
+    instances: bool,
+    inner: Arc<Mutex<SharedImage>>,
+}
+
+#[derive(Debug, Default)]
+struct SharedImage {
+    globals: IndexMap<SmolStr, Value>,
+    instances: FxHashMap<InstanceId, IndexMap<SmolStr, Value>>,
+}
+
+/// Image values a task fork pulled, used to detect what it changed.
+#[derive(Debug, Default)]
+struct SharedBaseline {
+    globals: Vec<Value>,
+    instances: FxHashMap<InstanceId, IndexMap<SmolStr, Value>>,
 }
 
 impl SharedGlobals {
     /// Create a shared global set from a runtime snapshot.
     pub fn from_runtime(names: Vec<SmolStr>, runtime: &Runtime) -> Result<Self, RuntimeError> {
-        let mut values = IndexMap::new();
-        for name in &names {
-            let value = runtime
-                .storage()
-                .get_global(name.as_ref())
-                .ok_or_else(|| RuntimeError::UndefinedVariable(name.clone()))?;
-            values.insert(name.clone(), value.clone());
-        }
-        Ok(Self {
+        Self::build(names, false, runtime)
+    }
+
+    /// Shared image used to exchange values between task threads.
+    ///
+    /// Holds every global that is not a program or function block instance,
+    /// plus the variables of every instance, so the resource runtime sees the
+    /// program state the task threads produce.
+    pub fn task_image(runtime: &Runtime) -> Result<Self, RuntimeError> {
+        let names = runtime
+            .storage()
+            .globals()
+            .iter()
+            .filter(|(_, value)| !matches!(value, Value::Instance(_)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        Self::build(names, true, runtime)
+    }
+
+    fn build(
+        names: Vec<SmolStr>,
+        instances: bool,
+        runtime: &Runtime,
+    ) -> Result<Self, RuntimeError> {
+        let shared = Self {
             names,
-            inner: Arc::new(Mutex::new(values)),
-        })
+            instances,
+            inner: Arc::new(Mutex::new(SharedImage::default())),
+        };
+        shared.reseed(runtime)?;
+        Ok(shared)
     }
 
-    fn with_lock<T>(&self, f: impl FnOnce(&mut IndexMap<SmolStr, Value>) -> T) -> T {
+    fn with_lock<T>(&self, f: impl FnOnce(&mut SharedImage) -> T) -> T {
         let mut guard = self.inner.lock().expect("shared globals poisoned");
         f(&mut guard)
     }
@@ -974,28 +1212,83 @@ impl SharedGlobals {
     /// Read a shared global value by name.
     #[must_use]
     pub fn get(&self, name: &str) -> Option<Value> {
-        self.with_lock(|globals| globals.get(name).cloned())
+        self.with_lock(|image| image.globals.get(name).cloned())
     }
 
     fn sync_into_locked(
         &self,
-        globals: &IndexMap<SmolStr, Value>,
+        image: &SharedImage,
         runtime: &mut Runtime,
     ) -> Result<(), RuntimeError> {
         for name in &self.names {
-            let value = globals
+            let value = image
+                .globals
                 .get(name)
                 .ok_or_else(|| RuntimeError::UndefinedVariable(name.clone()))?;
             runtime
                 .storage_mut()
                 .set_global(name.clone(), value.clone());
         }
+        for (id, variables) in &image.instances {
+            if let Some(instance) = runtime.storage_mut().get_instance_mut(*id) {
+                instance.variables.clone_from(variables);
+            }
+        }
         Ok(())
     }
 
+    /// Copy the image into `runtime` and return the copied values.
+    fn pull(&self, runtime: &mut Runtime) -> Result<SharedBaseline, RuntimeError> {
+        self.with_lock(|image| {
+            self.sync_into_locked(image, runtime)?;
+            Ok(SharedBaseline {
+                globals: self
+                    .names
+                    .iter()
+                    .map(|name| image.globals.get(name).cloned().unwrap_or(Value::Null))
+                    .collect(),
+                instances: image.instances.clone(),
+            })
+        })
+    }
+
+    /// Publish the values `runtime` changed since `pull` returned `baseline`.
+    fn publish(&self, runtime: &Runtime, baseline: &SharedBaseline) -> Result<(), RuntimeError> {
+        self.with_lock(|image| {
+            for (name, before) in self.names.iter().zip(&baseline.globals) {
+                let value = runtime
+                    .storage()
+                    .get_global(name.as_ref())
+                    .ok_or_else(|| RuntimeError::UndefinedVariable(name.clone()))?;
+                if value != before {
+                    image.globals.insert(name.clone(), value.clone());
+                }
+            }
+            for (id, before) in &baseline.instances {
+                let (Some(instance), Some(target)) = (
+                    runtime.storage().get_instance(*id),
+                    image.instances.get_mut(id),
+                ) else {
+                    continue;
+                };
+                for (name, value) in &instance.variables {
+                    if before.get(name) != Some(value) {
+                        target.insert(name.clone(), value.clone());
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Overwrite the image with the current values of `runtime`.
+    fn reseed(&self, runtime: &Runtime) -> Result<(), RuntimeError> {
+        self.with_lock(|image| self.sync_from_locked(image, runtime))
+    }
+
     fn sync_from_locked(
         &self,
-        globals: &mut IndexMap<SmolStr, Value>,
+        image: &mut SharedImage,
         runtime: &Runtime,
     ) -> Result<(), RuntimeError> {
         for name in &self.names {
@@ -1003,7 +1296,15 @@ impl SharedGlobals {
                 .storage()
                 .get_global(name.as_ref())
                 .ok_or_else(|| RuntimeError::UndefinedVariable(name.clone()))?;
-            globals.insert(name.clone(), value.clone());
+            image.globals.insert(name.clone(), value.clone());
+        }
+        if self.instances {
+            image.instances = runtime
+                .storage()
+                .instances()
+                .iter()
+                .map(|(id, instance)| (*id, instance.variables.clone()))
+                .collect();
         }
         Ok(())
     }
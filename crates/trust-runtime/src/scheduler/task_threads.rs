@@ -0,0 +1,270 @@
+//! One OS thread per configured task.
+//!
+//! Each task runs on a fork of the resource runtime (`Runtime::fork_task`).
+//! Globals and instance variables are exchanged through a `SharedGlobals`
+//! image: a task copies the image in before it runs and publishes the values
+//! it changed when it finishes. The resource thread keeps I/O, background programs and retain,
+//! and holds the image lock for its whole cycle.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+use smol_str::SmolStr;
+
+use crate::error::RuntimeError;
+use crate::value::Duration;
+use crate::Runtime;
+
+use super::{scaled_sleep_interval, scaled_time, Clock, SharedGlobals};
+
+/// Running task threads of one resource.
+pub(super) struct TaskThreads<C: Clock + Clone> {
+    workers: Vec<TaskWorker>,
+    shared: SharedGlobals,
+    clock: C,
+    halt: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<RuntimeError>>>,
+}
+
+struct TaskWorker {
+    task: SmolStr,
+    reload: Sender<ForkUpdate>,
+    join: Option<thread::JoinHandle<()>>,
+}
+
+struct WorkerContext<C: Clock> {
+    clock: C,
+    priority: u32,
+    poll: Duration,
+    time_scale: u32,
+    shared: SharedGlobals,
+    gate: Arc<PriorityGate>,
+    reload: Receiver<ForkUpdate>,
+    halt: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<RuntimeError>>>,
+}
+
+/// New program for a task fork.
+enum ForkUpdate {
+    /// Discard the fork state (restart or bytecode reload).
+    Replace(Runtime),
+    /// Carry the fork state over into the new program.
+    OnlineChange(Runtime),
+}
+
+impl<C: Clock + Clone> TaskThreads<C> {
+    /// Fork every task of `runtime` onto its own thread and detach the tasks
+    /// from the resource cycle.
+    pub(super) fn spawn(
+        runtime: &mut Runtime,
+        shared: SharedGlobals,
+        clock: &C,
+        cycle_interval: Duration,
+        time_scale: u32,
+    ) -> Result<Self, RuntimeError> {
+        let halt = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(AtomicBool::new(false));
+        let error = Arc::new(Mutex::new(None));
+        let gate = Arc::new(PriorityGate::default());
+        let mut threads = Self {
+            workers: Vec::new(),
+            shared,
+            clock: clock.clone(),
+            halt,
+            paused,
+            error,
+        };
+        // Start in priority order so the most urgent tasks run first.
+        let mut tasks = runtime.tasks().to_vec();
+        tasks.sort_by_key(|task| task.priority);
+        for task in tasks {
+            let fork = runtime.fork_task(&task.name)?;
+            let poll = if task.interval.as_nanos() > 0 && task.single.is_none() {
+                task.interval
+            } else {
+                cycle_interval
+            };
+            let (reload_tx, reload_rx) = mpsc::channel();
+            let context = WorkerContext {
+                clock: clock.clone(),
+                priority: task.priority,
+                poll,
+                time_scale,
+                shared: threads.shared.clone(),
+                gate: gate.clone(),
+                reload: reload_rx,
+                halt: threads.halt.clone(),
+                paused: threads.paused.clone(),
+                error: threads.error.clone(),
+            };
+            let join = thread::Builder::new()
+                .name(format!("task-{}", task.name))
+                .spawn(move || run_task_worker(fork, context));
+            let join = match join {
+                Ok(join) => join,
+                Err(err) => {
+                    threads.shutdown();
+                    return Err(RuntimeError::ThreadSpawn(err.to_string().into()));
+                }
+            };
+            threads.workers.push(TaskWorker {
+                task: task.name.clone(),
+                reload: reload_tx,
+                join: Some(join),
+            });
+        }
+        runtime.set_tasks_detached(true);
+        Ok(threads)
+    }
+
+    /// Replace every task fork after the resource runtime restarted or
+    /// reloaded its program, and reseed the shared image from it.
+    pub(super) fn reload(&self, runtime: &Runtime) -> Result<(), RuntimeError> {
+        self.update(runtime, ForkUpdate::Replace)
+    }
+
+    /// Hand every task fork the program of `runtime` after an online change;
+    /// each fork migrates the instance state it owns.
+    pub(super) fn online_change(&self, runtime: &Runtime) -> Result<(), RuntimeError> {
+        self.update(runtime, ForkUpdate::OnlineChange)
+    }
+
+    fn update(
+        &self,
+        runtime: &Runtime,
+        update: fn(Runtime) -> ForkUpdate,
+    ) -> Result<(), RuntimeError> {
+        self.shared.reseed(runtime)?;
+        for worker in &self.workers {
+            let fork = runtime.fork_task(&worker.task)?;
+            worker
+                .reload
+                .send(update(fork))
+                .map_err(|_| RuntimeError::ControlError("task thread stopped".into()))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    /// First fault reported by a task thread since the last call.
+    pub(super) fn take_error(&self) -> Option<RuntimeError> {
+        self.error.lock().expect("task error poisoned").take()
+    }
+
+    /// Stop and join all task threads.
+    pub(super) fn shutdown(&mut self) {
+        self.halt.store(true, Ordering::SeqCst);
+        self.clock.wake();
+        for worker in &mut self.workers {
+            if let Some(join) = worker.join.take() {
+                let _ = join.join();
+            }
+        }
+    }
+}
+
+fn run_task_worker<C: Clock>(mut runtime: Runtime, context: WorkerContext<C>) {
+    // A faulted fork idles until the resource restarts it or shuts down.
+    let mut faulted = false;
+    while !context.halt.load(Ordering::SeqCst) {
+        while let Ok(update) = context.reload.try_recv() {
+            match update {
+                ForkUpdate::Replace(next) => {
+                    runtime = next;
+                    faulted = false;
+                }
+                ForkUpdate::OnlineChange(next) => {
+                    if let Err(err) = runtime.apply_online_change(next) {
+                        report_error(&context, err);
+                        faulted = true;
+                    }
+                }
+            }
+        }
+        let now_raw = context.clock.now();
+        if !faulted && !context.paused.load(Ordering::SeqCst) {
+            runtime.set_current_time(scaled_time(now_raw, context.time_scale));
+            if let Err(err) = run_task_once(&mut runtime, &context) {
+                report_error(&context, err);
+                faulted = true;
+            }
+        }
+        let interval = context.poll.as_nanos();
+        if interval <= 0 {
+            thread::yield_now();
+            continue;
+        }
+        let sleep_interval = scaled_sleep_interval(context.poll, context.time_scale);
+        let deadline =
+            Duration::from_nanos(now_raw.as_nanos().saturating_add(sleep_interval.as_nanos()));
+        context.clock.sleep_until(deadline);
+    }
+}
+
+fn report_error<C: Clock>(context: &WorkerContext<C>, err: RuntimeError) {
+    let mut slot = context.error.lock().expect("task error poisoned");
+    if slot.is_none() {
+        *slot = Some(err);
+    }
+}
+
+fn run_task_once<C: Clock>(
+    runtime: &mut Runtime,
+    context: &WorkerContext<C>,
+) -> Result<(), RuntimeError> {
+    let baseline = context
+        .gate
+        .run(context.priority, || context.shared.pull(runtime))?;
+    if runtime.execute_ready_tasks()? {
+        context.gate.run(context.priority, || {
+            context.shared.publish(runtime, &baseline)
+        })?;
+    }
+    if runtime.watchdog_expired() {
+        return Err(runtime.watchdog_timeout());
+    }
+    Ok(())
+}
+
+/// Orders access to the shared image by task priority (0 = highest).
+#[derive(Debug, Default)]
+struct PriorityGate {
+    state: Mutex<GateState>,
+    cvar: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct GateState {
+    busy: bool,
+    waiting: Vec<u32>,
+}
+
+impl PriorityGate {
+    fn run<T>(&self, priority: u32, f: impl FnOnce() -> T) -> T {
+        let mut state = self.state.lock().expect("priority gate poisoned");
+        state.waiting.push(priority);
+        while state.busy || state.waiting.iter().any(|waiting| *waiting < priority) {
+            state = self.cvar.wait(state).expect("priority gate poisoned");
+        }
+        if let Some(idx) = state
+            .waiting
+            .iter()
+            .position(|waiting| *waiting == priority)
+        {
+            state.waiting.swap_remove(idx);
+        }
+        state.busy = true;
+        drop(state);
+        let result = f();
+        self.state.lock().expect("priority gate poisoned").busy = false;
+        self.cvar.notify_all();
+        result
+    }
+}
@@ -148,6 +148,42 @@ pub fn rustls_client_config(
     Ok(Arc::new(config))
 }
 
+/// PEM material for a TLS client connection: the CA that signs the server
+/// certificate and an optional client certificate/key pair.
+#[derive(Debug, Clone)]
+pub struct TlsClientPem {
+    pub ca_pem: Vec<u8>,
+    pub client_auth: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Read and validate client TLS files (CA, optional client cert and key).
+pub fn load_tls_client_pem(
+    ca_path: &Path,
+    client: Option<(&Path, &Path)>,
+) -> Result<TlsClientPem, RuntimeError> {
+    let read = |path: &Path, label: &str| {
+        std::fs::read(path).map_err(|err| {
+            RuntimeError::ControlError(format!("read {label} '{}': {err}", path.display()).into())
+        })
+    };
+    let ca_pem = read(ca_path, "tls ca")?;
+    parse_pem_certs(&ca_pem, "tls ca certificate")?;
+    let client_auth = match client {
+        Some((cert_path, key_path)) => {
+            let cert_pem = read(cert_path, "tls client cert")?;
+            parse_pem_certs(&cert_pem, "tls client certificate")?;
+            let key_pem = read(key_path, "tls client key")?;
+            parse_pem_key(&key_pem, "tls client key")?;
+            Some((cert_pem, key_pem))
+        }
+        None => None,
+    };
+    Ok(TlsClientPem {
+        ca_pem,
+        client_auth,
+    })
+}
+
 fn resolve_tls_path(path: &Path, project_root: Option<&Path>) -> Result<PathBuf, RuntimeError> {
     if path.is_absolute() {
         return Ok(path.to_path_buf());
@@ -3,13 +3,15 @@
 #![allow(missing_docs)]
 
 use std::collections::VecDeque;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
 use smol_str::SmolStr;
 
+use crate::bundle_builder::project_compile_session;
 use crate::error::RuntimeError;
-use crate::io::{IoAddress, IoSize};
+use crate::harness::SourceFile;
+use crate::io::{IoAddress, IoInterface, IoSize};
 use crate::memory::IoArea;
 use crate::value::{Duration, Value};
 use crate::Runtime;
@@ -21,6 +23,7 @@ pub struct SimulationConfig {
     pub time_scale: u32,
     pub couplings: Vec<SignalCouplingRule>,
     pub disturbances: Vec<SimulationDisturbance>,
+    pub plant: Option<PlantModelConfig>,
 }
 
 impl Default for SimulationConfig {
@@ -31,6 +34,7 @@ impl Default for SimulationConfig {
             time_scale: 1,
             couplings: Vec::new(),
             disturbances: Vec::new(),
+            plant: None,
         }
     }
 }
@@ -52,7 +56,7 @@ impl SimulationConfig {
                 format!("{}: invalid simulation config: {err}", path.display()).into(),
             )
         })?;
-        raw.into_config()
+        raw.into_config(path.parent().unwrap_or_else(|| Path::new(".")))
     }
 
     pub fn load_optional(path: impl AsRef<Path>) -> Result<Option<Self>, RuntimeError> {
@@ -64,6 +68,14 @@ impl SimulationConfig {
     }
 }
 
+/// Plant program sources (`.st` files or directories of them).
+#[derive(Debug, Clone)]
+pub struct PlantModelConfig {
+    pub sources: Vec<PathBuf>,
+    /// Project folder whose `trust-lsp.toml` selects the compile defines.
+    pub project_root: PathBuf,
+}
+
 #[derive(Debug, Clone)]
 pub struct SignalCouplingRule {
     pub source: IoAddress,
@@ -86,6 +98,9 @@ pub enum SimulationDisturbanceKind {
     Fault { message: SmolStr },
 }
 
+/// Plant source directory used when `[plant]` lists no sources.
+const DEFAULT_PLANT_DIR: &str = "plant";
+
 #[derive(Debug, Clone)]
 struct PendingEffect {
     due: Duration,
@@ -94,7 +109,81 @@ struct PendingEffect {
     value: Value,
 }
 
-#[derive(Debug, Clone)]
+/// Plant ST program running alongside the controller on the same clock.
+///
+/// The process images are mirrored: the controller's `%Q` image is the
+/// plant's `%I` image and the plant's `%Q` image is the controller's `%I`
+/// image, so `%QW0` written by the controller is read by the plant at
+/// `%IW0`.
+pub struct PlantModel {
+    runtime: Runtime,
+}
+
+impl std::fmt::Debug for PlantModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlantModel").finish_non_exhaustive()
+    }
+}
+
+impl PlantModel {
+    #[must_use]
+    pub fn new(runtime: Runtime) -> Self {
+        Self { runtime }
+    }
+
+    /// Compile the plant program from its configured sources.
+    pub fn load(config: &PlantModelConfig) -> Result<Self, RuntimeError> {
+        let mut files = Vec::new();
+        for source in &config.sources {
+            collect_plant_sources(source, &mut files)?;
+        }
+        if files.is_empty() {
+            return Err(RuntimeError::InvalidConfig(
+                "plant model has no .st sources".into(),
+            ));
+        }
+        let sources = files
+            .iter()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map(|text| SourceFile::with_path(path.display().to_string(), text))
+                    .map_err(|err| {
+                        RuntimeError::InvalidConfig(
+                            format!("{}: failed to read plant source: {err}", path.display())
+                                .into(),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let runtime = project_compile_session(&config.project_root, sources)
+            .and_then(|session| Ok(session.build_runtime()?))
+            .map_err(|err| {
+                RuntimeError::InvalidConfig(format!("plant model compile failed: {err}").into())
+            })?;
+        Ok(Self::new(runtime))
+    }
+
+    #[must_use]
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    pub fn runtime_mut(&mut self) -> &mut Runtime {
+        &mut self.runtime
+    }
+
+    /// Run one plant cycle at `now` against the controller's process image.
+    pub fn step(&mut self, now: Duration, controller: &mut Runtime) -> Result<(), RuntimeError> {
+        let commands = controller.io().outputs().to_vec();
+        copy_into_inputs(self.runtime.io_mut(), &commands);
+        self.runtime.set_current_time(now);
+        self.runtime.execute_cycle()?;
+        copy_into_inputs(controller.io_mut(), self.runtime.io().outputs());
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
 pub struct SimulationController {
     config: SimulationConfig,
     disturbances: Vec<SimulationDisturbance>,
@@ -102,6 +191,7 @@ pub struct SimulationController {
     pending_effects: VecDeque<PendingEffect>,
     next_sequence: u64,
     last_coupling_values: Vec<Option<Value>>,
+    plant: Option<PlantModel>,
 }
 
 impl SimulationController {
@@ -114,9 +204,32 @@ impl SimulationController {
             pending_effects: VecDeque::new(),
             next_sequence: 0,
             last_coupling_values,
+            plant: None,
         }
     }
 
+    /// Build a controller and compile the configured plant program, if any.
+    pub fn from_config(config: SimulationConfig) -> Result<Self, RuntimeError> {
+        let plant = config.plant.as_ref().map(PlantModel::load).transpose()?;
+        let controller = Self::new(config);
+        Ok(match plant {
+            Some(plant) => controller.with_plant(plant),
+            None => controller,
+        })
+    }
+
+    /// Attach a plant program stepped before each controller cycle.
+    #[must_use]
+    pub fn with_plant(mut self, plant: PlantModel) -> Self {
+        self.plant = Some(plant);
+        self
+    }
+
+    #[must_use]
+    pub fn plant(&self) -> Option<&PlantModel> {
+        self.plant.as_ref()
+    }
+
     #[must_use]
     pub fn enabled(&self) -> bool {
         self.config.enabled
@@ -136,6 +249,14 @@ impl SimulationController {
             return Ok(());
         }
 
+        // The plant runs first so scripted disturbances and couplings can
+        // still override the inputs it drives.
+        if let Some(plant) = self.plant.as_mut() {
+            if let Err(err) = plant.step(now, runtime) {
+                return Err(runtime.simulation_fault(format!("plant model fault: {err}")));
+            }
+        }
+
         while self.disturbance_cursor < self.disturbances.len() {
             let disturbance = &self.disturbances[self.disturbance_cursor];
             if disturbance.at.as_nanos() > now.as_nanos() {
@@ -221,6 +342,7 @@ struct SimulationToml {
     simulation: Option<SimulationSection>,
     couplings: Option<Vec<CouplingSection>>,
     disturbances: Option<Vec<DisturbanceSection>>,
+    plant: Option<PlantSection>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -240,6 +362,11 @@ struct CouplingSection {
     on_false: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct PlantSection {
+    sources: Option<Vec<String>>,
+}
+
 #[derive(Debug, Deserialize)]
 struct DisturbanceSection {
     at_ms: u64,
@@ -250,7 +377,7 @@ struct DisturbanceSection {
 }
 
 impl SimulationToml {
-    fn into_config(self) -> Result<SimulationConfig, RuntimeError> {
+    fn into_config(self, base_dir: &Path) -> Result<SimulationConfig, RuntimeError> {
         let section = self.simulation.unwrap_or_default();
         let couplings = self
             .couplings
@@ -266,7 +393,9 @@ impl SimulationToml {
             .collect::<Result<Vec<_>, _>>()?;
         disturbances.sort_by_key(|entry| entry.at.as_nanos());
 
-        let has_rules = !couplings.is_empty() || !disturbances.is_empty();
+        let plant = self.plant.map(|section| section.into_config(base_dir));
+
+        let has_rules = !couplings.is_empty() || !disturbances.is_empty() || plant.is_some();
         let enabled = section.enabled.unwrap_or(has_rules);
         let time_scale = section.time_scale.unwrap_or(1);
         if time_scale == 0 {
@@ -281,10 +410,26 @@ impl SimulationToml {
             time_scale,
             couplings,
             disturbances,
+            plant,
         })
     }
 }
 
+impl PlantSection {
+    fn into_config(self, base_dir: &Path) -> PlantModelConfig {
+        let sources = self
+            .sources
+            .unwrap_or_else(|| vec![DEFAULT_PLANT_DIR.to_string()])
+            .into_iter()
+            .map(|source| base_dir.join(source))
+            .collect();
+        PlantModelConfig {
+            sources,
+            project_root: base_dir.to_path_buf(),
+        }
+    }
+}
+
 impl CouplingSection {
     fn into_rule(self) -> Result<SignalCouplingRule, RuntimeError> {
         let source = IoAddress::parse(self.source.as_str())?;
@@ -365,6 +510,37 @@ impl DisturbanceSection {
     }
 }
 
+fn collect_plant_sources(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), RuntimeError> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let entries = std::fs::read_dir(path).map_err(|err| {
+        RuntimeError::InvalidConfig(
+            format!("{}: failed to read plant sources: {err}", path.display()).into(),
+        )
+    })?;
+    let mut found = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|entry| {
+            entry
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("st"))
+        })
+        .collect::<Vec<_>>();
+    found.sort();
+    files.extend(found);
+    Ok(())
+}
+
+/// Grow `io`'s input image to fit `image` and copy it in.
+fn copy_into_inputs(io: &mut IoInterface, image: &[u8]) {
+    let inputs = io.inputs().len().max(image.len());
+    let (outputs, memory) = (io.outputs().len(), io.memory().len());
+    io.resize(inputs, outputs, memory);
+    io.inputs_mut()[..image.len()].copy_from_slice(image);
+}
+
 fn derive_coupling_value(rule: &SignalCouplingRule, source: &Value) -> Result<Value, RuntimeError> {
     if let Some(threshold) = rule.threshold {
         let number = value_to_f64(source).unwrap_or(0.0);
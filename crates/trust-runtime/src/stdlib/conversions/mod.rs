@@ -11,6 +11,8 @@ mod string;
 mod time;
 mod util;
 
+pub(crate) use util::value_type_id;
+
 use super::StandardLibrary;
 use crate::error::RuntimeError;
 use crate::value::Value;
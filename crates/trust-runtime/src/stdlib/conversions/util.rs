@@ -180,7 +180,7 @@ fn normalize_string_type_id(ty: TypeId) -> TypeId {
     }
 }
 
-pub(super) fn value_type_id(value: &Value) -> Option<TypeId> {
+pub(crate) fn value_type_id(value: &Value) -> Option<TypeId> {
     match value {
         Value::Bool(_) => Some(TypeId::BOOL),
         Value::SInt(_) => Some(TypeId::SINT),
@@ -1,10 +1,11 @@
-//! Standard function blocks (TON, CTU, etc.).
+//! Standard function blocks (TON, CTU, etc.) and plant simulation blocks.
 
 #![allow(missing_docs)]
 
 mod bistable;
 mod counters;
 mod instance;
+mod plant;
 mod registry;
 mod state;
 mod timers;
@@ -12,6 +13,7 @@ mod triggers;
 
 pub use bistable::{Rs, Sr};
 pub use counters::{CounterOutput, CounterUpDownOutput, Ctd, Ctu, Ctud};
+pub use plant::{DeadTime, FirstOrderLag, Integrator, TankLevel, TankOutput, Valve, ValveOutput};
 pub use registry::{builtin_kind, standard_function_blocks, BuiltinFbKind};
 pub use timers::{TimerOutput, Tof, Ton, Tp};
 pub use triggers::{FTrig, RTrig};
@@ -36,5 +38,10 @@ pub fn execute_builtin(
         BuiltinFbKind::Tp => timers::exec_tp(ctx, instance_id),
         BuiltinFbKind::Ton => timers::exec_ton(ctx, instance_id),
         BuiltinFbKind::Tof => timers::exec_tof(ctx, instance_id),
+        BuiltinFbKind::PlantLag => plant::exec_lag(ctx, instance_id),
+        BuiltinFbKind::PlantDeadTime => plant::exec_deadtime(ctx, instance_id),
+        BuiltinFbKind::PlantIntegrator => plant::exec_integrator(ctx, instance_id),
+        BuiltinFbKind::PlantValve => plant::exec_valve(ctx, instance_id),
+        BuiltinFbKind::PlantTank => plant::exec_tank(ctx, instance_id),
     }
 }
@@ -0,0 +1,374 @@
+//! Plant model blocks for process simulation (PLANT_LAG, PLANT_TANK, ...).
+//!
+//! Rates are per second; TIME inputs are time constants or travel times.
+
+use std::collections::VecDeque;
+
+use crate::error::RuntimeError;
+use crate::eval::EvalContext;
+use crate::memory::InstanceId;
+use crate::value::{ArrayValue, Duration, Value};
+
+use super::instance::{read_bool, write_bool};
+use super::state::{STATE_SAMPLES, STATE_SAMPLE_TIMES};
+use super::timers::elapsed_since;
+
+/// Upper bound on buffered PLANT_DEADTIME samples; longer delays record the
+/// input at a resolution of `DELAY / MAX_DEADTIME_SAMPLES`.
+const MAX_DEADTIME_SAMPLES: i64 = 1_000;
+
+/// Full valve stroke in percent.
+const VALVE_FULL_OPEN: f64 = 100.0;
+
+/// First-order lag (PT1) with unity gain.
+#[derive(Debug, Clone, Default)]
+pub struct FirstOrderLag {
+    out: f64,
+}
+
+impl FirstOrderLag {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance by `delta`; a non-positive time constant passes the input through.
+    pub fn step(&mut self, input: f64, t: Duration, delta: Duration) -> f64 {
+        if t.as_nanos() <= 0 {
+            self.out = input;
+        } else {
+            let decay = (-seconds(delta) / seconds(t)).exp();
+            self.out = input + (self.out - input) * decay;
+        }
+        self.out
+    }
+}
+
+/// Transport delay: the output follows the input `delay` later.
+#[derive(Debug, Clone, Default)]
+pub struct DeadTime {
+    samples: VecDeque<(Duration, f64)>,
+    out: f64,
+}
+
+impl DeadTime {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `input` at `now` and return the sample that is `delay` old.
+    ///
+    /// Inputs closer than `delay / MAX_DEADTIME_SAMPLES` to the last recorded
+    /// sample are skipped, so the buffer stays bounded for any scan rate.
+    pub fn step(&mut self, input: f64, delay: Duration, now: Duration) -> f64 {
+        let delay = delay.as_nanos().max(0);
+        let resolution = delay / MAX_DEADTIME_SAMPLES;
+        let skip = self
+            .samples
+            .back()
+            .is_some_and(|(at, _)| now.as_nanos().saturating_sub(at.as_nanos()) < resolution);
+        if !skip {
+            self.samples.push_back((now, input));
+        }
+        while let Some((at, value)) = self.samples.front().copied() {
+            if at.as_nanos().saturating_add(delay) > now.as_nanos() {
+                break;
+            }
+            self.out = value;
+            self.samples.pop_front();
+        }
+        self.out
+    }
+}
+
+/// Integrator: `OUT += IN * dt`, loaded with `Y0` while `RESET` is set.
+#[derive(Debug, Clone, Default)]
+pub struct Integrator {
+    out: f64,
+}
+
+impl Integrator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn step(&mut self, input: f64, reset: bool, y0: f64, delta: Duration) -> f64 {
+        if reset {
+            self.out = y0;
+        } else {
+            self.out += input * seconds(delta);
+        }
+        self.out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValveOutput {
+    pub pos: f64,
+    pub flow: f64,
+    pub opened: bool,
+    pub closed: bool,
+}
+
+/// Control valve whose position follows the command (0..100 %) at a
+/// limited stroke speed; flow is linear in the position.
+#[derive(Debug, Clone, Default)]
+pub struct Valve {
+    pos: f64,
+}
+
+impl Valve {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `travel` is the time for a full stroke; zero moves instantly.
+    pub fn step(&mut self, cmd: f64, travel: Duration, kv: f64, delta: Duration) -> ValveOutput {
+        let target = cmd.clamp(0.0, VALVE_FULL_OPEN);
+        if travel.as_nanos() <= 0 {
+            self.pos = target;
+        } else {
+            let max_step = VALVE_FULL_OPEN * seconds(delta) / seconds(travel);
+            self.pos += (target - self.pos).clamp(-max_step, max_step);
+        }
+        ValveOutput {
+            pos: self.pos,
+            flow: kv * self.pos / VALVE_FULL_OPEN,
+            opened: self.pos >= VALVE_FULL_OPEN,
+            closed: self.pos <= 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TankOutput {
+    pub level: f64,
+    pub volume: f64,
+    pub outflow: f64,
+    pub overflow: bool,
+    pub empty: bool,
+}
+
+/// Open tank with a constant cross-section.
+///
+/// The drawn outflow is limited to what the tank holds; the level is
+/// clamped to `HEIGHT` (no limit when `HEIGHT <= 0`). A non-positive
+/// `AREA` is treated as 1.
+#[derive(Debug, Clone, Default)]
+pub struct TankLevel {
+    volume: f64,
+}
+
+impl TankLevel {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn step(
+        &mut self,
+        qin: f64,
+        qout: f64,
+        area: f64,
+        height: f64,
+        reset: bool,
+        l0: f64,
+        delta: Duration,
+    ) -> TankOutput {
+        let area = if area > 0.0 { area } else { 1.0 };
+        let capacity = if height > 0.0 {
+            area * height
+        } else {
+            f64::INFINITY
+        };
+        let qin = qin.max(0.0);
+        let mut outflow = qout.max(0.0);
+        if reset {
+            self.volume = (l0 * area).clamp(0.0, capacity);
+        } else {
+            let dt = seconds(delta);
+            let available = if dt > 0.0 {
+                qin + self.volume / dt
+            } else if self.volume > 0.0 {
+                f64::INFINITY
+            } else {
+                qin
+            };
+            outflow = outflow.min(available);
+            self.volume = (self.volume + (qin - outflow) * dt).clamp(0.0, capacity);
+        }
+        TankOutput {
+            level: self.volume / area,
+            volume: self.volume,
+            outflow,
+            overflow: self.volume >= capacity,
+            empty: self.volume <= 0.0,
+        }
+    }
+}
+
+pub(super) fn exec_lag(
+    ctx: &mut EvalContext<'_>,
+    instance_id: InstanceId,
+) -> Result<(), RuntimeError> {
+    let input = read_real(ctx, instance_id, "IN")?;
+    let t = read_time(ctx, instance_id, "T")?;
+    let out = read_real(ctx, instance_id, "OUT")?;
+    let delta = elapsed_since(ctx, instance_id)?;
+    let out = FirstOrderLag { out }.step(input, t, delta);
+    write_real(ctx, instance_id, "OUT", out);
+    Ok(())
+}
+
+pub(super) fn exec_deadtime(
+    ctx: &mut EvalContext<'_>,
+    instance_id: InstanceId,
+) -> Result<(), RuntimeError> {
+    let input = read_real(ctx, instance_id, "IN")?;
+    let delay = read_time(ctx, instance_id, "DELAY")?;
+    let out = read_real(ctx, instance_id, "OUT")?;
+    let mut deadtime = DeadTime {
+        samples: read_samples(ctx, instance_id)?,
+        out,
+    };
+    let out = deadtime.step(input, delay, ctx.now);
+    write_real(ctx, instance_id, "OUT", out);
+    write_samples(ctx, instance_id, &deadtime.samples);
+    Ok(())
+}
+
+pub(super) fn exec_integrator(
+    ctx: &mut EvalContext<'_>,
+    instance_id: InstanceId,
+) -> Result<(), RuntimeError> {
+    let input = read_real(ctx, instance_id, "IN")?;
+    let reset = read_bool(ctx, instance_id, "RESET")?;
+    let y0 = read_real(ctx, instance_id, "Y0")?;
+    let out = read_real(ctx, instance_id, "OUT")?;
+    let delta = elapsed_since(ctx, instance_id)?;
+    let out = Integrator { out }.step(input, reset, y0, delta);
+    write_real(ctx, instance_id, "OUT", out);
+    Ok(())
+}
+
+pub(super) fn exec_valve(
+    ctx: &mut EvalContext<'_>,
+    instance_id: InstanceId,
+) -> Result<(), RuntimeError> {
+    let cmd = read_real(ctx, instance_id, "CMD")?;
+    let travel = read_time(ctx, instance_id, "TRAVEL")?;
+    let kv = read_real(ctx, instance_id, "KV")?;
+    let pos = read_real(ctx, instance_id, "POS")?;
+    let delta = elapsed_since(ctx, instance_id)?;
+    let out = Valve { pos }.step(cmd, travel, kv, delta);
+    write_real(ctx, instance_id, "POS", out.pos);
+    write_real(ctx, instance_id, "FLOW", out.flow);
+    write_bool(ctx, instance_id, "OPENED", out.opened);
+    write_bool(ctx, instance_id, "CLOSED", out.closed);
+    Ok(())
+}
+
+pub(super) fn exec_tank(
+    ctx: &mut EvalContext<'_>,
+    instance_id: InstanceId,
+) -> Result<(), RuntimeError> {
+    let qin = read_real(ctx, instance_id, "QIN")?;
+    let qout = read_real(ctx, instance_id, "QOUT")?;
+    let area = read_real(ctx, instance_id, "AREA")?;
+    let height = read_real(ctx, instance_id, "HEIGHT")?;
+    let reset = read_bool(ctx, instance_id, "RESET")?;
+    let l0 = read_real(ctx, instance_id, "L0")?;
+    let volume = read_real(ctx, instance_id, "VOLUME")?;
+    let delta = elapsed_since(ctx, instance_id)?;
+    let out = TankLevel { volume }.step(qin, qout, area, height, reset, l0, delta);
+    write_real(ctx, instance_id, "LEVEL", out.level);
+    write_real(ctx, instance_id, "VOLUME", out.volume);
+    write_real(ctx, instance_id, "OUTFLOW", out.outflow);
+    write_bool(ctx, instance_id, "OVERFLOW", out.overflow);
+    write_bool(ctx, instance_id, "EMPTY", out.empty);
+    Ok(())
+}
+
+fn seconds(value: Duration) -> f64 {
+    value.as_nanos() as f64 / 1_000_000_000.0
+}
+
+fn read_real(
+    ctx: &EvalContext<'_>,
+    instance_id: InstanceId,
+    name: &str,
+) -> Result<f64, RuntimeError> {
+    match ctx.storage.get_instance_var(instance_id, name) {
+        Some(Value::Real(value)) => Ok(f64::from(*value)),
+        Some(Value::LReal(value)) => Ok(*value),
+        Some(Value::Null) | None => Ok(0.0),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+fn write_real(ctx: &mut EvalContext<'_>, instance_id: InstanceId, name: &str, value: f64) {
+    ctx.storage
+        .set_instance_var(instance_id, name, Value::Real(value as f32));
+}
+
+fn read_time(
+    ctx: &EvalContext<'_>,
+    instance_id: InstanceId,
+    name: &str,
+) -> Result<Duration, RuntimeError> {
+    match ctx.storage.get_instance_var(instance_id, name) {
+        Some(Value::Time(value)) | Some(Value::LTime(value)) => Ok(*value),
+        Some(Value::Null) | None => Ok(Duration::ZERO),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+fn read_samples(
+    ctx: &EvalContext<'_>,
+    instance_id: InstanceId,
+) -> Result<VecDeque<(Duration, f64)>, RuntimeError> {
+    let times = ctx
+        .storage
+        .get_instance_var(instance_id, STATE_SAMPLE_TIMES);
+    let values = ctx.storage.get_instance_var(instance_id, STATE_SAMPLES);
+    match (times, values) {
+        (Some(Value::Array(times)), Some(Value::Array(values))) => times
+            .elements
+            .iter()
+            .zip(values.elements.iter())
+            .map(|pair| match pair {
+                (Value::LTime(at), Value::LReal(value)) => Ok((*at, *value)),
+                _ => Err(RuntimeError::TypeMismatch),
+            })
+            .collect(),
+        (None, None) => Ok(VecDeque::new()),
+        _ => Err(RuntimeError::TypeMismatch),
+    }
+}
+
+fn write_samples(
+    ctx: &mut EvalContext<'_>,
+    instance_id: InstanceId,
+    samples: &VecDeque<(Duration, f64)>,
+) {
+    let dimensions = vec![(0, samples.len() as i64 - 1)];
+    let times = ArrayValue {
+        elements: samples.iter().map(|(at, _)| Value::LTime(*at)).collect(),
+        dimensions: dimensions.clone(),
+    };
+    let values = ArrayValue {
+        elements: samples
+            .iter()
+            .map(|(_, value)| Value::LReal(*value))
+            .collect(),
+        dimensions,
+    };
+    ctx.storage
+        .set_instance_var(instance_id, STATE_SAMPLE_TIMES, Value::Array(times));
+    ctx.storage
+        .set_instance_var(instance_id, STATE_SAMPLES, Value::Array(values));
+}
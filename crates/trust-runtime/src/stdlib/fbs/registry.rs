@@ -14,6 +14,11 @@ pub enum BuiltinFbKind {
     Tp,
     Ton,
     Tof,
+    PlantLag,
+    PlantDeadTime,
+    PlantIntegrator,
+    PlantValve,
+    PlantTank,
 }
 
 pub fn builtin_kind(name: &str) -> Option<BuiltinFbKind> {
@@ -35,6 +40,11 @@ pub fn builtin_kind(name: &str) -> Option<BuiltinFbKind> {
         "TP" | "TP_LTIME" => Some(BuiltinFbKind::Tp),
         "TON" | "TON_LTIME" => Some(BuiltinFbKind::Ton),
         "TOF" | "TOF_LTIME" => Some(BuiltinFbKind::Tof),
+        "PLANT_LAG" => Some(BuiltinFbKind::PlantLag),
+        "PLANT_DEADTIME" => Some(BuiltinFbKind::PlantDeadTime),
+        "PLANT_INTEGRATOR" => Some(BuiltinFbKind::PlantIntegrator),
+        "PLANT_VALVE" => Some(BuiltinFbKind::PlantValve),
+        "PLANT_TANK" => Some(BuiltinFbKind::PlantTank),
         _ => None,
     }
 }
@@ -175,5 +185,61 @@ pub fn standard_function_blocks() -> Vec<FunctionBlockDef> {
         ));
     }
 
+    defs.extend([
+        fb(
+            "PLANT_LAG",
+            &[
+                ("IN", TypeId::REAL, ParamDirection::In),
+                ("T", TypeId::TIME, ParamDirection::In),
+                ("OUT", TypeId::REAL, ParamDirection::Out),
+            ],
+        ),
+        fb(
+            "PLANT_DEADTIME",
+            &[
+                ("IN", TypeId::REAL, ParamDirection::In),
+                ("DELAY", TypeId::TIME, ParamDirection::In),
+                ("OUT", TypeId::REAL, ParamDirection::Out),
+            ],
+        ),
+        fb(
+            "PLANT_INTEGRATOR",
+            &[
+                ("IN", TypeId::REAL, ParamDirection::In),
+                ("RESET", TypeId::BOOL, ParamDirection::In),
+                ("Y0", TypeId::REAL, ParamDirection::In),
+                ("OUT", TypeId::REAL, ParamDirection::Out),
+            ],
+        ),
+        fb(
+            "PLANT_VALVE",
+            &[
+                ("CMD", TypeId::REAL, ParamDirection::In),
+                ("TRAVEL", TypeId::TIME, ParamDirection::In),
+                ("KV", TypeId::REAL, ParamDirection::In),
+                ("POS", TypeId::REAL, ParamDirection::Out),
+                ("FLOW", TypeId::REAL, ParamDirection::Out),
+                ("OPENED", TypeId::BOOL, ParamDirection::Out),
+                ("CLOSED", TypeId::BOOL, ParamDirection::Out),
+            ],
+        ),
+        fb(
+            "PLANT_TANK",
+            &[
+                ("QIN", TypeId::REAL, ParamDirection::In),
+                ("QOUT", TypeId::REAL, ParamDirection::In),
+                ("AREA", TypeId::REAL, ParamDirection::In),
+                ("HEIGHT", TypeId::REAL, ParamDirection::In),
+                ("RESET", TypeId::BOOL, ParamDirection::In),
+                ("L0", TypeId::REAL, ParamDirection::In),
+                ("LEVEL", TypeId::REAL, ParamDirection::Out),
+                ("VOLUME", TypeId::REAL, ParamDirection::Out),
+                ("OUTFLOW", TypeId::REAL, ParamDirection::Out),
+                ("OVERFLOW", TypeId::BOOL, ParamDirection::Out),
+                ("EMPTY", TypeId::BOOL, ParamDirection::Out),
+            ],
+        ),
+    ]);
+
     defs
 }
@@ -5,3 +5,5 @@ pub(super) const STATE_LAST_TIME: &str = "__ST_LAST_TIME";
 pub(super) const STATE_PREV_IN: &str = "__ST_PREV_IN";
 pub(super) const STATE_TIMING: &str = "__ST_TIMING";
 pub(super) const STATE_ACTIVE: &str = "__ST_ACTIVE";
+pub(super) const STATE_SAMPLE_TIMES: &str = "__ST_SAMPLE_TIMES";
+pub(super) const STATE_SAMPLES: &str = "__ST_SAMPLES";
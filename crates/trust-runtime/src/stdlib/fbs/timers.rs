@@ -273,7 +273,7 @@ fn write_time_value(
     ctx.storage.set_instance_var(instance_id, name, value);
 }
 
-fn elapsed_since(
+pub(super) fn elapsed_since(
     ctx: &mut EvalContext<'_>,
     instance_id: InstanceId,
 ) -> Result<Duration, RuntimeError> {
@@ -112,3 +112,43 @@ impl From<u16> for Value {
         Value::UInt(value)
     }
 }
+
+/// IEC type name of a runtime value (`STRING`, struct/enum type name, ...).
+pub fn value_type_name(value: &Value) -> Option<String> {
+    let name = match value {
+        Value::Bool(_) => "BOOL",
+        Value::SInt(_) => "SINT",
+        Value::Int(_) => "INT",
+        Value::DInt(_) => "DINT",
+        Value::LInt(_) => "LINT",
+        Value::USInt(_) => "USINT",
+        Value::UInt(_) => "UINT",
+        Value::UDInt(_) => "UDINT",
+        Value::ULInt(_) => "ULINT",
+        Value::Real(_) => "REAL",
+        Value::LReal(_) => "LREAL",
+        Value::Byte(_) => "BYTE",
+        Value::Word(_) => "WORD",
+        Value::DWord(_) => "DWORD",
+        Value::LWord(_) => "LWORD",
+        Value::Time(_) => "TIME",
+        Value::LTime(_) => "LTIME",
+        Value::Date(_) => "DATE",
+        Value::LDate(_) => "LDATE",
+        Value::Tod(_) => "TOD",
+        Value::LTod(_) => "LTOD",
+        Value::Dt(_) => "DT",
+        Value::Ldt(_) => "LDT",
+        Value::String(_) => "STRING",
+        Value::WString(_) => "WSTRING",
+        Value::Char(_) => "CHAR",
+        Value::WChar(_) => "WCHAR",
+        Value::Array(_) => "ARRAY",
+        Value::Struct(value) => return Some(value.type_name.to_string()),
+        Value::Enum(value) => return Some(value.type_name.to_string()),
+        Value::Reference(_) => "REF",
+        Value::Instance(_) => "INSTANCE",
+        Value::Null => "NULL",
+    };
+    Some(name.to_string())
+}
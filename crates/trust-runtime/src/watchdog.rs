@@ -131,7 +131,7 @@ impl Default for WatchdogPolicy {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FaultInfo {
     pub reason: SmolStr,
 }
@@ -28,7 +28,7 @@ use crate::memory::IoArea;
 use crate::security::{AccessRole, TlsMaterials};
 use crate::setup::SetupOptions;
 
-mod deploy;
+pub mod deploy;
 pub mod ide;
 pub mod pairing;
 
@@ -739,7 +739,7 @@ END_PROGRAM
 }
 
 #[test]
-fn encoder_does_not_emit_partial_if_with_unsupported_elsif() {
+fn encoder_emits_call_in_elsif_condition() {
     let source = r#"
 FUNCTION IsReady : BOOL
 IsReady := TRUE;
@@ -785,9 +785,10 @@ END_PROGRAM
     let code_start = program.code_offset as usize;
     let code_end = code_start + program.code_length as usize;
     let code = &bodies[code_start..code_end];
-    assert_eq!(code, &[0x00]);
-
-    let _ = module.section(SectionId::DebugMap);
+    assert_eq!(program.flags, 0);
+    let opcodes = collect_opcodes(code);
+    assert!(opcodes.contains(&0x05));
+    assert!(!opcodes.contains(&0x00));
 }
 
 #[test]
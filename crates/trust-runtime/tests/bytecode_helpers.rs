@@ -79,6 +79,7 @@ pub fn base_pou_index() -> PouIndex {
             id: 1,
             name_idx: 2,
             kind: PouKind::Program,
+            flags: 0,
             code_offset: 0,
             code_length: 1,
             local_ref_start: 0,
@@ -314,6 +315,7 @@ pub fn sample_pou_index() -> PouIndex {
                 id: 1,
                 name_idx: 2,
                 kind: PouKind::Program,
+                flags: 0,
                 code_offset: 0,
                 code_length: 1,
                 local_ref_start: 0,
@@ -332,6 +334,7 @@ pub fn sample_pou_index() -> PouIndex {
                 id: 2,
                 name_idx: 10,
                 kind: PouKind::FunctionBlock,
+                flags: 0,
                 code_offset: 1,
                 code_length: 1,
                 local_ref_start: 0,
@@ -284,3 +284,41 @@ fn var_meta_decode() {
         Some(SectionData::RetainInit(_))
     ));
 }
+
+#[test]
+fn pou_decls_and_init_image_decode() {
+    let source = r#"
+        FUNCTION_BLOCK Acc
+        VAR_INPUT
+            inc : DINT := 2;
+        END_VAR
+        VAR
+            total : DINT := 10;
+        END_VAR
+        total := total + inc;
+        END_FUNCTION_BLOCK
+
+        PROGRAM Main
+        VAR
+            out AT %QX0.0 : BOOL;
+            acc : Acc;
+            values : ARRAY[1..2] OF INT;
+        END_VAR
+        acc();
+        END_PROGRAM
+    "#;
+    let bytes = trust_runtime::harness::bytecode_bytes_from_source(source).unwrap();
+    let module = BytecodeModule::decode(&bytes).expect("decode");
+    let Some(SectionData::PouDecls(decls)) = module.section(SectionId::PouDecls) else {
+        panic!("expected POU declarations");
+    };
+    assert_eq!(decls.entries.len(), 2);
+    let Some(SectionData::InitImage(image)) = module.section(SectionId::InitImage) else {
+        panic!("expected initial image");
+    };
+    assert_eq!(image.instances.len(), 2);
+    assert_eq!(image.io_names.len(), 1);
+
+    let decoded = BytecodeModule::decode(&module.encode().expect("encode")).expect("decode");
+    assert_eq!(decoded, module);
+}
@@ -1,7 +1,9 @@
 mod bytecode_helpers;
 
 use bytecode_helpers::{base_module, module_with_debug};
-use trust_runtime::bytecode::{BytecodeError, BytecodeModule, SectionData, SectionId};
+use trust_runtime::bytecode::{
+    BytecodeError, BytecodeModule, SectionData, SectionId, POU_FLAG_PARTIAL_BODY,
+};
 
 #[test]
 fn opcode_validation() {
@@ -63,6 +65,18 @@ fn call_validation() {
     assert!(matches!(err, BytecodeError::InvalidPouId(99)));
 }
 
+#[test]
+fn partial_body_validation() {
+    let mut module = base_module();
+    if let Some(SectionData::PouIndex(index)) = module.section_mut(SectionId::PouIndex) {
+        index.entries[0].flags |= POU_FLAG_PARTIAL_BODY;
+    }
+    let bytes = module.encode().expect("encode");
+    let decoded = BytecodeModule::decode(&bytes).expect("decode");
+    let err = decoded.validate().unwrap_err();
+    assert!(matches!(err, BytecodeError::MissingPouBody(_)));
+}
+
 #[test]
 fn debug_map_validation() {
     let mut module = module_with_debug();
@@ -0,0 +1,825 @@
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use trust_runtime::debug::{DebugBreakpoint, DebugStopReason};
+use trust_runtime::error::RuntimeError;
+use trust_runtime::harness::TestHarness;
+use trust_runtime::value::Value;
+
+/// Compare the evaluator against the VM, both next to the compiled sources
+/// and in a runtime rebuilt from the bytecode alone.
+fn assert_same_outputs(source: &str, names: &[&str], cycles: u32) {
+    let vm = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    assert_same_outputs_as(vm, source, names, cycles);
+    let restored = TestHarness::from_source_bytecode_only(source).unwrap();
+    assert_same_outputs_as(restored, source, names, cycles);
+}
+
+fn assert_same_outputs_as(mut vm: TestHarness, source: &str, names: &[&str], cycles: u32) {
+    let mut tree = TestHarness::from_source(source).unwrap();
+    for _ in 0..cycles {
+        let tree_result = tree.cycle();
+        let vm_result = vm.cycle();
+        assert!(tree_result.errors.is_empty(), "{:?}", tree_result.errors);
+        assert!(vm_result.errors.is_empty(), "{:?}", vm_result.errors);
+        for name in names {
+            assert_eq!(tree.get_output(name), vm.get_output(name), "{name}");
+        }
+    }
+}
+
+#[test]
+fn counter_program_runs_from_bytecode() {
+    let source = r#"
+        PROGRAM Demo
+        VAR
+            count: DINT := 0;
+        END_VAR
+        count := count + 1;
+        END_PROGRAM
+    "#;
+
+    let mut harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Demo"));
+    harness.assert_eq("count", 0i32);
+    harness.cycle();
+    harness.cycle();
+    harness.assert_eq("count", 2i32);
+}
+
+#[test]
+fn control_flow_matches_evaluator() {
+    let source = r#"
+        PROGRAM Flow
+        VAR
+            flag: BOOL := TRUE;
+            count: DINT := 0;
+            total: DINT := 0;
+            i: DINT := 0;
+            arr: ARRAY[0..1, 0..2] OF DINT;
+            ratio: REAL := 0.5;
+        END_VAR
+        VAR_TEMP
+            scratch: DINT;
+        END_VAR
+
+        IF flag THEN
+            count := count + 1;
+        ELSIF count = 0 THEN
+            count := count + 10;
+        ELSE
+            count := count + 100;
+        END_IF;
+
+        CASE count OF
+            1: total := total + 1;
+            2, 3: total := total + 2;
+            4..6: total := total + 3;
+        ELSE
+            total := total - 1;
+        END_CASE;
+
+        scratch := 0;
+        FOR i := 0 TO 4 BY 2 DO
+            scratch := scratch + i;
+        END_FOR;
+        arr[1, 2] := scratch;
+        arr[0, 1] := arr[1, 2] * 2;
+
+        WHILE scratch < 20 DO
+            scratch := scratch + 3;
+        END_WHILE;
+
+        REPEAT
+            scratch := scratch - 1;
+        UNTIL scratch MOD 5 = 0
+        END_REPEAT;
+        total := total + scratch;
+        ratio := ratio * 2.0;
+        END_PROGRAM
+    "#;
+
+    let harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Flow"));
+    assert_same_outputs(source, &["count", "total", "i", "arr", "ratio"], 8);
+}
+
+#[test]
+fn fb_outputs_resolve_through_instances() {
+    let source = r#"
+        TYPE Point : STRUCT
+            x: DINT;
+            y: DINT;
+        END_STRUCT
+        END_TYPE
+
+        FUNCTION_BLOCK Acc
+        VAR_OUTPUT
+            sum: DINT := 7;
+        END_VAR
+        sum := sum + 1;
+        END_FUNCTION_BLOCK
+
+        PROGRAM Main
+        VAR
+            acc: Acc;
+            pt: Point;
+            seen: DINT;
+        END_VAR
+        seen := acc.sum + seen;
+        pt.x := pt.x + seen;
+        pt.y := pt.x - 1;
+        END_PROGRAM
+    "#;
+
+    let harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    assert_same_outputs(source, &["seen", "pt"], 3);
+}
+
+#[test]
+fn function_calls_run_from_bytecode() {
+    let source = r#"
+        FUNCTION Twice : DINT
+        VAR_INPUT
+            x: DINT;
+        END_VAR
+        Twice := x * 2;
+        END_FUNCTION
+
+        PROGRAM Main
+        VAR
+            count: DINT := 1;
+        END_VAR
+        count := Twice(count);
+        END_PROGRAM
+    "#;
+
+    let mut harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    harness.cycle();
+    harness.cycle();
+    harness.assert_eq("count", 4i32);
+}
+
+#[test]
+fn call_arguments_match_evaluator() {
+    let source = r#"
+        FUNCTION Scale : DINT
+        VAR_INPUT
+            x: DINT;
+            factor: DINT := 3;
+        END_VAR
+        VAR_OUTPUT
+            doubled: DINT;
+        END_VAR
+        VAR_IN_OUT
+            acc: DINT;
+        END_VAR
+        doubled := x * 2;
+        acc := acc + x;
+        Scale := x * factor;
+        END_FUNCTION
+
+        FUNCTION Guarded : DINT
+        VAR_INPUT
+            EN: BOOL := TRUE;
+            x: DINT;
+        END_VAR
+        VAR_OUTPUT
+            ENO: BOOL;
+        END_VAR
+        Guarded := x + 1;
+        ENO := TRUE;
+        END_FUNCTION
+
+        PROGRAM Main
+        VAR
+            a: DINT := 1;
+            b: DINT;
+            c: DINT;
+            acc: DINT;
+            ok: BOOL;
+            skipped: DINT;
+        END_VAR
+        a := Scale(acc := acc, x := a, doubled => b) + 1;
+        c := Scale(x := 2, factor := a, doubled => b, acc := acc);
+        skipped := Guarded(EN := a > 100, x := a, ENO => ok);
+        END_PROGRAM
+    "#;
+
+    let harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    assert_same_outputs(source, &["a", "b", "c", "acc", "ok", "skipped"], 4);
+}
+
+#[test]
+fn fb_and_method_calls_match_evaluator() {
+    let source = r#"
+        INTERFACE IShape
+        METHOD Area : DINT
+        END_METHOD
+        END_INTERFACE
+
+        FUNCTION_BLOCK Base IMPLEMENTS IShape
+        VAR
+            size: DINT;
+        END_VAR
+        VAR_OUTPUT
+            calls: DINT;
+        END_VAR
+        calls := calls + 1;
+
+        METHOD PUBLIC SetSize
+        VAR_INPUT
+            value: DINT;
+        END_VAR
+        size := value;
+        END_METHOD
+
+        METHOD PUBLIC Area : DINT
+        Area := size * size;
+        END_METHOD
+
+        METHOD PUBLIC Describe : DINT
+        Describe := THIS.Area() + 1;
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        FUNCTION_BLOCK Doubled EXTENDS Base
+        METHOD PUBLIC Area : DINT
+        Area := SUPER.Area() * 2;
+        END_METHOD
+        END_FUNCTION_BLOCK
+
+        PROGRAM Main
+        VAR
+            base: Base;
+            twice: Doubled;
+            shape: IShape;
+            area: DINT;
+            desc: DINT;
+            calls: DINT;
+        END_VAR
+        base.SetSize(3);
+        base(calls => calls);
+        twice.SetSize(value := 2);
+        twice();
+        shape := twice;
+        area := shape.Area() + base.Area();
+        desc := twice.Describe();
+        calls := calls + base.calls;
+        END_PROGRAM
+    "#;
+
+    let harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    assert_same_outputs(source, &["area", "desc", "calls"], 3);
+}
+
+#[test]
+fn strings_and_references_match_evaluator() {
+    let source = r#"
+        TYPE Pair : STRUCT
+            left: DINT;
+            right: DINT;
+        END_STRUCT
+        END_TYPE
+
+        PROGRAM Main
+        VAR
+            text: STRING := 'ab';
+            size: DINT;
+            pair: Pair;
+            values: ARRAY[1..3] OF DINT;
+            idx: DINT := 1;
+            target: REF_TO DINT;
+            pair_ref: REF_TO Pair;
+            bytes: DINT;
+        END_VAR
+        text := CONCAT(text, 'c');
+        size := LEN(text);
+        target := REF(values[idx]);
+        target^ := target^ + size;
+        pair_ref := REF(pair);
+        pair.left := values[idx] + pair_ref^.right;
+        pair.right := size;
+        bytes := SIZEOF(DINT);
+        idx := idx MOD 3 + 1;
+        END_PROGRAM
+    "#;
+
+    let harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    assert_same_outputs(
+        source,
+        &["text", "size", "pair", "values", "idx", "bytes"],
+        4,
+    );
+}
+
+#[test]
+fn loop_exits_and_jumps_match_evaluator() {
+    let source = r#"
+        FUNCTION FirstAbove : DINT
+        VAR_INPUT
+            limit: DINT;
+        END_VAR
+        VAR
+            i: DINT;
+        END_VAR
+        FOR i := 1 TO 100 DO
+            IF i * i > limit THEN
+                RETURN i;
+            END_IF;
+        END_FOR;
+        FirstAbove := -1;
+        END_FUNCTION
+
+        PROGRAM Main
+        VAR
+            i: DINT;
+            odd: DINT;
+            stop: DINT;
+            first: DINT;
+            jumps: DINT;
+        END_VAR
+        odd := 0;
+        FOR i := 1 TO 10 DO
+            IF i MOD 2 = 0 THEN
+                CONTINUE;
+            END_IF;
+            odd := odd + i;
+        END_FOR;
+        stop := 0;
+        WHILE TRUE DO
+            stop := stop + 1;
+            IF stop >= 4 THEN
+                EXIT;
+            END_IF;
+        END_WHILE;
+        first := FirstAbove(20);
+        jumps := 1;
+        JMP skip;
+        jumps := 100;
+    skip:
+        jumps := jumps + 1;
+        END_PROGRAM
+    "#;
+
+    let harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    assert_same_outputs(source, &["odd", "stop", "first", "jumps"], 2);
+}
+
+#[test]
+fn dynamic_index_out_of_bounds_faults() {
+    let source = r#"
+        PROGRAM Main
+        VAR
+            values: ARRAY[0..2] OF DINT;
+            idx: DINT := 3;
+            seen: DINT;
+        END_VAR
+        seen := values[idx];
+        END_PROGRAM
+    "#;
+
+    let mut harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let result = harness.cycle();
+    assert!(matches!(
+        result.errors.as_slice(),
+        [RuntimeError::IndexOutOfBounds {
+            index: 3,
+            lower: 0,
+            upper: 2
+        }]
+    ));
+}
+
+#[test]
+fn for_step_zero_faults() {
+    let source = r#"
+        PROGRAM Main
+        VAR
+            i: DINT;
+            total: DINT;
+            delta: DINT := 0;
+        END_VAR
+        FOR i := 0 TO 3 BY delta DO
+            total := total + i;
+        END_FOR;
+        END_PROGRAM
+    "#;
+
+    let mut harness = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    let result = harness.cycle();
+    assert!(matches!(
+        result.errors.as_slice(),
+        [RuntimeError::ForStepZero]
+    ));
+}
+
+#[test]
+fn breakpoint_pauses_bytecode_execution() {
+    let source = r#"PROGRAM Main
+VAR
+    count : DINT := 0;
+END_VAR
+    count := count + 1;
+    count := count + 10;
+END_PROGRAM
+"#;
+
+    let mut runtime = TestHarness::from_source_with_bytecode_vm(source)
+        .unwrap()
+        .into_runtime();
+    let line = source
+        .lines()
+        .position(|line| line.contains("count + 10"))
+        .unwrap() as u32;
+    let location = runtime
+        .resolve_breakpoint_location(source, 0, line, 0)
+        .expect("breakpoint location");
+
+    let control = runtime.enable_debug();
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+    control.set_breakpoints_for_file(0, vec![DebugBreakpoint::new(location)]);
+
+    let runtime = Arc::new(Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        runtime.execute_cycle().unwrap();
+    });
+
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500));
+    control.continue_run();
+    handle.join().unwrap();
+
+    let stop = stop.expect("breakpoint stop");
+    assert_eq!(stop.reason, DebugStopReason::Breakpoint);
+    let stop_location = stop.location.expect("stop location");
+    assert_eq!(stop_location.start, location.start);
+    let runtime = runtime.lock().unwrap();
+    assert_eq!(
+        runtime.storage().get_instance_var_recursive(
+            match runtime.storage().get_global("Main") {
+                Some(Value::Instance(id)) => *id,
+                _ => panic!("missing program instance"),
+            },
+            "count"
+        ),
+        Some(&Value::DInt(11))
+    );
+}
+
+#[test]
+fn bundle_bytecode_drives_program_bodies() {
+    let source = r#"
+        PROGRAM Demo
+        VAR
+            count: DINT := 0;
+        END_VAR
+        count := count + 1;
+        END_PROGRAM
+    "#;
+    let bytes = trust_runtime::harness::bytecode_bytes_from_source(source).unwrap();
+    let module = trust_runtime::bytecode::BytecodeModule::decode(&bytes).unwrap();
+
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness
+        .runtime_mut()
+        .load_bytecode_program(&module, &[source], &[])
+        .unwrap();
+    assert!(harness
+        .runtime()
+        .bytecode_vm()
+        .is_some_and(|vm| vm.has_program_body("Demo")));
+    harness.cycle();
+    harness.cycle();
+    harness.assert_eq("count", 2i32);
+}
+
+#[test]
+fn stale_bundle_bytecode_is_rejected() {
+    let built = r#"
+        PROGRAM Demo
+        VAR
+            count: DINT := 0;
+        END_VAR
+        count := count + 1;
+        END_PROGRAM
+    "#;
+    let edited = r#"
+        PROGRAM Demo
+        VAR
+            extra: DINT := 0;
+            count: DINT := 0;
+        END_VAR
+        count := count + 2;
+        END_PROGRAM
+    "#;
+    let bytes = trust_runtime::harness::bytecode_bytes_from_source(built).unwrap();
+    let module = trust_runtime::bytecode::BytecodeModule::decode(&bytes).unwrap();
+
+    let mut harness = TestHarness::from_source(edited).unwrap();
+    let err = harness
+        .runtime_mut()
+        .load_bytecode_program(&module, &[edited], &[])
+        .unwrap_err();
+    assert!(matches!(err, RuntimeError::InvalidBytecode(_)), "{err:?}");
+    assert!(harness.runtime().bytecode_vm().is_none());
+}
+
+#[test]
+fn sfc_program_round_trips_through_bytecode() {
+    let source = r#"
+PROGRAM Main
+VAR
+    start : BOOL;
+    count : DINT;
+    pulses : DINT;
+    lamp : BOOL;
+    done : BOOL;
+END_VAR
+    INITIAL_STEP Idle:
+    END_STEP
+    STEP Fill:
+        Count(N);
+        Pulse(P);
+        lamp(D, T#20ms);
+    END_STEP
+    STEP Drain:
+        done(S);
+    END_STEP
+    TRANSITION FROM Idle TO Fill := start;
+    END_TRANSITION
+    TRANSITION FROM Fill TO Drain := Fill.T >= T#50ms;
+    END_TRANSITION
+    TRANSITION FROM Drain TO Idle := NOT start;
+    END_TRANSITION
+    ACTION Count:
+        count := count + 1;
+    END_ACTION
+    ACTION Pulse:
+        pulses := pulses + 1;
+    END_ACTION
+END_PROGRAM
+"#;
+    let bytes = trust_runtime::harness::bytecode_bytes_from_source(source).unwrap();
+    let module = trust_runtime::bytecode::BytecodeModule::decode(&bytes).unwrap();
+    module.validate().unwrap();
+
+    let mut tree = TestHarness::from_source(source).unwrap();
+    let mut vm = TestHarness::from_source(source).unwrap();
+    vm.runtime_mut()
+        .load_bytecode_program(&module, &[source], &[])
+        .unwrap();
+    assert!(vm
+        .runtime()
+        .bytecode_vm()
+        .is_some_and(|vm| vm.has_program_body("Main")));
+    for harness in [&mut tree, &mut vm] {
+        harness.set_input("start", true);
+    }
+    for cycle in 0..12 {
+        if cycle == 9 {
+            tree.set_input("start", false);
+            vm.set_input("start", false);
+        }
+        for harness in [&mut tree, &mut vm] {
+            harness.advance_time(trust_runtime::value::Duration::from_millis(10));
+            let result = harness.cycle();
+            assert!(result.errors.is_empty(), "{:?}", result.errors);
+        }
+        for name in ["count", "pulses", "lamp", "done", "Idle", "Fill", "Drain"] {
+            assert_eq!(
+                tree.get_output(name),
+                vm.get_output(name),
+                "{name} @ {cycle}"
+            );
+        }
+    }
+    vm.assert_eq("done", true);
+    vm.assert_eq("pulses", 1i32);
+}
+
+#[test]
+fn sfc_function_block_matches_evaluator() {
+    let source = r#"
+FUNCTION_BLOCK Seq
+VAR_INPUT
+    go : BOOL;
+END_VAR
+VAR_OUTPUT
+    a_done : BOOL;
+    b_done : BOOL;
+    joined : BOOL;
+    ticks : DINT;
+END_VAR
+    INITIAL_STEP Start:
+        joined(R);
+    END_STEP
+    STEP BranchA:
+        a_done(S);
+        Tick(N);
+    END_STEP
+    STEP BranchB:
+        b_done(S);
+    END_STEP
+    STEP Join:
+        joined(S);
+    END_STEP
+    TRANSITION FROM Start TO (BranchA, BranchB) := go;
+    END_TRANSITION
+    TRANSITION FROM (BranchA, BranchB) TO Join := a_done AND b_done;
+    END_TRANSITION
+    ACTION Tick:
+        ticks := ticks + 1;
+    END_ACTION
+END_FUNCTION_BLOCK
+
+PROGRAM Main
+VAR
+    seq : Seq;
+    go : BOOL := TRUE;
+    joined : BOOL;
+    ticks : DINT;
+END_VAR
+    seq(go := go);
+    joined := seq.joined;
+    ticks := seq.ticks;
+END_PROGRAM
+"#;
+    assert_same_outputs(source, &["joined", "ticks"], 6);
+    let mut vm = TestHarness::from_source_with_bytecode_vm(source).unwrap();
+    vm.run_cycles(4);
+    vm.assert_eq("joined", true);
+}
+
+#[test]
+fn namespaced_function_return_runs_from_bytecode() {
+    let library = r#"
+        NAMESPACE Utilities
+        FUNCTION Helper : INT
+        VAR_INPUT
+            x: INT;
+        END_VAR
+        Helper := x;
+        END_FUNCTION
+        END_NAMESPACE
+    "#;
+    let program = r#"
+        USING Utilities;
+        PROGRAM Multi
+        VAR
+            count: DINT := 0;
+        END_VAR
+        count := Helper(x := 5);
+        END_PROGRAM
+    "#;
+
+    let mut harness = TestHarness::from_sources_with_bytecode_vm(&[library, program]).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Multi"));
+    let result = harness.cycle();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    harness.assert_eq("count", 5i32);
+}
+
+#[test]
+fn wide_calls_run_from_bytecode() {
+    // More parameters than a single argument mask can mark.
+    let inputs: String = (0..70).map(|idx| format!("a{idx}: DINT;\n")).collect();
+    let source = format!(
+        r#"
+        FUNCTION Wide : DINT
+        VAR_INPUT
+            {inputs}
+        END_VAR
+        VAR_OUTPUT
+            echo: DINT;
+        END_VAR
+        echo := a69;
+        Wide := a0 + a63 + a69;
+        END_FUNCTION
+
+        FUNCTION_BLOCK Caster
+        VAR_OUTPUT
+            hits: DINT;
+            last: DINT;
+        END_VAR
+        hits := hits + Wide(a0 := 1, a63 := 2, a69 := 4, echo => last);
+        END_FUNCTION_BLOCK
+
+        PROGRAM Main
+        VAR
+            caster: Caster;
+            count: DINT;
+            last: DINT;
+        END_VAR
+        caster(hits => count, last => last);
+        END_PROGRAM
+    "#
+    );
+
+    let mut harness = TestHarness::from_source_with_bytecode_vm(&source).unwrap();
+    let vm = harness.runtime().bytecode_vm().expect("bytecode vm");
+    assert!(vm.has_program_body("Main"));
+    harness.run_cycles(2);
+    harness.assert_eq("count", 14i32);
+    harness.assert_eq("last", 4i32);
+    assert_same_outputs(&source, &["count", "last"], 3);
+}
+
+#[test]
+fn runtime_runs_from_bytecode_without_sources() {
+    let source = r#"
+        TYPE
+            Mode : (Idle, Busy);
+            Point : STRUCT
+                x : DINT;
+                y : DINT;
+            END_STRUCT;
+        END_TYPE
+
+        CLASS Counter
+        VAR
+            delta : DINT := 5;
+            total : DINT;
+        END_VAR
+        METHOD PUBLIC Add : DINT
+        total := total + delta;
+        Add := total;
+        END_METHOD
+        END_CLASS
+
+        PROGRAM Main
+        VAR
+            lamp AT %QX0.0 : BOOL;
+            mode : Mode := Mode#Busy;
+            origin : Point;
+            counter : Counter;
+            total : DINT;
+            label : STRING[8] := 'ready';
+        END_VAR
+        lamp := NOT lamp;
+        origin.y := origin.y + 3;
+        total := counter.Add();
+        END_PROGRAM
+    "#;
+    let mut harness = TestHarness::from_source_bytecode_only(source).unwrap();
+    assert!(harness.runtime().programs()["Main"].body.is_empty());
+    harness.assert_eq("label", Value::String("ready".into()));
+    harness.cycle();
+    harness.cycle();
+    harness.assert_eq("total", 10i32);
+    assert_eq!(harness.runtime().io().outputs(), &[0]);
+
+    harness
+        .runtime_mut()
+        .restart(trust_runtime::RestartMode::Cold)
+        .unwrap();
+    harness.cycle();
+    harness.assert_eq("total", 5i32);
+    assert_eq!(harness.runtime().io().outputs(), &[1]);
+    let Some(Value::Struct(origin)) = harness.get_output("origin") else {
+        panic!("expected struct value");
+    };
+    assert_eq!(origin.fields.get("y"), Some(&Value::DInt(3)));
+}
+
+#[test]
+fn bytecode_without_declarations_needs_sources() {
+    let source = r#"
+        PROGRAM Demo
+        VAR
+            count: DINT := 0;
+        END_VAR
+        count := count + 1;
+        END_PROGRAM
+    "#;
+    let bytes = trust_runtime::harness::bytecode_bytes_from_source(source).unwrap();
+    let mut module = trust_runtime::bytecode::BytecodeModule::decode(&bytes).unwrap();
+    module
+        .sections
+        .retain(|section| section.id != trust_runtime::bytecode::SectionId::PouDecls.as_raw());
+
+    let err = trust_runtime::Runtime::from_bytecode_module(&module, None).unwrap_err();
+    assert!(matches!(err, RuntimeError::InvalidBytecode(_)), "{err:?}");
+    assert!(err.to_string().contains("POU_DECLS"), "{err}");
+}
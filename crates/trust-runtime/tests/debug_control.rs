@@ -6,15 +6,19 @@ use std::time::Duration;
 
 use trust_hir::types::TypeRegistry;
 use trust_runtime::debug::{
-    offset_to_line_col, resolve_breakpoint_location, DebugBreakpoint, DebugControl, DebugHook,
-    DebugStopReason, HitCondition, LogFragment, SourceLocation,
+    offset_to_line_col, resolve_breakpoint_location, DataAccess, DebugBreakpoint, DebugControl,
+    DebugDataBreakpoint, DebugFunctionBreakpoint, DebugHook, DebugStopReason, FaultCategory,
+    HitCondition, LogFragment, SourceLocation,
 };
+use trust_runtime::error::RuntimeError;
 use trust_runtime::eval::expr::Expr;
 use trust_runtime::eval::stmt::{exec_stmt, Stmt};
 use trust_runtime::harness::parse_debug_expression;
 use trust_runtime::harness::TestHarness;
 use trust_runtime::memory::VariableStorage;
+use trust_runtime::scheduler::{ResourceCommand, ResourceControl, ResourceRunner, StdClock};
 use trust_runtime::value::{DateTimeProfile, Value};
+use trust_runtime::watchdog::{WatchdogAction, WatchdogPolicy};
 use trust_runtime::Runtime;
 
 #[test]
@@ -574,3 +578,761 @@ fn logpoint_emits_output_without_pausing() {
     assert_eq!(logs.len(), 1);
     assert!(logs[0].message.contains("x=DInt(41)"));
 }
+
+const DATA_SOURCE: &str = r#"
+CONFIGURATION Conf
+VAR_GLOBAL
+    total : DINT := 0;
+    out AT %QD0 : DINT;
+END_VAR
+PROGRAM P1 : Prog;
+END_CONFIGURATION
+
+FUNCTION_BLOCK Counter
+VAR_INPUT
+    delta : DINT;
+END_VAR
+VAR
+    count : DINT;
+END_VAR
+count := count + delta;
+END_FUNCTION_BLOCK
+
+PROGRAM Prog
+VAR
+    idle : DINT;
+    c : Counter;
+END_VAR
+idle := 1;
+total := total + 1;
+c(delta := 2);
+out := total;
+idle := 2;
+END_PROGRAM
+"#;
+
+fn data_breakpoint_runtime() -> (Runtime, DebugControl) {
+    let mut harness = TestHarness::from_source(DATA_SOURCE).unwrap();
+    let control = harness.runtime_mut().enable_debug();
+    (harness.into_runtime(), control)
+}
+
+fn statement_start(text: &str) -> u32 {
+    DATA_SOURCE.find(text).unwrap() as u32
+}
+
+#[test]
+fn data_breakpoint_stops_at_writing_statement() {
+    let (runtime, control) = data_breakpoint_runtime();
+    let target = runtime.storage().ref_for_global("total").unwrap();
+    control.set_data_breakpoints(vec![DebugDataBreakpoint::new(target, DataAccess::Write)]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runtime = std::sync::Arc::new(std::sync::Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        runtime.execute_cycle().unwrap();
+    });
+
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::DataBreakpoint);
+    assert_eq!(
+        stop.location.map(|location| location.start),
+        Some(statement_start("total := total + 1;"))
+    );
+    let snapshot = control.snapshot().unwrap();
+    assert_eq!(snapshot.storage.get_global("total"), Some(&Value::DInt(1)));
+
+    control.continue_run();
+    handle.join().unwrap();
+    assert!(stop_rx.try_recv().is_err());
+}
+
+#[test]
+fn data_breakpoint_write_fires_on_same_value_writes() {
+    let (mut runtime, control) = data_breakpoint_runtime();
+    runtime.execute_cycle().unwrap();
+    let Some(Value::Instance(program_id)) = runtime.storage().get_global("P1").cloned() else {
+        panic!("program instance");
+    };
+    let Some(Value::Instance(counter_id)) =
+        runtime.storage().get_instance_var(program_id, "c").cloned()
+    else {
+        panic!("counter instance");
+    };
+    // `c(delta := 2)` writes the value the input already holds.
+    let target = runtime
+        .storage()
+        .ref_for_instance(counter_id, "delta")
+        .unwrap();
+    control.set_data_breakpoints(vec![DebugDataBreakpoint::new(target, DataAccess::Write)]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runtime = std::sync::Arc::new(std::sync::Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        for _ in 0..2 {
+            runtime.execute_cycle().unwrap();
+        }
+    });
+
+    for _ in 0..2 {
+        let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+        assert_eq!(stop.reason, DebugStopReason::DataBreakpoint);
+        control.continue_run();
+    }
+    handle.join().unwrap();
+    assert!(stop_rx.try_recv().is_err());
+    assert_eq!(control.data_breakpoints()[0].hits, 2);
+}
+
+#[test]
+fn data_breakpoint_condition_on_instance_member() {
+    let (runtime, control) = data_breakpoint_runtime();
+    let Some(Value::Instance(program_id)) = runtime.storage().get_global("P1").cloned() else {
+        panic!("program instance");
+    };
+    let Some(Value::Instance(counter_id)) =
+        runtime.storage().get_instance_var(program_id, "c").cloned()
+    else {
+        panic!("counter instance");
+    };
+    let target = runtime
+        .storage()
+        .ref_for_instance(counter_id, "count")
+        .unwrap();
+    let mut registry = TypeRegistry::new();
+    let condition =
+        parse_debug_expression("count = 4", &mut registry, DateTimeProfile::default(), &[])
+            .unwrap();
+    let mut breakpoint = DebugDataBreakpoint::new(target, DataAccess::Write);
+    breakpoint.condition = Some(condition);
+    control.set_data_breakpoints(vec![breakpoint]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runtime = std::sync::Arc::new(std::sync::Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        for _ in 0..3 {
+            runtime.execute_cycle().unwrap();
+        }
+    });
+
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::DataBreakpoint);
+    assert_eq!(
+        stop.location.map(|location| location.start),
+        Some(statement_start("count := count + delta;"))
+    );
+    let snapshot = control.snapshot().unwrap();
+    assert_eq!(snapshot.storage.get_global("total"), Some(&Value::DInt(2)));
+
+    control.continue_run();
+    handle.join().unwrap();
+    assert!(stop_rx.try_recv().is_err());
+}
+
+#[test]
+fn data_breakpoint_ignores_output_latching() {
+    let (runtime, control) = data_breakpoint_runtime();
+    let target = runtime.storage().ref_for_global("out").unwrap();
+    control.set_data_breakpoints(vec![DebugDataBreakpoint::new(target, DataAccess::Read)]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let mut runtime = runtime;
+    runtime.execute_cycle().unwrap();
+    runtime.execute_cycle().unwrap();
+    assert!(stop_rx.try_recv().is_err());
+    assert_eq!(runtime.storage().get_global("out"), Some(&Value::DInt(2)));
+}
+
+const FUNCTION_SOURCE: &str = r#"
+CONFIGURATION Conf
+PROGRAM P1 : Prog;
+END_CONFIGURATION
+
+FUNCTION_BLOCK Motor
+VAR_INPUT
+    enable : BOOL;
+END_VAR
+VAR
+    speed : DINT;
+END_VAR
+speed := speed + 1;
+METHOD PUBLIC Start : BOOL
+VAR_INPUT
+    target : DINT;
+END_VAR
+speed := target;
+Start := TRUE;
+END_METHOD
+END_FUNCTION_BLOCK
+
+PROGRAM Prog
+VAR
+    m1 : Motor;
+    m2 : Motor;
+END_VAR
+m1(enable := TRUE);
+m2(enable := TRUE);
+m1.Start(target := 10);
+m2.Start(target := 20);
+END_PROGRAM
+"#;
+
+fn function_start(text: &str) -> u32 {
+    FUNCTION_SOURCE.find(text).unwrap() as u32
+}
+
+fn program_instance_member(runtime: &Runtime, name: &str) -> trust_runtime::memory::InstanceId {
+    let Some(Value::Instance(program_id)) = runtime.storage().get_global("P1").cloned() else {
+        panic!("program instance");
+    };
+    let Some(Value::Instance(id)) = runtime
+        .storage()
+        .get_instance_var(program_id, name)
+        .cloned()
+    else {
+        panic!("member instance");
+    };
+    id
+}
+
+#[test]
+fn function_breakpoint_resolves_pous_methods_and_instances() {
+    let runtime = TestHarness::from_source(FUNCTION_SOURCE)
+        .unwrap()
+        .into_runtime();
+    let metadata = runtime.metadata_snapshot();
+    let resolve = |name: &str| metadata.resolve_function_breakpoint(runtime.storage(), name);
+
+    let program = resolve("p1").unwrap();
+    assert_eq!(
+        program.location.start,
+        function_start("m1(enable := TRUE);")
+    );
+    assert_eq!(program.instance, None);
+    let body = resolve("Motor").unwrap();
+    assert_eq!(body.location.start, function_start("speed := speed + 1;"));
+    let method = resolve("Motor.Start").unwrap();
+    assert_eq!(method.location.start, function_start("speed := target;"));
+    assert_eq!(method.instance, None);
+    assert_eq!(resolve("Start"), Some(method.clone()));
+
+    let m2 = program_instance_member(&runtime, "m2");
+    let instance_method = resolve("P1.m2.Start").unwrap();
+    assert_eq!(instance_method.location, method.location);
+    assert_eq!(instance_method.instance, Some(m2));
+    let instance_body = resolve("P1.m2").unwrap();
+    assert_eq!(instance_body.location, body.location);
+    assert_eq!(instance_body.instance, Some(m2));
+
+    assert_eq!(resolve("Motor.Stop"), None);
+    assert_eq!(resolve("P1.m3.Start"), None);
+}
+
+#[test]
+fn function_breakpoint_on_instance_path_stops_for_that_instance_only() {
+    let mut harness = TestHarness::from_source(FUNCTION_SOURCE).unwrap();
+    let control = harness.runtime_mut().enable_debug();
+    let runtime = harness.into_runtime();
+    let target = runtime
+        .metadata_snapshot()
+        .resolve_function_breakpoint(runtime.storage(), "P1.m2.Start")
+        .unwrap();
+    let m1 = program_instance_member(&runtime, "m1");
+    let mut breakpoint = DebugBreakpoint::new(target.location);
+    breakpoint.hit_condition = Some(HitCondition::Equal(2));
+    control.set_function_breakpoints(vec![DebugFunctionBreakpoint {
+        name: "P1.m2.Start".into(),
+        breakpoint,
+        instance: target.instance,
+    }]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runtime = std::sync::Arc::new(std::sync::Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        runtime.execute_cycle().unwrap();
+        runtime.execute_cycle().unwrap();
+    });
+
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::FunctionBreakpoint);
+    assert_eq!(
+        stop.location.map(|location| location.start),
+        Some(function_start("speed := target;"))
+    );
+    let snapshot = control.snapshot().unwrap();
+    // Second cycle: m1 has already run its method, m2 has only run its body.
+    assert_eq!(
+        snapshot.storage.get_instance_var(m1, "speed"),
+        Some(&Value::DInt(10))
+    );
+
+    control.continue_run();
+    handle.join().unwrap();
+    assert!(stop_rx.try_recv().is_err());
+}
+
+#[test]
+fn cycle_history_keeps_last_cycles() {
+    let (mut runtime, control) = data_breakpoint_runtime();
+    control.enable_history(3);
+    for _ in 0..5 {
+        runtime.execute_cycle().unwrap();
+    }
+    let Some(Value::Instance(program_id)) = runtime.storage().get_global("P1").cloned() else {
+        panic!("program instance");
+    };
+    let total = runtime.storage().ref_for_global("total").unwrap();
+    let idle = runtime
+        .storage()
+        .ref_for_instance(program_id, "idle")
+        .unwrap();
+
+    control
+        .with_history(|history| {
+            assert_eq!(history.oldest_cycle(), Some(2));
+            assert_eq!(history.newest_cycle(), Some(4));
+            assert!(history.record(1).is_none());
+            assert!(history.record(3).unwrap().touches(&total));
+            assert!(!history.record(3).unwrap().touches(&idle));
+            let at = |cycle| history.storage_at(cycle).unwrap();
+            assert_eq!(at(2).get_global("total"), Some(&Value::DInt(3)));
+            assert_eq!(at(4).get_global("total"), Some(&Value::DInt(5)));
+            assert_eq!(at(4).get_global("out"), Some(&Value::DInt(5)));
+        })
+        .expect("history enabled");
+}
+
+#[test]
+fn cycle_history_skips_detached_task_cycles() {
+    let (mut runtime, control) = data_breakpoint_runtime();
+    control.enable_history(3);
+    runtime.set_tasks_detached(true);
+    runtime.execute_cycle().unwrap();
+    assert_eq!(
+        control.with_history(|history| history.newest_cycle()),
+        Some(None)
+    );
+}
+
+#[test]
+fn step_back_replays_recorded_cycles_while_paused() {
+    let (mut runtime, control) = data_breakpoint_runtime();
+    control.enable_history(10);
+    for _ in 0..3 {
+        runtime.execute_cycle().unwrap();
+    }
+    let Some(Value::Instance(program_id)) = runtime.storage().get_global("P1").cloned() else {
+        panic!("program instance");
+    };
+    let idle = runtime
+        .storage()
+        .ref_for_instance(program_id, "idle")
+        .unwrap();
+    control.set_data_breakpoints(vec![DebugDataBreakpoint::new(idle, DataAccess::Write)]);
+    assert_eq!(control.step_back(), None);
+
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+    control.pause();
+    let runtime = std::sync::Arc::new(std::sync::Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        runtime.execute_cycle().unwrap();
+    });
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::Pause);
+
+    let total = |control: &DebugControl| {
+        control
+            .snapshot()
+            .and_then(|snapshot| snapshot.storage.get_global("total").cloned())
+    };
+    assert_eq!(control.step_back(), Some(2));
+    assert_eq!(total(&control), Some(Value::DInt(3)));
+    assert_eq!(control.step_back(), Some(1));
+    assert_eq!(total(&control), Some(Value::DInt(2)));
+    assert_eq!(control.reverse_continue(), Some(0));
+    assert_eq!(total(&control), Some(Value::DInt(1)));
+    assert_eq!(control.step_back(), None);
+    assert_eq!(control.history_cursor(), Some(0));
+
+    assert_eq!(control.step_forward_history(), Some(1));
+    assert_eq!(control.step_forward_history(), Some(2));
+    assert_eq!(control.step_forward_history(), None);
+    assert_eq!(control.history_cursor(), None);
+    assert_eq!(total(&control), Some(Value::DInt(3)));
+
+    control.set_data_breakpoints(Vec::new());
+    control.continue_run();
+    handle.join().unwrap();
+    assert!(stop_rx.try_recv().is_err());
+}
+
+const FAULT_SOURCE: &str = r#"
+CONFIGURATION Conf
+PROGRAM P1 : Prog;
+END_CONFIGURATION
+
+FUNCTION Ratio : DINT
+VAR_INPUT
+    num : DINT;
+    den : DINT;
+END_VAR
+Ratio := num / den;
+END_FUNCTION
+
+PROGRAM Prog
+VAR
+    divisor : DINT := 0;
+    result : DINT;
+END_VAR
+result := Ratio(num := 10, den := divisor);
+END_PROGRAM
+"#;
+
+fn fault_runtime(filters: Vec<FaultCategory>) -> (Runtime, DebugControl) {
+    let mut harness = TestHarness::from_source(FAULT_SOURCE).unwrap();
+    let control = harness.runtime_mut().enable_debug();
+    control.set_exception_filters(filters);
+    (harness.into_runtime(), control)
+}
+
+#[test]
+fn exception_filter_pauses_at_faulting_statement_before_fault_policy() {
+    let (runtime, control) = fault_runtime(vec![FaultCategory::Arithmetic]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runtime = std::sync::Arc::new(std::sync::Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        runtime.execute_cycle()
+    });
+
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::Exception);
+    assert_eq!(
+        stop.location.map(|location| location.start),
+        FAULT_SOURCE
+            .find("Ratio := num / den;")
+            .map(|offset| offset as u32)
+    );
+    let fault = control.last_fault().unwrap();
+    assert_eq!(fault.category, FaultCategory::Arithmetic);
+    assert_eq!(fault.message, "division by zero");
+    let snapshot = control.snapshot().unwrap();
+    let ratio = snapshot
+        .storage
+        .frames()
+        .iter()
+        .find(|frame| frame.owner.eq_ignore_ascii_case("Ratio"))
+        .expect("callee frame kept while paused");
+    assert_eq!(ratio.variables.get("den"), Some(&Value::DInt(0)));
+
+    control.continue_run();
+    let result = handle.join().unwrap();
+    assert!(matches!(result, Err(RuntimeError::DivisionByZero)));
+    assert!(stop_rx.try_recv().is_err());
+    assert!(runtime.lock().unwrap().faulted());
+}
+
+#[test]
+fn exception_filter_ignores_other_categories() {
+    let (mut runtime, control) = fault_runtime(vec![FaultCategory::Bounds]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let result = runtime.execute_cycle();
+    assert!(matches!(result, Err(RuntimeError::DivisionByZero)));
+    assert!(stop_rx.try_recv().is_err());
+    assert!(control.last_fault().is_none());
+}
+
+#[test]
+fn watchdog_filter_pauses_when_cycle_overruns() {
+    let (mut runtime, control) = data_breakpoint_runtime();
+    runtime.set_watchdog_policy(WatchdogPolicy {
+        enabled: true,
+        timeout: trust_runtime::value::Duration::from_millis(0),
+        action: WatchdogAction::SafeHalt,
+    });
+    control.set_exception_filters(vec![FaultCategory::Watchdog]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runtime = std::sync::Arc::new(std::sync::Mutex::new(runtime));
+    let runtime_thread = runtime.clone();
+    let handle = thread::spawn(move || {
+        let mut runtime = runtime_thread.lock().expect("runtime lock poisoned");
+        runtime.execute_cycle().unwrap();
+    });
+
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::Exception);
+    assert_eq!(
+        control.last_fault().map(|fault| fault.category),
+        Some(FaultCategory::Watchdog)
+    );
+    control.continue_run();
+    handle.join().unwrap();
+    assert!(stop_rx.try_recv().is_err());
+    // The stop does not hide the overrun from the runtime's own watchdog.
+    assert!(runtime.lock().unwrap().watchdog_expired());
+}
+
+const OVERRUN_SOURCE: &str = r#"
+CONFIGURATION Conf
+VAR_GLOBAL
+    spins : DINT := 0;
+END_VAR
+TASK Busy (INTERVAL := T#10ms, PRIORITY := 1);
+PROGRAM P1 WITH Busy : BusyProg;
+END_CONFIGURATION
+
+PROGRAM BusyProg
+VAR
+    i : DINT;
+END_VAR
+FOR i := 1 TO 100000 DO
+    spins := spins + 1;
+END_FOR;
+END_PROGRAM
+"#;
+
+#[test]
+fn watchdog_filter_stops_task_thread_before_the_runtime_watchdog_faults() {
+    let mut harness = TestHarness::from_source(OVERRUN_SOURCE).unwrap();
+    let control = harness.runtime_mut().enable_debug();
+    control.set_exception_filters(vec![FaultCategory::Watchdog]);
+    let mut runtime = harness.into_runtime();
+    runtime.set_watchdog_policy(WatchdogPolicy {
+        enabled: true,
+        timeout: trust_runtime::value::Duration::from_millis(1),
+        action: WatchdogAction::Halt,
+    });
+    let busy_id = runtime
+        .metadata_snapshot()
+        .task_thread_id(&"Busy".into())
+        .unwrap();
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runner = ResourceRunner::new(
+        runtime,
+        StdClock::new(),
+        trust_runtime::value::Duration::from_millis(1),
+    )
+    .with_task_threads(true);
+    let mut handle = runner.spawn("res-watchdog").unwrap();
+
+    let stop = stop_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::Exception);
+    assert_eq!(stop.thread_id, Some(busy_id));
+    assert_eq!(
+        control.last_fault().map(|fault| fault.category),
+        Some(FaultCategory::Watchdog)
+    );
+    assert!(handle.last_error().is_none());
+
+    control.continue_run();
+    let start = std::time::Instant::now();
+    while handle.last_error().is_none() {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "runtime watchdog did not fault the resource"
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(matches!(
+        handle.last_error(),
+        Some(RuntimeError::WatchdogTimeout)
+    ));
+    handle.stop();
+    handle.join().unwrap();
+}
+
+#[test]
+fn non_stop_breakpoint_stops_only_its_task() {
+    let control = DebugControl::new();
+    control.set_non_stop(true);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+    let breakpoint = SourceLocation::new(0, 0, 10);
+    control.set_breakpoints_for_file(0, vec![DebugBreakpoint::new(breakpoint)]);
+
+    let mut slow = control.clone();
+    let slow_handle = thread::spawn(move || {
+        slow.set_current_task(Some(2), "Slow");
+        slow.on_statement(Some(&breakpoint), 0);
+    });
+    let stop = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::Breakpoint);
+    assert_eq!(stop.thread_id, Some(2));
+    assert_eq!(control.thread_name(2).as_deref(), Some("Slow"));
+    assert!(control.thread_stopped(2));
+
+    let (tx, rx) = channel();
+    let mut fast = control.clone();
+    let fast_handle = thread::spawn(move || {
+        fast.set_current_task(Some(1), "Fast");
+        for offset in 0..5 {
+            fast.on_statement(Some(&SourceLocation::new(0, 20 + offset, 21 + offset)), 0);
+            tx.send(offset).unwrap();
+        }
+    });
+    for offset in 0..5 {
+        assert_eq!(rx.recv_timeout(Duration::from_millis(250)).unwrap(), offset);
+    }
+    fast_handle.join().unwrap();
+    // The running task neither steals the stop nor moves its location.
+    assert_eq!(control.stopped_thread(), Some(2));
+    assert_eq!(control.last_location(), Some(breakpoint));
+
+    control.continue_run();
+    slow_handle.join().unwrap();
+    assert_eq!(control.stopped_thread(), None);
+}
+
+#[test]
+fn non_stop_keeps_a_stop_per_task() {
+    let control = DebugControl::new();
+    control.set_non_stop(true);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+    let breakpoint = SourceLocation::new(0, 0, 10);
+    control.set_breakpoints_for_file(0, vec![DebugBreakpoint::new(breakpoint)]);
+
+    let spawn_task = |thread_id: u32, name: &'static str| {
+        let mut hook = control.clone();
+        thread::spawn(move || {
+            hook.set_current_task(Some(thread_id), name);
+            hook.on_statement(Some(&breakpoint), 0);
+        })
+    };
+    let slow_handle = spawn_task(2, "Slow");
+    let first = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(first.thread_id, Some(2));
+
+    // A second task stops on its own while the first stays stopped.
+    let fast_handle = spawn_task(1, "Fast");
+    let second = stop_rx.recv_timeout(Duration::from_millis(500)).unwrap();
+    assert_eq!(second.reason, DebugStopReason::Breakpoint);
+    assert_eq!(second.thread_id, Some(1));
+    assert!(control.thread_stopped(1));
+    assert!(control.thread_stopped(2));
+    assert_eq!(control.stopped_thread(), Some(1));
+    assert!(control.focus_thread(2));
+    assert_eq!(control.stopped_thread(), Some(2));
+
+    control.continue_thread(2);
+    slow_handle.join().unwrap();
+    assert!(!control.thread_stopped(2));
+    assert!(!control.focus_thread(2));
+    assert_eq!(control.stopped_thread(), Some(1));
+    assert!(control.is_paused());
+
+    control.continue_thread(1);
+    fast_handle.join().unwrap();
+    assert_eq!(control.stopped_thread(), None);
+    assert!(!control.is_paused());
+}
+
+const TASK_SOURCE: &str = r#"
+CONFIGURATION Conf
+VAR_GLOBAL
+    fast_count : DINT := 0;
+    slow_count : DINT := 0;
+END_VAR
+TASK Fast (INTERVAL := T#2ms, PRIORITY := 0);
+TASK Slow (INTERVAL := T#10ms, PRIORITY := 5);
+PROGRAM P1 WITH Fast : FastProg;
+PROGRAM P2 WITH Slow : SlowProg;
+END_CONFIGURATION
+
+PROGRAM FastProg
+fast_count := fast_count + 1;
+END_PROGRAM
+
+PROGRAM SlowProg
+slow_count := slow_count + 1;
+END_PROGRAM
+"#;
+
+fn resource_dint(control: &ResourceControl<StdClock>, name: &str) -> i32 {
+    let (tx, rx) = channel();
+    control
+        .send_command(ResourceCommand::Snapshot { respond_to: tx })
+        .unwrap();
+    let snapshot = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+    match snapshot.storage.get_global(name) {
+        Some(Value::DInt(value)) => *value,
+        other => panic!("unexpected value for {name}: {other:?}"),
+    }
+}
+
+#[test]
+fn non_stop_keeps_other_task_threads_running() {
+    let mut harness = TestHarness::from_source(TASK_SOURCE).unwrap();
+    let control = harness.runtime_mut().enable_debug();
+    control.set_non_stop(true);
+    let runtime = harness.into_runtime();
+    let metadata = runtime.metadata_snapshot();
+    let slow_id = metadata.task_thread_id(&"Slow".into()).unwrap();
+    let target = metadata
+        .resolve_function_breakpoint(runtime.storage(), "P2")
+        .unwrap();
+    control.set_function_breakpoints(vec![DebugFunctionBreakpoint {
+        name: "P2".into(),
+        breakpoint: DebugBreakpoint::new(target.location),
+        instance: target.instance,
+    }]);
+    let (stop_tx, stop_rx) = channel();
+    control.set_stop_sender(stop_tx);
+
+    let runner = ResourceRunner::new(
+        runtime,
+        StdClock::new(),
+        trust_runtime::value::Duration::from_millis(1),
+    )
+    .with_task_threads(true);
+    let mut handle = runner.spawn("res-non-stop").unwrap();
+    let resource = handle.control();
+
+    let stop = stop_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(stop.reason, DebugStopReason::FunctionBreakpoint);
+    assert_eq!(stop.thread_id, Some(slow_id));
+    assert_eq!(control.thread_name(slow_id).as_deref(), Some("Slow"));
+
+    let fast_before = resource_dint(&resource, "fast_count");
+    let start = std::time::Instant::now();
+    while resource_dint(&resource, "fast_count") < fast_before + 5 {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "fast task stalled while the slow task was stopped"
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(resource_dint(&resource, "slow_count"), 0);
+    assert_eq!(control.stopped_thread(), Some(slow_id));
+
+    control.set_function_breakpoints(Vec::new());
+    control.continue_run();
+    handle.stop();
+    handle.join().unwrap();
+    assert!(handle.last_error().is_none());
+}
@@ -0,0 +1,116 @@
+use std::process::Command;
+
+use trust_runtime::web::deploy::{apply_deploy, sign_deploy_request, DeployRequest, DeploySource};
+
+const RUNTIME_TOML: &str = r#"
+[bundle]
+version = 1
+
+[resource]
+name = "main"
+cycle_interval_ms = 100
+
+[runtime.control]
+endpoint = "unix:///tmp/trust-runtime.sock"
+mode = "production"
+debug_enabled = false
+
+[runtime.deploy]
+require_signed = true
+keyring_path = "security/deploy-keys.toml"
+
+[runtime.log]
+level = "info"
+
+[runtime.retain]
+mode = "none"
+save_interval_ms = 1000
+
+[runtime.watchdog]
+enabled = false
+timeout_ms = 5000
+action = "halt"
+
+[runtime.fault]
+policy = "halt"
+"#;
+
+#[test]
+fn deploy_keygen_key_signs_payload_accepted_by_public_keyring() {
+    let root = std::env::temp_dir().join(format!("trust-deploy-keygen-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("security")).expect("create root");
+    std::fs::write(root.join("runtime.toml"), RUNTIME_TOML).expect("install runtime.toml");
+    let key_path = root.join("keys/ci.pem");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_trust-runtime"))
+        .args(["deploy-keygen", "--id", "ci", "--out"])
+        .arg(&key_path)
+        .output()
+        .expect("run deploy-keygen");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&key_path)
+            .expect("key metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600, "private key must be owner-only");
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let entry = &stdout[stdout.find("[[keys]]").expect("keyring entry")..];
+    assert!(entry.contains("public_key = "), "{entry}");
+    std::fs::write(root.join("security/deploy-keys.toml"), entry).expect("write keyring");
+
+    let mut request = DeployRequest {
+        runtime_toml: Some(RUNTIME_TOML.to_string()),
+        io_toml: None,
+        program_stbc_b64: None,
+        sources: Some(vec![DeploySource {
+            path: "main.st".to_string(),
+            content: "PROGRAM Main\nEND_PROGRAM\n".to_string(),
+        }]),
+        signature: None,
+        signatures: None,
+        restart: None,
+    };
+    let pem = std::fs::read_to_string(&key_path).expect("read private key");
+    request.signature = Some(sign_deploy_request(&request, "ci", &pem).expect("sign"));
+    let result = apply_deploy(&root, request).expect("signed deploy");
+    assert!(result.written.contains(&"sources/main.st".to_string()));
+
+    let again = Command::new(env!("CARGO_BIN_EXE_trust-runtime"))
+        .args(["deploy-keygen", "--id", "ci", "--out"])
+        .arg(&key_path)
+        .output()
+        .expect("rerun deploy-keygen");
+    assert!(
+        !again.status.success(),
+        "existing key must not be overwritten"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&key_path).expect("read private key"),
+        pem
+    );
+
+    let forced = Command::new(env!("CARGO_BIN_EXE_trust-runtime"))
+        .args(["deploy-keygen", "--id", "ci", "--force", "--out"])
+        .arg(&key_path)
+        .output()
+        .expect("rerun deploy-keygen with --force");
+    assert!(
+        forced.status.success(),
+        "{}",
+        String::from_utf8_lossy(&forced.stderr)
+    );
+    assert_ne!(
+        std::fs::read_to_string(&key_path).expect("read replaced key"),
+        pem
+    );
+    let _ = std::fs::remove_dir_all(root);
+}
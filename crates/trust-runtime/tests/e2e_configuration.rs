@@ -20,11 +20,17 @@ count := count + INT#1;
 END_PROGRAM
 "#;
 
-    let mut harness = TestHarness::from_source(source).unwrap();
-    harness.cycle();
-    assert_eq!(harness.get_output("count"), Some(Value::Int(0)));
+    for build in [
+        TestHarness::from_source,
+        TestHarness::from_source_with_bytecode_vm,
+        TestHarness::from_source_bytecode_only,
+    ] {
+        let mut harness = build(source).unwrap();
+        harness.cycle();
+        assert_eq!(harness.get_output("count"), Some(Value::Int(0)));
 
-    harness.advance_time(Duration::from_millis(10));
-    harness.cycle();
-    assert_eq!(harness.get_output("count"), Some(Value::Int(1)));
+        harness.advance_time(Duration::from_millis(10));
+        harness.cycle();
+        assert_eq!(harness.get_output("count"), Some(Value::Int(1)));
+    }
 }
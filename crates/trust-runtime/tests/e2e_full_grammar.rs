@@ -51,16 +51,22 @@ fn var_and_stmt_coverage() {
         END_PROGRAM
     "#;
 
-    let mut harness = TestHarness::from_source(source).unwrap();
-    harness.cycle();
-    harness.assert_eq("count", 7i32);
+    for build in [
+        TestHarness::from_source,
+        TestHarness::from_source_with_bytecode_vm,
+        TestHarness::from_source_bytecode_only,
+    ] {
+        let mut harness = build(source).unwrap();
+        harness.cycle();
+        harness.assert_eq("count", 7i32);
 
-    let arr = harness.get_output("arr").expect("missing arr");
-    let Value::Array(array) = arr else {
-        panic!("expected array value");
-    };
-    assert_eq!(array.dimensions, vec![(0, 1), (0, 2)]);
-    assert_eq!(array.elements[1], Value::DInt(4));
-    assert_eq!(array.elements[3], Value::DInt(4));
-    assert_eq!(array.elements[4], Value::DInt(5));
+        let arr = harness.get_output("arr").expect("missing arr");
+        let Value::Array(array) = arr else {
+            panic!("expected array value");
+        };
+        assert_eq!(array.dimensions, vec![(0, 1), (0, 2)]);
+        assert_eq!(array.elements[1], Value::DInt(4));
+        assert_eq!(array.elements[3], Value::DInt(4));
+        assert_eq!(array.elements[4], Value::DInt(5));
+    }
 }
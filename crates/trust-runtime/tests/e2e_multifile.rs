@@ -23,9 +23,15 @@ fn namespace_resolution() {
         END_PROGRAM
     "#;
 
-    let mut harness = TestHarness::from_sources(&[library, program]).unwrap();
-    harness.cycle();
-    harness.assert_eq("count", 1i32);
+    for build in [
+        TestHarness::from_sources,
+        TestHarness::from_sources_with_bytecode_vm,
+        TestHarness::from_sources_bytecode_only,
+    ] {
+        let mut harness = build(&[library, program]).unwrap();
+        harness.cycle();
+        harness.assert_eq("count", 1i32);
+    }
 }
 
 #[test]
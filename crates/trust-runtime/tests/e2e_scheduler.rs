@@ -1,6 +1,6 @@
 use trust_runtime::debug::RuntimeEvent;
 use trust_runtime::error::RuntimeError;
-use trust_runtime::harness::TestHarness;
+use trust_runtime::harness::{CompileError, TestHarness};
 use trust_runtime::scheduler::{ManualClock, ResourceRunner};
 use trust_runtime::value::{Duration, Value};
 
@@ -41,78 +41,84 @@ count := count + 1;
 END_PROGRAM
 "#;
 
-    let runtime = TestHarness::from_source(source).unwrap().into_runtime();
-    let clock = ManualClock::new();
-    let mut runner = ResourceRunner::new(runtime, clock.clone(), Duration::from_millis(1));
-
-    let periodic_id = match runner.runtime().storage().get_global("P1") {
-        Some(Value::Instance(id)) => *id,
-        other => panic!("expected P1 instance, got {other:?}"),
-    };
-    let event_id = match runner.runtime().storage().get_global("P2") {
-        Some(Value::Instance(id)) => *id,
-        other => panic!("expected P2 instance, got {other:?}"),
-    };
-
-    runner.tick().unwrap();
-    assert_counter(
-        runner
-            .runtime()
-            .storage()
-            .get_instance_var(periodic_id, "count"),
-        0,
-    );
-    assert_counter(
-        runner
-            .runtime()
-            .storage()
-            .get_instance_var(event_id, "count"),
-        0,
-    );
-
-    runner
-        .runtime_mut()
-        .storage_mut()
-        .set_global("trigger", Value::Bool(true));
-    clock.advance(Duration::from_millis(10));
-    runner.tick().unwrap();
-
-    assert_counter(
-        runner
-            .runtime()
-            .storage()
-            .get_instance_var(periodic_id, "count"),
-        1,
-    );
-    assert_counter(
-        runner
-            .runtime()
-            .storage()
-            .get_instance_var(event_id, "count"),
-        1,
-    );
-
-    runner
-        .runtime_mut()
-        .storage_mut()
-        .set_global("trigger", Value::Bool(false));
-    clock.advance(Duration::from_millis(10));
-    runner.tick().unwrap();
-
-    assert_counter(
+    for build in [
+        TestHarness::from_source,
+        TestHarness::from_source_with_bytecode_vm,
+        TestHarness::from_source_bytecode_only,
+    ] {
+        let runtime = build(source).unwrap().into_runtime();
+        let clock = ManualClock::new();
+        let mut runner = ResourceRunner::new(runtime, clock.clone(), Duration::from_millis(1));
+
+        let periodic_id = match runner.runtime().storage().get_global("P1") {
+            Some(Value::Instance(id)) => *id,
+            other => panic!("expected P1 instance, got {other:?}"),
+        };
+        let event_id = match runner.runtime().storage().get_global("P2") {
+            Some(Value::Instance(id)) => *id,
+            other => panic!("expected P2 instance, got {other:?}"),
+        };
+
+        runner.tick().unwrap();
+        assert_counter(
+            runner
+                .runtime()
+                .storage()
+                .get_instance_var(periodic_id, "count"),
+            0,
+        );
+        assert_counter(
+            runner
+                .runtime()
+                .storage()
+                .get_instance_var(event_id, "count"),
+            0,
+        );
+
         runner
-            .runtime()
-            .storage()
-            .get_instance_var(periodic_id, "count"),
-        2,
-    );
-    assert_counter(
+            .runtime_mut()
+            .storage_mut()
+            .set_global("trigger", Value::Bool(true));
+        clock.advance(Duration::from_millis(10));
+        runner.tick().unwrap();
+
+        assert_counter(
+            runner
+                .runtime()
+                .storage()
+                .get_instance_var(periodic_id, "count"),
+            1,
+        );
+        assert_counter(
+            runner
+                .runtime()
+                .storage()
+                .get_instance_var(event_id, "count"),
+            1,
+        );
+
         runner
-            .runtime()
-            .storage()
-            .get_instance_var(event_id, "count"),
-        1,
-    );
+            .runtime_mut()
+            .storage_mut()
+            .set_global("trigger", Value::Bool(false));
+        clock.advance(Duration::from_millis(10));
+        runner.tick().unwrap();
+
+        assert_counter(
+            runner
+                .runtime()
+                .storage()
+                .get_instance_var(periodic_id, "count"),
+            2,
+        );
+        assert_counter(
+            runner
+                .runtime()
+                .storage()
+                .get_instance_var(event_id, "count"),
+            1,
+        );
+    }
 }
 
 #[test]
@@ -143,29 +149,35 @@ x := 1 / 0;
 END_PROGRAM
 "#;
 
-    let runtime = TestHarness::from_source(source).unwrap().into_runtime();
-    let clock = ManualClock::new();
-    let mut runner = ResourceRunner::new(runtime, clock.clone(), Duration::from_millis(1));
+    for build in [
+        TestHarness::from_source,
+        TestHarness::from_source_with_bytecode_vm,
+        TestHarness::from_source_bytecode_only,
+    ] {
+        let runtime = build(source).unwrap().into_runtime();
+        let clock = ManualClock::new();
+        let mut runner = ResourceRunner::new(runtime, clock.clone(), Duration::from_millis(1));
 
-    clock.advance(Duration::from_millis(15));
-    runner.tick().unwrap();
-    assert_eq!(runner.runtime().task_overrun_count("Fast"), Some(2));
+        clock.advance(Duration::from_millis(15));
+        runner.tick().unwrap();
+        assert_eq!(runner.runtime().task_overrun_count("Fast"), Some(2));
 
-    runner
-        .runtime_mut()
-        .storage_mut()
-        .set_global("fault_trigger", Value::Bool(true));
-    let err = runner.tick().unwrap_err();
-    assert!(matches!(err, RuntimeError::DivisionByZero));
-    assert!(runner.runtime().faulted());
+        runner
+            .runtime_mut()
+            .storage_mut()
+            .set_global("fault_trigger", Value::Bool(true));
+        let err = runner.tick().unwrap_err();
+        assert!(matches!(err, RuntimeError::DivisionByZero));
+        assert!(runner.runtime().faulted());
 
-    let err = runner.tick().unwrap_err();
-    assert!(matches!(err, RuntimeError::ResourceFaulted));
+        let err = runner.tick().unwrap_err();
+        assert!(matches!(err, RuntimeError::ResourceFaulted));
+    }
 }
 
 #[test]
 fn trace_determinism() {
-    fn run_trace() -> Vec<RuntimeEvent> {
+    fn run_trace(build: fn(&str) -> Result<TestHarness, CompileError>) -> Vec<RuntimeEvent> {
         let source = r#"
 CONFIGURATION C
 VAR_GLOBAL
@@ -192,7 +204,7 @@ count := count + 1;
 END_PROGRAM
 "#;
 
-        let mut runtime = TestHarness::from_source(source).unwrap().into_runtime();
+        let mut runtime = build(source).unwrap().into_runtime();
         let debug = runtime.enable_debug();
         let clock = ManualClock::new();
         let mut runner = ResourceRunner::new(runtime, clock.clone(), Duration::from_millis(1));
@@ -214,7 +226,13 @@ END_PROGRAM
         debug.drain_runtime_events()
     }
 
-    let trace_a = run_trace();
-    let trace_b = run_trace();
-    assert_eq!(trace_a, trace_b);
+    for build in [
+        TestHarness::from_source,
+        TestHarness::from_source_with_bytecode_vm,
+        TestHarness::from_source_bytecode_only,
+    ] {
+        let trace_a = run_trace(build);
+        let trace_b = run_trace(build);
+        assert_eq!(trace_a, trace_b);
+    }
 }
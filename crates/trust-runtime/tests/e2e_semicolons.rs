@@ -14,9 +14,15 @@ fn trailing_semicolons() {
         END_PROGRAM
     "#;
 
-    let mut harness = TestHarness::from_source(source).unwrap();
-    harness.assert_eq("count", 0i32);
-    harness.set_input("flag", true);
-    harness.cycle();
-    harness.assert_eq("count", 1i32);
+    for build in [
+        TestHarness::from_source,
+        TestHarness::from_source_with_bytecode_vm,
+        TestHarness::from_source_bytecode_only,
+    ] {
+        let mut harness = build(source).unwrap();
+        harness.assert_eq("count", 0i32);
+        harness.set_input("flag", true);
+        harness.cycle();
+        harness.assert_eq("count", 1i32);
+    }
 }
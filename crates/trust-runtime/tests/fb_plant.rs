@@ -0,0 +1,124 @@
+use trust_runtime::harness::TestHarness;
+use trust_runtime::stdlib::fbs::{DeadTime, FirstOrderLag, Integrator, TankLevel, Valve};
+use trust_runtime::value::{Duration, Value};
+
+#[test]
+fn lag_integrator_and_deadtime() {
+    let mut lag = FirstOrderLag::new();
+    let t = Duration::from_millis(1000);
+    let out = lag.step(10.0, t, Duration::from_millis(1000));
+    assert!((out - 10.0 * (1.0 - (-1.0f64).exp())).abs() < 1e-9);
+    assert_eq!(
+        lag.step(4.0, Duration::ZERO, Duration::from_millis(10)),
+        4.0
+    );
+
+    let mut integrator = Integrator::new();
+    assert_eq!(
+        integrator.step(2.0, false, 0.0, Duration::from_millis(500)),
+        1.0
+    );
+    assert_eq!(
+        integrator.step(2.0, false, 0.0, Duration::from_millis(500)),
+        2.0
+    );
+    assert_eq!(
+        integrator.step(2.0, true, 5.0, Duration::from_millis(500)),
+        5.0
+    );
+
+    let mut deadtime = DeadTime::new();
+    let delay = Duration::from_millis(30);
+    let outputs = (0..6)
+        .map(|idx| deadtime.step(idx as f64, delay, Duration::from_millis(idx * 10)))
+        .collect::<Vec<_>>();
+    assert_eq!(outputs, vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0]);
+}
+
+#[test]
+fn deadtime_longer_than_its_sample_buffer() {
+    // 20 s at a 1 ms scan is far more samples than are buffered; the input
+    // is kept every 20 ms instead.
+    let mut deadtime = DeadTime::new();
+    let delay = Duration::from_millis(20_000);
+    for ms in 0..30_000i64 {
+        let out = deadtime.step(ms as f64, delay, Duration::from_millis(ms));
+        if ms < 20_000 {
+            assert_eq!(out, 0.0, "at {ms} ms");
+        } else {
+            let expected = (ms - 20_000) as f64;
+            assert!(
+                out <= expected && out >= expected - 20.0,
+                "at {ms} ms: {out}"
+            );
+        }
+    }
+}
+
+#[test]
+fn valve_travel_and_tank_limits() {
+    let mut valve = Valve::new();
+    let travel = Duration::from_millis(2000);
+    let out = valve.step(100.0, travel, 4.0, Duration::from_millis(1000));
+    assert_eq!(out.pos, 50.0);
+    assert_eq!(out.flow, 2.0);
+    assert!(!out.opened && !out.closed);
+    let out = valve.step(150.0, travel, 4.0, Duration::from_millis(1500));
+    assert!(out.opened);
+    assert_eq!(out.pos, 100.0);
+    let out = valve.step(0.0, Duration::ZERO, 4.0, Duration::from_millis(10));
+    assert!(out.closed);
+    assert_eq!(out.flow, 0.0);
+
+    let mut tank = TankLevel::new();
+    let second = Duration::from_millis(1000);
+    let out = tank.step(3.0, 1.0, 2.0, 5.0, false, 0.0, second);
+    assert_eq!(out.level, 1.0);
+    assert_eq!(out.volume, 2.0);
+    let out = tank.step(0.0, 5.0, 2.0, 5.0, false, 0.0, second);
+    assert_eq!(out.outflow, 2.0);
+    assert!(out.empty);
+    let out = tank.step(50.0, 0.0, 2.0, 5.0, false, 0.0, second);
+    assert!(out.overflow);
+    assert_eq!(out.level, 5.0);
+    let out = tank.step(0.0, 0.0, 2.0, 5.0, true, 1.5, second);
+    assert_eq!(out.level, 1.5);
+    assert!(!out.overflow && !out.empty);
+}
+
+#[test]
+fn plant_blocks_run_from_structured_text() {
+    let source = r#"
+        PROGRAM Test
+        VAR
+            lag : PLANT_LAG;
+            delay : PLANT_DEADTIME;
+            valve : PLANT_VALVE;
+            tank : PLANT_TANK;
+            cmd : REAL;
+            lagged : REAL;
+            delayed : REAL;
+            level : REAL;
+            opened : BOOL;
+        END_VAR
+        lag(IN := cmd, T := T#0s, OUT => lagged);
+        delay(IN := cmd, DELAY := T#20ms, OUT => delayed);
+        valve(CMD := cmd, TRAVEL := T#0s, KV := 2.0, OPENED => opened);
+        tank(QIN := valve.FLOW, AREA := 1.0, HEIGHT := 10.0, LEVEL => level);
+        END_PROGRAM
+    "#;
+
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness.set_input("cmd", Value::Real(100.0));
+    harness.cycle();
+    harness.assert_eq("lagged", Value::Real(100.0));
+    harness.assert_eq("delayed", Value::Real(0.0));
+    harness.assert_eq("opened", Value::Bool(true));
+
+    for _ in 0..2 {
+        harness.advance_time(Duration::from_millis(10));
+        harness.cycle();
+    }
+    harness.assert_eq("delayed", Value::Real(100.0));
+    harness.assert_eq("level", Value::Real(0.04));
+}
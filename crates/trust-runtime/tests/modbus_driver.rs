@@ -3,9 +3,19 @@ use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-use trust_runtime::io::{IoDriver, ModbusTcpDriver};
+use trust_runtime::io::{IoDriver, IoDriverHealth, ModbusTcpDriver, ModbusTcpServerDriver};
 
 fn start_modbus_server(regs: Arc<Mutex<Vec<u16>>>, requests: usize) -> SocketAddr {
+    start_modbus_device(regs, Arc::new(Mutex::new(Vec::new())), requests)
+}
+
+/// Serve `requests` frames on a single accepted connection; a driver that
+/// reconnects between blocks would not be answered.
+fn start_modbus_device(
+    regs: Arc<Mutex<Vec<u16>>>,
+    coils: Arc<Mutex<Vec<bool>>>,
+    requests: usize,
+) -> SocketAddr {
     let listener = TcpListener::bind("127.0.0.1:0").expect("bind modbus test server");
     let addr = listener.local_addr().expect("server addr");
     thread::spawn(move || {
@@ -13,7 +23,7 @@ fn start_modbus_server(regs: Arc<Mutex<Vec<u16>>>, requests: usize) -> SocketAdd
         let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(2)));
         let _ = stream.set_write_timeout(Some(std::time::Duration::from_secs(2)));
         for _ in 0..requests {
-            if handle_modbus_request(&mut stream, &regs).is_err() {
+            if handle_modbus_request(&mut stream, &regs, &coils).is_err() {
                 break;
             }
         }
@@ -21,7 +31,11 @@ fn start_modbus_server(regs: Arc<Mutex<Vec<u16>>>, requests: usize) -> SocketAdd
     addr
 }
 
-fn handle_modbus_request(stream: &mut TcpStream, regs: &Arc<Mutex<Vec<u16>>>) -> Result<(), ()> {
+fn handle_modbus_request(
+    stream: &mut TcpStream,
+    regs: &Arc<Mutex<Vec<u16>>>,
+    coils: &Arc<Mutex<Vec<bool>>>,
+) -> Result<(), ()> {
     let mut header = [0u8; 6];
     stream.read_exact(&mut header).map_err(|_| ())?;
     let tx = u16::from_be_bytes([header[0], header[1]]);
@@ -35,7 +49,9 @@ fn handle_modbus_request(stream: &mut TcpStream, regs: &Arc<Mutex<Vec<u16>>>) ->
     let pdu = &body[1..];
     let function = pdu[0];
     let response = match function {
-        0x04 => handle_read_input(pdu, regs),
+        0x01 => handle_read_coils(pdu, coils),
+        0x03 | 0x04 => handle_read_input(pdu, regs),
+        0x0F => handle_write_coils(pdu, coils),
         0x10 => handle_write_multiple(pdu, regs),
         _ => vec![function | 0x80, 0x01],
     };
@@ -61,7 +77,7 @@ fn handle_read_input(pdu: &[u8], regs: &Arc<Mutex<Vec<u16>>>) -> Vec<u8> {
         return vec![0x84, 0x02];
     }
     let mut payload = Vec::with_capacity(2 + qty * 2);
-    payload.push(0x04);
+    payload.push(pdu[0]);
     payload.push((qty * 2) as u8);
     for reg in &guard[start..start + qty] {
         payload.push((reg >> 8) as u8);
@@ -99,6 +115,37 @@ fn handle_write_multiple(pdu: &[u8], regs: &Arc<Mutex<Vec<u16>>>) -> Vec<u8> {
     ]
 }
 
+fn handle_read_coils(pdu: &[u8], coils: &Arc<Mutex<Vec<bool>>>) -> Vec<u8> {
+    let start = u16::from_be_bytes([pdu[1], pdu[2]]) as usize;
+    let qty = u16::from_be_bytes([pdu[3], pdu[4]]) as usize;
+    let guard = coils.lock().expect("coils lock");
+    if start + qty > guard.len() {
+        return vec![0x81, 0x02];
+    }
+    let mut packed = vec![0u8; qty.div_ceil(8)];
+    for idx in 0..qty {
+        if guard[start + idx] {
+            packed[idx / 8] |= 1 << (idx % 8);
+        }
+    }
+    let mut payload = vec![0x01, packed.len() as u8];
+    payload.extend_from_slice(&packed);
+    payload
+}
+
+fn handle_write_coils(pdu: &[u8], coils: &Arc<Mutex<Vec<bool>>>) -> Vec<u8> {
+    let start = u16::from_be_bytes([pdu[1], pdu[2]]) as usize;
+    let qty = u16::from_be_bytes([pdu[3], pdu[4]]) as usize;
+    let mut guard = coils.lock().expect("coils lock");
+    if start + qty > guard.len() {
+        return vec![0x8F, 0x02];
+    }
+    for idx in 0..qty {
+        guard[start + idx] = pdu[6 + idx / 8] & (1 << (idx % 8)) != 0;
+    }
+    pdu[..5].to_vec()
+}
+
 #[test]
 fn modbus_driver_reads_and_writes() {
     let regs = Arc::new(Mutex::new(vec![0u16; 4]));
@@ -136,7 +183,213 @@ fn modbus_driver_warn_policy_does_not_fault() {
     assert!(result.is_ok(), "warn policy should not fault runtime");
     let health = driver.health();
     match health {
-        trust_runtime::io::IoDriverHealth::Degraded { .. } => {}
+        IoDriverHealth::Degraded { .. } => {}
         other => panic!("expected degraded health, got {other:?}"),
     }
 }
+
+#[test]
+fn modbus_driver_polls_blocks_over_one_connection() {
+    let regs = Arc::new(Mutex::new(vec![0x1122, 0x3344, 0x5566, 0x7788, 0, 0, 0, 0]));
+    let coils = Arc::new(Mutex::new(vec![
+        true, false, true, true, false, false, false, false, false, true,
+    ]));
+    let addr = start_modbus_device(regs.clone(), coils.clone(), 5);
+    let params: toml::Value = toml::from_str(&format!(
+        r#"
+address = "{addr}"
+
+[[blocks]]
+function = 3
+start = 0
+count = 2
+target = "%IW0"
+
+[[blocks]]
+function = 4
+start = 2
+count = 2
+target = "%IW4"
+word_order = "big"
+
+[[blocks]]
+function = 1
+start = 0
+count = 10
+target = "%IX8.2"
+
+[[blocks]]
+function = 16
+start = 4
+count = 1
+target = "%QW0"
+byte_order = "little"
+
+[[blocks]]
+function = 15
+start = 0
+count = 3
+target = "%QX2.0"
+"#
+    ))
+    .expect("params");
+    let mut driver = ModbusTcpDriver::from_params(&params).expect("driver");
+    let mut inputs = vec![0u8; 10];
+    driver.read_inputs(&mut inputs).expect("read inputs");
+    // Registers land as native %IW words.
+    assert_eq!(u16::from_le_bytes([inputs[0], inputs[1]]), 0x1122);
+    assert_eq!(u16::from_le_bytes([inputs[2], inputs[3]]), 0x3344);
+    // High word first: %ID4 holds 0x5566_7788 as a native value.
+    assert_eq!(
+        u32::from_le_bytes([inputs[4], inputs[5], inputs[6], inputs[7]]),
+        0x5566_7788
+    );
+    assert_eq!(inputs[8], 0b0011_0100);
+    assert_eq!(inputs[9], 0b0000_1000);
+
+    let mut outputs = vec![0u8; 3];
+    outputs[..2].copy_from_slice(&0xBEEFu16.to_le_bytes());
+    outputs[2] = 0b0000_0110;
+    driver.write_outputs(&outputs).expect("write outputs");
+    assert_eq!(driver.health(), IoDriverHealth::Ok);
+    // `byte_order = "little"` sends the low byte of %QW0 first.
+    assert_eq!(regs.lock().expect("regs lock")[4], 0xEFBE);
+    assert_eq!(
+        &coils.lock().expect("coils lock")[..3],
+        &[false, true, true]
+    );
+}
+
+#[test]
+fn modbus_driver_round_trips_with_modbus_server() {
+    let server_params: toml::Value = toml::from_str(
+        r#"
+listen = "127.0.0.1:0"
+
+[[map]]
+table = "input_registers"
+start = 0
+count = 3
+address = "%QW0"
+
+[[map]]
+table = "holding_registers"
+start = 100
+count = 1
+address = "%MW0"
+"#,
+    )
+    .expect("server params");
+    let mut server = ModbusTcpServerDriver::from_params(&server_params).expect("server");
+    let mut served = vec![0u8; 6];
+    served[..2].copy_from_slice(&0x1234u16.to_le_bytes());
+    served[2..].copy_from_slice(&0x1122_3344u32.to_le_bytes());
+    server.write_outputs(&served).expect("publish outputs");
+    let mut memory = vec![0u8; 2];
+    server.write_memory(&memory).expect("publish memory");
+
+    let params: toml::Value = toml::from_str(&format!(
+        r#"
+address = "{}"
+
+[[blocks]]
+function = 4
+start = 0
+count = 1
+target = "%IW0"
+
+[[blocks]]
+function = 4
+start = 1
+count = 2
+target = "%ID2"
+word_order = "little"
+
+[[blocks]]
+function = 16
+start = 100
+count = 1
+target = "%QW0"
+"#,
+        server.local_addr()
+    ))
+    .expect("params");
+    let mut driver = ModbusTcpDriver::from_params(&params).expect("driver");
+    let mut inputs = vec![0u8; 6];
+    driver.read_inputs(&mut inputs).expect("read inputs");
+    assert_eq!(u16::from_le_bytes([inputs[0], inputs[1]]), 0x1234);
+    assert_eq!(
+        u32::from_le_bytes([inputs[2], inputs[3], inputs[4], inputs[5]]),
+        0x1122_3344
+    );
+
+    driver
+        .write_outputs(&0xBEEFu16.to_le_bytes())
+        .expect("write outputs");
+    assert_eq!(driver.health(), IoDriverHealth::Ok);
+    server.read_memory(&mut memory).expect("read memory");
+    assert_eq!(u16::from_le_bytes([memory[0], memory[1]]), 0xBEEF);
+}
+
+#[test]
+fn modbus_driver_respects_block_poll_rate() {
+    let regs = Arc::new(Mutex::new(vec![0x0102, 0x0304]));
+    let addr = start_modbus_device(regs.clone(), Arc::new(Mutex::new(Vec::new())), 3);
+    let params: toml::Value = toml::from_str(&format!(
+        r#"
+address = "{addr}"
+blocks = [
+  {{ function = 4, start = 0, count = 1, target = "%IW0" }},
+  {{ function = 4, start = 1, count = 1, target = "%IW2", poll_ms = 60000 }},
+]
+"#
+    ))
+    .expect("params");
+    let mut driver = ModbusTcpDriver::from_params(&params).expect("driver");
+    let mut inputs = vec![0u8; 4];
+    driver.read_inputs(&mut inputs).expect("first poll");
+    assert_eq!(inputs, vec![0x02, 0x01, 0x04, 0x03]);
+
+    *regs.lock().expect("regs lock") = vec![0x0A0B, 0x0C0D];
+    driver.read_inputs(&mut inputs).expect("second poll");
+    assert_eq!(
+        inputs,
+        vec![0x0B, 0x0A, 0x04, 0x03],
+        "slow block not due yet"
+    );
+    assert_eq!(driver.health(), IoDriverHealth::Ok);
+}
+
+#[test]
+fn modbus_driver_rejects_invalid_blocks() {
+    let cases = [
+        (
+            "function = 4\nstart = 0\ncount = 1\ntarget = \"%QW0\"",
+            "%I",
+        ),
+        (
+            "function = 16\nstart = 0\ncount = 1\ntarget = \"%QX0.1\"",
+            "byte aligned",
+        ),
+        (
+            "function = 7\nstart = 0\ncount = 1\ntarget = \"%IW0\"",
+            "function",
+        ),
+        (
+            "function = 3\nstart = 0\ncount = 3\ntarget = \"%IW0\"\nword_order = \"little\"",
+            "even",
+        ),
+        (
+            "function = 1\nstart = 0\ncount = 0\ntarget = \"%IX0.0\"",
+            "count",
+        ),
+    ];
+    for (block, expected) in cases {
+        let params: toml::Value = toml::from_str(&format!(
+            "address = \"127.0.0.1:502\"\n[[blocks]]\n{block}\n"
+        ))
+        .expect("params");
+        let err = ModbusTcpDriver::from_params(&params).expect_err(block);
+        assert!(err.to_string().contains(expected), "{block}: {err}");
+    }
+}
@@ -0,0 +1,168 @@
+#![cfg(unix)]
+
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serialport::{SerialPort, TTYPort};
+use trust_runtime::io::{IoDriver, IoDriverHealth, ModbusRtuDriver};
+
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for byte in data {
+        crc ^= u16::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+type Slaves = Arc<Mutex<Vec<(u8, Vec<u16>)>>>;
+
+/// Answer `requests` frames on the master side of a pty pair. Unit ids that
+/// are not in `slaves` stay silent, like a missing device on the bus. The
+/// line is handed back on join so the pty stays open until the test is done.
+fn start_rtu_slaves(
+    mut line: TTYPort,
+    slaves: Slaves,
+    requests: usize,
+) -> thread::JoinHandle<TTYPort> {
+    line.set_timeout(Duration::from_secs(2)).expect("timeout");
+    thread::spawn(move || {
+        for _ in 0..requests {
+            let mut head = [0u8; 7];
+            if line.read_exact(&mut head).is_err() {
+                break;
+            }
+            let mut frame = head.to_vec();
+            let rest = if head[1] == 0x10 {
+                head[6] as usize + 2
+            } else {
+                1
+            };
+            let mut tail = vec![0u8; rest];
+            if line.read_exact(&mut tail).is_err() {
+                break;
+            }
+            frame.extend_from_slice(&tail);
+            let body = frame.len() - 2;
+            let crc = u16::from_le_bytes([frame[body], frame[body + 1]]);
+            assert_eq!(crc, crc16(&frame[..body]), "request crc");
+            let mut guard = slaves.lock().expect("slaves lock");
+            let Some((_, regs)) = guard.iter_mut().find(|(id, _)| *id == frame[0]) else {
+                continue;
+            };
+            let start = u16::from_be_bytes([frame[2], frame[3]]) as usize;
+            let qty = u16::from_be_bytes([frame[4], frame[5]]) as usize;
+            let mut response = vec![frame[0], frame[1]];
+            match frame[1] {
+                0x04 => {
+                    response.push((qty * 2) as u8);
+                    for reg in &regs[start..start + qty] {
+                        response.extend_from_slice(&reg.to_be_bytes());
+                    }
+                }
+                0x10 => {
+                    for idx in 0..qty {
+                        let offset = 7 + idx * 2;
+                        regs[start + idx] = u16::from_be_bytes([frame[offset], frame[offset + 1]]);
+                    }
+                    response.extend_from_slice(&frame[2..6]);
+                }
+                function => response = vec![frame[0], function | 0x80, 0x01],
+            }
+            let crc = crc16(&response);
+            response.extend_from_slice(&crc.to_le_bytes());
+            line.write_all(&response).expect("write response");
+        }
+        line
+    })
+}
+
+fn params(port: &str, extra: &str) -> toml::Value {
+    toml::from_str(&format!(
+        r#"
+port = "{port}"
+baud = 115200
+parity = "none"
+timeout_ms = 200
+{extra}
+
+[[slaves]]
+unit_id = 1
+inputs = [{{ start = 0, count = 2, offset = 0 }}]
+outputs = [{{ start = 4, count = 1, offset = 0 }}]
+
+[[slaves]]
+unit_id = 7
+inputs = [{{ start = 1, count = 1, offset = 4 }}]
+"#
+    ))
+    .expect("params")
+}
+
+#[test]
+fn modbus_rtu_polls_multiple_slaves() {
+    let (master, slave) = TTYPort::pair().expect("pty pair");
+    let path = slave.name().expect("pty name");
+    let slaves: Slaves = Arc::new(Mutex::new(vec![
+        (1, vec![0x1122, 0x3344, 0, 0, 0]),
+        (7, vec![0, 0xBEEF]),
+    ]));
+    let server = start_rtu_slaves(master, slaves.clone(), 3);
+
+    let mut driver = ModbusRtuDriver::from_params(&params(&path, "")).expect("driver");
+    let mut inputs = vec![0u8; 6];
+    driver.read_inputs(&mut inputs).expect("read inputs");
+    assert_eq!(inputs, vec![0x11, 0x22, 0x33, 0x44, 0xBE, 0xEF]);
+    driver.write_outputs(&[0xAA, 0xBB]).expect("write outputs");
+    assert_eq!(driver.health(), IoDriverHealth::Ok);
+    server.join().expect("slave thread");
+    assert_eq!(slaves.lock().expect("slaves lock")[0].1[4], 0xAABB);
+    drop(slave);
+}
+
+#[test]
+fn modbus_rtu_warn_policy_degrades_on_silent_slave() {
+    let (master, slave) = TTYPort::pair().expect("pty pair");
+    let path = slave.name().expect("pty name");
+    let slaves: Slaves = Arc::new(Mutex::new(vec![(1, vec![0x0102, 0x0304])]));
+    let server = start_rtu_slaves(master, slaves, 2);
+
+    let mut driver =
+        ModbusRtuDriver::from_params(&params(&path, "on_error = \"warn\"")).expect("driver");
+    let mut inputs = vec![0u8; 6];
+    driver
+        .read_inputs(&mut inputs)
+        .expect("warn policy should not fault runtime");
+    assert_eq!(&inputs[..4], &[0x01, 0x02, 0x03, 0x04]);
+    match driver.health() {
+        IoDriverHealth::Degraded { error } => assert!(error.contains("read"), "{error}"),
+        other => panic!("expected degraded health, got {other:?}"),
+    }
+    server.join().expect("slave thread");
+    drop(slave);
+}
+
+#[test]
+fn modbus_rtu_rejects_invalid_line_settings() {
+    let bad = toml::from_str::<toml::Value>(
+        "port = \"/dev/ttyUSB0\"\nstop_bits = 3\n[[slaves]]\nunit_id = 1\n",
+    )
+    .expect("params");
+    let err = ModbusRtuDriver::validate_params(&bad).expect_err("stop bits");
+    assert!(err.to_string().contains("stop_bits"), "{err}");
+
+    let bad = toml::from_str::<toml::Value>(
+        "port = \"/dev/ttyUSB0\"\n[[slaves]]\nunit_id = 1\n[[slaves]]\nunit_id = 1\n",
+    )
+    .expect("params");
+    let err = ModbusRtuDriver::validate_params(&bad).expect_err("duplicate unit");
+    assert!(err.to_string().contains("duplicated"), "{err}");
+}
@@ -0,0 +1,186 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use trust_runtime::io::{IoDriver, IoDriverHealth, ModbusTcpServerDriver};
+
+const MAP: &str = r#"
+listen = "127.0.0.1:0"
+unit_id = 1
+
+[[map]]
+table = "coils"
+start = 0
+count = 8
+address = "%MX0.0"
+
+[[map]]
+table = "discrete_inputs"
+start = 0
+count = 8
+address = "%QX0.0"
+
+[[map]]
+table = "holding_registers"
+start = 100
+count = 2
+address = "%MW10"
+
+[[map]]
+table = "input_registers"
+start = 0
+count = 1
+address = "%QW2"
+
+[[map]]
+table = "holding_registers"
+start = 200
+count = 1
+address = "%IW0"
+"#;
+
+fn server() -> ModbusTcpServerDriver {
+    let params: toml::Value = toml::from_str(MAP).expect("params");
+    ModbusTcpServerDriver::from_params(&params).expect("server driver")
+}
+
+fn connect(addr: SocketAddr) -> TcpStream {
+    let stream = TcpStream::connect(addr).expect("connect modbus server");
+    stream
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .expect("read timeout");
+    stream
+}
+
+fn request(stream: &mut TcpStream, unit_id: u8, pdu: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x00, 0x07, 0x00, 0x00];
+    frame.extend_from_slice(&((pdu.len() + 1) as u16).to_be_bytes());
+    frame.push(unit_id);
+    frame.extend_from_slice(pdu);
+    stream.write_all(&frame).expect("write request");
+    let mut header = [0u8; 7];
+    stream
+        .read_exact(&mut header)
+        .expect("read response header");
+    assert_eq!(&header[0..2], &[0x00, 0x07], "transaction id echoed");
+    assert_eq!(header[6], unit_id);
+    let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+    let mut body = vec![0u8; length - 1];
+    stream.read_exact(&mut body).expect("read response body");
+    body
+}
+
+#[test]
+fn modbus_server_serves_process_image() {
+    let mut driver = server();
+    let mut stream = connect(driver.local_addr());
+
+    let mut outputs = vec![0u8; 4];
+    outputs[0] = 0b0000_0101;
+    outputs[2..4].copy_from_slice(&0x1234u16.to_le_bytes());
+    driver.write_outputs(&outputs).expect("publish outputs");
+
+    let response = request(&mut stream, 1, &[0x02, 0x00, 0x00, 0x00, 0x03]);
+    assert_eq!(response, vec![0x02, 0x01, 0b0000_0101]);
+
+    let response = request(&mut stream, 1, &[0x04, 0x00, 0x00, 0x00, 0x01]);
+    assert_eq!(response, vec![0x04, 0x02, 0x12, 0x34]);
+}
+
+#[test]
+fn modbus_server_applies_client_writes_at_next_read() {
+    let mut driver = server();
+    let mut stream = connect(driver.local_addr());
+    let mut memory = vec![0u8; 16];
+    driver.write_memory(&memory).expect("publish memory");
+    let mut inputs = vec![0u8; 2];
+    driver.read_inputs(&mut inputs).expect("seed inputs");
+
+    let response = request(&mut stream, 1, &[0x05, 0x00, 0x02, 0xFF, 0x00]);
+    assert_eq!(response, vec![0x05, 0x00, 0x02, 0xFF, 0x00]);
+    let response = request(
+        &mut stream,
+        1,
+        &[0x10, 0x00, 0x64, 0x00, 0x02, 0x04, 0x04, 0xD2, 0x00, 0x2A],
+    );
+    assert_eq!(response, vec![0x10, 0x00, 0x64, 0x00, 0x02]);
+    let response = request(&mut stream, 1, &[0x06, 0x00, 0xC8, 0xBE, 0xEF]);
+    assert_eq!(response, vec![0x06, 0x00, 0xC8, 0xBE, 0xEF]);
+
+    // Client reads see their own writes before the runtime consumes them.
+    let response = request(&mut stream, 1, &[0x03, 0x00, 0x64, 0x00, 0x02]);
+    assert_eq!(response, vec![0x03, 0x04, 0x04, 0xD2, 0x00, 0x2A]);
+
+    driver.read_memory(&mut memory).expect("read memory");
+    assert_eq!(memory[0], 0b0000_0100);
+    assert_eq!(u16::from_le_bytes([memory[10], memory[11]]), 1234);
+    assert_eq!(u16::from_le_bytes([memory[12], memory[13]]), 42);
+    driver.read_inputs(&mut inputs).expect("read inputs");
+    assert_eq!(u16::from_le_bytes([inputs[0], inputs[1]]), 0xBEEF);
+
+    // Consumed writes are not applied again over newer program values.
+    memory[10] = 0;
+    driver.write_memory(&memory).expect("publish memory");
+    driver.read_memory(&mut memory).expect("read memory");
+    assert_eq!(memory[10], 0);
+}
+
+#[test]
+fn modbus_server_reports_exceptions() {
+    let driver = server();
+    let mut stream = connect(driver.local_addr());
+
+    let response = request(&mut stream, 1, &[0x03, 0x00, 0x00, 0x00, 0x01]);
+    assert_eq!(response, vec![0x83, 0x02], "unmapped register");
+    let response = request(&mut stream, 1, &[0x05, 0x00, 0x00, 0x12, 0x34]);
+    assert_eq!(response, vec![0x85, 0x03], "invalid coil value");
+    let response = request(&mut stream, 1, &[0x2B, 0x0E, 0x01, 0x00]);
+    assert_eq!(response, vec![0xAB, 0x01], "unsupported function");
+}
+
+#[test]
+fn modbus_server_rejects_writable_tables_on_outputs() {
+    let params: toml::Value = toml::from_str(
+        r#"
+listen = "127.0.0.1:0"
+
+[[map]]
+table = "holding_registers"
+start = 0
+count = 1
+address = "%QW0"
+"#,
+    )
+    .expect("params");
+    let err = ModbusTcpServerDriver::validate_params(&params).expect_err("outputs rejected");
+    assert!(err.to_string().contains("%I or %M"), "{err}");
+}
+
+#[test]
+fn modbus_server_health_recovers_after_rejected_client() {
+    let params: toml::Value = toml::from_str(
+        r#"
+listen = "127.0.0.1:0"
+max_connections = 1
+
+[[map]]
+table = "input_registers"
+start = 0
+count = 1
+address = "%QW0"
+"#,
+    )
+    .expect("params");
+    let driver = ModbusTcpServerDriver::from_params(&params).expect("server driver");
+    let mut first = connect(driver.local_addr());
+    request(&mut first, 1, &[0x04, 0x00, 0x00, 0x00, 0x01]);
+
+    // Over the connection limit: the second client is dropped.
+    let mut second = connect(driver.local_addr());
+    let mut byte = [0u8; 1];
+    assert!(matches!(second.read(&mut byte), Ok(0) | Err(_)));
+    assert!(matches!(driver.health(), IoDriverHealth::Degraded { .. }));
+
+    request(&mut first, 1, &[0x04, 0x00, 0x00, 0x00, 0x01]);
+    assert!(matches!(driver.health(), IoDriverHealth::Ok));
+}
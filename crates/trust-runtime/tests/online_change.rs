@@ -0,0 +1,220 @@
+use std::time::Duration as StdDuration;
+
+use trust_runtime::harness::{CompileSession, SourceFile, TestHarness};
+use trust_runtime::scheduler::{
+    OnlineChangeProgram, ResourceCommand, ResourceControl, ResourceRunner, StdClock,
+};
+use trust_runtime::value::{Duration, Value};
+
+const COUNTER: &str = r#"
+PROGRAM Main
+VAR
+    run : BOOL := TRUE;
+    delay : TON;
+    pulses : CTU;
+    cycles : DINT;
+    toggle : BOOL;
+    mode : INT := 7;
+END_VAR
+toggle := NOT toggle;
+delay(IN := run, PT := T#1s);
+pulses(CU := toggle, PV := INT#100);
+cycles := cycles + 1;
+END_PROGRAM
+"#;
+
+#[test]
+fn online_change_preserves_program_and_fb_instance_state() {
+    let mut harness = TestHarness::from_source(COUNTER).unwrap();
+    for _ in 0..6 {
+        harness.advance_time(Duration::from_millis(100));
+        harness.cycle();
+    }
+    assert_eq!(harness.get_output("cycles"), Some(Value::DInt(6)));
+
+    let updated = COUNTER
+        .replace(
+            "    mode : INT := 7;",
+            "    mode : DINT := 7;\n    extra : BOOL;",
+        )
+        .replace("cycles := cycles + 1;", "cycles := cycles + 10;");
+    let report = harness.online_change_source(&updated).unwrap();
+
+    assert_eq!(harness.get_output("cycles"), Some(Value::DInt(6)));
+    assert_eq!(harness.get_output("mode"), Some(Value::DInt(7)));
+    assert!(
+        report.added.iter().any(|path| path == "Main.extra"),
+        "{report}"
+    );
+    assert!(
+        report
+            .converted
+            .iter()
+            .any(|entry| entry.path == "Main.mode"),
+        "{report}"
+    );
+    assert!(report.reinitialized.is_empty(), "{report}");
+
+    // The timer keeps its elapsed time and fires 1s after it started.
+    for _ in 0..6 {
+        harness.advance_time(Duration::from_millis(100));
+        harness.cycle();
+    }
+    assert_eq!(harness.get_output("cycles"), Some(Value::DInt(66)));
+    let runtime = harness.runtime();
+    let storage = runtime.storage();
+    let Some(Value::Instance(main)) = storage.get_global("Main") else {
+        panic!("program instance");
+    };
+    let Some(Value::Instance(delay)) = storage.get_instance_var(*main, "delay") else {
+        panic!("timer instance");
+    };
+    assert_eq!(
+        storage.get_instance_var(*delay, "Q"),
+        Some(&Value::Bool(true))
+    );
+    let Some(Value::Instance(pulses)) = storage.get_instance_var(*main, "pulses") else {
+        panic!("counter instance");
+    };
+    assert_eq!(
+        storage.get_instance_var(*pulses, "CV"),
+        Some(&Value::Int(6))
+    );
+}
+
+#[test]
+fn online_change_keeps_bytecode_execution() {
+    let mut harness = TestHarness::from_source_with_bytecode_vm(COUNTER).unwrap();
+    harness.run_cycles(3);
+    assert_eq!(harness.get_output("cycles"), Some(Value::DInt(3)));
+
+    let updated = COUNTER.replace("cycles := cycles + 1;", "cycles := cycles + 10;");
+    harness.online_change_source(&updated).unwrap();
+
+    let vm = harness.runtime().bytecode_vm().expect("bytecode VM kept");
+    assert!(vm.has_program_body("Main"));
+    harness.cycle();
+    assert_eq!(harness.get_output("cycles"), Some(Value::DInt(13)));
+}
+
+#[test]
+fn online_change_reports_removed_and_reinitialized_instances() {
+    let source = r#"
+PROGRAM Main
+VAR
+    a : TON;
+    b : CTU;
+    gone : INT := 3;
+END_VAR
+a(IN := TRUE, PT := T#5s);
+b(CU := TRUE, PV := INT#1);
+gone := gone + 1;
+END_PROGRAM
+"#;
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness.cycle();
+
+    let updated = r#"
+PROGRAM Main
+VAR
+    a : TOF;
+    b : CTU;
+END_VAR
+a(IN := TRUE, PT := T#5s);
+b(CU := TRUE, PV := INT#1);
+END_PROGRAM
+"#;
+    let report = harness.online_change_source(updated).unwrap();
+    assert!(
+        report.removed.iter().any(|path| path == "Main.gone"),
+        "{report}"
+    );
+    let reset = report
+        .reinitialized
+        .iter()
+        .find(|entry| entry.path == "Main.a")
+        .unwrap_or_else(|| panic!("timer type change not reported: {report}"));
+    assert!(reset.reason.contains("TON"), "{}", reset.reason);
+    assert!(
+        report.preserved.iter().any(|path| path == "Main.b.CV"),
+        "{:?}",
+        report.preserved
+    );
+}
+
+const THREADED: &str = r#"
+CONFIGURATION C
+VAR_GLOBAL
+    total : DINT := 0;
+END_VAR
+TASK Fast (INTERVAL := T#2ms, PRIORITY := 0);
+PROGRAM P1 WITH Fast : Counter;
+END_CONFIGURATION
+
+PROGRAM Counter
+VAR
+    count : DINT;
+END_VAR
+count := count + 1;
+total := count;
+END_PROGRAM
+"#;
+
+fn total(control: &ResourceControl<StdClock>) -> i32 {
+    let (tx, rx) = std::sync::mpsc::channel();
+    control
+        .send_command(ResourceCommand::Snapshot { respond_to: tx })
+        .unwrap();
+    let snapshot = rx.recv_timeout(StdDuration::from_secs(2)).unwrap();
+    match snapshot.storage.get_global("total") {
+        Some(Value::DInt(value)) => *value,
+        other => panic!("unexpected total: {other:?}"),
+    }
+}
+
+#[test]
+fn online_change_keeps_task_thread_state() {
+    let runtime = TestHarness::from_source(THREADED).unwrap().into_runtime();
+    let runner = ResourceRunner::new(runtime, StdClock::new(), Duration::from_millis(1))
+        .with_task_threads(true);
+    let mut handle = runner.spawn("online-change").unwrap();
+    let control = handle.control();
+
+    let start = std::time::Instant::now();
+    while total(&control) < 3 {
+        assert!(
+            start.elapsed() < StdDuration::from_secs(10),
+            "task did not run"
+        );
+        std::thread::sleep(StdDuration::from_millis(2));
+    }
+    let next = CompileSession::from_sources(vec![SourceFile::new(
+        THREADED.replace("count := count + 1;", "count := count + 1000;"),
+    )])
+    .build_runtime()
+    .unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    control
+        .send_command(ResourceCommand::OnlineChange {
+            program: OnlineChangeProgram::new(next),
+            respond_to: tx,
+        })
+        .unwrap();
+    let (_, report) = rx.recv_timeout(StdDuration::from_secs(5)).unwrap().unwrap();
+    assert!(report.reinitialized.is_empty(), "{report}");
+
+    let start = std::time::Instant::now();
+    while total(&control) < 2000 {
+        assert!(
+            start.elapsed() < StdDuration::from_secs(10),
+            "new program did not run"
+        );
+        std::thread::sleep(StdDuration::from_millis(2));
+    }
+    let total = total(&control);
+    handle.stop();
+    handle.join().unwrap();
+    assert!(handle.last_error().is_none());
+    // The task fork kept its counter across the change instead of restarting it.
+    assert!(total % 1000 >= 3, "counter restarted: total={total}");
+}
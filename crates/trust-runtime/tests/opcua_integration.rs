@@ -2,13 +2,16 @@
 
 use std::net::TcpListener;
 use std::path::PathBuf;
-use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use trust_runtime::config::{validate_runtime_toml_text, RuntimeConfig};
 use trust_runtime::harness::TestHarness;
+use trust_runtime::hmi::{HmiAlarmRecord, HmiAlarmResult};
+use trust_runtime::io::{IoDriver, IoDriverHealth, OpcUaClientDriver};
 use trust_runtime::opcua::{
-    start_wire_server, OpcUaClientIdentity, OpcUaClientOptions, OpcUaDataType, OpcUaVariant,
+    start_wire_server, OpcUaAlarmSource, OpcUaClientIdentity, OpcUaClientOptions, OpcUaDataType,
+    OpcUaMethodCaller, OpcUaVariant,
 };
 
 fn reserve_loopback_port() -> u16 {
@@ -42,33 +45,109 @@ fn load_runtime_fixture(name: &str, port: u16) -> RuntimeConfig {
     RuntimeConfig::load(runtime_path).expect("load runtime fixture")
 }
 
-fn snapshot_provider() -> Arc<dyn Fn() -> Option<trust_runtime::debug::DebugSnapshot> + Send + Sync>
-{
+fn fixture_harness() -> Arc<Mutex<TestHarness>> {
     let mut harness = TestHarness::from_source(include_str!("fixtures/opcua/program/main.st"))
         .expect("build opcua harness");
     let _ = harness.runtime_mut().enable_debug();
     harness.cycle();
-    let snapshot = trust_runtime::debug::DebugSnapshot {
-        storage: harness.runtime().storage().clone(),
-        now: harness.runtime().current_time(),
-    };
-    Arc::new(move || Some(snapshot.clone()))
+    Arc::new(Mutex::new(harness))
+}
+
+fn snapshot_provider(
+    harness: Arc<Mutex<TestHarness>>,
+) -> Arc<dyn Fn() -> Option<trust_runtime::debug::DebugSnapshot> + Send + Sync> {
+    Arc::new(move || {
+        let harness = harness.lock().ok()?;
+        Some(trust_runtime::debug::DebugSnapshot {
+            storage: harness.runtime().storage().clone(),
+            now: harness.runtime().current_time(),
+        })
+    })
+}
+
+fn method_caller(harness: Arc<Mutex<TestHarness>>) -> OpcUaMethodCaller {
+    Arc::new(move |instance, method, inputs| {
+        harness
+            .lock()
+            .expect("harness lock")
+            .runtime_mut()
+            .call_instance_method(instance, method, inputs)
+    })
 }
 
 fn start_fixture_server(name: &str) -> trust_runtime::opcua::OpcUaWireServer {
+    start_fixture_server_with_alarms(name, None)
+}
+
+fn start_fixture_server_with_alarms(
+    name: &str,
+    alarms: Option<OpcUaAlarmSource>,
+) -> trust_runtime::opcua::OpcUaWireServer {
     let port = reserve_loopback_port();
     let runtime = load_runtime_fixture(name, port);
     let runtime_root = temp_runtime_root(name);
+    let harness = fixture_harness();
+    let metadata = harness
+        .lock()
+        .expect("harness lock")
+        .runtime()
+        .metadata_snapshot();
     start_wire_server(
         runtime.resource_name.as_str(),
         &runtime.opcua,
-        snapshot_provider(),
+        snapshot_provider(harness.clone()),
         Some(runtime_root.as_path()),
+        Some(&metadata),
+        Some(method_caller(harness)),
+        alarms,
     )
     .expect("start opcua wire server")
     .expect("opcua enabled")
 }
 
+/// Alarm feed over a shared list of active HMI alarms; acknowledging
+/// flips the record like the HMI alarm state machine does.
+fn alarm_source(active: Arc<Mutex<Vec<HmiAlarmRecord>>>) -> OpcUaAlarmSource {
+    let view_active = active.clone();
+    OpcUaAlarmSource {
+        view: Arc::new(move || {
+            Some(HmiAlarmResult {
+                connected: true,
+                timestamp_ms: 1_000,
+                active: view_active.lock().ok()?.clone(),
+                history: Vec::new(),
+            })
+        }),
+        acknowledge: Arc::new(move |alarm_id| {
+            let mut active = active.lock().expect("alarm lock");
+            let record = active
+                .iter_mut()
+                .find(|record| record.id == alarm_id)
+                .ok_or_else(|| format!("unknown alarm '{alarm_id}'"))?;
+            record.acknowledged = true;
+            record.state = "acknowledged";
+            record.last_change_ms += 1;
+            Ok(())
+        }),
+    }
+}
+
+fn level_alarm() -> HmiAlarmRecord {
+    HmiAlarmRecord {
+        id: "level".to_string(),
+        widget_id: "level".to_string(),
+        path: "Main.level".to_string(),
+        label: "Tank level".to_string(),
+        state: "raised",
+        acknowledged: false,
+        raised_at_ms: 1_000,
+        last_change_ms: 1_000,
+        value: 12.5,
+        min: None,
+        max: Some(10.0),
+    }
+}
+
 fn preferred_node_name(server: &trust_runtime::opcua::OpcUaWireServer) -> String {
     server
         .exposed_nodes()
@@ -173,3 +252,175 @@ fn opcua_load_fixture_covers_browse_read_write_cycle() {
     assert_eq!(report.write_ok, 20);
     server.stop();
 }
+
+#[test]
+fn opcua_structured_values_and_methods_round_trip() {
+    let mut server = start_fixture_server("structured");
+    let names = server
+        .struct_definitions()
+        .iter()
+        .map(|definition| definition.name.to_ascii_uppercase())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["RECIPE"]);
+
+    let recipe = server
+        .probe_read("recipe", OpcUaClientIdentity::Anonymous)
+        .expect("read recipe");
+    let OpcUaVariant::Structure(recipe) = recipe else {
+        panic!("expected structure, got {recipe:?}");
+    };
+    assert_eq!(recipe.fields[0].1, OpcUaVariant::Int32(1200));
+    assert_eq!(recipe.fields[1].1, OpcUaVariant::Boolean(true));
+    let OpcUaVariant::Array(setpoints) = &recipe.fields[2].1 else {
+        panic!("expected setpoints array");
+    };
+    assert_eq!(
+        setpoints.values,
+        [10, 20, 30].map(OpcUaVariant::Int16).to_vec()
+    );
+
+    let levels = server
+        .probe_read("levels", OpcUaClientIdentity::Anonymous)
+        .expect("read levels");
+    let OpcUaVariant::Array(levels) = levels else {
+        panic!("expected array, got {levels:?}");
+    };
+    assert_eq!(levels.dimensions, [4]);
+    assert_eq!(levels.values[3], OpcUaVariant::Float(3.5));
+
+    let outputs = server
+        .probe_call(
+            "doser",
+            "Dose",
+            &[OpcUaVariant::Int32(5)],
+            OpcUaClientIdentity::Anonymous,
+        )
+        .expect("call Dose");
+    assert_eq!(
+        outputs,
+        [OpcUaVariant::Int32(5), OpcUaVariant::Boolean(true)]
+    );
+    let outputs = server
+        .probe_call(
+            "doser",
+            "Dose",
+            &[OpcUaVariant::Int32(7)],
+            OpcUaClientIdentity::Anonymous,
+        )
+        .expect("call Dose again");
+    assert_eq!(outputs[0], OpcUaVariant::Int32(12));
+    server.stop();
+}
+
+#[test]
+fn opcua_client_driver_maps_remote_node_into_inputs() {
+    let mut server = start_fixture_server("interop");
+    let node = server
+        .exposed_nodes()
+        .iter()
+        .find(|node| node.name.ends_with("counter"))
+        .expect("counter node")
+        .clone();
+    let params: toml::Value = toml::from_str(&format!(
+        r#"
+endpoint = "{endpoint}"
+security_policy = "none"
+security_mode = "none"
+pki_dir = "{pki}"
+publish_interval_ms = 50
+reconnect_ms = 100
+
+[[nodes]]
+node_id = "{node_id}"
+address = "%ID0"
+type = "DINT"
+"#,
+        endpoint = server.endpoint_url(),
+        pki = temp_runtime_root("client-io").join("pki").display(),
+        node_id = node.node_id,
+    ))
+    .expect("driver params");
+    let mut driver = OpcUaClientDriver::from_params(&params).expect("opcua client driver");
+
+    let mut inputs = [0u8; 4];
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while i32::from_le_bytes(inputs) != 42 {
+        assert!(
+            Instant::now() < deadline,
+            "no data change from {}",
+            node.node_id
+        );
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(matches!(driver.health(), IoDriverHealth::Ok));
+
+    server.stop();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while matches!(driver.health(), IoDriverHealth::Ok) {
+        assert!(Instant::now() < deadline, "session loss not detected");
+        driver.read_inputs(&mut inputs).expect("read inputs");
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    // The last value is held while the server is unreachable.
+    assert_eq!(i32::from_le_bytes(inputs), 42);
+}
+
+#[test]
+fn opcua_alarm_events_acknowledge_with_current_event_id() {
+    let active = Arc::new(Mutex::new(Vec::new()));
+    let mut server =
+        start_fixture_server_with_alarms("interop", Some(alarm_source(active.clone())));
+
+    // Raise the alarm once the probe has subscribed to Server events.
+    let raise = {
+        let active = active.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(500));
+            active.lock().expect("alarm lock").push(level_alarm());
+        })
+    };
+    let events = server
+        .probe_alarm_events(Duration::from_secs(2), OpcUaClientIdentity::Anonymous)
+        .expect("subscribe to alarm events");
+    raise.join().expect("raise alarm");
+    let raised = events
+        .iter()
+        .find(|event| event.alarm_id == "level")
+        .expect("level alarm event");
+    assert!(!raised.acknowledged);
+    assert!(!raised.event_id.is_empty());
+
+    let err = server
+        .probe_acknowledge_alarm("level", b"stale", false, OpcUaClientIdentity::Anonymous)
+        .expect_err("unknown EventId is rejected");
+    assert!(err.to_string().contains("BadEventIdUnknown"), "{err}");
+    assert!(!active.lock().expect("alarm lock")[0].acknowledged);
+
+    server
+        .probe_acknowledge_alarm(
+            "level",
+            &raised.event_id,
+            false,
+            OpcUaClientIdentity::Anonymous,
+        )
+        .expect("acknowledge with current EventId");
+    assert!(active.lock().expect("alarm lock")[0].acknowledged);
+
+    // The acknowledgement raises a new event, so the old EventId goes stale.
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        let result = server.probe_acknowledge_alarm(
+            "level",
+            &raised.event_id,
+            true,
+            OpcUaClientIdentity::Anonymous,
+        );
+        match result {
+            Err(err) if err.to_string().contains("BadEventIdUnknown") => break,
+            _ => assert!(Instant::now() < deadline, "EventId never went stale"),
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    server.stop();
+}
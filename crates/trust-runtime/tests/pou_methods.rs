@@ -1,4 +1,5 @@
 use trust_runtime::harness::TestHarness;
+use trust_runtime::value::Value;
 
 #[test]
 fn method_calls() {
@@ -50,3 +51,73 @@ END_PROGRAM
     harness.assert_eq("out_c2", 3i16);
     harness.assert_eq("out_fb", 3i16);
 }
+
+#[test]
+fn external_method_calls_on_instances() {
+    let source = r#"
+FUNCTION_BLOCK Doser
+VAR
+    total : DINT := 0;
+END_VAR
+METHOD PUBLIC Dose : DINT
+VAR_INPUT
+    amount : DINT;
+END_VAR
+VAR_OUTPUT
+    accepted : BOOL;
+END_VAR
+VAR_IN_OUT
+    budget : DINT;
+END_VAR
+accepted := amount <= budget;
+IF accepted THEN
+    total := total + amount;
+    budget := budget - amount;
+END_IF;
+Dose := total;
+END_METHOD
+END_FUNCTION_BLOCK
+
+PROGRAM Main
+VAR
+    fb : Doser;
+END_VAR
+END_PROGRAM
+"#;
+
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness.cycle();
+    let outputs = harness
+        .runtime_mut()
+        .call_instance_method("Main.fb", "dose", vec![Value::DInt(4), Value::DInt(10)])
+        .unwrap();
+    assert_eq!(
+        outputs,
+        vec![Value::DInt(4), Value::Bool(true), Value::DInt(6)]
+    );
+    let outputs = harness
+        .runtime_mut()
+        .call_instance_method("Main.fb", "Dose", vec![Value::DInt(8), Value::DInt(6)])
+        .unwrap();
+    assert_eq!(
+        outputs,
+        vec![Value::DInt(4), Value::Bool(false), Value::DInt(6)]
+    );
+
+    let err = harness
+        .runtime_mut()
+        .call_instance_method("Main.fb", "Dose", vec![Value::DInt(1)])
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("expects 2 input argument(s)"),
+        "{err}"
+    );
+    assert!(harness
+        .runtime_mut()
+        .call_instance_method("Main.fb", "Missing", Vec::new())
+        .is_err());
+    assert!(harness
+        .runtime_mut()
+        .call_instance_method("Main.nothing", "Dose", Vec::new())
+        .is_err());
+}
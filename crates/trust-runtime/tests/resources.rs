@@ -1,9 +1,14 @@
 use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
 use std::time::Duration as StdDuration;
 
 use trust_runtime::debug::RuntimeEvent;
+use trust_runtime::error::RuntimeError;
 use trust_runtime::harness::TestHarness;
-use trust_runtime::scheduler::{ManualClock, ResourceRunner, SharedGlobals};
+use trust_runtime::io::IoDriver;
+use trust_runtime::scheduler::{
+    ManualClock, ResourceCommand, ResourceControl, ResourceRunner, SharedGlobals, StdClock,
+};
 use trust_runtime::value::{Duration, Value};
 
 fn wait_for_cycle(rx: &Receiver<RuntimeEvent>, target: u64) {
@@ -25,6 +30,48 @@ fn wait_for_cycle(rx: &Receiver<RuntimeEvent>, target: u64) {
     }
 }
 
+const TASK_THREADS_SOURCE: &str = r#"
+CONFIGURATION C
+VAR_GLOBAL
+    fast_count : DINT := 0;
+    slow_count : DINT := 0;
+    seen_fast : DINT := 0;
+END_VAR
+TASK Fast (INTERVAL := T#2ms, PRIORITY := 0);
+TASK Slow (INTERVAL := T#10ms, PRIORITY := 5);
+PROGRAM P1 WITH Fast : FastProg;
+PROGRAM P2 WITH Slow : SlowProg;
+END_CONFIGURATION
+
+PROGRAM FastProg
+fast_count := fast_count + 1;
+END_PROGRAM
+
+PROGRAM SlowProg
+VAR
+    i : DINT;
+    acc : DINT;
+END_VAR
+seen_fast := fast_count;
+FOR i := 1 TO 100000 DO
+    acc := acc + 1;
+END_FOR;
+slow_count := slow_count + 1;
+END_PROGRAM
+"#;
+
+fn snapshot_dint(control: &ResourceControl<StdClock>, name: &str) -> i32 {
+    let (tx, rx) = std::sync::mpsc::channel();
+    control
+        .send_command(ResourceCommand::Snapshot { respond_to: tx })
+        .unwrap();
+    let snapshot = rx.recv_timeout(StdDuration::from_secs(2)).unwrap();
+    match snapshot.storage.get_global(name) {
+        Some(Value::DInt(value)) => *value,
+        other => panic!("unexpected value for {name}: {other:?}"),
+    }
+}
+
 #[test]
 fn multiple_resources() {
     let source = r#"
@@ -126,3 +173,307 @@ END_PROGRAM
         other => panic!("unexpected shared value {other:?}"),
     }
 }
+
+#[test]
+fn global_sync_between_different_programs() {
+    let source_a = r#"
+CONFIGURATION C
+VAR_GLOBAL
+    shared : INT := 0;
+END_VAR
+TASK T (INTERVAL := T#100ms, PRIORITY := 0);
+PROGRAM P1 WITH T : Mine;
+END_CONFIGURATION
+
+PROGRAM Mine
+VAR
+    mine : INT := 0;
+END_VAR
+mine := mine + 1;
+shared := shared + 1;
+END_PROGRAM
+"#;
+    let source_b = r#"
+CONFIGURATION C
+VAR_GLOBAL
+    shared : INT := 0;
+END_VAR
+TASK T (INTERVAL := T#100ms, PRIORITY := 0);
+PROGRAM P1 WITH T : Theirs;
+END_CONFIGURATION
+
+PROGRAM Theirs
+VAR
+    theirs : INT := 0;
+END_VAR
+theirs := theirs + 1;
+shared := shared + 1;
+END_PROGRAM
+"#;
+
+    let mut runtime_a = TestHarness::from_source(source_a).unwrap().into_runtime();
+    let debug_a = runtime_a.enable_debug();
+    let (tx_a, rx_a) = std::sync::mpsc::channel();
+    debug_a.set_runtime_sender(tx_a);
+
+    let mut runtime_b = TestHarness::from_source(source_b).unwrap().into_runtime();
+    let debug_b = runtime_b.enable_debug();
+    let (tx_b, rx_b) = std::sync::mpsc::channel();
+    debug_b.set_runtime_sender(tx_b);
+
+    let shared = SharedGlobals::from_runtime(vec!["shared".into()], &runtime_a).unwrap();
+
+    let clock_a = ManualClock::new();
+    let clock_b = ManualClock::new();
+
+    let runner_a = ResourceRunner::new(runtime_a, clock_a.clone(), Duration::from_millis(100));
+    let runner_b = ResourceRunner::new(runtime_b, clock_b.clone(), Duration::from_millis(100));
+
+    let mut handle_a = runner_a.spawn_with_shared("res-a", shared.clone()).unwrap();
+    let mut handle_b = runner_b.spawn_with_shared("res-b", shared.clone()).unwrap();
+
+    wait_for_cycle(&rx_a, 0);
+    wait_for_cycle(&rx_b, 0);
+
+    clock_a.advance(Duration::from_millis(100));
+    clock_b.advance(Duration::from_millis(100));
+
+    wait_for_cycle(&rx_a, 1);
+    wait_for_cycle(&rx_b, 1);
+
+    handle_a.stop();
+    handle_b.stop();
+    handle_a.join().unwrap();
+    handle_b.join().unwrap();
+
+    // Only the named globals are shared; each resource keeps its own instances.
+    assert!(handle_a.last_error().is_none());
+    assert!(handle_b.last_error().is_none());
+    match shared.get("shared") {
+        Some(Value::Int(value)) => assert_eq!(i64::from(value), 2),
+        Some(Value::DInt(value)) => assert_eq!(i64::from(value), 2),
+        other => panic!("unexpected shared value {other:?}"),
+    }
+}
+
+#[test]
+fn task_fork_runs_only_its_task() {
+    let mut runtime = TestHarness::from_source(TASK_THREADS_SOURCE)
+        .unwrap()
+        .into_runtime();
+    let mut fast = runtime.fork_task("fast").unwrap();
+    assert_eq!(fast.tasks().len(), 1);
+    assert!(!fast.has_background_programs());
+
+    runtime.set_tasks_detached(true);
+    runtime.advance_time(Duration::from_millis(10));
+    runtime.execute_cycle().unwrap();
+    assert_eq!(
+        runtime.storage().get_global("fast_count"),
+        Some(&Value::DInt(0))
+    );
+
+    fast.advance_time(Duration::from_millis(10));
+    assert!(fast.execute_ready_tasks().unwrap());
+    assert!(!fast.execute_ready_tasks().unwrap());
+    assert_eq!(
+        fast.storage().get_global("fast_count"),
+        Some(&Value::DInt(1))
+    );
+    assert_eq!(
+        fast.storage().get_global("slow_count"),
+        Some(&Value::DInt(0))
+    );
+}
+
+#[test]
+fn task_threads_keep_fast_task_running_during_slow_task() {
+    let runtime = TestHarness::from_source(TASK_THREADS_SOURCE)
+        .unwrap()
+        .into_runtime();
+    let runner = ResourceRunner::new(runtime, StdClock::new(), Duration::from_millis(1))
+        .with_task_threads(true);
+    let mut handle = runner.spawn("res-threads").unwrap();
+    let control = handle.control();
+
+    let start = std::time::Instant::now();
+    while snapshot_dint(&control, "slow_count") < 2 {
+        assert!(
+            start.elapsed() < StdDuration::from_secs(20),
+            "slow task did not complete"
+        );
+        std::thread::sleep(StdDuration::from_millis(5));
+    }
+    let fast_count = snapshot_dint(&control, "fast_count");
+    let seen_fast = snapshot_dint(&control, "seen_fast");
+    handle.stop();
+    handle.join().unwrap();
+    assert!(handle.last_error().is_none());
+
+    // The slow task only reads `fast_count`, so its publish does not roll
+    // back the increments the fast task made while it was running.
+    assert!(
+        fast_count > seen_fast + 1,
+        "fast task stalled: fast_count={fast_count} seen_fast={seen_fast}"
+    );
+}
+
+#[test]
+fn task_threads_reject_cross_resource_sharing() {
+    let runtime = TestHarness::from_source(TASK_THREADS_SOURCE)
+        .unwrap()
+        .into_runtime();
+    let shared = SharedGlobals::from_runtime(vec!["fast_count".into()], &runtime).unwrap();
+    let runner = ResourceRunner::new(runtime, ManualClock::new(), Duration::from_millis(10))
+        .with_task_threads(true);
+    assert!(runner.spawn_with_shared("res", shared).is_err());
+}
+
+const TASK_THREADS_LOCAL_SOURCE: &str = r#"
+CONFIGURATION C
+TASK Fast (INTERVAL := T#2ms, PRIORITY := 0);
+PROGRAM P1 WITH Fast : LocalProg;
+END_CONFIGURATION
+
+PROGRAM LocalProg
+VAR
+    start AT %IX0.0 : BOOL;
+    lamp AT %QX0.0 : BOOL;
+    count : DINT := 0;
+END_VAR
+count := count + 1;
+lamp := start;
+END_PROGRAM
+"#;
+
+struct LoopbackDriver {
+    outputs: Arc<Mutex<Vec<u8>>>,
+}
+
+impl IoDriver for LoopbackDriver {
+    fn read_inputs(&mut self, inputs: &mut [u8]) -> Result<(), RuntimeError> {
+        if let Some(byte) = inputs.first_mut() {
+            *byte = 1;
+        }
+        Ok(())
+    }
+
+    fn write_outputs(&mut self, outputs: &[u8]) -> Result<(), RuntimeError> {
+        *self.outputs.lock().unwrap() = outputs.to_vec();
+        Ok(())
+    }
+}
+
+fn snapshot_program_dint(control: &ResourceControl<StdClock>, program: &str, name: &str) -> i32 {
+    let (tx, rx) = std::sync::mpsc::channel();
+    control
+        .send_command(ResourceCommand::Snapshot { respond_to: tx })
+        .unwrap();
+    let snapshot = rx.recv_timeout(StdDuration::from_secs(2)).unwrap();
+    let Some(Value::Instance(id)) = snapshot.storage.get_global(program) else {
+        panic!("missing program instance {program}");
+    };
+    match snapshot.storage.get_instance_var(*id, name) {
+        Some(Value::DInt(value)) => *value,
+        other => panic!("unexpected value for {program}.{name}: {other:?}"),
+    }
+}
+
+#[test]
+fn task_threads_sync_program_state_and_at_outputs() {
+    let mut runtime = TestHarness::from_source(TASK_THREADS_LOCAL_SOURCE)
+        .unwrap()
+        .into_runtime();
+    let outputs = Arc::new(Mutex::new(Vec::new()));
+    runtime.io_mut().resize(1, 1, 0);
+    runtime.add_io_driver(
+        "loopback",
+        Box::new(LoopbackDriver {
+            outputs: outputs.clone(),
+        }),
+    );
+    let runner = ResourceRunner::new(runtime, StdClock::new(), Duration::from_millis(1))
+        .with_task_threads(true);
+    let mut handle = runner.spawn("res-local").unwrap();
+    let control = handle.control();
+
+    let start = std::time::Instant::now();
+    loop {
+        let count = snapshot_program_dint(&control, "P1", "count");
+        let lamp = outputs.lock().unwrap().first().copied().unwrap_or(0) & 1;
+        if count >= 3 && lamp == 1 {
+            break;
+        }
+        assert!(
+            start.elapsed() < StdDuration::from_secs(20),
+            "program state not synced: count={count} lamp={lamp}"
+        );
+        std::thread::sleep(StdDuration::from_millis(5));
+    }
+    handle.stop();
+    handle.join().unwrap();
+    assert!(handle.last_error().is_none());
+}
+
+const TASK_THREADS_METHOD_SOURCE: &str = r#"
+CONFIGURATION C
+TASK Fast (INTERVAL := T#2ms, PRIORITY := 0);
+PROGRAM P1 WITH Fast : MethodProg;
+END_CONFIGURATION
+
+FUNCTION_BLOCK Motor
+VAR_OUTPUT
+    speed : DINT;
+END_VAR
+METHOD PUBLIC SetSpeed
+VAR_INPUT
+    value : DINT;
+END_VAR
+speed := value;
+END_METHOD
+END_FUNCTION_BLOCK
+
+PROGRAM MethodProg
+VAR
+    m : Motor;
+    seen : DINT := 0;
+END_VAR
+seen := m.speed;
+END_PROGRAM
+"#;
+
+#[test]
+fn task_threads_keep_method_call_results() {
+    let runtime = TestHarness::from_source(TASK_THREADS_METHOD_SOURCE)
+        .unwrap()
+        .into_runtime();
+    let runner = ResourceRunner::new(runtime, StdClock::new(), Duration::from_millis(1))
+        .with_task_threads(true);
+    let mut handle = runner.spawn("res-method").unwrap();
+    let control = handle.control();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    control
+        .send_command(ResourceCommand::CallMethod {
+            instance: "P1.m".into(),
+            method: "SetSpeed".into(),
+            inputs: vec![Value::DInt(42)],
+            respond_to: tx,
+        })
+        .unwrap();
+    let result = rx.recv_timeout(StdDuration::from_secs(2)).unwrap();
+    assert!(result.is_ok(), "{result:?}");
+
+    // The task fork reads the value the method wrote.
+    let start = std::time::Instant::now();
+    while snapshot_program_dint(&control, "P1", "seen") != 42 {
+        assert!(
+            start.elapsed() < StdDuration::from_secs(20),
+            "method call result was overwritten"
+        );
+        std::thread::sleep(StdDuration::from_millis(5));
+    }
+    handle.stop();
+    handle.join().unwrap();
+    assert!(handle.last_error().is_none());
+}
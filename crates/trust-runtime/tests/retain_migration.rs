@@ -0,0 +1,109 @@
+use smol_str::SmolStr;
+use trust_runtime::harness::TestHarness;
+use trust_runtime::value::{StructValue, Value};
+use trust_runtime::RetainSnapshot;
+
+fn harness(globals: &str) -> TestHarness {
+    let source = format!(
+        r#"
+TYPE Settings : STRUCT
+    limit : DINT;
+    speed : INT;
+END_STRUCT
+END_TYPE
+
+PROGRAM Main
+END_PROGRAM
+
+CONFIGURATION Plant
+VAR_GLOBAL RETAIN
+{globals}
+END_VAR
+RESOURCE R ON PLC
+    TASK T (INTERVAL := T#10ms, PRIORITY := 1);
+    PROGRAM P WITH T : Main;
+END_RESOURCE
+END_CONFIGURATION
+"#
+    );
+    TestHarness::from_source(&source).unwrap()
+}
+
+#[test]
+fn retyped_values_are_converted() {
+    let mut harness = harness("    Count : DINT;\n    Small : SINT;");
+    let mut snapshot = RetainSnapshot::default();
+    snapshot.insert("Count", Value::Int(42));
+    snapshot.insert("Small", Value::Int(1000));
+
+    let report = harness.runtime_mut().apply_retain_snapshot(&snapshot);
+    assert_eq!(harness.get_output("Count"), Some(Value::DInt(42)));
+    assert_eq!(harness.get_output("Small"), Some(Value::SInt(0)));
+    assert_eq!(report.converted.len(), 1);
+    assert_eq!(report.converted[0].detail.as_str(), "INT -> DINT");
+    assert_eq!(report.dropped.len(), 1);
+    assert!(report.dropped[0].reason.contains("out of range for SINT"));
+}
+
+#[test]
+fn retain_alias_restores_renamed_and_moved_values() {
+    let mut harness = harness(
+        "    {attribute 'retain_alias' := 'OldTotal'}\n    Total : DINT;\n    {attribute 'retain_alias' := 'limit := Limit, speed := Motor.Speed'}\n    Config : Settings;",
+    );
+    let mut snapshot = RetainSnapshot::default();
+    snapshot.insert("OldTotal", Value::DInt(7));
+    snapshot.insert("Limit", Value::DInt(90));
+    snapshot.insert(
+        "Motor",
+        Value::Struct(StructValue {
+            type_name: SmolStr::new("MotorState"),
+            fields: [(SmolStr::new("Speed"), Value::Int(12))]
+                .into_iter()
+                .collect(),
+        }),
+    );
+
+    let report = harness.runtime_mut().apply_retain_snapshot(&snapshot);
+    assert_eq!(harness.get_output("Total"), Some(Value::DInt(7)));
+    let Some(Value::Struct(config)) = harness.get_output("Config") else {
+        panic!("expected struct");
+    };
+    assert_eq!(config.fields.get("limit"), Some(&Value::DInt(90)));
+    assert_eq!(config.fields.get("speed"), Some(&Value::Int(12)));
+    assert!(report.dropped.is_empty());
+    let targets = report
+        .converted
+        .iter()
+        .map(|entry| entry.target.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(targets, ["Total", "Config.limit", "Config.speed"]);
+}
+
+#[test]
+fn removed_values_are_reported() {
+    let mut harness = harness("    Count : DINT;");
+    let mut snapshot = RetainSnapshot::default();
+    snapshot.insert("Count", Value::DInt(3));
+    snapshot.insert("Gone", Value::Bool(true));
+
+    let report = harness.runtime_mut().apply_retain_snapshot(&snapshot);
+    assert_eq!(report.restored, ["Count"]);
+    assert_eq!(report.dropped.len(), 1);
+    assert_eq!(report.dropped[0].source.as_str(), "Gone");
+}
+
+#[test]
+fn invalid_retain_alias_is_rejected() {
+    let source = r#"
+CONFIGURATION Plant
+VAR_GLOBAL RETAIN
+    {attribute 'retain_alias' := 'bad name'}
+    Count : DINT;
+END_VAR
+END_CONFIGURATION
+"#;
+    let Err(err) = TestHarness::from_source(source) else {
+        panic!("expected compile error");
+    };
+    assert!(err.to_string().contains("invalid retain_alias entry"));
+}
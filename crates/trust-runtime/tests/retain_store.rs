@@ -52,3 +52,86 @@ fn retain_store_missing_file_returns_default() {
     let snapshot = store.load().expect("load missing retain snapshot");
     assert!(snapshot.values().is_empty());
 }
+
+fn counter_snapshot(value: i16) -> RetainSnapshot {
+    let mut snapshot = RetainSnapshot::default();
+    snapshot.insert("Count", Value::Int(value));
+    snapshot
+}
+
+fn cleanup(store: &FileRetainStore, path: &std::path::Path) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(store.backup_path());
+}
+
+#[test]
+fn retain_store_keeps_previous_copy() {
+    let path = temp_path("previous");
+    let store = FileRetainStore::new(&path);
+    cleanup(&store, &path);
+    store.store(&counter_snapshot(1)).expect("store first");
+    store.store(&counter_snapshot(2)).expect("store second");
+
+    let mut tmp = path.clone().into_os_string();
+    tmp.push(".tmp");
+    assert!(!std::path::Path::new(&tmp).exists());
+    let previous = FileRetainStore::new(store.backup_path());
+    assert_eq!(previous.load().expect("load backup"), counter_snapshot(1));
+    assert_eq!(store.load().expect("load latest"), counter_snapshot(2));
+
+    cleanup(&store, &path);
+}
+
+#[test]
+fn retain_store_truncated_snapshot_falls_back_to_previous() {
+    let path = temp_path("truncated");
+    let store = FileRetainStore::new(&path);
+    cleanup(&store, &path);
+    store.store(&counter_snapshot(1)).expect("store first");
+    store.store(&counter_snapshot(2)).expect("store second");
+    let bytes = std::fs::read(&path).expect("read latest");
+    std::fs::write(&path, &bytes[..bytes.len() - 3]).expect("truncate latest");
+
+    let loaded = store.recover().expect("recover");
+    assert_eq!(loaded.snapshot, counter_snapshot(1));
+    let fault = loaded.fault.expect("fault reported");
+    assert!(fault.reason.contains("restored previous copy"));
+
+    cleanup(&store, &path);
+}
+
+#[test]
+fn retain_store_detects_checksum_mismatch() {
+    let path = temp_path("checksum");
+    let store = FileRetainStore::new(&path);
+    cleanup(&store, &path);
+    store.store(&counter_snapshot(7)).expect("store");
+    let mut bytes = std::fs::read(&path).expect("read");
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xFF;
+    std::fs::write(&path, &bytes).expect("corrupt");
+
+    let loaded = store.recover().expect("recover");
+    assert!(loaded.snapshot.values().is_empty());
+    let fault = loaded.fault.expect("fault reported");
+    assert!(fault.reason.contains("checksum"));
+    assert!(fault.reason.contains("retained values lost"));
+
+    cleanup(&store, &path);
+}
+
+#[test]
+fn retain_store_missing_latest_uses_previous() {
+    let path = temp_path("interrupted");
+    let store = FileRetainStore::new(&path);
+    cleanup(&store, &path);
+    store.store(&counter_snapshot(3)).expect("store first");
+    store.store(&counter_snapshot(4)).expect("store second");
+    std::fs::remove_file(&path).expect("simulate interrupted rename");
+
+    let loaded = store.recover().expect("recover");
+    assert_eq!(loaded.snapshot, counter_snapshot(3));
+    assert!(loaded.fault.is_some());
+
+    cleanup(&store, &path);
+}
@@ -134,6 +134,32 @@ END_PROGRAM
     assert!(snapshot.values().is_empty());
 }
 
+#[test]
+fn retain_loss_is_reported_as_fault() {
+    let source = r#"
+PROGRAM Main
+VAR RETAIN
+    r : INT := 5;
+END_VAR
+END_PROGRAM
+"#;
+
+    let path = temp_path("retain_corrupt");
+    std::fs::write(&path, b"STRN\x02\x00garbage").unwrap();
+    let mut harness = TestHarness::from_source(source).unwrap();
+    let store = FileRetainStore::new(&path);
+    harness
+        .runtime_mut()
+        .set_retain_store(Some(Box::new(store)), None);
+    harness.runtime_mut().load_retain_store().unwrap();
+
+    let fault = harness.runtime().retain_fault().expect("retain fault");
+    assert!(fault.reason.contains("retained values lost"));
+    assert_eq!(harness.get_output("r"), Some(Value::Int(5)));
+
+    let _ = std::fs::remove_file(path);
+}
+
 #[test]
 fn watchdog_faults_resource_on_overrun() {
     let source = r#"
@@ -65,3 +65,30 @@ END_PROGRAM
 
     let _ = std::fs::remove_file(path);
 }
+
+#[test]
+fn cold_restart_keeps_io_bindings() {
+    let source = r#"
+PROGRAM Main
+VAR
+    lamp AT %QX0.0 : BOOL;
+    count : INT := INT#5;
+END_VAR
+count := count + INT#1;
+lamp := TRUE;
+END_PROGRAM
+"#;
+    for build in [
+        TestHarness::from_source,
+        TestHarness::from_source_with_bytecode_vm,
+        TestHarness::from_source_bytecode_only,
+    ] {
+        let mut harness = build(source).unwrap();
+        harness.cycle();
+        harness.restart(RestartMode::Cold).unwrap();
+        harness.runtime_mut().io_mut().outputs_mut().fill(0);
+        harness.cycle();
+        assert_eq!(harness.runtime().io().outputs(), &[1]);
+        assert_eq!(harness.get_output("count"), Some(Value::Int(6)));
+    }
+}
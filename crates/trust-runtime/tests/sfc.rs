@@ -0,0 +1,162 @@
+use trust_runtime::harness::TestHarness;
+use trust_runtime::value::{Duration, Value};
+
+fn step_field(harness: &TestHarness, step: &str, field: &str) -> Value {
+    match harness.get_output(step) {
+        Some(Value::Struct(value)) => value
+            .fields
+            .get(field)
+            .cloned()
+            .unwrap_or_else(|| panic!("missing field {step}.{field}")),
+        other => panic!("expected step struct for {step}, got {other:?}"),
+    }
+}
+
+#[test]
+fn sfc_program_steps_actions_and_transitions() {
+    let source = r#"
+PROGRAM Main
+VAR
+    start : BOOL;
+    count : DINT;
+    pulses : DINT;
+    lamp : BOOL;
+END_VAR
+    INITIAL_STEP Idle:
+    END_STEP
+    STEP Fill:
+        Count(N);
+        Pulse(P);
+        lamp(D, T#20ms);
+    END_STEP
+    TRANSITION FROM Idle TO Fill := start;
+    END_TRANSITION
+    TRANSITION FROM Fill TO Idle := Fill.T >= T#50ms;
+    END_TRANSITION
+    ACTION Count:
+        count := count + 1;
+    END_ACTION
+    ACTION Pulse:
+        pulses := pulses + 1;
+    END_ACTION
+END_PROGRAM
+"#;
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness.cycle();
+    assert_eq!(step_field(&harness, "Idle", "X"), Value::Bool(true));
+    assert_eq!(step_field(&harness, "Fill", "X"), Value::Bool(false));
+
+    harness.set_input("start", true);
+    harness.cycle();
+    assert_eq!(step_field(&harness, "Idle", "X"), Value::Bool(false));
+    assert_eq!(step_field(&harness, "Fill", "X"), Value::Bool(true));
+    harness.assert_eq("count", 0i32);
+
+    harness.set_input("start", false);
+    for _ in 0..3 {
+        harness.advance_time(Duration::from_millis(10));
+        harness.cycle();
+    }
+    harness.assert_eq("count", 3i32);
+    harness.assert_eq("pulses", 1i32);
+    harness.assert_eq("lamp", true);
+    assert_eq!(
+        step_field(&harness, "Fill", "T"),
+        Value::Time(Duration::from_millis(30))
+    );
+
+    harness.advance_time(Duration::from_millis(20));
+    harness.cycle();
+    assert_eq!(step_field(&harness, "Fill", "X"), Value::Bool(false));
+    assert_eq!(step_field(&harness, "Idle", "X"), Value::Bool(true));
+    harness.cycle();
+    harness.assert_eq("lamp", false);
+}
+
+#[test]
+fn sfc_parallel_branches_in_function_block() {
+    let source = r#"
+FUNCTION_BLOCK Seq
+VAR_INPUT
+    go : BOOL;
+END_VAR
+VAR_OUTPUT
+    a_done : BOOL;
+    b_done : BOOL;
+    joined : BOOL;
+END_VAR
+    INITIAL_STEP Start:
+        joined(R);
+    END_STEP
+    STEP BranchA:
+        a_done(S);
+    END_STEP
+    STEP BranchB:
+        b_done(S);
+    END_STEP
+    STEP Join:
+        joined(S);
+    END_STEP
+    TRANSITION FROM Start TO (BranchA, BranchB) := go;
+    END_TRANSITION
+    TRANSITION FROM (BranchA, BranchB) TO Join := a_done AND b_done;
+    END_TRANSITION
+END_FUNCTION_BLOCK
+
+PROGRAM Main
+VAR
+    seq : Seq;
+    go : BOOL;
+    joined : BOOL;
+END_VAR
+    seq(go := go);
+    joined := seq.joined;
+END_PROGRAM
+"#;
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness.cycle();
+    harness.assert_eq("joined", false);
+    harness.set_input("go", true);
+    harness.run_cycles(4);
+    harness.assert_eq("joined", true);
+}
+
+#[test]
+fn sfc_non_stored_action_runs_final_scan_on_deactivation() {
+    let source = r#"
+PROGRAM Main
+VAR
+    go : BOOL;
+    runs : DINT;
+END_VAR
+    INITIAL_STEP Idle:
+    END_STEP
+    STEP Work:
+        Count(N);
+    END_STEP
+    TRANSITION FROM Idle TO Work := go;
+    END_TRANSITION
+    TRANSITION FROM Work TO Idle := NOT go;
+    END_TRANSITION
+    ACTION Count:
+        runs := runs + 1;
+    END_ACTION
+END_PROGRAM
+"#;
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness.set_input("go", true);
+    harness.cycle();
+    harness.run_cycles(2);
+    harness.assert_eq("runs", 2i32);
+
+    harness.set_input("go", false);
+    harness.cycle();
+    harness.assert_eq("runs", 3i32);
+    assert_eq!(step_field(&harness, "Idle", "X"), Value::Bool(true));
+
+    // Work is no longer active: the action executes once more with Q = FALSE.
+    harness.cycle();
+    harness.assert_eq("runs", 4i32);
+    harness.run_cycles(3);
+    harness.assert_eq("runs", 4i32);
+}
@@ -0,0 +1,179 @@
+use trust_runtime::harness::{CompileError, CompileSession, TestHarness};
+use trust_runtime::value::{Duration, Value};
+use trust_syntax::parser::Dialect;
+
+const SOURCE: &str = r#"
+FUNCTION_BLOCK "FB_Motor"
+VERSION : 0.1
+    VAR_INPUT
+        enable : BOOL;
+        setpoint : INT;
+    END_VAR
+    VAR_OUTPUT
+        running : BOOL;
+        speed : INT;
+    END_VAR
+BEGIN
+    REGION Output
+        #running := #enable;
+        IF #running THEN
+            #speed := #setpoint;
+        ELSE
+            #speed := 0;
+        END_IF;
+    END_REGION
+END_FUNCTION_BLOCK
+
+DATA_BLOCK "Line_DB"
+{ S7_Optimized_Access := 'TRUE' }
+VERSION : 0.1
+NON_RETAIN
+    VAR
+        setpoint : INT := 10;
+        enable : BOOL;
+        cycles : DINT;
+    END_VAR
+BEGIN
+    setpoint := 25;
+    enable := TRUE;
+END_DATA_BLOCK
+
+DATA_BLOCK "Motor_Inst"
+"FB_Motor"
+BEGIN
+    setpoint := 5;
+END_DATA_BLOCK
+
+ORGANIZATION_BLOCK "Main"
+TITLE = Main cycle
+VERSION : 0.1
+    VAR
+        speed : INT;
+        running : BOOL;
+    END_VAR
+BEGIN
+    REGION Count
+        "Line_DB".cycles := "Line_DB".cycles + 1;
+    END_REGION
+    "Motor_Inst"(enable := "Line_DB".enable, setpoint := "Line_DB".setpoint);
+    #speed := "Motor_Inst".speed;
+    #running := "Motor_Inst".running;
+END_ORGANIZATION_BLOCK
+"#;
+
+fn siemens(source: &str) -> Result<TestHarness, CompileError> {
+    TestHarness::from_session(&CompileSession::from_source(source).dialect(Dialect::Siemens))
+}
+
+#[test]
+fn data_block_start_values_are_applied() {
+    let harness = siemens(SOURCE).unwrap();
+    let Some(Value::Struct(line)) = harness.get_output("Line_DB") else {
+        panic!("expected struct data block");
+    };
+    assert_eq!(line.fields.get("setpoint"), Some(&Value::Int(25)));
+    assert_eq!(line.fields.get("enable"), Some(&Value::Bool(true)));
+    assert_eq!(line.fields.get("cycles"), Some(&Value::DInt(0)));
+}
+
+#[test]
+fn organization_block_runs_with_data_blocks() {
+    let mut harness = siemens(SOURCE).unwrap();
+    harness.cycle();
+    harness.cycle();
+    harness.assert_eq("speed", 25i16);
+    harness.assert_eq("running", true);
+    let Some(Value::Struct(line)) = harness.get_output("Line_DB") else {
+        panic!("expected struct data block");
+    };
+    assert_eq!(line.fields.get("cycles"), Some(&Value::DInt(2)));
+}
+
+#[test]
+fn data_block_rejects_statements_after_begin() {
+    let source = r#"
+DATA_BLOCK "Bad_DB"
+    VAR
+        value : INT;
+    END_VAR
+BEGIN
+    IF TRUE THEN
+        value := 1;
+    END_IF;
+END_DATA_BLOCK
+
+ORGANIZATION_BLOCK "Main"
+BEGIN
+    ;
+END_ORGANIZATION_BLOCK
+"#;
+    let err = siemens(source).err().expect("compile error");
+    assert!(
+        err.to_string().contains("only supports assignments"),
+        "{err}"
+    );
+}
+
+#[test]
+fn cyclic_organization_blocks_run_as_interval_tasks() {
+    let source = r#"
+ORGANIZATION_BLOCK "Main"
+    VAR
+        main_cycles : DINT;
+    END_VAR
+BEGIN
+    #main_cycles := #main_cycles + 1;
+END_ORGANIZATION_BLOCK
+
+ORGANIZATION_BLOCK OB35
+    VAR
+        fast_cycles : DINT;
+    END_VAR
+BEGIN
+    #fast_cycles := #fast_cycles + 1;
+END_ORGANIZATION_BLOCK
+"#;
+    let mut harness = siemens(source).unwrap();
+    let tasks = harness.runtime().tasks();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].programs, vec!["OB35"]);
+    assert_eq!(tasks[0].interval, Duration::from_millis(100));
+
+    for _ in 0..3 {
+        harness.advance_time(Duration::from_millis(50));
+        harness.cycle();
+    }
+    harness.assert_eq("main_cycles", 3i32);
+    harness.assert_eq("fast_cycles", 1i32);
+}
+
+#[test]
+fn non_cyclic_organization_blocks_are_rejected_without_configuration() {
+    let source = r#"
+ORGANIZATION_BLOCK "OB100"
+BEGIN
+    ;
+END_ORGANIZATION_BLOCK
+"#;
+    let err = siemens(source).err().expect("compile error");
+    assert!(err.to_string().contains("not a cyclic OB"), "{err}");
+}
+
+#[test]
+fn siemens_block_words_are_identifiers_in_iec() {
+    let source = r#"
+PROGRAM Main
+VAR
+    data_block : DINT;
+    organization_block : DINT;
+END_VAR
+data_block := data_block + 1;
+organization_block := data_block;
+END_PROGRAM
+"#;
+    let mut harness = TestHarness::from_source(source).unwrap();
+    harness.cycle();
+    harness.cycle();
+    harness.assert_eq("data_block", 2i32);
+    harness.assert_eq("organization_block", 2i32);
+}
@@ -1,8 +1,9 @@
 use trust_runtime::error::RuntimeError;
+use trust_runtime::harness::TestHarness;
 use trust_runtime::io::IoAddress;
 use trust_runtime::scheduler::{ResourceRunner, ResourceState, ScaledClock};
 use trust_runtime::simulation::{
-    SignalCouplingRule, SimulationConfig, SimulationController, SimulationDisturbance,
+    PlantModel, SignalCouplingRule, SimulationConfig, SimulationController, SimulationDisturbance,
     SimulationDisturbanceKind,
 };
 use trust_runtime::value::{Duration, Value};
@@ -61,6 +62,134 @@ message = "fault-script"
     let _ = std::fs::remove_dir_all(&root);
 }
 
+#[test]
+fn plant_section_resolves_sources_next_to_config() {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("unix epoch")
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!(
+        "trust-runtime-simulation-plant-{}-{stamp}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(root.join("plant")).expect("create plant dir");
+    std::fs::write(root.join("plant").join("plant.st"), PLANT_SOURCE).expect("write plant");
+    let path = root.join("simulation.toml");
+    std::fs::write(&path, "[plant]\n").expect("write simulation.toml");
+
+    let config = SimulationConfig::load(&path).expect("load config");
+    assert!(config.enabled);
+    let plant = config.plant.as_ref().expect("plant config");
+    assert_eq!(plant.sources, vec![root.join("plant")]);
+    let simulation = SimulationController::from_config(config).expect("compile plant");
+    assert!(simulation.plant().is_some());
+
+    std::fs::write(&path, "[plant]\nsources = [\"missing.st\"]\n").expect("rewrite config");
+    let config = SimulationConfig::load(&path).expect("load config");
+    let err = SimulationController::from_config(config).expect_err("missing plant source");
+    assert!(matches!(err, RuntimeError::InvalidConfig(_)));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn plant_compile_applies_project_defines() {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("unix epoch")
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!(
+        "trust-runtime-simulation-defines-{}-{stamp}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(root.join("plant")).expect("create plant dir");
+    std::fs::write(
+        root.join("plant").join("plant.st"),
+        r#"
+PROGRAM Plant
+VAR
+    y : INT;
+END_VAR
+{IF defined(SIM)}
+y := 1;
+{ELSE}
+y := missing_io;
+{END_IF}
+END_PROGRAM
+"#,
+    )
+    .expect("write plant");
+    let path = root.join("simulation.toml");
+    std::fs::write(&path, "[plant]\n").expect("write simulation.toml");
+
+    std::fs::write(
+        root.join("trust-lsp.toml"),
+        "[build]\ndefines = [\"SIM\"]\n",
+    )
+    .expect("write manifest");
+    let config = SimulationConfig::load(&path).expect("load config");
+    SimulationController::from_config(config).expect("plant compiles with SIM defined");
+
+    std::fs::write(root.join("trust-lsp.toml"), "[build]\n").expect("rewrite manifest");
+    let config = SimulationConfig::load(&path).expect("load config");
+    let err = SimulationController::from_config(config).expect_err("else branch is compiled");
+    assert!(err.to_string().contains("missing_io"), "{err}");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[test]
+fn plant_program_closes_the_loop_with_the_controller() {
+    let mut controller = TestHarness::from_source(CONTROLLER_SOURCE)
+        .expect("compile controller")
+        .into_runtime();
+    let plant = TestHarness::from_source(PLANT_SOURCE)
+        .expect("compile plant")
+        .into_runtime();
+    let mut simulation = SimulationController::new(SimulationConfig {
+        enabled: true,
+        ..SimulationConfig::default()
+    })
+    .with_plant(PlantModel::new(plant));
+
+    let level = IoAddress::parse("%IW0").expect("level address");
+    let fill = IoAddress::parse("%QX0.0").expect("fill address");
+    let mut switches = 0;
+    let mut last_fill = false;
+    let mut max_level = 0u16;
+    for cycle in 0..3_000 {
+        let now = Duration::from_millis(cycle * 100);
+        controller.set_current_time(now);
+        simulation
+            .apply_pre_cycle(now, &mut controller)
+            .expect("plant cycle");
+        controller.execute_cycle().expect("controller cycle");
+        simulation
+            .apply_post_cycle(now, &controller)
+            .expect("post cycle");
+
+        let Value::Bool(filling) = controller.io().read(&fill).expect("read fill") else {
+            panic!("expected BOOL fill command");
+        };
+        if filling != last_fill {
+            switches += 1;
+            last_fill = filling;
+        }
+        let Value::Word(mm) = controller.io().read(&level).expect("read level") else {
+            panic!("expected WORD level");
+        };
+        if cycle > 1_500 {
+            max_level = max_level.max(mm);
+            assert!((300..=700).contains(&mm), "level {mm} mm left the band");
+        }
+    }
+    assert!(
+        switches >= 3,
+        "controller switched the inlet {switches} times"
+    );
+    assert!(max_level > 550);
+}
+
 #[test]
 fn deterministic_trace_with_same_simulation_config() {
     let trace_a = run_simulation_trace();
@@ -86,6 +215,7 @@ fn coupling_applies_threshold_with_delay() {
             on_false: Some(Value::Bool(false)),
         }],
         disturbances: Vec::new(),
+        plant: None,
     });
 
     runtime
@@ -137,6 +267,7 @@ fn scripted_fault_disturbance_faults_runtime() {
                 message: "inject-fault".into(),
             },
         }],
+        plant: None,
     });
 
     simulation
@@ -192,6 +323,7 @@ fn run_simulation_trace() -> Vec<bool> {
                 value: Value::Bool(true),
             },
         }],
+        plant: None,
     });
 
     let output_pattern = [1u16, 12u16, 4u16, 18u16, 0u16, 25u16];
@@ -219,3 +351,37 @@ fn read_input_bit(runtime: &Runtime, address: &IoAddress) -> bool {
         other => panic!("expected bool input, got {other:?}"),
     }
 }
+
+const CONTROLLER_SOURCE: &str = r#"
+PROGRAM Control
+VAR
+    level AT %IW0 : INT;
+    fill AT %QX0.0 : BOOL;
+END_VAR
+IF level < 400 THEN
+    fill := TRUE;
+ELSIF level > 600 THEN
+    fill := FALSE;
+END_IF;
+END_PROGRAM
+"#;
+
+const PLANT_SOURCE: &str = r#"
+PROGRAM Plant
+VAR
+    fill AT %IX0.0 : BOOL;
+    level_mm AT %QW0 : INT;
+    inlet : PLANT_VALVE;
+    tank : PLANT_TANK;
+    cmd : REAL;
+END_VAR
+IF fill THEN
+    cmd := 100.0;
+ELSE
+    cmd := 0.0;
+END_IF;
+inlet(CMD := cmd, TRAVEL := T#2s, KV := 0.01);
+tank(QIN := inlet.FLOW, QOUT := 0.002, AREA := 1.0, HEIGHT := 2.0);
+level_mm := REAL_TO_INT(tank.LEVEL * REAL#1000.0);
+END_PROGRAM
+"#;
@@ -7,6 +7,7 @@ mod tokens;
 
 pub use tokens::TokenKind;
 
+use crate::parser::Dialect;
 use logos::Logos;
 use std::collections::VecDeque;
 use text_size::{TextRange, TextSize};
@@ -151,6 +152,28 @@ pub fn lex(source: &str) -> Vec<Token> {
     Lexer::new(source).collect()
 }
 
+/// Lex the entire source with the keywords of a vendor dialect.
+///
+/// Under [`Dialect::Siemens`] the SCL block keywords (`DATA_BLOCK`,
+/// `ORGANIZATION_BLOCK`, `END_REGION`, ...) are recognized; in IEC they stay
+/// identifiers.
+#[must_use]
+pub fn lex_with_dialect(source: &str, dialect: Dialect) -> Vec<Token> {
+    let mut tokens = lex(source);
+    if dialect == Dialect::Siemens {
+        for token in &mut tokens {
+            if token.kind == TokenKind::Ident {
+                let text =
+                    &source[usize::from(token.range.start())..usize::from(token.range.end())];
+                if let Some(kind) = TokenKind::siemens_keyword(text) {
+                    token.kind = kind;
+                }
+            }
+        }
+    }
+    tokens
+}
+
 /// Lex source and return tokens paired with their text.
 ///
 /// Useful for debugging and testing.
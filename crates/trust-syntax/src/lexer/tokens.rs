@@ -283,6 +283,21 @@ pub enum TokenKind {
     #[token("USING", ignore(ascii_case))]
     KwUsing,
 
+    /// `ORGANIZATION_BLOCK` (Siemens SCL extension, see [`TokenKind::siemens_keyword`])
+    KwOrganizationBlock,
+
+    /// `END_ORGANIZATION_BLOCK` (Siemens SCL extension)
+    KwEndOrganizationBlock,
+
+    /// `DATA_BLOCK` (Siemens SCL extension)
+    KwDataBlock,
+
+    /// `END_DATA_BLOCK` (Siemens SCL extension)
+    KwEndDataBlock,
+
+    /// `END_REGION` (Siemens SCL extension; `REGION` itself is contextual)
+    KwEndRegion,
+
     /// `ACTION`
     #[token("ACTION", ignore(ascii_case))]
     KwAction,
@@ -1012,6 +1027,24 @@ impl TokenKind {
         )
     }
 
+    /// Returns the Siemens SCL block keyword spelled `text`, if any.
+    ///
+    /// These words are ordinary identifiers in IEC 61131-3, so the lexer
+    /// never produces them; `lex_with_dialect` applies them under the
+    /// Siemens dialect.
+    #[must_use]
+    pub fn siemens_keyword(text: &str) -> Option<Self> {
+        let kind = match text.to_ascii_uppercase().as_str() {
+            "ORGANIZATION_BLOCK" => Self::KwOrganizationBlock,
+            "END_ORGANIZATION_BLOCK" => Self::KwEndOrganizationBlock,
+            "DATA_BLOCK" => Self::KwDataBlock,
+            "END_DATA_BLOCK" => Self::KwEndDataBlock,
+            "END_REGION" => Self::KwEndRegion,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
     /// Returns `true` if this token is a keyword.
     pub fn is_keyword(self) -> bool {
         matches!(
@@ -1037,6 +1070,11 @@ impl TokenKind {
                 | Self::KwNamespace
                 | Self::KwEndNamespace
                 | Self::KwUsing
+                | Self::KwOrganizationBlock
+                | Self::KwEndOrganizationBlock
+                | Self::KwDataBlock
+                | Self::KwEndDataBlock
+                | Self::KwEndRegion
                 | Self::KwAction
                 | Self::KwEndAction
                 | Self::KwVar
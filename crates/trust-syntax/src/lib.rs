@@ -39,8 +39,9 @@
 
 pub mod lexer;
 pub mod parser;
+pub mod preprocess;
 pub mod syntax;
 mod token_kinds;
 
-pub use lexer::{lex, Lexer, Token, TokenKind};
+pub use lexer::{lex, lex_with_dialect, Lexer, Token, TokenKind};
 pub use syntax::{StLanguage, SyntaxKind, SyntaxNode, SyntaxToken};
@@ -19,7 +19,7 @@ impl Parser<'_, '_> {
         self.bump(); // TYPE
 
         while !self.at(TokenKind::KwEndType) && !self.at_end() {
-            if self.at(TokenKind::Ident) {
+            if self.at_name() {
                 self.parse_name();
             } else {
                 self.error("expected type name");
@@ -418,6 +418,8 @@ impl Parser<'_, '_> {
                 self.parse_name();
             }
             self.parse_type_subrange();
+        } else if self.at(TokenKind::WideStringLiteral) {
+            self.parse_name();
         } else {
             self.error("expected type");
         }
@@ -444,6 +446,9 @@ impl Parser<'_, '_> {
         self.start_node(SyntaxKind::Name);
         if self.at(TokenKind::Ident) || self.at(TokenKind::KwEn) || self.at(TokenKind::KwEno) {
             self.bump();
+        } else if self.at(TokenKind::WideStringLiteral) {
+            // Siemens quoted name: `"Motor_DB"`.
+            self.bump_as(SyntaxKind::Ident);
         }
         self.finish_node();
     }
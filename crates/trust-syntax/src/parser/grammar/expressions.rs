@@ -13,8 +13,8 @@
 use crate::lexer::TokenKind;
 use crate::syntax::SyntaxKind;
 
-use super::super::CompletedMarker;
 use super::super::Parser;
+use super::super::{CompletedMarker, Dialect};
 
 // Guard against pathological recursive inputs discovered by fuzzing.
 const MAX_EXPRESSION_DEPTH: usize = 1024;
@@ -79,7 +79,7 @@ impl Parser<'_, '_> {
             TokenKind::Dot => {
                 let marker = lhs.precede(self);
                 self.bump();
-                if self.at(TokenKind::Ident) {
+                if self.at_name() {
                     self.parse_name();
                 } else if self.at(TokenKind::IntLiteral) || self.at(TokenKind::DirectAddress) {
                     self.start_node(SyntaxKind::Literal);
@@ -122,6 +122,22 @@ impl Parser<'_, '_> {
     /// Parse primary expressions (literals, identifiers, etc.).
     pub(crate) fn parse_primary_expr(&mut self) -> CompletedMarker {
         match self.current() {
+            TokenKind::WideStringLiteral
+                if self.dialect == Dialect::Siemens
+                    || matches!(
+                        self.peek_kind_n(1),
+                        TokenKind::Dot
+                            | TokenKind::LParen
+                            | TokenKind::LBracket
+                            | TokenKind::Assign
+                    ) =>
+            {
+                // Siemens quoted identifier: `"Motor_DB".speed`, `"Tag" := TRUE`,
+                // and with the Siemens dialect any `"Tag"` operand.
+                let marker = self.start();
+                self.bump_as(SyntaxKind::Ident);
+                marker.complete(self, SyntaxKind::NameRef)
+            }
             TokenKind::IntLiteral
             | TokenKind::RealLiteral
             | TokenKind::StringLiteral
@@ -215,6 +231,8 @@ impl Parser<'_, '_> {
                     TokenKind::Ident | TokenKind::KwEn | TokenKind::KwEno
                 ) {
                     self.bump();
+                } else if self.at(TokenKind::WideStringLiteral) {
+                    self.bump_as(SyntaxKind::Ident);
                 } else {
                     self.error("expected identifier after '#'");
                 }
@@ -6,8 +6,12 @@
 //! - `declarations.rs` - Variable and type declarations
 //! - `statements.rs` - Statement parsing
 //! - `expressions.rs` - Expression parsing (Pratt parser)
+//! - `sfc.rs` - Sequential Function Chart steps and transitions (textual form)
+//! - `siemens.rs` - Siemens SCL blocks, header properties, and regions
 
 mod declarations;
 mod expressions;
 mod pou;
+mod sfc;
+mod siemens;
 mod statements;
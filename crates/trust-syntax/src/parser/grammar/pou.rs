@@ -3,6 +3,7 @@
 //! Handles:
 //! - PROGRAM / END_PROGRAM
 //! - TEST_PROGRAM / END_TEST_PROGRAM (extension)
+//! - ORGANIZATION_BLOCK / END_ORGANIZATION_BLOCK (Siemens SCL, parsed as a program)
 //! - FUNCTION / END_FUNCTION
 //! - FUNCTION_BLOCK / END_FUNCTION_BLOCK
 //! - TEST_FUNCTION_BLOCK / END_TEST_FUNCTION_BLOCK (extension)
@@ -15,6 +16,9 @@
 //! - INTERFACE / END_INTERFACE
 //! - NAMESPACE / END_NAMESPACE
 //! - ACTION / END_ACTION
+//!
+//! SFC steps and transitions inside PROGRAM / FUNCTION_BLOCK bodies are
+//! handled in `sfc.rs`.
 
 use crate::lexer::TokenKind;
 use crate::syntax::SyntaxKind;
@@ -22,45 +26,55 @@ use crate::syntax::SyntaxKind;
 use super::super::Parser;
 
 impl Parser<'_, '_> {
-    /// Parse a PROGRAM declaration.
+    /// Parse a PROGRAM declaration (also TEST_PROGRAM and ORGANIZATION_BLOCK).
     pub(crate) fn parse_program(&mut self) {
-        let is_test_program = self.at(TokenKind::KwTestProgram);
-        let expected_end = if is_test_program {
-            TokenKind::KwEndTestProgram
-        } else {
-            TokenKind::KwEndProgram
-        };
-        let alternate_end = if is_test_program {
-            TokenKind::KwEndProgram
-        } else {
-            TokenKind::KwEndTestProgram
+        let (expected_end, alternate_end, end_error) = match self.current() {
+            TokenKind::KwTestProgram => (
+                TokenKind::KwEndTestProgram,
+                TokenKind::KwEndProgram,
+                "expected END_TEST_PROGRAM",
+            ),
+            TokenKind::KwOrganizationBlock => (
+                TokenKind::KwEndOrganizationBlock,
+                TokenKind::KwEndProgram,
+                "expected END_ORGANIZATION_BLOCK",
+            ),
+            _ => (
+                TokenKind::KwEndProgram,
+                TokenKind::KwEndTestProgram,
+                "expected END_PROGRAM",
+            ),
         };
 
         self.start_node(SyntaxKind::Program);
-        self.bump(); // PROGRAM / TEST_PROGRAM
+        self.bump(); // PROGRAM / TEST_PROGRAM / ORGANIZATION_BLOCK
 
-        if self.at(TokenKind::Ident) {
+        if self.at_name() {
             self.parse_name();
         } else {
             self.error("expected program name");
         }
 
+        self.parse_block_properties();
         self.parse_using_directives();
 
         // Parse var blocks
         while self.current().is_var_keyword() {
             self.parse_var_block();
         }
+        self.parse_block_begin();
 
         // Parse statements and actions in a statement list
         self.start_node(SyntaxKind::StmtList);
         while !self.at(expected_end)
             && !self.at(alternate_end)
             && !self.at_end()
-            && !self.at_stmt_list_end()
+            && (!self.at_stmt_list_end() || self.at(TokenKind::KwAction) || self.at_sfc_element())
         {
             if self.at(TokenKind::KwAction) {
                 self.parse_action();
+            } else if self.at_sfc_element() {
+                self.parse_sfc_element();
             } else {
                 self.parse_statement();
             }
@@ -70,11 +84,7 @@ impl Parser<'_, '_> {
         if self.at(expected_end) {
             self.bump();
         } else {
-            if is_test_program {
-                self.error("expected END_TEST_PROGRAM");
-            } else {
-                self.error("expected END_PROGRAM");
-            }
+            self.error(end_error);
             if self.at(alternate_end) {
                 self.bump();
             }
@@ -88,7 +98,7 @@ impl Parser<'_, '_> {
         self.start_node(SyntaxKind::Function);
         self.bump(); // FUNCTION
 
-        if self.at(TokenKind::Ident) {
+        if self.at_name() {
             self.parse_name();
         } else {
             self.error("expected function name");
@@ -100,12 +110,14 @@ impl Parser<'_, '_> {
             self.parse_type_ref();
         }
 
+        self.parse_block_properties();
         self.parse_using_directives();
 
         // Parse var blocks
         while self.current().is_var_keyword() {
             self.parse_var_block();
         }
+        self.parse_block_begin();
 
         // Parse statements
         self.start_node(SyntaxKind::StmtList);
@@ -144,12 +156,13 @@ impl Parser<'_, '_> {
             self.bump();
         }
 
-        if self.at(TokenKind::Ident) {
+        if self.at_name() {
             self.parse_name();
         } else {
             self.error("expected function block name");
         }
 
+        self.parse_block_properties();
         self.parse_using_directives();
 
         // Parse EXTENDS clause
@@ -166,6 +179,7 @@ impl Parser<'_, '_> {
         while self.current().is_var_keyword() {
             self.parse_var_block();
         }
+        self.parse_block_begin();
 
         // Parse methods, properties, actions, and statements
         loop {
@@ -187,9 +201,12 @@ impl Parser<'_, '_> {
                 }
             } else if self.at(TokenKind::KwAction) {
                 self.parse_action();
+            } else if self.at_sfc_element() {
+                self.parse_sfc_element();
             } else if self.at(expected_end) || self.at(alternate_end) || self.at_end() {
                 break;
-            } else if self.current().can_start_statement() {
+            } else if self.current().can_start_statement() || self.at(TokenKind::WideStringLiteral)
+            {
                 self.parse_statement();
             } else {
                 break;
@@ -1086,6 +1103,11 @@ impl Parser<'_, '_> {
             self.error("expected action name");
         }
 
+        // SFC textual form allows `ACTION name:`
+        if self.at(TokenKind::Colon) {
+            self.bump();
+        }
+
         // Parse statements
         self.start_node(SyntaxKind::StmtList);
         while !self.at(TokenKind::KwEndAction) && !self.at_end() && !self.at_stmt_list_end() {
@@ -0,0 +1,219 @@
+//! Sequential Function Chart (SFC) parsing, textual form.
+//!
+//! Handles:
+//! - INITIAL_STEP / STEP ... END_STEP with action associations
+//! - TRANSITION [name] [(PRIORITY := n)] FROM steps TO steps := cond; END_TRANSITION
+//!
+//! Action bodies reuse `ACTION ... END_ACTION` from `pou.rs`.
+
+use crate::lexer::TokenKind;
+use crate::syntax::SyntaxKind;
+
+use super::super::Parser;
+
+/// Action qualifiers that take a duration operand (`L, T#5s`).
+const TIMED_QUALIFIERS: &[&str] = &["L", "D", "SD", "DS", "SL"];
+
+impl Parser<'_, '_> {
+    /// Returns true when the current token starts an SFC element.
+    pub(crate) fn at_sfc_element(&self) -> bool {
+        matches!(
+            self.current(),
+            TokenKind::KwStep | TokenKind::KwInitialStep | TokenKind::KwTransition
+        )
+    }
+
+    /// Parse an SFC element (step or transition).
+    pub(crate) fn parse_sfc_element(&mut self) {
+        if self.at(TokenKind::KwTransition) {
+            self.parse_transition();
+        } else {
+            self.parse_step();
+        }
+    }
+
+    /// Parse a STEP or INITIAL_STEP declaration.
+    pub(crate) fn parse_step(&mut self) {
+        self.start_node(SyntaxKind::Step);
+        self.bump(); // STEP / INITIAL_STEP
+
+        if self.at(TokenKind::Ident) {
+            self.parse_name();
+        } else {
+            self.error("expected step name");
+        }
+
+        if self.at(TokenKind::Colon) {
+            self.bump();
+        } else {
+            self.error("expected ':' after step name");
+        }
+
+        while !self.at(TokenKind::KwEndStep) && !self.at_end() {
+            if self.at(TokenKind::Ident) {
+                self.parse_action_association();
+            } else if self.current().is_trivia() {
+                self.bump();
+            } else if self.is_sync_point() || self.at_sfc_element() {
+                break;
+            } else {
+                self.error("expected action association or END_STEP");
+                self.bump();
+            }
+        }
+
+        if self.at(TokenKind::KwEndStep) {
+            self.bump();
+        } else {
+            self.error("expected END_STEP");
+        }
+
+        self.finish_node();
+    }
+
+    fn parse_action_association(&mut self) {
+        self.start_node(SyntaxKind::ActionAssociation);
+        self.parse_sfc_name_ref();
+
+        if self.at(TokenKind::LParen) {
+            self.bump();
+            if self.at(TokenKind::Ident) {
+                self.parse_action_qualifier();
+            }
+            while self.at(TokenKind::Comma) {
+                self.bump();
+                if self.at(TokenKind::Ident) {
+                    self.parse_sfc_name_ref();
+                } else {
+                    self.error("expected indicator variable name");
+                    break;
+                }
+            }
+            if self.at(TokenKind::RParen) {
+                self.bump();
+            } else {
+                self.error("expected ')'");
+            }
+        } else {
+            self.error("expected '(' after action name");
+        }
+
+        self.expect_semicolon();
+        self.finish_node();
+    }
+
+    fn parse_action_qualifier(&mut self) {
+        self.start_node(SyntaxKind::ActionQualifier);
+        let qualifier = self.source.current_text().to_ascii_uppercase();
+        self.bump();
+        if TIMED_QUALIFIERS.contains(&qualifier.as_str()) {
+            if self.at(TokenKind::Comma) {
+                self.bump();
+                self.parse_expression();
+            } else {
+                self.error("expected ',' and duration after timed action qualifier");
+            }
+        }
+        self.finish_node();
+    }
+
+    /// Parse a TRANSITION declaration.
+    pub(crate) fn parse_transition(&mut self) {
+        self.start_node(SyntaxKind::Transition);
+        self.bump(); // TRANSITION
+
+        if self.at(TokenKind::Ident) {
+            self.parse_name();
+        }
+
+        if self.at(TokenKind::LParen) {
+            self.bump();
+            if self.at(TokenKind::Ident)
+                && self.source.current_text().eq_ignore_ascii_case("PRIORITY")
+            {
+                self.bump();
+            } else {
+                self.error("expected PRIORITY");
+            }
+            if self.at(TokenKind::Assign) {
+                self.bump();
+                self.parse_expression();
+            } else {
+                self.error("expected ':=' after PRIORITY");
+            }
+            if self.at(TokenKind::RParen) {
+                self.bump();
+            } else {
+                self.error("expected ')'");
+            }
+        }
+
+        if self.at(TokenKind::KwFrom) {
+            self.bump();
+            self.parse_step_list();
+        } else {
+            self.error("expected FROM");
+        }
+
+        if self.at(TokenKind::KwTo) {
+            self.bump();
+            self.parse_step_list();
+        } else {
+            self.error("expected TO");
+        }
+
+        if self.at(TokenKind::Assign) || self.at(TokenKind::Colon) {
+            self.bump();
+            self.start_node(SyntaxKind::Condition);
+            self.parse_expression();
+            self.finish_node();
+            self.expect_semicolon();
+        } else {
+            self.error("expected ':=' and transition condition");
+        }
+
+        if self.at(TokenKind::KwEndTransition) {
+            self.bump();
+        } else {
+            self.error("expected END_TRANSITION");
+        }
+
+        self.finish_node();
+    }
+
+    fn parse_step_list(&mut self) {
+        self.start_node(SyntaxKind::StepList);
+        if self.at(TokenKind::LParen) {
+            self.bump();
+            loop {
+                if self.at(TokenKind::Ident) {
+                    self.parse_sfc_name_ref();
+                } else {
+                    self.error("expected step name");
+                    break;
+                }
+                if self.at(TokenKind::Comma) {
+                    self.bump();
+                } else {
+                    break;
+                }
+            }
+            if self.at(TokenKind::RParen) {
+                self.bump();
+            } else {
+                self.error("expected ')'");
+            }
+        } else if self.at(TokenKind::Ident) {
+            self.parse_sfc_name_ref();
+        } else {
+            self.error("expected step name");
+        }
+        self.finish_node();
+    }
+
+    fn parse_sfc_name_ref(&mut self) {
+        self.start_node(SyntaxKind::NameRef);
+        self.bump();
+        self.finish_node();
+    }
+}
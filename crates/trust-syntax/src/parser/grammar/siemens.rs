@@ -0,0 +1,177 @@
+//! Siemens SCL (TIA Portal) extensions.
+//!
+//! Handles:
+//! - DATA_BLOCK / END_DATA_BLOCK with VAR, STRUCT, or instance type bodies
+//!   and a `BEGIN` section of initial values
+//! - Block header properties (`TITLE = ...`, `VERSION : 0.1`, `NON_RETAIN`)
+//! - The `BEGIN` separator between declarations and code
+//! - REGION / END_REGION statements
+//!
+//! ORGANIZATION_BLOCK reuses `parse_program` from `pou.rs`. Quoted names
+//! (`"Motor_DB"`) are handled by `parse_name` and the expression parser.
+//! `REGION`, `BEGIN`, and the header property names are contextual so they
+//! remain usable as ordinary identifiers. The block keywords
+//! (`ORGANIZATION_BLOCK`, `DATA_BLOCK`, `END_REGION`, ...) and `REGION`
+//! statements only exist under the Siemens dialect.
+
+use crate::lexer::TokenKind;
+use crate::syntax::SyntaxKind;
+
+use super::super::{Dialect, Parser};
+
+/// Header properties written as `NAME : value`.
+const VALUE_PROPERTIES: &[&str] = &["VERSION", "AUTHOR", "FAMILY", "NAME"];
+
+/// Header properties written as a bare flag.
+const FLAG_PROPERTIES: &[&str] = &["KNOW_HOW_PROTECT", "UNLINKED", "DB_SPECIFIC"];
+
+impl Parser<'_, '_> {
+    /// Parse a DATA_BLOCK declaration.
+    pub(crate) fn parse_data_block(&mut self) {
+        self.start_node(SyntaxKind::DataBlock);
+        self.bump(); // DATA_BLOCK
+
+        if self.at_name() {
+            self.parse_name();
+        } else {
+            self.error("expected data block name");
+        }
+
+        self.parse_block_properties();
+
+        if self.current().is_var_keyword() {
+            while self.current().is_var_keyword() {
+                self.parse_var_block();
+            }
+        } else if self.at(TokenKind::KwStruct) {
+            self.parse_struct_def();
+            if self.at(TokenKind::Semicolon) {
+                self.bump();
+            }
+        } else if self.at_block_begin() || self.at(TokenKind::KwEndDataBlock) {
+            // Empty data block.
+        } else if self.at_name() || self.current().is_type_keyword() {
+            // Instance data block (`"FB_Motor"`) or UDT data block.
+            self.parse_type_ref();
+        } else {
+            self.error("expected VAR block, STRUCT, or type name");
+        }
+
+        self.parse_block_begin();
+
+        self.start_node(SyntaxKind::StmtList);
+        while !self.at(TokenKind::KwEndDataBlock) && !self.at_end() && !self.at_stmt_list_end() {
+            self.parse_statement();
+        }
+        self.finish_node();
+
+        if self.at(TokenKind::KwEndDataBlock) {
+            self.bump();
+        } else {
+            self.error("expected END_DATA_BLOCK");
+        }
+
+        self.finish_node();
+    }
+
+    /// Parse block header properties that follow a block name.
+    pub(crate) fn parse_block_properties(&mut self) {
+        loop {
+            if self.at_contextual_keyword("TITLE") && self.peek_kind_n(1) == TokenKind::Eq {
+                self.start_node(SyntaxKind::BlockProperty);
+                self.bump(); // TITLE
+                self.bump(); // =
+                self.bump_rest_of_line();
+                self.finish_node();
+            } else if VALUE_PROPERTIES
+                .iter()
+                .any(|property| self.at_contextual_keyword(property))
+                && self.peek_kind_n(1) == TokenKind::Colon
+            {
+                self.start_node(SyntaxKind::BlockProperty);
+                self.bump(); // property name
+                self.bump(); // :
+                self.bump_rest_of_line();
+                self.finish_node();
+            } else if FLAG_PROPERTIES
+                .iter()
+                .any(|property| self.at_contextual_keyword(property))
+                || matches!(
+                    self.current(),
+                    TokenKind::KwRetain | TokenKind::KwNonRetain | TokenKind::KwReadOnly
+                )
+            {
+                self.start_node(SyntaxKind::BlockProperty);
+                self.bump();
+                self.finish_node();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns true at a `BEGIN` that separates declarations from code.
+    pub(crate) fn at_block_begin(&self) -> bool {
+        self.at_contextual_keyword("BEGIN") && !self.at_identifier_use_ahead()
+    }
+
+    /// Consume an optional `BEGIN` separator.
+    pub(crate) fn parse_block_begin(&mut self) {
+        if self.at_block_begin() {
+            self.bump();
+        }
+    }
+
+    /// Returns true at a `REGION` that opens a region statement.
+    pub(crate) fn at_region_start(&self) -> bool {
+        self.dialect == Dialect::Siemens
+            && self.at_contextual_keyword("REGION")
+            && !self.at_identifier_use_ahead()
+    }
+
+    /// Parse a REGION statement.
+    pub(crate) fn parse_region_stmt(&mut self) {
+        self.start_node(SyntaxKind::RegionStmt);
+        self.bump(); // REGION
+        self.bump_rest_of_line(); // free-text title
+
+        self.start_node(SyntaxKind::StmtList);
+        while !self.at(TokenKind::KwEndRegion) && !self.at_end() && !self.at_stmt_list_end() {
+            self.parse_statement();
+        }
+        self.finish_node();
+
+        if self.at(TokenKind::KwEndRegion) {
+            self.bump();
+            if self.at(TokenKind::Semicolon) {
+                self.bump();
+            }
+        } else {
+            self.error("expected END_REGION");
+        }
+
+        self.finish_node();
+    }
+
+    /// Returns true if the next token shows the current identifier is used as a
+    /// variable or callee rather than as a contextual keyword.
+    fn at_identifier_use_ahead(&self) -> bool {
+        matches!(
+            self.peek_kind_n(1),
+            TokenKind::Assign
+                | TokenKind::RefAssign
+                | TokenKind::Dot
+                | TokenKind::LParen
+                | TokenKind::LBracket
+                | TokenKind::Caret
+                | TokenKind::Colon
+        )
+    }
+
+    /// Consume the remaining tokens on the current line as free text.
+    fn bump_rest_of_line(&mut self) {
+        while !self.at_end() && !self.source.at_line_start() {
+            self.bump();
+        }
+    }
+}
@@ -10,6 +10,7 @@
 //! - REPEAT/UNTIL/END_REPEAT
 //! - RETURN, EXIT, CONTINUE
 //! - Empty statement: `;`
+//! - REGION/END_REGION (Siemens SCL, see `siemens.rs`)
 
 use crate::lexer::TokenKind;
 use crate::syntax::SyntaxKind;
@@ -51,6 +52,8 @@ impl Parser<'_, '_> {
             }
             self.expect_semicolon();
             self.finish_node();
+        } else if self.at_region_start() {
+            self.parse_region_stmt();
         } else if self.at(TokenKind::Ident) && self.peek_kind_n(1) == TokenKind::Colon {
             self.parse_label_stmt();
         } else if self.at(TokenKind::Semicolon) {
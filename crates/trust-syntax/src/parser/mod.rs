@@ -27,11 +27,38 @@ mod parser;
 mod sink;
 mod source;
 
-pub use parser::parse;
+pub use parser::{parse, parse_with_dialect};
 pub(crate) use parser::{CompletedMarker, Parser};
 
 use crate::syntax::SyntaxNode;
 
+/// Vendor dialect for syntax that reads differently per vendor.
+///
+/// The Siemens block keywords (`ORGANIZATION_BLOCK`, `DATA_BLOCK`,
+/// `REGION`, ...) only exist in the Siemens dialect, so IEC code may use
+/// them as identifiers; other vendor syntax such as `#` locals parses in
+/// every dialect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// IEC 61131-3: `"..."` is a `WSTRING` literal.
+    #[default]
+    Iec,
+    /// Siemens SCL: `"..."` is always a quoted identifier; `WSTRING`
+    /// literals use `WSTRING#'...'`; SCL block keywords are reserved.
+    Siemens,
+}
+
+impl Dialect {
+    /// Dialect for a `vendor_profile` setting (`None` or unknown is IEC).
+    #[must_use]
+    pub fn from_vendor_profile(profile: Option<&str>) -> Self {
+        match profile.map(|profile| profile.trim().to_ascii_lowercase()) {
+            Some(profile) if profile == "siemens" => Self::Siemens,
+            _ => Self::Iec,
+        }
+    }
+}
+
 /// Result of parsing source text.
 #[derive(Debug)]
 pub struct Parse {
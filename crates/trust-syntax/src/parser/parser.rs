@@ -1,18 +1,24 @@
 //! Main parser implementation.
 
-use crate::lexer::{lex, Token, TokenKind};
+use crate::lexer::{lex_with_dialect, Token, TokenKind};
 use crate::parser::event::Event;
 use crate::parser::sink::Sink;
 use crate::parser::source::Source;
-use crate::parser::{Parse, ParseError};
+use crate::parser::{Dialect, Parse, ParseError};
 use crate::syntax::SyntaxKind;
 use drop_bomb::DropBomb;
 
 /// Parses source text into a syntax tree.
 #[must_use]
 pub fn parse(source: &str) -> Parse {
-    let tokens = lex(source);
-    let parser = Parser::new(&tokens, source);
+    parse_with_dialect(source, Dialect::Iec)
+}
+
+/// Parses source text into a syntax tree using a vendor dialect.
+#[must_use]
+pub fn parse_with_dialect(source: &str, dialect: Dialect) -> Parse {
+    let tokens = lex_with_dialect(source, dialect);
+    let parser = Parser::new(&tokens, source, dialect);
     let (events, errors) = parser.parse();
 
     let sink = Sink::new(&tokens, source, events);
@@ -33,6 +39,7 @@ pub(crate) struct Parser<'t, 'src> {
     pub(crate) events: Vec<Event>,
     errors: Vec<ParseError>,
     pub(crate) expr_depth: usize,
+    pub(crate) dialect: Dialect,
 }
 
 pub(crate) struct Marker {
@@ -104,12 +111,13 @@ fn set_forward_parent(events: &mut [Event], from: usize, to: usize) {
 }
 
 impl<'t, 'src> Parser<'t, 'src> {
-    fn new(tokens: &'t [Token], source: &'src str) -> Self {
+    fn new(tokens: &'t [Token], source: &'src str, dialect: Dialect) -> Self {
         Self {
             source: Source::new(tokens, source),
             events: Vec::new(),
             errors: Vec::new(),
             expr_depth: 0,
+            dialect,
         }
     }
 
@@ -138,13 +146,18 @@ impl<'t, 'src> Parser<'t, 'src> {
                 self.parse_type_decl();
             } else if self.at(TokenKind::KwNamespace) {
                 self.parse_namespace();
+            } else if self.at(TokenKind::KwOrganizationBlock) {
+                self.parse_program();
+            } else if self.at(TokenKind::KwDataBlock) {
+                self.parse_data_block();
             } else if self.current().is_trivia() {
                 self.bump();
             } else {
                 // Error recovery: skip unknown token
-                self.error(
-                    "expected PROGRAM, TEST_PROGRAM, FUNCTION, FUNCTION_BLOCK, TEST_FUNCTION_BLOCK, CLASS, CONFIGURATION, INTERFACE, TYPE, or NAMESPACE",
-                );
+                self.error(match self.dialect {
+                    Dialect::Iec => "expected PROGRAM, TEST_PROGRAM, FUNCTION, FUNCTION_BLOCK, TEST_FUNCTION_BLOCK, CLASS, CONFIGURATION, INTERFACE, TYPE, or NAMESPACE",
+                    Dialect::Siemens => "expected PROGRAM, TEST_PROGRAM, FUNCTION, FUNCTION_BLOCK, TEST_FUNCTION_BLOCK, CLASS, CONFIGURATION, INTERFACE, TYPE, NAMESPACE, ORGANIZATION_BLOCK, or DATA_BLOCK",
+                });
                 self.bump();
             }
         }
@@ -180,6 +193,22 @@ impl<'t, 'src> Parser<'t, 'src> {
         self.source.bump();
     }
 
+    /// Bumps the current token, recording it under a different syntax kind.
+    pub(crate) fn bump_as(&mut self, kind: SyntaxKind) {
+        self.events.push(Event::token(kind));
+        self.source.bump();
+    }
+
+    /// Returns true if the current token is an identifier spelled like `keyword`.
+    pub(crate) fn at_contextual_keyword(&self, keyword: &str) -> bool {
+        self.at(TokenKind::Ident) && self.source.current_text().eq_ignore_ascii_case(keyword)
+    }
+
+    /// Returns true if the current token can start a (possibly quoted) name.
+    pub(crate) fn at_name(&self) -> bool {
+        self.at(TokenKind::Ident) || self.at(TokenKind::WideStringLiteral)
+    }
+
     pub(crate) fn start(&mut self) -> Marker {
         let pos = self.events.len();
         self.events.push(Event::Placeholder);
@@ -256,6 +285,11 @@ impl<'t, 'src> Parser<'t, 'src> {
             | TokenKind::KwEndAction
             | TokenKind::KwEndGet
             | TokenKind::KwEndSet
+            | TokenKind::KwEndStep
+            | TokenKind::KwEndTransition
+            | TokenKind::KwEndOrganizationBlock
+            | TokenKind::KwEndDataBlock
+            | TokenKind::KwEndRegion
             // Start of new constructs (recover at next item)
             | TokenKind::KwProgram
             | TokenKind::KwTestProgram
@@ -272,6 +306,11 @@ impl<'t, 'src> Parser<'t, 'src> {
             | TokenKind::KwTask
             | TokenKind::KwType
             | TokenKind::KwAction
+            | TokenKind::KwStep
+            | TokenKind::KwInitialStep
+            | TokenKind::KwTransition
+            | TokenKind::KwOrganizationBlock
+            | TokenKind::KwDataBlock
             | TokenKind::KwVarAccess
             | TokenKind::KwVarConfig
             // Variable blocks
@@ -86,6 +86,18 @@ impl<'t, 'src> Source<'t, 'src> {
         }
     }
 
+    /// Returns `true` if a line break separates the current token from the
+    /// previously consumed one.
+    pub fn at_line_start(&self) -> bool {
+        self.tokens[self.cursor.min(self.tokens.len())..]
+            .iter()
+            .take_while(|token| token.kind.is_trivia())
+            .any(|token| {
+                self.source[usize::from(token.range.start())..usize::from(token.range.end())]
+                    .contains('\n')
+            })
+    }
+
     /// Returns `true` if at end of input.
     pub fn at_end(&self) -> bool {
         self.peek_kind_n(0) == TokenKind::Eof
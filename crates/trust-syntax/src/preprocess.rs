@@ -0,0 +1,430 @@
+//! Conditional compilation pragmas.
+//!
+//! Evaluates CODESYS-style directives written as pragmas:
+//!
+//! ```text
+//! {IF defined(SIM)}
+//!     value := 1;
+//! {ELSIF defined(TRACE) AND NOT defined(SAFE)}
+//!     value := 2;
+//! {ELSE}
+//!     value := 3;
+//! {END_IF}
+//! ```
+//!
+//! Conditions support `defined(NAME)`, `hasvalue(NAME, 'value')`, `NOT`,
+//! `AND`, `OR`, and parentheses. Defines come from build configuration as
+//! `NAME` or `NAME=VALUE` and are matched case-insensitively.
+//!
+//! Directives that decide a branch stay in the source as trivia pragmas.
+//! Everything they exclude, including nested directives, is reported as byte
+//! ranges and can be blanked out with [`mask_inactive`], which keeps every
+//! offset and line break intact so diagnostics and navigation still map onto
+//! the original text.
+
+use rustc_hash::FxHashMap;
+use smol_str::SmolStr;
+use text_size::{TextRange, TextSize};
+
+use crate::lexer::{lex, TokenKind};
+use crate::parser::ParseError;
+
+/// A set of build defines (`NAME` or `NAME=VALUE`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Defines {
+    values: FxHashMap<SmolStr, SmolStr>,
+}
+
+impl Defines {
+    /// Creates a define set from `NAME` / `NAME=VALUE` entries.
+    pub fn new<I, S>(defines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut values = FxHashMap::default();
+        for define in defines {
+            let define = define.as_ref().trim();
+            let (name, value) = define.split_once('=').unwrap_or((define, ""));
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            values.insert(
+                SmolStr::new(name.to_ascii_uppercase()),
+                SmolStr::new(value.trim()),
+            );
+        }
+        Self { values }
+    }
+
+    /// Returns true if no defines are set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns true if `name` is defined.
+    #[must_use]
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.values.contains_key(name.to_ascii_uppercase().as_str())
+    }
+
+    /// Returns the value of `name`, or an empty string for a bare define.
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values
+            .get(name.to_ascii_uppercase().as_str())
+            .map(SmolStr::as_str)
+    }
+}
+
+/// Result of evaluating conditional compilation directives.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConditionalRegions {
+    /// Byte ranges of code excluded by the directives, in source order.
+    pub inactive: Vec<TextRange>,
+    /// Malformed or unbalanced directives.
+    pub errors: Vec<ParseError>,
+}
+
+impl ConditionalRegions {
+    /// Returns true if `offset` lies inside an inactive range.
+    #[must_use]
+    pub fn is_inactive(&self, offset: TextSize) -> bool {
+        self.inactive.iter().any(|range| range.contains(offset))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    parent_active: bool,
+    branch_taken: bool,
+    active: bool,
+    saw_else: bool,
+    start: TextRange,
+}
+
+/// Evaluates the conditional compilation directives in `source`.
+#[must_use]
+pub fn conditional_regions(source: &str, defines: &Defines) -> ConditionalRegions {
+    let mut result = ConditionalRegions::default();
+    if !source.contains('{') {
+        return result;
+    }
+
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut inactive_start: Option<TextSize> = None;
+
+    for token in lex(source) {
+        if token.kind != TokenKind::Pragma {
+            continue;
+        }
+        let Some(directive) = Directive::parse(&source[token.range]) else {
+            continue;
+        };
+        let active_before = stack.last().is_none_or(|frame| frame.active);
+
+        match directive {
+            Directive::If(condition) => {
+                let value = eval_condition(condition, defines, token.range, &mut result.errors);
+                stack.push(Frame {
+                    parent_active: active_before,
+                    branch_taken: value,
+                    active: active_before && value,
+                    saw_else: false,
+                    start: token.range,
+                });
+            }
+            Directive::Elsif(condition) => {
+                let value = eval_condition(condition, defines, token.range, &mut result.errors);
+                match stack.last_mut() {
+                    Some(frame) if frame.saw_else => result
+                        .errors
+                        .push(error(token.range, "{ELSIF} after {ELSE}")),
+                    Some(frame) => {
+                        frame.active = frame.parent_active && !frame.branch_taken && value;
+                        frame.branch_taken |= value;
+                    }
+                    None => result
+                        .errors
+                        .push(error(token.range, "{ELSIF} without matching {IF}")),
+                }
+            }
+            Directive::Else => match stack.last_mut() {
+                Some(frame) if frame.saw_else => result
+                    .errors
+                    .push(error(token.range, "duplicate {ELSE} in conditional block")),
+                Some(frame) => {
+                    frame.active = frame.parent_active && !frame.branch_taken;
+                    frame.branch_taken = true;
+                    frame.saw_else = true;
+                }
+                None => result
+                    .errors
+                    .push(error(token.range, "{ELSE} without matching {IF}")),
+            },
+            Directive::EndIf => {
+                if stack.pop().is_none() {
+                    result
+                        .errors
+                        .push(error(token.range, "{END_IF} without matching {IF}"));
+                }
+            }
+        }
+
+        let active_after = stack.last().is_none_or(|frame| frame.active);
+        if active_before && !active_after {
+            inactive_start = Some(token.range.end());
+        } else if !active_before && active_after {
+            if let Some(start) = inactive_start.take() {
+                push_range(
+                    &mut result.inactive,
+                    TextRange::new(start, token.range.start()),
+                );
+            }
+        }
+    }
+
+    if let Some(start) = inactive_start {
+        let end = TextSize::of(source);
+        push_range(&mut result.inactive, TextRange::new(start, end));
+    }
+    for frame in stack {
+        result
+            .errors
+            .push(error(frame.start, "{IF} without matching {END_IF}"));
+    }
+    result
+}
+
+/// Replaces inactive code with spaces, keeping offsets and line breaks.
+#[must_use]
+pub fn mask_inactive(source: &str, inactive: &[TextRange]) -> String {
+    let mut masked = String::with_capacity(source.len());
+    let mut cursor = 0usize;
+    for range in inactive {
+        let start = usize::from(range.start());
+        let end = usize::from(range.end());
+        masked.push_str(&source[cursor..start]);
+        for ch in source[start..end].chars() {
+            if matches!(ch, '\n' | '\r') {
+                masked.push(ch);
+            } else {
+                masked.extend(std::iter::repeat_n(' ', ch.len_utf8()));
+            }
+        }
+        cursor = end;
+    }
+    masked.push_str(&source[cursor..]);
+    masked
+}
+
+fn push_range(ranges: &mut Vec<TextRange>, range: TextRange) {
+    if !range.is_empty() {
+        ranges.push(range);
+    }
+}
+
+fn error(range: TextRange, message: &str) -> ParseError {
+    ParseError {
+        message: message.to_string(),
+        range,
+    }
+}
+
+enum Directive<'a> {
+    If(&'a str),
+    Elsif(&'a str),
+    Else,
+    EndIf,
+}
+
+impl<'a> Directive<'a> {
+    fn parse(pragma: &'a str) -> Option<Self> {
+        let body = pragma
+            .strip_prefix('{')?
+            .strip_suffix('}')
+            .unwrap_or_else(|| &pragma[1..])
+            .trim();
+        let (keyword, rest) = match body.find(|ch: char| ch.is_whitespace() || ch == '(') {
+            Some(split) => (&body[..split], &body[split..]),
+            None => (body, ""),
+        };
+        match keyword.to_ascii_uppercase().as_str() {
+            "IF" => Some(Self::If(rest)),
+            "ELSIF" => Some(Self::Elsif(rest)),
+            "ELSE" if rest.trim().is_empty() => Some(Self::Else),
+            "END_IF" if rest.trim().is_empty() => Some(Self::EndIf),
+            _ => None,
+        }
+    }
+}
+
+fn eval_condition(
+    condition: &str,
+    defines: &Defines,
+    range: TextRange,
+    errors: &mut Vec<ParseError>,
+) -> bool {
+    let mut parser = ConditionParser {
+        tokens: condition_tokens(condition),
+        pos: 0,
+        defines,
+    };
+    let value = parser.parse_or();
+    match value {
+        Ok(value) if parser.pos == parser.tokens.len() => value,
+        Ok(_) => {
+            errors.push(error(range, "unexpected input in conditional pragma"));
+            false
+        }
+        Err(message) => {
+            errors.push(error(range, &message));
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CondToken {
+    Word(String),
+    Str(String),
+    LParen,
+    RParen,
+    Comma,
+    Other(char),
+}
+
+fn condition_tokens(text: &str) -> Vec<CondToken> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push(CondToken::LParen),
+            ')' => tokens.push(CondToken::RParen),
+            ',' => tokens.push(CondToken::Comma),
+            '\'' | '"' => {
+                let mut value = String::new();
+                for next in chars.by_ref() {
+                    if next == ch {
+                        break;
+                    }
+                    value.push(next);
+                }
+                tokens.push(CondToken::Str(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' {
+                        word.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(CondToken::Word(word));
+            }
+            other => tokens.push(CondToken::Other(other)),
+        }
+    }
+    tokens
+}
+
+struct ConditionParser<'a> {
+    tokens: Vec<CondToken>,
+    pos: usize,
+    defines: &'a Defines,
+}
+
+impl ConditionParser<'_> {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.tokens.get(self.pos), Some(CondToken::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, token: CondToken, what: &str) -> Result<(), String> {
+        if self.tokens.get(self.pos) == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected {what} in conditional pragma"))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<bool, String> {
+        let mut value = self.parse_and()?;
+        while self.peek_keyword("OR") {
+            self.pos += 1;
+            value |= self.parse_and()?;
+        }
+        Ok(value)
+    }
+
+    fn parse_and(&mut self) -> Result<bool, String> {
+        let mut value = self.parse_unary()?;
+        while self.peek_keyword("AND") {
+            self.pos += 1;
+            value &= self.parse_unary()?;
+        }
+        Ok(value)
+    }
+
+    fn parse_unary(&mut self) -> Result<bool, String> {
+        if self.peek_keyword("NOT") {
+            self.pos += 1;
+            return Ok(!self.parse_unary()?);
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<bool, String> {
+        match self.tokens.get(self.pos).cloned() {
+            Some(CondToken::LParen) => {
+                self.pos += 1;
+                let value = self.parse_or()?;
+                self.expect(CondToken::RParen, "')'")?;
+                Ok(value)
+            }
+            Some(CondToken::Word(word)) if word.eq_ignore_ascii_case("defined") => {
+                self.pos += 1;
+                self.expect(CondToken::LParen, "'('")?;
+                let name = self.name()?;
+                self.expect(CondToken::RParen, "')'")?;
+                Ok(self.defines.is_defined(&name))
+            }
+            Some(CondToken::Word(word)) if word.eq_ignore_ascii_case("hasvalue") => {
+                self.pos += 1;
+                self.expect(CondToken::LParen, "'('")?;
+                let name = self.name()?;
+                self.expect(CondToken::Comma, "','")?;
+                let Some(CondToken::Str(expected)) = self.tokens.get(self.pos).cloned() else {
+                    return Err("expected quoted value in hasvalue()".to_string());
+                };
+                self.pos += 1;
+                self.expect(CondToken::RParen, "')'")?;
+                Ok(self.defines.value(&name) == Some(expected.as_str()))
+            }
+            Some(CondToken::Word(word)) => Err(format!(
+                "unsupported condition '{word}' in conditional pragma"
+            )),
+            _ => Err("expected condition in conditional pragma".to_string()),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, String> {
+        match self.tokens.get(self.pos).cloned() {
+            Some(CondToken::Word(name)) => {
+                self.pos += 1;
+                if self.tokens.get(self.pos) == Some(&CondToken::Other(':')) {
+                    return Err(format!(
+                        "unsupported defined({name}: ...) form in conditional pragma"
+                    ));
+                }
+                Ok(name)
+            }
+            _ => Err("expected define name in conditional pragma".to_string()),
+        }
+    }
+}
@@ -267,6 +267,32 @@ macro_rules! define_syntax_kind {
 
             /// Condition expression (for IF, WHILE, etc.)
             Condition,
+
+            // Sequential Function Chart (textual form)
+            /// An SFC step: `STEP name: ... END_STEP` or `INITIAL_STEP name: ... END_STEP`
+            Step,
+
+            /// An SFC action association inside a step: `Action(N);`
+            ActionAssociation,
+
+            /// An SFC action qualifier with optional duration: `L, T#5s`
+            ActionQualifier,
+
+            /// An SFC transition: `TRANSITION FROM a TO b := cond; END_TRANSITION`
+            Transition,
+
+            /// SFC step list in a transition: `a` or `(a, b)`
+            StepList,
+
+            // Siemens SCL extensions
+            /// A data block: `DATA_BLOCK name ... BEGIN ... END_DATA_BLOCK`
+            DataBlock,
+
+            /// A block header property: `TITLE = ...`, `VERSION : 0.1`, `NON_RETAIN`
+            BlockProperty,
+
+            /// A region statement: `REGION title ... END_REGION`
+            RegionStmt,
         }
     };
 }
@@ -316,6 +342,17 @@ impl From<SyntaxKind> for rowan::SyntaxKind {
     }
 }
 
+/// Returns identifier text without Siemens-style double quotes.
+///
+/// Quoted names such as `"Motor_DB"` are parsed as identifiers; semantic
+/// layers compare them by their unquoted spelling.
+#[must_use]
+pub fn identifier_text(text: &str) -> &str {
+    text.strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
 /// The language type for Structured Text.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum StLanguage {}
@@ -405,6 +442,14 @@ macro_rules! define_syntax_kinds {
             SyntaxKind::InitializerList,
             SyntaxKind::ArrayInitializer,
             SyntaxKind::Condition,
+            SyntaxKind::Step,
+            SyntaxKind::ActionAssociation,
+            SyntaxKind::ActionQualifier,
+            SyntaxKind::Transition,
+            SyntaxKind::StepList,
+            SyntaxKind::DataBlock,
+            SyntaxKind::BlockProperty,
+            SyntaxKind::RegionStmt,
         ];
     };
 }
@@ -55,6 +55,11 @@ macro_rules! for_each_token_kind {
             KwNamespace,
             KwEndNamespace,
             KwUsing,
+            KwOrganizationBlock,
+            KwEndOrganizationBlock,
+            KwDataBlock,
+            KwEndDataBlock,
+            KwEndRegion,
             KwAction,
             KwEndAction,
             KwVar,
@@ -1,13 +1,18 @@
 //! Shared helpers for parser snapshot tests.
 #![allow(dead_code, unused_imports)]
 
-pub use trust_syntax::parser::parse;
+pub use trust_syntax::parser::{parse, parse_with_dialect, Dialect};
 #[allow(unused_imports)]
 pub use trust_syntax::syntax::SyntaxKind;
 
 /// Helper to format a parse result for snapshot testing.
 pub fn snapshot_parse(source: &str) -> String {
-    let parsed = parse(source);
+    snapshot_parse_with_dialect(source, Dialect::Iec)
+}
+
+/// Like [`snapshot_parse`], parsing with a vendor dialect.
+pub fn snapshot_parse_with_dialect(source: &str, dialect: Dialect) -> String {
+    let parsed = parse_with_dialect(source, dialect);
     let syntax = parsed.syntax();
 
     let mut output = String::new();
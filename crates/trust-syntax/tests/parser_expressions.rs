@@ -132,3 +132,28 @@ fn test_siemens_hash_prefixed_locals() {
 END_PROGRAM"#
     ));
 }
+
+#[test]
+fn test_siemens_quoted_identifiers() {
+    insta::assert_snapshot!(snapshot_parse(
+        r#"FUNCTION_BLOCK "FB_Motor"
+VAR
+    drive : "FB_Drive";
+END_VAR
+    "Motor_DB".speed := #drive.rpm;
+    "Tag_Start" := "Motor_DB"."max speed" > 0.0;
+    #text := "literal";
+END_FUNCTION_BLOCK"#
+    ));
+}
+
+#[test]
+fn test_siemens_dialect_quoted_operands() {
+    insta::assert_snapshot!(snapshot_parse_with_dialect(
+        r#"PROGRAM Test
+    #run := "Tag_Start" AND NOT "Tag_Stop";
+    #text := WSTRING#'literal';
+END_PROGRAM"#,
+        Dialect::Siemens
+    ));
+}
@@ -243,3 +243,61 @@ END_VAR
 END_CONFIGURATION"#
     ));
 }
+
+#[test]
+fn test_siemens_organization_block() {
+    insta::assert_snapshot!(snapshot_parse_with_dialect(
+        r#"ORGANIZATION_BLOCK "Main"
+TITLE = Main Program Sweep (Cycle)
+{ S7_Optimized_Access := 'TRUE' }
+VERSION : 0.1
+   VAR_TEMP
+      cycleOk : Bool;
+   END_VAR
+
+BEGIN
+    "Motor1_DB"(start := #cycleOk);
+END_ORGANIZATION_BLOCK"#,
+        Dialect::Siemens
+    ));
+}
+
+#[test]
+fn test_siemens_data_blocks() {
+    insta::assert_snapshot!(snapshot_parse_with_dialect(
+        r#"DATA_BLOCK "Motor_DB"
+{ S7_Optimized_Access := 'TRUE' }
+VERSION : 0.1
+NON_RETAIN
+   VAR
+      speed : Real := 10.0;
+      running : Bool;
+   END_VAR
+
+BEGIN
+   speed := 100.0;
+END_DATA_BLOCK
+
+DATA_BLOCK "Motor1_DB"
+VERSION : 0.1
+"FB_Motor"
+
+BEGIN
+END_DATA_BLOCK"#,
+        Dialect::Siemens
+    ));
+}
+
+#[test]
+fn test_siemens_block_words_are_identifiers_in_iec() {
+    insta::assert_snapshot!(snapshot_parse(
+        r#"PROGRAM Main
+VAR
+    data_block : INT;
+    end_region : INT;
+END_VAR
+data_block := data_block + 1;
+end_region := data_block;
+END_PROGRAM"#
+    ));
+}
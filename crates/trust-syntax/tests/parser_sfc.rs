@@ -0,0 +1,57 @@
+mod common;
+use common::*;
+
+// Sequential Function Chart (textual form)
+#[test]
+// IEC 61131-3 Ed.3 Table 54/55 (SFC steps and transitions)
+fn test_sfc_program() {
+    insta::assert_snapshot!(snapshot_parse(
+        r#"PROGRAM Filler
+VAR
+    start : BOOL;
+    level : INT;
+END_VAR
+INITIAL_STEP Idle:
+END_STEP
+
+STEP Fill:
+    FillValve(N);
+    Alarm(D, T#5s);
+END_STEP
+
+TRANSITION FROM Idle TO Fill := start; END_TRANSITION
+TRANSITION Done (PRIORITY := 1) FROM Fill TO Idle := level > 90; END_TRANSITION
+
+ACTION FillValve:
+    level := level + 1;
+END_ACTION
+END_PROGRAM"#
+    ));
+}
+
+#[test]
+// IEC 61131-3 Ed.3 Table 56 (action qualifiers and indicators)
+fn test_sfc_parallel_branches_in_function_block() {
+    insta::assert_snapshot!(snapshot_parse(
+        r#"FUNCTION_BLOCK FB_Seq
+INITIAL_STEP S0:
+    Lamp(S, Indicator);
+END_STEP
+STEP S1: Heat(L, T#2s); END_STEP
+STEP S2: Mix(P); END_STEP
+TRANSITION FROM S0 TO (S1, S2) := TRUE; END_TRANSITION
+TRANSITION FROM (S1, S2) TO S0 := S1.T >= T#2s; END_TRANSITION
+END_FUNCTION_BLOCK"#
+    ));
+}
+
+#[test]
+fn test_sfc_missing_end_step() {
+    insta::assert_snapshot!(snapshot_parse(
+        r#"PROGRAM Test
+INITIAL_STEP S0:
+    Run(N);
+TRANSITION FROM S0 TO S0 := TRUE; END_TRANSITION
+END_PROGRAM"#
+    ));
+}
@@ -160,3 +160,28 @@ fn test_siemens_hash_prefixed_statement_forms() {
 END_PROGRAM"#
     ));
 }
+
+#[test]
+fn test_siemens_region() {
+    insta::assert_snapshot!(snapshot_parse_with_dialect(
+        r#"PROGRAM Test
+    REGION Set outputs
+        #out := #in;
+        REGION
+            region := 1;
+        END_REGION
+    END_REGION
+END_PROGRAM"#,
+        Dialect::Siemens
+    ));
+}
+
+#[test]
+fn test_region_is_an_identifier_in_iec() {
+    insta::assert_snapshot!(snapshot_parse(
+        r#"PROGRAM Test
+    REGION := 1;
+    region := region + 1;
+END_PROGRAM"#
+    ));
+}
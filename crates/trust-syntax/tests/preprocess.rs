@@ -0,0 +1,121 @@
+use trust_syntax::parser::parse;
+use trust_syntax::preprocess::{conditional_regions, mask_inactive, Defines};
+
+fn active_text(source: &str, defines: &[&str]) -> String {
+    let regions = conditional_regions(source, &Defines::new(defines));
+    assert!(regions.errors.is_empty(), "{:?}", regions.errors);
+    mask_inactive(source, &regions.inactive)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const BRANCHES: &str = "\
+{IF defined(SIM)}
+a := 1;
+{ELSIF hasvalue(MODE, 'fast') AND NOT defined(SAFE)}
+a := 2;
+{ELSE}
+a := 3;
+{END_IF}
+";
+
+#[test]
+fn selects_first_matching_branch() {
+    assert_eq!(
+        active_text(BRANCHES, &["sim"]),
+        "{IF defined(SIM)} a := 1; {ELSIF hasvalue(MODE, 'fast') AND NOT defined(SAFE)} {END_IF}"
+    );
+    assert_eq!(
+        active_text(BRANCHES, &["MODE=fast"]),
+        "{IF defined(SIM)} {ELSIF hasvalue(MODE, 'fast') AND NOT defined(SAFE)} a := 2; {ELSE} {END_IF}"
+    );
+    assert_eq!(
+        active_text(BRANCHES, &["MODE=fast", "SAFE"]),
+        "{IF defined(SIM)} {ELSE} a := 3; {END_IF}"
+    );
+}
+
+#[test]
+fn nested_blocks_follow_parent_state() {
+    let source = "\
+{IF defined(OUTER)}
+{IF defined(INNER)}
+a := 1;
+{ELSE}
+a := 2;
+{END_IF}
+{END_IF}
+b := 3;
+";
+    assert_eq!(
+        active_text(source, &[]),
+        "{IF defined(OUTER)} {END_IF} b := 3;"
+    );
+    assert_eq!(
+        active_text(source, &["OUTER"]),
+        "{IF defined(OUTER)} {IF defined(INNER)} {ELSE} a := 2; {END_IF} {END_IF} b := 3;"
+    );
+}
+
+#[test]
+fn masking_preserves_offsets_and_lines() {
+    let source = "x := 1;\n{IF defined(SIM)}\ny := 'ä';\n{END_IF}\nz := 2;\n";
+    let regions = conditional_regions(source, &Defines::default());
+    let masked = mask_inactive(source, &regions.inactive);
+    assert_eq!(masked.len(), source.len());
+    assert_eq!(masked.lines().count(), source.lines().count());
+    assert_eq!(masked.find("z := 2;"), source.find("z := 2;"));
+    assert!(!masked.contains("y :="));
+}
+
+#[test]
+fn inactive_code_is_not_parsed() {
+    let source = "\
+PROGRAM Main
+VAR
+    x : INT;
+END_VAR
+{IF defined(SIM)}
+    this is not valid structured text
+{END_IF}
+x := 1;
+END_PROGRAM
+";
+    let regions = conditional_regions(source, &Defines::default());
+    let parsed = parse(&mask_inactive(source, &regions.inactive));
+    assert!(parsed.errors().is_empty(), "{:?}", parsed.errors());
+    assert!(!parse(source).errors().is_empty());
+}
+
+#[test]
+fn reports_unbalanced_and_unsupported_directives() {
+    let messages = |source: &str| {
+        conditional_regions(source, &Defines::default())
+            .errors
+            .into_iter()
+            .map(|error| error.message)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        messages("{IF defined(A)}\nx := 1;\n"),
+        vec!["{IF} without matching {END_IF}"]
+    );
+    assert_eq!(messages("{END_IF}"), vec!["{END_IF} without matching {IF}"]);
+    assert_eq!(
+        messages("{IF defined(A)}{ELSE}{ELSIF defined(B)}{END_IF}"),
+        vec!["{ELSIF} after {ELSE}"]
+    );
+    assert_eq!(
+        messages("{IF SIM > 1}{END_IF}"),
+        vec!["unsupported condition 'SIM' in conditional pragma"]
+    );
+}
+
+#[test]
+fn other_pragmas_are_ignored() {
+    let source = "{attribute 'hide'}\nx := 1;\n{ S7_Optimized_Access := 'TRUE' }\n";
+    let regions = conditional_regions(source, &Defines::new(["SIM"]));
+    assert!(regions.inactive.is_empty());
+    assert!(regions.errors.is_empty());
+}